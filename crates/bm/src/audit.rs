@@ -0,0 +1,439 @@
+//! Audit log of operator actions.
+//!
+//! Every mutating `bm` command (hire, start, stop, sync, config set, ...)
+//! and every chat command that changes something appends a line to
+//! `~/.botminter/audit.log` once it finishes: when, who, which team, the
+//! command and its arguments, and whether it worked. The file is only ever
+//! appended to, so operators sharing a machine can tell who did what;
+//! `bm audit` reads it back.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::{
+    BoardCommand, Command, CompletionsCommand, ConfigCommand, DaemonCommand, DaemonConfigCommand,
+    DaemonEventsCommand, DiagCommand, KnowledgeCommand, MembersCommand, NotifyCommand,
+    ProfilesCommand, ProjectsCommand, RolesCommand, StateCommand, TeamsCommand, TopologyCommand,
+    WebhooksCommand, WorkspaceCommand,
+};
+use crate::config;
+
+const AUDIT_FILE: &str = "audit.log";
+
+/// Stands in for values that aren't written to the log.
+const REDACTED: &str = "<redacted>";
+
+/// One line of the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// When the command finished (RFC 3339).
+    pub at: String,
+    /// The local user, or the chat user for chat commands.
+    pub user: String,
+    /// The team acted on; absent for commands that span teams.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    /// The command, e.g. `teams sync` or `chatops stop`.
+    pub command: String,
+    /// The arguments it was given, with secrets redacted.
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub ok: bool,
+    /// Why it failed, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Entry {
+    /// An entry for `command`, finished now with `outcome`.
+    pub fn new<T>(
+        user: &str,
+        team: Option<&str>,
+        command: &str,
+        args: Vec<String>,
+        outcome: &Result<T>,
+    ) -> Self {
+        Entry {
+            at: Utc::now().to_rfc3339(),
+            user: user.to_string(),
+            team: team.map(str::to_string),
+            command: command.to_string(),
+            args,
+            ok: outcome.is_ok(),
+            error: outcome.as_ref().err().map(|e| format!("{:#}", e)),
+        }
+    }
+
+    /// When it was recorded, if the timestamp parses.
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.at)
+            .ok()
+            .map(|t| t.with_timezone(&Utc))
+    }
+}
+
+/// A mutating CLI command about to run, to be recorded once it has.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Action {
+    pub command: &'static str,
+    pub team: Option<String>,
+    pub args: Vec<String>,
+}
+
+impl Action {
+    /// The action `cmd` takes, or `None` for commands that only read, dry
+    /// runs included. `argv` is the command line after the program name.
+    ///
+    /// Every subcommand is listed, so a new one doesn't build until it's
+    /// decided whether it's audited.
+    pub fn of(cmd: &Command, argv: &[String]) -> Option<Self> {
+        let (command, team) = match cmd {
+            Command::Init { name, .. } => ("init", Team::Named(name.clone())),
+            Command::Quickstart { name, .. } => ("quickstart", Team::Named(name.clone())),
+            Command::Hire { dry_run: true, .. } => return None,
+            Command::Hire { team, .. } => ("hire", Team::Flag(team.clone())),
+            Command::Start { dry_run: true, .. } => return None,
+            Command::Start { team, .. } => ("start", Team::Flag(team.clone())),
+            Command::Stop { all: true, .. } => ("stop", Team::Named(None)),
+            Command::Stop { team, .. } => ("stop", Team::Flag(team.clone())),
+            Command::Pause { team } => ("pause", Team::Flag(team.clone())),
+            Command::Resume { team } => ("resume", Team::Flag(team.clone())),
+            Command::Exec { team, .. } => ("exec", Team::Flag(team.clone())),
+            Command::Teams { command } => match command {
+                TeamsCommand::List | TeamsCommand::Show { .. } | TeamsCommand::Verify { .. } => {
+                    return None
+                }
+                TeamsCommand::Pull { team, .. } => ("teams pull", Team::Flag(team.clone())),
+                TeamsCommand::Sync { check: true, .. } => return None,
+                TeamsCommand::Sync { dry_run: true, .. } => return None,
+                TeamsCommand::Sync { team, .. } => ("teams sync", Team::Flag(team.clone())),
+                TeamsCommand::AddRepo { team, .. } => ("teams add-repo", Team::Flag(team.clone())),
+                TeamsCommand::RemoveRepo { team, .. } => {
+                    ("teams remove-repo", Team::Flag(team.clone()))
+                }
+                TeamsCommand::Remove { name, .. } => {
+                    ("teams remove", Team::Named(Some(name.clone())))
+                }
+                TeamsCommand::SetDefault { name } => {
+                    ("teams set-default", Team::Named(Some(name.clone())))
+                }
+                TeamsCommand::Destroy { dry_run: true, .. } => return None,
+                TeamsCommand::Destroy { name, .. } => {
+                    ("teams destroy", Team::Named(Some(name.clone())))
+                }
+            },
+            Command::Members { command } => match command {
+                MembersCommand::List { .. } | MembersCommand::Show { .. } => return None,
+            },
+            Command::Roles { command } => match command {
+                RolesCommand::List { .. } | RolesCommand::Describe { .. } => return None,
+            },
+            Command::Profiles { command } => match command {
+                ProfilesCommand::List
+                | ProfilesCommand::Describe { .. }
+                | ProfilesCommand::Validate { .. }
+                | ProfilesCommand::Lint { .. } => return None,
+                ProfilesCommand::Export { .. } => ("profiles export", Team::Named(None)),
+            },
+            Command::Projects { command } => match command {
+                ProjectsCommand::List { .. } | ProjectsCommand::Show { .. } => return None,
+                ProjectsCommand::Add { dry_run: true, .. } => return None,
+                ProjectsCommand::Add { team, .. } => ("projects add", Team::Flag(team.clone())),
+                ProjectsCommand::Sync { check: true, .. } => return None,
+                ProjectsCommand::Sync { team, .. } => ("projects sync", Team::Flag(team.clone())),
+                ProjectsCommand::Seed { dry_run: true, .. } => return None,
+                ProjectsCommand::Seed { team, .. } => ("projects seed", Team::Flag(team.clone())),
+            },
+            Command::Board { command } => match command {
+                BoardCommand::View { .. } => return None,
+            },
+            Command::Config { command } => match command {
+                // Without -t the key may name any team, or none
+                ConfigCommand::Set { key, value, team } => {
+                    let args = redact_after(argv, key, value);
+                    return Some(Action {
+                        command: "config set",
+                        team: team.as_deref().and_then(resolve),
+                        args,
+                    });
+                }
+                ConfigCommand::Unset { team, .. } => {
+                    ("config unset", Team::Named(team.as_deref().and_then(resolve)))
+                }
+                ConfigCommand::Get { .. } => return None,
+            },
+            Command::State { command } => match command {
+                StateCommand::History { .. } => return None,
+            },
+            Command::Status { .. }
+            | Command::Dashboard { .. }
+            | Command::Usage { .. }
+            | Command::Audit { .. } => return None,
+            Command::Knowledge { command, team, .. } => match command {
+                None
+                | Some(KnowledgeCommand::List { .. })
+                | Some(KnowledgeCommand::Show { .. })
+                | Some(KnowledgeCommand::Grep { .. }) => return None,
+                Some(KnowledgeCommand::Add { team: t, .. }) => {
+                    ("knowledge add", Team::Flag(t.clone().or(team.clone())))
+                }
+                Some(KnowledgeCommand::Edit { team: t, .. }) => {
+                    ("knowledge edit", Team::Flag(t.clone().or(team.clone())))
+                }
+            },
+            Command::Daemon { command } => match command {
+                DaemonCommand::Start { dry_run: true, .. } => return None,
+                DaemonCommand::Start { team, .. } => ("daemon start", Team::Flag(team.clone())),
+                DaemonCommand::Restart { team, .. } => ("daemon restart", Team::Flag(team.clone())),
+                DaemonCommand::Stop { team, .. } => ("daemon stop", Team::Flag(team.clone())),
+                DaemonCommand::Status { .. }
+                | DaemonCommand::History { .. }
+                | DaemonCommand::Simulate { .. } => return None,
+                DaemonCommand::Config { command } => match command {
+                    DaemonConfigCommand::Set { team, .. } => {
+                        ("daemon config set", Team::Flag(team.clone()))
+                    }
+                    DaemonConfigCommand::Unset { team, .. } => {
+                        ("daemon config unset", Team::Flag(team.clone()))
+                    }
+                    DaemonConfigCommand::Get { .. } => return None,
+                },
+                DaemonCommand::Events { command } => match command {
+                    DaemonEventsCommand::List { .. } | DaemonEventsCommand::Show { .. } => {
+                        return None
+                    }
+                    DaemonEventsCommand::Replay { team, .. } => {
+                        ("daemon events replay", Team::Flag(team.clone()))
+                    }
+                },
+            },
+            // Each chat command is audited as it runs
+            Command::Chatops { .. } => return None,
+            Command::Webhooks { command } => match command {
+                WebhooksCommand::Setup { team, .. } => ("webhooks setup", Team::Flag(team.clone())),
+                WebhooksCommand::Status { .. } => return None,
+            },
+            Command::Notify { command } => match command {
+                NotifyCommand::Test { team } => ("notify test", Team::Flag(team.clone())),
+            },
+            Command::Topology { command } => match command {
+                TopologyCommand::Refresh { team } => {
+                    ("topology refresh", Team::Flag(team.clone()))
+                }
+            },
+            Command::Diag { command } => match command {
+                DiagCommand::Bundle { .. } => return None,
+            },
+            Command::Workspace { command } => match command {
+                WorkspaceCommand::Snapshot { team, .. } => {
+                    ("workspace snapshot", Team::Flag(team.clone()))
+                }
+                WorkspaceCommand::Restore { team, .. } => {
+                    ("workspace restore", Team::Flag(team.clone()))
+                }
+                WorkspaceCommand::Snapshots { .. } => return None,
+            },
+            Command::Completions { command, .. } => match command {
+                Some(CompletionsCommand::Install { .. }) => {
+                    ("completions install", Team::Named(None))
+                }
+                None => return None,
+            },
+            // Internal: started by `bm daemon start`, which is audited
+            Command::DaemonRun { .. } | Command::TokenRefresh { .. } => return None,
+        };
+        Some(Action {
+            command,
+            team: match team {
+                Team::Flag(flag) => resolve_flag(flag.as_deref()),
+                Team::Named(name) => name,
+            },
+            args: argv.to_vec(),
+        })
+    }
+
+    /// Appends the action to the audit log with how it went. A log that
+    /// can't be written is reported, not fatal: the command already ran.
+    pub fn record<T>(&self, outcome: &Result<T>) {
+        let entry = Entry::new(
+            &current_user(),
+            self.team.as_deref(),
+            self.command,
+            self.args.clone(),
+            outcome,
+        );
+        if let Err(e) = append_default(&entry) {
+            eprintln!("Warning: could not write the audit log: {:#}", e);
+        }
+    }
+}
+
+/// How an action names its team.
+enum Team {
+    /// A `-t` flag, falling back to the default team.
+    Flag(Option<String>),
+    /// The team itself, or none.
+    Named(Option<String>),
+}
+
+/// The team a `-t` flag resolves to, as the command itself will resolve it.
+fn resolve_flag(flag: Option<&str>) -> Option<String> {
+    let cfg = config::load().ok()?;
+    config::resolve_team(&cfg, flag).ok().map(|t| t.name.clone())
+}
+
+/// The registered team called `name`, or `name` itself if it isn't one.
+fn resolve(name: &str) -> Option<String> {
+    resolve_flag(Some(name)).or_else(|| Some(name.to_string()))
+}
+
+/// `argv` with the first `value` after `key` redacted, since config values
+/// are mostly credentials.
+fn redact_after(argv: &[String], key: &str, value: &str) -> Vec<String> {
+    let mut args = argv.to_vec();
+    let from = args.iter().position(|a| a == key).map_or(0, |i| i + 1);
+    if let Some(arg) = args[from..].iter_mut().find(|a| *a == value) {
+        *arg = REDACTED.to_string();
+    }
+    args
+}
+
+/// The local user running `bm`.
+pub fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
+
+/// The audit log, `~/.botminter/audit.log`.
+pub fn audit_path() -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(AUDIT_FILE))
+}
+
+/// Appends `entry` to the audit log.
+pub fn append_default(entry: &Entry) -> Result<()> {
+    let path = audit_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    append(&path, entry)
+}
+
+/// Appends `entry` to the log at `path` as one JSON line.
+pub fn append(path: &Path, entry: &Entry) -> Result<()> {
+    let mut line = serde_json::to_string(entry).context("Failed to serialize audit entry")?;
+    line.push('\n');
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(line.as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Reads the log at `path`, oldest first. Lines that don't parse are
+/// skipped; a missing log has no entries.
+pub fn load(path: &Path) -> Result<Vec<Entry>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    use crate::cli::Cli;
+
+    fn action(args: &[&str]) -> Option<Action> {
+        let argv: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let cli = Cli::try_parse_from(std::iter::once("bm").chain(args.iter().copied())).unwrap();
+        Action::of(&cli.command, &argv)
+    }
+
+    #[test]
+    fn only_mutating_commands_are_audited() {
+        let hire = action(&["hire", "dev", "-t", "my-team"]).unwrap();
+        assert_eq!(hire.command, "hire");
+        assert_eq!(hire.args, vec!["hire", "dev", "-t", "my-team"]);
+        assert_eq!(action(&["stop", "--all"]).unwrap().team, None);
+        assert_eq!(
+            action(&["teams", "sync", "--push"]).unwrap().command,
+            "teams sync"
+        );
+        assert_eq!(
+            action(&["teams", "destroy", "old", "--yes"]).unwrap().team,
+            Some("old".to_string())
+        );
+
+        assert_eq!(
+            action(&["exec", "dev-01", "-t", "my-team", "--", "rm", "-rf", "x"])
+                .unwrap()
+                .args,
+            vec!["exec", "dev-01", "-t", "my-team", "--", "rm", "-rf", "x"]
+        );
+        for argv in [
+            &["topology", "refresh"][..],
+            &["profiles", "export", "scrum", "out"],
+            &["completions", "install"],
+            &["notify", "test"],
+        ] {
+            assert!(action(argv).is_some(), "{:?} not audited", argv);
+        }
+
+        assert_eq!(action(&["hire", "dev", "--dry-run"]), None);
+        assert_eq!(action(&["teams", "sync", "--check"]), None);
+        assert_eq!(action(&["completions", "bash"]), None);
+        assert_eq!(action(&["teams", "sync", "--dry-run"]), None);
+        assert_eq!(action(&["status"]), None);
+        assert_eq!(action(&["config", "get", "workzone"]), None);
+    }
+
+    #[test]
+    fn config_set_values_are_redacted() {
+        let set = action(&["config", "set", "gh_token", "ghp_abc", "-t", "my-team"]).unwrap();
+        assert_eq!(set.command, "config set");
+        assert_eq!(set.args, vec!["config", "set", "gh_token", REDACTED, "-t", "my-team"]);
+        assert_eq!(set.team, Some("my-team".to_string()));
+    }
+
+    #[test]
+    fn entries_append_and_load_in_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(AUDIT_FILE);
+        assert!(load(&path).unwrap().is_empty());
+
+        let ok: Result<()> = Ok(());
+        let failed: Result<()> = Err(anyhow::anyhow!("no such team"));
+        let first = Entry::new("alice", Some("t"), "start", vec!["start".into()], &ok);
+        let second = Entry::new("bob", None, "stop", vec!["stop".into(), "--all".into()], &failed);
+        append(&path, &first).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+        append(&path, &second).unwrap();
+
+        let entries = load(&path).unwrap();
+        assert_eq!(entries, vec![first, second]);
+        assert!(entries[0].ok);
+        assert_eq!(entries[1].error.as_deref(), Some("no such team"));
+    }
+}
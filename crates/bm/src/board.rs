@@ -0,0 +1,315 @@
+//! Board snapshots for daemon-triggered runs.
+//!
+//! Before the daemon launches members it reads the team's project board
+//! once and writes each member the items in the statuses its role works,
+//! as `.botminter/board.json` in its workspace. Members start from that
+//! instead of re-querying GitHub at the top of every loop. When the board
+//! can't be read the previous snapshot stays, with its `fetched_at` telling
+//! how old it is.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::commands::init::find_project_number;
+use crate::config::TeamEntry;
+use crate::parse;
+use crate::profile::{ProfileManifest, RoleDef, StatusDef};
+
+/// Snapshot file, relative to a workspace.
+pub const SNAPSHOT_FILE: &str = ".botminter/board.json";
+
+/// Most board items `gh project item-list` is asked for.
+const ITEM_LIMIT: &str = "1000";
+
+/// A project board item, as printed by `gh project item-list`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Item {
+    #[serde(default)]
+    pub title: String,
+    /// The Status field; absent for items without one.
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub assignees: Vec<String>,
+    #[serde(default)]
+    pub content: ItemContent,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ItemContent {
+    #[serde(default)]
+    pub number: Option<u64>,
+    #[serde(default)]
+    pub repository: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ItemList {
+    #[serde(default)]
+    pub items: Vec<Item>,
+}
+
+/// Lists the items of project `number` owned by `owner`.
+pub fn list_items(owner: &str, number: u64, token: Option<&str>) -> Result<Vec<Item>> {
+    let number = number.to_string();
+    let args = [
+        "project",
+        "item-list",
+        &number,
+        "--owner",
+        owner,
+        "--format",
+        "json",
+        "--limit",
+        ITEM_LIMIT,
+    ];
+    debug!("Running gh {}", args.join(" "));
+    let mut cmd = Command::new("gh");
+    cmd.args(args);
+    if let Some(token) = token {
+        cmd.env("GH_TOKEN", token);
+    }
+    let output = cmd
+        .output()
+        .context("Failed to run `gh project item-list`")?;
+    if !output.status.success() {
+        bail!(
+            "gh project item-list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let list: ItemList =
+        serde_json::from_slice(&output.stdout).context("Could not parse project item-list JSON")?;
+    Ok(list.items)
+}
+
+/// One item in a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotItem {
+    /// Issue or pull request number; absent for draft items.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<u64>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub repo: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assignees: Vec<String>,
+}
+
+/// What `.botminter/board.json` holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub team: String,
+    pub member: String,
+    pub role: String,
+    /// When the board was read (RFC 3339).
+    pub fetched_at: String,
+    /// Items by status, for every status the role works, including empty ones.
+    pub statuses: BTreeMap<String, Vec<SnapshotItem>>,
+}
+
+/// The team's board as read at one moment.
+#[derive(Debug, Clone)]
+pub struct Board {
+    team: String,
+    statuses: Vec<StatusDef>,
+    roles: Vec<RoleDef>,
+    items: Vec<Item>,
+    fetched_at: DateTime<Utc>,
+}
+
+impl Board {
+    /// Reads `team`'s board, with the statuses and roles of its profile.
+    pub fn fetch(team: &TeamEntry, token: Option<&str>) -> Result<Board> {
+        if team.github_repo.is_empty() {
+            bail!("No GitHub repo configured for team '{}'", team.name);
+        }
+        let manifest_path = team.path.join("team").join("botminter.yml");
+        let contents = fs::read_to_string(&manifest_path)
+            .context("Failed to read team repo's botminter.yml")?;
+        let manifest: ProfileManifest = parse::yaml(&manifest_path, &contents)?;
+
+        let owner = team
+            .github_repo
+            .split('/')
+            .next()
+            .unwrap_or(&team.github_repo);
+        let number = find_project_number(owner, &team.name, token)?;
+        let items = list_items(owner, number, token)?;
+        Ok(Board {
+            team: team.name.clone(),
+            statuses: manifest.statuses,
+            roles: manifest.roles,
+            items,
+            fetched_at: Utc::now(),
+        })
+    }
+
+    /// The statuses `role` works: those matching its prefixes, or all of
+    /// them for a role without prefixes.
+    fn owned_statuses(&self, role: &str) -> Vec<&str> {
+        let prefixes = self
+            .roles
+            .iter()
+            .find(|r| r.name == role)
+            .map(|r| r.prefixes.as_slice())
+            .unwrap_or_default();
+        self.statuses
+            .iter()
+            .map(|s| s.name.as_str())
+            .filter(|name| {
+                prefixes.is_empty()
+                    || prefixes
+                        .iter()
+                        .any(|p| name.starts_with(&format!("{}:", p)))
+            })
+            .collect()
+    }
+
+    /// The snapshot for `member`, who works in `role`.
+    pub fn snapshot_for(&self, member: &str, role: &str) -> Snapshot {
+        let mut statuses: BTreeMap<String, Vec<SnapshotItem>> = self
+            .owned_statuses(role)
+            .into_iter()
+            .map(|s| (s.to_string(), Vec::new()))
+            .collect();
+        for item in &self.items {
+            let Some(items) = item.status.as_ref().and_then(|s| statuses.get_mut(s)) else {
+                continue;
+            };
+            items.push(SnapshotItem {
+                number: item.content.number,
+                repo: item.content.repository.clone(),
+                title: item.title.clone(),
+                assignees: item.assignees.clone(),
+            });
+        }
+        Snapshot {
+            team: self.team.clone(),
+            member: member.to_string(),
+            role: role.to_string(),
+            fetched_at: self.fetched_at.to_rfc3339(),
+            statuses,
+        }
+    }
+}
+
+impl Snapshot {
+    /// Writes the snapshot into `workspace`, keeping it out of `git status`
+    /// of the `.botminter/` clone.
+    pub fn write(&self, workspace: &Path) -> Result<()> {
+        let path = workspace.join(SNAPSHOT_FILE);
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize board")?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json + "\n")
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| format!("Failed to replace {}", path.display()))?;
+
+        let exclude = workspace.join(".botminter/.git/info/exclude");
+        if exclude.parent().is_some_and(Path::is_dir) {
+            let current = fs::read_to_string(&exclude).unwrap_or_default();
+            if !current.lines().any(|l| l == "/board.json") {
+                let mut updated = current;
+                if !updated.is_empty() && !updated.ends_with('\n') {
+                    updated.push('\n');
+                }
+                updated.push_str("/board.json\n");
+                fs::write(&exclude, updated)
+                    .with_context(|| format!("Failed to write {}", exclude.display()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Board {
+        let json = r#"{"items":[
+            {"title":"Design it","status":"arch:design","content":{"number":3,"repository":"org/team"}},
+            {"title":"Triage me","status":"po:triage","assignees":["ada"],
+             "content":{"number":7,"repository":"org/team"}},
+            {"title":"Draft","status":"arch:plan","content":{}},
+            {"title":"No status","content":{"number":8,"repository":"org/team"}}
+        ]}"#;
+        let list: ItemList = serde_json::from_str(json).unwrap();
+        let status = |name: &str| StatusDef {
+            name: name.to_string(),
+            description: String::new(),
+        };
+        let role = |name: &str, prefixes: &[&str]| RoleDef {
+            name: name.to_string(),
+            description: String::new(),
+            min: None,
+            max: None,
+            prefixes: prefixes.iter().map(|p| p.to_string()).collect(),
+        };
+        Board {
+            team: "t".to_string(),
+            statuses: vec![
+                status("po:triage"),
+                status("arch:design"),
+                status("arch:plan"),
+                status("arch:breakdown"),
+            ],
+            roles: vec![role("architect", &["arch"]), role("superman", &[])],
+            items: list.items,
+            fetched_at: DateTime::from_timestamp(1_771_668_000, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn snapshot_keeps_the_statuses_the_role_works() {
+        let board = board();
+        let snapshot = board.snapshot_for("architect-01", "architect");
+        let keys: Vec<&str> = snapshot.statuses.keys().map(String::as_str).collect();
+        assert_eq!(keys, vec!["arch:breakdown", "arch:design", "arch:plan"]);
+        assert!(snapshot.statuses["arch:breakdown"].is_empty());
+        assert_eq!(
+            snapshot.statuses["arch:design"],
+            vec![SnapshotItem {
+                number: Some(3),
+                repo: "org/team".to_string(),
+                title: "Design it".to_string(),
+                assignees: vec![],
+            }]
+        );
+        assert_eq!(snapshot.statuses["arch:plan"][0].number, None);
+
+        // Without prefixes, the whole board
+        let all = board.snapshot_for("superman-01", "superman");
+        assert_eq!(all.statuses.len(), 4);
+        assert_eq!(all.statuses["po:triage"][0].assignees, vec!["ada"]);
+    }
+
+    #[test]
+    fn write_replaces_the_snapshot_and_excludes_it_from_git() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join(".botminter/.git/info")).unwrap();
+        let board = board();
+        board
+            .snapshot_for("architect-01", "architect")
+            .write(tmp.path())
+            .unwrap();
+        board
+            .snapshot_for("architect-01", "architect")
+            .write(tmp.path())
+            .unwrap();
+
+        let written: Snapshot =
+            serde_json::from_str(&fs::read_to_string(tmp.path().join(SNAPSHOT_FILE)).unwrap())
+                .unwrap();
+        assert_eq!(written.member, "architect-01");
+        let exclude = fs::read_to_string(tmp.path().join(".botminter/.git/info/exclude")).unwrap();
+        assert_eq!(exclude, "/board.json\n");
+    }
+}
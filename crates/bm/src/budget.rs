@@ -0,0 +1,187 @@
+//! Spending caps per team.
+//!
+//! A team's `budget:` in config.yml caps what its members may spend in a UTC
+//! day and in a calendar month, as counted by [`crate::usage`]. Once spend
+//! reaches a cap, `bm start` refuses (unless `--override`) and the daemon
+//! holds queued events until the next day or month, or until the cap is
+//! raised.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::{bail, Result};
+use chrono::{Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::TeamEntry;
+use crate::members;
+use crate::notify::{Kind, Notification};
+use crate::usage::{self, Usage};
+
+/// The `budget:` section of a team in config.yml.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Budget {
+    /// Most the team may spend in a UTC day, in USD.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_usd: Option<f64>,
+    /// Most the team may spend in a calendar month (UTC), in USD.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_usd: Option<f64>,
+}
+
+impl Budget {
+    /// Checks the caps are positive amounts.
+    pub fn validate(&self) -> Result<()> {
+        for (key, cap) in [
+            ("daily_usd", self.daily_usd),
+            ("monthly_usd", self.monthly_usd),
+        ] {
+            if let Some(cap) = cap {
+                if !cap.is_finite() || cap <= 0.0 {
+                    bail!("budget.{} must be a positive amount, got {}", key, cap);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The first cap `days` (member → day → usage) has reached by `today`.
+    pub fn overrun(
+        &self,
+        days: &BTreeMap<String, BTreeMap<NaiveDate, Usage>>,
+        today: NaiveDate,
+    ) -> Option<Overrun> {
+        let spent = |from: NaiveDate| -> f64 {
+            days.values()
+                .flat_map(|d| d.range(from..=today))
+                .map(|(_, u)| u.cost_usd)
+                .sum()
+        };
+        if let Some(cap) = self.daily_usd {
+            let spent = spent(today);
+            if spent >= cap {
+                return Some(Overrun {
+                    period: "daily",
+                    spent,
+                    cap,
+                });
+            }
+        }
+        if let Some(cap) = self.monthly_usd {
+            let spent = spent(today.with_day(1).unwrap_or(today));
+            if spent >= cap {
+                return Some(Overrun {
+                    period: "monthly",
+                    spent,
+                    cap,
+                });
+            }
+        }
+        None
+    }
+}
+
+/// A cap that spend has reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overrun {
+    /// `daily` or `monthly`.
+    pub period: &'static str,
+    pub spent: f64,
+    pub cap: f64,
+}
+
+impl fmt::Display for Overrun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "spent ${:.2} of its ${:.2} {} budget",
+            self.spent, self.cap, self.period
+        )
+    }
+}
+
+/// Reads new usage from `team`'s member logs and returns the cap it has
+/// reached, if any. `None` for a team without a budget.
+pub fn check(team: &TeamEntry) -> Result<Option<Overrun>> {
+    let Some(ref budget) = team.budget else {
+        return Ok(None);
+    };
+    let names = members::list_names(&team.path.join("team").join("team")).unwrap_or_default();
+    let ledger = usage::collect(&team.name, &names)?;
+    let Some(days) = ledger.teams.get(&team.name) else {
+        return Ok(None);
+    };
+    Ok(budget.overrun(days, Utc::now().date_naive()))
+}
+
+/// The notification sent when the daemon stops at a cap.
+pub fn notification(team: &TeamEntry, overrun: &Overrun) -> Notification {
+    Notification::new(
+        &team.name,
+        Kind::Budget,
+        &format!("Over its {} budget", overrun.period),
+        format!(
+            "Team '{}' has {}. The daemon launches no members until spend is back under it.",
+            team.name, overrun
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spent(entries: &[(&str, u32, f64)]) -> BTreeMap<String, BTreeMap<NaiveDate, Usage>> {
+        let mut days: BTreeMap<String, BTreeMap<NaiveDate, Usage>> = BTreeMap::new();
+        for &(member, day, cost_usd) in entries {
+            days.entry(member.to_string()).or_default().insert(
+                NaiveDate::from_ymd_opt(2026, 3, day).unwrap(),
+                Usage {
+                    sessions: 1,
+                    cost_usd,
+                    ..Usage::default()
+                },
+            );
+        }
+        days
+    }
+
+    #[test]
+    fn overrun_sums_members_within_the_period() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let days = spent(&[("dev-01", 10, 6.0), ("qe-01", 10, 4.0), ("dev-01", 2, 30.0)]);
+
+        let daily = Budget {
+            daily_usd: Some(10.0),
+            ..Budget::default()
+        };
+        let over = daily.overrun(&days, today).unwrap();
+        assert_eq!(over.period, "daily");
+        assert_eq!(over.to_string(), "spent $10.00 of its $10.00 daily budget");
+        assert_eq!(daily.overrun(&days, today.succ_opt().unwrap()), None);
+
+        let monthly = Budget {
+            daily_usd: Some(50.0),
+            monthly_usd: Some(35.0),
+        };
+        assert_eq!(monthly.overrun(&days, today).unwrap().spent, 40.0);
+        // A new month starts from zero
+        let april = NaiveDate::from_ymd_opt(2026, 4, 1).unwrap();
+        assert_eq!(monthly.overrun(&days, april), None);
+    }
+
+    #[test]
+    fn validate_rejects_non_positive_caps() {
+        let budget = Budget {
+            monthly_usd: Some(0.0),
+            ..Budget::default()
+        };
+        assert!(budget
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("monthly_usd"));
+        assert!(Budget::default().validate().is_ok());
+    }
+}
@@ -0,0 +1,431 @@
+//! CPU and memory limits for locally launched members, via cgroup v2.
+//!
+//! Limits are applied with `systemd-run --user --scope` when a systemd user
+//! manager is available, otherwise by writing to a `botminter` cgroup under
+//! `/sys/fs/cgroup` directly (which needs root or a delegated hierarchy).
+//! `bm status` reads the member's cgroup back to report limit breaches.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+use anyhow::{Context, Result};
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// cgroup v2 `cpu.max` period, in microseconds.
+const CPU_PERIOD_USECS: u64 = 100_000;
+
+/// Resource limits for one member process.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Limits {
+    pub cpu_cores: Option<f64>,
+    pub memory_bytes: Option<u64>,
+}
+
+impl Limits {
+    pub fn is_empty(&self) -> bool {
+        self.cpu_cores.is_none() && self.memory_bytes.is_none()
+    }
+
+    /// `systemd-run` properties enforcing these limits.
+    fn systemd_properties(&self) -> Vec<String> {
+        let mut props = Vec::new();
+        if let Some(cores) = self.cpu_cores {
+            props.push(format!("CPUQuota={}%", (cores * 100.0).round() as u64));
+        }
+        if let Some(bytes) = self.memory_bytes {
+            props.push(format!("MemoryMax={}", bytes));
+        }
+        props
+    }
+
+    /// Contents for the cgroup `cpu.max` file.
+    fn cpu_max(cores: f64) -> String {
+        let quota = (cores * CPU_PERIOD_USECS as f64).round() as u64;
+        format!("{} {}", quota.max(1000), CPU_PERIOD_USECS)
+    }
+}
+
+/// How limits get applied on this machine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Backend {
+    /// Launch inside a transient systemd scope.
+    SystemdRun,
+    /// Move the launched process into a child of this cgroup.
+    Direct(PathBuf),
+    /// Limits can't be applied; the reason is shown to the user.
+    Unavailable(String),
+}
+
+/// Works out which backend can apply limits here.
+pub fn detect() -> Backend {
+    let systemd_ok = Command::new("systemd-run")
+        .args(["--user", "--scope", "--quiet", "--", "true"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success());
+    if systemd_ok {
+        return Backend::SystemdRun;
+    }
+
+    let root = Path::new(CGROUP_ROOT);
+    if !root.join("cgroup.controllers").exists() {
+        return Backend::Unavailable(format!("cgroup v2 is not mounted at {}", CGROUP_ROOT));
+    }
+    let base = root.join("botminter");
+    let prepared = fs::create_dir_all(&base)
+        .and_then(|_| fs::write(base.join("cgroup.subtree_control"), "+cpu +memory"));
+    match prepared {
+        Ok(()) => Backend::Direct(base),
+        Err(e) => Backend::Unavailable(format!(
+            "no systemd user manager, and {} is not writable ({})",
+            base.display(),
+            e
+        )),
+    }
+}
+
+/// Returns the command that launches `program args...` under `limits`.
+/// Only `SystemdRun` changes the command; `Direct` limits are applied with
+/// [`spawn_in`].
+pub fn command(backend: &Backend, limits: &Limits, program: &str, args: &[&str]) -> Command {
+    match backend {
+        Backend::SystemdRun if !limits.is_empty() => {
+            let mut cmd = Command::new("systemd-run");
+            cmd.args(["--user", "--scope", "--quiet", "--collect"]);
+            for prop in limits.systemd_properties() {
+                cmd.arg("-p").arg(prop);
+            }
+            cmd.arg("--").arg(program).args(args);
+            cmd
+        }
+        _ => {
+            let mut cmd = Command::new(program);
+            cmd.args(args);
+            cmd
+        }
+    }
+}
+
+/// Spawns the command `prepare` builds in a `name` cgroup under `base` with
+/// `limits` applied. If the cgroup can't be set up, or the child can't join
+/// it (no delegation, say), the command is spawned without limits and the
+/// reason is returned alongside the child.
+pub fn spawn_in(
+    base: &Path,
+    name: &str,
+    limits: &Limits,
+    prepare: impl Fn() -> Result<Command>,
+) -> Result<(Child, Option<anyhow::Error>)> {
+    let mut cmd = prepare()?;
+    if let Err(e) = attach(&mut cmd, base, name, limits) {
+        return Ok((cmd.spawn()?, Some(e)));
+    }
+    match cmd.spawn() {
+        Ok(child) => Ok((child, None)),
+        // A spawn error here may be the join or the command itself; if it
+        // starts without the join, it was the join
+        Err(join_err) => {
+            let child = prepare()?.spawn()?;
+            let dir = base.join(name);
+            let e =
+                anyhow::Error::new(join_err).context(format!("Failed to join {}", dir.display()));
+            Ok((child, Some(e)))
+        }
+    }
+}
+
+/// Creates a `name` cgroup under `base` with `limits` applied, and has `cmd`'s
+/// process join it between fork and exec, so the child and anything it forks
+/// run inside the limits from the start.
+fn attach(cmd: &mut Command, base: &Path, name: &str, limits: &Limits) -> Result<()> {
+    let dir = base.join(name);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let write = |file: &str, value: String| {
+        fs::write(dir.join(file), value)
+            .with_context(|| format!("Failed to write {}", dir.join(file).display()))
+    };
+    if let Some(cores) = limits.cpu_cores {
+        write("cpu.max", Limits::cpu_max(cores))?;
+    }
+    if let Some(bytes) = limits.memory_bytes {
+        write("memory.max", bytes.to_string())?;
+    }
+    let procs_path = dir.join("cgroup.procs");
+    let procs = fs::OpenOptions::new()
+        .write(true)
+        .open(&procs_path)
+        .with_context(|| format!("Failed to open {}", procs_path.display()))?;
+    join_on_exec(cmd, procs);
+    Ok(())
+}
+
+/// Writes the child's own PID to `procs` (an open `cgroup.procs`) before it
+/// execs.
+#[cfg(unix)]
+fn join_on_exec(cmd: &mut Command, procs: fs::File) {
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::process::CommandExt;
+
+    // Safety: the closure runs between fork and exec; it formats into a stack
+    // buffer and makes only async-signal-safe system calls.
+    unsafe {
+        cmd.pre_exec(move || {
+            let mut buf = [0u8; 20];
+            let pid = decimal(libc::getpid() as u32, &mut buf);
+            if libc::write(procs.as_raw_fd(), pid.as_ptr().cast(), pid.len()) < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn join_on_exec(_cmd: &mut Command, _procs: fs::File) {}
+
+/// Formats `n` into the end of `buf` without allocating, for use after fork.
+#[cfg(unix)]
+fn decimal(mut n: u32, buf: &mut [u8; 20]) -> &[u8] {
+    let mut start = buf.len();
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return &buf[start..];
+        }
+    }
+}
+
+/// Times a process has run into the limits of its cgroup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Breaches {
+    /// Times memory usage hit `memory.max`.
+    pub memory_max: u64,
+    /// Processes killed for exceeding `memory.max`.
+    pub oom_kills: u64,
+    /// CPU periods in which the process was throttled.
+    pub cpu_throttled: u64,
+}
+
+impl Breaches {
+    /// Human-readable summary, or `None` if no limit was hit.
+    pub fn describe(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.memory_max > 0 || self.oom_kills > 0 {
+            parts.push(format!(
+                "memory limit reached {} time(s), {} OOM kill(s)",
+                self.memory_max, self.oom_kills
+            ));
+        }
+        if self.cpu_throttled > 0 {
+            parts.push(format!(
+                "CPU throttled in {} period(s)",
+                self.cpu_throttled
+            ));
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join("; "))
+        }
+    }
+}
+
+/// Reads limit breaches from the cgroup of `pid`. Returns `None` when the
+/// process has no CPU or memory limit, or its cgroup can't be read.
+pub fn breaches(pid: u32) -> Option<Breaches> {
+    let membership = fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    let rel = membership.lines().find_map(|l| l.strip_prefix("0::"))?;
+    let dir = Path::new(CGROUP_ROOT).join(rel.trim_start_matches('/'));
+    read_breaches(&dir)
+}
+
+fn read_breaches(dir: &Path) -> Option<Breaches> {
+    let limited = |file: &str| {
+        fs::read_to_string(dir.join(file))
+            .is_ok_and(|c| c.split_whitespace().next().is_some_and(|v| v != "max"))
+    };
+    let memory_limited = limited("memory.max");
+    let cpu_limited = limited("cpu.max");
+    if !memory_limited && !cpu_limited {
+        return None;
+    }
+
+    let mut breaches = Breaches::default();
+    if memory_limited {
+        let events = fs::read_to_string(dir.join("memory.events")).unwrap_or_default();
+        breaches.memory_max = keyed_value(&events, "max").unwrap_or(0);
+        breaches.oom_kills = keyed_value(&events, "oom_kill").unwrap_or(0);
+    }
+    if cpu_limited {
+        let stat = fs::read_to_string(dir.join("cpu.stat")).unwrap_or_default();
+        breaches.cpu_throttled = keyed_value(&stat, "nr_throttled").unwrap_or(0);
+    }
+    Some(breaches)
+}
+
+/// Reads `key` from a flat-keyed cgroup file (`key value` per line).
+fn keyed_value(contents: &str, key: &str) -> Option<u64> {
+    contents.lines().find_map(|line| {
+        let (k, v) = line.split_once(' ')?;
+        if k == key {
+            v.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn systemd_run_wraps_command_with_properties() {
+        let limits = Limits {
+            cpu_cores: Some(1.5),
+            memory_bytes: Some(2 << 30),
+        };
+        let cmd = command(&Backend::SystemdRun, &limits, "ralph", &["run", "-p", "PROMPT.md"]);
+        assert_eq!(cmd.get_program(), "systemd-run");
+        let args: Vec<_> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(
+            args,
+            vec![
+                "--user",
+                "--scope",
+                "--quiet",
+                "--collect",
+                "-p",
+                "CPUQuota=150%",
+                "-p",
+                "MemoryMax=2147483648",
+                "--",
+                "ralph",
+                "run",
+                "-p",
+                "PROMPT.md"
+            ]
+        );
+
+        // Nothing to wrap without limits, or when the cgroup is written directly
+        let plain = command(&Backend::SystemdRun, &Limits::default(), "ralph", &["run"]);
+        assert_eq!(plain.get_program(), "ralph");
+        let direct = command(&Backend::Direct(PathBuf::from("/x")), &limits, "ralph", &["run"]);
+        assert_eq!(direct.get_program(), "ralph");
+    }
+
+    #[test]
+    fn cpu_max_uses_standard_period() {
+        assert_eq!(Limits::cpu_max(1.5), "150000 100000");
+        assert_eq!(Limits::cpu_max(0.25), "25000 100000");
+    }
+
+    #[test]
+    fn read_breaches_reports_events_for_limited_cgroups() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::write(dir.join("memory.max"), "2147483648\n").unwrap();
+        fs::write(dir.join("cpu.max"), "max 100000\n").unwrap();
+        fs::write(
+            dir.join("memory.events"),
+            "low 0\nhigh 0\nmax 7\noom 1\noom_kill 1\n",
+        )
+        .unwrap();
+        fs::write(dir.join("cpu.stat"), "usage_usec 10\nnr_throttled 42\n").unwrap();
+
+        let breaches = read_breaches(dir).unwrap();
+        assert_eq!(
+            breaches,
+            Breaches {
+                memory_max: 7,
+                oom_kills: 1,
+                cpu_throttled: 0,
+            }
+        );
+        assert_eq!(
+            breaches.describe().unwrap(),
+            "memory limit reached 7 time(s), 1 OOM kill(s)"
+        );
+
+        // Unlimited cgroups aren't reported at all
+        fs::write(dir.join("memory.max"), "max\n").unwrap();
+        assert!(read_breaches(dir).is_none());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn decimal_formats_without_allocating() {
+        let mut buf = [0u8; 20];
+        assert_eq!(decimal(0, &mut buf), b"0");
+        assert_eq!(decimal(4242, &mut buf), b"4242");
+        assert_eq!(decimal(u32::MAX, &mut buf), b"4294967295");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn attach_writes_limits_and_child_joins_before_exec() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("team-dev-bob");
+        fs::create_dir_all(&dir).unwrap();
+        // cgroupfs provides this file; a plain directory needs it made
+        fs::write(dir.join("cgroup.procs"), "").unwrap();
+        let limits = Limits {
+            cpu_cores: Some(2.0),
+            memory_bytes: Some(1024),
+        };
+        let mut cmd = Command::new("true");
+        attach(&mut cmd, tmp.path(), "team-dev-bob", &limits).unwrap();
+        let mut child = cmd.spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.join("cpu.max")).unwrap(),
+            "200000 100000"
+        );
+        assert_eq!(fs::read_to_string(dir.join("memory.max")).unwrap(), "1024");
+        assert_eq!(
+            fs::read_to_string(dir.join("cgroup.procs")).unwrap(),
+            pid.to_string()
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn spawn_in_runs_unlimited_when_the_child_cannot_join() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("team-dev-bob");
+        fs::create_dir_all(&dir).unwrap();
+        // Opens for writing, but every write fails (ENOSPC)
+        std::os::unix::fs::symlink("/dev/full", dir.join("cgroup.procs")).unwrap();
+        let limits = Limits {
+            cpu_cores: None,
+            memory_bytes: Some(1024),
+        };
+        let marker = tmp.path().join("ran");
+        let prepare = || {
+            let mut cmd = Command::new("touch");
+            cmd.arg(&marker);
+            Ok(cmd)
+        };
+        let (mut child, skipped) = spawn_in(tmp.path(), "team-dev-bob", &limits, prepare).unwrap();
+        assert!(child.wait().unwrap().success());
+        assert!(marker.exists());
+        let skipped = format!("{:#}", skipped.unwrap());
+        assert!(
+            skipped.starts_with(&format!("Failed to join {}", dir.display())),
+            "{}",
+            skipped
+        );
+
+        // A command that can't start at all is still an error
+        let missing = || Ok(Command::new("/nonexistent/ralph"));
+        assert!(spawn_in(tmp.path(), "team-dev-bob", &limits, missing).is_err());
+    }
+}
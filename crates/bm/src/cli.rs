@@ -1,9 +1,20 @@
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
 /// botminter — lead your own Claude Code agents
 #[derive(Parser)]
 #[command(name = "bm", version, about)]
 pub struct Cli {
+    /// Log more detail to stderr (-v debug, -vv trace); BM_LOG overrides
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// ASCII-only output: plain table borders and no Unicode symbols, for
+    /// logs, screen readers, and scripts
+    #[arg(long, global = true)]
+    pub plain: bool,
+
     #[command(subcommand)]
     pub command: Command,
 }
@@ -11,7 +22,31 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Command {
     /// Interactive wizard — create a new team
-    Init,
+    Init {
+        /// Join a team another operator created: clone its team repo
+        /// (owner/repo) and register it, instead of running the wizard
+        #[arg(long, value_name = "OWNER/REPO")]
+        from_existing: Option<String>,
+
+        /// Team name for --from-existing [default: the repo name]
+        #[arg(long, requires = "from_existing")]
+        name: Option<String>,
+    },
+
+    /// Guided demo — a sandbox team on a new private repo, one member, one run
+    Quickstart {
+        /// Team name [default: quickstart, or quickstart-N if taken]
+        #[arg(long)]
+        name: Option<String>,
+
+        /// GitHub owner (user or org) for the new repo [default: the authenticated user]
+        #[arg(long)]
+        owner: Option<String>,
+
+        /// Set up the team and demo issue without running the member
+        #[arg(long)]
+        no_run: bool,
+    },
 
     /// Hire a member into a role
     Hire {
@@ -22,6 +57,14 @@ pub enum Command {
         #[arg(long)]
         name: Option<String>,
 
+        /// Reuse a leftover workspace or stale state entry with the same name
+        #[arg(long)]
+        adopt_existing: bool,
+
+        /// Show the files and commit the hire would make without making them
+        #[arg(long)]
+        dry_run: bool,
+
         /// Team to operate on (defaults to default team)
         #[arg(short, long)]
         team: Option<String>,
@@ -37,6 +80,23 @@ pub enum Command {
         /// Formation to deploy with (default: local)
         #[arg(long)]
         formation: Option<String>,
+
+        /// Move a team running locally onto --formation: stop local members,
+        /// push their work, deploy, and merge the topology
+        #[arg(long, requires = "formation", conflicts_with = "dry_run")]
+        migrate: bool,
+
+        /// Start even if the team is paused
+        #[arg(long)]
+        force: bool,
+
+        /// Start even if the team is over its budget
+        #[arg(long = "override")]
+        override_budget: bool,
+
+        /// Show the processes that would be launched without launching them
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Stop all members
@@ -48,6 +108,38 @@ pub enum Command {
         /// Force-kill via SIGTERM instead of graceful stop
         #[arg(short, long)]
         force: bool,
+
+        /// Let all members finish their current iteration, then terminate any
+        /// still running after the graceful timeout
+        #[arg(long, conflicts_with = "force")]
+        drain: bool,
+
+        /// Seconds to wait for each member to exit after asking it to stop
+        #[arg(
+            long,
+            default_value_t = crate::commands::stop::DEFAULT_GRACEFUL_TIMEOUT_SECS,
+            conflicts_with = "force"
+        )]
+        graceful_timeout: u64,
+
+        /// Stop the members and daemons of every registered team
+        #[arg(long, conflicts_with = "team")]
+        all: bool,
+    },
+
+    /// Hold new work: the daemon launches no members and `bm start` refuses.
+    /// Running members carry on
+    Pause {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Lift a pause set with `bm pause`
+    Resume {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
     },
 
     /// Status dashboard
@@ -59,6 +151,60 @@ pub enum Command {
         /// Show verbose Ralph runtime details
         #[arg(short, long)]
         verbose: bool,
+
+        /// Keep refreshing the dashboard until interrupted
+        #[arg(short, long)]
+        watch: bool,
+
+        /// Seconds between refreshes in watch mode
+        #[arg(long, default_value_t = 2, requires = "watch")]
+        interval: u64,
+
+        /// Show cumulative uptime, crash counts, and mean time between crashes
+        #[arg(long)]
+        stats: bool,
+
+        /// Split the member table into groups: role or project
+        #[arg(long)]
+        group_by: Option<String>,
+
+        /// Order members by name, role, status, or uptime
+        #[arg(long, default_value = "name")]
+        sort: String,
+
+        /// Show the members of every registered team in one table
+        #[arg(
+            long,
+            conflicts_with_all = ["team", "verbose", "watch", "stats", "group_by"]
+        )]
+        all: bool,
+    },
+
+    /// Live control room: members, daemon, recent events, and member logs
+    Dashboard {
+        /// Team to show first
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Run a command where a member runs: in its workspace, or in its pod
+    Exec {
+        /// Member name (e.g., architect-01)
+        member: String,
+
+        /// Command to run, with its arguments, after `--`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Snapshot a member's workspace, or roll it back to a snapshot
+    Workspace {
+        #[command(subcommand)]
+        command: WorkspaceCommand,
     },
 
     /// Team management commands
@@ -91,6 +237,50 @@ pub enum Command {
         command: ProjectsCommand,
     },
 
+    /// Project board commands
+    Board {
+        #[command(subcommand)]
+        command: BoardCommand,
+    },
+
+    /// View or change ~/.botminter/config.yml
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Member state history
+    State {
+        #[command(subcommand)]
+        command: StateCommand,
+    },
+
+    /// Token and cost usage per member and day
+    Usage {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Only usage from this day on: a date (2026-03-01) or days back (7d)
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Who ran which mutating command, from ~/.botminter/audit.log
+    Audit {
+        /// Only entries from this day on: a date (2026-03-01) or days back (7d)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only entries for this team (default: all teams)
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Show at most this many of the latest entries
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+
     /// Knowledge and invariant management
     Knowledge {
         #[command(subcommand)]
@@ -111,6 +301,37 @@ pub enum Command {
         command: DaemonCommand,
     },
 
+    /// Take operator commands from the team's Telegram chat or Slack channel
+    Chatops {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// GitHub webhook registration and delivery health
+    Webhooks {
+        #[command(subcommand)]
+        command: WebhooksCommand,
+    },
+
+    /// Email notifications about crashes, errors, and a daily digest
+    Notify {
+        #[command(subcommand)]
+        command: NotifyCommand,
+    },
+
+    /// Formation topology commands
+    Topology {
+        #[command(subcommand)]
+        command: TopologyCommand,
+    },
+
+    /// Diagnostics for bug reports
+    Diag {
+        #[command(subcommand)]
+        command: DiagCommand,
+    },
+
     /// Internal: run the daemon event loop (not user-facing)
     #[command(hide = true)]
     DaemonRun {
@@ -129,6 +350,34 @@ pub enum Command {
         /// Polling interval in seconds for poll mode
         #[arg(long)]
         interval: u64,
+
+        /// IP address the webhook server binds to
+        #[arg(long, default_value = "0.0.0.0")]
+        bind: String,
+
+        /// URL path that accepts webhook deliveries
+        #[arg(long, default_value = "/webhook")]
+        path: String,
+
+        /// PEM certificate chain for HTTPS
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+
+        /// PEM private key for HTTPS
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+
+        /// Accept unsigned deliveries when the team has no webhook secret
+        #[arg(long)]
+        insecure_no_signature: bool,
+    },
+
+    /// Internal: keep GitHub App member tokens fresh (not user-facing)
+    #[command(hide = true)]
+    TokenRefresh {
+        /// Team name
+        #[arg(long)]
+        team: String,
     },
 
     /// Generate dynamic shell completions
@@ -146,6 +395,9 @@ Examples:
   Fish (save to completions directory):
     bm completions fish > ~/.config/fish/completions/bm.fish
 
+  Bash, Zsh, or Fish (detects $SHELL, writes the file, and checks it loads):
+    bm completions install
+
   PowerShell (add to $PROFILE):
     echo 'bm completions powershell | Invoke-Expression' >> $PROFILE
 
@@ -154,9 +406,66 @@ Examples:
 
 The generated script delegates to the bm binary at tab-time, so completions
 always reflect your current configuration (teams, roles, members, etc.).")]
+    #[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
     Completions {
+        #[command(subcommand)]
+        command: Option<CompletionsCommand>,
+
         /// Shell to generate completions for
-        shell: clap_complete::Shell,
+        shell: Option<clap_complete::Shell>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CompletionsCommand {
+    /// Write the completion file where your shell loads it and verify it
+    ///
+    /// Bash: the bash-completion user directory. Zsh: ~/.zfunc/_bm (add the
+    /// directory to fpath). Fish: ~/.config/fish/completions/bm.fish.
+    Install {
+        /// Shell to install for (default: detected from $SHELL)
+        #[arg(long)]
+        shell: Option<clap_complete::Shell>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WorkspaceCommand {
+    /// Archive a member's workspace under ~/.botminter/snapshots/
+    Snapshot {
+        /// Member name (e.g., architect-01)
+        member: String,
+
+        /// Leave out .git/objects; restoring keeps the workspace's current objects
+        #[arg(long)]
+        no_git_objects: bool,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// List a member's workspace snapshots
+    Snapshots {
+        /// Member name (e.g., architect-01)
+        member: String,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Replace a stopped member's workspace with a snapshot
+    Restore {
+        /// Member name (e.g., architect-01)
+        member: String,
+
+        /// Snapshot to restore, as listed by `bm workspace snapshots`
+        snapshot_id: String,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
     },
 }
 
@@ -175,16 +484,117 @@ pub enum TeamsCommand {
         team: Option<String>,
     },
 
+    /// Pull team repo changes pushed from other machines
+    Pull {
+        /// Rebase local commits onto origin when the histories have diverged
+        #[arg(long)]
+        rebase: bool,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
     /// Reconcile workspaces with team repo state
     Sync {
         /// Push team repo to GitHub before syncing
         #[arg(long)]
         push: bool,
 
+        /// Only report workspaces that drifted from the team repo (modified
+        /// copies, replaced or broken symlinks); exit non-zero if any did
+        #[arg(long, conflicts_with = "push")]
+        check: bool,
+
+        /// Show the commits, push, clones, and file refreshes a sync would
+        /// make without making them
+        #[arg(long, conflicts_with = "check")]
+        dry_run: bool,
+
+        /// Only sync this member's workspaces (e.g. architect-01)
+        #[arg(long)]
+        member: Option<String>,
+
+        /// Only sync workspaces for this project
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Check the team repo's structure against its profile; exit non-zero
+    /// on any error
+    Verify {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Add a GitHub repo the team works in besides its team repo
+    AddRepo {
+        /// Repo as owner/name (e.g. my-org/api)
+        repo: String,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Stop working in a repo added with add-repo
+    RemoveRepo {
+        /// Repo as owner/name
+        repo: String,
+
         /// Team to operate on
         #[arg(short, long)]
         team: Option<String>,
     },
+
+    /// Unregister a team, leaving its GitHub repo untouched
+    Remove {
+        /// Team to remove (never defaults)
+        name: String,
+
+        /// Stop the team's running members and daemon first
+        #[arg(long)]
+        stop: bool,
+
+        /// Also delete the team directory, its workspaces, and its runtime
+        /// files under ~/.botminter
+        #[arg(long)]
+        purge: bool,
+
+        /// Don't ask before purging
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Make a team the default for commands run without -t
+    SetDefault {
+        /// Team name
+        name: String,
+    },
+
+    /// Remove a team entirely: stop it, delete its GitHub repo, board, and
+    /// workspaces, and unregister it
+    Destroy {
+        /// Team to destroy (never defaults)
+        name: String,
+
+        /// Keep the GitHub repo, project board, and webhooks
+        #[arg(long)]
+        keep_github: bool,
+
+        /// Show what would be removed without removing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip typing the team name to confirm
+        #[arg(short, long)]
+        yes: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -215,6 +625,20 @@ pub enum RolesCommand {
         #[arg(short, long)]
         team: Option<String>,
     },
+
+    /// Show a role's skeleton, rendered PROMPT.md, and board statuses
+    Describe {
+        /// Role to describe
+        role: String,
+
+        /// Show all of PROMPT.md instead of its first lines
+        #[arg(long)]
+        full: bool,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -227,6 +651,33 @@ pub enum ProfilesCommand {
         /// Profile name to describe
         profile: String,
     },
+
+    /// Check profile manifests for unknown fields and invalid values
+    Validate {
+        /// Embedded profile name, or path to a botminter.yml (or a directory
+        /// containing one). Validates all embedded profiles if omitted.
+        target: Option<String>,
+    },
+
+    /// Copy an embedded profile, including role skeletons, to a directory
+    Export {
+        /// Embedded profile name
+        profile: String,
+
+        /// Directory to write to (must not exist or be empty)
+        dir: String,
+    },
+
+    /// Check a profile directory: manifest, role skeletons, labels, statuses
+    /// and views, formations, and daemon.yml
+    Lint {
+        /// Path to the profile directory
+        path: String,
+
+        /// Print findings as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -253,16 +704,112 @@ pub enum ProjectsCommand {
         /// Git URL of the project fork
         url: String,
 
+        /// Show the labels, files, and commit adding it would create
+        /// without creating them
+        #[arg(long)]
+        dry_run: bool,
+
         /// Team to operate on
         #[arg(short, long)]
         team: Option<String>,
     },
 
-    /// Sync GitHub Project board status options and print view setup instructions
+    /// Sync GitHub Project board status options and role-based views
     Sync {
         /// Team to operate on
         #[arg(short, long)]
         team: Option<String>,
+
+        /// Create missing views on the board instead of printing
+        /// instructions for them
+        #[arg(long)]
+        create_views: bool,
+
+        /// Only report work repo settings (merge strategy, branch
+        /// protection) that drifted from the profile; exit non-zero if any did
+        #[arg(long, conflicts_with = "create_views")]
+        check: bool,
+    },
+    /// Create the profile's seed issues on the team repo and board
+    Seed {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// List the issues that would be created without creating them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BoardCommand {
+    /// Show the board items in one of the profile's views, or list the views
+    View {
+        /// View name, e.g. PO (lists the views if omitted)
+        view: Option<String>,
+
+        /// Open the board in a browser, filtered to the view
+        #[arg(long)]
+        open: bool,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Show a setting, e.g. timestamps or teams.my-team.github_repo
+    Get {
+        /// Dotted setting key
+        key: String,
+
+        /// Read the key within this team's entry (e.g. credentials.gh_token)
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Change a setting, validating the result before it is saved
+    Set {
+        /// Dotted setting key, e.g. teams.my-team.credentials.telegram_bot_token
+        key: String,
+
+        /// New value, read as YAML: numbers, true/false, and [a, b] lists work
+        value: String,
+
+        /// Set the key within this team's entry
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Remove an optional setting
+    Unset {
+        /// Dotted setting key
+        key: String,
+
+        /// Remove the key within this team's entry
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum StateCommand {
+    /// Show when members started, stopped, and crashed
+    History {
+        /// Only this member (e.g. architect-01)
+        #[arg(long)]
+        member: Option<String>,
+
+        /// Show at most this many of the latest events
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
     },
 }
 
@@ -288,6 +835,75 @@ pub enum KnowledgeCommand {
         #[arg(short, long)]
         team: Option<String>,
     },
+
+    /// Create a knowledge/invariant file in a scope and commit it
+    Add {
+        /// Scope: team, project, member, or member-project
+        scope: String,
+
+        /// File name (".md" is appended if missing)
+        name: String,
+
+        /// Project, for the project and member-project scopes
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Member, for the member and member-project scopes
+        #[arg(long)]
+        member: Option<String>,
+
+        /// Create an invariant instead of a knowledge file
+        #[arg(long)]
+        invariant: bool,
+
+        /// Copy the content from this file
+        #[arg(long, conflicts_with = "edit")]
+        from_file: Option<PathBuf>,
+
+        /// Write the content in $EDITOR before committing
+        #[arg(long)]
+        edit: bool,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Edit a knowledge/invariant file in $EDITOR and commit the change
+    Edit {
+        /// Path to the file (relative to team repo root)
+        path: String,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Search knowledge/invariant files for a text pattern
+    Grep {
+        /// Text to search for (matched literally)
+        pattern: String,
+
+        /// Match case-insensitively
+        #[arg(short = 'i', long)]
+        ignore_case: bool,
+
+        /// Lines of context to show around each match
+        #[arg(short = 'C', long, default_value_t = 0)]
+        context: usize,
+
+        /// Print matches as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Filter by scope: team, project, member, or member-project
+        #[arg(long)]
+        scope: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -298,24 +914,108 @@ pub enum DaemonCommand {
         #[arg(short, long)]
         team: Option<String>,
 
-        /// Daemon mode: webhook or poll
-        #[arg(long, default_value = "webhook")]
-        mode: String,
+        /// Daemon mode: webhook or poll [default: from daemon.yml, else webhook]
+        #[arg(long)]
+        mode: Option<String>,
 
-        /// HTTP listener port for webhook mode
-        #[arg(long, default_value = "8484")]
-        port: u16,
+        /// HTTP listener port for webhook mode [default: from daemon.yml, else 8484]
+        #[arg(long)]
+        port: Option<u16>,
 
-        /// Polling interval in seconds for poll mode
-        #[arg(long, default_value = "60")]
-        interval: u64,
+        /// Polling interval in seconds for poll mode [default: from daemon.yml, else 60]
+        #[arg(long)]
+        interval: Option<u64>,
+
+        /// IP address the webhook server binds to [default: 0.0.0.0]
+        #[arg(long)]
+        bind: Option<String>,
+
+        /// URL path that accepts webhook deliveries [default: /webhook]
+        #[arg(long)]
+        path: Option<String>,
+
+        /// PEM certificate chain for serving the webhook over HTTPS
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+
+        /// PEM private key (PKCS#8 or RSA) for --tls-cert
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+
+        /// Expose the webhook through a public tunnel (ngrok or cloudflared)
+        /// and register it on the team repo
+        #[arg(long, conflicts_with = "insecure_no_signature")]
+        tunnel: Option<String>,
+
+        /// Run webhook mode without a webhook secret, accepting unsigned
+        /// deliveries
+        #[arg(long)]
+        insecure_no_signature: bool,
+
+        /// Show the daemon process, files, tunnel, and webhooks it would
+        /// create without starting anything
+        #[arg(long)]
+        dry_run: bool,
     },
 
-    /// Stop the running daemon
+    /// Restart the daemon, keeping its current settings unless overridden
+    Restart {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Daemon mode: webhook or poll [default: current, else from daemon.yml]
+        #[arg(long)]
+        mode: Option<String>,
+
+        /// HTTP listener port for webhook mode [default: current, else from daemon.yml]
+        #[arg(long)]
+        port: Option<u16>,
+
+        /// Polling interval in seconds for poll mode [default: current, else from daemon.yml]
+        #[arg(long)]
+        interval: Option<u64>,
+
+        /// IP address the webhook server binds to [default: current, else 0.0.0.0]
+        #[arg(long)]
+        bind: Option<String>,
+
+        /// URL path that accepts webhook deliveries [default: current, else /webhook]
+        #[arg(long)]
+        path: Option<String>,
+
+        /// PEM certificate chain for serving the webhook over HTTPS
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+
+        /// PEM private key (PKCS#8 or RSA) for --tls-cert
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+
+        /// Expose the webhook through a public tunnel (ngrok or cloudflared)
+        /// [default: current]
+        #[arg(long, conflicts_with = "insecure_no_signature")]
+        tunnel: Option<String>,
+
+        /// Run webhook mode without a webhook secret, accepting unsigned
+        /// deliveries [default: current]
+        #[arg(long)]
+        insecure_no_signature: bool,
+    },
+
+    /// Stop the running daemon, letting an in-flight run finish first
     Stop {
         /// Team to operate on
         #[arg(short, long)]
         team: Option<String>,
+
+        /// Seconds to let an in-flight run finish before its members are stopped
+        #[arg(long, default_value_t = crate::commands::daemon::DEFAULT_DRAIN_SECS)]
+        drain_timeout: u64,
+
+        /// Stop in-flight members right away instead of letting the run finish
+        #[arg(long, conflicts_with = "drain_timeout")]
+        no_drain: bool,
     },
 
     /// Show daemon status
@@ -323,5 +1023,209 @@ pub enum DaemonCommand {
         /// Team to operate on
         #[arg(short, long)]
         team: Option<String>,
+
+        /// Show the daemons of every registered team in one table
+        #[arg(long, conflicts_with = "team")]
+        all: bool,
+    },
+
+    /// Show recent one-shot runs: trigger events, members, and exit statuses
+    History {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Number of most recent runs to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Check whether an event would trigger a run, without launching anything
+    Simulate {
+        /// Event type in webhook naming, e.g. issues or issue_comment
+        event: String,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Event action, e.g. opened or labeled
+        #[arg(long)]
+        action: Option<String>,
+
+        /// Label on the issue or pull request (repeatable)
+        #[arg(long = "label")]
+        labels: Vec<String>,
+
+        /// Login of whoever triggered the event
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Repository the event comes from [default: the team repo]
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Branch the event concerns, e.g. the branch a push updated
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Webhook payload JSON to take action, labels, author, repo, and branch from
+        #[arg(long, conflicts_with_all = ["action", "labels", "author", "repo", "branch"])]
+        payload: Option<PathBuf>,
+
+        /// Filter expression to try in place of the configured filters.expr
+        #[arg(long)]
+        expr: Option<String>,
+    },
+
+    /// View or change daemon settings, reloading a running daemon
+    Config {
+        #[command(subcommand)]
+        command: DaemonConfigCommand,
+    },
+
+    /// Inspect and replay recent webhook deliveries
+    Events {
+        #[command(subcommand)]
+        command: DaemonEventsCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DaemonConfigCommand {
+    /// Show effective daemon settings and where each comes from
+    Get {
+        /// Setting to show (default: all)
+        key: Option<String>,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Change a daemon setting
+    Set {
+        /// Setting to change, e.g. filters.events
+        key: String,
+
+        /// New value; lists are comma-separated, quiet_hours is HH:MM-HH:MM
+        value: String,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Write the team repo's daemon.yml instead of the local override
+        #[arg(long)]
+        shared: bool,
+    },
+
+    /// Remove a setting, falling back to the next layer or the default
+    Unset {
+        /// Setting to remove
+        key: String,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Remove it from the team repo's daemon.yml instead of the local override
+        #[arg(long)]
+        shared: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DaemonEventsCommand {
+    /// List recent webhook deliveries and what the daemon did with each
+    List {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Number of most recent deliveries to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Show a delivery's headers, verdict, and redacted payload
+    Show {
+        /// Delivery ID, or a unique prefix of it
+        id: String,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+
+    /// Feed a recorded delivery to the running daemon again, under its
+    /// current settings
+    Replay {
+        /// Delivery ID, or a unique prefix of it
+        id: String,
+
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WebhooksCommand {
+    /// Create or update the team repo's webhook for a public URL
+    Setup {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Public URL GitHub delivers to; the daemon's webhook path is
+        /// appended when the URL has none
+        #[arg(long)]
+        url: String,
+    },
+
+    /// Show the team repo's webhooks and their recent deliveries
+    Status {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum NotifyCommand {
+    /// Send a test message through each of the team's notification backends
+    Test {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DiagCommand {
+    /// Write a redacted support archive (configs, logs, state, versions)
+    Bundle {
+        /// Only include this team (default: all registered teams)
+        #[arg(short, long)]
+        team: Option<String>,
+
+        /// Archive path (default: ./bm-diag-<timestamp>.tar.gz)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Number of most recent log files to include
+        #[arg(long, default_value_t = crate::commands::diag::DEFAULT_LOG_FILES)]
+        logs: usize,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TopologyCommand {
+    /// Re-check where members are running and update topology.json
+    Refresh {
+        /// Team to operate on
+        #[arg(short, long)]
+        team: Option<String>,
     },
 }
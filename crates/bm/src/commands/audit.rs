@@ -0,0 +1,73 @@
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::audit::{self, Entry};
+use crate::commands::usage::parse_since;
+use crate::tables;
+use crate::timefmt;
+
+/// Handles `bm audit [--since <date>] [-t team] [--limit n]`.
+/// Lists who ran which mutating command against which team, and whether
+/// it worked, oldest first.
+pub fn run(since: Option<&str>, team: Option<&str>, limit: usize) -> Result<()> {
+    let since = since
+        .map(|s| parse_since(s, Utc::now().date_naive()))
+        .transpose()?;
+
+    let entries = audit::load(&audit::audit_path()?)?;
+    let rows: Vec<&Entry> = entries
+        .iter()
+        .filter(|e| team.is_none() || e.team.as_deref() == team)
+        .filter(|e| match (since, e.time()) {
+            (Some(since), Some(at)) => at.date_naive() >= since,
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .collect();
+    if rows.is_empty() {
+        match team {
+            Some(t) => println!("No audit entries for team '{}'.", t),
+            None => println!("No audit entries."),
+        }
+        return Ok(());
+    }
+
+    let shown = &rows[rows.len().saturating_sub(limit)..];
+    let mut table = tables::new(vec!["Time", "User", "Team", "Command", "Result"]);
+    for entry in shown {
+        table.add_row(vec![
+            timefmt::rfc3339(&entry.at),
+            entry.user.clone(),
+            entry.team.clone().unwrap_or_default(),
+            command_line(entry),
+            result(entry),
+        ]);
+    }
+    println!("{}", tables::render(&table));
+    if shown.len() < rows.len() {
+        println!(
+            "Showing the last {} of {} entries; use --limit to see more.",
+            shown.len(),
+            rows.len()
+        );
+    }
+    Ok(())
+}
+
+/// The command as it was typed, or its name if no arguments were kept.
+fn command_line(entry: &Entry) -> String {
+    if entry.args.is_empty() {
+        entry.command.clone()
+    } else {
+        entry.args.join(" ")
+    }
+}
+
+/// `ok`, or the first line of why the command failed.
+fn result(entry: &Entry) -> String {
+    match (&entry.error, entry.ok) {
+        (_, true) => "ok".to_string(),
+        (Some(error), false) => format!("failed: {}", error.lines().next().unwrap_or_default()),
+        (None, false) => "failed".to_string(),
+    }
+}
@@ -0,0 +1,248 @@
+use std::fs;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use tracing::debug;
+
+use crate::board::{self, Item};
+use crate::config;
+use crate::parse;
+use crate::profile::{self, ViewDef};
+use crate::tables;
+
+use super::init::find_project_number;
+
+/// Handles `bm board view [<view>] [--open] [-t team]`.
+/// Without a view, lists the profile's views. With one, shows the board
+/// items in that view's statuses, or opens the board filtered to them.
+pub fn view(name: Option<&str>, open: bool, team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+
+    let manifest_path = team.path.join("team").join("botminter.yml");
+    let manifest: profile::ProfileManifest = {
+        let contents = fs::read_to_string(&manifest_path)
+            .context("Failed to read team repo's botminter.yml")?;
+        parse::yaml(&manifest_path, &contents)?
+    };
+
+    let Some(name) = name else {
+        if manifest.views.is_empty() {
+            println!("No views defined in the profile.");
+            return Ok(());
+        }
+        let mut table = tables::new(vec!["View", "Statuses"]);
+        for v in &manifest.views {
+            table.add_row(vec![
+                v.name.clone(),
+                v.resolve_statuses(&manifest.statuses).join(", "),
+            ]);
+        }
+        println!("{}", tables::render(&table));
+        return Ok(());
+    };
+    let view = find_view(&manifest.views, name)?;
+    let statuses = view.resolve_statuses(&manifest.statuses);
+
+    if team.github_repo.is_empty() {
+        bail!("No GitHub repo configured for team '{}'", team.name);
+    }
+    let owner = team
+        .github_repo
+        .split('/')
+        .next()
+        .unwrap_or(&team.github_repo);
+    let gh_token = team.credentials.gh_token.as_deref();
+    let number = find_project_number(owner, &team.name, gh_token)?;
+
+    if open {
+        let out = run_gh(
+            &[
+                "project", "view", &number.to_string(), "--owner", owner, "--format", "json",
+            ],
+            gh_token,
+        )?;
+        let project: serde_json::Value =
+            serde_json::from_str(&out).context("Could not parse project JSON")?;
+        let url = project["url"].as_str().context("Project JSON has no url")?;
+        let url = filtered_url(url, &view.filter_string(&manifest.statuses));
+        println!("{}", url);
+        if let Err(e) = open_in_browser(&url) {
+            eprintln!(
+                "Could not open a browser ({:#}); open the URL above instead.",
+                e
+            );
+        }
+        return Ok(());
+    }
+
+    let items = in_view(board::list_items(owner, number, gh_token)?, &statuses);
+    if items.is_empty() {
+        println!(
+            "No items in view '{}' ({}).",
+            view.name,
+            statuses.join(", ")
+        );
+        return Ok(());
+    }
+
+    let mut table = tables::new(vec!["Status", "Issue", "Title", "Assignees"]);
+    for item in &items {
+        let issue = match item.content.number {
+            Some(n) if item.content.repository == team.github_repo => format!("#{}", n),
+            Some(n) => format!("{}#{}", item.content.repository, n),
+            None => "draft".to_string(),
+        };
+        table.add_row(vec![
+            item.status.clone().unwrap_or_default(),
+            issue,
+            item.title.clone(),
+            item.assignees.join(", "),
+        ]);
+    }
+    println!("{} ({} items)", view.name, items.len());
+    println!("{}", tables::render(&table));
+    Ok(())
+}
+
+/// Finds the view named `name`, ignoring case.
+fn find_view<'a>(views: &'a [ViewDef], name: &str) -> Result<&'a ViewDef> {
+    if let Some(view) = views.iter().find(|v| v.name.eq_ignore_ascii_case(name)) {
+        return Ok(view);
+    }
+    if views.is_empty() {
+        bail!("View '{}' not found. The profile defines no views.", name);
+    }
+    let names: Vec<&str> = views.iter().map(|v| v.name.as_str()).collect();
+    bail!(
+        "View '{}' not found. Available views: {}",
+        name,
+        names.join(", ")
+    );
+}
+
+/// Keeps the items whose status is one of `statuses`, ordered as they are.
+fn in_view(items: Vec<Item>, statuses: &[String]) -> Vec<Item> {
+    let mut items: Vec<(usize, Item)> = items
+        .into_iter()
+        .filter_map(|item| {
+            let pos = statuses
+                .iter()
+                .position(|s| Some(s) == item.status.as_ref())?;
+            Some((pos, item))
+        })
+        .collect();
+    items.sort_by_key(|(pos, _)| *pos);
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
+/// The board URL with `filter` applied, as GitHub's filter bar would set it.
+fn filtered_url(url: &str, filter: &str) -> String {
+    let encoded: String = filter
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    format!("{}?filterQuery={}", url, encoded)
+}
+
+/// Opens `url` with the platform's default handler.
+fn open_in_browser(url: &str) -> Result<()> {
+    let mut cmd = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", ""]);
+        cmd
+    } else {
+        Command::new("xdg-open")
+    };
+    let status = cmd
+        .arg(url)
+        .status()
+        .context("Failed to launch a browser")?;
+    if !status.success() {
+        bail!("the browser launcher exited with {}", status);
+    }
+    Ok(())
+}
+
+/// Runs `gh` with `args`. Returns stdout.
+fn run_gh(args: &[&str], gh_token: Option<&str>) -> Result<String> {
+    debug!("Running gh {}", args.join(" "));
+    let mut cmd = Command::new("gh");
+    cmd.args(args);
+    if let Some(token) = gh_token {
+        cmd.env("GH_TOKEN", token);
+    }
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run `gh {}`", args[..2.min(args.len())].join(" ")))?;
+    if !output.status.success() {
+        bail!(
+            "gh {} failed: {}",
+            args[..2.min(args.len())].join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::ItemList;
+
+    fn view_def(name: &str) -> ViewDef {
+        ViewDef {
+            name: name.to_string(),
+            prefixes: vec!["po".to_string()],
+            also_include: vec!["done".to_string()],
+        }
+    }
+
+    #[test]
+    fn find_view_ignores_case_and_lists_names() {
+        let views = vec![view_def("PO"), view_def("Architect")];
+        assert_eq!(find_view(&views, "po").unwrap().name, "PO");
+        let err = find_view(&views, "qe").unwrap_err().to_string();
+        assert!(err.contains("Available views: PO, Architect"), "{}", err);
+    }
+
+    #[test]
+    fn in_view_filters_and_orders_by_status() {
+        let json = r#"{"items":[
+            {"title":"Ship it","status":"done","content":{"number":3,"repository":"org/team"}},
+            {"title":"Draft","status":"po:backlog","content":{}},
+            {"title":"Other","status":"dev:ready","content":{"number":5,"repository":"org/team"}},
+            {"title":"New","status":"po:triage","assignees":["ada"],
+             "content":{"number":7,"repository":"org/team"}},
+            {"title":"No status","content":{"number":8,"repository":"org/team"}}
+        ],"totalCount":5}"#;
+        let list: ItemList = serde_json::from_str(json).unwrap();
+        let statuses: Vec<String> = ["po:triage", "po:backlog", "done"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let titles: Vec<String> = in_view(list.items, &statuses)
+            .into_iter()
+            .map(|i| i.title)
+            .collect();
+        assert_eq!(titles, vec!["New", "Draft", "Ship it"]);
+    }
+
+    #[test]
+    fn filtered_url_encodes_the_filter() {
+        assert_eq!(
+            filtered_url(
+                "https://github.com/orgs/acme/projects/4",
+                "status:po:triage,done"
+            ),
+            "https://github.com/orgs/acme/projects/4?filterQuery=status%3Apo%3Atriage%2Cdone"
+        );
+    }
+}
@@ -0,0 +1,455 @@
+//! `bm chatops`: operator commands from the team's Telegram chat or Slack
+//! channel.
+//!
+//! Reads messages sent to the team bot in `telegram_chat_id` (or, for a team
+//! on Slack, mentions of its bot in `slack_channel`) and answers `/status`,
+//! `/stop <member>`, `/trigger`, `/pause`, and `/resume` from the users listed
+//! under `chatops.allowed_users` (`chatops.slack_users` on Slack) in
+//! `daemon.yml`. Messages sent while it wasn't running are skipped, so a stale
+//! `/stop` never fires late.
+
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+
+use crate::audit;
+use crate::commands::{daemon, status, stop};
+use crate::config::{self, TeamEntry};
+use crate::daemon_settings;
+use crate::procutil::{self, SHUTDOWN_REQUESTED};
+use crate::slack;
+use crate::state;
+use crate::telegram;
+
+/// Seconds each `getUpdates` long poll waits for a message.
+const POLL_WAIT_SECS: u64 = 25;
+
+/// Seconds between reads of a Slack channel's history.
+const SLACK_POLL_SECS: u64 = 3;
+
+/// Seconds to wait before polling again after a failed request.
+const RETRY_SECS: u64 = 5;
+
+const HELP: &str = "\
+/status — members and daemon
+/stop <member> — stop a running member after its current iteration
+/trigger — ask the daemon for a run now
+/pause — hold daemon launches (events keep queueing)
+/resume — lift a pause";
+
+/// A command an operator sent to the bot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ChatCommand {
+    Help,
+    Status,
+    Stop(String),
+    Trigger,
+    Pause,
+    Resume,
+}
+
+/// Where commands are read and answered.
+enum Chat {
+    Telegram {
+        token: String,
+        chat_id: i64,
+        /// The bot's username, for commands addressed `/status@bot`.
+        bot: String,
+        offset: i64,
+    },
+    Slack {
+        token: String,
+        channel: String,
+        /// The bot's member id; commands start with a mention of it.
+        bot: String,
+        /// Timestamp of the last message read.
+        oldest: String,
+    },
+}
+
+/// A command someone sent in the team's chat.
+struct Incoming {
+    /// Telegram user id or Slack member id, checked against the allowed users.
+    from_id: String,
+    from: String,
+    text: String,
+    command: Result<ChatCommand, String>,
+}
+
+impl Chat {
+    /// Connects to the team's Telegram chat, or its Slack channel if it has
+    /// no Telegram bot, skipping messages already there.
+    fn connect(team: &TeamEntry) -> Result<Chat> {
+        let c = &team.credentials;
+        if let Some(token) = c.telegram_bot_token.clone() {
+            let Some(chat_id) = c.telegram_chat_id else {
+                bail!(
+                    "Team '{}' has no Telegram chat id, so bm doesn't know which chat to listen \
+                     to. Add telegram_chat_id under the team's credentials in \
+                     ~/.botminter/config.yml.",
+                    team.name
+                );
+            };
+            let bot = telegram::get_me(&token)?.username;
+            let offset = telegram::skip_pending(&token)?;
+            return Ok(Chat::Telegram {
+                token,
+                chat_id,
+                bot,
+                offset,
+            });
+        }
+        if let Some(token) = c.slack_bot_token.clone() {
+            let Some(channel) = c.slack_channel.clone() else {
+                bail!(
+                    "Team '{}' has no Slack channel, so bm doesn't know where to listen. \
+                     Add slack_channel under the team's credentials in ~/.botminter/config.yml.",
+                    team.name
+                );
+            };
+            let bot = slack::auth_test(&token)?.user_id;
+            let oldest = slack::latest_ts(&token, &channel)?;
+            return Ok(Chat::Slack {
+                token,
+                channel,
+                bot,
+                oldest,
+            });
+        }
+        bail!(
+            "Team '{}' has no Telegram or Slack bot. Add telegram_bot_token and \
+             telegram_chat_id, or slack_bot_token and slack_channel, under the team's \
+             credentials in ~/.botminter/config.yml.",
+            team.name
+        );
+    }
+
+    /// The `daemon.yml` key listing who may send commands here.
+    fn users_key(&self) -> &'static str {
+        match self {
+            Chat::Telegram { .. } => "chatops.allowed_users",
+            Chat::Slack { .. } => "chatops.slack_users",
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Chat::Telegram { chat_id, bot, .. } => format!("as @{} in chat {}", bot, chat_id),
+            Chat::Slack { channel, .. } => format!("in Slack channel {}", channel),
+        }
+    }
+
+    /// Waits for the next commands. Telegram is long-polled; Slack has no
+    /// long poll, so its channel history is read every few seconds.
+    fn poll(&mut self) -> Result<Vec<Incoming>> {
+        match self {
+            Chat::Telegram {
+                token,
+                chat_id,
+                bot,
+                offset,
+            } => {
+                let updates = telegram::get_messages(token, *offset, POLL_WAIT_SECS)?;
+                *offset = updates.next_offset;
+                Ok(updates
+                    .messages
+                    .into_iter()
+                    .filter(|m| m.chat_id == *chat_id)
+                    .filter_map(|m| {
+                        Some(Incoming {
+                            command: parse(&m.text, bot)?,
+                            from_id: m.from_id.to_string(),
+                            from: m.from,
+                            text: m.text,
+                        })
+                    })
+                    .collect())
+            }
+            Chat::Slack {
+                token,
+                channel,
+                bot,
+                oldest,
+            } => {
+                thread::sleep(Duration::from_secs(SLACK_POLL_SECS));
+                let messages = slack::messages_after(token, channel, oldest)?;
+                if let Some(last) = messages.last() {
+                    *oldest = last.ts.clone();
+                }
+                Ok(messages
+                    .into_iter()
+                    .filter_map(|m| {
+                        Some(Incoming {
+                            command: parse_mention(&m.text, bot)?,
+                            from_id: m.user.clone(),
+                            from: format!("<@{}>", m.user),
+                            text: m.text,
+                        })
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Sends a reply, reporting (not raising) failures so the loop keeps going.
+    fn reply(&self, text: &str) {
+        let (result, place) = match self {
+            Chat::Telegram { token, chat_id, .. } => (
+                telegram::send_message(token, *chat_id, text),
+                format!("chat {}", chat_id),
+            ),
+            Chat::Slack { token, channel, .. } => (
+                slack::post_message(token, channel, text),
+                format!("Slack channel {}", channel),
+            ),
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to reply in {}: {:#}", place, e);
+        }
+    }
+}
+
+/// Handles `bm chatops [-t team]`.
+pub fn run(team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+
+    let mut chat = Chat::connect(team)?;
+    let settings = daemon_settings::load(&team.path.join("team"), &team.name)?;
+    let allowed: Vec<String> = match chat {
+        Chat::Telegram { .. } => settings.chatops_users().iter().map(|u| u.to_string()).collect(),
+        Chat::Slack { .. } => settings.chatops_slack_users().to_vec(),
+    };
+    if allowed.is_empty() {
+        bail!(
+            "No one may send chatops commands for team '{}'. List user ids under {} \
+             in the team repo's {}, or in {} for this machine only.",
+            team.name,
+            chat.users_key(),
+            daemon_settings::SETTINGS_FILE,
+            daemon_settings::local_override_path(&team.name)?.display()
+        );
+    }
+
+    procutil::install_signal_handlers();
+    println!(
+        "Listening {} for team '{}' ({} allowed user(s)). Press Ctrl-C to stop.",
+        chat.describe(),
+        team.name,
+        allowed.len()
+    );
+
+    while !SHUTDOWN_REQUESTED.load(Ordering::SeqCst) {
+        let messages = match chat.poll() {
+            Ok(messages) => messages,
+            Err(_) if SHUTDOWN_REQUESTED.load(Ordering::SeqCst) => break,
+            Err(e) => {
+                eprintln!("{:#}; retrying in {}s", e, RETRY_SECS);
+                thread::sleep(Duration::from_secs(RETRY_SECS));
+                continue;
+            }
+        };
+
+        for message in messages {
+            let reply = match message.command {
+                Err(usage) => usage,
+                Ok(_) if !allowed.contains(&message.from_id) => format!(
+                    "{} is not allowed to run commands. An operator can add user id {} \
+                     to {}.",
+                    message.from,
+                    message.from_id,
+                    chat.users_key()
+                ),
+                Ok(command) => {
+                    if let ChatCommand::Stop(ref member) = command {
+                        let ack = format!(
+                            "Stopping {}; it has up to {}s to finish its iteration.",
+                            member,
+                            stop::DEFAULT_GRACEFUL_TIMEOUT_SECS
+                        );
+                        chat.reply(&ack);
+                    }
+                    execute(team, &command, &message.from)
+                }
+            };
+            println!(
+                "[{}] {}: {} → {}",
+                chrono::Local::now().format("%H:%M:%S"),
+                message.from,
+                message.text,
+                reply.lines().next().unwrap_or_default()
+            );
+            chat.reply(&reply);
+        }
+    }
+
+    println!("Stopped listening.");
+    Ok(())
+}
+
+/// Parses a Slack message, which must start by mentioning the bot
+/// (`<@U0BOT> stop dev-01`); the slash before the command is optional, since
+/// Slack takes `/status` typed on its own as one of its own commands.
+fn parse_mention(text: &str, bot_id: &str) -> Option<Result<ChatCommand, String>> {
+    let rest = text
+        .trim_start()
+        .strip_prefix(&format!("<@{}>", bot_id))?
+        .trim_start();
+    if rest.is_empty() {
+        return Some(Ok(ChatCommand::Help));
+    }
+    parse(&format!("/{}", rest.strip_prefix('/').unwrap_or(rest)), bot_id)
+}
+
+/// Parses a chat message. Returns `None` if it isn't a command, or is one
+/// addressed to another bot (`/status@other_bot`); `Err` holds a usage hint.
+fn parse(text: &str, bot_username: &str) -> Option<Result<ChatCommand, String>> {
+    let mut words = text.split_whitespace();
+    let first = words.next()?.strip_prefix('/')?;
+    let (name, target) = match first.split_once('@') {
+        Some((name, target)) => (name, Some(target)),
+        None => (first, None),
+    };
+    if target.is_some_and(|t| !t.eq_ignore_ascii_case(bot_username)) {
+        return None;
+    }
+    let name = name.to_lowercase();
+    let args: Vec<&str> = words.collect();
+
+    let command = match (name.as_str(), args.as_slice()) {
+        ("stop", [member]) => ChatCommand::Stop(member.to_string()),
+        ("stop", _) => return Some(Err("Usage: /stop <member>, e.g. /stop dev-01".to_string())),
+        (_, [_, ..]) if is_known(&name) => {
+            return Some(Err(format!("/{} takes no arguments.", name)))
+        }
+        ("help" | "start", _) => ChatCommand::Help,
+        ("status", _) => ChatCommand::Status,
+        ("trigger", _) => ChatCommand::Trigger,
+        ("pause", _) => ChatCommand::Pause,
+        ("resume", _) => ChatCommand::Resume,
+        _ => return Some(Err(format!("Unknown command /{}. Send /help for the list.", name))),
+    };
+    Some(Ok(command))
+}
+
+fn is_known(name: &str) -> bool {
+    ["help", "start", "status", "stop", "trigger", "pause", "resume"].contains(&name)
+}
+
+/// Carries out a command on behalf of `by` and returns the reply.
+fn execute(team: &TeamEntry, command: &ChatCommand, by: &str) -> String {
+    let result = match command {
+        ChatCommand::Help => Ok(HELP.to_string()),
+        ChatCommand::Status => status_report(team),
+        ChatCommand::Stop(member) => {
+            stop::stop_member(team, member, stop::DEFAULT_GRACEFUL_TIMEOUT_SECS)
+        }
+        ChatCommand::Trigger => daemon::trigger(&team.name, by).map(|()| {
+            match state::paused(&team.name) {
+                Some(note) => format!(
+                    "Run queued, but launches are paused {}. Send /resume to let it start.",
+                    note
+                ),
+                None => "Run queued; the daemon starts it within a few seconds.".to_string(),
+            }
+        }),
+        ChatCommand::Pause => state::pause(&team.name, by).map(|paused| {
+            if paused {
+                "Paused. Events keep queueing; no members launch until /resume.".to_string()
+            } else {
+                format!(
+                    "Already paused {}.",
+                    state::paused(&team.name).unwrap_or_default()
+                )
+            }
+        }),
+        ChatCommand::Resume => state::resume(&team.name).map(|resumed| {
+            if resumed {
+                "Resumed. Queued events go out shortly.".to_string()
+            } else {
+                "Launches weren't paused.".to_string()
+            }
+        }),
+    };
+    if let Some((name, args)) = audited(command) {
+        let entry = audit::Entry::new(by, Some(&team.name), name, args, &result);
+        if let Err(e) = audit::append_default(&entry) {
+            eprintln!("Warning: could not write the audit log: {:#}", e);
+        }
+    }
+    result.unwrap_or_else(|e| format!("Failed: {:#}", e))
+}
+
+/// The audit log name and arguments of a command that changes something.
+fn audited(command: &ChatCommand) -> Option<(&'static str, Vec<String>)> {
+    match command {
+        ChatCommand::Help | ChatCommand::Status => None,
+        ChatCommand::Stop(member) => Some(("chatops stop", vec!["/stop".into(), member.clone()])),
+        ChatCommand::Trigger => Some(("chatops trigger", vec!["/trigger".into()])),
+        ChatCommand::Pause => Some(("chatops pause", vec!["/pause".into()])),
+        ChatCommand::Resume => Some(("chatops resume", vec!["/resume".into()])),
+    }
+}
+
+/// `/status`: one line per member, then the daemon.
+fn status_report(team: &TeamEntry) -> Result<String> {
+    let mut lines = vec![format!("Team {}", team.name)];
+    let members = status::member_lines(team)?;
+    if members.is_empty() {
+        lines.push("No members hired.".to_string());
+    }
+    lines.extend(members);
+    lines.push(daemon::summary(&team.name)?);
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ok(command: ChatCommand) -> Option<Result<ChatCommand, String>> {
+        Some(Ok(command))
+    }
+
+    #[test]
+    fn parse_recognizes_commands() {
+        assert_eq!(parse("/status", "team_bot"), ok(ChatCommand::Status));
+        assert_eq!(parse("/Status@Team_Bot", "team_bot"), ok(ChatCommand::Status));
+        assert_eq!(parse("  /stop   dev-01 ", "team_bot"), ok(ChatCommand::Stop("dev-01".into())));
+        assert_eq!(parse("/trigger", "team_bot"), ok(ChatCommand::Trigger));
+        assert_eq!(parse("/pause", "team_bot"), ok(ChatCommand::Pause));
+        assert_eq!(parse("/resume", "team_bot"), ok(ChatCommand::Resume));
+        assert_eq!(parse("/start", "team_bot"), ok(ChatCommand::Help));
+    }
+
+    #[test]
+    fn parse_ignores_chatter_and_other_bots() {
+        assert_eq!(parse("hello team", "team_bot"), None);
+        assert_eq!(parse("", "team_bot"), None);
+        assert_eq!(parse("/status@other_bot", "team_bot"), None);
+    }
+
+    #[test]
+    fn parse_explains_bad_usage() {
+        let usage = |text: &str| parse(text, "team_bot").unwrap().unwrap_err();
+        assert!(usage("/stop").contains("Usage: /stop <member>"));
+        assert!(usage("/stop dev-01 qe-02").contains("Usage"));
+        assert_eq!(usage("/pause now"), "/pause takes no arguments.");
+        assert!(usage("/deploy").contains("Unknown command /deploy"));
+    }
+
+    #[test]
+    fn parse_mention_needs_the_bot_mentioned_first() {
+        assert_eq!(parse_mention("<@UBOT> status", "UBOT"), ok(ChatCommand::Status));
+        assert_eq!(
+            parse_mention("<@UBOT> /stop dev-01", "UBOT"),
+            ok(ChatCommand::Stop("dev-01".into()))
+        );
+        assert_eq!(parse_mention("<@UBOT>", "UBOT"), ok(ChatCommand::Help));
+        assert_eq!(parse_mention("status", "UBOT"), None);
+        assert_eq!(parse_mention("<@UOTHER> status", "UBOT"), None);
+        assert!(parse_mention("<@UBOT> deploy", "UBOT").unwrap().is_err());
+    }
+}
@@ -1,4 +1,9 @@
-use anyhow::{bail, Result};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
 use clap_complete::env::Shells;
 use clap_complete::Shell;
 
@@ -8,22 +13,199 @@ use clap_complete::Shell;
 /// binary. Subsequent tab presses invoke `bm` with `COMPLETE=<shell>`, which is
 /// intercepted by `CompleteEnv` in `main.rs` to return live candidates.
 pub fn run(shell: Shell) -> Result<()> {
-    let shells = Shells::builtins();
+    std::io::stdout().write_all(&registration(shell)?)?;
+    Ok(())
+}
+
+/// Writes the registration script where the shell picks it up on its own and
+/// checks that sourcing it registers a completer for `bm`.
+///
+/// The shell comes from `--shell` or the basename of `$SHELL`. Only shells
+/// with a per-user completions directory are supported; the rest get a hint
+/// to eval `bm completions <shell>` from their profile instead.
+pub fn install(shell: Option<Shell>) -> Result<()> {
+    let shell = match shell {
+        Some(s) => s,
+        None => detect_shell()?,
+    };
+    let home = dirs::home_dir().context("Could not determine home directory")?;
+    let path = install_path(shell, &home, |k| std::env::var_os(k))?;
+
+    let mut script = registration(shell)?;
+    if shell == Shell::Zsh {
+        // Autoloaded from fpath, the file body becomes `_bm`: defining the
+        // completer alone would leave the first tab press without candidates.
+        script.extend_from_slice(
+            b"[[ ${funcstack[1]-} == _bm ]] && _clap_dynamic_completer_bm \"$@\"\n",
+        );
+    }
+
+    if fs::read(&path).ok().as_deref() == Some(script.as_slice()) {
+        println!("Completions for {} are up to date at {}", shell, path.display());
+    } else {
+        let dir = path.parent().expect("completion path has a parent");
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        fs::write(&path, &script)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!("Installed {} completions to {}", shell, path.display());
+    }
+
+    match verify(shell, &path) {
+        Ok(true) => println!("Verified: sourcing it registers completions for bm"),
+        Ok(false) => bail!(
+            "{} loaded {} but bm has no completer registered",
+            shell,
+            path.display()
+        ),
+        Err(e) => eprintln!("Warning: could not verify with {}: {}", shell, e),
+    }
+
+    if shell == Shell::Zsh {
+        zsh_fpath_hint(&home, path.parent().unwrap());
+    }
+    println!("Open a new shell to use them.");
+    Ok(())
+}
+
+/// The registration script for `shell`, as `bm completions <shell>` prints it.
+fn registration(shell: Shell) -> Result<Vec<u8>> {
     let shell_name = shell.to_string();
-    let completer = shells.completer(&shell_name);
+    let shells = Shells::builtins();
+    let Some(completer) = shells.completer(&shell_name) else {
+        bail!(
+            "Shell '{}' is not supported for dynamic completions",
+            shell_name
+        );
+    };
+    let mut buf = Vec::new();
+    completer
+        .write_registration("COMPLETE", "bm", "bm", "bm", &mut buf)
+        .map_err(|e| anyhow::anyhow!(e))?;
+    Ok(buf)
+}
 
-    match completer {
-        Some(c) => {
-            c.write_registration("COMPLETE", "bm", "bm", "bm", &mut std::io::stdout())
-                .map_err(|e| anyhow::anyhow!(e))?;
-        }
-        None => {
-            bail!(
-                "Shell '{}' is not supported for dynamic completions",
-                shell_name
-            );
+fn detect_shell() -> Result<Shell> {
+    let var = std::env::var("SHELL").unwrap_or_default();
+    let name = Path::new(&var)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    match name {
+        "bash" => Ok(Shell::Bash),
+        "zsh" => Ok(Shell::Zsh),
+        "fish" => Ok(Shell::Fish),
+        "" => bail!("Could not detect your shell from $SHELL. Pass --shell bash, zsh, or fish."),
+        other => bail!(
+            "Unsupported shell '{}'. Pass --shell bash, zsh, or fish.",
+            other
+        ),
+    }
+}
+
+/// Where `shell` looks for user completion files.
+fn install_path(
+    shell: Shell,
+    home: &Path,
+    env: impl Fn(&str) -> Option<std::ffi::OsString>,
+) -> Result<PathBuf> {
+    let dir_var = |key: &str, default: PathBuf| {
+        env(key)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or(default)
+    };
+    let path = match shell {
+        Shell::Bash => {
+            let data = dir_var("XDG_DATA_HOME", home.join(".local/share"));
+            dir_var("BASH_COMPLETION_USER_DIR", data.join("bash-completion"))
+                .join("completions")
+                .join("bm")
         }
+        Shell::Zsh => dir_var("ZDOTDIR", home.to_path_buf())
+            .join(".zfunc")
+            .join("_bm"),
+        Shell::Fish => dir_var("XDG_CONFIG_HOME", home.join(".config"))
+            .join("fish/completions/bm.fish"),
+        other => bail!(
+            "`bm completions install` supports bash, zsh, and fish. For {0}, \
+             load the output of `bm completions {0}` from your profile.",
+            other
+        ),
+    };
+    Ok(path)
+}
+
+/// Sources the installed file in a fresh non-interactive shell. `Ok(false)`
+/// means it loaded but registered nothing; `Err` means the shell couldn't run.
+fn verify(shell: Shell, path: &Path) -> Result<bool> {
+    let file = path.to_string_lossy();
+    let (program, script) = match shell {
+        Shell::Bash => ("bash", "source \"$1\" && complete -p bm".to_string()),
+        Shell::Zsh => (
+            "zsh",
+            "fpath=(\"${1:h}\" $fpath); autoload -Uz compinit; compinit -u -D; \
+             [[ ${_comps[bm]} == _bm ]]"
+                .to_string(),
+        ),
+        Shell::Fish => ("fish", "source $argv[1]; and complete --command bm".to_string()),
+        _ => unreachable!("install_path rejects other shells"),
+    };
+    let mut cmd = Command::new(program);
+    cmd.arg("-c").arg(&script);
+    if shell != Shell::Fish {
+        // bash and zsh bind the first argument after the script to $0.
+        cmd.arg(program);
     }
+    let output = cmd
+        .arg(file.as_ref())
+        .output()
+        .with_context(|| format!("{} not found", program))?;
+    Ok(output.status.success() && (shell != Shell::Fish || !output.stdout.is_empty()))
+}
 
-    Ok(())
+fn zsh_fpath_hint(home: &Path, dir: &Path) {
+    let zdotdir = std::env::var_os("ZDOTDIR")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.to_path_buf());
+    let zshrc = fs::read_to_string(zdotdir.join(".zshrc")).unwrap_or_default();
+    if !zshrc.contains(".zfunc") {
+        println!(
+            "Add this to {} before compinit runs:\n  fpath=({} $fpath)",
+            zdotdir.join(".zshrc").display(),
+            dir.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+
+    #[test]
+    fn install_paths_follow_shell_conventions() {
+        let home = Path::new("/home/u");
+        let none = |_: &str| None;
+        assert_eq!(
+            install_path(Shell::Bash, home, none).unwrap(),
+            PathBuf::from("/home/u/.local/share/bash-completion/completions/bm")
+        );
+        assert_eq!(
+            install_path(Shell::Zsh, home, none).unwrap(),
+            PathBuf::from("/home/u/.zfunc/_bm")
+        );
+        assert_eq!(
+            install_path(Shell::Fish, home, none).unwrap(),
+            PathBuf::from("/home/u/.config/fish/completions/bm.fish")
+        );
+        assert!(install_path(Shell::PowerShell, home, none).is_err());
+
+        let xdg = |k: &str| (k == "XDG_DATA_HOME").then(|| OsString::from("/data"));
+        assert_eq!(
+            install_path(Shell::Bash, home, xdg).unwrap(),
+            PathBuf::from("/data/bash-completion/completions/bm")
+        );
+    }
 }
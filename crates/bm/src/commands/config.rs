@@ -0,0 +1,52 @@
+use anyhow::Result;
+
+use crate::config;
+
+/// Handles `bm config get <key> [-t team]`. Scalars print bare; sections
+/// and lists print as YAML.
+pub fn get(key: &str, team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let key = full_key(&cfg, key, team_flag)?;
+    match config::get_key(&cfg, &key)? {
+        None => println!("(not set)"),
+        Some(serde_yml::Value::String(s)) => println!("{}", s),
+        Some(value) => print!("{}", serde_yml::to_string(&value)?),
+    }
+    Ok(())
+}
+
+/// Handles `bm config set <key> <value> [-t team]` and, with no value,
+/// `bm config unset <key> [-t team]`. The config is validated before it is
+/// written, under the config lock, and stays 0600. Values are not echoed,
+/// since most are credentials.
+pub fn set(key: &str, value: Option<&str>, team_flag: Option<&str>) -> Result<()> {
+    let path = config::config_path()?;
+    let (key, changed) = config::update(|cfg| {
+        let key = full_key(cfg, key, team_flag)?;
+        let changed = match value {
+            Some(value) => {
+                config::set_key(cfg, &key, value)?;
+                true
+            }
+            None => config::unset_key(cfg, &key)?,
+        };
+        Ok((key, changed))
+    })?;
+    match (value, changed) {
+        (Some(_), _) => println!("Set {} in {}", key, path.display()),
+        (None, true) => println!("Unset {} in {}", key, path.display()),
+        (None, false) => println!("{} is not set in {}", key, path.display()),
+    }
+    Ok(())
+}
+
+/// With `-t`, `key` is within that team's entry.
+fn full_key(cfg: &config::BotminterConfig, key: &str, team_flag: Option<&str>) -> Result<String> {
+    Ok(match team_flag {
+        Some(_) => {
+            let team = config::resolve_team(cfg, team_flag)?;
+            format!("teams.{}.{}", team.name, key)
+        }
+        None => key.to_string(),
+    })
+}
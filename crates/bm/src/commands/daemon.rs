@@ -1,21 +1,58 @@
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
-use std::io::Write as _;
-use std::os::unix::fs::PermissionsExt;
+use std::net::{IpAddr, SocketAddr};
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
-
-use crate::config;
+use tracing::{debug, error, info, info_span, warn};
+
+use crate::board::Board;
+use crate::budget;
+use crate::commands::status;
+use crate::commands::webhooks;
+use crate::config::{self, TeamEntry};
+use crate::daemon_settings::{self, DaemonSettings};
+use crate::delivery_ids::DeliveryIds;
+use crate::disk_quota::{self, DiskQuota};
+use crate::dry_run::{self, Plan, Step};
+use crate::event_log::{self, EventRecord, Signature};
+use crate::event_queue::EventQueue;
+use crate::github_app::{self, MemberAuth};
+use crate::github_hooks;
+use crate::launch_env::{self, MemberEnv};
+use crate::logging;
+use crate::members;
+use crate::notify::{self, Kind, Notification};
+use crate::parse;
+use crate::pre_stop;
+use crate::procutil::{self, RELOAD_REQUESTED, SHUTDOWN_REQUESTED};
 use crate::profile;
-use crate::state;
-
-/// Daemon config file stored at `~/.botminter/daemon-<team>.json`.
+use crate::rate_limit::{self, Limited, RateLimit};
+use crate::readiness::{self, Probe};
+use crate::run_history::{MemberRun, RunRecord};
+use crate::schedule::Scheduler;
+use crate::state::{self, RunOutcome};
+use crate::state_history;
+use crate::store::{self, Store};
+use crate::tables;
+use crate::timefmt;
+use crate::token_expiry;
+use crate::trigger_filter::{self, TriggerEvent};
+use crate::tunnel::{self, Provider, Tunnel};
+use crate::usage;
+use crate::watchdog;
+
+/// A running daemon's config, kept in the [`store`] under
+/// [`store::daemon_key`] (`~/.botminter/daemon-<team>.json` with the file
+/// backend).
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DaemonConfig {
     pub team: String,
@@ -24,31 +61,282 @@ pub struct DaemonConfig {
     pub interval_secs: u64,
     pub pid: u32,
     pub started_at: String,
+    #[serde(flatten)]
+    pub listener: Listener,
+    /// Public tunnel started with `--tunnel`, torn down by `bm daemon stop`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tunnel: Option<Tunnel>,
+}
+
+/// Default address the webhook server binds to.
+pub const DEFAULT_BIND: &str = "0.0.0.0";
+
+/// Default URL path GitHub delivers webhooks to.
+pub const DEFAULT_WEBHOOK_PATH: &str = "/webhook";
+
+/// Where and how the webhook server listens.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Listener {
+    /// IP address to bind to.
+    #[serde(default = "default_bind")]
+    pub bind: String,
+    /// URL path that accepts deliveries; everything else gets a 404.
+    #[serde(default = "default_webhook_path")]
+    pub path: String,
+    /// Serve HTTPS with this certificate and key instead of plain HTTP.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsFiles>,
+    /// Accept unsigned deliveries when the team has no webhook secret.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure_no_signature: bool,
+}
+
+/// PEM certificate chain and private key (PKCS#8 or RSA) for HTTPS.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TlsFiles {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+fn default_bind() -> String {
+    DEFAULT_BIND.to_string()
+}
+
+fn default_webhook_path() -> String {
+    DEFAULT_WEBHOOK_PATH.to_string()
+}
+
+impl Default for Listener {
+    fn default() -> Self {
+        Listener {
+            bind: default_bind(),
+            path: default_webhook_path(),
+            tls: None,
+            insecure_no_signature: false,
+        }
+    }
+}
+
+impl Listener {
+    /// Checks the bind address, path, and TLS files before the daemon starts.
+    pub fn validate(&self) -> Result<()> {
+        if self.bind.parse::<IpAddr>().is_err() {
+            bail!(
+                "Invalid bind address '{}'. Use an IP address such as 127.0.0.1, 0.0.0.0, or ::1.",
+                self.bind
+            );
+        }
+        if !self.path.starts_with('/')
+            || self.path.contains(|c: char| c.is_whitespace() || c == '?' || c == '#')
+        {
+            bail!(
+                "Invalid webhook path '{}'. It must start with '/' and contain no spaces, \
+                 '?' or '#' (e.g. /hooks/github).",
+                self.path
+            );
+        }
+        if let Some(ref tls) = self.tls {
+            for (what, path) in [("certificate", &tls.cert), ("private key", &tls.key)] {
+                fs::File::open(path).with_context(|| {
+                    format!("Cannot read TLS {} at {}", what, path.display())
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Socket address to bind on `port`.
+    fn socket_addr(&self, port: u16) -> Result<SocketAddr> {
+        let ip: IpAddr = self
+            .bind
+            .parse()
+            .with_context(|| format!("Invalid bind address '{}'", self.bind))?;
+        Ok(SocketAddr::new(ip, port))
+    }
+
+    /// URL the server accepts deliveries on, as seen from this machine.
+    pub fn url(&self, port: u16) -> String {
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        match self.socket_addr(port) {
+            Ok(addr) => format!("{}://{}{}", scheme, addr, self.path),
+            Err(_) => format!("{}://{}:{}{}", scheme, self.bind, port, self.path),
+        }
+    }
+
+    /// Address this machine reaches the server on. Wildcard binds are
+    /// reached over loopback.
+    fn local_addr(&self, port: u16) -> Result<SocketAddr> {
+        let mut addr = self.socket_addr(port)?;
+        if addr.ip().is_unspecified() {
+            addr.set_ip(match addr.ip() {
+                IpAddr::V4(_) => IpAddr::from([127, 0, 0, 1]),
+                IpAddr::V6(_) => IpAddr::from(std::net::Ipv6Addr::LOCALHOST),
+            });
+        }
+        Ok(addr)
+    }
+
+    /// Base URL a tunnel on this machine forwards to.
+    fn local_url(&self, port: u16) -> Result<String> {
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        Ok(format!("{}://{}", scheme, self.local_addr(port)?))
+    }
+
+    /// Starts the HTTP or HTTPS server.
+    fn serve(&self, port: u16) -> Result<tiny_http::Server> {
+        let addr = self.socket_addr(port)?;
+        let server = match self.tls {
+            None => tiny_http::Server::http(addr),
+            Some(ref tls) => {
+                let certificate = fs::read(&tls.cert).with_context(|| {
+                    format!("Failed to read TLS certificate at {}", tls.cert.display())
+                })?;
+                let private_key = fs::read(&tls.key).with_context(|| {
+                    format!("Failed to read TLS private key at {}", tls.key.display())
+                })?;
+                tiny_http::Server::https(
+                    addr,
+                    tiny_http::SslConfig {
+                        certificate,
+                        private_key,
+                    },
+                )
+            }
+        };
+        server.map_err(|e| anyhow::anyhow!("Failed to bind to {}: {}", addr, e))
+    }
+}
+
+/// Listener settings given as flags to `bm daemon start` or `restart`.
+#[derive(Debug, Clone, Default)]
+pub struct ListenerFlags {
+    pub bind: Option<String>,
+    pub path: Option<String>,
+    pub tls_cert: Option<PathBuf>,
+    pub tls_key: Option<PathBuf>,
+    pub insecure_no_signature: bool,
+}
+
+impl ListenerFlags {
+    /// Applies the flags on top of `base`. TLS needs both a certificate and a
+    /// key; otherwise `base`'s TLS setting is kept. Accepting unsigned
+    /// deliveries has to be asked for every time, so a restart without
+    /// `--insecure-no-signature` checks signatures again.
+    pub fn apply(&self, base: Listener) -> Listener {
+        Listener {
+            bind: self.bind.clone().unwrap_or(base.bind),
+            path: self.path.clone().unwrap_or(base.path),
+            tls: match (&self.tls_cert, &self.tls_key) {
+                (Some(cert), Some(key)) => Some(TlsFiles {
+                    cert: cert.clone(),
+                    key: key.clone(),
+                }),
+                _ => base.tls,
+            },
+            insecure_no_signature: self.insecure_no_signature,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bind.is_none()
+            && self.path.is_none()
+            && self.tls_cert.is_none()
+            && !self.insecure_no_signature
+    }
 }
 
 /// Poll state tracking for poll mode.
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct PollState {
+    /// Newest event seen in the team repo.
     pub last_event_id: Option<String>,
     pub last_poll_at: Option<String>,
+    /// Newest event seen in each of the team's extra repos.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra_event_ids: BTreeMap<String, String>,
+    /// ETag of each repo's latest events page. Sent back as `If-None-Match`,
+    /// so an unchanged page comes back as a 304 that costs no rate limit.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub etags: BTreeMap<String, String>,
+    /// Request budget reported with the latest response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+    /// Polling waits until then (RFC 3339) after hitting a rate limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backoff_until: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backoff_reason: Option<String>,
+    /// Secondary rate limits hit in a row, for exponential backoff.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub throttled: u32,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
 }
 
-/// Maximum log file size before rotation (10 MB).
-const MAX_LOG_SIZE: u64 = 10 * 1024 * 1024;
+impl PollState {
+    fn last_event_id(&self, repo: &str, team_repo: &str) -> Option<&str> {
+        if repo == team_repo {
+            self.last_event_id.as_deref()
+        } else {
+            self.extra_event_ids.get(repo).map(String::as_str)
+        }
+    }
+
+    fn record(&mut self, repo: &str, team_repo: &str, event_id: &str) {
+        if repo == team_repo {
+            self.last_event_id = Some(event_id.to_string());
+        } else {
+            self.extra_event_ids.insert(repo.to_string(), event_id.to_string());
+        }
+    }
+
+    /// Seconds polling still has to wait after a rate limit. Clears a
+    /// backoff that has run out.
+    fn backoff_remaining(&mut self, now: chrono::DateTime<chrono::Utc>) -> Option<u64> {
+        let until = self.backoff_until.as_deref().and_then(timefmt::parse)?;
+        if until > now {
+            return Some((until - now).num_seconds().max(1) as u64);
+        }
+        self.backoff_until = None;
+        self.backoff_reason = None;
+        None
+    }
+
+    fn back_off(&mut self, limited: &Limited, now: chrono::DateTime<chrono::Utc>) {
+        let until = now + chrono::Duration::seconds(limited.wait_secs as i64);
+        self.backoff_until = Some(until.to_rfc3339());
+        self.backoff_reason = Some(limited.reason.clone());
+        if limited.secondary {
+            self.throttled += 1;
+        }
+    }
+}
+
+/// How often the running daemon re-checks GitHub token expiry (12 hours).
+const TOKEN_EXPIRY_CHECK_SECS: u64 = 12 * 60 * 60;
+
+/// How long `bm daemon stop` lets an in-flight run finish by default (10 minutes).
+pub const DEFAULT_DRAIN_SECS: u64 = 10 * 60;
+
+/// How long `bm daemon stop` waits past the drain timeout for the daemon to
+/// end the run's members and exit, before signaling it.
+const DRAIN_GRACE: Duration = Duration::from_secs(30);
+
+/// How many times the webhook server retries binding its port, 100ms apart.
+const REBIND_ATTEMPTS: u32 = 30;
 
-/// GitHub event types that trigger member launches.
-const RELEVANT_EVENTS: &[&str] = &[
-    "issues",
-    "issue_comment",
-    "pull_request",
-];
+/// Event type queued for a manual run; route it in `daemon.yml` like any other.
+pub const MANUAL_EVENT: &str = "manual";
 
 /// Returns the PID file path for a daemon.
 pub fn pid_path(team_name: &str) -> Result<PathBuf> {
     Ok(config::config_dir()?.join(format!("daemon-{}.pid", team_name)))
 }
 
-/// Returns the config file path for a daemon.
+/// Returns the config file path for a daemon with the file backend; see
+/// [`load_daemon_config`].
 pub fn config_path(team_name: &str) -> Result<PathBuf> {
     Ok(config::config_dir()?.join(format!("daemon-{}.json", team_name)))
 }
@@ -61,6 +349,63 @@ pub fn poll_state_path(team_name: &str) -> Result<PathBuf> {
     )))
 }
 
+/// Returns the event queue file path for a daemon. Unlike the other runtime
+/// files it survives `bm daemon stop`, so queued events aren't lost.
+pub fn queue_path(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("daemon-{}-queue.json", team_name)))
+}
+
+
+/// Returns the path recording the day the last daily digest went out, so a
+/// restart doesn't send it twice.
+pub fn digest_path(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("daemon-{}.digest", team_name)))
+}
+
+/// Returns the path of a pending manual run request, picked up by the
+/// running daemon within a second.
+pub fn trigger_path(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("daemon-{}.trigger", team_name)))
+}
+
+/// Returns the path of the webhook delivery IDs the daemon has handled.
+/// Like the queue it survives `bm daemon stop`, so GitHub retrying a
+/// delivery across a restart doesn't launch members twice.
+pub fn deliveries_path(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("daemon-{}-deliveries.json", team_name)))
+}
+
+/// Returns the path of the schedules' next fire times. Like the queue it
+/// survives `bm daemon stop`, so a restart doesn't fire a schedule twice.
+pub fn schedules_path(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("daemon-{}-schedules.json", team_name)))
+}
+
+/// Returns the path of the daemon's one-shot run history. Like the queue it
+/// survives `bm daemon stop`.
+pub fn runs_path(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("daemon-{}-runs.json", team_name)))
+}
+
+/// Returns the directory of recent webhook deliveries, one redacted JSON
+/// file each. Like the queue it survives `bm daemon stop`.
+pub fn events_dir(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("daemon-{}-events", team_name)))
+}
+
+/// Returns the path of pending replay requests, one delivery ID per line,
+/// picked up by the running daemon within a second.
+pub fn replay_path(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("daemon-{}.replay", team_name)))
+}
+
+/// Returns the path of a drain request: `bm daemon stop` writes the drain
+/// timeout in seconds, and the running daemon stops taking events and exits
+/// once its in-flight run finishes.
+pub fn drain_path(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("daemon-{}.drain", team_name)))
+}
+
 /// Returns the log file path for a daemon.
 pub fn log_path(team_name: &str) -> Result<PathBuf> {
     let logs_dir = config::config_dir()?.join("logs");
@@ -75,13 +420,137 @@ pub fn member_log_path(team_name: &str, member_name: &str) -> Result<PathBuf> {
     Ok(logs_dir.join(format!("member-{}-{}.log", team_name, member_name)))
 }
 
+/// Sends `cmd`'s stdout and stderr to the member's log, rotating it first
+/// if it is due.
+pub fn redirect_to_member_log(
+    cmd: &mut Command,
+    team_name: &str,
+    member_name: &str,
+    retention: &logging::Retention,
+) -> Result<()> {
+    let log_file_path = member_log_path(team_name, member_name)?;
+    match logging::rotate_if_due(&log_file_path, retention) {
+        Ok(true) => info!("{}: rotated {}", member_name, log_file_path.display()),
+        Ok(false) => {}
+        Err(e) => warn!("{}: {:#}", member_name, e),
+    }
+    info!("{}: log file at {}", member_name, log_file_path.display());
+    let log_file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_file_path)
+        .with_context(|| {
+            format!(
+                "Failed to open member log file at {}",
+                log_file_path.display()
+            )
+        })?;
+    let log_file_err = log_file
+        .try_clone()
+        .context("Failed to clone member log file handle")?;
+    cmd.stdout(log_file).stderr(log_file_err);
+    Ok(())
+}
+
+/// What `bm daemon start` or `bm daemon restart` started.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Started {
+    pub team: String,
+    pub pid: u32,
+    pub mode: String,
+    /// Where the webhook listener accepts deliveries, in webhook mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub log: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tunnel: Option<Tunnel>,
+    /// Webhooks registered for the tunnel, as `(repo, id)`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<(String, u64)>,
+}
+
+impl fmt::Display for Started {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Daemon started (PID {})", self.pid)?;
+        if let Some(ref url) = self.url {
+            writeln!(f, "Listening on {}", url)?;
+        }
+        if let Some(ref t) = self.tunnel {
+            writeln!(f, "Tunnel ({}): {}", t.provider, t.url)?;
+        }
+        for (repo, id) in &self.webhooks {
+            writeln!(f, "Registered webhook #{} on {}", id, repo)?;
+        }
+        Ok(())
+    }
+}
+
 /// Handles `bm daemon start`.
+///
+/// Flags that are not given fall back to the team's daemon settings
+/// (local override, then the team repo's `daemon.yml`, then built-in defaults).
 pub fn start(
     team_flag: Option<&str>,
-    mode: &str,
-    port: u16,
-    interval: u64,
-) -> Result<()> {
+    mode_flag: Option<&str>,
+    port_flag: Option<u16>,
+    interval_flag: Option<u64>,
+    listener_flags: &ListenerFlags,
+    tunnel: Option<Provider>,
+) -> Result<Started> {
+    let listener = listener_flags.apply(Listener::default());
+    start_with(
+        team_flag,
+        mode_flag,
+        port_flag,
+        interval_flag,
+        listener,
+        !listener_flags.is_empty(),
+        tunnel,
+        None,
+    )?
+    .context("daemon start returned no daemon")
+}
+
+/// Handles `bm daemon start --dry-run`: makes the same checks as `bm daemon
+/// start`, then lists the daemon process, files, tunnel, and webhooks it
+/// would create.
+pub fn start_plan(
+    team_flag: Option<&str>,
+    mode_flag: Option<&str>,
+    port_flag: Option<u16>,
+    interval_flag: Option<u64>,
+    listener_flags: &ListenerFlags,
+    tunnel: Option<Provider>,
+) -> Result<Plan> {
+    let mut plan = Plan::default();
+    let listener = listener_flags.apply(Listener::default());
+    start_with(
+        team_flag,
+        mode_flag,
+        port_flag,
+        interval_flag,
+        listener,
+        !listener_flags.is_empty(),
+        tunnel,
+        Some(&mut plan),
+    )?;
+    Ok(plan)
+}
+
+/// Starts the daemon with a resolved webhook listener. `listener_given`
+/// says whether any listener flag was passed, for the poll-mode note. With
+/// `plan`, records what it would do instead and returns `None`.
+#[allow(clippy::too_many_arguments)]
+fn start_with(
+    team_flag: Option<&str>,
+    mode_flag: Option<&str>,
+    port_flag: Option<u16>,
+    interval_flag: Option<u64>,
+    listener: Listener,
+    listener_given: bool,
+    tunnel: Option<Provider>,
+    mut plan: Option<&mut Plan>,
+) -> Result<Option<Started>> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, team_flag)?;
     let team_repo = team.path.join("team");
@@ -90,38 +559,116 @@ pub fn start(
     let team_schema = read_team_schema(&team_repo)?;
     profile::require_current_schema(&team.name, &team_schema)?;
 
+    let settings = daemon_settings::load(&team_repo, &team.name)?;
+    let mode = mode_flag.unwrap_or(settings.mode());
+    let port = port_flag.unwrap_or(settings.port());
+    let interval = interval_flag.unwrap_or(settings.interval_secs());
+
     // Check if already running
     let pid_file = pid_path(&team.name)?;
     if pid_file.exists() {
         let pid_str = fs::read_to_string(&pid_file)
             .context("Failed to read daemon PID file")?;
         if let Ok(pid) = pid_str.trim().parse::<u32>() {
-            if state::is_alive(pid) {
+            if procutil::is_alive(pid) {
                 bail!(
                     "Daemon already running for team '{}' (PID {})",
                     team.name,
                     pid
                 );
             }
-            // Stale PID file — clean up
-            let _ = fs::remove_file(&pid_file);
+            // Stale PID file — clean up, including a tunnel the daemon left behind
+            if let Some(plan) = plan.as_deref_mut() {
+                if let Some(t) = read_daemon_config(&team.name).and_then(|c| c.tunnel) {
+                    for (repo, id) in t.hooks(&team.github_repo) {
+                        plan.push(Step::GitHub(format!("delete webhook #{} on {}", id, repo)));
+                    }
+                }
+                plan.push(Step::RemoveFile(pid_file.clone()));
+            } else {
+                teardown_tunnel(team);
+                let _ = fs::remove_file(&pid_file);
+            }
         }
     }
 
-    // Validate mode
-    if mode != "webhook" && mode != "poll" {
-        bail!("Invalid daemon mode '{}'. Use 'webhook' or 'poll'.", mode);
+    daemon_settings::validate_mode(mode)?;
+    listener.validate()?;
+    if mode != "webhook" && listener_given {
+        eprintln!(
+            "Note: --bind, --path, --tls-*, and --insecure-no-signature only apply in webhook mode."
+        );
     }
-
-    eprintln!(
-        "Starting daemon for team '{}' in {} mode...",
-        team.name, mode
-    );
+    if mode == "webhook" {
+        check_webhook_secret(team, &listener)?;
+    }
+    let webhook_secret = match tunnel {
+        Some(provider) => Some(check_tunnel_prerequisites(team, mode, provider)?),
+        None => None,
+    };
 
     // Spawn the daemon as a detached child process using `bm daemon-run`
     let exe = std::env::current_exe().context("Failed to determine bm executable path")?;
     let log_file_path = log_path(&team.name)?;
+    let mut daemon_cmd = Command::new(exe);
+    daemon_cmd.args([
+        "daemon-run",
+        "--team",
+        &team.name,
+        "--mode",
+        mode,
+        "--port",
+        &port.to_string(),
+        "--interval",
+        &interval.to_string(),
+        "--bind",
+        &listener.bind,
+        "--path",
+        &listener.path,
+    ]);
+    if let Some(directives) = logging::daemon_directives() {
+        daemon_cmd.env(logging::ENV_VAR, directives);
+    }
+    if let Some(ref tls) = listener.tls {
+        daemon_cmd
+            .arg("--tls-cert")
+            .arg(&tls.cert)
+            .arg("--tls-key")
+            .arg(&tls.key);
+    }
+    if listener.insecure_no_signature {
+        daemon_cmd.arg("--insecure-no-signature");
+    }
+
+    if let Some(plan) = plan {
+        plan.push(Step::Run {
+            command: dry_run::command_line(&daemon_cmd),
+            dir: std::env::current_dir().unwrap_or_default(),
+        });
+        plan.push(Step::WriteFile(log_file_path));
+        plan.push(Step::WriteFile(pid_path(&team.name)?));
+        plan.push(Step::WriteFile(store::open()?.location(&store::daemon_key(&team.name))));
+        if let Some(provider) = tunnel {
+            let mut cmd = Command::new(provider.binary());
+            cmd.args(provider.args(&listener.local_url(port)?));
+            plan.push(Step::Run {
+                command: dry_run::command_line(&cmd),
+                dir: std::env::current_dir().unwrap_or_default(),
+            });
+            for repo in team.repos() {
+                plan.push(Step::GitHub(format!(
+                    "create a webhook on {} for the tunnel's URL",
+                    repo
+                )));
+            }
+        }
+        return Ok(None);
+    }
 
+    eprintln!(
+        "Starting daemon for team '{}' in {} mode...",
+        team.name, mode
+    );
     let log_file = fs::OpenOptions::new()
         .create(true)
         .append(true)
@@ -132,18 +679,7 @@ pub fn start(
         .try_clone()
         .context("Failed to clone log file handle")?;
 
-    let child = Command::new(exe)
-        .args([
-            "daemon-run",
-            "--team",
-            &team.name,
-            "--mode",
-            mode,
-            "--port",
-            &port.to_string(),
-            "--interval",
-            &interval.to_string(),
-        ])
+    let child = daemon_cmd
         .stdin(std::process::Stdio::null())
         .stdout(log_file)
         .stderr(log_file_err)
@@ -158,46 +694,228 @@ pub fn start(
         fs::create_dir_all(dir)?;
     }
     fs::write(&pid_file, pid.to_string())?;
-    fs::set_permissions(&pid_file, fs::Permissions::from_mode(0o600))?;
+    config::set_mode(&pid_file, 0o600)?;
 
     // Write config
-    let daemon_cfg = DaemonConfig {
+    let mut daemon_cfg = DaemonConfig {
         team: team.name.clone(),
         mode: mode.to_string(),
         port,
         interval_secs: interval,
         pid,
         started_at: chrono::Utc::now().to_rfc3339(),
+        listener: listener.clone(),
+        tunnel: None,
     };
-    let cfg_path = config_path(&team.name)?;
-    let contents =
-        serde_json::to_string_pretty(&daemon_cfg).context("Failed to serialize daemon config")?;
-    fs::write(&cfg_path, contents)?;
+    save_daemon_config(&daemon_cfg)?;
 
     // Brief wait to detect immediate failures
     thread::sleep(Duration::from_millis(500));
-    if !state::is_alive(pid) {
-        // Clean up PID/config files
+    if !procutil::is_alive(pid) {
+        // Clean up PID file and config
         let _ = fs::remove_file(&pid_file);
-        let _ = fs::remove_file(&cfg_path);
+        remove_daemon_config(&team.name);
         bail!(
             "Daemon process exited immediately. Check logs at {}",
             log_file_path.display()
         );
     }
 
-    println!("Daemon started (PID {})", pid);
+    let mut started = Started {
+        team: team.name.clone(),
+        pid,
+        mode: mode.to_string(),
+        url: None,
+        log: log_file_path.clone(),
+        tunnel: None,
+        webhooks: Vec::new(),
+    };
+    if mode == "webhook" {
+        started.url = Some(listener.url(port));
+        if !procutil::wait_for_port(listener.local_addr(port)?, Duration::from_secs(5)) {
+            eprintln!(
+                "Warning: the daemon isn't accepting connections yet. Check logs at {}",
+                log_file_path.display()
+            );
+        }
+    }
+
+    if let (Some(provider), Some(secret)) = (tunnel, webhook_secret) {
+        match open_tunnel(team, provider, &listener, port, &secret) {
+            Ok(t) => {
+                started.webhooks = t.hooks(&team.github_repo);
+                started.tunnel = Some(t.clone());
+                daemon_cfg.tunnel = Some(t);
+                save_daemon_config(&daemon_cfg)?;
+            }
+            Err(e) => {
+                eprintln!("Tunnel setup failed, stopping the daemon");
+                let _ = stop(Some(&team.name), None);
+                return Err(e);
+            }
+        }
+    }
+    Ok(Some(started))
+}
+
+/// Refuses webhook mode without a webhook secret, since the daemon could
+/// not tell GitHub's deliveries from spoofed ones, unless
+/// `--insecure-no-signature` allows it.
+fn check_webhook_secret(team: &config::TeamEntry, listener: &Listener) -> Result<()> {
+    if webhooks::require_secret(team, "Webhook mode").is_ok() {
+        return Ok(());
+    }
+    if !listener.insecure_no_signature {
+        bail!(
+            "Team '{}' has no webhook secret, so the daemon could not verify that deliveries \
+             come from GitHub and anyone reaching it could trigger member runs. Set \
+             teams[].credentials.webhook_secret in ~/.botminter/config.yml, use --mode poll, \
+             or pass --insecure-no-signature to accept unsigned deliveries.",
+            team.name
+        );
+    }
+    eprintln!(
+        "WARNING: team '{}' has no webhook secret; accepting unsigned deliveries \
+         (--insecure-no-signature). Anyone who can reach the listener can trigger member runs.",
+        team.name
+    );
     Ok(())
 }
 
-/// Handles `bm daemon stop`.
-pub fn stop(team_flag: Option<&str>) -> Result<()> {
+/// Checks what `--tunnel` needs before anything is started, and returns the
+/// webhook secret to register the hook with.
+fn check_tunnel_prerequisites(
+    team: &config::TeamEntry,
+    mode: &str,
+    provider: Provider,
+) -> Result<String> {
+    if mode != "webhook" {
+        bail!("--tunnel only works in webhook mode. Drop --mode {} or the --tunnel flag.", mode);
+    }
+    if team.github_repo.is_empty() {
+        bail!(
+            "Team '{}' has no GitHub repo configured, so there is nowhere to register the webhook.",
+            team.name
+        );
+    }
+    // Anyone can reach a public tunnel URL, so deliveries must be signed
+    let secret = webhooks::require_secret(team, "--tunnel")?;
+    tunnel::require_binary(provider)?;
+    Ok(secret)
+}
+
+/// Opens a tunnel to the local listener and registers a repo webhook for it.
+fn open_tunnel(
+    team: &config::TeamEntry,
+    provider: Provider,
+    listener: &Listener,
+    port: u16,
+    secret: &str,
+) -> Result<Tunnel> {
+    let target = listener.local_url(port)?;
+    eprintln!("Opening {} tunnel to {}...", provider, target);
+    let (pid, public) = tunnel::open(provider, &target, &tunnel::log_path(&team.name)?)?;
+    let mut t = Tunnel {
+        provider,
+        pid,
+        url: format!("{}{}", public, listener.path),
+        hook_id: None,
+        extra_hook_ids: BTreeMap::new(),
+    };
+
+    let prepare = || -> Result<(DaemonSettings, Option<String>)> {
+        let settings = daemon_settings::load(&team.path.join("team"), &team.name)?;
+        let token = github_app::TeamTokenSource::default().token(team)?;
+        Ok((settings, token))
+    };
+    let (settings, token) = match prepare() {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            procutil::terminate(pid);
+            return Err(e);
+        }
+    };
+    for repo in team.repos() {
+        let created = github_hooks::create(
+            &repo,
+            token.as_deref(),
+            &t.url,
+            secret,
+            &settings.relevant_events(),
+        );
+        match created {
+            Ok(id) if repo == team.github_repo => t.hook_id = Some(id),
+            Ok(id) => {
+                t.extra_hook_ids.insert(repo, id);
+            }
+            Err(e) => {
+                // Don't leave hooks behind on the repos that did work
+                delete_hooks(team, &t, token.as_deref());
+                procutil::terminate(pid);
+                return Err(e.context(format!("Failed to register the webhook on {}", repo)));
+            }
+        }
+    }
+    Ok(t)
+}
+
+/// Deletes the tunnel's webhooks, reporting failures without stopping.
+fn delete_hooks(team: &config::TeamEntry, t: &Tunnel, token: Option<&str>) {
+    for (repo, id) in t.hooks(&team.github_repo) {
+        match github_hooks::delete(&repo, token, id) {
+            Ok(()) => eprintln!("Removed webhook #{} from {}", id, repo),
+            Err(e) => eprintln!(
+                "Warning: could not remove webhook #{} from {}: {:#}\n  \
+                 Delete it under Settings → Webhooks on GitHub.",
+                id, repo, e
+            ),
+        }
+    }
+}
+
+/// Deletes the webhook and stops the tunnel recorded in the team's daemon
+/// config, if any. Failures are reported but don't stop the caller.
+fn teardown_tunnel(team: &config::TeamEntry) {
+    let Some(t) = read_daemon_config(&team.name).and_then(|c| c.tunnel) else {
+        return;
+    };
+    if !t.hooks(&team.github_repo).is_empty() {
+        match github_app::TeamTokenSource::default().token(team) {
+            Ok(token) => delete_hooks(team, &t, token.as_deref()),
+            Err(e) => eprintln!(
+                "Warning: could not remove the tunnel's webhooks: {:#}\n  \
+                 Delete them under Settings → Webhooks on GitHub.",
+                e
+            ),
+        }
+    }
+    if procutil::is_alive(t.pid) {
+        procutil::terminate(t.pid);
+        eprintln!("Stopped {} tunnel (PID {})", t.provider, t.pid);
+    }
+}
+
+/// Handles `bm daemon stop`. With `drain`, the daemon stops taking events
+/// and exits once its in-flight run finishes, ending the run's members only
+/// when the timeout passes; without, it ends them right away.
+pub fn stop(team_flag: Option<&str>, drain: Option<Duration>) -> Result<()> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, team_flag)?;
 
+    if !shutdown(team, drain)? {
+        bail!("Daemon not running for team '{}'", team.name);
+    }
+    println!("Daemon stopped");
+    Ok(())
+}
+
+/// Stops the team's daemon, as `bm daemon stop` does, and removes its
+/// runtime files. Returns false if it had no PID file; a daemon that had
+/// already died only has its files removed.
+pub fn shutdown(team: &TeamEntry, drain: Option<Duration>) -> Result<bool> {
     let pid_file = pid_path(&team.name)?;
     if !pid_file.exists() {
-        bail!("Daemon not running for team '{}'", team.name);
+        return Ok(false);
     }
 
     let pid_str = fs::read_to_string(&pid_file)
@@ -207,95 +925,804 @@ pub fn stop(team_flag: Option<&str>) -> Result<()> {
         .parse()
         .context("Invalid PID in daemon PID file")?;
 
-    if state::is_alive(pid) {
+    let drained = match drain {
+        Some(timeout) if procutil::is_alive(pid) => drain_daemon(&team.name, pid, timeout)?,
+        _ => false,
+    };
+    if !drained && procutil::is_alive(pid) {
         // Send SIGTERM
-        unsafe {
-            libc::kill(pid as i32, libc::SIGTERM);
-        }
-
-        // Wait up to 30 seconds
-        for _ in 0..30 {
-            if !state::is_alive(pid) {
-                break;
-            }
-            thread::sleep(Duration::from_secs(1));
-        }
+        procutil::terminate(pid);
 
-        // If still alive, SIGKILL
-        if state::is_alive(pid) {
-            unsafe {
-                libc::kill(pid as i32, libc::SIGKILL);
-            }
+        // Wait up to 30 seconds, then SIGKILL
+        if !procutil::wait_for_exit(pid, Duration::from_secs(30)) {
+            procutil::kill(pid);
         }
     }
+    teardown_tunnel(team);
 
     // Clean up files
     let _ = fs::remove_file(&pid_file);
-    let cfg_file = config_path(&team.name)?;
-    let _ = fs::remove_file(&cfg_file);
-    let poll_file = poll_state_path(&team.name)?;
-    let _ = fs::remove_file(&poll_file);
+    let _ = fs::remove_file(drain_path(&team.name)?);
+    remove_daemon_config(&team.name);
+    if let Err(e) = store::open().and_then(|s| s.remove(&store::poll_key(&team.name))) {
+        eprintln!("Warning: could not clear the poll state: {:#}", e);
+    }
+    let _ = fs::remove_file(watchdog::heartbeat_path(&team.name)?);
+    Ok(true)
+}
 
-    println!("Daemon stopped");
-    Ok(())
+/// Asks the daemon to drain and waits for it to exit: up to `timeout` for
+/// the in-flight run, plus time to end its members. Returns whether it did.
+fn drain_daemon(team_name: &str, pid: u32, timeout: Duration) -> Result<bool> {
+    let path = drain_path(team_name)?;
+    fs::write(&path, format!("{}\n", timeout.as_secs()))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    let queue = EventQueue::load_snapshot(&queue_path(team_name)?);
+    if !queue.in_flight.is_empty() {
+        eprintln!(
+            "Waiting up to {} for the run for {} to finish...",
+            timefmt::duration(timeout.as_secs()),
+            queue.in_flight.join(", ")
+        );
+    }
+    Ok(procutil::wait_for_exit(pid, timeout + DRAIN_GRACE))
 }
 
-/// Handles `bm daemon status`.
-pub fn status(team_flag: Option<&str>) -> Result<()> {
+/// Handles `bm daemon restart`.
+///
+/// Settings not given as flags carry over from the running daemon; if none is
+/// running, this behaves like `bm daemon start`.
+pub fn restart(
+    team_flag: Option<&str>,
+    mode_flag: Option<&str>,
+    port_flag: Option<u16>,
+    interval_flag: Option<u64>,
+    listener_flags: &ListenerFlags,
+    tunnel_flag: Option<Provider>,
+) -> Result<Started> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, team_flag)?;
 
-    let pid_file = pid_path(&team.name)?;
-    if !pid_file.exists() {
-        println!("Daemon: not running");
-        return Ok(());
+    if !is_running(&team.name)? {
+        eprintln!("Daemon not running for team '{}', starting it", team.name);
+        return start(
+            Some(&team.name),
+            mode_flag,
+            port_flag,
+            interval_flag,
+            listener_flags,
+            tunnel_flag,
+        );
     }
 
-    let pid_str = fs::read_to_string(&pid_file)
-        .context("Failed to read daemon PID file")?;
-    let pid: u32 = match pid_str.trim().parse() {
-        Ok(p) => p,
-        Err(_) => {
-            println!("Daemon: not running (corrupt PID file)");
-            let _ = fs::remove_file(&pid_file);
-            return Ok(());
-        }
-    };
+    // Read the current settings before `stop` removes the config file.
+    let current = read_daemon_config(&team.name);
+    stop(Some(&team.name), Some(Duration::from_secs(DEFAULT_DRAIN_SECS)))?;
+
+    let mode = mode_flag.or(current.as_ref().map(|c| c.mode.as_str()));
+    let port = port_flag.or(current.as_ref().map(|c| c.port));
+    let interval = interval_flag.or(current.as_ref().map(|c| c.interval_secs));
+    let current_listener = current.as_ref().map(|c| c.listener.clone()).unwrap_or_default();
+    let listener = listener_flags.apply(current_listener);
+    let current_tunnel = current.as_ref().and_then(|c| c.tunnel.as_ref()).map(|t| t.provider);
+    let tunnel = tunnel_flag.or(current_tunnel);
+    start_with(
+        Some(&team.name),
+        mode,
+        port,
+        interval,
+        listener,
+        !listener_flags.is_empty(),
+        tunnel,
+        None,
+    )?
+    .context("daemon restart returned no daemon")
+}
 
-    if !state::is_alive(pid) {
-        println!("Daemon: not running (stale PID file)");
-        // Clean up stale files
-        let _ = fs::remove_file(&pid_file);
-        let cfg_file = config_path(&team.name)?;
-        let _ = fs::remove_file(&cfg_file);
-        return Ok(());
+/// Returns true if the team's daemon PID file points at a live process.
+fn is_running(team_name: &str) -> Result<bool> {
+    Ok(running_pid(team_name)?.is_some())
+}
+
+/// Returns the PID of the team's daemon, if it is running.
+fn running_pid(team_name: &str) -> Result<Option<u32>> {
+    let pid = fs::read_to_string(pid_path(team_name)?)
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok());
+    Ok(pid.filter(|pid| procutil::is_alive(*pid)))
+}
+
+/// Handles `bm daemon config get [key] [-t team]`.
+/// Shows each setting's effective value and the layer it comes from.
+pub fn config_get(team_flag: Option<&str>, key: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let shared = daemon_settings::load_layer(&daemon_settings::team_settings_path(
+        &team.path.join("team"),
+    ))?
+    .unwrap_or_default();
+    let local_path = daemon_settings::local_override_path(&team.name)?;
+    let local = daemon_settings::load_layer(&local_path)?.unwrap_or_default();
+    let effective = shared.clone().overlay(local.clone());
+
+    if let Some(key) = key {
+        let value = effective.effective(key)?;
+        println!("{}", value.as_deref().unwrap_or("(not set)"));
+        return Ok(());
     }
 
-    // Read daemon config for details
-    let cfg_file = config_path(&team.name)?;
-    if cfg_file.exists() {
-        let contents = fs::read_to_string(&cfg_file)
-            .context("Failed to read daemon config")?;
-        if let Ok(daemon_cfg) = serde_json::from_str::<DaemonConfig>(&contents) {
-            println!("Daemon: running (PID {})", pid);
-            match daemon_cfg.mode.as_str() {
-                "webhook" => println!("Mode: webhook (port {})", daemon_cfg.port),
-                "poll" => println!("Mode: poll (interval {}s)", daemon_cfg.interval_secs),
-                other => println!("Mode: {}", other),
+    let width = daemon_settings::CONFIG_KEYS
+        .iter()
+        .map(|k| k.len())
+        .max()
+        .unwrap_or(0);
+    for key in daemon_settings::CONFIG_KEYS {
+        let value = effective.effective(key)?;
+        let source = if local.get(key)?.is_some() {
+            local_path.display().to_string()
+        } else if shared.get(key)?.is_some() {
+            daemon_settings::SETTINGS_FILE.to_string()
+        } else {
+            "default".to_string()
+        };
+        println!(
+            "{:<width$}  {}  ({})",
+            key,
+            value.as_deref().unwrap_or("(not set)"),
+            source,
+            width = width
+        );
+    }
+
+    // Flags given to `bm daemon start` win over the settings files
+    if let Some(pid) = running_pid(&team.name)? {
+        if let Some(running) = load_daemon_config(&team.name)? {
+            if running.mode != effective.mode()
+                || running.port != effective.port()
+                || running.interval_secs != effective.interval_secs()
+            {
+                println!();
+                println!(
+                    "The running daemon (PID {}) uses mode={}, port={}, interval_secs={} \
+                     from its start flags.",
+                    pid, running.mode, running.port, running.interval_secs
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Handles `bm daemon config set <key> <value>` and `bm daemon config unset
+/// <key>` (`value` is `None`). Edits the local override, or the team repo's
+/// `daemon.yml` with `shared`, then reloads a running daemon.
+pub fn config_set(
+    team_flag: Option<&str>,
+    key: &str,
+    value: Option<&str>,
+    shared: bool,
+) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let team_repo = team.path.join("team");
+    let local_path = daemon_settings::local_override_path(&team.name)?;
+    let path = if shared {
+        daemon_settings::team_settings_path(&team_repo)
+    } else {
+        local_path.clone()
+    };
+
+    let mut layer = daemon_settings::load_layer(&path)?.unwrap_or_else(|| DaemonSettings {
+        version: shared.then_some(daemon_settings::SETTINGS_VERSION),
+        ..Default::default()
+    });
+    match value {
+        Some(value) => {
+            layer.set(key, value)?;
+            daemon_settings::save_layer(&path, &layer)?;
+            println!("Set {} = {} in {}", key, value, path.display());
+        }
+        None => {
+            if !layer.unset(key)? {
+                println!("{} is not set in {}", key, path.display());
+                return Ok(());
             }
-            println!("Team: {}", daemon_cfg.team);
-            println!("Started: {}", format_timestamp(&daemon_cfg.started_at));
+            daemon_settings::save_layer(&path, &layer)?;
+            println!("Removed {} from {}", key, path.display());
+        }
+    }
+    if shared {
+        let local = daemon_settings::load_layer(&local_path)?.unwrap_or_default();
+        if local.get(key)?.is_some() {
+            println!(
+                "Note: {} still sets {} on this machine.",
+                local_path.display(),
+                key
+            );
+        }
+        println!(
+            "Commit and push {} to share it with the team.",
+            daemon_settings::SETTINGS_FILE
+        );
+    }
+
+    let Some(pid) = running_pid(&team.name)? else {
+        return Ok(());
+    };
+    if key == "log_format" {
+        println!("Restart the daemon to switch its log format: bm daemon restart");
+        return Ok(());
+    }
+    if matches!(key, "mode" | "port" | "interval_secs") {
+        // The running daemon reloads these from its persisted config, where
+        // the other start flags stay as they were
+        let settings = daemon_settings::load(&team_repo, &team.name)?;
+        let mut daemon_cfg = load_daemon_config(&team.name)?
+            .with_context(|| format!("No daemon config recorded for team '{}'", team.name))?;
+        match key {
+            "mode" => daemon_cfg.mode = settings.mode().to_string(),
+            "port" => daemon_cfg.port = settings.port(),
+            _ => daemon_cfg.interval_secs = settings.interval_secs(),
+        }
+        save_daemon_config(&daemon_cfg)?;
+    }
+    procutil::reload(pid);
+    println!("Reloaded the running daemon (PID {})", pid);
+    Ok(())
+}
+
+/// Asks the running daemon for a one-shot run, as if a relevant event had
+/// arrived. Fails if the daemon isn't running.
+pub fn trigger(team_name: &str, by: &str) -> Result<()> {
+    if !is_running(team_name)? {
+        bail!(
+            "The daemon for team '{}' is not running. Start it with `bm daemon start -t {}`.",
+            team_name,
+            team_name
+        );
+    }
+    let path = trigger_path(team_name)?;
+    fs::write(&path, format!("{}\n", by))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Describes the team's daemon in one line, for plain-text reports.
+pub fn summary(team_name: &str) -> Result<String> {
+    let row = daemon_row(team_name, chrono::Utc::now())?;
+    let mut line = match row.status {
+        "running" => format!("Daemon: running, {}, up {}", row.mode, row.uptime),
+        _ => "Daemon: not running".to_string(),
+    };
+    let queue = EventQueue::load_snapshot(&queue_path(team_name)?);
+    if !queue.in_flight.is_empty() {
+        line.push_str(&format!("; running for {}", queue.in_flight.join(", ")));
+    }
+    if !queue.pending.is_empty() {
+        line.push_str(&format!("; pending {}", queue.pending.join(", ")));
+    }
+    if let Some(note) = state::paused(team_name) {
+        line.push_str(&format!("; paused {}", note));
+    }
+    Ok(line)
+}
+
+/// Reads the persisted daemon config, if present and parseable.
+pub(crate) fn read_daemon_config(team_name: &str) -> Option<DaemonConfig> {
+    load_daemon_config(team_name).ok().flatten()
+}
+
+/// Loads the running daemon's config from the [`store`], or `None` if none
+/// is recorded.
+pub(crate) fn load_daemon_config(team_name: &str) -> Result<Option<DaemonConfig>> {
+    let store = store::open()?;
+    let key = store::daemon_key(team_name);
+    match store.read(&key)? {
+        Some(contents) => Ok(Some(parse::json(&store.location(&key), &contents)?)),
+        None => Ok(None),
+    }
+}
+
+/// Records the daemon's config in the [`store`].
+fn save_daemon_config(daemon_cfg: &DaemonConfig) -> Result<()> {
+    let contents =
+        serde_json::to_string_pretty(daemon_cfg).context("Failed to serialize daemon config")?;
+    store::open()?.update(&store::daemon_key(&daemon_cfg.team), &mut |_| Ok(contents.clone()))
+}
+
+/// Removes the daemon's config from the [`store`], warning on failure.
+fn remove_daemon_config(team_name: &str) {
+    if let Err(e) = store::open().and_then(|s| s.remove(&store::daemon_key(team_name))) {
+        eprintln!("Warning: could not clear the daemon config: {:#}", e);
+    }
+}
+
+/// Handles `bm daemon status`.
+pub fn status(team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+
+    let pid_file = pid_path(&team.name)?;
+    if !pid_file.exists() {
+        println!("Daemon: not running");
+        return Ok(());
+    }
+
+    let pid_str = fs::read_to_string(&pid_file)
+        .context("Failed to read daemon PID file")?;
+    let pid: u32 = match pid_str.trim().parse() {
+        Ok(p) => p,
+        Err(_) => {
+            println!("Daemon: not running (corrupt PID file)");
+            let _ = fs::remove_file(&pid_file);
             return Ok(());
         }
+    };
+
+    if !procutil::is_alive(pid) {
+        println!("Daemon: not running (stale PID file)");
+        // Clean up stale files
+        teardown_tunnel(team);
+        let _ = fs::remove_file(&pid_file);
+        remove_daemon_config(&team.name);
+        let _ = fs::remove_file(watchdog::heartbeat_path(&team.name)?);
+        return Ok(());
+    }
+
+    // Read daemon config for details
+    if let Some(daemon_cfg) = read_daemon_config(&team.name) {
+        println!("Daemon: running (PID {})", pid);
+        match daemon_cfg.mode.as_str() {
+            "webhook" => {
+                println!("Mode: webhook (port {})", daemon_cfg.port);
+                println!("Listening: {}", daemon_cfg.listener.url(daemon_cfg.port));
+                if daemon_cfg.listener.insecure_no_signature
+                    && load_webhook_secret(&daemon_cfg.team).is_none()
+                {
+                    println!("Signatures: not verified (--insecure-no-signature)");
+                }
+                if let Some(ref t) = daemon_cfg.tunnel {
+                    let note = if procutil::is_alive(t.pid) {
+                        ""
+                    } else {
+                        " (tunnel process not running)"
+                    };
+                    println!("Tunnel: {} ({}){}", t.url, t.provider, note);
+                }
+            }
+            "poll" => {
+                println!("Mode: poll (interval {}s)", daemon_cfg.interval_secs);
+                print_rate_limit(&team.name)?;
+            }
+            other => println!("Mode: {}", other),
+        }
+        println!("Team: {}", daemon_cfg.team);
+        match timefmt::parse(&daemon_cfg.started_at) {
+            Some(t) => println!(
+                "Started: {} (up {})",
+                timefmt::timestamp(t),
+                timefmt::duration(timefmt::elapsed(t, chrono::Utc::now()))
+            ),
+            None => println!("Started: {}", daemon_cfg.started_at),
+        }
+        print_queue(&team.name)?;
+        print_schedules(team)?;
+        print_pause(&team.name);
+        print_heartbeat(&team.name)?;
+        print_token_warning(&cfg, team);
+        return Ok(());
     }
 
     // Fallback: PID exists but no config
     println!("Daemon: running (PID {})", pid);
     println!("Team: {}", team.name);
+    print_pause(&team.name);
+    print_heartbeat(&team.name)?;
+    print_token_warning(&cfg, team);
+
+    Ok(())
+}
 
+/// Prints the GitHub API budget poll mode last saw, and any backoff after
+/// hitting a rate limit.
+fn print_rate_limit(team_name: &str) -> Result<()> {
+    let poll = load_poll_state(store::open()?.as_ref(), team_name);
+    if let Some(ref rate) = poll.rate_limit {
+        let reset = timefmt::parse(&rate.reset_at)
+            .map(timefmt::timestamp)
+            .unwrap_or_else(|| rate.reset_at.clone());
+        println!(
+            "GitHub API: {} of {} requests left (resets {})",
+            rate.remaining, rate.limit, reset
+        );
+    }
+    let backoff = poll.backoff_until.as_deref().and_then(timefmt::parse);
+    if let Some(until) = backoff.filter(|until| *until > chrono::Utc::now()) {
+        println!(
+            "Polling paused until {}: {}",
+            timefmt::timestamp(until),
+            poll.backoff_reason.as_deref().unwrap_or("rate limited")
+        );
+    }
     Ok(())
 }
 
+/// One team's daemon as shown by `bm daemon status --all`.
+#[derive(Debug, Clone, PartialEq)]
+struct DaemonRow {
+    team: String,
+    status: &'static str,
+    pid: String,
+    mode: String,
+    uptime: String,
+    last_poll: String,
+}
+
+/// Describes a team's daemon from its runtime files, without cleaning up
+/// stale ones.
+fn daemon_row(team_name: &str, now: chrono::DateTime<chrono::Utc>) -> Result<DaemonRow> {
+    let mut row = DaemonRow {
+        team: team_name.to_string(),
+        status: "stopped",
+        pid: "—".to_string(),
+        mode: "—".to_string(),
+        uptime: "—".to_string(),
+        last_poll: "—".to_string(),
+    };
+    let pid = fs::read_to_string(pid_path(team_name)?)
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok());
+    let Some(pid) = pid else {
+        return Ok(row);
+    };
+    row.pid = pid.to_string();
+    if !procutil::is_alive(pid) {
+        row.status = "stale";
+        return Ok(row);
+    }
+    row.status = "running";
+
+    if let Some(dcfg) = read_daemon_config(team_name) {
+        row.mode = match dcfg.mode.as_str() {
+            "webhook" => format!("webhook (port {})", dcfg.port),
+            "poll" => format!("poll ({}s)", dcfg.interval_secs),
+            other => other.to_string(),
+        };
+        if let Some(started) = timefmt::parse(&dcfg.started_at) {
+            row.uptime = timefmt::duration(timefmt::elapsed(started, now));
+        }
+    }
+    let poll = load_poll_state(store::open()?.as_ref(), team_name);
+    if let Some(at) = poll.last_poll_at {
+        row.last_poll = match timefmt::parse(&at) {
+            Some(t) => format!(
+                "{} ({})",
+                timefmt::timestamp(t),
+                timefmt::ago(timefmt::elapsed(t, now))
+            ),
+            None => at,
+        };
+    }
+    Ok(row)
+}
+
+/// Handles `bm daemon status --all` — one table of every registered team's
+/// daemon.
+pub fn status_all() -> Result<()> {
+    let cfg = config::load()?;
+    if cfg.teams.is_empty() {
+        println!("No teams registered. Run `bm init` to create one.");
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now();
+    let mut table = tables::new(vec!["Team", "Daemon", "PID", "Mode", "Uptime", "Last poll"]);
+    let mut running = 0;
+    for team in &cfg.teams {
+        let row = daemon_row(&team.name, now)?;
+        if row.status == "running" {
+            running += 1;
+        }
+        table.add_row(vec![
+            row.team.as_str(),
+            row.status,
+            row.pid.as_str(),
+            row.mode.as_str(),
+            row.uptime.as_str(),
+            row.last_poll.as_str(),
+        ]);
+    }
+
+    println!("{}", tables::render(&table));
+    println!("{} of {} daemon(s) running", running, cfg.teams.len());
+    Ok(())
+}
+
+/// Shows the most recent one-shot runs recorded by the daemon, oldest first.
+pub fn history(team_flag: Option<&str>, limit: usize) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+
+    let runs = store::open()?.runs(&team.name)?;
+    if runs.is_empty() {
+        println!("No daemon runs recorded for team '{}'.", team.name);
+        return Ok(());
+    }
+    let shown = &runs[runs.len().saturating_sub(limit)..];
+
+    let mut table = tables::new(vec!["Started", "Duration", "Events", "Members", "Result"]);
+    for run in shown {
+        table.add_row(history_row(run));
+    }
+    println!("{}", tables::render(&table));
+
+    let errors: Vec<_> = shown
+        .iter()
+        .flat_map(|r| r.errors.iter().map(move |e| (r, e)))
+        .collect();
+    if !errors.is_empty() {
+        println!("Errors:");
+        for (run, error) in errors {
+            println!("  {}  {}", timefmt::timestamp(run.started_at), error);
+        }
+    }
+    println!("Showing {} of {} recorded run(s)", shown.len(), runs.len());
+    Ok(())
+}
+
+/// Formats one run as a `bm daemon history` row.
+fn history_row(run: &RunRecord) -> Vec<String> {
+    let secs = (run.finished_at - run.started_at).num_seconds().max(0) as u64;
+    let members = if run.members.is_empty() {
+        "—".to_string()
+    } else {
+        run.members
+            .iter()
+            .map(|m| {
+                if m.ok && m.status != "terminated" {
+                    m.name.clone()
+                } else {
+                    format!("{} ({})", m.name, m.status)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let result = if run.interrupted {
+        "interrupted"
+    } else if run.ok() {
+        "ok"
+    } else {
+        "failed"
+    };
+    vec![
+        timefmt::timestamp(run.started_at),
+        timefmt::duration(secs),
+        run.events.join(", "),
+        members,
+        result.to_string(),
+    ]
+}
+
+/// Runs an event through the team's filters and routing without launching
+/// anything. `expr` tries a filter expression in place of the configured one.
+pub fn simulate(
+    team_flag: Option<&str>,
+    mut event: TriggerEvent,
+    payload: Option<&Path>,
+    expr: Option<&str>,
+) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let mut settings = daemon_settings::load(&team.path.join("team"), &team.name)?;
+    if let Some(expr) = expr {
+        settings.set("filters.expr", expr)?;
+    }
+
+    if let Some(path) = payload {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read payload at {}", path.display()))?;
+        let payload: serde_json::Value = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse payload at {}", path.display()))?;
+        event = TriggerEvent::from_webhook(&event.event, &payload);
+    }
+    if event.repo.is_empty() {
+        event.repo = team.github_repo.clone();
+    }
+
+    println!("Event:  {}", event.describe());
+    println!("Types:  {}", settings.relevant_events().join(", "));
+    if let Some(expr) = settings.filters.as_ref().and_then(|f| f.expr.as_deref()) {
+        println!("Filter: {}", expr);
+    }
+    println!("Result: {}", simulate_verdict(&settings, &event)?);
+    if let Some(q) = &settings.quiet_hours {
+        if settings.in_quiet_hours(chrono::Utc::now().time()) {
+            println!(
+                "Note: quiet hours ({}-{} UTC) are in effect; nothing launches until they end.",
+                q.start, q.end
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Handles `bm daemon events list`: recent webhook deliveries, oldest first.
+pub fn events_list(team_flag: Option<&str>, limit: usize) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+
+    let events = event_log::load(&events_dir(&team.name)?);
+    if events.is_empty() {
+        println!("No webhook deliveries recorded for team '{}'.", team.name);
+        return Ok(());
+    }
+    let shown = &events[events.len().saturating_sub(limit)..];
+
+    let mut table = tables::new(vec!["ID", "Received", "Event", "Signature", "Result"]);
+    for e in shown {
+        let kind = TriggerEvent::from_webhook(&e.event, &e.payload);
+        table.add_row(vec![
+            e.id.clone(),
+            timefmt::timestamp(e.received_at),
+            if kind.event.is_empty() { "—".to_string() } else { kind.describe() },
+            e.signature.as_str().to_string(),
+            e.verdict.clone(),
+        ]);
+    }
+    println!("{}", tables::render(&table));
+    println!("Showing {} of {} recorded deliveries", shown.len(), events.len());
+    Ok(())
+}
+
+/// Handles `bm daemon events show`: a delivery's verdict then and under
+/// the current settings, and its redacted payload.
+pub fn events_show(team_flag: Option<&str>, id: &str) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let record = event_log::find(&events_dir(&team.name)?, id)?;
+    let settings = daemon_settings::load(&team.path.join("team"), &team.name)?;
+
+    println!("Delivery:  {}", record.id);
+    println!("Received:  {}", timefmt::timestamp(record.received_at));
+    println!("Signature: {}", record.signature.as_str());
+    if record.event.is_empty() {
+        println!("Event:     (no X-GitHub-Event header)");
+    } else {
+        let event = TriggerEvent::from_webhook(&record.event, &record.payload);
+        println!("Event:     {}", event.describe());
+        println!("Then:      {}", record.verdict);
+        println!("Now:       {}", simulate_verdict(&settings, &event)?);
+    }
+    println!();
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&record.payload).context("Failed to format payload")?
+    );
+    Ok(())
+}
+
+/// Handles `bm daemon events replay`: asks the running daemon to run a
+/// recorded delivery through its current filters again. Signature checks
+/// are not repeated.
+pub fn events_replay(team_flag: Option<&str>, id: &str) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let record = event_log::find(&events_dir(&team.name)?, id)?;
+    if record.event.is_empty() {
+        bail!("Delivery '{}' has no event type to replay", record.id);
+    }
+    if !is_running(&team.name)? {
+        bail!(
+            "The daemon for team '{}' is not running. Start it with `bm daemon start -t {}`.",
+            team.name,
+            team.name
+        );
+    }
+
+    let settings = daemon_settings::load(&team.path.join("team"), &team.name)?;
+    let event = TriggerEvent::from_webhook(&record.event, &record.payload);
+    let path = replay_path(&team.name)?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    std::io::Write::write_all(&mut file, format!("{}\n", record.id).as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!("Replaying {}: {}", record.id, event.describe());
+    println!("Expected: {}", simulate_verdict(&settings, &event)?);
+    println!("Follow it with `bm daemon history -t {}`.", team.name);
+    Ok(())
+}
+
+/// Describes what the daemon would do with `event` under `settings`.
+fn simulate_verdict(settings: &DaemonSettings, event: &TriggerEvent) -> Result<String> {
+    if !settings.is_relevant(&event.event) {
+        return Ok(format!(
+            "ignored, '{}' is not a relevant event type",
+            event.event
+        ));
+    }
+    if !settings.passes_triggers(event) {
+        return Ok(format!(
+            "ignored, no filters.triggers entry for '{}' matches",
+            event.event
+        ));
+    }
+    if let Some(expr) = settings.filter_expr()? {
+        if !expr.eval(event) {
+            return Ok("ignored, the filter expression is false".to_string());
+        }
+    }
+    Ok(match settings.roles_for_events(&[&event.event]) {
+        None => "triggers a run of every member".to_string(),
+        Some(roles) if roles.is_empty() => {
+            "triggers a run, but routing selects no roles".to_string()
+        }
+        Some(roles) => format!("triggers a run of roles: {}", roles.join(", ")),
+    })
+}
+
+/// Prints queued and in-flight events for `bm daemon status`.
+fn print_queue(team_name: &str) -> Result<()> {
+    let queue = EventQueue::load_snapshot(&queue_path(team_name)?);
+    if !queue.in_flight.is_empty() {
+        println!("Running for: {}", queue.in_flight.join(", "));
+    }
+    if !queue.pending.is_empty() {
+        println!("Pending events: {}", queue.pending.join(", "));
+    }
+    Ok(())
+}
+
+/// Prints each schedule's next run for `bm daemon status`.
+fn print_schedules(team: &TeamEntry) -> Result<()> {
+    let settings = daemon_settings::load(&team.path.join("team"), &team.name)?;
+    let next = Scheduler::load_snapshot(&schedules_path(&team.name)?);
+    for schedule in settings.schedules() {
+        let when = match next.get(&schedule.name) {
+            Some(n) if n.cron == schedule.cron => format!("next run {}", timefmt::timestamp(n.at)),
+            _ => "not yet picked up (reload the daemon)".to_string(),
+        };
+        println!("Schedule: {} ({}), {}", schedule.name, schedule.cron, when);
+    }
+    Ok(())
+}
+
+/// Prints the pause for `bm daemon status`, if launches are paused.
+fn print_pause(team_name: &str) {
+    if let Some(note) = state::paused(team_name) {
+        println!("Paused: {} (events queue, no members launch)", note);
+    }
+}
+
+/// Prints the heartbeat age for `bm daemon status`, warning if it is stale.
+fn print_heartbeat(team_name: &str) -> Result<()> {
+    let path = watchdog::heartbeat_path(team_name)?;
+    let Some(age) = watchdog::heartbeat_age(&path, chrono::Utc::now()) else {
+        return Ok(());
+    };
+    println!("Heartbeat: {}", timefmt::ago(age.as_secs()));
+    if watchdog::is_stale(age) {
+        println!(
+            "Warning: daemon heartbeat is stale (last beat {}); the process is alive \
+             but its event loop appears stuck. Check {} and run `bm daemon restart -t {}`.",
+            timefmt::ago(age.as_secs()),
+            log_path(team_name)?.display(),
+            team_name
+        );
+    }
+    Ok(())
+}
+
+/// Prints a token expiry warning for `bm daemon status`, if one applies.
+fn print_token_warning(cfg: &config::BotminterConfig, team: &config::TeamEntry) {
+    if let Some(warning) = token_expiry::check_team(team, cfg.token_expiry_warn_days) {
+        println!("Warning: {}", warning);
+    }
+}
+
 // ── Daemon event loop (called by hidden `bm daemon-run` command) ─────
 
 /// Runs the daemon event loop. Called by the hidden `bm daemon-run` command.
@@ -305,79 +1732,282 @@ pub fn run_daemon(
     mode: &str,
     port: u16,
     interval: u64,
+    listener: Listener,
 ) -> Result<()> {
-    // Set up signal handling for graceful shutdown
+    // `shutdown` stops taking events; `terminate` also ends in-flight
+    // members. A signal sets both, a drain request only the first.
     let shutdown = Arc::new(AtomicBool::new(false));
+    let terminate = Arc::new(AtomicBool::new(false));
+    let drain_secs = Arc::new(AtomicU64::new(0));
     {
-        let shutdown = Arc::clone(&shutdown);
-        unsafe {
-            libc::signal(
-                libc::SIGTERM,
-                sigterm_handler as *const () as libc::sighandler_t,
-            );
-            libc::signal(
-                libc::SIGINT,
-                sigterm_handler as *const () as libc::sighandler_t,
-            );
-        }
-        // Use a thread to poll for the signal flag
-        SHUTDOWN_FLAG.store(false, Ordering::SeqCst);
-        let s = shutdown;
+        let (shutdown, terminate, drain_secs) =
+            (Arc::clone(&shutdown), Arc::clone(&terminate), Arc::clone(&drain_secs));
+        procutil::install_signal_handlers();
+        // Use a thread to poll for the signal flag and drain requests
+        SHUTDOWN_REQUESTED.store(false, Ordering::SeqCst);
+        let drain_file = drain_path(team_name)?;
+        let _ = fs::remove_file(&drain_file);
         thread::spawn(move || {
-            loop {
-                if SHUTDOWN_FLAG.load(Ordering::SeqCst) {
-                    s.store(true, Ordering::SeqCst);
+            while !terminate.load(Ordering::SeqCst) {
+                if SHUTDOWN_REQUESTED.load(Ordering::SeqCst) {
+                    shutdown.store(true, Ordering::SeqCst);
+                    terminate.store(true, Ordering::SeqCst);
                     break;
                 }
+                if let Ok(secs) = fs::read_to_string(&drain_file) {
+                    let _ = fs::remove_file(&drain_file);
+                    let secs = secs.trim().parse().unwrap_or(DEFAULT_DRAIN_SECS);
+                    drain_secs.store(secs, Ordering::SeqCst);
+                    shutdown.store(true, Ordering::SeqCst);
+                }
                 thread::sleep(Duration::from_millis(200));
             }
         });
     }
 
-    daemon_log(team_name, "INFO", &format!("Daemon starting in {} mode", mode));
+    let mut settings = load_settings(team_name)?;
+    let retention = config::load()
+        .ok()
+        .and_then(|cfg| cfg.logs)
+        .unwrap_or_default();
+    logging::init_daemon(log_path(team_name)?, settings.log_format(), retention);
+    let _span = info_span!("daemon", team = %team_name).entered();
+    info!("Daemon starting in {} mode", mode);
+    {
+        let (team_name, mode) = (team_name.to_string(), mode.to_string());
+        spawn_in_span(move || {
+            notify_team(
+                &team_name,
+                Kind::DaemonStart,
+                "Daemon started",
+                format!("The daemon started in {} mode (PID {}).", mode, std::process::id()),
+            )
+        });
+    }
 
-    match mode {
-        "webhook" => run_webhook_mode(team_name, port, &shutdown),
-        "poll" => run_poll_mode(team_name, interval, &shutdown),
-        _ => bail!("Invalid daemon mode: {}", mode),
+    RELOAD_REQUESTED.store(false, Ordering::SeqCst);
+    mark_progress();
+    // Members still running during a drain need the heartbeat and fresh tokens
+    spawn_heartbeat_thread(team_name, &terminate);
+    spawn_token_refresh_thread(team_name, &terminate);
+    spawn_token_expiry_thread(team_name, &shutdown);
+    spawn_snapshot_thread(team_name, &shutdown);
+
+    let mut mode = mode.to_string();
+    let mut port = port;
+    let mut interval = interval;
+    let mut listener = listener;
+    let mut dispatcher = Dispatcher::new(team_name, &terminate)?;
+    notify_systemd("READY=1");
+
+    loop {
+        let exit = match mode.as_str() {
+            "webhook" => run_webhook_mode(
+                team_name,
+                port,
+                &listener,
+                &settings,
+                &mut dispatcher,
+                &shutdown,
+            )?,
+            "poll" => run_poll_mode(team_name, interval, &settings, &mut dispatcher, &shutdown)?,
+            _ => bail!("Invalid daemon mode: {}", mode),
+        };
+        if exit == LoopExit::Shutdown {
+            break;
+        }
+
+        // SIGHUP: re-read the persisted daemon config, daemon settings, and
+        // credentials (the latter are read fresh on every launch anyway).
+        notify_systemd("RELOADING=1");
+        match reload(team_name) {
+            Ok((new_cfg, new_settings)) => {
+                mode = new_cfg.mode;
+                port = new_cfg.port;
+                interval = new_cfg.interval_secs;
+                listener = new_cfg.listener;
+                settings = new_settings;
+                info!(
+                    "Reloaded configuration: mode={}, port={}, interval={}s, events=[{}]",
+                    mode,
+                    port,
+                    interval,
+                    settings.relevant_events().join(", ")
+                );
+            }
+            Err(e) => error!("Reload failed, keeping previous configuration: {:#}", e),
+        }
+        notify_systemd("READY=1");
+    }
+
+    notify_systemd("STOPPING=1");
+    let drain_secs = drain_secs.load(Ordering::SeqCst);
+    if drain_secs > 0 {
+        dispatcher.drain(Duration::from_secs(drain_secs));
     }
+    terminate.store(true, Ordering::SeqCst);
+    dispatcher.finish();
+    if let Ok(path) = watchdog::heartbeat_path(team_name) {
+        let _ = fs::remove_file(path);
+    }
+    notify_team(
+        team_name,
+        Kind::DaemonStop,
+        "Daemon stopped",
+        format!("The daemon (PID {}) stopped.", std::process::id()),
+    );
+    info!("Daemon stopped");
+    Ok(())
 }
 
-// Global flag set by SIGTERM handler
-static SHUTDOWN_FLAG: AtomicBool = AtomicBool::new(false);
+/// Why a mode loop returned.
+#[derive(Debug, PartialEq)]
+enum LoopExit {
+    Shutdown,
+    Reload,
+}
 
-extern "C" fn sigterm_handler(_sig: libc::c_int) {
-    SHUTDOWN_FLAG.store(true, Ordering::SeqCst);
+/// Re-reads the persisted daemon config and daemon settings for a reload.
+fn reload(team_name: &str) -> Result<(DaemonConfig, DaemonSettings)> {
+    let daemon_cfg = load_daemon_config(team_name)?
+        .with_context(|| format!("No daemon config recorded for team '{}'", team_name))?;
+    daemon_settings::validate_mode(&daemon_cfg.mode)?;
+    daemon_cfg.listener.validate()?;
+    let settings = load_settings(team_name)?;
+    Ok((daemon_cfg, settings))
+}
+
+// Unix time of the event loop's last sign of life; read by the heartbeat thread
+static LAST_PROGRESS: AtomicU64 = AtomicU64::new(0);
+
+/// Records that the event loop is alive. Call from the main loop only, so a
+/// wedged loop stops the heartbeat even while helper threads keep running.
+fn mark_progress() {
+    LAST_PROGRESS.store(chrono::Utc::now().timestamp() as u64, Ordering::SeqCst);
+}
+
+/// Sends a systemd notification, logging failures.
+fn notify_systemd(state: &str) {
+    if let Err(e) = watchdog::sd_notify(state) {
+        warn!("{:#}", e);
+    }
+}
+
+/// Spawns a thread that logs within the current span, so its entries carry
+/// the daemon's team.
+fn spawn_in_span<F, T>(f: F) -> thread::JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let span = tracing::Span::current();
+    thread::spawn(move || span.in_scope(f))
+}
+
+/// Writes the heartbeat file and pings the systemd watchdog while the event
+/// loop keeps making progress. When the loop stalls, beats are withheld so
+/// `bm daemon status` and systemd can tell the daemon is wedged.
+fn spawn_heartbeat_thread(team_name: &str, shutdown: &Arc<AtomicBool>) {
+    let path = match watchdog::heartbeat_path(team_name) {
+        Ok(p) => p,
+        Err(e) => {
+            warn!("Heartbeat disabled: {:#}", e);
+            return;
+        }
+    };
+    let every = watchdog::beat_interval(watchdog::watchdog_timeout());
+    let shutdown = Arc::clone(shutdown);
+    spawn_in_span(move || {
+        let mut stalled = false;
+        while !shutdown.load(Ordering::SeqCst) {
+            let now = chrono::Utc::now();
+            let idle = (now.timestamp() as u64).saturating_sub(LAST_PROGRESS.load(Ordering::SeqCst));
+            if idle <= watchdog::HEARTBEAT_STALE_SECS {
+                stalled = false;
+                if let Err(e) = watchdog::write_heartbeat(&path, now) {
+                    warn!("{:#}", e);
+                }
+                notify_systemd("WATCHDOG=1");
+            } else if !stalled {
+                stalled = true;
+                error!("Event loop has made no progress for {}s, withholding heartbeat", idle);
+            }
+            thread::sleep(every);
+        }
+    });
+}
+
+/// Consumes a pending reload request, logging it.
+fn take_reload_request() -> bool {
+    if RELOAD_REQUESTED.swap(false, Ordering::SeqCst) {
+        info!("Received SIGHUP, reloading configuration");
+        return true;
+    }
+    false
 }
 
 /// Runs the daemon in webhook mode using tiny_http.
 fn run_webhook_mode(
     team_name: &str,
     port: u16,
+    listener: &Listener,
+    settings: &DaemonSettings,
+    dispatcher: &mut Dispatcher,
     shutdown: &Arc<AtomicBool>,
-) -> Result<()> {
-    let addr = format!("0.0.0.0:{}", port);
-    let server = tiny_http::Server::http(&addr)
-        .map_err(|e| anyhow::anyhow!("Failed to bind to {}: {}", addr, e))?;
+) -> Result<LoopExit> {
+    // After a reload the previous server releases the port asynchronously
+    let mut attempts = 0;
+    let server = loop {
+        match listener.serve(port) {
+            Ok(server) => break server,
+            Err(_) if attempts < REBIND_ATTEMPTS => {
+                attempts += 1;
+                thread::sleep(Duration::from_millis(100));
+            }
+            Err(e) => return Err(e),
+        }
+    };
 
-    daemon_log(team_name, "INFO", &format!("Webhook server listening on port {}", port));
+    info!("Webhook server listening on {}", listener.url(port));
 
-    // Load webhook secret if configured
+    // Without a secret, deliveries are only accepted when explicitly allowed
     let webhook_secret = load_webhook_secret(team_name);
+    if webhook_secret.is_none() {
+        if !listener.insecure_no_signature {
+            bail!(
+                "Team '{}' has no webhook secret; refusing to accept unsigned deliveries. \
+                 Set teams[].credentials.webhook_secret or restart with --insecure-no-signature.",
+                team_name
+            );
+        }
+        warn!(
+            "INSECURE: no webhook secret for team '{}'; signature validation is disabled \
+             (--insecure-no-signature). Any request to {} can trigger member runs.",
+            team_name,
+            listener.url(port)
+        );
+    }
+
+    let mut handled = DeliveryIds::load(&deliveries_path(team_name)?);
 
     loop {
+        mark_progress();
         if shutdown.load(Ordering::SeqCst) {
-            daemon_log(team_name, "INFO", "Received shutdown signal, stopping webhook server");
-            break;
+            info!("Received shutdown request, stopping webhook server");
+            return Ok(LoopExit::Shutdown);
         }
+        if take_reload_request() {
+            return Ok(LoopExit::Reload);
+        }
+        dispatcher.tick(settings);
 
         // Non-blocking accept with timeout
         match server.recv_timeout(Duration::from_secs(1)) {
             Ok(Some(mut request)) => {
-                let path = request.url().to_string();
+                let path = request.url().split('?').next().unwrap_or("").to_string();
                 let method = request.method().to_string();
 
-                if method != "POST" || path != "/webhook" {
+                if method != "POST" || path != listener.path {
                     let response = tiny_http::Response::from_string("Not Found")
                         .with_status_code(404);
                     let _ = request.respond(response);
@@ -387,13 +2017,39 @@ fn run_webhook_mode(
                 // Read body
                 let mut body = String::new();
                 if let Err(e) = request.as_reader().read_to_string(&mut body) {
-                    daemon_log(team_name, "ERROR", &format!("Failed to read request body: {}", e));
+                    error!("Failed to read request body: {}", e);
                     let response = tiny_http::Response::from_string("Bad Request")
                         .with_status_code(400);
                     let _ = request.respond(response);
                     continue;
                 }
 
+                // Parse event type and delivery ID from headers
+                let event_type = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.as_str() == "X-GitHub-Event"
+                        || h.field.as_str() == "x-github-event")
+                    .map(|h| h.value.as_str().to_string());
+
+                let delivery_header = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("X-GitHub-Delivery"))
+                    .map(|h| h.value.as_str().to_string());
+                let delivery = delivery_header
+                    .clone()
+                    .unwrap_or_else(|| format!("local-{}", chrono::Utc::now().timestamp_micros()));
+                let payload: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+                let mut delivered = EventRecord {
+                    id: delivery.clone(),
+                    received_at: chrono::Utc::now(),
+                    event: event_type.clone().unwrap_or_default(),
+                    signature: Signature::Unchecked,
+                    verdict: String::new(),
+                    payload: payload.clone(),
+                };
+
                 // Validate signature if webhook secret is configured
                 if let Some(ref secret) = webhook_secret {
                     let sig_header = request
@@ -404,167 +2060,851 @@ fn run_webhook_mode(
                         .map(|h| h.value.as_str().to_string());
 
                     if !validate_webhook_signature(secret, &body, sig_header.as_deref()) {
-                        daemon_log(team_name, "WARN", "Webhook signature validation failed");
+                        warn!("Webhook signature validation failed (delivery {})", delivery);
                         let response = tiny_http::Response::from_string("Forbidden")
                             .with_status_code(403);
                         let _ = request.respond(response);
+                        delivered.signature = Signature::Invalid;
+                        delivered.verdict = "rejected, signature validation failed".to_string();
+                        record_event(team_name, delivered);
                         continue;
                     }
+                    delivered.signature = Signature::Valid;
                 }
 
-                // Parse event type from header
-                let event_type = request
-                    .headers()
-                    .iter()
-                    .find(|h| h.field.as_str() == "X-GitHub-Event"
-                        || h.field.as_str() == "x-github-event")
-                    .map(|h| h.value.as_str().to_string());
-
                 let response = tiny_http::Response::from_string("OK")
                     .with_status_code(200);
                 let _ = request.respond(response);
 
-                if let Some(event_type) = event_type {
-                    if is_relevant_event(&event_type) {
-                        daemon_log(
-                            team_name,
-                            "INFO",
-                            &format!("Received relevant event: {}", event_type),
-                        );
-                        // Launch members one-shot (blocks until all exit)
-                        handle_member_launch(team_name, shutdown);
-                    } else {
-                        daemon_log(
-                            team_name,
-                            "DEBUG",
-                            &format!("Ignoring irrelevant event: {}", event_type),
-                        );
+                // GitHub retries deliveries; each retry must not launch members again
+                if let Some(id) = &delivery_header {
+                    if handled.seen(id).unwrap_or_else(|e| {
+                        warn!("Failed to update handled deliveries: {:#}", e);
+                        true
+                    }) {
+                        // The event log keeps the first delivery's verdict
+                        info!("Ignoring redelivery of delivery {}", id);
+                        continue;
+                    }
+                }
+
+                match event_type {
+                    Some(event_type) => {
+                        let _event =
+                            info_span!("event", id = %delivery, kind = %event_type).entered();
+                        delivered.verdict = handle_delivery(settings, dispatcher, &event_type, &payload);
                     }
+                    None => delivered.verdict = "ignored, no X-GitHub-Event header".to_string(),
                 }
+                if let Some(id) = &delivery_header {
+                    if let Err(e) = handled.insert(id) {
+                        warn!("Failed to remember delivery {}: {:#}", id, e);
+                    }
+                }
+                record_event(team_name, delivered);
             }
             Ok(None) => {
                 // Timeout — no request, check shutdown flag on next iteration
             }
             Err(e) => {
-                daemon_log(team_name, "ERROR", &format!("Server error: {}", e));
+                error!("Server error: {}", e);
                 thread::sleep(Duration::from_secs(1));
             }
         }
     }
+}
 
-    daemon_log(team_name, "INFO", "Daemon stopped");
-    Ok(())
+/// Queues a webhook delivery if it passes the team's event filters, and
+/// returns what became of it for the event log.
+fn handle_delivery(
+    settings: &DaemonSettings,
+    dispatcher: &mut Dispatcher,
+    event_type: &str,
+    payload: &serde_json::Value,
+) -> String {
+    let event = TriggerEvent::from_webhook(event_type, payload);
+    if is_relevant_event(settings, &event) {
+        info!("Received relevant event: {}", event.describe());
+        dispatcher.enqueue(event_type);
+        "queued".to_string()
+    } else {
+        debug!("Ignoring irrelevant event: {}", event.describe());
+        simulate_verdict(settings, &event).unwrap_or_else(|e| format!("ignored, {:#}", e))
+    }
+}
+
+/// Adds a delivery to the team's event log, logging rather than failing.
+fn record_event(team_name: &str, record: EventRecord) {
+    if let Err(e) = events_dir(team_name).and_then(|dir| event_log::record(&dir, record)) {
+        warn!("Failed to record webhook delivery: {:#}", e);
+    }
+}
+
+/// Runs the daemon in poll mode using gh API.
+fn run_poll_mode(
+    team_name: &str,
+    interval: u64,
+    settings: &DaemonSettings,
+    dispatcher: &mut Dispatcher,
+    shutdown: &Arc<AtomicBool>,
+) -> Result<LoopExit> {
+    info!("Poll mode started, interval: {}s", interval);
+
+    // Load poll state
+    let store = store::open()?;
+    let mut poll_state = load_poll_state(store.as_ref(), team_name);
+    let mut tokens = github_app::TeamTokenSource::default();
+
+    loop {
+        mark_progress();
+        if shutdown.load(Ordering::SeqCst) {
+            info!("Received shutdown request, stopping poll loop");
+            return Ok(LoopExit::Shutdown);
+        }
+        if take_reload_request() {
+            return Ok(LoopExit::Reload);
+        }
+
+        // Don't consume events during quiet hours; they are picked up afterwards
+        if in_quiet_hours(settings) {
+            idle(interval, settings, dispatcher, shutdown);
+            continue;
+        }
+        if let Some(wait) = poll_state.backoff_remaining(chrono::Utc::now()) {
+            idle(wait.min(interval), settings, dispatcher, shutdown);
+            continue;
+        }
+
+        // Resolve GitHub repos and credentials for this team
+        let target = match resolve_poll_target(team_name, &mut tokens) {
+            Ok(target) => target,
+            Err(e) => {
+                error!("Failed to resolve GitHub repo: {:#}", e);
+                idle(interval, settings, dispatcher, shutdown);
+                continue;
+            }
+        };
+
+        // Poll each repo for events
+        for repo in &target.repos {
+            let polled = poll_github_events(
+                repo,
+                poll_state.last_event_id(repo, &target.team_repo),
+                poll_state.etags.get(repo).map(String::as_str),
+                target.token.as_deref(),
+                poll_state.throttled,
+            );
+            if let Some(rate) = polled.as_ref().ok().and_then(|p| p.rate_limit.clone()) {
+                debug!("{} of {} GitHub API requests left", rate.remaining, rate.limit);
+                poll_state.rate_limit = Some(rate);
+            }
+            match polled {
+                Ok(Polled {
+                    limited: Some(limited),
+                    ..
+                }) => {
+                    warn!(
+                        "GitHub refused polling {}: {}; waiting {} before polling again",
+                        repo,
+                        limited.reason,
+                        timefmt::duration(limited.wait_secs)
+                    );
+                    poll_state.back_off(&limited, chrono::Utc::now());
+                    break;
+                }
+                Ok(Polled {
+                    not_modified: true,
+                    ..
+                }) => {
+                    poll_state.throttled = 0;
+                    debug!("No new events in {}", repo);
+                }
+                Ok(Polled { events, etag, .. }) => {
+                    poll_state.throttled = 0;
+                    match etag {
+                        Some(etag) => poll_state.etags.insert(repo.clone(), etag),
+                        None => poll_state.etags.remove(repo),
+                    };
+                    let mut relevant: Vec<&str> = Vec::new();
+                    for e in &events {
+                        let _event =
+                            info_span!("event", id = %e.id, kind = %e.event_type).entered();
+                        let event = e.to_trigger_event();
+                        if is_relevant_event(settings, &event) {
+                            debug!("Received relevant event in {}: {}", repo, event.describe());
+                            relevant.push(&e.event_type);
+                        }
+                    }
+
+                    if !relevant.is_empty() {
+                        info!("Found {} relevant event(s) in {}", relevant.len(), repo);
+                        for event_type in &relevant {
+                            dispatcher.enqueue(event_type);
+                        }
+                    }
+
+                    // Update poll state with latest event ID
+                    if let Some(latest) = events.first() {
+                        poll_state.record(repo, &target.team_repo, &latest.id);
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to poll GitHub events for {}: {}", repo, e);
+                }
+            }
+        }
+        let now = chrono::Utc::now();
+        if poll_state.backoff_until.is_none() {
+            let low = poll_state.rate_limit.as_ref().and_then(|rate| {
+                rate.too_low_for(target.repos.len() as u64, now)
+            });
+            if let Some(limited) = low {
+                warn!(
+                    "GitHub {}; waiting {} for it to reset",
+                    limited.reason,
+                    timefmt::duration(limited.wait_secs)
+                );
+                poll_state.back_off(&limited, now);
+            }
+        }
+        poll_state.last_poll_at = Some(now.to_rfc3339());
+        save_poll_state(store.as_ref(), team_name, &poll_state);
+
+        idle(interval, settings, dispatcher, shutdown);
+    }
+}
+
+/// Loads the team's daemon settings from the team repo and local override.
+fn load_settings(team_name: &str) -> Result<DaemonSettings> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, Some(team_name))?;
+    daemon_settings::load(&team.path.join("team"), team_name)
+}
+
+/// Keeps GitHub App member tokens fresh during long one-shot runs.
+/// No-op for teams that authenticate with a plain `gh_token`.
+fn spawn_token_refresh_thread(team_name: &str, shutdown: &Arc<AtomicBool>) {
+    let uses_app = config::load()
+        .ok()
+        .and_then(|cfg| {
+            config::resolve_team(&cfg, Some(team_name))
+                .ok()
+                .map(|t| t.credentials.github_app.is_some())
+        })
+        .unwrap_or(false);
+    if !uses_app {
+        return;
+    }
+
+    let team_name = team_name.to_string();
+    let shutdown = Arc::clone(shutdown);
+    spawn_in_span(move || loop {
+        sleep_interruptible(github_app::REFRESH_CHECK_SECS, &shutdown);
+        if shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        match github_app::refresh_team_tokens(&team_name) {
+            Ok(refresh) => {
+                if !refresh.refreshed.is_empty() {
+                    info!("Refreshed GitHub App tokens: {}", refresh.refreshed.join(", "));
+                }
+                for (member, e) in &refresh.failed {
+                    error!("GitHub App token refresh failed for {}: {:#}", member, e);
+                }
+            }
+            Err(e) => error!("GitHub App token refresh failed: {:#}", e),
+        }
+    });
+}
+
+/// Logs a warning at startup and twice a day while the team's GitHub token
+/// is close to expiry, and notifies the team once when it enters the warning
+/// window and once when it expires.
+fn spawn_token_expiry_thread(team_name: &str, shutdown: &Arc<AtomicBool>) {
+    let team_name = team_name.to_string();
+    let shutdown = Arc::clone(shutdown);
+    spawn_in_span(move || {
+        let mut notices = token_expiry::Notices::default();
+        while !shutdown.load(Ordering::SeqCst) {
+            if let Ok(cfg) = config::load() {
+                if let Ok(team) = config::resolve_team(&cfg, Some(&team_name)) {
+                    match token_expiry::team_warning(team, cfg.token_expiry_warn_days) {
+                        Ok(Some(warning)) => {
+                            warn!("{}", warning);
+                            if notices.crossed(warning.stage) {
+                                let summary = match warning.stage {
+                                    token_expiry::Stage::Expired => "GitHub token expired",
+                                    _ => "GitHub token expiring",
+                                };
+                                notify_team(
+                                    &team_name,
+                                    Kind::TokenExpiring,
+                                    summary,
+                                    warning.message,
+                                );
+                            }
+                        }
+                        Ok(None) => {
+                            notices.crossed(token_expiry::Stage::Valid);
+                        }
+                        // A failed lookup says nothing about the token
+                        Err(_) => {}
+                    }
+                }
+            }
+            sleep_interruptible(TOKEN_EXPIRY_CHECK_SECS, &shutdown);
+        }
+    });
+}
+
+/// Records a snapshot of the team's live members in the state history
+/// every few minutes, so a crash can be placed between two snapshots.
+fn spawn_snapshot_thread(team_name: &str, shutdown: &Arc<AtomicBool>) {
+    let team_name = team_name.to_string();
+    let shutdown = Arc::clone(shutdown);
+    spawn_in_span(move || {
+        while !shutdown.load(Ordering::SeqCst) {
+            if let Err(e) = state_history::record_snapshot(&team_name) {
+                warn!("Failed to record state snapshot: {:#}", e);
+            }
+            sleep_interruptible(state_history::SNAPSHOT_INTERVAL_SECS, &shutdown);
+        }
+    });
+}
+
+/// Returns true (and logs) if launches are suppressed by quiet hours.
+fn in_quiet_hours(settings: &DaemonSettings) -> bool {
+    let now = chrono::Utc::now().time();
+    if settings.in_quiet_hours(now) {
+        info!("Quiet hours in effect, not launching members");
+        return true;
+    }
+    false
+}
+
+/// Launches members one-shot with logging, and records the run in the
+/// team's run history.
+///
+/// `roles` restricts the launch to members of those roles; `None` launches everyone.
+fn handle_member_launch(
+    team_name: &str,
+    events: Vec<String>,
+    roles: Option<&[String]>,
+    settings: &DaemonSettings,
+    shutdown: &Arc<AtomicBool>,
+) {
+    let _run = info_span!("run", events = %events.join(",")).entered();
+    let started_at = chrono::Utc::now();
+    let mut members = Vec::new();
+    let mut errors = Vec::new();
+    match launch_members_oneshot(team_name, roles, settings, shutdown, &mut members, &mut errors) {
+        Ok(()) => {
+            info!("One-shot run complete: {} member(s) processed", members.len());
+        }
+        Err(e) => {
+            error!("Member launch failed: {}", e);
+            errors.push(format!("{:#}", e));
+        }
+    }
+
+    let record = RunRecord {
+        events,
+        started_at,
+        finished_at: chrono::Utc::now(),
+        members,
+        errors,
+        interrupted: shutdown.load(Ordering::SeqCst),
+    };
+    if !record.ok() && !record.interrupted {
+        notify_team(
+            team_name,
+            Kind::RunFailed,
+            &format!("Run for {} failed", record.events.join(", ")),
+            run_report(&record),
+        );
+    }
+    let launched: Vec<String> = record.members.iter().map(|m| m.name.clone()).collect();
+    if let Err(e) = store::open().and_then(|s| s.append_run(team_name, &record)) {
+        warn!("Failed to record run history: {:#}", e);
+    }
+    if let Err(e) = usage::collect(team_name, &launched) {
+        warn!("Failed to record usage: {:#}", e);
+    }
+}
+
+/// Sleeps for the given duration, checking the shutdown and reload flags every second.
+fn sleep_interruptible(seconds: u64, shutdown: &Arc<AtomicBool>) {
+    for _ in 0..seconds {
+        if shutdown.load(Ordering::SeqCst) || RELOAD_REQUESTED.load(Ordering::SeqCst) {
+            break;
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// Like [`sleep_interruptible`], but for the event loop: keeps marking progress
+/// so a long poll interval doesn't look like a wedged daemon, and keeps the
+/// dispatcher ticking so queued events go out once their window closes.
+fn idle(
+    seconds: u64,
+    settings: &DaemonSettings,
+    dispatcher: &mut Dispatcher,
+    shutdown: &Arc<AtomicBool>,
+) {
+    for _ in 0..seconds {
+        mark_progress();
+        if shutdown.load(Ordering::SeqCst) || RELOAD_REQUESTED.load(Ordering::SeqCst) {
+            break;
+        }
+        dispatcher.tick(settings);
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// Delay before the first digest check, so the digest sees the PID file and
+/// config that `bm daemon start` writes after spawning the daemon.
+const DIGEST_STARTUP_DELAY: Duration = Duration::from_secs(3);
+
+/// When the digest goes out for a team without `smtp.digest_at`.
+const DEFAULT_DIGEST_TIME: chrono::NaiveTime = match chrono::NaiveTime::from_hms_opt(8, 0, 0) {
+    Some(time) => time,
+    None => panic!("invalid digest time"),
+};
+
+/// How often the dispatcher checks whether the daily digest is due.
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long to wait before checking again after the digest settings failed.
+const DIGEST_RETRY_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Feeds relevant events through the persisted [`EventQueue`] and runs at
+/// most one one-shot member launch at a time. Runs happen on a worker thread
+/// so the webhook server keeps answering GitHub while members work.
+struct Dispatcher {
+    team_name: String,
+    queue: EventQueue,
+    /// The in-flight run; yields `true` if it completed (not interrupted).
+    worker: Option<thread::JoinHandle<bool>>,
+    /// Why queued events are being held, once logged.
+    held: Option<String>,
+    /// When to next check whether the daily digest is due.
+    next_digest_check: Instant,
+    /// Next fire time of each schedule.
+    scheduler: Scheduler,
+    /// Set to end the in-flight run's members.
+    terminate: Arc<AtomicBool>,
+}
+
+impl Dispatcher {
+    fn new(team_name: &str, terminate: &Arc<AtomicBool>) -> Result<Dispatcher> {
+        let queue = EventQueue::load(&queue_path(team_name)?);
+        if !queue.state().pending.is_empty() {
+            info!(
+                "Resuming {} queued event type(s): {}",
+                queue.state().pending.len(),
+                queue.state().pending.join(", ")
+            );
+        }
+        Ok(Dispatcher {
+            team_name: team_name.to_string(),
+            queue,
+            worker: None,
+            held: None,
+            next_digest_check: Instant::now() + DIGEST_STARTUP_DELAY,
+            scheduler: Scheduler::load(&schedules_path(team_name)?),
+            terminate: Arc::clone(terminate),
+        })
+    }
+
+    /// Queues an event, coalescing it with pending events of the same type.
+    fn enqueue(&mut self, event_type: &str) {
+        if let Err(e) = self.queue.push(event_type, chrono::Utc::now()) {
+            error!("{:#}", e);
+        }
+    }
+
+    /// Reaps a finished run and starts the next one once the queue is ready.
+    fn tick(&mut self, settings: &DaemonSettings) {
+        self.take_trigger();
+        self.take_replays(settings);
+        self.fire_schedules(settings);
+        self.send_digest_if_due();
+        if self.worker.as_ref().is_some_and(|w| !w.is_finished()) {
+            return;
+        }
+        self.reap();
+
+        let now = chrono::Utc::now();
+        if !self.queue.ready(now, settings.debounce_secs()) {
+            return;
+        }
+        if let Some(note) = state::paused(&self.team_name) {
+            self.hold(format!("Paused {}, holding queued events until resumed", note));
+            return;
+        }
+        if settings.in_quiet_hours(now.time()) {
+            self.hold("Quiet hours in effect, holding queued events until they end".to_string());
+            return;
+        }
+        if self.over_budget() {
+            return;
+        }
+        self.held = None;
+
+        let events = match self.queue.take() {
+            Ok(events) => events,
+            Err(e) => {
+                error!("{:#}", e);
+                return;
+            }
+        };
+        let refs: Vec<&str> = events.iter().map(String::as_str).collect();
+        let roles = settings.roles_for_events(&refs);
+        info!("Dispatching one-shot run for: {}", events.join(", "));
+
+        let team_name = self.team_name.clone();
+        let settings = settings.clone();
+        let terminate = Arc::clone(&self.terminate);
+        self.worker = Some(spawn_in_span(move || {
+            handle_member_launch(&team_name, events, roles.as_deref(), &settings, &terminate);
+            !terminate.load(Ordering::SeqCst)
+        }));
+    }
+
+    /// Queues a manual run if one was requested with [`trigger`].
+    fn take_trigger(&mut self) {
+        let Ok(path) = trigger_path(&self.team_name) else {
+            return;
+        };
+        let Ok(by) = fs::read_to_string(&path) else {
+            return;
+        };
+        let _ = fs::remove_file(&path);
+        let by = by.trim();
+        let message = if by.is_empty() {
+            "Manual run requested".to_string()
+        } else {
+            format!("Manual run requested by {}", by)
+        };
+        info!("{}", message);
+        self.enqueue(MANUAL_EVENT);
+    }
+
+    /// Runs deliveries requested with [`events_replay`] through the current
+    /// filters again, queueing those that pass.
+    fn take_replays(&mut self, settings: &DaemonSettings) {
+        let Ok(path) = replay_path(&self.team_name) else {
+            return;
+        };
+        let Ok(ids) = fs::read_to_string(&path) else {
+            return;
+        };
+        let _ = fs::remove_file(&path);
+        let Ok(dir) = events_dir(&self.team_name) else {
+            return;
+        };
+        for id in ids.lines().map(str::trim).filter(|id| !id.is_empty()) {
+            let record = match event_log::find(&dir, id) {
+                Ok(record) => record,
+                Err(e) => {
+                    warn!("Cannot replay delivery: {:#}", e);
+                    continue;
+                }
+            };
+            let _event = info_span!("event", id = %record.id, kind = %record.event).entered();
+            info!("Replaying delivery received {}", timefmt::timestamp(record.received_at));
+            handle_delivery(settings, self, &record.event, &record.payload);
+        }
+    }
+
+    /// Queues a run for each schedule that has come due.
+    fn fire_schedules(&mut self, settings: &DaemonSettings) {
+        match self.scheduler.due(settings.schedules(), chrono::Utc::now()) {
+            Ok(due) => {
+                for schedule in due {
+                    info!("Schedule '{}' ({}) is due", schedule.name, schedule.cron);
+                    self.enqueue(&schedule.event());
+                }
+            }
+            Err(e) => error!("{:#}", e),
+        }
+    }
+
+    /// Mails the daily digest once its time of day has passed.
+    fn send_digest_if_due(&mut self) {
+        if Instant::now() < self.next_digest_check {
+            return;
+        }
+        self.next_digest_check = Instant::now() + DIGEST_CHECK_INTERVAL;
+        match claim_digest(&self.team_name, chrono::Local::now().naive_local()) {
+            Ok(Some(team)) => {
+                spawn_in_span(move || send_digest(&team));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                // A bad digest_at stays bad until the config changes; don't
+                // repeat it every minute
+                self.next_digest_check = Instant::now() + DIGEST_RETRY_INTERVAL;
+                warn!("Daily digest: {:#}", e);
+            }
+        }
+    }
+
+    /// Holds queued events while the team is over its budget, telling the
+    /// team's chat when the hold starts.
+    fn over_budget(&mut self) -> bool {
+        let team = match config::load().and_then(|cfg| {
+            config::resolve_team(&cfg, Some(&self.team_name)).cloned()
+        }) {
+            Ok(team) => team,
+            Err(e) => {
+                warn!("Could not check the budget: {:#}", e);
+                return false;
+            }
+        };
+        let overrun = match budget::check(&team) {
+            Ok(Some(overrun)) => overrun,
+            Ok(None) => return false,
+            Err(e) => {
+                warn!("Could not check the budget: {:#}", e);
+                return false;
+            }
+        };
+        let reason = format!(
+            "Over budget: the team has {}, holding queued events",
+            overrun
+        );
+        if self.held.as_deref() != Some(reason.as_str()) {
+            let notification = budget::notification(&team, &overrun);
+            spawn_in_span(move || send_notification(&team, &notification));
+        }
+        self.hold(reason);
+        true
+    }
+
+    /// Logs why queued events are held, once per reason.
+    fn hold(&mut self, reason: String) {
+        if self.held.as_deref() != Some(reason.as_str()) {
+            info!("{}", reason);
+            self.held = Some(reason);
+        }
+    }
+
+    /// Lets the in-flight run finish for up to `timeout`, then ends its
+    /// members. Returns early if a signal asks to terminate.
+    fn drain(&mut self, timeout: Duration) {
+        let Some(worker) = self.worker.as_ref().filter(|w| !w.is_finished()) else {
+            return;
+        };
+        info!(
+            "Draining: waiting up to {} for the in-flight run to finish",
+            timefmt::duration(timeout.as_secs())
+        );
+        let deadline = Instant::now() + timeout;
+        while !worker.is_finished() && !self.terminate.load(Ordering::SeqCst) {
+            if Instant::now() >= deadline {
+                warn!("Drain timed out, stopping the in-flight run's members");
+                self.terminate.store(true, Ordering::SeqCst);
+                break;
+            }
+            mark_progress();
+            thread::sleep(Duration::from_millis(200));
+        }
+    }
+
+    /// Waits for the in-flight run, if any (used on shutdown).
+    fn finish(&mut self) {
+        self.reap();
+    }
+
+    /// Joins a finished (or finishing) run. Events of a run cut short by
+    /// shutdown stay in flight and are re-queued on the next start.
+    fn reap(&mut self) {
+        let Some(worker) = self.worker.take() else {
+            return;
+        };
+        let completed = worker.join().unwrap_or(false);
+        if completed {
+            if let Err(e) = self.queue.complete() {
+                error!("{:#}", e);
+            }
+        }
+    }
+}
+
+/// Returns the team if its daily digest is due at `now` (local time), and
+/// records today as sent so the digest goes out once a day.
+fn claim_digest(team_name: &str, now: chrono::NaiveDateTime) -> Result<Option<TeamEntry>> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, Some(team_name))?;
+    if !notify::wanted(team, Kind::Digest) {
+        return Ok(None);
+    }
+    let due = match team.credentials.smtp {
+        Some(ref smtp) => smtp.digest_time()?,
+        None => DEFAULT_DIGEST_TIME,
+    };
+    if now.time() < due {
+        return Ok(None);
+    }
+    let path = digest_path(team_name)?;
+    let today = now.date().to_string();
+    if fs::read_to_string(&path).is_ok_and(|last| last.trim() == today) {
+        return Ok(None);
+    }
+    fs::write(&path, &today).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(Some(team.clone()))
 }
 
-/// Runs the daemon in poll mode using gh API.
-fn run_poll_mode(
-    team_name: &str,
-    interval: u64,
-    shutdown: &Arc<AtomicBool>,
-) -> Result<()> {
-    daemon_log(team_name, "INFO", &format!("Poll mode started, interval: {}s", interval));
-
-    // Load poll state
-    let poll_state_file = poll_state_path(team_name)?;
-    let mut poll_state = load_poll_state(&poll_state_file);
+/// Mails the daily digest, logging the outcome.
+fn send_digest(team: &TeamEntry) {
+    match digest(team).and_then(|digest| notify::send(team, &digest)) {
+        Ok(_) => info!("Sent the daily digest"),
+        Err(e) => warn!("Failed to send the daily digest: {:#}", e),
+    }
+}
 
-    loop {
-        if shutdown.load(Ordering::SeqCst) {
-            daemon_log(team_name, "INFO", "Received shutdown signal, stopping poll loop");
-            break;
+/// The daily digest: members, the daemon, and each member's run history.
+fn digest(team: &TeamEntry) -> Result<Notification> {
+    let mut lines = vec!["Members:".to_string()];
+    let members = status::member_lines(team)?;
+    if members.is_empty() {
+        lines.push("  No members hired.".to_string());
+    }
+    lines.extend(members.iter().map(|m| format!("  {}", m)));
+    lines.push(String::new());
+    lines.push(summary(&team.name)?);
+
+    let runtime_state = state::load()?;
+    let prefix = format!("{}/", team.name);
+    let mut history: Vec<(&str, &state::MemberStats)> = runtime_state
+        .stats
+        .iter()
+        .filter_map(|(key, stats)| Some((key.strip_prefix(&prefix)?, stats)))
+        .collect();
+    history.sort_by_key(|(member, _)| *member);
+    if !history.is_empty() {
+        lines.push(String::new());
+        lines.push("Run history:".to_string());
+    }
+    for (member, stats) in history {
+        let mut line = format!("  {}: {} run(s), {} crash(es)", member, stats.runs, stats.crashes);
+        if let Some(ref at) = stats.last_crash_at {
+            line.push_str(&format!(", last crash {}", at));
         }
+        lines.push(line);
+    }
 
-        // Resolve GitHub repo for this team
-        let github_repo = match resolve_github_repo(team_name) {
-            Ok(repo) => repo,
-            Err(e) => {
-                daemon_log(team_name, "ERROR", &format!("Failed to resolve GitHub repo: {}", e));
-                sleep_interruptible(interval, shutdown);
-                continue;
-            }
-        };
-
-        // Poll for events
-        match poll_github_events(&github_repo, &poll_state) {
-            Ok(events) => {
-                let relevant_count = events
-                    .iter()
-                    .filter(|e| is_relevant_event(&e.event_type))
-                    .count();
-
-                if relevant_count > 0 {
-                    daemon_log(
-                        team_name,
-                        "INFO",
-                        &format!("Found {} relevant event(s)", relevant_count),
-                    );
-                    // Launch members one-shot (blocks until all exit)
-                    handle_member_launch(team_name, shutdown);
-                }
+    let date = chrono::Local::now().format("%Y-%m-%d");
+    Ok(Notification::new(
+        &team.name,
+        Kind::Digest,
+        &format!("Daily digest {}", date),
+        lines.join("\n"),
+    ))
+}
 
-                // Update poll state with latest event ID
-                if let Some(latest) = events.first() {
-                    poll_state.last_event_id = Some(latest.id.clone());
-                }
-                poll_state.last_poll_at = Some(chrono::Utc::now().to_rfc3339());
-                save_poll_state(&poll_state_file, &poll_state);
-            }
-            Err(e) => {
-                daemon_log(
-                    team_name,
-                    "ERROR",
-                    &format!("Failed to poll GitHub events: {}", e),
-                );
-            }
-        }
+/// Lines of a member's log included in crash mails.
+const CRASH_LOG_LINES: usize = 20;
 
-        sleep_interruptible(interval, shutdown);
+/// Mails the crashes and launch errors of one run, logging the outcome.
+fn notify_problems(team: &TeamEntry, crashed: &[(String, String)], errors: &[String]) {
+    let mut notifications = Vec::new();
+    if !crashed.is_empty() && notify::wanted(team, Kind::Crash) {
+        let summary = match crashed {
+            [(member, _)] => format!("{} crashed", member),
+            _ => format!("{} members crashed", crashed.len()),
+        };
+        let body: Vec<String> = crashed
+            .iter()
+            .map(|(member, status)| crash_report(&team.name, member, status))
+            .collect();
+        let body = body.join("\n\n");
+        notifications.push(Notification::new(&team.name, Kind::Crash, &summary, body));
+    }
+    if !errors.is_empty() {
+        let summary = format!("{} member(s) failed to launch or become ready", errors.len());
+        let body = errors.join("\n");
+        notifications.push(Notification::new(&team.name, Kind::Error, &summary, body));
     }
+    for notification in notifications {
+        send_notification(team, &notification);
+    }
+}
 
-    daemon_log(team_name, "INFO", "Daemon stopped");
-    Ok(())
+/// What a failed run launched and how each member ended.
+fn run_report(record: &RunRecord) -> String {
+    let mut lines = vec![format!(
+        "Run for {} started {}.",
+        record.events.join(", "),
+        timefmt::timestamp(record.started_at)
+    )];
+    for member in &record.members {
+        lines.push(format!(
+            "  {}: {}{}",
+            member.name,
+            member.status,
+            if member.ok { "" } else { " (failed)" }
+        ));
+    }
+    for error in &record.errors {
+        lines.push(format!("  error: {}", error));
+    }
+    lines.join("\n")
 }
 
-/// Launches members one-shot with logging.
-fn handle_member_launch(team_name: &str, shutdown: &Arc<AtomicBool>) {
-    match launch_members_oneshot(team_name, shutdown) {
-        Ok(count) => {
-            daemon_log(
-                team_name,
-                "INFO",
-                &format!("One-shot run complete: {} member(s) processed", count),
-            );
-        }
+/// Sends a notification of `kind` to the team's backends that want it.
+fn notify_team(team_name: &str, kind: Kind, summary: &str, body: String) {
+    let team = match config::load().and_then(|cfg| {
+        config::resolve_team(&cfg, Some(team_name)).cloned()
+    }) {
+        Ok(team) => team,
         Err(e) => {
-            daemon_log(
-                team_name,
-                "ERROR",
-                &format!("Member launch failed: {}", e),
-            );
+            warn!("Failed to send {} notification: {:#}", kind, e);
+            return;
         }
+    };
+    if notify::wanted(&team, kind) {
+        send_notification(&team, &Notification::new(team_name, kind, summary, body));
     }
 }
 
-/// Sleeps for the given duration, checking the shutdown flag every second.
-fn sleep_interruptible(seconds: u64, shutdown: &Arc<AtomicBool>) {
-    for _ in 0..seconds {
-        if shutdown.load(Ordering::SeqCst) {
-            break;
+/// Sends `notification` to the team's backends, logging the outcome.
+fn send_notification(team: &TeamEntry, notification: &Notification) {
+    match notify::send(team, notification) {
+        Ok(true) => info!("Sent {} notification: {}", notification.kind, notification.subject),
+        Ok(false) => {}
+        Err(e) => warn!("Failed to send {} notification: {:#}", notification.kind, e),
+    }
+}
+
+/// A crashed member's exit status and the end of its log.
+fn crash_report(team_name: &str, member: &str, status: &str) -> String {
+    let mut report = format!("{} exited ({}).", member, status);
+    if let Ok(path) = member_log_path(team_name, member) {
+        let log = fs::read_to_string(&path).unwrap_or_default();
+        let lines: Vec<&str> = log.lines().collect();
+        let tail = &lines[lines.len().saturating_sub(CRASH_LOG_LINES)..];
+        report.push_str(&format!("\nLog: {}", path.display()));
+        if !tail.is_empty() {
+            report.push_str(&format!("\n\n{}", tail.join("\n")));
         }
-        thread::sleep(Duration::from_secs(1));
     }
+    report
 }
 
 /// Waits for a child process to exit, checking the shutdown flag every 500ms.
 ///
-/// If the shutdown flag is set while the child is still running, sends SIGTERM
-/// to the child, waits up to 5 seconds, then escalates to SIGKILL.
+/// If the shutdown flag is set while the child is still running, calls
+/// `before_terminate`, sends SIGTERM to the child's process group, waits up
+/// to 5 seconds, then escalates to SIGKILL.
 ///
 /// Returns `Some(status)` if the child exited normally, or `None` if it was
 /// terminated due to shutdown.
 fn wait_interruptible(
     child: &mut std::process::Child,
     shutdown: &Arc<AtomicBool>,
+    before_terminate: impl FnOnce(),
 ) -> Option<std::process::ExitStatus> {
     loop {
         match child.try_wait() {
@@ -572,11 +2912,10 @@ fn wait_interruptible(
             Ok(None) => {
                 // Child still running — check shutdown flag
                 if shutdown.load(Ordering::SeqCst) {
-                    // Graceful: SIGTERM first
+                    before_terminate();
+                    // Graceful: SIGTERM first, to Ralph and what it spawned
                     let pid = child.id();
-                    unsafe {
-                        libc::kill(pid as i32, libc::SIGTERM);
-                    }
+                    procutil::terminate_group(pid);
                     // Wait up to 5 seconds for child to exit
                     for _ in 0..10 {
                         thread::sleep(Duration::from_millis(500));
@@ -585,12 +2924,12 @@ fn wait_interruptible(
                         }
                     }
                     // Escalate to SIGKILL
-                    unsafe {
-                        libc::kill(pid as i32, libc::SIGKILL);
-                    }
+                    procutil::kill_group(pid);
                     let _ = child.wait();
                     return None;
                 }
+                // A member still running counts as progress for the heartbeat
+                mark_progress();
                 thread::sleep(Duration::from_millis(500));
             }
             Err(_) => return None,
@@ -600,9 +2939,27 @@ fn wait_interruptible(
 
 // ── One-shot member launch ──────────────────────────────────────────
 
-/// Launches all team members one-shot and waits for them to exit.
-/// Returns the number of members launched.
-fn launch_members_oneshot(team_name: &str, shutdown: &Arc<AtomicBool>) -> Result<u32> {
+/// A member launched by a one-shot run.
+struct Launched {
+    name: String,
+    ws: PathBuf,
+    child: std::process::Child,
+    started_at: String,
+}
+
+/// Launches members one-shot and waits for them, appending each launched
+/// member to `runs` and each member that could not be launched to `errors`.
+///
+/// Members launch in `role_priority` order. With `max_concurrent_members`
+/// set, the rest of the queue waits until a running member exits.
+fn launch_members_oneshot(
+    team_name: &str,
+    roles: Option<&[String]>,
+    settings: &DaemonSettings,
+    shutdown: &Arc<AtomicBool>,
+    runs: &mut Vec<MemberRun>,
+    errors: &mut Vec<String>,
+) -> Result<()> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, Some(team_name))?;
     let team_repo = team.path.join("team");
@@ -610,175 +2967,318 @@ fn launch_members_oneshot(team_name: &str, shutdown: &Arc<AtomicBool>) -> Result
     // Discover members
     let members_dir = team_repo.join("team");
     if !members_dir.is_dir() {
-        daemon_log(team_name, "WARN", "No members directory found");
-        return Ok(0);
+        warn!("No members directory found");
+        return Ok(());
     }
 
-    let member_dirs = list_member_dirs(&members_dir)?;
+    let member_dirs = members::list_names(&members_dir)?;
     if member_dirs.is_empty() {
-        daemon_log(team_name, "WARN", "No members found");
-        return Ok(0);
+        warn!("No members found");
+        return Ok(());
     }
 
-    // Get credentials
-    let gh_token = team
-        .credentials
-        .gh_token
-        .as_deref()
-        .unwrap_or("");
-    let telegram_token = team.credentials.telegram_bot_token.as_deref();
+    let mut queue: Vec<(String, String)> = Vec::new();
+    for member_dir_name in member_dirs {
+        let role = members::read_role(&members_dir, &member_dir_name);
+        if let Some(roles) = roles {
+            if !roles.contains(&role) {
+                debug!("{}: role '{}' not routed for this event, skipping", member_dir_name, role);
+                continue;
+            }
+        }
+        queue.push((member_dir_name, role));
+    }
+    // Stable, so members of the same rank keep their name order
+    queue.sort_by_key(|(_, role)| settings.role_rank(role));
 
     let workzone = &cfg.workzone;
     let team_ws_base = workzone.join(team_name);
+    let quota = settings.disk_quota.as_ref();
+
+    let mut running: Vec<Launched> = Vec::new();
+    let mut crashed: Vec<(String, String)> = Vec::new();
+    // Read once, by the first member actually launched
+    let board = OnceCell::new();
+
+    for (position, (member_dir_name, role)) in queue.iter().enumerate() {
+        let mut waited = None;
+        if let Some(max) = settings.max_concurrent_members.filter(|&max| running.len() >= max) {
+            let waiting: Vec<String> = queue[position..]
+                .iter()
+                .enumerate()
+                .map(|(i, (name, role))| format!("{}. {} ({})", i + 1, name, role))
+                .collect();
+            info!(
+                "{} of {} member slot(s) busy; waiting: {}",
+                running.len(),
+                max,
+                waiting.join(", ")
+            );
+            let since = Instant::now();
+            if !wait_for_slot(team_name, &mut running, max, shutdown, runs, &mut crashed) {
+                info!("Shutting down; {} queued member(s) not launched", waiting.len());
+                break;
+            }
+            waited = Some(since.elapsed());
+        }
 
-    let mut children: Vec<(String, std::process::Child)> = Vec::new();
-
-    for member_dir_name in &member_dirs {
-        let ws = find_workspace(&team_ws_base, member_dir_name);
+        let _member = info_span!("member", member = %member_dir_name).entered();
+        let ws = members::find_workspace(&team_ws_base, member_dir_name).map(|w| w.path);
         let ws = match ws {
             Some(ws) => ws,
             None => {
-                daemon_log(
-                    team_name,
-                    "WARN",
-                    &format!("{}: no workspace found, skipping", member_dir_name),
-                );
+                warn!("{}: no workspace found, skipping", member_dir_name);
                 continue;
             }
         };
 
-        match launch_ralph_oneshot(&ws, gh_token, telegram_token, team_name, member_dir_name) {
-            Ok(child) => {
-                daemon_log(
-                    team_name,
-                    "INFO",
-                    &format!("{}: launched (PID {})", member_dir_name, child.id()),
-                );
-                children.push((member_dir_name.clone(), child));
+        if let Some(quota) = quota {
+            if !within_disk_quota(member_dir_name, &ws, quota) {
+                continue;
             }
+        }
+
+        let auth = match github_app::resolve_member_auth(team, member_dir_name) {
+            Ok(auth) => auth.unwrap_or_else(|| MemberAuth::Token(String::new())),
             Err(e) => {
-                daemon_log(
-                    team_name,
-                    "ERROR",
-                    &format!("{}: failed to launch — {}", member_dir_name, e),
-                );
+                let error = format!("{}: GitHub auth failed — {:#}", member_dir_name, e);
+                error!("{}", error);
+                errors.push(error);
+                continue;
+            }
+        };
+
+        let env = match launch_env::read_overrides(&members_dir.join(member_dir_name))
+            .and_then(|o| MemberEnv::new(&auth, &team.credentials).with_overrides(&o, &team.credentials))
+        {
+            Ok(env) => env,
+            Err(e) => {
+                let error = format!("{}: {:#}", member_dir_name, e);
+                error!("{}", error);
+                errors.push(error);
+                continue;
+            }
+        };
+        let probe = match readiness::read(&members_dir.join(member_dir_name)) {
+            Ok(probe) => probe,
+            Err(e) => {
+                let error = format!("{}: {:#}", member_dir_name, e);
+                error!("{}", error);
+                errors.push(error);
+                continue;
+            }
+        };
+        if let Some(board) = board.get_or_init(|| fetch_board(team)) {
+            if let Err(e) = board.snapshot_for(member_dir_name, role).write(&ws) {
+                warn!("{}: {:#}", member_dir_name, e);
+            }
+        }
+        let retention = cfg.logs.clone().unwrap_or_default();
+        let launch = readiness::Launch::new(&ws, &member_log_path(team_name, member_dir_name)?);
+        match launch_ralph_oneshot(&ws, &env, team_name, member_dir_name, &retention) {
+            Ok(mut child) => {
+                let started_at = chrono::Utc::now().to_rfc3339();
+                match waited {
+                    Some(waited) => info!(
+                        "{}: launched (PID {}) after waiting {} for a slot",
+                        member_dir_name,
+                        child.id(),
+                        timefmt::duration(waited.as_secs())
+                    ),
+                    None => info!("{}: launched (PID {})", member_dir_name, child.id()),
+                }
+                info!("{}: env {}", member_dir_name, env.audit_line());
+                if let Some(probe) = probe {
+                    let alive = || {
+                        mark_progress();
+                        !shutdown.load(Ordering::SeqCst) && matches!(child.try_wait(), Ok(None))
+                    };
+                    match probe.wait(&launch, alive) {
+                        Probe::Ready => info!(
+                            "{}: ready after {}",
+                            member_dir_name,
+                            timefmt::duration(launch.elapsed().as_secs())
+                        ),
+                        // Recorded when the exit is waited on below
+                        Probe::Exited => {}
+                        Probe::TimedOut(pending) => {
+                            let error = format!(
+                                "{}: unhealthy, not ready after {}s ({}); left running",
+                                member_dir_name, probe.timeout_secs, pending
+                            );
+                            warn!("{}", error);
+                            errors.push(error);
+                        }
+                    }
+                }
+                running.push(Launched {
+                    name: member_dir_name.clone(),
+                    ws,
+                    child,
+                    started_at,
+                });
+            }
+            Err(e) => {
+                let error = format!("{}: failed to launch — {}", member_dir_name, e);
+                error!("{}", error);
+                errors.push(error);
             }
         }
     }
 
-    let launched = children.len() as u32;
-
     // Wait for all members to exit (interruptible by shutdown signal)
-    for (name, mut child) in children {
-        match wait_interruptible(&mut child, shutdown) {
-            Some(status) => {
-                daemon_log(
-                    team_name,
-                    "INFO",
-                    &format!("{}: exited ({})", name, status),
-                );
+    for mut launched in running {
+        let _member = info_span!("member", member = %launched.name).entered();
+        let run_hook = || {
+            if let Some(report) = pre_stop::run_for_member(team, &launched.name, &launched.ws) {
+                info!("{}: {}", launched.name, report);
             }
-            None => {
-                daemon_log(
-                    team_name,
-                    "INFO",
-                    &format!("{}: terminated due to shutdown", name),
-                );
+        };
+        let status = wait_interruptible(&mut launched.child, shutdown, run_hook);
+        record_member_exit(team_name, &launched, status, runs, &mut crashed);
+    }
+    notify_problems(team, &crashed, errors);
+
+    Ok(())
+}
+
+/// Waits until fewer than `max` launched members are still running,
+/// recording each one that exits. Returns false if shutdown came first.
+fn wait_for_slot(
+    team_name: &str,
+    running: &mut Vec<Launched>,
+    max: usize,
+    shutdown: &Arc<AtomicBool>,
+    runs: &mut Vec<MemberRun>,
+    crashed: &mut Vec<(String, String)>,
+) -> bool {
+    loop {
+        let mut i = 0;
+        while i < running.len() {
+            match running[i].child.try_wait() {
+                Ok(None) => i += 1,
+                result => {
+                    let launched = running.remove(i);
+                    record_member_exit(team_name, &launched, result.ok().flatten(), runs, crashed);
+                }
             }
         }
+        if running.len() < max {
+            return true;
+        }
+        if shutdown.load(Ordering::SeqCst) {
+            return false;
+        }
+        // A member still running counts as progress for the heartbeat
+        mark_progress();
+        thread::sleep(Duration::from_millis(500));
     }
-
-    Ok(launched)
 }
 
-/// Lists member directory names under `team/team/`.
-fn list_member_dirs(team_dir: &Path) -> Result<Vec<String>> {
-    let mut dirs = Vec::new();
-    for entry in fs::read_dir(team_dir)? {
-        let entry = entry?;
-        if !entry.file_type()?.is_dir() {
-            continue;
+/// Records how a launched member's run ended; `status` is `None` when it
+/// was terminated for shutdown.
+fn record_member_exit(
+    team_name: &str,
+    launched: &Launched,
+    status: Option<std::process::ExitStatus>,
+    runs: &mut Vec<MemberRun>,
+    crashed: &mut Vec<(String, String)>,
+) {
+    let name = &launched.name;
+    let (outcome, status) = match status {
+        Some(status) => {
+            info!("{}: exited ({})", name, status);
+            if status.success() {
+                (RunOutcome::Stopped, status.to_string())
+            } else {
+                crashed.push((name.clone(), status.to_string()));
+                (RunOutcome::Crashed, status.to_string())
+            }
         }
-        let name = entry.file_name().to_string_lossy().to_string();
-        if name.starts_with('.') {
-            continue;
+        None => {
+            info!("{}: terminated due to shutdown", name);
+            (RunOutcome::Stopped, "terminated".to_string())
         }
-        dirs.push(name);
+    };
+    let left = procutil::sweep_group(launched.child.id(), Duration::from_secs(5));
+    if !left.is_empty() {
+        warn!(
+            "{}: {} process(es) outlived ralph and were terminated: {}",
+            name,
+            left.len(),
+            left.iter().map(u32::to_string).collect::<Vec<_>>().join(", ")
+        );
+    }
+    runs.push(MemberRun {
+        name: name.clone(),
+        status,
+        ok: outcome == RunOutcome::Stopped,
+    });
+    let key = format!("{}/{}", team_name, name);
+    if let Err(e) = state::update(|s| {
+        s.record_run(&key, &launched.started_at, chrono::Utc::now(), outcome);
+        Ok(())
+    }) {
+        warn!("{}: failed to record run stats — {:#}", name, e);
     }
-    dirs.sort();
-    Ok(dirs)
 }
 
-/// Finds the workspace path for a member (same logic as start.rs).
-fn find_workspace(team_ws_base: &Path, member_dir_name: &str) -> Option<PathBuf> {
-    let member_ws = team_ws_base.join(member_dir_name);
-    if !member_ws.is_dir() {
+/// Reads the team's board for the snapshots handed to launched members.
+/// On failure members keep their previous snapshot.
+fn fetch_board(team: &TeamEntry) -> Option<Board> {
+    if team.github_repo.is_empty() {
         return None;
     }
-
-    // Check for project subdirectories
-    if let Ok(entries) = fs::read_dir(&member_ws) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                let name = entry.file_name().to_string_lossy().to_string();
-                if !name.starts_with('.') && path.join(".botminter").is_dir() {
-                    return Some(path);
-                }
-            }
+    let board = github_app::TeamTokenSource::default()
+        .token(team)
+        .and_then(|token| Board::fetch(team, token.as_deref()));
+    match board {
+        Ok(board) => Some(board),
+        Err(e) => {
+            warn!("Could not read the board; members keep their last snapshot: {:#}", e);
+            None
         }
     }
+}
 
-    // No-project mode
-    if member_ws.join(".botminter").is_dir() {
-        return Some(member_ws);
+/// Checks a workspace against the disk quota, logging any overage. Returns
+/// false if the member must not be launched.
+fn within_disk_quota(member: &str, ws: &Path, quota: &DiskQuota) -> bool {
+    match quota.check(ws) {
+        Ok(None) => true,
+        Ok(Some(over)) if quota.action == disk_quota::Action::Block => {
+            warn!("{}: over disk quota ({}), not launching", member, over.describe());
+            false
+        }
+        Ok(Some(over)) => {
+            warn!("{}: over disk quota ({})", member, over.describe());
+            true
+        }
+        Err(e) => {
+            error!("{:#}", e);
+            true
+        }
     }
-
-    None
 }
 
 /// Launches ralph one-shot (blocking child — we hold the Child handle to wait on it).
 fn launch_ralph_oneshot(
     workspace: &Path,
-    gh_token: &str,
-    telegram_token: Option<&str>,
+    env: &MemberEnv,
     team_name: &str,
     member_name: &str,
+    retention: &logging::Retention,
 ) -> Result<std::process::Child> {
     let mut cmd = Command::new("ralph");
-    cmd.args(["run", "-p", "PROMPT.md"])
-        .current_dir(workspace)
-        .env("GH_TOKEN", gh_token)
-        .env_remove("CLAUDECODE");
+    cmd.args(["run", "-p", "PROMPT.md"]).current_dir(workspace);
+    env.apply(&mut cmd);
 
-    if let Some(token) = telegram_token {
-        cmd.env("RALPH_TELEGRAM_BOT_TOKEN", token);
-    }
-
-    // One-shot: null stdin
+    // One-shot: null stdin, own session so shutdown reaches Ralph's children
+    procutil::detach(&mut cmd);
     cmd.stdin(std::process::Stdio::null());
 
-    // Redirect stdout/stderr to per-member log file
-    let log_file_path = member_log_path(team_name, member_name)?;
-    daemon_log(
-        team_name,
-        "INFO",
-        &format!("{}: log file at {}", member_name, log_file_path.display()),
-    );
-    let log_file = fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&log_file_path)
-        .with_context(|| {
-            format!(
-                "Failed to open member log file at {}",
-                log_file_path.display()
-            )
-        })?;
-    let log_file_err = log_file
-        .try_clone()
-        .context("Failed to clone member log file handle")?;
-    cmd.stdout(log_file).stderr(log_file_err);
+    // Redirect stdout/stderr to per-member log file, rotated between runs
+    redirect_to_member_log(&mut cmd, team_name, member_name, retention)?;
 
     let child = cmd.spawn().with_context(|| {
         format!("Failed to spawn ralph in {}", workspace.display())
@@ -789,75 +3289,180 @@ fn launch_ralph_oneshot(
 
 // ── GitHub event types ──────────────────────────────────────────────
 
-/// A GitHub event from the events API.
+/// A GitHub event from the events API, trimmed to what filters look at.
 #[derive(Debug, Deserialize)]
 pub struct GitHubEvent {
     pub id: String,
     #[serde(rename = "type")]
     pub event_type: String,
+    #[serde(default)]
+    pub actor: Option<String>,
+    #[serde(default)]
+    pub repo: Option<String>,
+    #[serde(default)]
+    pub action: Option<String>,
+    /// Branch the event concerns, as [`trigger_filter::branch_of`] picks it.
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Labels of the issue or pull request the event concerns.
+    #[serde(default)]
+    pub labels: Vec<String>,
 }
 
-/// Checks if an event type is relevant for triggering member launches.
-pub fn is_relevant_event(event_type: &str) -> bool {
-    // The events API uses PascalCase type names, webhook headers use snake_case
-    let normalized = event_type.to_lowercase();
-    RELEVANT_EVENTS.iter().any(|&re| {
-        normalized == re
-            || normalized == re.replace('_', "")
-            // Events API format: IssuesEvent, IssueCommentEvent, PullRequestEvent
-            || normalized == format!("{}event", re.replace('_', ""))
-    })
+impl GitHubEvent {
+    /// Normalizes the event for filter expressions.
+    pub fn to_trigger_event(&self) -> TriggerEvent {
+        TriggerEvent {
+            event: trigger_filter::normalize_event_type(&self.event_type),
+            action: self.action.clone().unwrap_or_default(),
+            author: self.actor.clone().unwrap_or_default(),
+            repo: self.repo.clone().unwrap_or_default(),
+            branch: self
+                .branch
+                .as_deref()
+                .unwrap_or_default()
+                .trim_start_matches("refs/heads/")
+                .to_string(),
+            labels: self.labels.clone(),
+        }
+    }
+}
+
+/// Checks if an event passes the team's event filters: its type is listed
+/// (or a default), it satisfies a `filters.triggers` entry for its type when
+/// there is one, and it satisfies `filters.expr`.
+pub fn is_relevant_event(settings: &DaemonSettings, event: &TriggerEvent) -> bool {
+    settings.triggers(event)
 }
 
-/// Polls the GitHub events API for new events.
+/// What one poll of a repo's events returned.
+#[derive(Debug, Default)]
+struct Polled {
+    /// Events newer than the last one seen, newest first.
+    events: Vec<GitHubEvent>,
+    /// Set when GitHub refused the request for a rate limit.
+    limited: Option<Limited>,
+    /// The request budget GitHub reported.
+    rate_limit: Option<RateLimit>,
+    /// ETag of the first page, to send with the next poll.
+    etag: Option<String>,
+    /// GitHub answered 304: nothing happened since the ETag sent.
+    not_modified: bool,
+}
+
+/// Polls the GitHub events API for events newer than `last_event_id`.
+/// `etag` is the one recorded by the previous poll, if any. `throttled`
+/// counts the secondary rate limits already hit in a row.
 fn poll_github_events(
     github_repo: &str,
-    poll_state: &PollState,
-) -> Result<Vec<GitHubEvent>> {
-    let output = Command::new("gh")
-        .args([
-            "api",
-            &format!("repos/{}/events", github_repo),
-            "--paginate",
-            "--jq",
-            "[.[] | {id: .id, type: .type}]",
-        ])
-        .output()
-        .context("Failed to run gh api command")?;
+    last_event_id: Option<&str>,
+    etag: Option<&str>,
+    token: Option<&str>,
+    throttled: u32,
+) -> Result<Polled> {
+    debug!(
+        "Polling {} for events after {}",
+        github_repo,
+        last_event_id.unwrap_or("(none)")
+    );
+    let mut cmd = Command::new("gh");
+    cmd.args([
+        "api",
+        &format!("repos/{}/events", github_repo),
+        "--include",
+        "--paginate",
+        "--jq",
+        "[.[] | {id: .id, type: .type, actor: .actor.login, repo: .repo.name, \
+         action: .payload.action, \
+         branch: (if .payload.ref_type == \"tag\" or (.payload.ref // \"\" | startswith(\"refs/tags/\")) \
+           then null \
+           else .payload.ref // .payload.pull_request.base.ref // .payload.release.target_commitish end), \
+         labels: [((.payload.issue // .payload.pull_request // {}).labels // [])[] | .name]}]",
+    ]);
+    if let Some(etag) = etag {
+        cmd.args(["-H", &format!("If-None-Match: {}", etag)]);
+    }
+    if let Some(token) = token {
+        cmd.env("GH_TOKEN", token);
+    }
+    let output = cmd.output().context("Failed to run gh api command")?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let responses = rate_limit::parse_included(&stdout);
+    let mut polled = Polled {
+        rate_limit: responses.iter().rev().find_map(RateLimit::from_response),
+        etag: responses
+            .first()
+            .and_then(|r| r.header("etag"))
+            .map(str::to_string),
+        not_modified: responses.first().is_some_and(|r| r.status == 304),
+        ..Polled::default()
+    };
+    // gh exits non-zero on a 304, which only means there is nothing new
+    if polled.not_modified {
+        return Ok(polled);
+    }
+    let now = chrono::Utc::now();
+    polled.limited = responses
+        .iter()
+        .find_map(|r| rate_limit::limited(r, throttled, now));
+    if polled.limited.is_some() {
+        return Ok(polled);
+    }
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         bail!("gh api failed: {}", stderr.trim());
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    if stdout.trim().is_empty() {
-        return Ok(Vec::new());
+    // Without headers (a gh that ignores --include) stdout is the body
+    let bodies: Vec<&str> = if responses.is_empty() {
+        vec![&stdout]
+    } else {
+        responses.iter().map(|r| r.body.as_str()).collect()
+    };
+    for body in bodies {
+        if body.trim().is_empty() {
+            continue;
+        }
+        let page: Vec<GitHubEvent> =
+            serde_json::from_str(body).context("Failed to parse GitHub events response")?;
+        polled.events.extend(page);
     }
 
-    let events: Vec<GitHubEvent> = serde_json::from_str(&stdout)
-        .context("Failed to parse GitHub events response")?;
-
     // Filter to events newer than last_event_id
-    if let Some(ref last_id) = poll_state.last_event_id {
-        let new_events: Vec<GitHubEvent> = events
-            .into_iter()
-            .take_while(|e| &e.id != last_id)
-            .collect();
-        Ok(new_events)
-    } else {
-        Ok(events)
+    if let Some(last_id) = last_event_id {
+        let pos = polled.events.iter().position(|e| e.id == last_id);
+        polled.events.truncate(pos.unwrap_or(polled.events.len()));
     }
+    Ok(polled)
+}
+
+/// What poll mode watches: every repo of the team, team repo first.
+struct PollTarget {
+    team_repo: String,
+    repos: Vec<String>,
+    token: Option<String>,
 }
 
-/// Resolves the GitHub repo (owner/name) for a team.
-fn resolve_github_repo(team_name: &str) -> Result<String> {
+/// Resolves the GitHub repos (owner/name) for a team and the token to poll
+/// them with. Config is re-read each time so rotated tokens and repos added
+/// with `bm teams add-repo` are picked up.
+fn resolve_poll_target(
+    team_name: &str,
+    tokens: &mut github_app::TeamTokenSource,
+) -> Result<PollTarget> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, Some(team_name))?;
     if team.github_repo.is_empty() {
         bail!("No GitHub repo configured for team '{}'", team_name);
     }
-    Ok(team.github_repo.clone())
+    let token = tokens.token(team)?;
+    Ok(PollTarget {
+        team_repo: team.github_repo.clone(),
+        repos: team.repos(),
+        token,
+    })
 }
 
 // ── Webhook signature validation ────────────────────────────────────
@@ -900,56 +3505,29 @@ pub fn validate_webhook_signature(
 fn load_webhook_secret(team_name: &str) -> Option<String> {
     let cfg = config::load().ok()?;
     let team = config::resolve_team(&cfg, Some(team_name)).ok()?;
-    team.credentials.webhook_secret.clone()
+    team.credentials
+        .webhook_secret
+        .clone()
+        .filter(|secret| !secret.is_empty())
 }
 
 // ── Poll state persistence ──────────────────────────────────────────
 
-fn load_poll_state(path: &Path) -> PollState {
-    if !path.exists() {
-        return PollState::default();
-    }
-    match fs::read_to_string(path) {
-        Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
-        Err(_) => PollState::default(),
+fn load_poll_state(store: &dyn Store, team_name: &str) -> PollState {
+    match store.read(&store::poll_key(team_name)) {
+        Ok(Some(contents)) => serde_json::from_str(&contents).unwrap_or_default(),
+        _ => PollState::default(),
     }
 }
 
-fn save_poll_state(path: &Path, state: &PollState) {
+fn save_poll_state(store: &dyn Store, team_name: &str, state: &PollState) {
     if let Ok(contents) = serde_json::to_string_pretty(state) {
-        let _ = fs::write(path, contents);
+        let _ = store.update(&store::poll_key(team_name), &mut |_| Ok(contents.clone()));
     }
 }
 
 // ── Logging ─────────────────────────────────────────────────────────
 
-/// Writes a log entry to the daemon's log file.
-pub fn daemon_log(team_name: &str, level: &str, message: &str) {
-    let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
-    let line = format!("[{}] [{}] {}\n", timestamp, level, message);
-
-    // Also print to stdout/stderr (redirected to log file by the parent)
-    eprint!("{}", line);
-
-    // Try direct file write as backup (in case stderr isn't redirected)
-    if let Ok(log_file) = log_path(team_name) {
-        // Rotate if too large
-        if let Ok(meta) = fs::metadata(&log_file) {
-            if meta.len() > MAX_LOG_SIZE {
-                let rotated = log_file.with_extension("log.old");
-                let _ = fs::rename(&log_file, rotated);
-            }
-        }
-        if let Ok(mut f) = fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_file)
-        {
-            let _ = f.write_all(line.as_bytes());
-        }
-    }
-}
-
 /// Reads the schema version from the team's botminter.yml.
 fn read_team_schema(team_repo: &Path) -> Result<String> {
     let manifest_path = team_repo.join("botminter.yml");
@@ -961,26 +3539,17 @@ fn read_team_schema(team_repo: &Path) -> Result<String> {
     }
     let contents = fs::read_to_string(&manifest_path)
         .context("Failed to read team botminter.yml")?;
-    let val: serde_yml::Value =
-        serde_yml::from_str(&contents).context("Failed to parse team botminter.yml")?;
+    let val: serde_yml::Value = parse::yaml(&manifest_path, &contents)?;
     Ok(val["schema_version"]
         .as_str()
         .unwrap_or("")
         .to_string())
 }
 
-/// Formats an ISO 8601 timestamp for display.
-fn format_timestamp(ts: &str) -> String {
-    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(ts) {
-        dt.format("%Y-%m-%d %H:%M:%S UTC").to_string()
-    } else {
-        ts.to_string()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::store::FileStore;
 
     #[test]
     fn daemon_config_round_trip() {
@@ -994,6 +3563,8 @@ mod tests {
             interval_secs: 60,
             pid: 12345,
             started_at: "2026-02-21T10:00:00Z".to_string(),
+            listener: Listener::default(),
+            tunnel: None,
         };
 
         let contents = serde_json::to_string_pretty(&cfg).unwrap();
@@ -1009,6 +3580,120 @@ mod tests {
         assert_eq!(loaded.pid, 12345);
     }
 
+    #[test]
+    fn daemon_config_without_listener_uses_defaults() {
+        // Written before the listener was configurable
+        let loaded: DaemonConfig = serde_json::from_str(
+            r#"{"team":"t","mode":"webhook","port":8484,"interval_secs":60,"pid":1,"started_at":"x"}"#,
+        )
+        .unwrap();
+        assert_eq!(loaded.listener, Listener::default());
+        assert_eq!(loaded.listener.url(8484), "http://0.0.0.0:8484/webhook");
+
+        let tls = Listener {
+            bind: "::1".to_string(),
+            path: "/hooks/github".to_string(),
+            tls: Some(TlsFiles {
+                cert: PathBuf::from("/c.pem"),
+                key: PathBuf::from("/k.pem"),
+            }),
+            insecure_no_signature: false,
+        };
+        let json = serde_json::to_value(&tls).unwrap();
+        assert_eq!(json["tls"]["cert"], "/c.pem");
+        assert!(json.get("insecure_no_signature").is_none());
+        assert_eq!(tls.url(9000), "https://[::1]:9000/hooks/github");
+    }
+
+    #[test]
+    fn tunnel_target_is_reachable_locally() {
+        // Tunnels can't dial a wildcard address; they go through loopback
+        assert_eq!(
+            Listener::default().local_url(8484).unwrap(),
+            "http://127.0.0.1:8484"
+        );
+        let v6 = Listener {
+            bind: "::".to_string(),
+            ..Listener::default()
+        };
+        assert_eq!(v6.local_url(8484).unwrap(), "http://[::1]:8484");
+        let specific = Listener {
+            bind: "10.0.0.5".to_string(),
+            tls: Some(TlsFiles {
+                cert: PathBuf::from("/c.pem"),
+                key: PathBuf::from("/k.pem"),
+            }),
+            ..Listener::default()
+        };
+        assert_eq!(specific.local_url(443).unwrap(), "https://10.0.0.5:443");
+    }
+
+    #[test]
+    fn listener_flags_override_base() {
+        let base = Listener {
+            bind: "127.0.0.1".to_string(),
+            path: "/gh".to_string(),
+            tls: Some(TlsFiles {
+                cert: PathBuf::from("/c.pem"),
+                key: PathBuf::from("/k.pem"),
+            }),
+            insecure_no_signature: false,
+        };
+        // Nothing given: restart keeps the running listener
+        assert_eq!(ListenerFlags::default().apply(base.clone()), base);
+        let insecure = ListenerFlags {
+            insecure_no_signature: true,
+            ..Default::default()
+        };
+        let unsigned = insecure.apply(base.clone());
+        assert!(unsigned.insecure_no_signature);
+        // A later restart without the flag turns signature checks back on
+        assert!(!ListenerFlags::default()
+            .apply(unsigned)
+            .insecure_no_signature);
+
+        let flags = ListenerFlags {
+            path: Some("/other".to_string()),
+            tls_cert: Some(PathBuf::from("/new.pem")),
+            tls_key: Some(PathBuf::from("/new.key")),
+            ..Default::default()
+        };
+        let applied = flags.apply(base);
+        assert_eq!(applied.bind, "127.0.0.1");
+        assert_eq!(applied.path, "/other");
+        assert_eq!(applied.tls.unwrap().cert, PathBuf::from("/new.pem"));
+    }
+
+    #[test]
+    fn listener_validate_rejects_bad_values() {
+        let ok = Listener::default();
+        assert!(ok.validate().is_ok());
+
+        let host = Listener {
+            bind: "localhost".to_string(),
+            ..Listener::default()
+        };
+        assert!(host.validate().unwrap_err().to_string().contains("Invalid bind address"));
+
+        for path in ["webhook", "/a b", "/a?x=1"] {
+            let bad = Listener {
+                path: path.to_string(),
+                ..Listener::default()
+            };
+            assert!(bad.validate().is_err(), "{} should be rejected", path);
+        }
+
+        let missing = Listener {
+            tls: Some(TlsFiles {
+                cert: PathBuf::from("/nonexistent/cert.pem"),
+                key: PathBuf::from("/nonexistent/key.pem"),
+            }),
+            ..Listener::default()
+        };
+        let err = missing.validate().unwrap_err().to_string();
+        assert!(err.contains("Cannot read TLS certificate"), "{}", err);
+    }
+
     #[test]
     fn poll_state_round_trip() {
         let tmp = tempfile::tempdir().unwrap();
@@ -1017,6 +3702,8 @@ mod tests {
         let state = PollState {
             last_event_id: Some("12345678".to_string()),
             last_poll_at: Some("2026-02-21T10:00:00Z".to_string()),
+            extra_event_ids: BTreeMap::new(),
+            ..PollState::default()
         };
 
         let contents = serde_json::to_string_pretty(&state).unwrap();
@@ -1036,43 +3723,132 @@ mod tests {
     }
 
     #[test]
-    fn format_timestamp_rfc3339() {
-        let result = format_timestamp("2026-02-21T10:30:00+00:00");
-        assert_eq!(result, "2026-02-21 10:30:00 UTC");
+    fn simulate_verdict_explains_the_outcome() {
+        let mut settings = DaemonSettings::default();
+        settings.set("filters.expr", r#""kind/story" in labels"#).unwrap();
+        let mut routing = BTreeMap::new();
+        routing.insert("issues".to_string(), vec!["po".to_string()]);
+        settings.routing = Some(routing);
+
+        let mut event = TriggerEvent {
+            event: "issues".to_string(),
+            labels: vec!["kind/story".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            simulate_verdict(&settings, &event).unwrap(),
+            "triggers a run of roles: po"
+        );
+        event.event = "pull_request".to_string();
+        assert_eq!(
+            simulate_verdict(&settings, &event).unwrap(),
+            "triggers a run of every member"
+        );
+        event.labels.clear();
+        assert_eq!(
+            simulate_verdict(&settings, &event).unwrap(),
+            "ignored, the filter expression is false"
+        );
+        event.event = "push".to_string();
+        assert_eq!(
+            simulate_verdict(&settings, &event).unwrap(),
+            "ignored, 'push' is not a relevant event type"
+        );
     }
 
     #[test]
-    fn format_timestamp_unparseable() {
-        let result = format_timestamp("not-a-timestamp");
-        assert_eq!(result, "not-a-timestamp");
+    fn history_row_flags_failed_members() {
+        use chrono::TimeZone;
+        let started_at = chrono::Utc.with_ymd_and_hms(2026, 3, 1, 2, 15, 0).unwrap();
+        let member = |name: &str, status: &str, ok| MemberRun {
+            name: name.to_string(),
+            status: status.to_string(),
+            ok,
+        };
+        let mut run = RunRecord {
+            events: vec!["issues".to_string(), "push".to_string()],
+            started_at,
+            finished_at: started_at + chrono::Duration::seconds(95),
+            members: vec![
+                member("dev-01", "exit status: 0", true),
+                member("qe-01", "exit status: 1", false),
+            ],
+            errors: Vec::new(),
+            interrupted: false,
+        };
+        assert_eq!(
+            history_row(&run),
+            vec![
+                "2026-03-01 02:15:00 UTC",
+                "1m 35s",
+                "issues, push",
+                "dev-01, qe-01 (exit status: 1)",
+                "failed",
+            ]
+        );
+
+        run.members = vec![member("dev-01", "terminated", true)];
+        run.interrupted = true;
+        let row = history_row(&run);
+        assert_eq!(row[3], "dev-01 (terminated)");
+        assert_eq!(row[4], "interrupted");
     }
 
     // ── Event filtering tests ────────────────────────────────────────
 
+    fn relevant(event_type: &str) -> bool {
+        let event = TriggerEvent {
+            event: event_type.to_string(),
+            ..Default::default()
+        };
+        is_relevant_event(&DaemonSettings::default(), &event)
+    }
+
     #[test]
     fn relevant_event_types_webhook_format() {
-        assert!(is_relevant_event("issues"));
-        assert!(is_relevant_event("issue_comment"));
-        assert!(is_relevant_event("pull_request"));
+        assert!(relevant("issues"));
+        assert!(relevant("issue_comment"));
+        assert!(relevant("pull_request"));
     }
 
     #[test]
     fn relevant_event_types_api_format() {
         // The events API uses PascalCase like "IssuesEvent"
-        assert!(is_relevant_event("IssuesEvent"));
-        assert!(is_relevant_event("IssueCommentEvent"));
-        assert!(is_relevant_event("PullRequestEvent"));
+        assert!(relevant("IssuesEvent"));
+        assert!(relevant("IssueCommentEvent"));
+        assert!(relevant("PullRequestEvent"));
     }
 
     #[test]
     fn irrelevant_event_types() {
-        assert!(!is_relevant_event("push"));
-        assert!(!is_relevant_event("PushEvent"));
-        assert!(!is_relevant_event("create"));
-        assert!(!is_relevant_event("delete"));
-        assert!(!is_relevant_event("fork"));
-        assert!(!is_relevant_event("watch"));
-        assert!(!is_relevant_event("star"));
+        assert!(!relevant("push"));
+        assert!(!relevant("PushEvent"));
+        assert!(!relevant("create"));
+        assert!(!relevant("delete"));
+        assert!(!relevant("fork"));
+        assert!(!relevant("watch"));
+        assert!(!relevant("star"));
+    }
+
+    #[test]
+    fn relevant_events_follow_team_triggers() {
+        let settings: DaemonSettings = serde_yml::from_str(
+            "filters:\n  triggers:\n    - event: push\n      branches: [main]\n",
+        )
+        .unwrap();
+        let push = |branch: &str| TriggerEvent {
+            event: "push".to_string(),
+            branch: branch.to_string(),
+            ..Default::default()
+        };
+        assert!(is_relevant_event(&settings, &push("main")));
+        assert!(!is_relevant_event(&settings, &push("feature/x")));
+        assert!(!is_relevant_event(&DaemonSettings::default(), &push("main")));
+
+        let json = r#"{"id":"3","type":"PushEvent","actor":"bob","repo":"org/team",
+                       "action":null,"branch":"refs/heads/main","labels":[]}"#;
+        let event: GitHubEvent = serde_json::from_str(json).unwrap();
+        assert!(is_relevant_event(&settings, &event.to_trigger_event()));
     }
 
     // ── Webhook signature tests ──────────────────────────────────────
@@ -1124,15 +3900,17 @@ mod tests {
     #[test]
     fn poll_state_save_and_load() {
         let tmp = tempfile::tempdir().unwrap();
-        let path = tmp.path().join("poll-state.json");
+        let store = FileStore::new(tmp.path());
 
         let state = PollState {
             last_event_id: Some("99999".to_string()),
             last_poll_at: Some("2026-02-21T12:00:00Z".to_string()),
+            extra_event_ids: BTreeMap::new(),
+            ..PollState::default()
         };
 
-        save_poll_state(&path, &state);
-        let loaded = load_poll_state(&path);
+        save_poll_state(&store, "t", &state);
+        let loaded = load_poll_state(&store, "t");
 
         assert_eq!(loaded.last_event_id, Some("99999".to_string()));
         assert_eq!(loaded.last_poll_at, Some("2026-02-21T12:00:00Z".to_string()));
@@ -1141,9 +3919,8 @@ mod tests {
     #[test]
     fn poll_state_load_missing_file() {
         let tmp = tempfile::tempdir().unwrap();
-        let path = tmp.path().join("nonexistent.json");
 
-        let state = load_poll_state(&path);
+        let state = load_poll_state(&FileStore::new(tmp.path()), "t");
         assert!(state.last_event_id.is_none());
         assert!(state.last_poll_at.is_none());
     }
@@ -1151,13 +3928,35 @@ mod tests {
     #[test]
     fn poll_state_load_corrupt_file() {
         let tmp = tempfile::tempdir().unwrap();
-        let path = tmp.path().join("corrupt.json");
-        fs::write(&path, "not valid json!!!").unwrap();
+        let store = FileStore::new(tmp.path());
+        fs::write(store.path(&store::poll_key("t")), "not valid json!!!").unwrap();
 
-        let state = load_poll_state(&path);
+        let state = load_poll_state(&store, "t");
         assert!(state.last_event_id.is_none());
     }
 
+    #[test]
+    fn poll_state_tracks_each_repo() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = FileStore::new(tmp.path());
+        // Files written before extra repos existed still load
+        fs::write(
+            store.path(&store::poll_key("t")),
+            r#"{"last_event_id":"10","last_poll_at":null}"#,
+        )
+        .unwrap();
+        let mut state = load_poll_state(&store, "t");
+        assert_eq!(state.last_event_id("org/team", "org/team"), Some("10"));
+        assert_eq!(state.last_event_id("org/api", "org/team"), None);
+
+        state.record("org/api", "org/team", "77");
+        state.record("org/team", "org/team", "11");
+        save_poll_state(&store, "t", &state);
+        let loaded = load_poll_state(&store, "t");
+        assert_eq!(loaded.last_event_id("org/team", "org/team"), Some("11"));
+        assert_eq!(loaded.last_event_id("org/api", "org/team"), Some("77"));
+    }
+
     // ── GitHub event deserialization tests ────────────────────────────
 
     #[test]
@@ -1172,45 +3971,21 @@ mod tests {
         assert_eq!(events[1].event_type, "PushEvent");
     }
 
-    // ── Member discovery tests (daemon-internal) ─────────────────────
-
-    #[test]
-    fn list_member_dirs_returns_sorted() {
-        let tmp = tempfile::tempdir().unwrap();
-        fs::create_dir(tmp.path().join("bob")).unwrap();
-        fs::create_dir(tmp.path().join("alice")).unwrap();
-        fs::create_dir(tmp.path().join(".hidden")).unwrap();
-        fs::write(tmp.path().join("file.txt"), "").unwrap();
-
-        let result = list_member_dirs(tmp.path()).unwrap();
-        assert_eq!(result, vec!["alice", "bob"]);
-    }
-
-    #[test]
-    fn find_workspace_project_mode() {
-        let tmp = tempfile::tempdir().unwrap();
-        let project_dir = tmp.path().join("member").join("my-project");
-        fs::create_dir_all(project_dir.join(".botminter")).unwrap();
-
-        let result = find_workspace(tmp.path(), "member");
-        assert_eq!(result, Some(project_dir));
-    }
-
-    #[test]
-    fn find_workspace_no_project_mode() {
-        let tmp = tempfile::tempdir().unwrap();
-        let member_dir = tmp.path().join("member");
-        fs::create_dir_all(member_dir.join(".botminter")).unwrap();
-
-        let result = find_workspace(tmp.path(), "member");
-        assert_eq!(result, Some(member_dir));
-    }
-
     #[test]
-    fn find_workspace_missing() {
-        let tmp = tempfile::tempdir().unwrap();
-        let result = find_workspace(tmp.path(), "nonexistent");
-        assert_eq!(result, None);
+    fn github_event_normalizes_for_filters() {
+        let json = r#"{"id":"1","type":"IssueCommentEvent","actor":"alice","repo":"org/team",
+                       "action":"created","labels":["kind/story"]}"#;
+        let event: GitHubEvent = serde_json::from_str(json).unwrap();
+        let e = event.to_trigger_event();
+        assert_eq!(e.event, "issue_comment");
+        assert_eq!(e.author, "alice");
+        assert_eq!(e.action, "created");
+        assert_eq!(e.labels, vec!["kind/story"]);
+
+        // jq yields null for events without an action
+        let json = r#"{"id":"2","type":"PushEvent","actor":"bob","repo":"org/team","action":null,"labels":[]}"#;
+        let event: GitHubEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.to_trigger_event().action, "");
     }
 
     // ── Per-member log path tests ─────────────────────────────────────
@@ -1233,7 +4008,9 @@ mod tests {
             .unwrap();
         let shutdown = Arc::new(AtomicBool::new(false));
 
-        let result = wait_interruptible(&mut child, &shutdown);
+        let result = wait_interruptible(&mut child, &shutdown, || {
+            panic!("the hook only runs when terminating")
+        });
         assert!(result.is_some(), "Should return Some(status) on normal exit");
         assert!(result.unwrap().success(), "sleep 0.1 should exit 0");
     }
@@ -1253,8 +4030,10 @@ mod tests {
             shutdown_clone.store(true, Ordering::SeqCst);
         });
 
-        let result = wait_interruptible(&mut child, &shutdown);
+        let mut hooked = false;
+        let result = wait_interruptible(&mut child, &shutdown, || hooked = true);
         assert!(result.is_none(), "Should return None when shutdown triggered");
+        assert!(hooked, "before_terminate should run before SIGTERM");
         // Verify the child is actually dead
         assert!(
             child.try_wait().unwrap().is_some(),
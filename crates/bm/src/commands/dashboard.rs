@@ -0,0 +1,736 @@
+use std::fs::File;
+use std::io::{self, IsTerminal, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::config;
+use crate::members;
+use crate::run_history::RunRecord;
+use crate::state;
+use crate::state_history::{self, Entry};
+use crate::store;
+use crate::timefmt;
+
+use super::daemon;
+use super::start::{self, MemberStatus, Outcome, Started};
+use super::stop;
+
+/// How often the dashboard re-reads state and logs.
+const REFRESH: Duration = Duration::from_secs(2);
+
+/// Most log lines kept for the log pane.
+const LOG_LINES: usize = 500;
+
+/// How far back from the end of a log file the log pane reads.
+const LOG_TAIL_BYTES: u64 = 256 * 1024;
+
+/// Entries shown under recent events.
+const RECENT_EVENTS: usize = 20;
+
+const HELP: &str = "q quit · ←/→ team · ↑/↓ member · s start · x stop · r restart · \
+                    l member/daemon log · PgUp/PgDn scroll · End follow";
+
+/// Handles `bm dashboard [-t team]`.
+/// Shows every team's members, daemon, recent events, and logs, refreshed
+/// every two seconds, with keys to start, stop, and restart members.
+pub fn run(team_flag: Option<&str>) -> Result<()> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        bail!("bm dashboard needs a terminal. Use `bm status` for a plain-text view.");
+    }
+    let cfg = config::load()?;
+    let first = config::resolve_team(&cfg, team_flag)?.name.clone();
+    let teams: Vec<String> = cfg.teams.iter().map(|t| t.name.clone()).collect();
+    let mut app = App::new(teams, &first);
+    app.refresh();
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> Result<()> {
+    let (done_tx, done_rx) = mpsc::channel::<String>();
+    let mut refreshed = Instant::now();
+    loop {
+        terminal.draw(|frame| render(frame, app))?;
+
+        if event::poll(Duration::from_millis(250))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    match app.handle_key(key.code, key.modifiers) {
+                        Some(Action::Quit) => return Ok(()),
+                        Some(Action::Refresh) => {
+                            app.refresh();
+                            refreshed = Instant::now();
+                        }
+                        Some(Action::Member(op, member)) => {
+                            let team = app.team_name().to_string();
+                            app.busy = Some(format!("{} {}…", op.verb(), member));
+                            let tx = done_tx.clone();
+                            thread::spawn(move || {
+                                let message = match perform(&team, op, &member) {
+                                    Ok(message) => message,
+                                    Err(e) => format!("{}: {:#}", member, e),
+                                };
+                                let _ = tx.send(message);
+                            });
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        if let Ok(message) = done_rx.try_recv() {
+            app.busy = None;
+            app.message = Some(message);
+            app.refresh();
+            refreshed = Instant::now();
+            // Start and stop may have written to the terminal
+            terminal.clear()?;
+        }
+        if refreshed.elapsed() >= REFRESH {
+            app.refresh();
+            refreshed = Instant::now();
+        }
+    }
+}
+
+/// What a member key asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl Op {
+    fn verb(self) -> &'static str {
+        match self {
+            Op::Start => "Starting",
+            Op::Stop => "Stopping",
+            Op::Restart => "Restarting",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Action {
+    Quit,
+    /// Re-read everything now, e.g. after switching teams.
+    Refresh,
+    Member(Op, String),
+}
+
+/// Starts, stops, or restarts `member` of `team`. Runs off the UI thread,
+/// since stopping waits for the member to finish its iteration.
+fn perform(team: &str, op: Op, member: &str) -> Result<String> {
+    let stop = || {
+        let cfg = config::load()?;
+        let entry = config::resolve_team(&cfg, Some(team))?;
+        stop::stop_member(entry, member, stop::DEFAULT_GRACEFUL_TIMEOUT_SECS)
+    };
+    let start = || start::run_member(Some(team), member).map(|s| describe_start(&s, member));
+    match op {
+        Op::Start => start(),
+        Op::Stop => stop(),
+        Op::Restart => {
+            let state = state::load()?;
+            if !matches!(
+                start::resolve_member_status(&state, team, member),
+                MemberStatus::Stopped
+            ) {
+                stop()?;
+            }
+            start()
+        }
+    }
+}
+
+/// One line describing how starting `member` went.
+fn describe_start(started: &Started, member: &str) -> String {
+    match started.members.first().map(|m| &m.outcome) {
+        Some(Outcome::Started { pid }) => format!("{} started (PID {})", member, pid),
+        Some(Outcome::AlreadyRunning { pid }) => {
+            format!("{} is already running (PID {})", member, pid)
+        }
+        Some(Outcome::Held { reason }) => format!("{} held: {}", member, reason),
+        Some(Outcome::Failed { error }) => format!("{} failed to start: {}", member, error),
+        Some(Outcome::Unhealthy { pid, reason }) => {
+            format!("{} started (PID {}) but is not ready: {}", member, pid, reason)
+        }
+        None => format!("{} was not started", member),
+    }
+}
+
+/// A row of the members table.
+#[derive(Debug, Clone, PartialEq)]
+struct MemberLine {
+    name: String,
+    role: String,
+    status: &'static str,
+    pid: Option<u32>,
+    uptime: Option<String>,
+}
+
+/// Which log the log pane follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogSource {
+    Member,
+    Daemon,
+}
+
+/// Everything the dashboard shows, and where the user is in it.
+#[derive(Debug)]
+struct App {
+    teams: Vec<String>,
+    team: usize,
+    members: Vec<MemberLine>,
+    selected: usize,
+    daemon: Vec<String>,
+    events: Vec<String>,
+    log_source: LogSource,
+    log_path: Option<PathBuf>,
+    log: Vec<String>,
+    /// Lines scrolled up from the end of the log; zero follows it.
+    log_scroll: usize,
+    /// The member action in progress.
+    busy: Option<String>,
+    /// Outcome of the last action, or why the last refresh failed.
+    message: Option<String>,
+}
+
+impl App {
+    fn new(teams: Vec<String>, first: &str) -> App {
+        let team = teams.iter().position(|t| t == first).unwrap_or(0);
+        App {
+            teams,
+            team,
+            members: Vec::new(),
+            selected: 0,
+            daemon: Vec::new(),
+            events: Vec::new(),
+            log_source: LogSource::Member,
+            log_path: None,
+            log: Vec::new(),
+            log_scroll: 0,
+            busy: None,
+            message: None,
+        }
+    }
+
+    fn team_name(&self) -> &str {
+        self.teams.get(self.team).map(String::as_str).unwrap_or("")
+    }
+
+    fn selected_member(&self) -> Option<&MemberLine> {
+        self.members.get(self.selected)
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        let teams = self.teams.len().max(1);
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(Action::Quit)
+            }
+            KeyCode::Right | KeyCode::Tab => {
+                self.switch_team((self.team + 1) % teams);
+                return Some(Action::Refresh);
+            }
+            KeyCode::Left | KeyCode::BackTab => {
+                self.switch_team((self.team + teams - 1) % teams);
+                return Some(Action::Refresh);
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.selected + 1 < self.members.len() => {
+                self.selected += 1;
+                self.log_scroll = 0;
+                return Some(Action::Refresh);
+            }
+            KeyCode::Up | KeyCode::Char('k') if self.selected > 0 => {
+                self.selected -= 1;
+                self.log_scroll = 0;
+                return Some(Action::Refresh);
+            }
+            KeyCode::Char('l') => {
+                self.log_source = match self.log_source {
+                    LogSource::Member => LogSource::Daemon,
+                    LogSource::Daemon => LogSource::Member,
+                };
+                self.log_scroll = 0;
+                return Some(Action::Refresh);
+            }
+            KeyCode::PageUp => {
+                self.log_scroll = (self.log_scroll + 10).min(self.log.len().saturating_sub(1));
+            }
+            KeyCode::PageDown => self.log_scroll = self.log_scroll.saturating_sub(10),
+            KeyCode::End | KeyCode::Char('G') => self.log_scroll = 0,
+            KeyCode::Char(c @ ('s' | 'x' | 'r')) => {
+                if let Some(busy) = &self.busy {
+                    self.message = Some(format!("Still busy: {}", busy));
+                    return None;
+                }
+                let member = self.selected_member()?.name.clone();
+                let op = match c {
+                    's' => Op::Start,
+                    'x' => Op::Stop,
+                    _ => Op::Restart,
+                };
+                return Some(Action::Member(op, member));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn switch_team(&mut self, team: usize) {
+        if team != self.team {
+            self.team = team;
+            self.selected = 0;
+            self.log_scroll = 0;
+            self.members.clear();
+        }
+    }
+
+    /// Re-reads the current team's members, daemon, events, and log.
+    fn refresh(&mut self) {
+        if let Err(e) = self.load() {
+            self.message = Some(format!("{:#}", e));
+        }
+    }
+
+    fn load(&mut self) -> Result<()> {
+        let team_name = self.team_name().to_string();
+        let cfg = config::load()?;
+        let team = config::resolve_team(&cfg, Some(&team_name))?;
+
+        let state = state::load()?;
+        let members_dir = team.path.join("team").join("team");
+        let names = if members_dir.is_dir() {
+            members::list_names(&members_dir)?
+        } else {
+            Vec::new()
+        };
+        let now = Utc::now();
+        self.members = names
+            .iter()
+            .map(|name| {
+                let status = start::resolve_member_status(&state, &team_name, name);
+                let (pid, uptime) = match &status {
+                    MemberStatus::Running { pid, started_at, .. } => (
+                        Some(*pid),
+                        timefmt::parse(started_at)
+                            .map(|t| timefmt::duration(timefmt::elapsed(t, now))),
+                    ),
+                    MemberStatus::Crashed { pid, .. } => (Some(*pid), None),
+                    MemberStatus::Stopped => (None, None),
+                };
+                MemberLine {
+                    name: name.clone(),
+                    role: members::read_role(&members_dir, name),
+                    status: status.label(),
+                    pid,
+                    uptime,
+                }
+            })
+            .collect();
+        self.selected = self.selected.min(self.members.len().saturating_sub(1));
+
+        self.daemon = daemon::summary(&team_name)?
+            .split("; ")
+            .map(str::to_string)
+            .collect();
+        let history = state_history::load()?;
+        let runs = store::open()?.runs(&team_name)?;
+        self.events = recent_events(&history, &runs, &team_name, now);
+
+        self.log_path = match self.log_source {
+            LogSource::Daemon => Some(daemon::log_path(&team_name)?),
+            LogSource::Member => match self.selected_member() {
+                Some(m) => Some(daemon::member_log_path(&team_name, &m.name)?),
+                None => None,
+            },
+        };
+        self.log = match &self.log_path {
+            Some(path) => tail(path, LOG_LINES),
+            None => Vec::new(),
+        };
+        Ok(())
+    }
+}
+
+/// The team's latest member starts and exits and daemon runs, newest first.
+fn recent_events(
+    history: &[Entry],
+    runs: &[RunRecord],
+    team: &str,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let mut events: Vec<(DateTime<Utc>, String)> = Vec::new();
+    for entry in history.iter().filter(|e| e.team == team) {
+        let (Some(member), Some(at)) = (&entry.member, timefmt::parse(&entry.at)) else {
+            continue;
+        };
+        let what = match entry.pid {
+            Some(pid) => format!("{} {} (PID {})", member, entry.event, pid),
+            None => format!("{} {}", member, entry.event),
+        };
+        events.push((at, what));
+    }
+    for run in runs {
+        let members: Vec<String> = run
+            .members
+            .iter()
+            .map(|m| match m.ok {
+                true => m.name.clone(),
+                false => format!("{} ({})", m.name, m.status),
+            })
+            .collect();
+        let mut what = format!("run for {}", run.events.join(", "));
+        if !members.is_empty() {
+            what.push_str(&format!(": {}", members.join(", ")));
+        }
+        if !run.errors.is_empty() {
+            what.push_str(&format!("; {} error(s)", run.errors.len()));
+        }
+        events.push((run.started_at, what));
+    }
+    events.sort_by_key(|(at, _)| std::cmp::Reverse(*at));
+    events
+        .into_iter()
+        .take(RECENT_EVENTS)
+        .map(|(at, what)| format!("{:>12}  {}", timefmt::ago(timefmt::elapsed(at, now)), what))
+        .collect()
+}
+
+/// The last `max` lines of the file at `path`. Missing means empty.
+fn tail(path: &Path, max: usize) -> Vec<String> {
+    let Ok(mut file) = File::open(path) else {
+        return Vec::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(LOG_TAIL_BYTES);
+    let mut bytes = Vec::new();
+    if file.seek(SeekFrom::Start(start)).is_err() || file.read_to_end(&mut bytes).is_err() {
+        return Vec::new();
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let mut lines: Vec<&str> = text.lines().collect();
+    // A partial first line when reading from the middle of the file
+    if start > 0 && !lines.is_empty() {
+        lines.remove(0);
+    }
+    let skip = lines.len().saturating_sub(max);
+    lines[skip..].iter().map(|l| l.to_string()).collect()
+}
+
+fn render(frame: &mut Frame, app: &App) {
+    let [tabs, main, log, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Percentage(45),
+        Constraint::Min(5),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [members, side] =
+        Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(main);
+    let [daemon_area, events] =
+        Layout::vertical([Constraint::Length(6), Constraint::Min(3)]).areas(side);
+
+    frame.render_widget(
+        Tabs::new(app.teams.iter().map(String::as_str))
+            .select(app.team)
+            .highlight_style(Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+            .block(Block::default().borders(Borders::ALL).title(" Teams ")),
+        tabs,
+    );
+    render_members(frame, app, members);
+    frame.render_widget(
+        Paragraph::new(
+            app.daemon
+                .iter()
+                .map(|l| Line::from(l.as_str()))
+                .collect::<Vec<_>>(),
+        )
+        .block(Block::default().borders(Borders::ALL).title(" Daemon ")),
+        daemon_area,
+    );
+    frame.render_widget(
+        Paragraph::new(
+            app.events
+                .iter()
+                .map(|l| Line::from(l.as_str()))
+                .collect::<Vec<_>>(),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Recent events "),
+        ),
+        events,
+    );
+    render_log(frame, app, log);
+
+    let footer_line = match (&app.busy, &app.message) {
+        (Some(busy), _) => Line::from(Span::styled(busy.as_str(), Style::new().fg(Color::Yellow))),
+        (None, Some(message)) => Line::from(vec![
+            Span::styled(message.as_str(), Style::new().fg(Color::Yellow)),
+            Span::raw("  ·  "),
+            Span::styled(HELP, Style::new().fg(Color::DarkGray)),
+        ]),
+        (None, None) => Line::from(Span::styled(HELP, Style::new().fg(Color::DarkGray))),
+    };
+    frame.render_widget(Paragraph::new(footer_line), footer);
+}
+
+fn render_members(frame: &mut Frame, app: &App, area: Rect) {
+    let rows: Vec<Row> = app
+        .members
+        .iter()
+        .map(|m| {
+            let color = match m.status {
+                "running" => Color::Green,
+                "starting" => Color::Yellow,
+                "crashed" | "unhealthy" => Color::Red,
+                _ => Color::DarkGray,
+            };
+            Row::new(vec![
+                Cell::from(m.name.as_str()),
+                Cell::from(m.role.as_str()),
+                Cell::from(Span::styled(m.status, Style::new().fg(color))),
+                Cell::from(m.pid.map(|p| p.to_string()).unwrap_or_default()),
+                Cell::from(m.uptime.clone().unwrap_or_default()),
+            ])
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+        ],
+    )
+    .header(
+        Row::new(vec!["Member", "Role", "Status", "PID", "Uptime"])
+            .style(Style::new().add_modifier(Modifier::BOLD)),
+    )
+    .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+    .block(Block::default().borders(Borders::ALL).title(" Members "));
+    let mut state = TableState::default().with_selected(Some(app.selected));
+    if app.members.is_empty() {
+        state.select(None);
+    }
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn render_log(frame: &mut Frame, app: &App, area: Rect) {
+    let title = match (&app.log_path, app.log_source) {
+        (Some(path), LogSource::Daemon) => format!(" Daemon log: {} ", path.display()),
+        (Some(path), LogSource::Member) => match app.selected_member() {
+            Some(m) => format!(" Log: {} ({}) ", m.name, path.display()),
+            None => " Log ".to_string(),
+        },
+        (None, _) => " Log ".to_string(),
+    };
+    let height = area.height.saturating_sub(2) as usize;
+    let end = app.log.len().saturating_sub(app.log_scroll);
+    let start = end.saturating_sub(height);
+    let lines: Vec<Line> = if app.log.is_empty() {
+        vec![Line::from(Span::styled(
+            "No log output yet.",
+            Style::new().fg(Color::DarkGray),
+        ))]
+    } else {
+        app.log[start..end]
+            .iter()
+            .map(|l| Line::from(l.as_str()))
+            .collect()
+    };
+    let title = if app.log_scroll > 0 {
+        format!("{}[scrolled, End to follow] ", title)
+    } else {
+        title
+    };
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_history::MemberRun;
+    use crate::state_history::Event as HistoryEvent;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn app() -> App {
+        let mut app = App::new(vec!["alpha".to_string(), "beta".to_string()], "beta");
+        let member = |name: &str, status| MemberLine {
+            name: name.to_string(),
+            role: "architect".to_string(),
+            status,
+            pid: None,
+            uptime: None,
+        };
+        app.members = vec![
+            member("architect-01", "running"),
+            member("architect-02", "stopped"),
+        ];
+        app.log = (1..=30).map(|i| format!("line {}", i)).collect();
+        app
+    }
+
+    #[test]
+    fn keys_move_between_teams_and_members_and_act_on_the_selection() {
+        let mut app = app();
+        let none = KeyModifiers::NONE;
+        assert_eq!(app.team_name(), "beta");
+
+        assert_eq!(app.handle_key(KeyCode::Down, none), Some(Action::Refresh));
+        assert_eq!(app.handle_key(KeyCode::Down, none), None);
+        assert_eq!(
+            app.handle_key(KeyCode::Char('x'), none),
+            Some(Action::Member(Op::Stop, "architect-02".to_string()))
+        );
+        app.busy = Some("Stopping architect-02…".to_string());
+        assert_eq!(app.handle_key(KeyCode::Char('s'), none), None);
+        assert!(app.message.as_deref().unwrap().starts_with("Still busy"));
+        app.busy = None;
+
+        app.handle_key(KeyCode::PageUp, none);
+        assert_eq!(app.log_scroll, 10);
+        app.handle_key(KeyCode::End, none);
+        assert_eq!(app.log_scroll, 0);
+
+        assert_eq!(app.handle_key(KeyCode::Right, none), Some(Action::Refresh));
+        assert_eq!((app.team_name(), app.selected), ("alpha", 0));
+        assert_eq!(
+            app.handle_key(KeyCode::Char('r'), none),
+            None,
+            "no members loaded yet"
+        );
+        assert_eq!(
+            app.handle_key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(Action::Quit)
+        );
+    }
+
+    #[test]
+    fn recent_events_merges_member_history_and_runs() {
+        let at = |s: &str| timefmt::parse(s).unwrap();
+        let now = at("2026-02-20T12:00:00Z");
+        let entry = |at: &str, team: &str, event, member: Option<&str>| Entry {
+            at: at.to_string(),
+            event,
+            team: team.to_string(),
+            member: member.map(str::to_string),
+            pid: Some(42),
+            started_at: None,
+            running: Default::default(),
+        };
+        let history = vec![
+            entry(
+                "2026-02-20T11:00:00Z",
+                "t",
+                HistoryEvent::Started,
+                Some("arch-01"),
+            ),
+            entry("2026-02-20T11:30:00Z", "t", HistoryEvent::Snapshot, None),
+            entry(
+                "2026-02-20T11:50:00Z",
+                "other",
+                HistoryEvent::Crashed,
+                Some("dev-01"),
+            ),
+        ];
+        let runs = vec![RunRecord {
+            events: vec!["issues".to_string()],
+            started_at: at("2026-02-20T11:55:00Z"),
+            finished_at: at("2026-02-20T11:56:00Z"),
+            members: vec![MemberRun {
+                name: "arch-01".to_string(),
+                status: "exit status: 1".to_string(),
+                ok: false,
+            }],
+            errors: vec![],
+            interrupted: false,
+        }];
+
+        let events = recent_events(&history, &runs, "t", now);
+        assert_eq!(events.len(), 2);
+        assert!(
+            events[0].ends_with("run for issues: arch-01 (exit status: 1)"),
+            "{}",
+            events[0]
+        );
+        assert!(events[0].contains("5m 00s ago"), "{}", events[0]);
+        assert!(
+            events[1].ends_with("arch-01 started (PID 42)"),
+            "{}",
+            events[1]
+        );
+    }
+
+    #[test]
+    fn tail_keeps_the_last_lines() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("member.log");
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        assert_eq!(tail(&path, 2), vec!["two", "three"]);
+        assert!(tail(&tmp.path().join("missing.log"), 2).is_empty());
+    }
+
+    #[test]
+    fn render_shows_members_and_the_end_of_the_log() {
+        let mut app = app();
+        app.daemon = vec!["Daemon: not running".to_string()];
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| render(frame, &app)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        for expected in [
+            "alpha",
+            "architect-02",
+            "Daemon: not running",
+            "line 30",
+            "q quit",
+        ] {
+            assert!(screen.contains(expected), "missing {:?}", expected);
+        }
+        assert!(!screen.contains("line 1 "));
+
+        app.log_scroll = 25;
+        terminal.draw(|frame| render(frame, &app)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(screen.contains("End to follow"));
+        assert!(!screen.contains("line 30"));
+    }
+}
@@ -0,0 +1,533 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::commands::daemon;
+use crate::config::{self, BotminterConfig, TeamEntry};
+use crate::daemon_settings;
+use crate::launch_env;
+use crate::parse;
+use crate::state::{self, RuntimeState};
+use crate::state_history;
+use crate::store;
+use crate::topology;
+
+/// Default number of log files included in a bundle.
+pub const DEFAULT_LOG_FILES: usize = 5;
+
+/// Only the tail of each log goes into the bundle; daemon logs rotate at 10 MiB.
+const MAX_LOG_BYTES: usize = 1024 * 1024;
+
+/// Prefixes of GitHub token formats, redacted wherever they appear.
+const GITHUB_TOKEN_PREFIXES: &[&str] = &["ghp_", "gho_", "ghu_", "ghs_", "ghr_", "github_pat_"];
+
+/// Tools whose versions go into `versions.txt`.
+const TOOLS: &[(&str, &[&str])] = &[
+    ("ralph", &["--version"]),
+    ("claude", &["--version"]),
+    ("gh", &["--version"]),
+    ("git", &["--version"]),
+    ("kubectl", &["version", "--client"]),
+    ("systemd-run", &["--version"]),
+];
+
+/// Replaces secrets in text collected for a bundle.
+struct Scrubber {
+    secrets: Vec<String>,
+}
+
+impl Scrubber {
+    /// Collects the credentials of every registered team, so a secret of
+    /// one team never leaks through another team's files.
+    fn new(cfg: &BotminterConfig) -> Scrubber {
+        let mut secrets: Vec<String> = cfg
+            .teams
+            .iter()
+            .flat_map(|t| {
+                let c = &t.credentials;
+                let smtp_password = c.smtp.as_ref().and_then(|s| s.password.as_ref());
+                let notifier_secrets = c
+                    .notifiers
+                    .iter()
+                    .flat_map(|n| n.url.iter().chain(n.headers.values()));
+                [
+                    c.gh_token.as_ref(),
+                    c.telegram_bot_token.as_ref(),
+                    c.slack_bot_token.as_ref(),
+                    c.webhook_secret.as_ref(),
+                    smtp_password,
+                ]
+                .into_iter()
+                .flatten()
+                .chain(notifier_secrets)
+            })
+            .filter(|s| !s.is_empty())
+            .cloned()
+            .collect();
+        // Longest first, so a secret containing another is replaced whole
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        secrets.dedup();
+        Scrubber { secrets }
+    }
+
+    fn scrub(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        for secret in &self.secrets {
+            out = out.replace(secret.as_str(), &launch_env::redact(secret));
+        }
+        redact_github_tokens(&out)
+    }
+}
+
+/// Redacts anything shaped like a GitHub token, e.g. one minted for a member
+/// that never went through config.yml.
+fn redact_github_tokens(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphanumeric() || c == '_') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let word = &rest[..end];
+        let is_token = GITHUB_TOKEN_PREFIXES
+            .iter()
+            .any(|p| word.len() > p.len() + 8 && word.starts_with(p));
+        if is_token {
+            out.push_str(&launch_env::redact(word));
+        } else {
+            out.push_str(word);
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Returns config.yml with every credential replaced by its fingerprint.
+fn redacted_config(cfg: &BotminterConfig) -> BotminterConfig {
+    let mut cfg = cfg.clone();
+    for team in &mut cfg.teams {
+        let c = &mut team.credentials;
+        for secret in [
+            &mut c.gh_token,
+            &mut c.telegram_bot_token,
+            &mut c.slack_bot_token,
+            &mut c.webhook_secret,
+        ] {
+            if let Some(value) = secret.as_mut() {
+                *value = launch_env::redact(value);
+            }
+        }
+        if let Some(password) = c.smtp.as_mut().and_then(|s| s.password.as_mut()) {
+            *password = launch_env::redact(password);
+        }
+        // Webhook URLs carry their secret in the path
+        for notifier in &mut c.notifiers {
+            for value in notifier.url.iter_mut().chain(notifier.headers.values_mut()) {
+                *value = launch_env::redact(value);
+            }
+        }
+    }
+    cfg
+}
+
+/// Picks the `limit` most recently written logs of the given teams, newest
+/// first. Compressed archives (`.log.1.gz`, …) are left out.
+fn select_logs(logs_dir: &Path, teams: &[&str], limit: usize) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(logs_dir) else {
+        return Vec::new();
+    };
+    let mut logs: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            !name.ends_with(".gz")
+                && teams.iter().any(|team| {
+                    name.starts_with(&format!("daemon-{}.log", team))
+                        || name.starts_with(&format!("member-{}-", team))
+                })
+        })
+        .filter_map(|e| {
+            let modified = e.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, e.path()))
+        })
+        .collect();
+    logs.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    logs.into_iter().take(limit).map(|(_, p)| p).collect()
+}
+
+/// Reads the last `MAX_LOG_BYTES` of a log, starting at a line boundary.
+/// Returns the text and whether it was truncated.
+fn read_log_tail(path: &Path) -> Result<(String, bool)> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if bytes.len() <= MAX_LOG_BYTES {
+        return Ok((String::from_utf8_lossy(&bytes).to_string(), false));
+    }
+    let tail = &bytes[bytes.len() - MAX_LOG_BYTES..];
+    let start = tail.iter().position(|&b| b == b'\n').map_or(0, |i| i + 1);
+    Ok((String::from_utf8_lossy(&tail[start..]).to_string(), true))
+}
+
+/// Builds the version matrix: bm, the platform, and external tools.
+fn versions(teams: &[&TeamEntry]) -> String {
+    let mut out = format!(
+        "bm: {}\nplatform: {} {}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    for (tool, args) in TOOLS {
+        let version = if which::which(tool).is_err() {
+            "not installed".to_string()
+        } else {
+            match Command::new(tool).args(*args).output() {
+                Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout)
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .to_string(),
+                Ok(o) => format!("error (exit {})", o.status.code().unwrap_or(-1)),
+                Err(e) => format!("error ({})", e),
+            }
+        };
+        out.push_str(&format!("{}: {}\n", tool, version));
+    }
+    for team in teams {
+        let manifest = team.path.join("team").join("botminter.yml");
+        let field = |value: &serde_yml::Value, key: &str| {
+            value
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string()
+        };
+        let line = match fs::read_to_string(&manifest)
+            .ok()
+            .and_then(|c| parse::yaml::<serde_yml::Value>(&manifest, &c).ok())
+        {
+            Some(v) => format!(
+                "profile {} {} (schema {})",
+                team.profile,
+                field(&v, "version"),
+                field(&v, "schema_version")
+            ),
+            None => format!("profile {} (no botminter.yml in team repo)", team.profile),
+        };
+        out.push_str(&format!("team {}: {}\n", team.name, line));
+    }
+    out
+}
+
+/// Keeps only the runtime entries and run history of the given teams.
+fn state_for(mut state: RuntimeState, teams: &[&str]) -> RuntimeState {
+    let keep = |key: &String| teams.iter().any(|t| key.starts_with(&format!("{}/", t)));
+    state.members.retain(|k, _| keep(k));
+    state.stats.retain(|k, _| keep(k));
+    state.paused.retain(|team, _| teams.contains(&team.as_str()));
+    state
+}
+
+/// An archive being assembled, with a manifest of what went in.
+struct Bundle {
+    root: String,
+    tar: tar::Builder<GzEncoder<fs::File>>,
+    scrubber: Scrubber,
+    manifest: Vec<String>,
+}
+
+impl Bundle {
+    /// Adds `contents`, scrubbed of secrets, at `name` under the bundle root.
+    fn add(&mut self, name: &str, contents: &str, note: &str) -> Result<()> {
+        let data = self.scrubber.scrub(contents);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+        header.set_cksum();
+        self.tar
+            .append_data(
+                &mut header,
+                format!("{}/{}", self.root, name),
+                data.as_bytes(),
+            )
+            .with_context(|| format!("Failed to add {} to the bundle", name))?;
+        self.manifest.push(if note.is_empty() {
+            name.to_string()
+        } else {
+            format!("{} ({})", name, note)
+        });
+        Ok(())
+    }
+
+    /// Adds a file from disk if it exists.
+    fn add_file(&mut self, name: &str, path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        self.add(name, &contents, "")
+    }
+}
+
+/// Handles `bm diag bundle [-t team] [-o path] [--logs N]` — writes a
+/// redacted support archive for attaching to bug reports.
+pub fn bundle(team_flag: Option<&str>, output: Option<&Path>, log_files: usize) -> Result<()> {
+    let cfg = config::load()?;
+    let teams: Vec<&TeamEntry> = match team_flag {
+        Some(_) => vec![config::resolve_team(&cfg, team_flag)?],
+        None => cfg.teams.iter().collect(),
+    };
+    let team_names: Vec<&str> = teams.iter().map(|t| t.name.as_str()).collect();
+
+    let now = chrono::Utc::now();
+    let root = format!("bm-diag-{}", now.format("%Y%m%d-%H%M%S"));
+    let path = match output {
+        Some(p) => p.to_path_buf(),
+        None => PathBuf::from(format!("{}.tar.gz", root)),
+    };
+    let file =
+        fs::File::create(&path).with_context(|| format!("Failed to create {}", path.display()))?;
+    // Redacted, but still full of paths and history
+    config::set_mode(&path, 0o600)
+        .with_context(|| format!("Failed to set permissions on {}", path.display()))?;
+
+    let mut bundle = Bundle {
+        root,
+        tar: tar::Builder::new(GzEncoder::new(file, Compression::default())),
+        scrubber: Scrubber::new(&cfg),
+        manifest: Vec::new(),
+    };
+
+    bundle.add("versions.txt", &versions(&teams), "")?;
+
+    let mut shown = redacted_config(&cfg);
+    shown
+        .teams
+        .retain(|t| team_names.contains(&t.name.as_str()));
+    let config_yml =
+        serde_yml::to_string(&shown).context("Failed to serialize config for the bundle")?;
+    bundle.add("config.yml", &config_yml, "credentials redacted")?;
+
+    let runtime = state_for(state::load()?, &team_names);
+    let state_json =
+        serde_json::to_string_pretty(&runtime).context("Failed to serialize runtime state")?;
+    bundle.add("state.json", &state_json, "running members and run history")?;
+
+    let history: Vec<String> = state_history::load()?
+        .iter()
+        .filter(|e| team_names.contains(&e.team.as_str()))
+        .filter_map(|e| serde_json::to_string(e).ok())
+        .collect();
+    if !history.is_empty() {
+        bundle.add(
+            "state-history.jsonl",
+            &(history.join("\n") + "\n"),
+            "member starts, stops, crashes, and snapshots",
+        )?;
+    }
+
+    let store = store::open()?;
+    for team in &teams {
+        let dir = format!("teams/{}", team.name);
+        let topo = topology::topology_path(&cfg.workzone, &team.name);
+        bundle.add_file(&format!("{}/topology.json", dir), &topo)?;
+        let team_repo = team.path.join("team");
+        bundle.add_file(
+            &format!("{}/daemon.yml", dir),
+            &daemon_settings::team_settings_path(&team_repo),
+        )?;
+        bundle.add_file(
+            &format!("{}/daemon-local.yml", dir),
+            &daemon_settings::local_override_path(&team.name)?,
+        )?;
+        if let Some(daemon_cfg) = store.read(&store::daemon_key(&team.name))? {
+            bundle.add(&format!("{}/daemon.json", dir), &daemon_cfg, "")?;
+        }
+        if let Some(poll) = store.read(&store::poll_key(&team.name))? {
+            bundle.add(&format!("{}/daemon-poll.json", dir), &poll, "")?;
+        }
+        bundle.add_file(
+            &format!("{}/daemon-queue.json", dir),
+            &daemon::queue_path(&team.name)?,
+        )?;
+        bundle.add_file(
+            &format!("{}/daemon-schedules.json", dir),
+            &daemon::schedules_path(&team.name)?,
+        )?;
+        let runs = store.runs(&team.name)?;
+        if !runs.is_empty() {
+            let runs = serde_json::to_string_pretty(&runs).context("Failed to serialize runs")?;
+            bundle.add(&format!("{}/daemon-runs.json", dir), &runs, "")?;
+        }
+    }
+
+    let logs = select_logs(&config::config_dir()?.join("logs"), &team_names, log_files);
+    for log in &logs {
+        let name = log
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let (contents, truncated) = read_log_tail(log)?;
+        let note = if truncated { "last 1 MiB" } else { "" };
+        bundle.add(&format!("logs/{}", name), &contents, note)?;
+    }
+
+    let mut manifest = format!(
+        "Created by bm {} at {}\nTeams: {}\n\
+         Secrets from config.yml and GitHub tokens are replaced with\n\
+         <redacted sha256:...> fingerprints throughout.\n\nFiles:\n",
+        env!("CARGO_PKG_VERSION"),
+        now.to_rfc3339(),
+        team_names.join(", ")
+    );
+    for entry in &bundle.manifest {
+        manifest.push_str(&format!("  {}\n", entry));
+    }
+    let files = bundle.manifest.len() + 1;
+    bundle.add("MANIFEST.txt", &manifest, "")?;
+
+    bundle
+        .tar
+        .into_inner()
+        .and_then(|gz| gz.finish())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!(
+        "Wrote {} ({} file(s), {} log(s)).",
+        path.display(),
+        files,
+        logs.len()
+    );
+    println!("Secrets are redacted; review the archive before attaching it to a public issue.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Credentials, Smtp, SmtpTls};
+    use crate::notify::{Channel, Notifier};
+    use std::collections::BTreeMap;
+
+    fn config_with_token(token: &str) -> BotminterConfig {
+        BotminterConfig {
+            version: config::CONFIG_VERSION,
+            workzone: PathBuf::from("/w"),
+            default_team: None,
+            token_expiry_warn_days: None,
+            logs: None,
+            timestamps: None,
+            workspace: None,
+            state_backend: None,
+            teams: vec![TeamEntry {
+                name: "alpha".to_string(),
+                path: PathBuf::from("/w/alpha"),
+                profile: "scrum".to_string(),
+                github_repo: "org/alpha".to_string(),
+                extra_repos: Vec::new(),
+                budget: None,
+                credentials: Credentials {
+                    gh_token: Some(token.to_string()),
+                    webhook_secret: Some("s3cret-hook".to_string()),
+                    slack_bot_token: Some("xoxb-sl4ck-bot".to_string()),
+                    smtp: Some(Smtp {
+                        server: "mail.example.com".to_string(),
+                        port: None,
+                        tls: SmtpTls::Starttls,
+                        username: Some("bm".to_string()),
+                        password: Some("mail-pa55".to_string()),
+                        from: "bm@example.com".to_string(),
+                        to: vec!["ops@example.com".to_string()],
+                        notify_on: Vec::new(),
+                        digest_at: None,
+                    }),
+                    notifiers: vec![Notifier {
+                        channel: Channel::Slack,
+                        url: Some("https://hooks.slack.com/services/T0/B0/sl4ck".to_string()),
+                        headers: BTreeMap::new(),
+                        notify_on: Vec::new(),
+                    }],
+                    ..Default::default()
+                },
+            }],
+        }
+    }
+
+    #[test]
+    fn scrub_replaces_configured_secrets_and_github_tokens() {
+        let cfg = config_with_token("plain-token-value");
+        let scrubber = Scrubber::new(&cfg);
+        let minted = "ghs_0123456789abcdefABCDEF";
+        let text = format!(
+            "token=plain-token-value hook s3cret-hook mail mail-pa55 \
+             slack https://hooks.slack.com/services/T0/B0/sl4ck xoxb-sl4ck-bot\n\
+             minted {}, kept ghs_short\n",
+            minted
+        );
+        let out = scrubber.scrub(&text);
+
+        assert!(!out.contains("plain-token-value"), "{}", out);
+        assert!(!out.contains("s3cret-hook"), "{}", out);
+        assert!(!out.contains("mail-pa55"), "{}", out);
+        assert!(!out.contains("sl4ck"), "{}", out);
+        assert!(!out.contains(minted), "{}", out);
+        assert!(out.contains(&launch_env::redact("plain-token-value")));
+        assert!(out.contains(&format!("{}, kept ghs_short\n", launch_env::redact(minted))));
+    }
+
+    #[test]
+    fn redacted_config_keeps_structure() {
+        let cfg = redacted_config(&config_with_token("plain-token-value"));
+        let creds = &cfg.teams[0].credentials;
+        assert_eq!(
+            creds.gh_token.as_deref(),
+            Some(launch_env::redact("plain-token-value").as_str())
+        );
+        assert!(creds.telegram_bot_token.is_none());
+        let smtp = creds.smtp.as_ref().unwrap();
+        assert_eq!(smtp.password.as_deref(), Some(launch_env::redact("mail-pa55").as_str()));
+        assert_eq!(smtp.server, "mail.example.com");
+        assert!(!creds.notifiers[0].url.as_ref().unwrap().contains("sl4ck"));
+        assert!(!creds.slack_bot_token.as_ref().unwrap().contains("sl4ck"));
+        assert_eq!(cfg.teams[0].github_repo, "org/alpha");
+    }
+
+    #[test]
+    fn select_logs_filters_by_team_and_keeps_newest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let names = [
+            "daemon-alpha.log.1.gz",
+            "member-alpha-dev-bob.log",
+            "daemon-alpha.log",
+            "daemon-alphabet.log",
+            "member-beta-qe-amy.log",
+        ];
+        let base = SystemTime::now() - std::time::Duration::from_secs(100);
+        for (i, name) in names.iter().enumerate() {
+            let path = dir.join(name);
+            fs::write(&path, name).unwrap();
+            let mtime = base + std::time::Duration::from_secs(i as u64 * 10);
+            filetime::set_file_mtime(&path, filetime::FileTime::from_system_time(mtime)).unwrap();
+        }
+
+        let picked: Vec<String> = select_logs(dir, &["alpha"], 2)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(picked, vec!["daemon-alpha.log", "member-alpha-dev-bob.log"]);
+
+        assert_eq!(select_logs(dir, &["alpha", "beta"], 10).len(), 3);
+        assert!(select_logs(&dir.join("missing"), &["alpha"], 5).is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+use std::io::IsTerminal;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+use crate::config;
+use crate::procutil;
+use crate::topology::{self, Endpoint};
+
+/// Handles `bm exec <member> [-t team] -- <command>...`.
+/// Runs `command` where the member runs, as recorded in the team's
+/// topology: in its workspace for local endpoints, or through
+/// `kubectl exec` in its pod. Returns the command's exit code.
+pub fn run(member: &str, command: &[String], team_flag: Option<&str>) -> Result<i32> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+
+    let topo_path = topology::topology_path(&cfg.workzone, &team.name);
+    let Some(topo) = topology::load(&topo_path)? else {
+        bail!(
+            "No topology for team '{}'. Start its members with `bm start` first.",
+            team.name
+        );
+    };
+    let Some(entry) = topo.members.get(member) else {
+        let mut names: Vec<&str> = topo.members.keys().map(|k| k.as_str()).collect();
+        names.sort();
+        if names.is_empty() {
+            bail!(
+                "Member '{}' is not running in team '{}'; no members are.",
+                member,
+                team.name
+            );
+        }
+        bail!(
+            "Member '{}' is not running in team '{}'. Running members: {}",
+            member,
+            team.name,
+            names.join(", ")
+        );
+    };
+
+    match &entry.endpoint {
+        Endpoint::Local { pid, workspace } => {
+            if !workspace.is_dir() {
+                bail!(
+                    "Workspace {} of member '{}' no longer exists",
+                    workspace.display(),
+                    member
+                );
+            }
+            if !procutil::is_alive(*pid) {
+                eprintln!(
+                    "Member '{}' (PID {}) is no longer running; running in its workspace anyway.",
+                    member, pid
+                );
+            }
+        }
+        Endpoint::K8s { .. } => {
+            if which::which("kubectl").is_err() {
+                bail!(
+                    "kubectl not found on PATH; it is needed to exec into member '{}''s pod",
+                    member
+                );
+            }
+        }
+    }
+
+    let tty = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    let mut cmd = build(&entry.endpoint, command, tty);
+    let status = cmd
+        .status()
+        .with_context(|| format!("Failed to run `{}`", cmd.get_program().to_string_lossy()))?;
+    Ok(status.code().unwrap_or(1))
+}
+
+/// The process that runs `command` at `endpoint`. `tty` allocates a
+/// terminal in the pod, for interactive commands such as shells.
+fn build(endpoint: &Endpoint, command: &[String], tty: bool) -> Command {
+    match endpoint {
+        Endpoint::Local { workspace, .. } => {
+            let mut cmd = Command::new(&command[0]);
+            cmd.args(&command[1..]).current_dir(workspace);
+            cmd
+        }
+        Endpoint::K8s {
+            namespace,
+            pod,
+            container,
+            context,
+        } => {
+            let mut cmd = Command::new("kubectl");
+            cmd.args(["--context", context, "-n", namespace, "exec"]);
+            if tty {
+                cmd.arg("-it");
+            }
+            cmd.args([pod.as_str(), "-c", container, "--"])
+                .args(command);
+            cmd
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    fn args(cmd: &Command) -> Vec<String> {
+        cmd.get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn build_runs_locally_in_workspace_or_through_kubectl() {
+        let command = vec!["git".to_string(), "status".to_string()];
+
+        let local = Endpoint::Local {
+            pid: 42,
+            workspace: PathBuf::from("/ws/architect-01"),
+        };
+        let cmd = build(&local, &command, true);
+        assert_eq!(cmd.get_program(), "git");
+        assert_eq!(args(&cmd), vec!["status"]);
+        assert_eq!(cmd.get_current_dir(), Some(Path::new("/ws/architect-01")));
+
+        let pod = Endpoint::K8s {
+            namespace: "team-ns".to_string(),
+            pod: "architect-01-abc".to_string(),
+            container: "ralph".to_string(),
+            context: "kind-bm".to_string(),
+        };
+        let cmd = build(&pod, &command, false);
+        assert_eq!(cmd.get_program(), "kubectl");
+        assert_eq!(
+            args(&cmd).join(" "),
+            "--context kind-bm -n team-ns exec architect-01-abc -c ralph -- git status"
+        );
+        assert!(args(&build(&pod, &command, true)).contains(&"-it".to_string()));
+    }
+}
@@ -1,15 +1,91 @@
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
-
-use crate::config;
+use serde::Serialize;
+
+use crate::config::{self, TeamEntry};
+use crate::dry_run::{Plan, Step};
+use crate::member_templates;
+use crate::members;
+use crate::parse;
+use crate::procutil;
 use crate::profile;
+use crate::profile_lint::{Findings, Severity};
+use crate::state::{self, RuntimeState};
+use crate::team_verify;
 
 use super::init::{finalize_member_manifest, run_git};
 
-/// Handles `bm hire <role> [--name <name>] [-t team]`.
-pub fn run(role: &str, name: Option<&str>, team_flag: Option<&str>) -> Result<()> {
+/// What `bm hire` did.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Hired {
+    pub team: String,
+    pub role: String,
+    /// The member's name within its role (`--name` or the generated suffix).
+    pub name: String,
+    /// The member's directory in the team repo.
+    pub member_dir: PathBuf,
+    /// The leftover workspace taken over with `--adopt-existing`, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adopted_workspace: Option<PathBuf>,
+}
+
+impl fmt::Display for Hired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Hired {} as {} in team '{}'.",
+            self.role, self.name, self.team
+        )?;
+        if let Some(ref ws) = self.adopted_workspace {
+            writeln!(
+                f,
+                "Adopted the existing workspace at {}. Run `bm teams sync` to refresh it.",
+                ws.display()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Handles `bm hire <role> [--name <name>] [--adopt-existing] [-t team]`.
+///
+/// Hiring is all or nothing: the member is built in a staging directory next
+/// to the team repo, checked, and only then moved into `team/` and
+/// committed. If any step fails, the member directory and anything staged in
+/// git are rolled back, leaving the team repo as it was.
+pub fn run(
+    role: &str,
+    name: Option<&str>,
+    adopt_existing: bool,
+    team_flag: Option<&str>,
+) -> Result<Hired> {
+    hire(role, name, adopt_existing, team_flag, None)
+}
+
+/// Handles `bm hire --dry-run`: runs every check a hire does and builds the
+/// member in the scratch staging directory, but only lists what would be
+/// written and committed.
+pub fn plan(
+    role: &str,
+    name: Option<&str>,
+    adopt_existing: bool,
+    team_flag: Option<&str>,
+) -> Result<Plan> {
+    let mut plan = Plan::default();
+    hire(role, name, adopt_existing, team_flag, Some(&mut plan))?;
+    Ok(plan)
+}
+
+fn hire(
+    role: &str,
+    name: Option<&str>,
+    adopt_existing: bool,
+    team_flag: Option<&str>,
+    plan: Option<&mut Plan>,
+) -> Result<Hired> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, team_flag)?;
     let team_repo = team.path.join("team");
@@ -19,7 +95,7 @@ pub fn run(role: &str, name: Option<&str>, team_flag: Option<&str>) -> Result<()
     let manifest: profile::ProfileManifest = {
         let contents = fs::read_to_string(&manifest_path)
             .context("Failed to read team repo's botminter.yml")?;
-        serde_yml::from_str(&contents).context("Failed to parse botminter.yml")?
+        parse::yaml(&manifest_path, &contents)?
     };
 
     // Schema version guard
@@ -36,10 +112,17 @@ pub fn run(role: &str, name: Option<&str>, team_flag: Option<&str>) -> Result<()
         );
     }
 
+    check_capacity(&manifest, &team_repo, role, &team.name)?;
+
+    let runtime_state = state::load()?;
+
     // Determine member name: use --name flag or auto-generate suffix
     let member_name = match name {
         Some(n) => n.to_string(),
-        None => auto_suffix(&team_repo, role)?,
+        None => {
+            let leftovers = leftover_names(team, &runtime_state)?;
+            auto_suffix(&team_repo, role, &leftovers)?
+        }
     };
 
     let member_dir_name = format!("{}-{}", role, member_name);
@@ -52,57 +135,311 @@ pub fn run(role: &str, name: Option<&str>, team_flag: Option<&str>) -> Result<()
         );
     }
 
-    // Extract member skeleton from embedded profile
-    fs::create_dir_all(&member_dir)
-        .with_context(|| format!("Failed to create member dir {}", member_dir.display()))?;
+    let (adopted, stale_entry) =
+        check_leftovers(team, &runtime_state, role, &member_name, adopt_existing)?;
+
+    let staging = Staging::new(&team.path, &member_dir_name)?;
+    stage_member(team, role, &member_name, &staging.member_dir)?;
+    let commit_msg = format!("feat: hire {} as {}", role, member_name);
+
+    if let Some(plan) = plan {
+        plan.push(Step::CreateDir(member_dir.clone()));
+        let mut files = Vec::new();
+        staged_files(&staging.member_dir, Path::new(""), &mut files)?;
+        for file in files {
+            plan.push(Step::WriteFile(member_dir.join(file)));
+        }
+        plan.push(Step::Commit {
+            repo: team_repo.clone(),
+            message: commit_msg,
+        });
+        if let Some(key) = stale_entry {
+            plan.push(Step::UpdateState(format!("drop the stale entry '{}'", key)));
+        }
+        return Ok(Hired {
+            team: team.name.clone(),
+            role: role.to_string(),
+            adopted_workspace: adopted.then(|| team.path.join(&member_dir_name)),
+            name: member_name,
+            member_dir,
+        });
+    }
+
+    fs::create_dir_all(team_repo.join("team")).context("Failed to create team/ dir")?;
+    fs::rename(&staging.member_dir, &member_dir).with_context(|| {
+        format!(
+            "Failed to move the staged member into {}",
+            member_dir.display()
+        )
+    })?;
+    if let Err(e) = commit_member(&team_repo, &member_dir_name, &commit_msg) {
+        rollback(&team_repo, &member_dir, &member_dir_name);
+        return Err(e.context(format!(
+            "Hiring {} as {} failed; the team repo was left unchanged",
+            role, member_name
+        )));
+    }
+
+    // Only a completed hire drops the stale runtime entry it adopted
+    if let Some(key) = stale_entry {
+        state::update(|s| {
+            s.members.remove(&key);
+            Ok(())
+        })?;
+    }
+
+    Ok(Hired {
+        team: team.name.clone(),
+        role: role.to_string(),
+        adopted_workspace: adopted.then(|| team.path.join(&member_dir_name)),
+        name: member_name,
+        member_dir,
+    })
+}
+
+/// A directory for building a member outside the team repo, removed on drop.
+/// It sits in the team directory, on the same filesystem as the team repo,
+/// so the finished member can be renamed into place.
+struct Staging {
+    root: PathBuf,
+    /// `{root}/{member_dir_name}`, named like the final directory since
+    /// template variables are derived from it.
+    member_dir: PathBuf,
+}
+
+impl Staging {
+    fn new(team_dir: &Path, member_dir_name: &str) -> Result<Staging> {
+        let root = team_dir.join(format!(".hire-{}", member_dir_name));
+        if root.exists() {
+            // Left by a hire that was killed midway
+            fs::remove_dir_all(&root)
+                .with_context(|| format!("Failed to clear {}", root.display()))?;
+        }
+        let member_dir = root.join(member_dir_name);
+        fs::create_dir_all(&member_dir)
+            .with_context(|| format!("Failed to create staging dir {}", member_dir.display()))?;
+        Ok(Staging { root, member_dir })
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Collects the files under `dir`, relative to it, in a stable order.
+fn staged_files(dir: &Path, rel: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir.join(rel))?.collect::<std::io::Result<_>>()?;
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = rel.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            staged_files(dir, &path, out)?;
+        } else {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
 
-    profile::extract_member_to(&team.profile, role, &member_dir)?;
+/// Builds the member in `member_dir` from the profile's skeleton and checks
+/// the result is a complete, finalized member.
+fn stage_member(team: &TeamEntry, role: &str, member_name: &str, member_dir: &Path) -> Result<()> {
+    profile::extract_member_to(&team.profile, role, member_dir)?;
 
     // Finalize member manifest: .botminter.yml → botminter.yml with name added
-    finalize_member_manifest(&member_dir, &member_name)?;
+    finalize_member_manifest(member_dir, member_name)?;
+
+    // Fill in {{ member_name }} etc. in PROMPT.md and CLAUDE.md
+    let vars = member_templates::vars(member_dir, &team.name, &team.github_repo)?;
+    member_templates::render_member_files(member_dir, &vars).with_context(|| {
+        format!(
+            "The {} skeleton in profile '{}' has a template error",
+            role, team.profile
+        )
+    })?;
+
+    let mut findings = Findings::default();
+    let rel = format!("team/{}-{}", role, member_name);
+    team_verify::check_member_dir(member_dir, &rel, &mut findings);
+    let errors: Vec<String> = findings
+        .0
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .map(|f| format!("  {}: {}", f.file, f.message))
+        .collect();
+    if !errors.is_empty() {
+        bail!(
+            "The {} skeleton in profile '{}' doesn't produce a complete member:\n{}",
+            role,
+            team.profile,
+            errors.join("\n")
+        );
+    }
+    Ok(())
+}
 
-    // Git add + commit (no auto-push)
-    run_git(
-        &team_repo,
-        &["add", &format!("team/{}/", member_dir_name)],
-    )?;
-    let commit_msg = format!("feat: hire {} as {}", role, member_name);
-    run_git(&team_repo, &["commit", "-m", &commit_msg])?;
+/// Stages and commits only the member's directory (no auto-push), leaving
+/// anything else staged in the team repo out of the commit.
+fn commit_member(team_repo: &Path, member_dir_name: &str, message: &str) -> Result<()> {
+    let path = format!("team/{}/", member_dir_name);
+    run_git(team_repo, &["add", &path])?;
+    run_git(team_repo, &["commit", "-m", message, "--", &path])
+}
 
-    println!(
-        "Hired {} as {} in team '{}'.",
-        role, member_name, team.name
-    );
+/// Undoes a hire that failed after the member was moved into the team repo:
+/// unstages and removes its directory. Best effort, since it runs on the way
+/// out of an error.
+fn rollback(team_repo: &Path, member_dir: &Path, member_dir_name: &str) {
+    let path = format!("team/{}/", member_dir_name);
+    let _ = run_git(team_repo, &["reset", "-q", "--", &path]);
+    let _ = fs::remove_dir_all(member_dir);
+}
+
+/// Enforces the role's `max` from the team repo's botminter.yml.
+fn check_capacity(
+    manifest: &profile::ProfileManifest,
+    team_repo: &Path,
+    role: &str,
+    team_name: &str,
+) -> Result<()> {
+    let Some(role_def) = manifest.roles.iter().find(|r| r.name == role) else {
+        return Ok(());
+    };
+    let Some(max) = role_def.max else {
+        return Ok(());
+    };
 
+    let members_dir = team_repo.join("team");
+    let mut hired = Vec::new();
+    if members_dir.is_dir() {
+        hired = members::list_names(&members_dir)?;
+        hired.retain(|name| profile::role_of_member_dir(&manifest.roles, name) == Some(role));
+    }
+
+    if hired.len() as u32 >= max {
+        bail!(
+            "Role '{}' is at capacity in team '{}' ({} allowed, hired: {}).\n\
+             To raise the limit, edit `max` for this role in the team repo's botminter.yml.",
+            role,
+            team_name,
+            role_def.capacity().unwrap_or_default(),
+            hired.join(", ")
+        );
+    }
     Ok(())
 }
 
-/// Computes the next auto-suffix for a role by scanning existing member dirs.
+/// Refuses to hire over a leftover workspace or state.json entry for the
+/// member, unless `adopt_existing` is set. Adopting keeps the
+/// workspace and drops a stale state entry; a member that is still running
+/// is never adopted. Returns whether a leftover workspace is adopted, and the
+/// stale state entry to drop once the hire completes.
+fn check_leftovers(
+    team: &TeamEntry,
+    runtime_state: &RuntimeState,
+    role: &str,
+    member_name: &str,
+    adopt_existing: bool,
+) -> Result<(bool, Option<String>)> {
+    let member_dir_name = format!("{}-{}", role, member_name);
+    let state_key = format!("{}/{}", team.name, member_dir_name);
+    let workspace = team.path.join(&member_dir_name);
+    let runtime = runtime_state.members.get(&state_key);
+
+    if let Some(rt) = runtime {
+        if procutil::is_alive(rt.pid) {
+            bail!(
+                "A member named '{}' is still running in team '{}' (PID {}, workspace {}), \
+                 although it is not in the team repo.\n\
+                 Stop it first:  bm stop -t {}",
+                member_dir_name,
+                team.name,
+                rt.pid,
+                rt.workspace.display(),
+                team.name
+            );
+        }
+    }
+
+    let has_workspace = workspace.exists();
+    if !has_workspace && runtime.is_none() {
+        return Ok((false, None));
+    }
+
+    if !adopt_existing {
+        let mut found = Vec::new();
+        if has_workspace {
+            found.push(format!("  - workspace directory {}", workspace.display()));
+        }
+        if runtime.is_some() {
+            found.push(format!(
+                "  - stale entry '{}' in ~/.botminter/state.json",
+                state_key
+            ));
+        }
+        bail!(
+            "Member '{}' is not in the team repo, but leftovers from an earlier member \
+             with that name exist:\n{}\n\n\
+             To reuse them:   bm hire {} --name {} --adopt-existing -t {}\n\
+             To start fresh:  remove the leftovers, or choose a different --name",
+            member_dir_name,
+            found.join("\n"),
+            role,
+            member_name,
+            team.name
+        );
+    }
+
+    Ok((has_workspace, runtime.is_some().then_some(state_key)))
+}
+
+/// Returns member dir names that still have a workspace or state.json entry
+/// for this team, so auto-generated names avoid them.
+fn leftover_names(team: &TeamEntry, runtime_state: &RuntimeState) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    if team.path.is_dir() {
+        for entry in fs::read_dir(&team.path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                names.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+    let prefix = format!("{}/", team.name);
+    names.extend(
+        runtime_state
+            .members
+            .keys()
+            .filter_map(|key| key.strip_prefix(&prefix))
+            .map(str::to_string),
+    );
+    Ok(names)
+}
+
+/// Computes the next auto-suffix for a role by scanning existing member dirs
+/// plus `leftovers` (member dir names claimed outside the team repo).
 /// Returns a 2-digit, zero-padded string (e.g., "01", "02").
 /// Fills gaps: if 01 and 03 exist, returns "02".
-fn auto_suffix(team_repo: &Path, role: &str) -> Result<String> {
+fn auto_suffix(team_repo: &Path, role: &str, leftovers: &[String]) -> Result<String> {
     let team_members_dir = team_repo.join("team");
     let prefix = format!("{}-", role);
 
-    let mut used: Vec<u32> = Vec::new();
+    let mut names: Vec<String> = leftovers.to_vec();
 
     if team_members_dir.is_dir() {
-        for entry in fs::read_dir(&team_members_dir)? {
-            let entry = entry?;
-            if !entry.file_type()?.is_dir() {
-                continue;
-            }
-            let name = entry.file_name().to_string_lossy().to_string();
-            if name.starts_with(&prefix) {
-                let suffix = &name[prefix.len()..];
-                if let Ok(n) = suffix.parse::<u32>() {
-                    used.push(n);
-                }
-            }
-        }
+        names.extend(members::list_names(&team_members_dir)?);
     }
 
+    let mut used: Vec<u32> = names
+        .iter()
+        .filter_map(|name| name.strip_prefix(&prefix))
+        .filter_map(|suffix| suffix.parse::<u32>().ok())
+        .collect();
+
     used.sort();
+    used.dedup();
 
     // Fill gaps or increment from 1
     let mut next = 1u32;
@@ -127,7 +464,7 @@ mod tests {
         let team_repo = tmp.path();
         fs::create_dir_all(team_repo.join("team")).unwrap();
 
-        let result = auto_suffix(team_repo, "architect").unwrap();
+        let result = auto_suffix(team_repo, "architect", &[]).unwrap();
         assert_eq!(result, "01");
     }
 
@@ -137,7 +474,7 @@ mod tests {
         let team_repo = tmp.path();
         fs::create_dir_all(team_repo.join("team/architect-01")).unwrap();
 
-        let result = auto_suffix(team_repo, "architect").unwrap();
+        let result = auto_suffix(team_repo, "architect", &[]).unwrap();
         assert_eq!(result, "02");
     }
 
@@ -148,7 +485,7 @@ mod tests {
         fs::create_dir_all(team_repo.join("team/architect-01")).unwrap();
         fs::create_dir_all(team_repo.join("team/architect-03")).unwrap();
 
-        let result = auto_suffix(team_repo, "architect").unwrap();
+        let result = auto_suffix(team_repo, "architect", &[]).unwrap();
         assert_eq!(result, "02");
     }
 
@@ -160,7 +497,7 @@ mod tests {
         fs::create_dir_all(team_repo.join("team/architect-01")).unwrap();
 
         // "bob" is not numeric, so ignored. Next after 01 is 02.
-        let result = auto_suffix(team_repo, "architect").unwrap();
+        let result = auto_suffix(team_repo, "architect", &[]).unwrap();
         assert_eq!(result, "02");
     }
 
@@ -173,10 +510,22 @@ mod tests {
         fs::create_dir_all(team_repo.join("team/dev-01")).unwrap();
 
         // dev suffix is independent of architect
-        let result = auto_suffix(team_repo, "dev").unwrap();
+        let result = auto_suffix(team_repo, "dev", &[]).unwrap();
         assert_eq!(result, "02");
 
-        let result = auto_suffix(team_repo, "architect").unwrap();
+        let result = auto_suffix(team_repo, "architect", &[]).unwrap();
+        assert_eq!(result, "03");
+    }
+
+    #[test]
+    fn auto_suffix_skips_leftovers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let team_repo = tmp.path();
+        fs::create_dir_all(team_repo.join("team/architect-01")).unwrap();
+
+        // architect-02 has a leftover workspace or state entry
+        let leftovers = vec!["architect-02".to_string(), "team".to_string()];
+        let result = auto_suffix(team_repo, "architect", &leftovers).unwrap();
         assert_eq!(result, "03");
     }
 }
@@ -3,9 +3,16 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{bail, Context, Result};
+use tracing::debug;
 
-use crate::config::{self, BotminterConfig, Credentials, TeamEntry};
+use crate::commands::teams;
+use crate::config::{self, BotminterConfig, Credentials, GitHubApp, TeamEntry};
+use crate::member_templates;
+use crate::parse;
 use crate::profile;
+use crate::profile_checksums;
+use crate::repo_templates;
+use crate::telegram;
 
 /// Whether to create a new GitHub Project board or use an existing one.
 enum ProjectChoice {
@@ -17,6 +24,8 @@ enum ProjectChoice {
 pub fn run() -> Result<()> {
     // Prerequisite checks
     check_prerequisites()?;
+    // An existing config that can't be loaded fails here, before the wizard
+    load_or_default_config()?;
 
     // --- Wizard ---
     cliclack::intro("botminter — create a new team")?;
@@ -88,15 +97,16 @@ pub fn run() -> Result<()> {
     let project_choice = select_or_create_project(&token, github_owner, &team_name)?;
 
     let gh_token = Some(token);
+    let github_app = prompt_github_app()?;
 
-    let telegram_token: String = cliclack::input("Telegram bot token (optional, enter to skip)")
-        .default_input("")
-        .required(false)
-        .interact()?;
-    let telegram_bot_token = if telegram_token.is_empty() {
-        None
+    let (telegram_bot_token, telegram_chat_id) = if profile::uses_telegram(&selected_profile)? {
+        prompt_telegram()?
     } else {
-        Some(telegram_token)
+        let token: String = cliclack::input("Telegram bot token (optional, enter to skip)")
+            .default_input("")
+            .required(false)
+            .interact()?;
+        ((!token.is_empty()).then_some(token), None)
     };
 
     let manifest = profile::read_manifest(&selected_profile)?;
@@ -130,6 +140,11 @@ pub fn run() -> Result<()> {
             summary.push_str(&format!("\nProject board: existing (#{n})"));
         }
     }
+    match (&telegram_bot_token, telegram_chat_id) {
+        (Some(_), Some(chat_id)) => summary.push_str(&format!("\nTelegram: chat {}", chat_id)),
+        (Some(_), None) => summary.push_str("\nTelegram: bot token only"),
+        _ => {}
+    }
     if !members_to_hire.is_empty() {
         summary.push_str("\nMembers:");
         for (role, name) in &members_to_hire {
@@ -165,46 +180,15 @@ pub fn run() -> Result<()> {
     let team_repo = team_dir.join("team");
 
     if is_new_repo {
-        fs::create_dir_all(&team_repo).context("Failed to create team repo directory")?;
-
-        spinner.start("Initializing git repository...");
-        run_git(&team_repo, &["init", "-b", "main"])?;
-
-        spinner.start("Extracting profile content...");
-        profile::extract_profile_to(&selected_profile, &team_repo)?;
-
-        if !projects_to_add.is_empty() {
-            augment_manifest_with_projects(&team_repo, &projects_to_add)?;
-        }
-
-        fs::create_dir_all(team_repo.join("team")).context("Failed to create team/ dir")?;
-        fs::create_dir_all(team_repo.join("projects")).context("Failed to create projects/ dir")?;
-        fs::write(team_repo.join("team/.gitkeep"), "").ok();
-        fs::write(team_repo.join("projects/.gitkeep"), "").ok();
-
-        for (role, name) in &members_to_hire {
-            let member_dir_name = format!("{}-{}", role, name);
-            let member_dir = team_repo.join("team").join(&member_dir_name);
-            fs::create_dir_all(&member_dir)
-                .with_context(|| format!("Failed to create member dir {}", member_dir.display()))?;
-            profile::extract_member_to(&selected_profile, role, &member_dir)?;
-            finalize_member_manifest(&member_dir, name)?;
-        }
-
-        for (proj_name, _url) in &projects_to_add {
-            let proj_dir = team_repo.join("projects").join(proj_name);
-            fs::create_dir_all(proj_dir.join("knowledge"))
-                .with_context(|| format!("Failed to create projects/{}/knowledge/", proj_name))?;
-            fs::create_dir_all(proj_dir.join("invariants"))
-                .with_context(|| format!("Failed to create projects/{}/invariants/", proj_name))?;
-            fs::write(proj_dir.join("knowledge/.gitkeep"), "").ok();
-            fs::write(proj_dir.join("invariants/.gitkeep"), "").ok();
-        }
-
-        spinner.start("Creating initial commit...");
-        run_git(&team_repo, &["add", "-A"])?;
-        let commit_msg = format!("feat: initialize team repo ({} profile)", selected_profile);
-        run_git(&team_repo, &["commit", "-m", &commit_msg])?;
+        scaffold_team_repo(
+            &team_repo,
+            &selected_profile,
+            &team_name,
+            &github_repo,
+            &members_to_hire,
+            &projects_to_add,
+            &spinner,
+        )?;
 
         spinner.start("Creating GitHub repository...");
         create_github_repo(&team_repo, &github_repo, gh_token.as_deref())?;
@@ -213,20 +197,44 @@ pub fn run() -> Result<()> {
         clone_existing_repo(&team_dir, &github_repo, gh_token.as_deref())?;
     }
 
+    // Unknown fields are usually typos; warn, but keep going since a repo
+    // written by a newer bm may carry fields this version doesn't know.
+    let manifest_path = team_repo.join("botminter.yml");
+    if let Ok(contents) = fs::read_to_string(&manifest_path) {
+        if let Err(e) = profile::parse_manifest_strict(&manifest_path, &contents) {
+            spinner.stop("Team repo ready");
+            cliclack::log::warning(format!(
+                "{:#}\nContinuing with lenient parsing; unrecognized fields are ignored. \
+                 Run `bm profiles validate {}` after fixing.",
+                e,
+                manifest_path.display()
+            ))?;
+        }
+    }
+
     // 3. Register in config (early — before GitHub metadata ops so a failure
     //    in labels/project doesn't leave ~/.botminter in a broken state)
     spinner.start("Registering team...");
-    let mut cfg = load_or_default_config();
+    let config_lock = config::lock()?;
+    let mut cfg = load_or_default_config()?;
 
     let team_entry = TeamEntry {
         name: team_name.clone(),
         path: team_dir.clone(),
         profile: selected_profile.clone(),
         github_repo: github_repo.clone(),
+        extra_repos: Vec::new(),
+        budget: None,
         credentials: Credentials {
             gh_token: gh_token.clone(),
             telegram_bot_token: telegram_bot_token.clone(),
+            telegram_chat_id,
+            slack_bot_token: None,
+            slack_channel: None,
             webhook_secret: None,
+            github_app,
+            smtp: None,
+            notifiers: Vec::new(),
         },
     };
     cfg.teams.push(team_entry);
@@ -237,6 +245,7 @@ pub fn run() -> Result<()> {
     cfg.workzone = workzone.clone();
 
     config::save(&cfg)?;
+    drop(config_lock);
 
     // 4. Bootstrap labels (idempotent via --force)
     spinner.start("Bootstrapping labels...");
@@ -261,6 +270,39 @@ pub fn run() -> Result<()> {
         );
     }
 
+    // Issue and PR templates, from the team repo's manifest (an existing
+    // repo may differ from the profile). Failures only warn;
+    // `bm projects sync` retries.
+    let team_manifest: profile::ProfileManifest = fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|contents| parse::yaml(&manifest_path, &contents).ok())
+        .unwrap_or_else(|| manifest.clone());
+    if !repo_templates::files(&team_manifest).is_empty() {
+        spinner.start("Pushing issue and PR templates...");
+        let team_entry = cfg
+            .teams
+            .iter()
+            .find(|t| t.name == team_name)
+            .expect("team was just registered");
+        let mut failures = Vec::new();
+        for (repo, result) in
+            repo_templates::sync(team_entry, &team_manifest, gh_token.as_deref())
+        {
+            if let Err(e) = result {
+                failures.push(format!("{}: {:#}", repo, e));
+            }
+        }
+        if failures.is_empty() {
+            spinner.stop("Issue and PR templates pushed");
+        } else {
+            spinner.stop("Some templates weren't pushed");
+            cliclack::log::warning(format!(
+                "{}\nRun `bm projects sync` to retry.",
+                failures.join("\n")
+            ))?;
+        }
+    }
+
     // 5. Create or sync GitHub Project board
     let owner = github_repo.split('/').next().unwrap_or(&github_repo);
     let project_number = match project_choice {
@@ -302,11 +344,18 @@ pub fn run() -> Result<()> {
         );
         cliclack::log::info(format!(
             "Set up role-based views at: {}\n\
-             Run `bm projects sync` anytime to see view instructions.",
+             Run `bm projects sync --create-views` to create them.",
             project_url,
         ))?;
     }
 
+    if !manifest.seed_issues.is_empty() {
+        cliclack::log::info(format!(
+            "The profile suggests {} starter issue(s). Run `bm projects seed` to create them.",
+            manifest.seed_issues.len(),
+        ))?;
+    }
+
     spinner.stop("Done!");
     cliclack::outro(format!(
         "Team '{}' created at {}",
@@ -317,8 +366,189 @@ pub fn run() -> Result<()> {
     Ok(())
 }
 
+/// Handles `bm init --from-existing <owner/repo>`: joins a team another
+/// operator created, by cloning its team repo and registering it in config.
+///
+/// The repo already holds the profile content, members, and projects, and
+/// its labels and project board exist, so nothing is extracted or created on
+/// GitHub. The GitHub token comes from `GH_TOKEN` or `gh auth token`.
+pub fn join_existing(repo: &str, name: Option<&str>) -> Result<()> {
+    check_prerequisites()?;
+    if !teams::is_repo_slug(repo) {
+        bail!("'{}' is not a GitHub repo. Use owner/name, e.g. my-org/my-team.", repo);
+    }
+    let cfg = load_or_default_config()?;
+    let team_name = name
+        .unwrap_or_else(|| repo.split('/').nth(1).unwrap_or(repo))
+        .to_string();
+    if team_name.contains(' ') {
+        bail!("Team name '{}' can't contain spaces. Pass --name.", team_name);
+    }
+    if let Some(existing) = cfg
+        .teams
+        .iter()
+        .find(|t| t.name == team_name || t.github_repo.eq_ignore_ascii_case(repo))
+    {
+        bail!(
+            "Team '{}' ({}) is already registered. Pass a different --name, or \
+             `bm teams remove {}` first.",
+            existing.name,
+            existing.github_repo,
+            existing.name
+        );
+    }
+    let team_dir = cfg.workzone.join(&team_name);
+    if team_dir.exists() {
+        bail!(
+            "Directory '{}' already exists. Pass a different --name, or delete it and retry.",
+            team_dir.display()
+        );
+    }
+
+    let token = detect_gh_token().context(
+        "No GitHub token found. Set GH_TOKEN or run `gh auth login`, then retry.",
+    )?;
+    validate_gh_token(&token)?;
+
+    fs::create_dir_all(&team_dir)
+        .with_context(|| format!("Failed to create team directory at {}", team_dir.display()))?;
+    let registered = clone_existing_repo(&team_dir, repo, Some(&token))
+        .and_then(|()| existing_team_profile(&team_dir.join("team"), repo))
+        .and_then(|profile_name| {
+            let _lock = config::lock()?;
+            let mut cfg = load_or_default_config()?;
+            cfg.teams.push(TeamEntry {
+                name: team_name.clone(),
+                path: team_dir.clone(),
+                profile: profile_name.clone(),
+                github_repo: repo.to_string(),
+                extra_repos: Vec::new(),
+                budget: None,
+                credentials: Credentials {
+                    gh_token: Some(token.clone()),
+                    ..Credentials::default()
+                },
+            });
+            if cfg.teams.len() == 1 {
+                cfg.default_team = Some(team_name.clone());
+            }
+            config::save(&cfg)?;
+            Ok(profile_name)
+        });
+    let profile_name = match registered {
+        Ok(profile_name) => profile_name,
+        Err(e) => {
+            // Leave nothing behind, so the same command can be retried
+            let _ = fs::remove_dir_all(&team_dir);
+            return Err(e);
+        }
+    };
+
+    let members =
+        crate::members::list_names(&team_dir.join("team").join("team")).unwrap_or_default();
+    println!(
+        "Joined team '{}' from {} (profile {}, {} member(s)) at {}.",
+        team_name,
+        repo,
+        profile_name,
+        members.len(),
+        team_dir.display()
+    );
+    println!(
+        "Next: `bm teams sync -t {}` to create the member workspaces. Chat, webhook, and \
+         notification credentials aren't in the repo; add them under the team's credentials \
+         in ~/.botminter/config.yml.",
+        team_name
+    );
+    Ok(())
+}
+
+/// Checks that `team_repo`, cloned from `repo`, is a team repo this `bm` can
+/// work with, and returns its profile.
+fn existing_team_profile(team_repo: &Path, repo: &str) -> Result<String> {
+    let manifest_path = team_repo.join("botminter.yml");
+    let contents = match fs::read_to_string(&manifest_path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => bail!(
+            "{} has no botminter.yml at its root, so it isn't a botminter team repo.",
+            repo
+        ),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", manifest_path.display()))
+        }
+    };
+    let manifest: profile::ProfileManifest = parse::yaml(&manifest_path, &contents)?;
+    if !profile::list_profiles().contains(&manifest.name) {
+        bail!(
+            "{} uses profile '{}', which this version of `bm` doesn't carry. \
+             Upgrade `bm`, or ask the team's operators which version they use.",
+            repo,
+            manifest.name
+        );
+    }
+    profile::check_schema_version(&manifest.name, &manifest.schema_version)?;
+    Ok(manifest.name)
+}
+
+/// Lays out a new team repo at `team_repo` — profile content, the `(role,
+/// name)` members, and the `(name, fork_url)` projects — and makes the initial
+/// commit.
+pub(crate) fn scaffold_team_repo(
+    team_repo: &Path,
+    profile_name: &str,
+    team_name: &str,
+    github_repo: &str,
+    members: &[(String, String)],
+    projects: &[(String, String)],
+    spinner: &cliclack::ProgressBar,
+) -> Result<()> {
+    fs::create_dir_all(team_repo).context("Failed to create team repo directory")?;
+
+    spinner.start("Initializing git repository...");
+    run_git(team_repo, &["init", "-b", "main"])?;
+
+    spinner.start("Extracting profile content...");
+    profile::extract_profile_to(profile_name, team_repo)?;
+
+    if !projects.is_empty() {
+        augment_manifest_with_projects(team_repo, projects)?;
+    }
+
+    fs::create_dir_all(team_repo.join("team")).context("Failed to create team/ dir")?;
+    fs::create_dir_all(team_repo.join("projects")).context("Failed to create projects/ dir")?;
+    fs::write(team_repo.join("team/.gitkeep"), "").ok();
+    fs::write(team_repo.join("projects/.gitkeep"), "").ok();
+
+    for (role, name) in members {
+        let member_dir_name = format!("{}-{}", role, name);
+        let member_dir = team_repo.join("team").join(&member_dir_name);
+        fs::create_dir_all(&member_dir)
+            .with_context(|| format!("Failed to create member dir {}", member_dir.display()))?;
+        profile::extract_member_to(profile_name, role, &member_dir)?;
+        finalize_member_manifest(&member_dir, name)?;
+        let vars = member_templates::vars(&member_dir, team_name, github_repo)?;
+        member_templates::render_member_files(&member_dir, &vars)?;
+    }
+
+    for (proj_name, _url) in projects {
+        let proj_dir = team_repo.join("projects").join(proj_name);
+        fs::create_dir_all(proj_dir.join("knowledge"))
+            .with_context(|| format!("Failed to create projects/{}/knowledge/", proj_name))?;
+        fs::create_dir_all(proj_dir.join("invariants"))
+            .with_context(|| format!("Failed to create projects/{}/invariants/", proj_name))?;
+        fs::write(proj_dir.join("knowledge/.gitkeep"), "").ok();
+        fs::write(proj_dir.join("invariants/.gitkeep"), "").ok();
+    }
+
+    spinner.start("Creating initial commit...");
+    run_git(team_repo, &["add", "-A"])?;
+    let commit_msg = format!("feat: initialize team repo ({} profile)", profile_name);
+    run_git(team_repo, &["commit", "-m", &commit_msg])?;
+    Ok(())
+}
+
 /// Checks that `git` is available. Errors if not found.
-fn check_prerequisites() -> Result<()> {
+pub(crate) fn check_prerequisites() -> Result<()> {
     let mut missing = Vec::new();
     if which::which("git").is_err() {
         missing.push("git — https://git-scm.com/");
@@ -355,6 +585,7 @@ fn expand_tilde(path: &str) -> PathBuf {
 
 /// Run a git command in the given directory.
 pub(crate) fn run_git(dir: &Path, args: &[&str]) -> Result<()> {
+    debug!("Running git {} in {}", args.join(" "), dir.display());
     let output = Command::new("git")
         .args(args)
         .current_dir(dir)
@@ -420,6 +651,136 @@ fn collect_members(roles: &[String]) -> Result<Vec<(String, String)>> {
     Ok(members)
 }
 
+/// Optionally collect GitHub App credentials so members run on short-lived,
+/// repo-scoped installation tokens instead of the personal token.
+fn prompt_github_app() -> Result<Option<GitHubApp>> {
+    let use_app: bool =
+        cliclack::confirm("Authenticate members with a GitHub App instead of your token?")
+            .initial_value(false)
+            .interact()?;
+    if !use_app {
+        return Ok(None);
+    }
+
+    let app_id: String = cliclack::input("GitHub App ID")
+        .validate(|input: &String| {
+            if input.trim().is_empty() {
+                Err("App ID cannot be empty")
+            } else {
+                Ok(())
+            }
+        })
+        .interact()?;
+    let installation_id: String = cliclack::input("Installation ID")
+        .validate(|input: &String| match input.trim().parse::<u64>() {
+            Ok(_) => Ok(()),
+            Err(_) => Err("Installation ID must be a number"),
+        })
+        .interact()?;
+    let key_path: String = cliclack::input("Path to the app's private key (.pem)")
+        .validate(|input: &String| {
+            if expand_tilde(input).is_file() {
+                Ok(())
+            } else {
+                Err("File not found")
+            }
+        })
+        .interact()?;
+
+    Ok(Some(GitHubApp {
+        app_id: app_id.trim().to_string(),
+        installation_id: installation_id.trim().parse()?,
+        private_key_path: expand_tilde(&key_path),
+    }))
+}
+
+/// Seconds each attempt waits for a message to the bot.
+const TELEGRAM_DISCOVERY_WAIT_SECS: u64 = 60;
+
+/// Prompts for the Telegram bot of a profile whose members use it: verifies
+/// the token with `getMe`, then finds the chat id by waiting for a message
+/// to the bot. Returns the token and chat id, either of which may be skipped.
+fn prompt_telegram() -> Result<(Option<String>, Option<i64>)> {
+    let (token, bot) = loop {
+        let token: String =
+            cliclack::input("Telegram bot token (from @BotFather; enter to skip)")
+                .default_input("")
+                .required(false)
+                .interact()?;
+        let token = token.trim().to_string();
+        if token.is_empty() {
+            cliclack::log::warning(
+                "This profile's members ask for approval over Telegram and can't reach you \
+                 without a bot. Add telegram_bot_token and telegram_chat_id under the team's \
+                 credentials in ~/.botminter/config.yml later.",
+            )?;
+            return Ok((None, None));
+        }
+        let spinner = cliclack::spinner();
+        spinner.start("Verifying bot token...");
+        match telegram::get_me(&token) {
+            Ok(bot) => {
+                spinner.stop(format!("Bot verified: {} (@{})", bot.first_name, bot.username));
+                break (token, bot);
+            }
+            Err(e) => {
+                spinner.stop("Bot token check failed");
+                cliclack::log::warning(format!("{:#}", e))?;
+            }
+        }
+    };
+
+    cliclack::note(
+        "Telegram chat",
+        format!(
+            "Send any message to @{} from the chat members should use.\n\
+             For a group, add the bot to the group and send the message there.",
+            bot.username
+        ),
+    )?;
+    loop {
+        let spinner = cliclack::spinner();
+        spinner.start("Waiting for your message...");
+        match telegram::discover_chats(&token, TELEGRAM_DISCOVERY_WAIT_SECS) {
+            Ok(chats) if !chats.is_empty() => {
+                spinner.stop(format!("Found {} chat(s)", chats.len()));
+                let items: Vec<(i64, String, String)> = chats
+                    .iter()
+                    .map(|c| (c.id, c.label.clone(), format!("{} chat {}", c.kind, c.id)))
+                    .collect();
+                let chat_id: i64 = cliclack::select("Which chat should members use?")
+                    .items(&items)
+                    .interact()?;
+                return Ok((Some(token), Some(chat_id)));
+            }
+            Ok(_) => spinner.stop("No messages received"),
+            Err(e) => {
+                spinner.stop("Could not read the bot's messages");
+                cliclack::log::warning(format!("{:#}", e))?;
+            }
+        }
+
+        let next: &str = cliclack::select("How do you want to continue?")
+            .item("retry", "Wait again", "")
+            .item("manual", "Enter the chat id", "e.g. from @userinfobot")
+            .item("skip", "Skip", "set telegram_chat_id in config.yml later")
+            .interact()?;
+        match next {
+            "retry" => continue,
+            "manual" => {
+                let chat_id: String = cliclack::input("Telegram chat id")
+                    .validate(|input: &String| match input.trim().parse::<i64>() {
+                        Ok(_) => Ok(()),
+                        Err(_) => Err("Chat id must be a number, e.g. 123456789 or -100123456789"),
+                    })
+                    .interact()?;
+                return Ok((Some(token), Some(chat_id.trim().parse()?)));
+            }
+            _ => return Ok((Some(token), None)),
+        }
+    }
+}
+
 /// Collect projects to add during init (optional).
 /// When `gh_token` and `org` are provided, offers interactive repo selection.
 fn collect_projects(
@@ -533,7 +894,7 @@ fn augment_manifest_with_projects(
     let mut manifest: profile::ProfileManifest = {
         let contents = fs::read_to_string(&manifest_path)
             .context("Failed to read botminter.yml from team repo")?;
-        serde_yml::from_str(&contents).context("Failed to parse botminter.yml")?
+        parse::yaml(&manifest_path, &contents)?
     };
 
     manifest.projects = projects
@@ -561,8 +922,7 @@ pub(crate) fn finalize_member_manifest(member_dir: &Path, name: &str) -> Result<
             .context("Failed to read .botminter.yml template")?;
 
         // Parse, augment with name, write back
-        let mut value: serde_yml::Value =
-            serde_yml::from_str(&contents).context("Failed to parse .botminter.yml")?;
+        let mut value: serde_yml::Value = parse::yaml(&template_path, &contents)?;
 
         if let serde_yml::Value::Mapping(ref mut map) = value {
             map.insert(
@@ -576,18 +936,24 @@ pub(crate) fn finalize_member_manifest(member_dir: &Path, name: &str) -> Result<
 
         // Write as botminter.yml (no dot prefix)
         let manifest_path = member_dir.join("botminter.yml");
-        fs::write(&manifest_path, augmented)
+        fs::write(&manifest_path, &augmented)
             .context("Failed to write member botminter.yml")?;
 
         // Remove the template (.botminter.yml)
         fs::remove_file(&template_path).ok();
+
+        // botminter.yml now stands in for the template as a profile-origin file
+        profile_checksums::update(member_dir, |checksums| {
+            checksums.forget(Path::new(".botminter.yml"));
+            checksums.record(Path::new("botminter.yml"), augmented.as_bytes());
+        })?;
     }
 
     Ok(())
 }
 
 /// Creates a GitHub repo and pushes the team repo.
-fn create_github_repo(team_repo: &Path, repo_name: &str, gh_token: Option<&str>) -> Result<()> {
+pub(crate) fn create_github_repo(team_repo: &Path, repo_name: &str, gh_token: Option<&str>) -> Result<()> {
     let mut cmd = Command::new("gh");
     cmd.args([
         "repo",
@@ -680,8 +1046,9 @@ fn mask_token(token: &str) -> String {
     format!("{}...{}", &token[..4], &token[token.len() - 4..])
 }
 
-/// Validates that a GitHub token works by calling `gh api user`.
-fn validate_gh_token(token: &str) -> Result<()> {
+/// Validates that a GitHub token works by calling `gh api user`. Returns the
+/// authenticated login.
+pub(crate) fn validate_gh_token(token: &str) -> Result<String> {
     let output = Command::new("gh")
         .args(["api", "user", "--jq", ".login"])
         .env("GH_TOKEN", token)
@@ -701,12 +1068,12 @@ fn validate_gh_token(token: &str) -> Result<()> {
 
     let login = String::from_utf8_lossy(&output.stdout).trim().to_string();
     cliclack::log::info(format!("Authenticated as: {}", login))?;
-    Ok(())
+    Ok(login)
 }
 
-/// Detects an existing GH_TOKEN or prompts the user for one.
-fn detect_or_prompt_gh_token() -> Result<String> {
-    let detected = std::env::var("GH_TOKEN")
+/// The token in `GH_TOKEN`, or else the one `gh` is logged in with.
+fn detect_gh_token() -> Option<String> {
+    std::env::var("GH_TOKEN")
         .ok()
         .filter(|t| !t.is_empty())
         .or_else(|| {
@@ -717,9 +1084,12 @@ fn detect_or_prompt_gh_token() -> Result<String> {
                 .filter(|o| o.status.success())
                 .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
                 .filter(|t| !t.is_empty())
-        });
+        })
+}
 
-    if let Some(existing) = detected {
+/// Detects an existing GH_TOKEN or prompts the user for one.
+pub(crate) fn detect_or_prompt_gh_token() -> Result<String> {
+    if let Some(existing) = detect_gh_token() {
         let masked = mask_token(&existing);
         let use_existing: bool =
             cliclack::confirm(format!("GitHub token detected ({}). Use it?", masked))
@@ -960,7 +1330,7 @@ fn list_gh_repos(gh_token: &str, owner: &str) -> Result<Vec<String>> {
 }
 
 /// Bootstraps labels on the GitHub repo from the profile manifest.
-fn bootstrap_labels(
+pub fn bootstrap_labels(
     repo: &str,
     labels: &[profile::LabelDef],
     gh_token: Option<&str>,
@@ -1045,7 +1415,7 @@ pub fn create_github_label(
 /// Creates a GitHub Project (v2), syncs the Status field options, and returns the project number.
 /// Uses the `updateProjectV2Field` GraphQL mutation to replace the built-in
 /// Status field's default options with the profile's status definitions.
-fn create_github_project(
+pub(crate) fn create_github_project(
     owner: &str,
     team_name: &str,
     statuses: &[profile::StatusDef],
@@ -1230,11 +1600,22 @@ pub fn find_project_number(
         .with_context(|| format!("No project named '{}' found for owner '{}'", board_title, owner))
 }
 
-/// Loads the existing config or returns a fresh default.
-fn load_or_default_config() -> BotminterConfig {
-    config::load().unwrap_or_else(|_| BotminterConfig {
+/// Loads the existing config, or returns a fresh default if there is none
+/// yet. A config that exists but can't be loaded is an error, so it is never
+/// saved over.
+pub(crate) fn load_or_default_config() -> Result<BotminterConfig> {
+    if config::config_path()?.exists() {
+        return config::load();
+    }
+    Ok(BotminterConfig {
+        version: config::CONFIG_VERSION,
         workzone: default_workzone_path(),
         default_team: None,
+        token_expiry_warn_days: None,
+        logs: None,
+        timestamps: None,
+        workspace: None,
+        state_backend: None,
         teams: Vec::new(),
     })
 }
@@ -1,10 +1,14 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
+use serde::Serialize;
 
+use crate::commands::init::run_git;
 use crate::config::{self, TeamEntry};
+use crate::parse;
 use crate::profile;
+use crate::session;
 
 /// Handles `bm knowledge list [-t team] [--scope <scope>]`.
 pub fn list(team_flag: Option<&str>, scope_filter: Option<&str>) -> Result<()> {
@@ -121,6 +125,414 @@ pub fn show(path: &str, team_flag: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// Knowledge and invariant scopes, as accepted by `--scope`.
+const SCOPES: &[&str] = &["team", "project", "member", "member-project"];
+
+/// Options for `bm knowledge grep`.
+pub struct GrepOptions {
+    pub ignore_case: bool,
+    pub context: usize,
+    pub json: bool,
+}
+
+/// A matching line in a knowledge or invariant file.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct KnowledgeMatch {
+    pub scope: &'static str,
+    /// Path relative to the team repo root (usable with `bm knowledge show`).
+    pub path: String,
+    /// 1-based line number.
+    pub line: usize,
+    pub text: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub before: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<String>,
+}
+
+/// Handles `bm knowledge grep <pattern> [-i] [-C n] [--json] [-t team] [--scope <scope>]`.
+pub fn grep(
+    pattern: &str,
+    options: &GrepOptions,
+    team_flag: Option<&str>,
+    scope_filter: Option<&str>,
+) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let team_repo = team.path.join("team");
+
+    let team_schema = read_team_schema(&team_repo)?;
+    profile::require_current_schema(&team.name, &team_schema)?;
+
+    if let Some(scope) = scope_filter {
+        if !SCOPES.contains(&scope) {
+            bail!(
+                "Unknown scope '{}'. Valid scopes: {}",
+                scope,
+                SCOPES.join(", ")
+            );
+        }
+    }
+
+    let mut matches = Vec::new();
+    for (scope, path) in scoped_files(&team_repo, scope_filter) {
+        let Ok(contents) = fs::read_to_string(team_repo.join(&path)) else {
+            continue;
+        };
+        for (line, text, before, after) in
+            search_lines(&contents, pattern, options.ignore_case, options.context)
+        {
+            matches.push(KnowledgeMatch {
+                scope,
+                path: path.clone(),
+                line,
+                text,
+                before,
+                after,
+            });
+        }
+    }
+
+    if options.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&matches).context("Failed to serialize matches")?
+        );
+        return Ok(());
+    }
+
+    if matches.is_empty() {
+        println!("No matches for '{}'.", pattern);
+        return Ok(());
+    }
+
+    for (i, m) in matches.iter().enumerate() {
+        if options.context > 0 && i > 0 {
+            println!("--");
+        }
+        let first = m.line - m.before.len();
+        for (offset, text) in m.before.iter().enumerate() {
+            println!("{}-{}-{}", m.path, first + offset, text);
+        }
+        println!("{}:{}:{}", m.path, m.line, m.text);
+        for (offset, text) in m.after.iter().enumerate() {
+            println!("{}-{}-{}", m.path, m.line + 1 + offset, text);
+        }
+    }
+
+    let mut files: Vec<&str> = matches.iter().map(|m| m.path.as_str()).collect();
+    files.dedup();
+    println!();
+    println!(
+        "{} match{} in {} file{}",
+        matches.len(),
+        if matches.len() == 1 { "" } else { "es" },
+        files.len(),
+        if files.len() == 1 { "" } else { "s" }
+    );
+
+    Ok(())
+}
+
+/// Collects `(scope, path)` for every `.md` file in the knowledge and
+/// invariant directories of the selected scopes, paths relative to `team_repo`.
+pub(crate) fn scoped_files(team_repo: &Path, scope_filter: Option<&str>) -> Vec<(&'static str, String)> {
+    let wants = |scope: &str| scope_filter.is_none() || scope_filter == Some(scope);
+    let mut bases: Vec<(&'static str, String, &[&str])> = Vec::new();
+    const BOTH: &[&str] = &["knowledge", "invariants"];
+
+    if wants("team") {
+        bases.push(("team", String::new(), BOTH));
+    }
+    if wants("project") {
+        for project in list_subdirs(&team_repo.join("projects")) {
+            bases.push(("project", format!("projects/{}/", project), BOTH));
+        }
+    }
+    let members = list_subdirs(&team_repo.join("team"));
+    if wants("member") {
+        for member in &members {
+            bases.push(("member", format!("team/{}/", member), BOTH));
+        }
+    }
+    if wants("member-project") {
+        for member in &members {
+            let prefix = format!("team/{}/projects", member);
+            for project in list_subdirs(&team_repo.join(&prefix)) {
+                bases.push(("member-project", format!("{}/{}/", prefix, project), &["knowledge"]));
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    for (scope, prefix, subdirs) in bases {
+        for subdir in subdirs {
+            let rel_dir = format!("{}{}", prefix, subdir);
+            let mut found = Vec::new();
+            collect_md_files(&team_repo.join(&rel_dir), &rel_dir, &mut found);
+            found.sort();
+            files.extend(found.into_iter().map(|path| (scope, path)));
+        }
+    }
+    files
+}
+
+/// Recursively collects `.md` files under `dir`, as `rel_dir/...` paths.
+fn collect_md_files(dir: &Path, rel_dir: &str, out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let rel = format!("{}/{}", rel_dir, name);
+        match entry.file_type() {
+            Ok(ft) if ft.is_dir() && !name.starts_with('.') => {
+                collect_md_files(&entry.path(), &rel, out)
+            }
+            Ok(ft) if ft.is_file() && name.ends_with(".md") => out.push(rel),
+            _ => {}
+        }
+    }
+}
+
+/// Finds lines containing `pattern`, returning `(line number, line, context
+/// before, context after)` for each.
+fn search_lines(
+    contents: &str,
+    pattern: &str,
+    ignore_case: bool,
+    context: usize,
+) -> Vec<(usize, String, Vec<String>, Vec<String>)> {
+    let needle = if ignore_case {
+        pattern.to_lowercase()
+    } else {
+        pattern.to_string()
+    };
+    let lines: Vec<&str> = contents.lines().collect();
+    let owned = |range: &[&str]| range.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| {
+            if ignore_case {
+                line.to_lowercase().contains(&needle)
+            } else {
+                line.contains(&needle)
+            }
+        })
+        .map(|(i, line)| {
+            let before = owned(&lines[i.saturating_sub(context)..i]);
+            let after = owned(&lines[i + 1..(i + 1 + context).min(lines.len())]);
+            (i + 1, line.to_string(), before, after)
+        })
+        .collect()
+}
+
+/// Where `bm knowledge add` puts the file within its scope.
+pub struct AddTarget<'a> {
+    pub project: Option<&'a str>,
+    pub member: Option<&'a str>,
+    pub invariant: bool,
+}
+
+/// Where the content of a new knowledge file comes from.
+pub enum AddSource {
+    /// A heading derived from the file name.
+    Template,
+    /// An existing file, copied verbatim.
+    File(PathBuf),
+    /// The template, then edited in `$EDITOR`.
+    Editor,
+}
+
+/// Handles `bm knowledge add <scope> <name> [--project p] [--member m]
+/// [--invariant] [--from-file f | --edit] [-t team]`.
+pub fn add(
+    scope: &str,
+    name: &str,
+    target: &AddTarget,
+    source: AddSource,
+    team_flag: Option<&str>,
+) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let team_repo = team.path.join("team");
+
+    let team_schema = read_team_schema(&team_repo)?;
+    profile::require_current_schema(&team.name, &team_schema)?;
+
+    let dir = scope_dir(&team_repo, scope, target)?;
+    if name.is_empty() || name.starts_with('/') || name.split('/').any(|part| part == "..") {
+        bail!("Invalid file name '{}'", name);
+    }
+    let file_name = if name.ends_with(".md") {
+        name.to_string()
+    } else {
+        format!("{}.md", name)
+    };
+    let rel_path = format!("{}/{}", dir, file_name);
+    validate_knowledge_path(&rel_path)?;
+
+    let file_path = team_repo.join(&rel_path);
+    if file_path.exists() {
+        bail!(
+            "{} already exists. To change it, run `bm knowledge edit {}`.",
+            rel_path,
+            rel_path
+        );
+    }
+
+    let contents = match &source {
+        AddSource::File(from) => fs::read_to_string(from)
+            .with_context(|| format!("Failed to read {}", from.display()))?,
+        AddSource::Template | AddSource::Editor => template_for(&file_name),
+    };
+
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(&file_path, &contents).with_context(|| format!("Failed to write {}", rel_path))?;
+
+    if let AddSource::Editor = source {
+        let edited = session::edit_file(&file_path)
+            .and_then(|()| fs::read_to_string(&file_path).context("Failed to read edited file"));
+        match edited {
+            Ok(text) if !text.trim().is_empty() => {}
+            Ok(_) => {
+                fs::remove_file(&file_path).ok();
+                bail!("Aborted: {} is empty, nothing was added.", rel_path);
+            }
+            Err(e) => {
+                fs::remove_file(&file_path).ok();
+                return Err(e);
+            }
+        }
+    }
+
+    let kind = if target.invariant { "invariant" } else { "knowledge" };
+    run_git(&team_repo, &["add", &rel_path])?;
+    run_git(
+        &team_repo,
+        &["commit", "-m", &format!("docs: add {} {}", kind, rel_path)],
+    )?;
+
+    println!("Added {} (committed, not pushed).", rel_path);
+    Ok(())
+}
+
+/// Handles `bm knowledge edit <path> [-t team]`.
+pub fn edit(path: &str, team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let team_repo = team.path.join("team");
+
+    let team_schema = read_team_schema(&team_repo)?;
+    profile::require_current_schema(&team.name, &team_schema)?;
+
+    validate_knowledge_path(path)?;
+    if path.split('/').any(|part| part == "..") {
+        bail!("Path resolves outside the team repo");
+    }
+
+    let file_path = team_repo.join(path);
+    if !file_path.is_file() {
+        bail!(
+            "File not found: {}. To create it, run `bm knowledge add`.",
+            path
+        );
+    }
+
+    let before = fs::read_to_string(&file_path).with_context(|| format!("Failed to read {}", path))?;
+    session::edit_file(&file_path)?;
+    let after = fs::read_to_string(&file_path).with_context(|| format!("Failed to read {}", path))?;
+
+    if before == after {
+        println!("No changes to {}.", path);
+        return Ok(());
+    }
+
+    run_git(&team_repo, &["add", path])?;
+    run_git(&team_repo, &["commit", "-m", &format!("docs: update {}", path)])?;
+
+    println!("Updated {} (committed, not pushed).", path);
+    Ok(())
+}
+
+/// Resolves the directory (relative to the team repo) that a new file in
+/// `scope` goes into, checking that the targeted project/member exists.
+fn scope_dir(team_repo: &Path, scope: &str, target: &AddTarget) -> Result<String> {
+    let subdir = if target.invariant {
+        "invariants"
+    } else {
+        "knowledge"
+    };
+
+    let project = |flag: Option<&str>| -> Result<String> {
+        let Some(project) = flag else {
+            bail!("The '{}' scope requires --project <name>", scope);
+        };
+        let known = list_subdirs(&team_repo.join("projects"));
+        if !known.iter().any(|p| p == project) {
+            bail!(
+                "Project '{}' not found. Available projects: {}",
+                project,
+                if known.is_empty() { "none".to_string() } else { known.join(", ") }
+            );
+        }
+        Ok(project.to_string())
+    };
+    let member = |flag: Option<&str>| -> Result<String> {
+        let Some(member) = flag else {
+            bail!("The '{}' scope requires --member <name>", scope);
+        };
+        let known = list_subdirs(&team_repo.join("team"));
+        if !known.iter().any(|m| m == member) {
+            bail!(
+                "Member '{}' not found. Hired members: {}",
+                member,
+                if known.is_empty() { "none".to_string() } else { known.join(", ") }
+            );
+        }
+        Ok(member.to_string())
+    };
+
+    match scope {
+        "team" => Ok(subdir.to_string()),
+        "project" => Ok(format!("projects/{}/{}", project(target.project)?, subdir)),
+        "member" => Ok(format!("team/{}/{}", member(target.member)?, subdir)),
+        "member-project" => {
+            if target.invariant {
+                bail!("The member-project scope has knowledge only, not invariants");
+            }
+            Ok(format!(
+                "team/{}/projects/{}/knowledge",
+                member(target.member)?,
+                project(target.project)?
+            ))
+        }
+        other => bail!(
+            "Unknown scope '{}'. Valid scopes: {}",
+            other,
+            SCOPES.join(", ")
+        ),
+    }
+}
+
+/// Starting content for a new file: a heading made from its name.
+fn template_for(file_name: &str) -> String {
+    let stem = file_name.trim_end_matches(".md");
+    let stem = stem.rsplit('/').next().unwrap_or(stem);
+    let words = stem.replace(['-', '_'], " ");
+    let mut chars = words.chars();
+    let title: String = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    };
+    format!("# {}\n\n", title)
+}
+
 /// Handles `bm knowledge [-t team]` (bare — launches interactive Claude session).
 pub fn interactive(team_flag: Option<&str>, _scope: Option<&str>) -> Result<()> {
     let cfg = config::load()?;
@@ -157,6 +569,15 @@ fn credentials_env(team: &TeamEntry) -> Vec<(String, String)> {
     if let Some(token) = &team.credentials.telegram_bot_token {
         env.push(("RALPH_TELEGRAM_BOT_TOKEN".to_string(), token.clone()));
     }
+    if let Some(chat_id) = team.credentials.telegram_chat_id {
+        env.push(("RALPH_TELEGRAM_CHAT_ID".to_string(), chat_id.to_string()));
+    }
+    if let Some(token) = &team.credentials.slack_bot_token {
+        env.push(("RALPH_SLACK_BOT_TOKEN".to_string(), token.clone()));
+    }
+    if let Some(channel) = &team.credentials.slack_channel {
+        env.push(("RALPH_SLACK_CHANNEL".to_string(), channel.clone()));
+    }
     env
 }
 
@@ -211,8 +632,7 @@ fn read_team_schema(team_repo: &Path) -> Result<String> {
     }
     let contents = fs::read_to_string(&manifest_path)
         .context("Failed to read team botminter.yml")?;
-    let val: serde_yml::Value =
-        serde_yml::from_str(&contents).context("Failed to parse team botminter.yml")?;
+    let val: serde_yml::Value = parse::yaml(&manifest_path, &contents)?;
     Ok(val["schema_version"]
         .as_str()
         .unwrap_or("")
@@ -271,6 +691,87 @@ fn list_subdirs(dir: &Path) -> Vec<String> {
 mod tests {
     use super::*;
 
+    // ── search_lines / scoped_files ──────────────────────────────────
+
+    #[test]
+    fn search_lines_with_context_and_case() {
+        let contents = "one\nTwo target\nthree\nfour\nfive target";
+        let hits = search_lines(contents, "target", false, 1);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].0, 2);
+        assert_eq!(hits[0].2, vec!["one"]);
+        assert_eq!(hits[0].3, vec!["three"]);
+        assert_eq!(hits[1].0, 5);
+        assert!(hits[1].3.is_empty(), "no context past the last line");
+
+        assert!(search_lines(contents, "TWO", false, 0).is_empty());
+        assert_eq!(search_lines(contents, "TWO", true, 0)[0].1, "Two target");
+    }
+
+    #[test]
+    fn scoped_files_covers_all_scopes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        for dir in [
+            "knowledge/sub",
+            "invariants",
+            "projects/app/knowledge",
+            "team/arch-01/invariants",
+            "team/arch-01/projects/app/knowledge",
+        ] {
+            fs::create_dir_all(repo.join(dir)).unwrap();
+            fs::write(repo.join(dir).join("a.md"), "x").unwrap();
+        }
+        fs::write(repo.join("knowledge/notes.txt"), "x").unwrap();
+
+        let all = scoped_files(repo, None);
+        assert_eq!(
+            all,
+            vec![
+                ("team", "knowledge/sub/a.md".to_string()),
+                ("team", "invariants/a.md".to_string()),
+                ("project", "projects/app/knowledge/a.md".to_string()),
+                ("member", "team/arch-01/invariants/a.md".to_string()),
+                (
+                    "member-project",
+                    "team/arch-01/projects/app/knowledge/a.md".to_string()
+                ),
+            ]
+        );
+        assert_eq!(scoped_files(repo, Some("member")).len(), 1);
+    }
+
+    #[test]
+    fn template_for_titles_file_name() {
+        assert_eq!(template_for("retry-policy.md"), "# Retry policy\n\n");
+        assert_eq!(template_for("api/error_codes.md"), "# Error codes\n\n");
+    }
+
+    #[test]
+    fn scope_dir_resolves_and_validates_targets() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path();
+        fs::create_dir_all(repo.join("projects/app")).unwrap();
+        fs::create_dir_all(repo.join("team/arch-01")).unwrap();
+        let target = |project, member, invariant| AddTarget {
+            project,
+            member,
+            invariant,
+        };
+
+        assert_eq!(scope_dir(repo, "team", &target(None, None, true)).unwrap(), "invariants");
+        assert_eq!(
+            scope_dir(repo, "member-project", &target(Some("app"), Some("arch-01"), false))
+                .unwrap(),
+            "team/arch-01/projects/app/knowledge"
+        );
+        let err = scope_dir(repo, "project", &target(None, None, false)).unwrap_err();
+        assert!(err.to_string().contains("requires --project"));
+        let err = scope_dir(repo, "member", &target(None, Some("dev-01"), false)).unwrap_err();
+        assert!(err.to_string().contains("Hired members: arch-01"));
+        assert!(scope_dir(repo, "galaxy", &target(None, None, false)).is_err());
+    }
+
     // ── validate_knowledge_path ──────────────────────────────────────
 
     #[test]
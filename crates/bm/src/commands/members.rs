@@ -1,19 +1,19 @@
 use std::fs;
 
-use anyhow::{bail, Context, Result};
-use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL_CONDENSED, Table};
-use serde::Deserialize;
+use anyhow::{bail, Result};
 
+use crate::commands::daemon;
 use crate::commands::start::{resolve_member_status, MemberStatus};
 use crate::config;
-use crate::state;
+use crate::members;
+use crate::state::{self, Health};
+use crate::tables;
+use crate::timefmt;
+use crate::topology::{self, Endpoint};
+use crate::workspace;
 
-/// Minimal member manifest — only the fields we need for listing.
-#[derive(Debug, Deserialize)]
-struct MemberManifest {
-    #[serde(default)]
-    role: Option<String>,
-}
+/// Lines of the member log shown by `bm members show`.
+const LOG_TAIL_LINES: usize = 10;
 
 /// Handles `bm members list [-t team]`.
 pub fn list(team_flag: Option<&str>) -> Result<()> {
@@ -27,37 +27,10 @@ pub fn list(team_flag: Option<&str>) -> Result<()> {
         return Ok(());
     }
 
-    let mut entries: Vec<(String, String, String)> = Vec::new();
-
-    for entry in fs::read_dir(&team_members_dir)? {
-        let entry = entry?;
-        if !entry.file_type()?.is_dir() {
-            continue;
-        }
-
-        let dir_name = entry.file_name().to_string_lossy().to_string();
-        // Skip hidden dirs (e.g., .gitkeep wouldn't be a dir, but be safe)
-        if dir_name.starts_with('.') {
-            continue;
-        }
-
-        let manifest_path = entry.path().join("botminter.yml");
-        let role = if manifest_path.exists() {
-            let contents = fs::read_to_string(&manifest_path)
-                .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
-            let manifest: MemberManifest = serde_yml::from_str(&contents)
-                .with_context(|| format!("Failed to parse {}", manifest_path.display()))?;
-            manifest
-                .role
-                .unwrap_or_else(|| infer_role_from_dir(&dir_name))
-        } else {
-            infer_role_from_dir(&dir_name)
-        };
-
-        entries.push((dir_name, role, String::new()));
-    }
-
-    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    let entries = members::list_names(&team_members_dir)?
+        .iter()
+        .map(|name| members::load(&team_members_dir, name))
+        .collect::<Result<Vec<_>>>()?;
 
     if entries.is_empty() {
         println!("No members hired yet. Run `bm hire <role>` to hire a member.");
@@ -66,18 +39,14 @@ pub fn list(team_flag: Option<&str>) -> Result<()> {
 
     let runtime_state = state::load().unwrap_or_default();
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL_CONDENSED)
-        .apply_modifier(UTF8_ROUND_CORNERS)
-        .set_header(vec!["Member", "Role", "Status"]);
+    let mut table = tables::new(vec!["Member", "Role", "Status"]);
 
-    for (member, role, _) in &entries {
-        let status = resolve_member_status(&runtime_state, &team.name, member);
-        table.add_row(vec![member.as_str(), role.as_str(), status.label()]);
+    for member in &entries {
+        let status = resolve_member_status(&runtime_state, &team.name, &member.name);
+        table.add_row(vec![member.name.as_str(), member.role.as_str(), status.label()]);
     }
 
-    println!("{table}");
+    println!("{}", tables::render(&table));
     Ok(())
 }
 
@@ -97,19 +66,7 @@ pub fn show(member: &str, team_flag: Option<&str>) -> Result<()> {
         );
     }
 
-    // Read role
-    let manifest_path = member_dir.join("botminter.yml");
-    let role = if manifest_path.exists() {
-        let contents = fs::read_to_string(&manifest_path)
-            .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
-        let manifest: MemberManifest = serde_yml::from_str(&contents)
-            .with_context(|| format!("Failed to parse {}", manifest_path.display()))?;
-        manifest
-            .role
-            .unwrap_or_else(|| infer_role_from_dir(member))
-    } else {
-        infer_role_from_dir(member)
-    };
+    let role = members::load(&team_members_dir, member)?.role;
 
     println!("Member: {}", member);
     println!("Role: {}", role);
@@ -118,15 +75,22 @@ pub fn show(member: &str, team_flag: Option<&str>) -> Result<()> {
     let runtime_state = state::load().unwrap_or_default();
     let status = resolve_member_status(&runtime_state, &team.name, member);
     match &status {
-        MemberStatus::Running { pid, started_at } => {
-            println!("Status: running");
+        MemberStatus::Running {
+            pid,
+            started_at,
+            health,
+        } => {
+            println!("Status: {}", status.label());
+            if let Some(Health::Unhealthy { reason }) = health {
+                println!("Not ready: {}", reason);
+            }
             println!("PID: {}", pid);
-            println!("Started: {}", started_at);
+            println!("Started: {}", timefmt::rfc3339(started_at));
         }
         MemberStatus::Crashed { pid, started_at } => {
             println!("Status: crashed");
             println!("PID: {}", pid);
-            println!("Started: {}", started_at);
+            println!("Started: {}", timefmt::rfc3339(started_at));
         }
         MemberStatus::Stopped => {
             println!("Status: stopped");
@@ -137,6 +101,47 @@ pub fn show(member: &str, team_flag: Option<&str>) -> Result<()> {
     let state_key = format!("{}/{}", team.name, member);
     if let Some(rt) = runtime_state.members.get(&state_key) {
         println!("Workspace: {}", rt.workspace.display());
+        if !rt.env.is_empty() {
+            println!("Launch env:");
+            for (name, value) in &rt.env {
+                println!("  {}={}", name, value);
+            }
+        }
+    }
+
+    // Topology entry, if a formation has recorded one
+    let topo_path = topology::topology_path(&cfg.workzone, &team.name);
+    if let Ok(Some(topo)) = topology::load(&topo_path) {
+        if let Some(entry) = topo.members.get(member) {
+            let endpoint = match &entry.endpoint {
+                Endpoint::Local { pid, .. } => format!("local, PID {}", pid),
+                Endpoint::K8s { namespace, pod, .. } => format!("k8s, pod {}/{}", namespace, pod),
+            };
+            println!("Topology: {} ({}, {})", entry.status, topo.formation, endpoint);
+        }
+    }
+
+    // Provisioned workspaces: git state and surfaced symlinks
+    let workspaces = members::workspaces(&team.path, member);
+    println!();
+    if workspaces.is_empty() {
+        println!("Workspaces: none (run `bm teams sync` to provision)");
+    } else {
+        println!("Workspaces:");
+        for ws in &workspaces {
+            println!("  {}", ws.path.display());
+            if let Some(git) = workspace::git_summary(&ws.path) {
+                println!("    Git: {}", git);
+            }
+            for (link, target, resolves) in workspace::list_symlinks(&ws.path) {
+                println!(
+                    "    {} -> {}{}",
+                    link.display(),
+                    target.display(),
+                    if resolves { "" } else { " (broken)" }
+                );
+            }
+        }
     }
 
     // Knowledge files
@@ -164,9 +169,26 @@ pub fn show(member: &str, team_flag: Option<&str>) -> Result<()> {
         }
     }
 
+    // Tail of the member log (written by daemon one-shot runs)
+    if let Ok(log_path) = daemon::member_log_path(&team.name, member) {
+        if let Ok(contents) = fs::read_to_string(&log_path) {
+            println!();
+            println!("Recent log ({}):", log_path.display());
+            for line in tail_lines(&contents, LOG_TAIL_LINES) {
+                println!("  {}", line);
+            }
+        }
+    }
+
     Ok(())
 }
 
+/// Returns the last `n` lines of `contents`.
+fn tail_lines(contents: &str, n: usize) -> Vec<&str> {
+    let lines: Vec<&str> = contents.lines().collect();
+    lines[lines.len().saturating_sub(n)..].to_vec()
+}
+
 /// Lists non-hidden files in a directory, returning their names sorted.
 fn list_files_in_dir(dir: &std::path::Path) -> Vec<String> {
     if !dir.is_dir() {
@@ -186,36 +208,15 @@ fn list_files_in_dir(dir: &std::path::Path) -> Vec<String> {
     files
 }
 
-/// Infers the role from a member dir name by taking everything before the first '-'.
-fn infer_role_from_dir(dir_name: &str) -> String {
-    dir_name
-        .split('-')
-        .next()
-        .unwrap_or("unknown")
-        .to_string()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn infer_role_standard_pattern() {
-        assert_eq!(infer_role_from_dir("architect-alice"), "architect");
-    }
-
-    #[test]
-    fn infer_role_multiple_hyphens() {
-        assert_eq!(infer_role_from_dir("po-bob-senior"), "po");
-    }
-
-    #[test]
-    fn infer_role_no_hyphen() {
-        assert_eq!(infer_role_from_dir("superman"), "superman");
-    }
-
-    #[test]
-    fn infer_role_empty_string() {
-        assert_eq!(infer_role_from_dir(""), "");
+    fn tail_lines_keeps_last_n() {
+        let log = (1..=15).map(|i| i.to_string()).collect::<Vec<_>>().join("\n");
+        assert_eq!(tail_lines(&log, 3), vec!["13", "14", "15"]);
+        assert_eq!(tail_lines("only\n", 10), vec!["only"]);
+        assert!(tail_lines("", 10).is_empty());
     }
 }
@@ -1,13 +1,28 @@
+pub mod audit;
+pub mod board;
+pub mod chatops;
 pub mod completions;
+pub mod config;
 pub mod daemon;
+pub mod dashboard;
+pub mod diag;
+pub mod exec;
 pub mod hire;
 pub mod init;
 pub mod knowledge;
 pub mod members;
+pub mod notify;
+pub mod pause;
 pub mod profiles;
 pub mod projects;
+pub mod quickstart;
 pub mod roles;
 pub mod start;
+pub mod state;
 pub mod status;
 pub mod stop;
 pub mod teams;
+pub mod topology;
+pub mod usage;
+pub mod webhooks;
+pub mod workspace;
@@ -0,0 +1,115 @@
+use anyhow::{bail, Result};
+
+use crate::config::{self, Smtp, SmtpTls};
+use crate::notify::{Kind, Notification, Notifier};
+use crate::smtp;
+
+/// Handles `bm notify test [-t team]` — sends a test message through each of
+/// the team's notification backends, so their settings can be checked before
+/// a crash depends on them.
+pub fn test(team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let notifiers = &team.credentials.notifiers;
+    if team.credentials.smtp.is_none() && notifiers.is_empty() {
+        bail!(
+            "Team '{}' has no SMTP server or notifiers configured. Add an smtp block \
+             (server, from, to) or a notifiers list under the team's credentials in \
+             ~/.botminter/config.yml.",
+            team.name
+        );
+    }
+
+    let mut failed = Vec::new();
+    if let Some(ref smtp) = team.credentials.smtp {
+        if let Err(e) = test_smtp(&team.name, smtp) {
+            eprintln!("smtp: {:#}", e);
+            failed.push("smtp".to_string());
+        }
+    }
+    for notifier in notifiers {
+        let body = format!(
+            "This is a test message from bm for team {}.\n\nNotifications sent here: {}.",
+            team.name,
+            kinds(|k| notifier.wants(k))
+        );
+        let test = Notification::new(&team.name, Kind::Digest, "Test message", body);
+        match notifier.deliver(team, &test) {
+            Ok(()) => println!(
+                "Sent a test message via {}. Notifications sent there: {}",
+                describe(notifier),
+                kinds(|k| notifier.wants(k))
+            ),
+            Err(e) => {
+                eprintln!("{}: {:#}", notifier.channel, e);
+                failed.push(notifier.channel.to_string());
+            }
+        }
+    }
+    if !failed.is_empty() {
+        bail!("Test message failed for: {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// Mails a test message to every address in `to`.
+fn test_smtp(team_name: &str, smtp: &Smtp) -> Result<()> {
+    let digest_at = smtp.digest_time()?;
+    let security = match smtp.tls {
+        SmtpTls::Starttls => "STARTTLS",
+        SmtpTls::Tls => "TLS",
+        SmtpTls::None => "no encryption",
+    };
+    let mailed = kinds(|k| smtp.wants(k));
+    let body = format!(
+        "This is a test message from bm for team {}.\n\n\
+         Sent via {}:{} ({}).\n\
+         Notifications mailed: {}.\n\
+         Daily digest: {}.",
+        team_name,
+        smtp.server,
+        smtp.port(),
+        security,
+        mailed,
+        if smtp.wants(Kind::Digest) {
+            format!("{} local time", digest_at.format("%H:%M"))
+        } else {
+            "off".to_string()
+        }
+    );
+    let test = Notification::new(team_name, Kind::Digest, "Test message", body);
+    smtp::send(smtp, &test.subject, &test.body)?;
+
+    println!(
+        "Sent a test message to {} via {}:{} ({}).",
+        smtp.to.join(", "),
+        smtp.server,
+        smtp.port(),
+        security
+    );
+    println!("Notifications mailed: {}", mailed);
+    Ok(())
+}
+
+/// The kinds `wants` accepts, comma-separated.
+fn kinds(wants: impl Fn(Kind) -> bool) -> String {
+    let kinds: Vec<String> = Kind::ALL
+        .into_iter()
+        .filter(|k| wants(*k))
+        .map(|k| k.to_string())
+        .collect();
+    kinds.join(", ")
+}
+
+/// `slack (hooks.slack.com)`, without the URL's secret path.
+fn describe(notifier: &Notifier) -> String {
+    let host = notifier
+        .url
+        .as_deref()
+        .and_then(|u| u.split("://").nth(1))
+        .and_then(|rest| rest.split('/').next());
+    match host {
+        Some(host) => format!("{} ({})", notifier.channel, host),
+        None => notifier.channel.to_string(),
+    }
+}
@@ -0,0 +1,42 @@
+use anyhow::Result;
+
+use crate::audit::current_user;
+use crate::config;
+use crate::state;
+
+/// Handles `bm pause [-t team]`.
+/// Holds new work: the daemon keeps queueing events but launches no
+/// members, and `bm start` refuses without `--force`. Running members
+/// finish what they are doing.
+pub fn pause(team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    if !state::pause(&team.name, &current_user())? {
+        println!(
+            "Team '{}' is already paused {}.",
+            team.name,
+            state::paused(&team.name).unwrap_or_default()
+        );
+        return Ok(());
+    }
+    println!(
+        "Paused team '{}'. Running members carry on; no new ones launch until `bm resume`.",
+        team.name
+    );
+    Ok(())
+}
+
+/// Handles `bm resume [-t team]`.
+pub fn resume(team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    if state::resume(&team.name)? {
+        println!(
+            "Resumed team '{}'. The daemon dispatches queued events.",
+            team.name
+        );
+    } else {
+        println!("Team '{}' is not paused.", team.name);
+    }
+    Ok(())
+}
@@ -1,17 +1,17 @@
-use anyhow::Result;
-use comfy_table::{Table, presets::UTF8_FULL_CONDENSED, modifiers::UTF8_ROUND_CORNERS};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
 
 use crate::profile;
+use crate::profile_lint::{self, Severity};
+use crate::tables;
 
 /// Handles `bm profiles list` — displays a table of all embedded profiles.
 pub fn list() -> Result<()> {
     let names = profile::list_profiles();
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL_CONDENSED)
-        .apply_modifier(UTF8_ROUND_CORNERS)
-        .set_header(vec!["Profile", "Version", "Schema", "Description"]);
+    let mut table = tables::new(vec!["Profile", "Version", "Schema", "Description"]);
 
     for name in &names {
         let manifest = profile::read_manifest(name)?;
@@ -23,7 +23,7 @@ pub fn list() -> Result<()> {
         ]);
     }
 
-    println!("{table}");
+    println!("{}", tables::render(&table));
     Ok(())
 }
 
@@ -62,3 +62,116 @@ pub fn describe(name: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// Handles `bm profiles validate [<profile|path>]` — strict manifest checks.
+pub fn validate(target: Option<&str>) -> Result<()> {
+    let results: Vec<(String, Result<profile::ProfileManifest>)> = match target {
+        Some(t) if Path::new(t).exists() => {
+            let path = Path::new(t);
+            let path = if path.is_dir() {
+                path.join("botminter.yml")
+            } else {
+                path.to_path_buf()
+            };
+            let result = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))
+                .and_then(|contents| profile::parse_manifest_strict(&path, &contents));
+            vec![(path.display().to_string(), result)]
+        }
+        Some(name) => vec![(name.to_string(), profile::read_manifest_strict(name))],
+        None => profile::list_profiles()
+            .into_iter()
+            .map(|name| {
+                let result = profile::read_manifest_strict(&name);
+                (name, result)
+            })
+            .collect(),
+    };
+
+    let mut failed = 0;
+    for (label, result) in &results {
+        match result {
+            Ok(manifest) => println!("✓ {} ({} v{})", label, manifest.name, manifest.version),
+            Err(e) => {
+                failed += 1;
+                println!("✗ {}\n  {}", label, format!("{:#}", e).replace('\n', "\n  "));
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{} of {} manifest(s) failed validation", failed, results.len());
+    }
+    Ok(())
+}
+
+/// Handles `bm profiles export <profile> <dir>` — writes a full copy of an
+/// embedded profile for customizing.
+pub fn export(name: &str, dir: &str) -> Result<()> {
+    let target = Path::new(dir);
+    if target.exists() {
+        let is_empty = fs::read_dir(target)
+            .with_context(|| format!("{} exists and is not a directory", dir))?
+            .next()
+            .is_none();
+        if !is_empty {
+            bail!(
+                "{} is not empty. Export into a new or empty directory so the copy \
+                 matches the embedded profile exactly.",
+                dir
+            );
+        }
+    }
+
+    let manifest = profile::read_manifest(name)?;
+    let files = profile::export_profile_to(name, target)?;
+    println!(
+        "Exported profile '{}' v{} ({} files) to {}",
+        name, manifest.version, files, dir
+    );
+    println!("\nNext steps:");
+    println!("  Edit the copy, then check it with `bm profiles lint {}`", dir);
+    println!(
+        "  Compare with upstream after a bm upgrade: export again and `diff -r` the two"
+    );
+    Ok(())
+}
+
+/// Handles `bm profiles lint <path> [--json]` — checks a profile directory
+/// and fails if any errors are found.
+pub fn lint(path: &str, json: bool) -> Result<()> {
+    let findings = profile_lint::lint(Path::new(path))?;
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+
+    if json {
+        let report = serde_json::json!({
+            "path": path,
+            "errors": errors,
+            "warnings": warnings,
+            "findings": findings,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for f in &findings {
+            let label = match f.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            println!("{}: {}: {}", label, f.file, f.message);
+        }
+        if findings.is_empty() {
+            println!("✓ {}: no problems found", path);
+        } else {
+            println!("\n{} error(s), {} warning(s)", errors, warnings);
+        }
+    }
+
+    if errors > 0 {
+        bail!("Profile at {} has {} error(s)", path, errors);
+    }
+    Ok(())
+}
@@ -1,10 +1,16 @@
 use std::fs;
 
 use anyhow::{bail, Context, Result};
-use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL_CONDENSED, Table};
+use tracing::debug;
 
 use crate::config;
+use crate::dry_run::{Plan, Step};
+use crate::parse;
 use crate::profile;
+use crate::project_views::{self, Change};
+use crate::repo_settings;
+use crate::repo_templates;
+use crate::tables;
 
 use super::init::{derive_project_name, find_project_number, run_git, sync_project_status_field, verify_fork_url};
 
@@ -18,7 +24,7 @@ pub fn list(team_flag: Option<&str>) -> Result<()> {
     let manifest: profile::ProfileManifest = {
         let contents = fs::read_to_string(&manifest_path)
             .context("Failed to read team repo's botminter.yml")?;
-        serde_yml::from_str(&contents).context("Failed to parse botminter.yml")?
+        parse::yaml(&manifest_path, &contents)?
     };
 
     if manifest.projects.is_empty() {
@@ -26,17 +32,13 @@ pub fn list(team_flag: Option<&str>) -> Result<()> {
         return Ok(());
     }
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL_CONDENSED)
-        .apply_modifier(UTF8_ROUND_CORNERS)
-        .set_header(vec!["Project", "Fork URL"]);
+    let mut table = tables::new(vec!["Project", "Fork URL"]);
 
     for proj in &manifest.projects {
         table.add_row(vec![proj.name.as_str(), proj.fork_url.as_str()]);
     }
 
-    println!("{table}");
+    println!("{}", tables::render(&table));
     Ok(())
 }
 
@@ -50,7 +52,7 @@ pub fn show(project: &str, team_flag: Option<&str>) -> Result<()> {
     let manifest: profile::ProfileManifest = {
         let contents = fs::read_to_string(&manifest_path)
             .context("Failed to read team repo's botminter.yml")?;
-        serde_yml::from_str(&contents).context("Failed to parse botminter.yml")?
+        parse::yaml(&manifest_path, &contents)?
     };
 
     let proj = manifest
@@ -126,6 +128,18 @@ fn list_files_in_dir(dir: &std::path::Path) -> Vec<String> {
 
 /// Handles `bm projects add <url> [-t team]`.
 pub fn add(url: &str, team_flag: Option<&str>) -> Result<()> {
+    add_with(url, team_flag, None)
+}
+
+/// Handles `bm projects add --dry-run`: checks the fork is reachable, then
+/// lists the labels, files, and commit adding it would create.
+pub fn add_plan(url: &str, team_flag: Option<&str>) -> Result<Plan> {
+    let mut plan = Plan::default();
+    add_with(url, team_flag, Some(&mut plan))?;
+    Ok(plan)
+}
+
+fn add_with(url: &str, team_flag: Option<&str>, plan: Option<&mut Plan>) -> Result<()> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, team_flag)?;
     let team_repo = team.path.join("team");
@@ -135,7 +149,7 @@ pub fn add(url: &str, team_flag: Option<&str>) -> Result<()> {
     let mut manifest: profile::ProfileManifest = {
         let contents = fs::read_to_string(&manifest_path)
             .context("Failed to read team repo's botminter.yml")?;
-        serde_yml::from_str(&contents).context("Failed to parse botminter.yml")?
+        parse::yaml(&manifest_path, &contents)?
     };
 
     // Derive project name from URL
@@ -149,11 +163,29 @@ pub fn add(url: &str, team_flag: Option<&str>) -> Result<()> {
     // Verify the fork URL is reachable
     verify_fork_url(url, team.credentials.gh_token.as_deref())?;
 
-    // Create project label on GitHub if repo is configured (idempotent)
-    if !team.github_repo.is_empty() {
-        let label_name = format!("project/{}", name);
+    let label_name = format!("project/{}", name);
+    let proj_dir = team_repo.join("projects").join(&name);
+    let commit_msg = format!("feat: add project {}", name);
+    if let Some(plan) = plan {
+        for repo in team.repos() {
+            plan.push(Step::GitHub(format!("create label '{}' on {}", label_name, repo)));
+        }
+        plan.push(Step::WriteFile(manifest_path));
+        for dir in ["knowledge", "invariants"] {
+            plan.push(Step::CreateDir(proj_dir.join(dir)));
+            plan.push(Step::WriteFile(proj_dir.join(dir).join(".gitkeep")));
+        }
+        plan.push(Step::Commit {
+            repo: team_repo,
+            message: commit_msg,
+        });
+        return Ok(());
+    }
+
+    // Create project label on each of the team's GitHub repos (idempotent)
+    for repo in team.repos() {
         super::init::create_github_label(
-            &team.github_repo,
+            &repo,
             &label_name,
             "BFD4F2", // Light purple
             &format!("Issues for the {} project", name),
@@ -172,7 +204,6 @@ pub fn add(url: &str, team_flag: Option<&str>) -> Result<()> {
     fs::write(&manifest_path, contents).context("Failed to write botminter.yml")?;
 
     // Create project dirs with .gitkeep
-    let proj_dir = team_repo.join("projects").join(&name);
     fs::create_dir_all(proj_dir.join("knowledge"))
         .with_context(|| format!("Failed to create projects/{}/knowledge/", name))?;
     fs::create_dir_all(proj_dir.join("invariants"))
@@ -185,7 +216,6 @@ pub fn add(url: &str, team_flag: Option<&str>) -> Result<()> {
         &team_repo,
         &["add", "botminter.yml", &format!("projects/{}/", name)],
     )?;
-    let commit_msg = format!("feat: add project {}", name);
     run_git(&team_repo, &["commit", "-m", &commit_msg])?;
 
     println!("Added project '{}' to team '{}'.", name, team.name);
@@ -193,10 +223,12 @@ pub fn add(url: &str, team_flag: Option<&str>) -> Result<()> {
     Ok(())
 }
 
-/// Handles `bm projects sync [-t team]`.
+/// Handles `bm projects sync [-t team] [--create-views]`.
 /// Syncs the GitHub Project board's Status field options with the profile,
-/// then prints instructions for setting up role-based views.
-pub fn sync(team_flag: Option<&str>) -> Result<()> {
+/// pushes issue templates and repo settings to the work repos, then creates
+/// the role-based views (`--create-views`) or prints instructions for
+/// setting them up by hand. With `check`, only reports repo settings drift.
+pub fn sync(team_flag: Option<&str>, create_views: bool, check: bool) -> Result<()> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, team_flag)?;
     let team_repo = team.path.join("team");
@@ -206,7 +238,7 @@ pub fn sync(team_flag: Option<&str>) -> Result<()> {
     let manifest: profile::ProfileManifest = {
         let contents = fs::read_to_string(&manifest_path)
             .context("Failed to read team repo's botminter.yml")?;
-        serde_yml::from_str(&contents).context("Failed to parse botminter.yml")?
+        parse::yaml(&manifest_path, &contents)?
     };
 
     let owner = team
@@ -216,6 +248,10 @@ pub fn sync(team_flag: Option<&str>) -> Result<()> {
         .unwrap_or(&team.github_repo);
     let gh_token = team.credentials.gh_token.as_deref();
 
+    if check {
+        return check_repo_settings(team, &manifest, gh_token);
+    }
+
     // Find the project board
     let project_number = find_project_number(owner, &team.name, gh_token)?;
 
@@ -226,19 +262,82 @@ pub fn sync(team_flag: Option<&str>) -> Result<()> {
         manifest.statuses.len()
     );
 
-    // Print view setup instructions
+    let problems = manifest.issue_template_problems();
+    if problems.is_empty() {
+        for (repo, result) in repo_templates::sync(team, &manifest, gh_token) {
+            match result {
+                Ok(changes) => println!(
+                    "✓ Issue and PR templates on {}: {}",
+                    repo,
+                    repo_templates::summary(&changes)
+                ),
+                Err(e) => eprintln!("Warning: {:#}", e),
+            }
+        }
+    } else {
+        eprintln!(
+            "Warning: not pushing issue templates until botminter.yml is fixed:\n  {}",
+            problems.join("\n  ")
+        );
+    }
+
+    if let Some(ref settings) = manifest.repo_settings {
+        let problems = manifest.repo_settings_problems();
+        if !problems.is_empty() {
+            eprintln!(
+                "Warning: not applying repo settings until botminter.yml is fixed:\n  {}",
+                problems.join("\n  ")
+            );
+        } else {
+            for repo in repo_templates::work_repos(team, &manifest) {
+                match repo_settings::apply(&repo, settings, gh_token) {
+                    Ok(changed) if changed.is_empty() => {
+                        println!("✓ Repo settings on {}: up to date", repo)
+                    }
+                    Ok(changed) => {
+                        println!("✓ Repo settings on {}: {} changed", repo, changed.len());
+                        for drift in changed {
+                            println!("    {}", drift);
+                        }
+                    }
+                    Err(e) => eprintln!("Warning: {:#}", e),
+                }
+            }
+        }
+    }
+
     if manifest.views.is_empty() {
         println!("\nNo views defined in the profile.");
         return Ok(());
     }
 
+    let views: Vec<(String, String)> = manifest
+        .views
+        .iter()
+        .map(|v| (v.name.clone(), v.filter_string(&manifest.statuses)))
+        .collect();
+    let manual = if create_views {
+        sync_views(owner, project_number, &views, gh_token)?
+    } else {
+        views
+    };
+    if manual.is_empty() {
+        return Ok(());
+    }
+
     let project_url = format!(
         "https://github.com/orgs/{}/projects/{}",
         owner, project_number
     );
     println!();
-    println!("Your GitHub Project board needs role-based views so each role sees");
-    println!("only its relevant statuses. Create one view per role listed below.");
+    if create_views {
+        println!("Set up the remaining views by hand, so each role sees only its");
+        println!("relevant statuses.");
+    } else {
+        println!("Your GitHub Project board needs role-based views so each role sees");
+        println!("only its relevant statuses. Create one view per role listed below,");
+        println!("or rerun with --create-views to have bm create them.");
+    }
     println!();
     println!("Open the board: {}", project_url);
     println!();
@@ -252,10 +351,9 @@ pub fn sync(team_flag: Option<&str>) -> Result<()> {
     println!();
 
     // Calculate column widths
-    let name_width = manifest
-        .views
+    let name_width = manual
         .iter()
-        .map(|v| v.name.len())
+        .map(|(name, _)| name.len())
         .max()
         .unwrap_or(4)
         .max(4);
@@ -271,11 +369,10 @@ pub fn sync(team_flag: Option<&str>) -> Result<()> {
         width = name_width
     );
 
-    for view in &manifest.views {
-        let filter = view.filter_string(&manifest.statuses);
+    for (name, filter) in &manual {
         println!(
             "  {:<width$}  {}",
-            view.name,
+            name,
             filter,
             width = name_width
         );
@@ -286,6 +383,318 @@ pub fn sync(team_flag: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// Creates the profile's views missing from the board. Returns the views
+/// left for the operator: those whose filter drifted (the API can't edit a
+/// view) and, after a failed create, the ones not yet created.
+/// Handles `bm projects sync --check`: lists each work repo setting that
+/// differs from the profile, and fails if any does.
+fn check_repo_settings(
+    team: &config::TeamEntry,
+    manifest: &profile::ProfileManifest,
+    gh_token: Option<&str>,
+) -> Result<()> {
+    let Some(ref settings) = manifest.repo_settings else {
+        println!("No repo settings defined in the profile.");
+        return Ok(());
+    };
+    let problems = manifest.repo_settings_problems();
+    if !problems.is_empty() {
+        bail!(
+            "botminter.yml has invalid repo settings:\n  {}",
+            problems.join("\n  ")
+        );
+    }
+
+    let repos = repo_templates::work_repos(team, manifest);
+    let mut drifted = 0;
+    let mut unreadable = Vec::new();
+    for repo in &repos {
+        match repo_settings::check(repo, settings, gh_token) {
+            Ok(drift) if drift.is_empty() => println!("✓ {}: matches the profile", repo),
+            Ok(drift) => {
+                println!("✗ {}: {} setting(s) drifted", repo, drift.len());
+                for d in &drift {
+                    println!("    {}", d);
+                }
+                drifted += 1;
+            }
+            Err(e) => {
+                eprintln!("Warning: {:#}", e);
+                unreadable.push(repo.as_str());
+            }
+        }
+    }
+    if drifted > 0 {
+        bail!(
+            "{} of {} repo(s) drifted from the profile. Run `bm projects sync` to apply it.",
+            drifted,
+            repos.len()
+        );
+    }
+    if !unreadable.is_empty() {
+        bail!("Could not check {}", unreadable.join(", "));
+    }
+    Ok(())
+}
+
+fn sync_views(
+    owner: &str,
+    project_number: u64,
+    views: &[(String, String)],
+    gh_token: Option<&str>,
+) -> Result<Vec<(String, String)>> {
+    let board = project_views::load(owner, project_number, gh_token)?;
+    let mut manual = Vec::new();
+    let mut failed = false;
+    for (name, filter) in views {
+        match board.change(name, filter) {
+            Change::Unchanged => println!("✓ View '{}' up to date", name),
+            Change::Create if failed => manual.push((name.clone(), filter.clone())),
+            Change::Create => match project_views::create(&board, name, filter, gh_token) {
+                Ok(()) => println!("✓ View '{}' created", name),
+                Err(e) => {
+                    eprintln!("Warning: {:#}", e);
+                    failed = true;
+                    manual.push((name.clone(), filter.clone()));
+                }
+            },
+            Change::FilterDiffers(current) => {
+                println!(
+                    "! View '{}' filters on '{}'; GitHub's API can't edit views, \
+                     so update it by hand",
+                    name, current
+                );
+                manual.push((name.clone(), filter.clone()));
+            }
+        }
+    }
+    Ok(manual)
+}
+
+/// Handles `bm projects seed [-t team] [--dry-run]`.
+/// Creates the profile's `seed_issues` on the team repo, adds them to the
+/// project board, and sets their starting status. Issues whose title already
+/// exists on the repo are skipped, so re-running only fills gaps.
+pub fn seed(team_flag: Option<&str>, dry_run: bool) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    if team.github_repo.is_empty() {
+        bail!("No GitHub repo configured for team '{}'", team.name);
+    }
+    let repo = &team.github_repo;
+    let gh_token = team.credentials.gh_token.as_deref();
+
+    let manifest_path = team.path.join("team").join("botminter.yml");
+    let manifest: profile::ProfileManifest = {
+        let contents = fs::read_to_string(&manifest_path)
+            .context("Failed to read team repo's botminter.yml")?;
+        parse::yaml(&manifest_path, &contents)?
+    };
+    if manifest.seed_issues.is_empty() {
+        println!("The team's profile defines no seed_issues; nothing to create.");
+        return Ok(());
+    }
+    let problems = manifest.seed_issue_problems();
+    if !problems.is_empty() {
+        bail!(
+            "Fix seed_issues in the team repo's botminter.yml first:\n  {}",
+            problems.join("\n  ")
+        );
+    }
+
+    let existing = existing_issue_titles(repo, gh_token)?;
+    let (present, pending): (Vec<&profile::SeedIssue>, Vec<&profile::SeedIssue>) = manifest
+        .seed_issues
+        .iter()
+        .partition(|i| existing.contains(&i.title));
+    for issue in &present {
+        println!("- '{}' already exists, skipping", issue.title);
+    }
+    if pending.is_empty() {
+        println!("All {} seed issues already exist on {}.", present.len(), repo);
+        return Ok(());
+    }
+    if dry_run {
+        for issue in &pending {
+            println!(
+                "Would create '{}' [{}] in {}",
+                issue.title,
+                issue.labels.join(", "),
+                issue.status.as_deref().unwrap_or("no status")
+            );
+        }
+        return Ok(());
+    }
+
+    let owner = repo.split('/').next().unwrap_or(repo);
+    let project_number = find_project_number(owner, &team.name, gh_token)?;
+    let board = StatusBoard::load(owner, project_number, gh_token)?;
+    let mut created = 0;
+    for issue in pending {
+        let url = create_issue(repo, issue, gh_token)?;
+        created += 1;
+        match board.add(&url, issue.status.as_deref(), gh_token) {
+            Ok(()) => println!(
+                "✓ {} {} ({})",
+                url,
+                issue.title,
+                issue.status.as_deref().unwrap_or("no status")
+            ),
+            Err(e) => eprintln!(
+                "Warning: created {} but could not put it on the board: {:#}",
+                url, e
+            ),
+        }
+    }
+    println!("Created {} issue(s) on {}.", created, repo);
+    Ok(())
+}
+
+/// Titles of every issue on `repo`, open or closed.
+fn existing_issue_titles(repo: &str, gh_token: Option<&str>) -> Result<Vec<String>> {
+    let out = run_gh(
+        &[
+            "issue", "list", "--repo", repo, "--state", "all", "--limit", "1000", "--json",
+            "title",
+        ],
+        gh_token,
+    )
+    .with_context(|| format!("Failed to list issues on {}", repo))?;
+    let issues: Vec<serde_json::Value> =
+        serde_json::from_str(&out).context("Could not parse issue list JSON")?;
+    Ok(issues
+        .iter()
+        .filter_map(|i| i["title"].as_str().map(String::from))
+        .collect())
+}
+
+/// Opens a seed issue on `repo`. Returns its URL.
+pub(crate) fn create_issue(repo: &str, issue: &profile::SeedIssue, gh_token: Option<&str>) -> Result<String> {
+    let mut args = vec![
+        "issue", "create", "--repo", repo, "--title", &issue.title, "--body", &issue.body,
+    ];
+    for label in &issue.labels {
+        args.extend(["--label", label.as_str()]);
+    }
+    let out = run_gh(&args, gh_token)
+        .with_context(|| format!("Failed to create issue '{}' on {}", issue.title, repo))?;
+    out.lines()
+        .rev()
+        .find(|l| l.starts_with("https://"))
+        .map(String::from)
+        .with_context(|| format!("gh did not print the URL of issue '{}'", issue.title))
+}
+
+/// The project board and its Status field, for placing new issues.
+pub(crate) struct StatusBoard {
+    owner: String,
+    number: u64,
+    project_id: String,
+    field_id: String,
+    /// Status option IDs by name.
+    options: Vec<(String, String)>,
+}
+
+impl StatusBoard {
+    pub(crate) fn load(owner: &str, number: u64, gh_token: Option<&str>) -> Result<StatusBoard> {
+        let num = number.to_string();
+        let out = run_gh(
+            &["project", "view", &num, "--owner", owner, "--format", "json"],
+            gh_token,
+        )?;
+        let project: serde_json::Value =
+            serde_json::from_str(&out).context("Could not parse project JSON")?;
+        let project_id = project["id"]
+            .as_str()
+            .context("Project JSON has no id")?
+            .to_string();
+
+        let out = run_gh(
+            &["project", "field-list", &num, "--owner", owner, "--format", "json"],
+            gh_token,
+        )?;
+        let fields: serde_json::Value =
+            serde_json::from_str(&out).context("Could not parse field-list JSON")?;
+        let status = fields["fields"]
+            .as_array()
+            .and_then(|f| f.iter().find(|f| f["name"].as_str() == Some("Status")))
+            .context("Could not find Status field in project")?;
+        let options = status["options"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|o| {
+                Some((o["name"].as_str()?.to_string(), o["id"].as_str()?.to_string()))
+            })
+            .collect();
+        Ok(StatusBoard {
+            owner: owner.to_string(),
+            number,
+            project_id,
+            field_id: status["id"].as_str().unwrap_or_default().to_string(),
+            options,
+        })
+    }
+
+    /// Adds the issue at `url` to the board, in `status` if given.
+    pub(crate) fn add(&self, url: &str, status: Option<&str>, gh_token: Option<&str>) -> Result<()> {
+        let num = self.number.to_string();
+        let out = run_gh(
+            &[
+                "project", "item-add", &num, "--owner", &self.owner, "--url", url, "--format",
+                "json",
+            ],
+            gh_token,
+        )?;
+        let item: serde_json::Value =
+            serde_json::from_str(&out).context("Could not parse item-add JSON")?;
+        let Some(status) = status else {
+            return Ok(());
+        };
+        let item_id = item["id"].as_str().context("item-add JSON has no id")?;
+        let Some((_, option_id)) = self.options.iter().find(|(name, _)| name == status) else {
+            bail!("the board has no status '{}'. Run `bm projects sync` first.", status);
+        };
+        run_gh(
+            &[
+                "project",
+                "item-edit",
+                "--id",
+                item_id,
+                "--project-id",
+                &self.project_id,
+                "--field-id",
+                &self.field_id,
+                "--single-select-option-id",
+                option_id,
+            ],
+            gh_token,
+        )?;
+        Ok(())
+    }
+}
+
+/// Runs `gh` with `args`. Returns stdout.
+fn run_gh(args: &[&str], gh_token: Option<&str>) -> Result<String> {
+    debug!("Running gh {}", args.join(" "));
+    let mut cmd = std::process::Command::new("gh");
+    cmd.args(args);
+    if let Some(token) = gh_token {
+        cmd.env("GH_TOKEN", token);
+    }
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run `gh {}`", args[..2.min(args.len())].join(" ")))?;
+    if !output.status.success() {
+        bail!(
+            "gh {} failed: {}",
+            args[..2.min(args.len())].join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::init::derive_project_name;
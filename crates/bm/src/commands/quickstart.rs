@@ -0,0 +1,336 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+
+use crate::config::{self, BotminterConfig, Credentials, TeamEntry};
+use crate::github_app::{self, MemberAuth};
+use crate::launch_env::{self, MemberEnv};
+use crate::profile::{self, SeedIssue};
+
+use super::init;
+use super::projects::{self, StatusBoard};
+use super::teams;
+
+/// Profile the demo team is created from: one member covers every role.
+pub const PROFILE: &str = "scrum-compact";
+
+/// Team name used when `--name` is not given (`quickstart-2`, … if taken).
+const DEFAULT_NAME: &str = "quickstart";
+
+/// The issue the demo member picks up on its first run.
+fn demo_issue() -> SeedIssue {
+    SeedIssue {
+        title: "Demo: write a short README for this team".to_string(),
+        body: "This issue was opened by `bm quickstart`.\n\n\
+               Describe what this team repo is for and how members pick up work, \
+               in a README.md at the repo root. Keep it under a page."
+            .to_string(),
+        labels: vec!["kind/epic".to_string()],
+        status: Some("po:triage".to_string()),
+    }
+}
+
+/// Handles `bm quickstart [--name <team>] [--owner <owner>] [--no-run]`.
+///
+/// Creates a sandbox team on a new private repo with the compact profile,
+/// hires one member, opens a demo issue, runs the member once in the
+/// foreground, and explains how to remove it all again.
+pub fn run(name: Option<&str>, owner: Option<&str>, no_run: bool) -> Result<()> {
+    init::check_prerequisites()?;
+    if !no_run && which::which("ralph").is_err() {
+        bail!(
+            "ralph is needed to run the demo member and was not found on PATH.\n\
+             Install it, or pass --no-run to set up the team without running it."
+        );
+    }
+
+    cliclack::intro("botminter quickstart — a demo team and its first run")?;
+
+    let cfg = init::load_or_default_config()?;
+    let team_name = match name {
+        Some(n) => n.to_string(),
+        None => free_team_name(&cfg),
+    };
+    let team_dir = cfg.workzone.join(&team_name);
+    if cfg.teams.iter().any(|t| t.name == team_name) || team_dir.exists() {
+        bail!(
+            "Team '{}' already exists. Pass a different --name.",
+            team_name
+        );
+    }
+
+    let token = init::detect_or_prompt_gh_token()?;
+    let login = init::validate_gh_token(&token)?;
+    let owner = owner.unwrap_or(&login).to_string();
+    let github_repo = format!("{}/{}-team", owner, team_name);
+
+    let manifest = profile::read_manifest(PROFILE)?;
+    let role = manifest
+        .roles
+        .first()
+        .map(|r| r.name.clone())
+        .with_context(|| format!("Profile '{}' defines no roles", PROFILE))?;
+    let member_dir_name = format!("{}-01", role);
+
+    cliclack::log::info(format!(
+        "Team: {}\nProfile: {}\nWorkzone: {}\nGitHub: {} (new, private)\n\
+         Project board: new ({} Board)\nMember: {}\nDemo issue: {}",
+        team_name,
+        PROFILE,
+        cfg.workzone.display(),
+        github_repo,
+        team_name,
+        member_dir_name,
+        demo_issue().title,
+    ))?;
+    let confirm: bool = cliclack::confirm("Create this demo team?")
+        .initial_value(true)
+        .interact()?;
+    if !confirm {
+        cliclack::outro("Aborted.")?;
+        return Ok(());
+    }
+
+    let spinner = cliclack::spinner();
+    spinner.start("Creating team...");
+    fs::create_dir_all(&team_dir)
+        .with_context(|| format!("Failed to create team directory at {}", team_dir.display()))?;
+    let team_repo = team_dir.join("team");
+    init::scaffold_team_repo(
+        &team_repo,
+        PROFILE,
+        &team_name,
+        &github_repo,
+        &[(role.clone(), "01".to_string())],
+        &[],
+        &spinner,
+    )?;
+
+    spinner.start("Creating GitHub repository...");
+    init::create_github_repo(&team_repo, &github_repo, Some(&token))?;
+
+    // From here on there is something to clean up, so say how on failure
+    let demo = Demo {
+        team_name: team_name.clone(),
+        team_dir,
+        github_repo,
+        member: member_dir_name,
+        token,
+    };
+    if let Err(e) = demo.set_up_and_run(&manifest, no_run, &spinner) {
+        spinner.stop("Quickstart failed");
+        cliclack::log::error(format!("{:#}", e))?;
+        cliclack::outro(demo.cleanup_instructions())?;
+        return Err(e);
+    }
+
+    cliclack::outro(format!(
+        "Next steps:\n  \
+         bm status -t {team}            see the member and its last run\n  \
+         bm start -t {team}             keep the member running\n  \
+         bm daemon start -t {team}      run it whenever the repo changes\n\n{}",
+        demo.cleanup_instructions(),
+        team = team_name,
+    ))?;
+    Ok(())
+}
+
+/// The demo team, once its GitHub repo exists.
+struct Demo {
+    team_name: String,
+    team_dir: PathBuf,
+    github_repo: String,
+    /// Member directory name, e.g. `superman-01`.
+    member: String,
+    token: String,
+}
+
+impl Demo {
+    fn owner(&self) -> &str {
+        self.github_repo.split('/').next().unwrap_or(&self.github_repo)
+    }
+
+    /// Everything after the GitHub repo exists: registration, labels, board,
+    /// workspace, demo issue, and the supervised run.
+    fn set_up_and_run(
+        &self,
+        manifest: &profile::ProfileManifest,
+        no_run: bool,
+        spinner: &cliclack::ProgressBar,
+    ) -> Result<()> {
+        let token = Some(self.token.as_str());
+
+        spinner.start("Registering team...");
+        let config_lock = config::lock()?;
+        let mut cfg = init::load_or_default_config()?;
+        cfg.teams.push(TeamEntry {
+            name: self.team_name.clone(),
+            path: self.team_dir.clone(),
+            profile: PROFILE.to_string(),
+            github_repo: self.github_repo.clone(),
+            extra_repos: Vec::new(),
+            budget: None,
+            credentials: Credentials {
+                gh_token: Some(self.token.clone()),
+                ..Default::default()
+            },
+        });
+        if cfg.teams.len() == 1 {
+            cfg.default_team = Some(self.team_name.clone());
+        }
+        config::save(&cfg)?;
+        drop(config_lock);
+
+        spinner.start("Bootstrapping labels...");
+        init::bootstrap_labels(&self.github_repo, &manifest.labels, token)?;
+
+        spinner.start("Creating GitHub Project board...");
+        let number =
+            init::create_github_project(self.owner(), &self.team_name, &manifest.statuses, token)?;
+        spinner.stop("Team created");
+
+        let synced = teams::sync(false, false, None, None, Some(&self.team_name))?;
+        print!("{}", synced);
+        synced.ensure_ok()?;
+
+        spinner.start("Opening the demo issue...");
+        let issue = demo_issue();
+        let url = projects::create_issue(&self.github_repo, &issue, token)?;
+        StatusBoard::load(self.owner(), number, token)?.add(&url, issue.status.as_deref(), token)?;
+        spinner.stop(format!("Demo issue: {}", url));
+
+        if no_run {
+            cliclack::log::info(format!(
+                "Skipped the run. Start the member with `bm start -t {}`.",
+                self.team_name
+            ))?;
+            return Ok(());
+        }
+
+        cliclack::log::step(format!(
+            "Running {} once. Its output follows; press Ctrl-C to stop early.",
+            self.member
+        ))?;
+        let cfg = config::load()?;
+        let team = config::resolve_team(&cfg, Some(&self.team_name))?;
+        let status = run_member_once(team, &self.member)?;
+        if status.success() {
+            cliclack::log::success(format!(
+                "{} finished its first run. See what it did on {}",
+                self.member, url
+            ))?;
+        } else {
+            cliclack::log::warning(format!(
+                "{} exited with {}. Its output above shows why; rerun with `bm start -t {}`.",
+                self.member, status, self.team_name
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// How to remove everything the quickstart created.
+    fn cleanup_instructions(&self) -> String {
+        format!(
+            "To remove the demo team, its GitHub repo, and its board:\n  \
+             bm teams destroy {}\n\
+             (Deleting the repo needs the delete_repo scope: gh auth refresh -s delete_repo)",
+            self.team_name
+        )
+    }
+}
+
+/// Runs `ralph run -p PROMPT.md` for one member in the foreground, with its
+/// output on this terminal, and waits for it to exit.
+fn run_member_once(team: &TeamEntry, member_dir_name: &str) -> Result<std::process::ExitStatus> {
+    let ws = team.path.join(member_dir_name);
+    let auth = github_app::resolve_member_auth(team, member_dir_name)?
+        .unwrap_or_else(|| MemberAuth::Token(String::new()));
+    let members_dir = team.path.join("team").join("team");
+    let overrides = launch_env::read_overrides(&members_dir.join(member_dir_name))?;
+    let env = MemberEnv::new(&auth, &team.credentials).with_overrides(&overrides, &team.credentials)?;
+
+    let mut cmd = Command::new("ralph");
+    cmd.args(["run", "-p", "PROMPT.md"])
+        .current_dir(&ws)
+        .stdin(Stdio::null());
+    env.apply(&mut cmd);
+    cmd.status()
+        .with_context(|| format!("Failed to run ralph in {}", ws.display()))
+}
+
+/// First `quickstart`, `quickstart-2`, … not registered and not on disk.
+fn free_team_name(cfg: &BotminterConfig) -> String {
+    (1..)
+        .map(|n| match n {
+            1 => DEFAULT_NAME.to_string(),
+            n => format!("{}-{}", DEFAULT_NAME, n),
+        })
+        .find(|name| {
+            !cfg.teams.iter().any(|t| &t.name == name) && !cfg.workzone.join(name).exists()
+        })
+        .unwrap_or_else(|| DEFAULT_NAME.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn cfg(workzone: &Path, teams: &[&str]) -> BotminterConfig {
+        BotminterConfig {
+            version: config::CONFIG_VERSION,
+            workzone: workzone.to_path_buf(),
+            default_team: None,
+            token_expiry_warn_days: None,
+            logs: None,
+            timestamps: None,
+            workspace: None,
+            state_backend: None,
+            teams: teams
+                .iter()
+                .map(|name| TeamEntry {
+                    name: name.to_string(),
+                    path: workzone.join(name),
+                    profile: PROFILE.to_string(),
+                    github_repo: String::new(),
+                    extra_repos: Vec::new(),
+                    budget: None,
+                    credentials: Credentials::default(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn free_team_name_skips_registered_and_leftover_teams() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(free_team_name(&cfg(tmp.path(), &[])), "quickstart");
+        assert_eq!(free_team_name(&cfg(tmp.path(), &["quickstart"])), "quickstart-2");
+        fs::create_dir_all(tmp.path().join("quickstart-2")).unwrap();
+        assert_eq!(free_team_name(&cfg(tmp.path(), &["quickstart"])), "quickstart-3");
+    }
+
+    #[test]
+    fn demo_issue_fits_the_profile() {
+        let mut manifest = profile::read_manifest(PROFILE).unwrap();
+        manifest.seed_issues = vec![demo_issue()];
+        assert_eq!(manifest.seed_issue_problems(), Vec::<String>::new());
+        assert!(!manifest.roles.is_empty());
+    }
+
+    #[test]
+    fn cleanup_points_at_teams_destroy() {
+        let demo = Demo {
+            team_name: "quickstart-2".to_string(),
+            team_dir: PathBuf::from("/w/quickstart-2"),
+            github_repo: "alice/quickstart-2-team".to_string(),
+            member: "superman-01".to_string(),
+            token: String::new(),
+        };
+        let text = demo.cleanup_instructions();
+        assert!(text.contains("bm teams destroy quickstart-2"), "{}", text);
+        assert!(text.contains("delete_repo"), "{}", text);
+    }
+}
@@ -1,8 +1,14 @@
-use anyhow::Result;
-use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL_CONDENSED, Table};
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
 
 use crate::config;
-use crate::profile;
+use crate::profile::{self, ProfileManifest, RoleDef};
+use crate::tables;
+use crate::workspace_templates;
+
+/// Lines of PROMPT.md `bm roles describe` shows without `--full`.
+const PREVIEW_LINES: usize = 20;
 
 /// Handles `bm roles list [-t team]`.
 pub fn list(team_flag: Option<&str>) -> Result<()> {
@@ -11,16 +17,156 @@ pub fn list(team_flag: Option<&str>) -> Result<()> {
 
     let manifest = profile::read_manifest(&team.profile)?;
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL_CONDENSED)
-        .apply_modifier(UTF8_ROUND_CORNERS)
-        .set_header(vec!["Role", "Description"]);
+    let mut table = tables::new(vec!["Role", "Members", "Description"]);
 
     for role in &manifest.roles {
-        table.add_row(vec![role.name.as_str(), role.description.as_str()]);
+        let capacity = role.capacity().unwrap_or_else(|| "any".to_string());
+        table.add_row(vec![role.name.as_str(), capacity.as_str(), role.description.as_str()]);
     }
 
-    println!("{table}");
+    println!("{}", tables::render(&table));
     Ok(())
 }
+
+/// Handles `bm roles describe <role> [--full] [-t team]` — shows what hiring
+/// into a role gives a member: its skeleton, its PROMPT.md as rendered for a
+/// new hire, and the board statuses it works.
+pub fn describe(role_name: &str, full: bool, team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+
+    let manifest = profile::read_manifest(&team.profile)?;
+    let Some(role) = manifest.roles.iter().find(|r| r.name == role_name) else {
+        let names: Vec<&str> = manifest.roles.iter().map(|r| r.name.as_str()).collect();
+        bail!(
+            "Role '{}' not available in profile '{}'. Available roles: {}",
+            role_name,
+            team.profile,
+            names.join(", ")
+        );
+    };
+
+    println!("Role: {}", role.name);
+    println!("Profile: {}", team.profile);
+    println!("Description: {}", role.description);
+    println!(
+        "Members: {}",
+        role.capacity().unwrap_or_else(|| "any".to_string())
+    );
+
+    println!();
+    if role.prefixes.is_empty() {
+        println!("Statuses: the whole board (no prefixes)");
+    } else {
+        println!("Statuses ({}):", role.prefixes.join(", "));
+        let statuses = role_statuses(&manifest, role);
+        if statuses.is_empty() {
+            println!("  (no status matches these prefixes)");
+        }
+        for (status, views) in statuses {
+            if views.is_empty() {
+                println!("  {}", status);
+            } else {
+                println!("  {:<24} views: {}", status, views.join(", "));
+            }
+        }
+    }
+
+    println!();
+    println!("Skeleton files:");
+    for file in profile::list_skeleton_files(&team.profile, &role.name)? {
+        println!("  {}", file);
+    }
+
+    println!();
+    let Some(prompt) = profile::read_skeleton_file(&team.profile, &role.name, "PROMPT.md")? else {
+        println!("PROMPT.md: none");
+        return Ok(());
+    };
+    let member_dir = format!("{}-01", role.name);
+    let vars = BTreeMap::from([
+        ("member_name", "01".to_string()),
+        ("member_dir", member_dir.clone()),
+        ("role", role.name.clone()),
+        ("team_name", team.name.clone()),
+        ("github_repo", team.github_repo.clone()),
+    ]);
+    let rendered = workspace_templates::render(&prompt, &vars).unwrap_or(prompt);
+    println!("PROMPT.md (as rendered for {}):", member_dir);
+    let max_lines = if full { None } else { Some(PREVIEW_LINES) };
+    print!("{}", preview(&rendered, max_lines));
+    Ok(())
+}
+
+/// Statuses the role's prefixes claim, in board order, each with the views
+/// that show it.
+fn role_statuses<'a>(
+    manifest: &'a ProfileManifest,
+    role: &RoleDef,
+) -> Vec<(&'a str, Vec<&'a str>)> {
+    manifest
+        .statuses
+        .iter()
+        .filter(|s| {
+            role.prefixes
+                .iter()
+                .any(|p| s.name.starts_with(&format!("{}:", p)))
+        })
+        .map(|s| {
+            let views = manifest
+                .views
+                .iter()
+                .filter(|v| v.resolve_statuses(&manifest.statuses).contains(&s.name))
+                .map(|v| v.name.as_str())
+                .collect();
+            (s.name.as_str(), views)
+        })
+        .collect()
+}
+
+/// Indents `text` for display, cut to `max_lines` with a note of how many
+/// lines were left out.
+fn preview(text: &str, max_lines: Option<usize>) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let shown = max_lines.unwrap_or(lines.len()).min(lines.len());
+    let mut out = String::new();
+    for line in &lines[..shown] {
+        out.push_str(&format!("  │ {}\n", line).replace("│ \n", "│\n"));
+    }
+    if shown < lines.len() {
+        out.push_str(&format!(
+            "  … {} more line(s); pass --full to see all\n",
+            lines.len() - shown
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn role_statuses_follow_prefixes_and_views() {
+        let manifest = profile::read_manifest("scrum").unwrap();
+        let arch = manifest
+            .roles
+            .iter()
+            .find(|r| r.name == "architect")
+            .unwrap();
+        let statuses = role_statuses(&manifest, arch);
+        assert!(!statuses.is_empty());
+        assert!(statuses.iter().all(|(s, _)| s.starts_with("arch:")));
+        assert!(statuses.iter().any(|(_, views)| !views.is_empty()));
+    }
+
+    #[test]
+    fn preview_cuts_long_prompts() {
+        let text = "one\n\nthree\nfour\n";
+        assert_eq!(preview(text, None), "  │ one\n  │\n  │ three\n  │ four\n");
+        assert_eq!(
+            preview(text, Some(2)),
+            "  │ one\n  │\n  … 2 more line(s); pass --full to see all\n"
+        );
+    }
+}
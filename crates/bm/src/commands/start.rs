@@ -1,23 +1,405 @@
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
-use std::process::Command;
 use std::thread;
 use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use tracing::{debug, info_span};
 
+use crate::budget;
+use crate::cgroup::{self, Backend, Limits};
+use crate::commands::{daemon, stop};
 use crate::config::{self, TeamEntry};
-use crate::formation;
+use crate::daemon_settings;
+use crate::disk_quota::Action;
+use crate::dry_run::{self, Plan, Step};
+use crate::formation::{self, RoleResources};
+use crate::github_app::{self, MemberAuth};
+use crate::launch_env::{self, MemberEnv};
+use crate::logging;
+use crate::members;
+use crate::parse;
+use crate::preflight;
+use crate::priority::Priority;
+use crate::procutil;
 use crate::profile;
-use crate::state::{self, MemberRuntime, RuntimeState};
+use crate::readiness::{self, Readiness};
+use crate::state::{self, Health, MemberRuntime, RuntimeState};
 use crate::topology::{self, Endpoint, MemberTopology, Topology};
+use crate::workspace::{self, WipPush};
+
+/// What `bm start` did with one member.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum Outcome {
+    Started { pid: u32 },
+    AlreadyRunning { pid: u32 },
+    /// Not started because of a role's concurrency limit or the disk quota.
+    Held { reason: String },
+    Failed { error: String },
+    /// Running, but its readiness probe didn't pass in time.
+    Unhealthy { pid: u32, reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MemberStart {
+    pub member: String,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+}
+
+/// What `bm start` did.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Started {
+    pub team: String,
+    /// State entries of dead processes that were cleaned up, as `team/member`.
+    pub cleaned_stale: Vec<String>,
+    pub members: Vec<MemberStart>,
+    /// The non-local formation whose manager deployed the team, in place of
+    /// local launches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formation: Option<String>,
+}
 
-/// Handles `bm start [-t team] [--formation <name>]`.
-pub fn run(team_flag: Option<&str>, formation_flag: Option<&str>) -> Result<()> {
+impl Started {
+    fn count(&self, matches: fn(&Outcome) -> bool) -> usize {
+        self.members.iter().filter(|m| matches(&m.outcome)).count()
+    }
+
+    pub fn launched(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Started { .. }))
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::AlreadyRunning { .. }))
+    }
+
+    pub fn held(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Held { .. }))
+    }
+
+    pub fn failed(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Failed { .. }))
+    }
+
+    pub fn unhealthy(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Unhealthy { .. }))
+    }
+
+    /// Fails if any member could not be started or didn't become ready.
+    pub fn ensure_ok(&self) -> Result<()> {
+        if self.failed() > 0 {
+            bail!("Some members failed to start. See errors above.");
+        }
+        if self.unhealthy() > 0 {
+            bail!("Some members didn't become ready. They were left running; see `bm status`.");
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Started {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref name) = self.formation {
+            return writeln!(f, "Formation '{}' deployed successfully.", name);
+        }
+        for key in &self.cleaned_stale {
+            writeln!(f, "Cleaned stale entry for {}", key)?;
+        }
+        for m in &self.members {
+            match &m.outcome {
+                Outcome::Started { pid } => writeln!(f, "{}: started (PID {})", m.member, pid)?,
+                Outcome::AlreadyRunning { pid } => {
+                    writeln!(f, "{}: already running (PID {})", m.member, pid)?
+                }
+                Outcome::Held { reason } => writeln!(f, "{}: not started, {}", m.member, reason)?,
+                Outcome::Failed { error } => writeln!(f, "{}: {}", m.member, error)?,
+                Outcome::Unhealthy { pid, reason } => writeln!(
+                    f,
+                    "{}: unhealthy, running (PID {}) but not ready ({})",
+                    m.member, pid, reason
+                )?,
+            }
+        }
+        if self.unhealthy() > 0 {
+            writeln!(f, "\n{} member(s) didn't become ready.", self.unhealthy())?;
+        }
+        if self.held() > 0 {
+            writeln!(
+                f,
+                "\nStarted {} member(s), skipped {} (already running), {} held back by role or disk limits, {} error(s).",
+                self.launched(),
+                self.skipped(),
+                self.held(),
+                self.failed()
+            )
+        } else {
+            writeln!(
+                f,
+                "\nStarted {} member(s), skipped {} (already running), {} error(s).",
+                self.launched(),
+                self.skipped(),
+                self.failed()
+            )
+        }
+    }
+}
+
+/// Handles `bm start [-t team] [--formation <name>] [--force] [--override]`.
+/// `force` starts a paused team; `override_budget` one over its budget.
+pub fn run(
+    team_flag: Option<&str>,
+    formation_flag: Option<&str>,
+    force: bool,
+    override_budget: bool,
+) -> Result<Started> {
+    launch(team_flag, formation_flag, None, force, override_budget, None)
+}
+
+/// Handles `bm start --dry-run`: makes the same checks as `bm start`, then
+/// lists the processes it would launch and the state it would record.
+pub fn plan(
+    team_flag: Option<&str>,
+    formation_flag: Option<&str>,
+    force: bool,
+    override_budget: bool,
+) -> Result<Plan> {
+    let mut plan = Plan::default();
+    launch(team_flag, formation_flag, None, force, override_budget, Some(&mut plan))?;
+    Ok(plan)
+}
+
+/// Starts one member of a team deployed with the local formation, as
+/// `bm start` would.
+pub fn run_member(team_flag: Option<&str>, member: &str) -> Result<Started> {
+    launch(team_flag, None, Some(member), false, false, None)
+}
+
+/// What `bm start --migrate` did with one member.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemberMigration {
+    pub member: String,
+    /// Whether a local process was stopped.
+    pub stopped: bool,
+    /// Branches its workspaces' work was pushed to.
+    pub pushed: Vec<String>,
+    /// Its status under the new formation, if the formation placed it.
+    pub deployed: Option<String>,
+}
+
+/// What `bm start --migrate` did.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Migrated {
+    pub team: String,
+    pub formation: String,
+    pub members: Vec<MemberMigration>,
+    /// Local members the new formation didn't place, kept in the topology
+    /// as stopped.
+    pub left_behind: Vec<String>,
+}
+
+impl fmt::Display for Migrated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for m in &self.members {
+            let mut parts = Vec::new();
+            if m.stopped {
+                parts.push("stopped locally".to_string());
+            }
+            if m.pushed.is_empty() {
+                parts.push("nothing to push".to_string());
+            } else {
+                parts.push(format!("pushed {}", m.pushed.join(", ")));
+            }
+            parts.push(match m.deployed {
+                Some(ref status) => format!("deployed ({})", status),
+                None => "not deployed".to_string(),
+            });
+            writeln!(f, "{}: {}", m.member, parts.join(", "))?;
+        }
+        let deployed = self.members.iter().filter(|m| m.deployed.is_some()).count();
+        writeln!(
+            f,
+            "\nMigrated team '{}' to formation '{}': {} member(s) deployed, {} not.",
+            self.team,
+            self.formation,
+            deployed,
+            self.members.len() - deployed
+        )?;
+        if !self.left_behind.is_empty() {
+            writeln!(
+                f,
+                "Kept in the topology as stopped: {}. Run `bm start` to bring them back locally.",
+                self.left_behind.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Handles `bm start --formation <name> --migrate`: moves a team running
+/// locally onto a non-local formation. Stops each local member gracefully,
+/// pushes its workspaces' work, deploys the formation with the pushed
+/// branches in `BM_MIGRATED_BRANCHES`, then writes a topology merging the
+/// new placements with local members the formation left out.
+pub fn migrate(
+    team_flag: Option<&str>,
+    formation_name: &str,
+    force: bool,
+    override_budget: bool,
+) -> Result<Migrated> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, team_flag)?;
+    check_startable(team, force, override_budget)?;
     let team_repo = team.path.join("team");
+    let (team_schema, team_profile) = team_schema(team, &team_repo)?;
+    formation::check_available(&team_repo, &team_profile, formation_name)?;
+    let formation_cfg = formation::load(&team_repo, formation_name)?;
+    if formation_cfg.is_local() {
+        bail!(
+            "Formation '{}' is local; --migrate moves a team off the local formation. Use `bm start`.",
+            formation_name
+        );
+    }
+    profile::require_current_schema(&team.name, &team_schema)?;
+    formation_cfg.check_tools()?;
+    // Nothing is stopped unless the formation can be deployed
+    preflight::check_formation(&formation_cfg, team)?;
+
+    let topo_path = topology::topology_path(&cfg.workzone, &team.name);
+    let recorded = topology::load(&topo_path)?;
+    if recorded.as_ref().is_some_and(|t| t.formation == formation_cfg.name) {
+        bail!(
+            "Team '{}' is already deployed with formation '{}'.",
+            team.name,
+            formation_cfg.name
+        );
+    }
 
-    // Schema version guard
+    // Where members run now, for the merged topology
+    let team_prefix = format!("{}/", team.name);
+    let runtime_state = state::load()?;
+    let running: BTreeMap<String, &MemberRuntime> = runtime_state
+        .members
+        .iter()
+        .filter_map(|(key, rt)| Some((key.strip_prefix(&team_prefix)?.to_string(), rt)))
+        .collect();
+    let previous = recorded.unwrap_or_else(|| Topology {
+        formation: "local".to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        members: running
+            .iter()
+            .map(|(name, rt)| {
+                let endpoint = Endpoint::Local {
+                    pid: rt.pid,
+                    workspace: rt.workspace.clone(),
+                };
+                let status = "running".to_string();
+                (name.clone(), MemberTopology { status, endpoint })
+            })
+            .collect(),
+    });
+
+    let mut names = members::list_names(&team_repo.join("team")).unwrap_or_default();
+    names.extend(running.keys().filter(|n| !names.contains(n)).cloned().collect::<Vec<_>>());
+    names.sort();
+
+    let mut migrations = Vec::new();
+    let mut unpushed = Vec::new();
+    for name in names {
+        let mut migration = MemberMigration {
+            member: name.clone(),
+            ..MemberMigration::default()
+        };
+        if running.contains_key(&name) {
+            let how = stop::stop_member(team, &name, stop::DEFAULT_GRACEFUL_TIMEOUT_SECS)?;
+            eprintln!("{}", how);
+            migration.stopped = true;
+        }
+        let message = format!("wip: {} moving to the {} formation", name, formation_cfg.name);
+        for ws in members::workspaces(&team.path, &name) {
+            match workspace::push_wip(&ws.path, &name, &message) {
+                Ok(WipPush::Pushed { branch, .. }) => migration.pushed.push(branch),
+                Ok(WipPush::Clean) => {}
+                Err(e) => unpushed.push(format!("  {}: {:#}", ws.path.display(), e)),
+            }
+        }
+        migrations.push(migration);
+    }
+    if !unpushed.is_empty() {
+        bail!(
+            "Couldn't push work from these workspaces, so formation '{}' was not deployed:\n{}\n\
+             Local members were stopped; their work is still in the workspaces. \
+             Fix the push and rerun `bm start --formation {} --migrate`, or run `bm start` to resume locally.",
+            formation_cfg.name,
+            unpushed.join("\n"),
+            formation_cfg.name
+        );
+    }
+
+    let branches: BTreeMap<&str, &[String]> = migrations
+        .iter()
+        .filter(|m| !m.pushed.is_empty())
+        .map(|m| (m.member.as_str(), m.pushed.as_slice()))
+        .collect();
+    let env_vars = vec![(
+        "BM_MIGRATED_BRANCHES".to_string(),
+        serde_json::to_string(&branches).context("Failed to serialize migrated branches")?,
+    )];
+    deploy_formation(team, &team_repo, &formation_cfg, &cfg.workzone, env_vars).with_context(
+        || "Local members were stopped and their work pushed. Run `bm start` to resume them locally",
+    )?;
+
+    let mut topo = topology::load(&topo_path)?
+        .with_context(|| format!("No topology at {} after deploying", topo_path.display()))?;
+    let left_behind = topology::merge(&mut topo, previous);
+    topology::save(&topo_path, &topo)?;
+    for migration in &mut migrations {
+        if !left_behind.contains(&migration.member) {
+            migration.deployed = topo.members.get(&migration.member).map(|m| m.status.clone());
+        }
+    }
+
+    Ok(Migrated {
+        team: team.name.clone(),
+        formation: formation_cfg.name,
+        members: migrations,
+        left_behind,
+    })
+}
+
+/// Refuses to start a paused team, unless `force`, or one over its budget,
+/// unless `override_budget`.
+fn check_startable(team: &TeamEntry, force: bool, override_budget: bool) -> Result<()> {
+    if !force {
+        if let Some(note) = state::paused(&team.name) {
+            bail!(
+                "Team '{}' is paused {}. Run `bm resume` first, or `bm start --force`.",
+                team.name,
+                note
+            );
+        }
+    }
+    if let Some(overrun) = budget::check(team)? {
+        if !override_budget {
+            bail!(
+                "Team '{}' has {}. Raise `budget` in config.yml, or `bm start --override`.",
+                team.name,
+                overrun
+            );
+        }
+        eprintln!(
+            "Warning: starting team '{}' although it has {}.",
+            team.name, overrun
+        );
+    }
+    Ok(())
+}
+
+/// Reads the team repo's schema version and profile, checking that the
+/// schema is one this bm can run.
+fn team_schema(team: &TeamEntry, team_repo: &std::path::Path) -> Result<(String, String)> {
     let manifest_path = team_repo.join("botminter.yml");
     if !manifest_path.exists() {
         bail!(
@@ -27,27 +409,65 @@ pub fn run(team_flag: Option<&str>, formation_flag: Option<&str>) -> Result<()>
     }
     let manifest_contents = fs::read_to_string(&manifest_path)
         .context("Failed to read team botminter.yml")?;
-    let team_manifest: serde_yml::Value =
-        serde_yml::from_str(&manifest_contents).context("Failed to parse team botminter.yml")?;
+    let team_manifest: serde_yml::Value = parse::yaml(&manifest_path, &manifest_contents)?;
     let team_schema = team_manifest["schema_version"]
         .as_str()
-        .unwrap_or("");
+        .unwrap_or("")
+        .to_string();
     let team_profile = team_manifest["profile"]
         .as_str()
-        .unwrap_or(&team.profile);
-    profile::check_schema_version(team_profile, team_schema)?;
+        .unwrap_or(&team.profile)
+        .to_string();
+    profile::check_schema_version(&team_profile, &team_schema)?;
+    Ok((team_schema, team_profile))
+}
+
+/// Starts the team's members, or only `only`.
+fn launch(
+    team_flag: Option<&str>,
+    formation_flag: Option<&str>,
+    only: Option<&str>,
+    force: bool,
+    override_budget: bool,
+    mut plan: Option<&mut Plan>,
+) -> Result<Started> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    check_startable(team, force, override_budget)?;
+    let team_repo = team.path.join("team");
+    let (team_schema, team_profile) = team_schema(team, &team_repo)?;
 
     // Resolve formation
+    if let Some(name) = formation_flag {
+        formation::check_available(&team_repo, &team_profile, name)?;
+    }
     let resolved_formation = formation::resolve_formation(&team_repo, formation_flag)?;
 
     // Non-local formations require current schema
     if let Some(ref fname) = resolved_formation {
         if fname != "local" {
-            profile::require_current_schema(&team.name, team_schema)?;
+            profile::require_current_schema(&team.name, &team_schema)?;
             // Non-local formations delegate to formation manager
             let formation_cfg = formation::load(&team_repo, fname)?;
+            formation_cfg.check_tools()?;
             if !formation_cfg.is_local() {
-                return run_formation_manager(team, &team_repo, &formation_cfg, &cfg.workzone);
+                if let Some(member) = only {
+                    bail!(
+                        "Member '{}' is deployed with formation '{}'; \
+                         single members can only be started with the local formation",
+                        member,
+                        formation_cfg.name
+                    );
+                }
+                match plan {
+                    Some(plan) => plan_formation_manager(team, &team_repo, &formation_cfg, plan)?,
+                    None => run_formation_manager(team, &team_repo, &formation_cfg, &cfg.workzone)?,
+                }
+                return Ok(Started {
+                    team: team.name.clone(),
+                    formation: Some(formation_cfg.name),
+                    ..Started::default()
+                });
             }
         }
     }
@@ -57,9 +477,31 @@ pub fn run(team_flag: Option<&str>, formation_flag: Option<&str>) -> Result<()>
         bail!("'ralph' not found in PATH. Install ralph-orchestrator first.");
     }
 
-    // Credentials → env vars
-    let gh_token = require_gh_token(team)?;
-    let telegram_token = team.credentials.telegram_bot_token.as_deref();
+    // Per-role resource limits from the local formation, if it declares any
+    let resources: BTreeMap<String, RoleResources> = match resolved_formation.as_deref() {
+        Some(fname) if formation::formations_dir(&team_repo).join(fname).is_dir() => {
+            let formation_cfg = formation::load(&team_repo, fname)?;
+            formation_cfg.check_tools()?;
+            formation_cfg.resources
+        }
+        _ => BTreeMap::new(),
+    };
+    let backend = if resources.values().any(|r| r.cpu.is_some() || r.memory.is_some()) {
+        let backend = cgroup::detect();
+        if let Backend::Unavailable(ref reason) = backend {
+            eprintln!("Warning: CPU and memory limits will not be applied: {}", reason);
+        }
+        backend
+    } else {
+        Backend::Unavailable("no limits configured".to_string())
+    };
+
+    // Credentials → env vars (GitHub App teams mint per-member tokens below)
+    let gh_token = match team.credentials.github_app {
+        Some(_) => String::new(),
+        None => require_gh_token(team)?,
+    };
+    let disk_quota = daemon_settings::load(&team_repo, &team.name)?.disk_quota;
 
     // Discover members
     let members_dir = team_repo.join("team");
@@ -67,102 +509,279 @@ pub fn run(team_flag: Option<&str>, formation_flag: Option<&str>) -> Result<()>
         bail!("No members hired. Run `bm hire <role>` first.");
     }
 
-    let member_dirs = list_member_dirs(&members_dir)?;
+    let mut member_dirs = members::list_names(&members_dir)?;
     if member_dirs.is_empty() {
         bail!("No members hired. Run `bm hire <role>` first.");
     }
-
-    // Load state, clean up stale entries
-    let mut state = state::load()?;
-    let stale = state::cleanup_stale(&mut state);
-    if !stale.is_empty() {
-        for key in &stale {
-            eprintln!("Cleaned stale entry for {}", key);
+    if let Some(member) = only {
+        if !member_dirs.iter().any(|m| m == member) {
+            bail!("Member '{}' is not hired in team '{}'", member, team.name);
         }
-        state::save(&state)?;
+        member_dirs.retain(|m| m == member);
     }
 
+    // Clean up stale entries, keeping a snapshot of the result
+    let (mut state, cleaned_stale) = match plan.as_deref_mut() {
+        Some(plan) => {
+            let mut state = state::load()?;
+            let stale = state::cleanup_stale(&mut state);
+            for key in &stale {
+                plan.push(Step::UpdateState(format!("drop the stale entry '{}'", key)));
+            }
+            (state, stale)
+        }
+        None => state::update(|s| {
+            let stale = state::cleanup_stale(s);
+            Ok((s.clone(), stale))
+        })?,
+    };
+    let mut summary = Started {
+        team: team.name.clone(),
+        cleaned_stale,
+        ..Started::default()
+    };
+
     // Discover workspaces and launch
     let workzone = &cfg.workzone;
     let team_ws_base = workzone.join(&team.name);
-    let mut launched = 0u32;
-    let mut skipped = 0u32;
-    let mut errors = 0u32;
 
+    let mut planned_launches = 0;
+    let mut pending = Vec::new();
     for member_dir_name in &member_dirs {
+        let _member = info_span!("member", member = %member_dir_name).entered();
         let state_key = format!("{}/{}", team.name, member_dir_name);
+        let mut record = |outcome| {
+            summary.members.push(MemberStart {
+                member: member_dir_name.clone(),
+                outcome,
+            })
+        };
 
         // Check if already running
         if let Some(rt) = state.members.get(&state_key) {
-            if state::is_alive(rt.pid) {
-                eprintln!(
-                    "{}: already running (PID {})",
-                    member_dir_name, rt.pid
-                );
-                skipped += 1;
+            if procutil::is_alive(rt.pid) {
+                record(Outcome::AlreadyRunning { pid: rt.pid });
                 continue;
             }
             // Stale — remove and re-launch
             state.members.remove(&state_key);
         }
 
+        let role_resources = formation::resources_for(&resources, member_dir_name);
+        if let Some(held) = role_hold(&state, &team.name, &resources, role_resources) {
+            record(held);
+            continue;
+        }
+
         // Find workspace
-        let ws = find_workspace(&team_ws_base, member_dir_name);
+        let ws = members::find_workspace(&team_ws_base, member_dir_name).map(|w| w.path);
         let ws = match ws {
             Some(ws) => ws,
             None => {
+                record(Outcome::Failed {
+                    error: "no workspace found. Run `bm teams sync` first.".to_string(),
+                });
+                continue;
+            }
+        };
+
+        if let Some(ref quota) = disk_quota {
+            if let Some(over) = quota.check(&ws)? {
+                if quota.action == Action::Block {
+                    record(Outcome::Held {
+                        reason: format!(
+                            "workspace is over its disk quota ({}). Free up space in {}.",
+                            over.describe(),
+                            ws.display()
+                        ),
+                    });
+                    continue;
+                }
                 eprintln!(
-                    "{}: no workspace found. Run `bm teams sync` first.",
-                    member_dir_name
+                    "{}: workspace is over its disk quota ({})",
+                    member_dir_name,
+                    over.describe()
                 );
-                errors += 1;
+            }
+        }
+
+        let role_limits = match role_resources {
+            Some((_, r)) => (
+                Limits {
+                    cpu_cores: r.cpu_cores()?,
+                    memory_bytes: r.memory_bytes()?,
+                },
+                r.priority()?,
+            ),
+            None => (Limits::default(), Priority::default()),
+        };
+        let probe = match readiness::read(&members_dir.join(member_dir_name)) {
+            Ok(probe) => probe,
+            Err(e) => {
+                record(Outcome::Failed {
+                    error: format!("{:#}", e),
+                });
                 continue;
             }
         };
+        if let Some(plan) = plan.as_deref_mut() {
+            if team.credentials.github_app.is_some() {
+                plan.push(Step::GitHub(format!(
+                    "mint an installation token for {}",
+                    member_dir_name
+                )));
+            }
+            let (limits, priority) = &role_limits;
+            let cmd = ralph_command(&ws, &backend, limits, *priority);
+            plan.push(Step::Run {
+                command: dry_run::command_line(&cmd),
+                dir: ws.clone(),
+            });
+            plan.push(Step::UpdateState(match probe {
+                Some(_) => format!("record {} as starting until its readiness probe passes", state_key),
+                None => format!("record {} as running", state_key),
+            }));
+            planned_launches += 1;
+            continue;
+        }
+
+        let auth = match team.credentials.github_app {
+            Some(ref app) => match github_app::ensure_member_token(team, app, member_dir_name) {
+                Ok(dir) => MemberAuth::ConfigDir(dir),
+                Err(e) => {
+                    record(Outcome::Failed {
+                        error: format!("{:#}", e),
+                    });
+                    continue;
+                }
+            },
+            None => MemberAuth::Token(gh_token.clone()),
+        };
 
         // Launch ralph
-        match launch_ralph(&ws, &gh_token, telegram_token) {
+        let env = match launch_env::read_overrides(&members_dir.join(member_dir_name))
+            .and_then(|o| MemberEnv::new(&auth, &team.credentials).with_overrides(&o, &team.credentials))
+        {
+            Ok(env) => env,
+            Err(e) => {
+                record(Outcome::Failed {
+                    error: format!("{:#}", e),
+                });
+                continue;
+            }
+        };
+        let (limits, priority) = role_limits;
+        let launch_mark = readiness::Launch::new(&ws, &daemon::member_log_path(&team.name, member_dir_name)?);
+        let retention = cfg.logs.clone().unwrap_or_default();
+        // Check, launch and record the PID under one hold of the state lock:
+        // another `bm start` may have launched the member since the snapshot
+        // above, and must not launch it again before this one records it
+        let (launched, snapshot) = state::update(|s| {
+            if let Some(rt) = s.members.get(&state_key).filter(|rt| procutil::is_alive(rt.pid)) {
+                return Ok((Err(Outcome::AlreadyRunning { pid: rt.pid }), s.clone()));
+            }
+            if let Some(held) = role_hold(s, &team.name, &resources, role_resources) {
+                return Ok((Err(held), s.clone()));
+            }
+            let launch = launch_ralph(
+                &ws,
+                &env,
+                &backend,
+                &limits,
+                priority,
+                &team.name,
+                member_dir_name,
+                &retention,
+            );
+            let pid = match launch {
+                Ok(pid) => pid,
+                Err(e) => {
+                    let error = format!("failed to launch — {}", e);
+                    return Ok((Err(Outcome::Failed { error }), s.clone()));
+                }
+            };
+            let runtime = MemberRuntime {
+                pid,
+                started_at: chrono::Utc::now().to_rfc3339(),
+                workspace: ws,
+                env: env.audit(),
+                health: probe.as_ref().map(|_| Health::Starting),
+            };
+            s.members.insert(state_key.clone(), runtime);
+            Ok((Ok(pid), s.clone()))
+        })?;
+        state = snapshot;
+        match launched {
             Ok(pid) => {
-                let started_at = chrono::Utc::now().to_rfc3339();
-                state.members.insert(
-                    state_key,
-                    MemberRuntime {
-                        pid,
-                        started_at,
-                        workspace: ws,
-                    },
-                );
-                state::save(&state)?;
-
                 // Verify alive after 2 seconds
                 thread::sleep(Duration::from_secs(2));
-                if state::is_alive(pid) {
-                    eprintln!("{}: started (PID {})", member_dir_name, pid);
-                    launched += 1;
+                if procutil::is_alive(pid) {
+                    match probe {
+                        Some(probe) => pending.push(Pending {
+                            member: member_dir_name.clone(),
+                            state_key,
+                            pid,
+                            probe,
+                            launch: launch_mark,
+                        }),
+                        None => record(Outcome::Started { pid }),
+                    }
                 } else {
-                    eprintln!(
-                        "{}: process exited immediately (PID {}). Check workspace logs.",
-                        member_dir_name, pid
-                    );
-                    state.members.remove(&format!("{}/{}", team.name, member_dir_name));
-                    state::save(&state)?;
-                    errors += 1;
+                    state = state::update(|s| {
+                        s.end_crashed_run(
+                            &state_key,
+                            state::last_log_write(&state_key),
+                            chrono::Utc::now(),
+                        );
+                        Ok(s.clone())
+                    })?;
+                    record(Outcome::Failed {
+                        error: format!(
+                            "process exited immediately (PID {}). Check {}.",
+                            pid,
+                            launch_mark.log().display()
+                        ),
+                    });
                 }
             }
-            Err(e) => {
-                eprintln!("{}: failed to launch — {}", member_dir_name, e);
-                errors += 1;
+            Err(outcome) => record(outcome),
+        }
+    }
+
+    if let Some(plan) = plan {
+        for m in &summary.members {
+            match &m.outcome {
+                Outcome::AlreadyRunning { pid } => {
+                    plan.skip(format!("{}: already running (PID {})", m.member, pid))
+                }
+                Outcome::Held { reason } => plan.skip(format!("{}: {}", m.member, reason)),
+                Outcome::Failed { error } => plan.skip(format!("{}: {}", m.member, error)),
+                Outcome::Started { .. } | Outcome::Unhealthy { .. } => {}
             }
         }
+        if team.credentials.github_app.is_some() && planned_launches > 0 {
+            plan.push(Step::Run {
+                command: format!("bm token-refresh --team {}", team.name),
+                dir: team.path.clone(),
+            });
+        }
+        if resolved_formation.is_some() {
+            plan.push(Step::WriteFile(topology::topology_path(&cfg.workzone, &team.name)));
+        }
+        return Ok(summary);
     }
 
-    println!(
-        "\nStarted {} member(s), skipped {} (already running), {} error(s).",
-        launched, skipped, errors
-    );
+    if !pending.is_empty() {
+        state = await_readiness(pending, &mut summary)?;
+    }
+
+    if summary.failed() > 0 {
+        return Ok(summary);
+    }
 
-    if errors > 0 {
-        bail!("Some members failed to start. See errors above.");
+    // Installation tokens expire hourly; keep them fresh while members run
+    if team.credentials.github_app.is_some() && summary.launched() > 0 {
+        github_app::spawn_refresher(&team.name)?;
     }
 
     // Write topology file for v2 teams (when formations dir exists)
@@ -170,7 +789,81 @@ pub fn run(team_flag: Option<&str>, formation_flag: Option<&str>) -> Result<()>
         write_local_topology(&cfg.workzone, &team.name, &state)?;
     }
 
-    Ok(())
+    Ok(summary)
+}
+
+/// A launched member whose readiness probe hasn't passed yet.
+struct Pending {
+    member: String,
+    state_key: String,
+    pid: u32,
+    probe: Readiness,
+    launch: readiness::Launch,
+}
+
+/// Polls the probes of `pending` members together until each passes, its
+/// member exits, or its timeout runs out. Records the outcomes in `summary`
+/// and each member's health in state, returning the updated state.
+fn await_readiness(mut pending: Vec<Pending>, summary: &mut Started) -> Result<RuntimeState> {
+    eprintln!("Waiting for {} member(s) to become ready...", pending.len());
+    let mut resolved = Vec::new();
+    while !pending.is_empty() {
+        let mut i = 0;
+        while i < pending.len() {
+            let p = &pending[i];
+            let outcome = match p.probe.check(&p.launch) {
+                Ok(()) => Some(Outcome::Started { pid: p.pid }),
+                Err(_) if !procutil::is_alive(p.pid) => Some(Outcome::Failed {
+                    error: format!(
+                        "process exited before becoming ready (PID {}). Check {}.",
+                        p.pid,
+                        p.launch.log().display()
+                    ),
+                }),
+                Err(reason) if p.launch.elapsed() >= p.probe.timeout() => {
+                    Some(Outcome::Unhealthy { pid: p.pid, reason })
+                }
+                Err(_) => None,
+            };
+            match outcome {
+                Some(outcome) => resolved.push((pending.remove(i), outcome)),
+                None => i += 1,
+            }
+        }
+        if !pending.is_empty() {
+            thread::sleep(Duration::from_millis(250));
+        }
+    }
+
+    let state = state::update(|s| {
+        for (p, outcome) in &resolved {
+            let health = match outcome {
+                Outcome::Failed { .. } => {
+                    s.end_crashed_run(
+                        &p.state_key,
+                        state::last_log_write(&p.state_key),
+                        chrono::Utc::now(),
+                    );
+                    continue;
+                }
+                Outcome::Unhealthy { reason, .. } => Some(Health::Unhealthy {
+                    reason: reason.clone(),
+                }),
+                _ => None,
+            };
+            if let Some(rt) = s.members.get_mut(&p.state_key) {
+                rt.health = health;
+            }
+        }
+        Ok(s.clone())
+    })?;
+    for (p, outcome) in resolved {
+        summary.members.push(MemberStart {
+            member: p.member,
+            outcome,
+        });
+    }
+    Ok(state)
 }
 
 /// Extracts GH_TOKEN from credentials, erroring if missing.
@@ -187,86 +880,99 @@ fn require_gh_token(team: &TeamEntry) -> Result<String> {
         })
 }
 
-/// Lists member directory names under `team/`.
-fn list_member_dirs(team_dir: &std::path::Path) -> Result<Vec<String>> {
-    let mut dirs = Vec::new();
-    for entry in fs::read_dir(team_dir)? {
-        let entry = entry?;
-        if !entry.file_type()?.is_dir() {
-            continue;
-        }
-        let name = entry.file_name().to_string_lossy().to_string();
-        if name.starts_with('.') {
-            continue;
-        }
-        dirs.push(name);
-    }
-    dirs.sort();
-    Ok(dirs)
+/// Counts the team's live members that fall under `role` in `resources`.
+/// Returns [`Outcome::Held`] if the member's role, per `role_resources`,
+/// already has its `max_concurrent_loops` members running.
+fn role_hold(
+    state: &RuntimeState,
+    team_name: &str,
+    resources: &BTreeMap<String, RoleResources>,
+    role_resources: Option<(&str, &RoleResources)>,
+) -> Option<Outcome> {
+    let (role, max) = role_resources.and_then(|(role, r)| Some((role, r.max_concurrent_loops?)))?;
+    let running = running_in_role(state, team_name, resources, role);
+    (running >= max as usize).then(|| Outcome::Held {
+        reason: format!(
+            "role '{}' already has {} of {} allowed member(s) running",
+            role, running, max
+        ),
+    })
 }
 
-/// Finds the workspace path for a member.
-/// Looks for `{team_ws_base}/{member_dir}/{project}/` first (with-project mode),
-/// falls back to `{team_ws_base}/{member_dir}/` (no-project mode).
-fn find_workspace(
-    team_ws_base: &std::path::Path,
-    member_dir_name: &str,
-) -> Option<std::path::PathBuf> {
-    let member_ws = team_ws_base.join(member_dir_name);
-    if !member_ws.is_dir() {
-        return None;
-    }
-
-    // Check for project subdirectories (workspace with project)
-    if let Ok(entries) = fs::read_dir(&member_ws) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                let name = entry.file_name().to_string_lossy().to_string();
-                if !name.starts_with('.') && path.join(".botminter").is_dir() {
-                    return Some(path);
-                }
-            }
-        }
-    }
-
-    // No-project mode: member_ws itself is the workspace
-    if member_ws.join(".botminter").is_dir() {
-        return Some(member_ws);
-    }
+fn running_in_role(
+    state: &RuntimeState,
+    team_name: &str,
+    resources: &BTreeMap<String, RoleResources>,
+    role: &str,
+) -> usize {
+    let team_prefix = format!("{}/", team_name);
+    state
+        .members
+        .iter()
+        .filter(|(key, rt)| {
+            key.strip_prefix(&team_prefix).is_some_and(|member| {
+                formation::resources_for(resources, member).is_some_and(|(r, _)| r == role)
+            }) && procutil::is_alive(rt.pid)
+        })
+        .count()
+}
 
-    None
+/// The `ralph run -p PROMPT.md` command for a member, before its env.
+fn ralph_command(
+    workspace: &std::path::Path,
+    backend: &Backend,
+    limits: &Limits,
+    priority: Priority,
+) -> std::process::Command {
+    let mut cmd = cgroup::command(backend, limits, "ralph", &["run", "-p", "PROMPT.md"]);
+    cmd.current_dir(workspace);
+    priority.apply(&mut cmd);
+    cmd
 }
 
-/// Launches `ralph run -p PROMPT.md` in the given workspace directory.
-/// Returns the child PID.
+/// Launches `ralph run -p PROMPT.md` in the given workspace directory,
+/// confined to `limits` through `backend` and at `priority`, with its
+/// output in the member log. Returns the child PID.
+#[allow(clippy::too_many_arguments)]
 fn launch_ralph(
     workspace: &std::path::Path,
-    gh_token: &str,
-    telegram_token: Option<&str>,
+    env: &MemberEnv,
+    backend: &Backend,
+    limits: &Limits,
+    priority: Priority,
+    team_name: &str,
+    member: &str,
+    retention: &logging::Retention,
 ) -> Result<u32> {
-    let mut cmd = Command::new("ralph");
-    cmd.args(["run", "-p", "PROMPT.md"])
-        .current_dir(workspace)
-        .env("GH_TOKEN", gh_token)
-        // Unset CLAUDECODE to avoid nested-Claude issues
-        .env_remove("CLAUDECODE");
-
-    if let Some(token) = telegram_token {
-        cmd.env("RALPH_TELEGRAM_BOT_TOKEN", token);
-    }
-
-    // Detach from current process group
-    cmd.stdin(std::process::Stdio::null())
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null());
-
-    let child = cmd.spawn().with_context(|| {
-        format!(
-            "Failed to spawn ralph in {}",
-            workspace.display()
-        )
-    })?;
+    debug!(
+        "Spawning ralph in {} with env {}",
+        workspace.display(),
+        env.audit_line()
+    );
+    let prepare = || -> Result<std::process::Command> {
+        let mut cmd = ralph_command(workspace, backend, limits, priority);
+        env.apply(&mut cmd);
+
+        // Own session, so stopping the member reaches the processes Ralph spawns
+        procutil::detach(&mut cmd);
+        cmd.stdin(std::process::Stdio::null());
+        daemon::redirect_to_member_log(&mut cmd, team_name, member, retention)?;
+        Ok(cmd)
+    };
+    let spawn_failed = || format!("Failed to spawn ralph in {}", workspace.display());
+
+    let child = match backend {
+        Backend::Direct(base) if !limits.is_empty() => {
+            let cgroup_name = format!("{}-{}", team_name, member);
+            let (child, skipped) =
+                cgroup::spawn_in(base, &cgroup_name, limits, prepare).with_context(spawn_failed)?;
+            if let Some(e) = skipped {
+                eprintln!("Warning: resource limits not applied: {:#}", e);
+            }
+            child
+        }
+        _ => prepare()?.spawn().with_context(spawn_failed)?,
+    };
 
     Ok(child.id())
 }
@@ -280,10 +986,11 @@ pub fn resolve_member_status(
     let key = format!("{}/{}", team_name, member_dir_name);
     match state.members.get(&key) {
         Some(rt) => {
-            if state::is_alive(rt.pid) {
+            if procutil::is_alive(rt.pid) {
                 MemberStatus::Running {
                     pid: rt.pid,
                     started_at: rt.started_at.clone(),
+                    health: rt.health.clone(),
                 }
             } else {
                 MemberStatus::Crashed {
@@ -315,7 +1022,7 @@ fn write_local_topology(
         members.insert(
             member_name.to_string(),
             MemberTopology {
-                status: if crate::state::is_alive(rt.pid) {
+                status: if procutil::is_alive(rt.pid) {
                     "running".to_string()
                 } else {
                     "stopped".to_string()
@@ -341,11 +1048,46 @@ fn write_local_topology(
 }
 
 /// Runs a non-local formation manager (one-shot Ralph session).
+/// Records the formation manager session `run_formation_manager` would run.
+fn plan_formation_manager(
+    team: &TeamEntry,
+    team_repo: &std::path::Path,
+    formation_cfg: &formation::FormationConfig,
+    plan: &mut Plan,
+) -> Result<()> {
+    let mgr = formation_cfg.manager.as_ref().with_context(|| {
+        format!(
+            "Formation '{}' has no manager configuration",
+            formation_cfg.name
+        )
+    })?;
+    preflight::check_formation(formation_cfg, team)?;
+    let formation_dir = formation::formations_dir(team_repo).join(&formation_cfg.name);
+    plan.push(Step::Run {
+        command: format!("ralph run -p {}", formation_dir.join(&mgr.prompt).display()),
+        dir: formation_dir,
+    });
+    Ok(())
+}
+
 fn run_formation_manager(
     team: &TeamEntry,
     team_repo: &std::path::Path,
     formation_cfg: &formation::FormationConfig,
     workzone: &std::path::Path,
+) -> Result<()> {
+    preflight::check_formation(formation_cfg, team)?;
+    deploy_formation(team, team_repo, formation_cfg, workzone, Vec::new())
+}
+
+/// Runs the formation manager session, which deploys the team and writes
+/// its topology. `env_vars` are passed on to the manager.
+fn deploy_formation(
+    team: &TeamEntry,
+    team_repo: &std::path::Path,
+    formation_cfg: &formation::FormationConfig,
+    workzone: &std::path::Path,
+    mut env_vars: Vec<(String, String)>,
 ) -> Result<()> {
     let mgr = formation_cfg.manager.as_ref().with_context(|| {
         format!(
@@ -359,17 +1101,36 @@ fn run_formation_manager(
     let ralph_yml_path = formation_dir.join(&mgr.ralph_yml);
 
     // Prepare env vars
-    let mut env_vars = Vec::new();
     if let Some(token) = &team.credentials.gh_token {
         env_vars.push(("GH_TOKEN".to_string(), token.clone()));
     }
     if let Some(token) = &team.credentials.telegram_bot_token {
         env_vars.push(("RALPH_TELEGRAM_BOT_TOKEN".to_string(), token.clone()));
     }
+    if let Some(chat_id) = team.credentials.telegram_chat_id {
+        env_vars.push(("RALPH_TELEGRAM_CHAT_ID".to_string(), chat_id.to_string()));
+    }
+    if let Some(token) = &team.credentials.slack_bot_token {
+        env_vars.push(("RALPH_SLACK_BOT_TOKEN".to_string(), token.clone()));
+    }
+    if let Some(channel) = &team.credentials.slack_channel {
+        env_vars.push(("RALPH_SLACK_CHANNEL".to_string(), channel.clone()));
+    }
     // Pass workzone and team info to formation manager
     env_vars.push(("BM_WORKZONE".to_string(), workzone.display().to_string()));
     env_vars.push(("BM_TEAM_NAME".to_string(), team.name.clone()));
     env_vars.push(("BM_TEAM_REPO".to_string(), team_repo.display().to_string()));
+    if let Some(ref k8s) = formation_cfg.k8s {
+        env_vars.push((
+            "BM_K8S_NAMESPACE".to_string(),
+            preflight::k8s_namespace(k8s, &team.name),
+        ));
+    }
+    if !formation_cfg.resources.is_empty() {
+        let resources = serde_json::to_string(&formation_cfg.resources)
+            .context("Failed to serialize role resource limits")?;
+        env_vars.push(("BM_ROLE_RESOURCES".to_string(), resources));
+    }
 
     eprintln!(
         "Launching formation manager for '{}' formation...",
@@ -400,14 +1161,18 @@ fn run_formation_manager(
         );
     }
 
-    eprintln!("Formation '{}' deployed successfully.", formation_cfg.name);
     Ok(())
 }
 
 /// Status of a team member process.
 #[derive(Debug)]
 pub enum MemberStatus {
-    Running { pid: u32, started_at: String },
+    /// Alive; `health` is set until its readiness probe passes.
+    Running {
+        pid: u32,
+        started_at: String,
+        health: Option<Health>,
+    },
     Crashed { pid: u32, started_at: String },
     Stopped,
 }
@@ -415,7 +1180,15 @@ pub enum MemberStatus {
 impl MemberStatus {
     pub fn label(&self) -> &'static str {
         match self {
-            MemberStatus::Running { .. } => "running",
+            MemberStatus::Running { health: None, .. } => "running",
+            MemberStatus::Running {
+                health: Some(Health::Starting),
+                ..
+            } => "starting",
+            MemberStatus::Running {
+                health: Some(Health::Unhealthy { .. }),
+                ..
+            } => "unhealthy",
             MemberStatus::Crashed { .. } => "crashed",
             MemberStatus::Stopped => "stopped",
         }
@@ -427,72 +1200,6 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
-    // ── list_member_dirs ──────────────────────────────────────────
-
-    #[test]
-    fn list_member_dirs_returns_sorted_dirs_only() {
-        let tmp = tempfile::tempdir().unwrap();
-        // Create directories
-        fs::create_dir(tmp.path().join("bob")).unwrap();
-        fs::create_dir(tmp.path().join("alice")).unwrap();
-        fs::create_dir(tmp.path().join(".hidden")).unwrap();
-        // Create a plain file
-        fs::write(tmp.path().join("file.txt"), "hello").unwrap();
-
-        let result = list_member_dirs(tmp.path()).unwrap();
-        assert_eq!(result, vec!["alice", "bob"]);
-    }
-
-    #[test]
-    fn list_member_dirs_empty_dir() {
-        let tmp = tempfile::tempdir().unwrap();
-        let result = list_member_dirs(tmp.path()).unwrap();
-        assert!(result.is_empty());
-    }
-
-    // ── find_workspace ────────────────────────────────────────────
-
-    #[test]
-    fn find_workspace_project_mode() {
-        let tmp = tempfile::tempdir().unwrap();
-        let team_ws_base = tmp.path();
-        // Create: team_ws_base/member/project/.botminter/
-        let project_dir = team_ws_base.join("member").join("my-project");
-        fs::create_dir_all(project_dir.join(".botminter")).unwrap();
-
-        let result = find_workspace(team_ws_base, "member");
-        assert_eq!(result, Some(project_dir));
-    }
-
-    #[test]
-    fn find_workspace_no_project_mode() {
-        let tmp = tempfile::tempdir().unwrap();
-        let team_ws_base = tmp.path();
-        // Create: team_ws_base/member/.botminter/ (no project subdir)
-        let member_dir = team_ws_base.join("member");
-        fs::create_dir_all(member_dir.join(".botminter")).unwrap();
-
-        let result = find_workspace(team_ws_base, "member");
-        assert_eq!(result, Some(member_dir));
-    }
-
-    #[test]
-    fn find_workspace_missing_member_dir() {
-        let tmp = tempfile::tempdir().unwrap();
-        let result = find_workspace(tmp.path(), "nonexistent");
-        assert_eq!(result, None);
-    }
-
-    #[test]
-    fn find_workspace_no_botminter_marker() {
-        let tmp = tempfile::tempdir().unwrap();
-        // Create member dir without .botminter/
-        fs::create_dir_all(tmp.path().join("member")).unwrap();
-
-        let result = find_workspace(tmp.path(), "member");
-        assert_eq!(result, None);
-    }
-
     // ── resolve_member_status ─────────────────────────────────────
 
     #[test]
@@ -505,6 +1212,8 @@ mod tests {
                 pid: alive_pid,
                 started_at: "2026-02-21T10:00:00Z".to_string(),
                 workspace: PathBuf::from("/tmp/ws"),
+                env: Default::default(),
+                health: None,
             },
         );
 
@@ -526,6 +1235,8 @@ mod tests {
                 pid: dead_pid,
                 started_at: "2026-02-21T10:00:00Z".to_string(),
                 workspace: PathBuf::from("/tmp/ws"),
+                env: Default::default(),
+                health: None,
             },
         );
 
@@ -544,6 +1255,37 @@ mod tests {
         assert_eq!(status.label(), "stopped");
     }
 
+    // ── running_in_role ───────────────────────────────────────────
+
+    #[test]
+    fn running_in_role_counts_live_members_of_role() {
+        let mut resources = BTreeMap::new();
+        resources.insert("dev".to_string(), RoleResources::default());
+        resources.insert("qe".to_string(), RoleResources::default());
+        let mut state = RuntimeState::default();
+        for (key, pid) in [
+            ("team/dev-alice", std::process::id()),
+            ("team/dev-bob", 4_000_000), // crashed, not counted
+            ("team/qe-carol", std::process::id()),
+            ("other/dev-dan", std::process::id()),
+        ] {
+            state.members.insert(
+                key.to_string(),
+                MemberRuntime {
+                    pid,
+                    started_at: "2026-02-21T10:00:00Z".to_string(),
+                    workspace: PathBuf::from("/tmp/ws"),
+                    env: Default::default(),
+                    health: None,
+                },
+            );
+        }
+
+        assert_eq!(running_in_role(&state, "team", &resources, "dev"), 1);
+        assert_eq!(running_in_role(&state, "team", &resources, "qe"), 1);
+        assert_eq!(running_in_role(&state, "other", &resources, "qe"), 0);
+    }
+
     // ── require_gh_token ──────────────────────────────────────────
 
     #[test]
@@ -553,10 +1295,18 @@ mod tests {
             path: PathBuf::from("/tmp/team"),
             profile: "scrum".to_string(),
             github_repo: "org/repo".to_string(),
+            extra_repos: Vec::new(),
+            budget: None,
             credentials: config::Credentials {
                 gh_token: Some("ghp_test123".to_string()),
                 telegram_bot_token: None,
+                telegram_chat_id: None,
+                slack_bot_token: None,
+                slack_channel: None,
                 webhook_secret: None,
+                github_app: None,
+                smtp: None,
+                notifiers: Vec::new(),
             },
         };
         let token = require_gh_token(&team).unwrap();
@@ -570,10 +1320,18 @@ mod tests {
             path: PathBuf::from("/tmp/team"),
             profile: "scrum".to_string(),
             github_repo: "org/repo".to_string(),
+            extra_repos: Vec::new(),
+            budget: None,
             credentials: config::Credentials {
                 gh_token: None,
                 telegram_bot_token: None,
+                telegram_chat_id: None,
+                slack_bot_token: None,
+                slack_channel: None,
                 webhook_secret: None,
+                github_app: None,
+                smtp: None,
+                notifiers: Vec::new(),
             },
         };
         let err = require_gh_token(&team).unwrap_err();
@@ -584,6 +1342,54 @@ mod tests {
         );
     }
 
+    // ── Started ───────────────────────────────────────────────────
+
+    #[test]
+    fn started_summary_counts_and_renders() {
+        let member = |name: &str, outcome| MemberStart {
+            member: name.to_string(),
+            outcome,
+        };
+        let mut summary = Started {
+            team: "team".to_string(),
+            members: vec![
+                member("dev-01", Outcome::Started { pid: 10 }),
+                member("dev-02", Outcome::AlreadyRunning { pid: 11 }),
+                member(
+                    "qe-01",
+                    Outcome::Held {
+                        reason: "role 'qe' already has 1 of 1 allowed member(s) running"
+                            .to_string(),
+                    },
+                ),
+            ],
+            ..Started::default()
+        };
+        let text = summary.to_string();
+        assert!(text.contains("dev-01: started (PID 10)"), "{}", text);
+        assert!(text.contains("qe-01: not started, role 'qe'"), "{}", text);
+        assert!(
+            text.ends_with("Started 1 member(s), skipped 1 (already running), 1 held back by role or disk limits, 0 error(s).\n"),
+            "{}",
+            text
+        );
+        summary.ensure_ok().unwrap();
+
+        summary.members.push(member(
+            "po-01",
+            Outcome::Failed {
+                error: "no workspace found. Run `bm teams sync` first.".to_string(),
+            },
+        ));
+        assert_eq!(summary.failed(), 1);
+        assert!(summary.ensure_ok().is_err());
+        let json = serde_json::to_value(&summary.members[0]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"member": "dev-01", "outcome": "started", "pid": 10})
+        );
+    }
+
     // ── MemberStatus::label ───────────────────────────────────────
 
     #[test]
@@ -591,11 +1397,23 @@ mod tests {
         assert_eq!(
             MemberStatus::Running {
                 pid: 1,
-                started_at: String::new()
+                started_at: String::new(),
+                health: None,
             }
             .label(),
             "running"
         );
+        assert_eq!(
+            MemberStatus::Running {
+                pid: 1,
+                started_at: String::new(),
+                health: Some(Health::Unhealthy {
+                    reason: "port 8089 not answering".into()
+                }),
+            }
+            .label(),
+            "unhealthy"
+        );
         assert_eq!(
             MemberStatus::Crashed {
                 pid: 1,
@@ -0,0 +1,130 @@
+use anyhow::Result;
+
+use crate::config;
+use crate::state_history::{self, Entry, Event};
+use crate::tables;
+use crate::timefmt;
+
+/// Handles `bm state history [--member m] [--limit n] [-t team]`.
+/// Lists when the team's members started, stopped, and crashed, newest
+/// last, noting how long each run lasted and when a crashed member was last
+/// seen alive.
+pub fn history(member: Option<&str>, limit: usize, team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+
+    let entries = state_history::load()?;
+    let rows = transitions(&entries, &team.name, member);
+    if rows.is_empty() {
+        match member {
+            Some(m) => println!(
+                "No state history for member '{}' of team '{}'.",
+                m, team.name
+            ),
+            None => println!("No state history for team '{}'.", team.name),
+        }
+        return Ok(());
+    }
+
+    let shown = &rows[rows.len().saturating_sub(limit)..];
+    let mut table = tables::new(vec!["Time", "Member", "Event", "PID", "Notes"]);
+    for entry in shown {
+        table.add_row(vec![
+            timefmt::rfc3339(&entry.at),
+            entry.member.clone().unwrap_or_default(),
+            entry.event.to_string(),
+            entry.pid.map(|p| p.to_string()).unwrap_or_default(),
+            notes(&entries, entry),
+        ]);
+    }
+    println!("{}", tables::render(&table));
+    if shown.len() < rows.len() {
+        println!(
+            "Showing the last {} of {} events; use --limit to see more.",
+            shown.len(),
+            rows.len()
+        );
+    }
+    Ok(())
+}
+
+/// The starts, stops, and crashes of `team`'s members (or just `member`).
+fn transitions<'a>(entries: &'a [Entry], team: &str, member: Option<&str>) -> Vec<&'a Entry> {
+    entries
+        .iter()
+        .filter(|e| e.team == team && e.event != Event::Snapshot)
+        .filter(|e| member.is_none() || e.member.as_deref() == member)
+        .collect()
+}
+
+/// How long an ended run lasted and, for a crash, when the member was last
+/// seen alive.
+fn notes(entries: &[Entry], entry: &Entry) -> String {
+    let mut notes = Vec::new();
+    let (Some(at), Some(member)) = (timefmt::parse(&entry.at), entry.member.as_deref()) else {
+        return String::new();
+    };
+    if entry.event != Event::Started {
+        if let Some(started) = entry.started_at.as_deref().and_then(timefmt::parse) {
+            notes.push(format!(
+                "ran {}",
+                timefmt::duration(timefmt::elapsed(started, at))
+            ));
+        }
+    }
+    if entry.event == Event::Crashed {
+        if let Some(seen) = state_history::last_seen(entries, &entry.team, member, &entry.at)
+            .and_then(timefmt::parse)
+        {
+            notes.push(format!(
+                "last seen alive {} ({} earlier)",
+                timefmt::timestamp(seen),
+                timefmt::duration(timefmt::elapsed(seen, at))
+            ));
+        }
+    }
+    notes.join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(at: &str, event: Event, member: Option<&str>, running: &[&str]) -> Entry {
+        Entry {
+            at: at.to_string(),
+            event,
+            team: "t".to_string(),
+            member: member.map(str::to_string),
+            pid: Some(7),
+            started_at: Some("2026-02-20T10:00:00Z".to_string()),
+            running: running.iter().map(|m| (m.to_string(), 7)).collect(),
+        }
+    }
+
+    #[test]
+    fn crash_notes_include_run_length_and_last_sighting() {
+        let entries = vec![
+            entry(
+                "2026-02-20T10:00:00Z",
+                Event::Started,
+                Some("dev-01"),
+                &["dev-01"],
+            ),
+            entry("2026-02-20T11:55:00Z", Event::Snapshot, None, &["dev-01"]),
+            entry("2026-02-20T12:00:00Z", Event::Crashed, Some("dev-01"), &[]),
+        ];
+
+        let rows = transitions(&entries, "t", Some("dev-01"));
+        assert_eq!(rows.len(), 2);
+        assert_eq!(notes(&entries, rows[0]), "");
+        let crash = notes(&entries, rows[1]);
+        assert!(
+            crash.starts_with("ran 2h 00m; last seen alive"),
+            "{}",
+            crash
+        );
+        assert!(crash.ends_with("(5m 00s earlier)"), "{}", crash);
+        assert!(transitions(&entries, "t", Some("qe-01")).is_empty());
+    }
+}
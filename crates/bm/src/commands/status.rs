@@ -1,26 +1,312 @@
 use std::fs;
+use std::io::Write as _;
 use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
 
 use anyhow::{Context, Result};
-use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL_CONDENSED, Table};
+use comfy_table::Table;
 use serde::Deserialize;
 
+use crate::cgroup;
 use crate::commands::daemon;
 use crate::commands::start::{resolve_member_status, MemberStatus};
 use crate::config;
+use crate::daemon_settings;
+use crate::members;
+use crate::procutil;
 use crate::profile;
-use crate::state;
+use crate::state::{self, MemberStats, RuntimeState};
+use crate::tables;
+use crate::timefmt;
+use crate::token_expiry;
 use crate::topology;
 
-/// Minimal member manifest for reading role.
+/// Minimal member manifest for reading project assignment.
 #[derive(Debug, Deserialize)]
 struct MemberManifest {
     #[serde(default)]
-    role: Option<String>,
+    projects: Option<Vec<String>>,
 }
 
-/// Handles `bm status [-t team] [-v]`.
-pub fn run(team_flag: Option<&str>, verbose: bool) -> Result<()> {
+/// Group heading for members that work on no project.
+const NO_PROJECT: &str = "(no project)";
+
+/// How `bm status` splits the member table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Role,
+    Project,
+}
+
+/// Order of member rows within each table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    Name,
+    Role,
+    Status,
+    Uptime,
+}
+
+/// Display options for the status dashboard.
+#[derive(Debug, Clone, Copy)]
+pub struct View {
+    pub verbose: bool,
+    pub stats: bool,
+    pub group_by: Option<GroupBy>,
+    pub sort: SortBy,
+}
+
+impl View {
+    /// Builds the view from the flags and the raw `--group-by` and `--sort` values.
+    pub fn parse(
+        verbose: bool,
+        stats: bool,
+        group_by: Option<&str>,
+        sort: &str,
+    ) -> Result<View> {
+        let group_by = match group_by {
+            None => None,
+            Some("role") => Some(GroupBy::Role),
+            Some("project") => Some(GroupBy::Project),
+            Some(other) => anyhow::bail!(
+                "Unknown grouping '{}'. Valid groupings: role, project",
+                other
+            ),
+        };
+        let sort = match sort {
+            "name" => SortBy::Name,
+            "role" => SortBy::Role,
+            "status" => SortBy::Status,
+            "uptime" => SortBy::Uptime,
+            other => anyhow::bail!(
+                "Unknown sort key '{}'. Valid keys: name, role, status, uptime",
+                other
+            ),
+        };
+        Ok(View {
+            verbose,
+            stats,
+            group_by,
+            sort,
+        })
+    }
+}
+
+/// One member row of the dashboard.
+#[derive(Debug, Clone)]
+struct MemberRow {
+    member: String,
+    role: String,
+    status: &'static str,
+    started: String,
+    uptime: String,
+    uptime_secs: Option<u64>,
+    activity: String,
+    pid: String,
+    projects: Vec<String>,
+}
+
+/// Handles `bm status [-t team] [-v] [--group-by ...] [--sort ...]`.
+pub fn run(team_flag: Option<&str>, view: View) -> Result<()> {
+    render(team_flag, view)
+}
+
+/// Handles `bm status --all [--sort ...]` — one table of every registered
+/// team's members. Unlike the single-team dashboard it only reads state, so
+/// crashed members are shown but not cleaned up.
+pub fn run_all(sort: SortBy) -> Result<()> {
+    let cfg = config::load()?;
+    if cfg.teams.is_empty() {
+        println!("No teams registered. Run `bm init` to create one.");
+        return Ok(());
+    }
+
+    let runtime_state = state::load()?;
+    let now = SystemTime::now();
+    let mut table = tables::new(vec!["Team", "Member", "Role", "Status", "Uptime", "PID"]);
+
+    let mut running = 0;
+    let mut total = 0;
+    for team in &cfg.teams {
+        let members_dir = team.path.join("team").join("team");
+        let topo = team_topology(&cfg.workzone, &team.name);
+        let mut rows: Vec<MemberRow> = members::list_names(&members_dir)
+            .unwrap_or_default()
+            .iter()
+            .map(|m| {
+                let mut row = member_row(&team.name, &members_dir, m, &[], &runtime_state, now).0;
+                show_pod(&mut row, topo.as_ref(), topology::probe);
+                row
+            })
+            .collect();
+        sort_rows(&mut rows, sort);
+        if rows.is_empty() {
+            table.add_row(vec![team.name.as_str(), "—", "—", "no members", "—", "—"]);
+        }
+        for row in &rows {
+            total += 1;
+            if row.status == "running" {
+                running += 1;
+            }
+            table.add_row(vec![
+                team.name.as_str(),
+                row.member.as_str(),
+                row.role.as_str(),
+                row.status,
+                row.uptime.as_str(),
+                row.pid.as_str(),
+            ]);
+        }
+    }
+
+    println!("{}", tables::render(&table));
+    println!(
+        "{} team(s), {} member(s), {} running",
+        cfg.teams.len(),
+        total,
+        running
+    );
+    Ok(())
+}
+
+/// Describes a team's members in plain text, one line each
+/// (`dev-01 (dev): running 1h 5m, PID 4242`), for reports such as
+/// `bm chatops`. Read-only, like `bm status --all`.
+pub fn member_lines(team: &config::TeamEntry) -> Result<Vec<String>> {
+    let runtime_state = state::load()?;
+    let members_dir = team.path.join("team").join("team");
+    let topo = config::load()
+        .ok()
+        .and_then(|cfg| team_topology(&cfg.workzone, &team.name));
+    let now = SystemTime::now();
+    Ok(members::list_names(&members_dir)
+            .unwrap_or_default()
+        .iter()
+        .map(|m| {
+            let (mut row, _) = member_row(&team.name, &members_dir, m, &[], &runtime_state, now);
+            show_pod(&mut row, topo.as_ref(), topology::probe);
+            match row.status {
+                _ if row.pid.starts_with("pod/") => {
+                    format!("{} ({}): {}, {}", row.member, row.role, row.status, row.pid)
+                }
+                "running" | "starting" | "unhealthy" => format!(
+                    "{} ({}): {} {}, PID {}",
+                    row.member, row.role, row.status, row.uptime, row.pid
+                ),
+                status => format!("{} ({}): {}", row.member, row.role, status),
+            }
+        })
+        .collect())
+}
+
+/// Builds a member's dashboard row, returning its resolved status alongside.
+fn member_row(
+    team_name: &str,
+    members_dir: &std::path::Path,
+    member_dir_name: &str,
+    team_projects: &[String],
+    runtime_state: &RuntimeState,
+    now: SystemTime,
+) -> (MemberRow, MemberStatus) {
+    let role = members::read_role(members_dir, member_dir_name);
+    let status = resolve_member_status(runtime_state, team_name, member_dir_name);
+
+    let (status_label, started, uptime_secs, pid_str) = match &status {
+        MemberStatus::Running { pid, started_at, .. } => (
+            status.label(),
+            timefmt::rfc3339(started_at),
+            uptime_secs(started_at),
+            pid.to_string(),
+        ),
+        MemberStatus::Crashed { pid, started_at } => {
+            ("crashed", timefmt::rfc3339(started_at), None, pid.to_string())
+        }
+        MemberStatus::Stopped => ("stopped", "—".to_string(), None, "—".to_string()),
+    };
+    let activity = last_activity(team_name, member_dir_name)
+        .and_then(|t| now.duration_since(t).ok())
+        .map(|age| timefmt::ago(age.as_secs()))
+        .unwrap_or_else(|| "—".to_string());
+
+    let row = MemberRow {
+        member: member_dir_name.to_string(),
+        role,
+        status: status_label,
+        started,
+        uptime: uptime_secs.map(timefmt::duration).unwrap_or_else(|| "—".to_string()),
+        uptime_secs,
+        activity,
+        pid: pid_str,
+        projects: read_member_projects(members_dir, member_dir_name, team_projects),
+    };
+    (row, status)
+}
+
+/// Loads a team's topology, if it has a readable one.
+fn team_topology(workzone: &std::path::Path, team_name: &str) -> Option<topology::Topology> {
+    topology::load(&topology::topology_path(workzone, team_name)).ok().flatten()
+}
+
+/// Shows a member that a non-local formation placed by its pod: the status
+/// comes from the pod's phase and the pod stands in for the PID. Rows of
+/// members running locally are left alone.
+fn show_pod(
+    row: &mut MemberRow,
+    topo: Option<&topology::Topology>,
+    probe: impl Fn(&topology::Endpoint) -> topology::Probe,
+) {
+    if row.status != "stopped" {
+        return;
+    }
+    let Some(member) = topo.and_then(|t| t.members.get(&row.member)) else {
+        return;
+    };
+    let topology::Endpoint::K8s { ref pod, .. } = member.endpoint else {
+        return;
+    };
+    if member.status == "stopped" {
+        return;
+    }
+    row.status = match probe(&member.endpoint) {
+        topology::Probe::Alive(phase) => match phase.as_str() {
+            "running" => "running",
+            "pending" => "pending",
+            _ => "unknown",
+        },
+        topology::Probe::Gone => "stopped",
+        topology::Probe::Unknown(_) => "unknown",
+    };
+    row.pid = format!("pod/{}", pod);
+}
+
+/// Handles `bm status --watch`: redraws the dashboard every `interval` seconds
+/// until interrupted.
+pub fn watch(team_flag: Option<&str>, view: View, interval: u64) -> Result<()> {
+    // Fail fast on a bad team before taking over the screen
+    let cfg = config::load()?;
+    config::resolve_team(&cfg, team_flag)?;
+
+    let interval = interval.max(1);
+    loop {
+        // Clear screen and move the cursor home
+        print!("\x1b[2J\x1b[H");
+        println!(
+            "Every {}s: bm status (Ctrl+C to exit)    {}",
+            interval,
+            timefmt::timestamp(chrono::Utc::now())
+        );
+        println!();
+        if let Err(e) = render(team_flag, view) {
+            println!("Error: {:#}", e);
+        }
+        std::io::stdout().flush()?;
+        thread::sleep(Duration::from_secs(interval));
+    }
+}
+
+/// Prints the status dashboard once.
+fn render(team_flag: Option<&str>, view: View) -> Result<()> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, team_flag)?;
     let team_repo = team.path.join("team");
@@ -37,43 +323,42 @@ pub fn run(team_flag: Option<&str>, verbose: bool) -> Result<()> {
     println!("Profile: {}", team.profile);
     if !team.github_repo.is_empty() {
         println!("GitHub: {}", team.github_repo);
+        if !team.extra_repos.is_empty() {
+            println!("Also works in: {}", team.extra_repos.join(", "));
+        }
     }
 
     // Show projects from botminter.yml
+    let mut team_projects: Vec<String> = Vec::new();
     let manifest_path = team_repo.join("botminter.yml");
     if let Ok(contents) = fs::read_to_string(&manifest_path) {
         if let Ok(manifest) = serde_yml::from_str::<profile::ProfileManifest>(&contents) {
-            if !manifest.projects.is_empty() {
-                let names: Vec<&str> =
-                    manifest.projects.iter().map(|p| p.name.as_str()).collect();
-                println!("Projects: {}", names.join(", "));
+            team_projects = manifest.projects.into_iter().map(|p| p.name).collect();
+            if !team_projects.is_empty() {
+                println!("Projects: {}", team_projects.join(", "));
             }
         }
     }
 
-    // Show daemon status if running
+    // Show daemon status
+    let mut daemon_running = false;
     if let Ok(pid_file) = daemon::pid_path(team_name) {
         if pid_file.exists() {
             if let Ok(pid_str) = fs::read_to_string(&pid_file) {
                 if let Ok(pid) = pid_str.trim().parse::<u32>() {
-                    if state::is_alive(pid) {
-                        if let Ok(cfg_file) = daemon::config_path(team_name) {
-                            if let Ok(contents) = fs::read_to_string(&cfg_file) {
-                                if let Ok(dcfg) =
-                                    serde_json::from_str::<daemon::DaemonConfig>(&contents)
-                                {
-                                    match dcfg.mode.as_str() {
-                                        "webhook" => println!(
-                                            "Daemon: running (PID {}, webhook mode, port {})",
-                                            pid, dcfg.port
-                                        ),
-                                        "poll" => println!(
-                                            "Daemon: running (PID {}, poll mode, interval {}s)",
-                                            pid, dcfg.interval_secs
-                                        ),
-                                        _ => println!("Daemon: running (PID {})", pid),
-                                    }
-                                }
+                    if procutil::is_alive(pid) {
+                        daemon_running = true;
+                        if let Some(dcfg) = daemon::read_daemon_config(team_name) {
+                            match dcfg.mode.as_str() {
+                                "webhook" => println!(
+                                    "Daemon: running (PID {}, webhook mode, port {})",
+                                    pid, dcfg.port
+                                ),
+                                "poll" => println!(
+                                    "Daemon: running (PID {}, poll mode, interval {}s)",
+                                    pid, dcfg.interval_secs
+                                ),
+                                _ => println!("Daemon: running (PID {})", pid),
                             }
                         }
                     }
@@ -81,8 +366,18 @@ pub fn run(team_flag: Option<&str>, verbose: bool) -> Result<()> {
             }
         }
     }
+    if !daemon_running {
+        println!("Daemon: not running");
+    }
+    if let Some(note) = state::paused(team_name) {
+        println!("Paused {} (no new members launch)", note);
+    }
     println!();
 
+    if let Some(warning) = token_expiry::check_team(team, cfg.token_expiry_warn_days) {
+        eprintln!("Warning: {}\n", warning);
+    }
+
     // Read members
     let members_dir = team_repo.join("team");
     if !members_dir.is_dir() {
@@ -90,71 +385,113 @@ pub fn run(team_flag: Option<&str>, verbose: bool) -> Result<()> {
         return Ok(());
     }
 
-    let mut member_dirs: Vec<String> = Vec::new();
-    for entry in fs::read_dir(&members_dir)? {
-        let entry = entry?;
-        if !entry.file_type()?.is_dir() {
-            continue;
-        }
-        let name = entry.file_name().to_string_lossy().to_string();
-        if !name.starts_with('.') {
-            member_dirs.push(name);
-        }
-    }
-    member_dirs.sort();
+    let member_dirs = members::list_names(&members_dir)?;
 
     if member_dirs.is_empty() {
         println!("No members hired yet.");
         return Ok(());
     }
 
-    let mut runtime_state = state::load()?;
-
-    // Build table
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL_CONDENSED)
-        .apply_modifier(UTF8_ROUND_CORNERS)
-        .set_header(vec!["Member", "Role", "Status", "Started", "PID"]);
+    let runtime_state = state::load()?;
 
+    let now = SystemTime::now();
     let mut crashed_keys: Vec<String> = Vec::new();
+    let mut limit_breaches: Vec<(String, String)> = Vec::new();
+    let mut rows: Vec<MemberRow> = Vec::new();
 
     for member_dir_name in &member_dirs {
-        let role = read_member_role(&members_dir, member_dir_name);
-        let status = resolve_member_status(&runtime_state, team_name, member_dir_name);
-
-        let (status_label, started, pid_str) = match &status {
-            MemberStatus::Running { pid, started_at } => {
-                ("running", format_timestamp(started_at), pid.to_string())
+        let (mut row, status) = member_row(
+            team_name,
+            &members_dir,
+            member_dir_name,
+            &team_projects,
+            &runtime_state,
+            now,
+        );
+        show_pod(&mut row, topo.as_ref(), topology::probe);
+        match status {
+            MemberStatus::Running { pid, .. } => {
+                if let Some(breach) = cgroup::breaches(pid).and_then(|b| b.describe()) {
+                    limit_breaches.push((member_dir_name.clone(), breach));
+                }
             }
-            MemberStatus::Crashed { pid, started_at } => {
+            MemberStatus::Crashed { .. } => {
                 crashed_keys.push(format!("{}/{}", team_name, member_dir_name));
-                ("crashed", format_timestamp(started_at), pid.to_string())
             }
-            MemberStatus::Stopped => ("stopped", "—".to_string(), "—".to_string()),
-        };
+            MemberStatus::Stopped => {}
+        }
+        rows.push(row);
+    }
 
-        table.add_row(vec![
-            member_dir_name.as_str(),
-            &role,
-            status_label,
-            &started,
-            &pid_str,
-        ]);
+    sort_rows(&mut rows, view.sort);
+    match view.group_by {
+        None => println!("{}", tables::render(&member_table(&rows))),
+        Some(group_by) => {
+            for (i, (heading, group)) in group_rows(&rows, group_by).iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!("{}", tables::text(&group_summary(group_by, heading, group)));
+                println!("{}", tables::render(&member_table(group)));
+            }
+        }
     }
 
-    println!("{table}");
+    if !limit_breaches.is_empty() {
+        println!();
+        for (member, breach) in &limit_breaches {
+            eprintln!("Warning: {} is hitting its resource limits: {}", member, breach);
+        }
+    }
+
+    // Disk quota: a broken daemon.yml is reported by `bm daemon start`, not here
+    if let Some(quota) = daemon_settings::load(&team_repo, team_name)
+        .ok()
+        .and_then(|s| s.disk_quota)
+    {
+        let team_ws_base = cfg.workzone.join(team_name);
+        let mut over_quota = Vec::new();
+        for member_dir_name in &member_dirs {
+            let Some(ws) = members::find_workspace(&team_ws_base, member_dir_name) else {
+                continue;
+            };
+            if let Ok(Some(over)) = quota.check(&ws.path) {
+                over_quota.push((member_dir_name, over));
+            }
+        }
+        if !over_quota.is_empty() {
+            println!();
+            for (member, over) in &over_quota {
+                eprintln!(
+                    "Warning: {}'s workspace is over its disk quota: {}",
+                    member,
+                    over.describe()
+                );
+            }
+        }
+    }
 
     // Clean up crashed entries
     if !crashed_keys.is_empty() {
-        for key in &crashed_keys {
-            runtime_state.members.remove(key);
-        }
-        state::save(&runtime_state)?;
+        // Re-check liveness under the lock: another bm may have relaunched them
+        state::update(|s| {
+            let now = chrono::Utc::now();
+            for key in &crashed_keys {
+                if s.members.get(key).is_some_and(|rt| !procutil::is_alive(rt.pid)) {
+                    s.end_crashed_run(key, state::last_log_write(key), now);
+                }
+            }
+            Ok(())
+        })?;
+    }
+
+    if view.stats {
+        let runtime_state = state::load()?; // reload after cleanup
+        print_stats(team_name, &rows, &runtime_state);
     }
 
     // Verbose mode: show Ralph runtime details for running members
-    if verbose {
+    if view.verbose {
         let runtime_state = state::load()?; // reload after cleanup
         let team_prefix = format!("{}/", team_name);
 
@@ -162,12 +499,12 @@ pub fn run(team_flag: Option<&str>, verbose: bool) -> Result<()> {
             if !key.starts_with(&team_prefix) {
                 continue;
             }
-            if !state::is_alive(rt.pid) {
+            if !procutil::is_alive(rt.pid) {
                 continue;
             }
 
             let member_name = key.strip_prefix(&team_prefix).unwrap_or(key);
-            println!("\n── {} (PID {}) ──", member_name, rt.pid);
+            println!("{}", tables::text(&format!("\n── {} (PID {}) ──", member_name, rt.pid)));
 
             // Run Ralph CLI commands from the workspace, skipping unavailable ones
             for (label, args) in &[
@@ -194,22 +531,182 @@ pub fn run(team_flag: Option<&str>, verbose: bool) -> Result<()> {
     Ok(())
 }
 
-/// Reads the role from a member's botminter.yml, falling back to dir-name inference.
-fn read_member_role(members_dir: &std::path::Path, member_dir_name: &str) -> String {
-    let manifest_path = members_dir.join(member_dir_name).join("botminter.yml");
-    if let Ok(contents) = fs::read_to_string(&manifest_path) {
-        if let Ok(manifest) = serde_yml::from_str::<MemberManifest>(&contents) {
-            if let Some(role) = manifest.role {
-                return role;
-            }
+/// Returns the team projects a member works on: those listed under
+/// `projects:` in its botminter.yml, or every team project.
+fn read_member_projects(
+    members_dir: &std::path::Path,
+    member_dir_name: &str,
+    team_projects: &[String],
+) -> Vec<String> {
+    let assigned = fs::read_to_string(members_dir.join(member_dir_name).join("botminter.yml"))
+        .ok()
+        .and_then(|c| serde_yml::from_str::<MemberManifest>(&c).ok())
+        .and_then(|m| m.projects);
+    match assigned {
+        Some(names) => team_projects
+            .iter()
+            .filter(|p| names.contains(p))
+            .cloned()
+            .collect(),
+        None => team_projects.to_vec(),
+    }
+}
+
+/// Sorts member rows; ties fall back to the member name.
+fn sort_rows(rows: &mut [MemberRow], sort: SortBy) {
+    match sort {
+        SortBy::Name => rows.sort_by(|a, b| a.member.cmp(&b.member)),
+        SortBy::Role => rows.sort_by(|a, b| (&a.role, &a.member).cmp(&(&b.role, &b.member))),
+        SortBy::Status => rows.sort_by_key(|r| (status_rank(r.status), r.member.clone())),
+        // Longest-running first; members without an uptime last
+        SortBy::Uptime => rows.sort_by_key(|r| {
+            (
+                r.uptime_secs.is_none(),
+                std::cmp::Reverse(r.uptime_secs),
+                r.member.clone(),
+            )
+        }),
+    }
+}
+
+/// Orders statuses so problems surface first after running members.
+fn status_rank(status: &str) -> u8 {
+    match status {
+        "running" | "starting" | "pending" => 0,
+        "unhealthy" | "crashed" => 1,
+        _ => 2,
+    }
+}
+
+/// Splits rows into named groups, keeping each group's row order.
+///
+/// Groups are sorted by name, with members on no project listed last. A
+/// member assigned to several projects appears under each of them.
+fn group_rows(rows: &[MemberRow], group_by: GroupBy) -> Vec<(String, Vec<MemberRow>)> {
+    let mut groups: std::collections::BTreeMap<String, Vec<MemberRow>> =
+        std::collections::BTreeMap::new();
+    for row in rows {
+        let keys = match group_by {
+            GroupBy::Role => vec![row.role.clone()],
+            GroupBy::Project if row.projects.is_empty() => vec![NO_PROJECT.to_string()],
+            GroupBy::Project => row.projects.clone(),
+        };
+        for key in keys {
+            groups.entry(key).or_default().push(row.clone());
         }
     }
-    // Infer from dir name
-    member_dir_name
-        .split('-')
-        .next()
-        .unwrap_or("unknown")
-        .to_string()
+    let unassigned = groups.remove(NO_PROJECT);
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    if let Some(rows) = unassigned {
+        groups.push((NO_PROJECT.to_string(), rows));
+    }
+    groups
+}
+
+/// Formats a group heading with per-status counts, e.g.
+/// `Role: dev (3 members: 2 running, 1 stopped)`.
+fn group_summary(group_by: GroupBy, heading: &str, rows: &[MemberRow]) -> String {
+    let label = match group_by {
+        GroupBy::Role => "Role",
+        GroupBy::Project => "Project",
+    };
+    let counts: Vec<String> = ["running", "starting", "pending", "unhealthy", "crashed", "unknown", "stopped"]
+        .iter()
+        .filter_map(|status| {
+            let n = rows.iter().filter(|r| r.status == *status).count();
+            (n > 0).then(|| format!("{} {}", n, status))
+        })
+        .collect();
+    format!(
+        "{}: {} ({} member{}: {})",
+        label,
+        heading,
+        rows.len(),
+        if rows.len() == 1 { "" } else { "s" },
+        counts.join(", ")
+    )
+}
+
+/// Prints uptime and crash statistics for the team and each listed member.
+///
+/// Uptime includes the run in progress for members that are running now.
+fn print_stats(team_name: &str, rows: &[MemberRow], runtime_state: &RuntimeState) {
+    let mut team = MemberStats::default();
+    let mut team_current = 0;
+    let mut table = tables::new(vec!["Member", "Runs", "Crashes", "Uptime", "MTBC", "Last crash"]);
+    for row in rows {
+        let stats = runtime_state
+            .stats
+            .get(&format!("{}/{}", team_name, row.member))
+            .cloned()
+            .unwrap_or_default();
+        let current = row.uptime_secs.unwrap_or(0);
+        team.merge(&stats);
+        team_current += current;
+        table.add_row(vec![
+            row.member.clone(),
+            stats.runs.to_string(),
+            stats.crashes.to_string(),
+            timefmt::duration(stats.uptime_secs + current),
+            stats.mtbc_secs(current).map(timefmt::duration).unwrap_or_else(|| "—".to_string()),
+            stats
+                .last_crash_at
+                .as_deref()
+                .map(timefmt::rfc3339)
+                .unwrap_or_else(|| "—".to_string()),
+        ]);
+    }
+
+    println!();
+    println!("{}", tables::text(&stats_summary(&team, team_current)));
+    println!("{}", tables::render(&table));
+}
+
+/// Formats the team-wide statistics line, e.g.
+/// `Stats since 2026-03-01 10:00:00 UTC: 12 runs, 3 crashes, uptime 4d 02h, MTBC 1d 08h`.
+fn stats_summary(team: &MemberStats, current_secs: u64) -> String {
+    let since = team
+        .first_started_at
+        .as_deref()
+        .map(|t| format!(" since {}", timefmt::rfc3339(t)))
+        .unwrap_or_default();
+    format!(
+        "Stats{}: {} run{}, {} crash{}, uptime {}, MTBC {}",
+        since,
+        team.runs,
+        if team.runs == 1 { "" } else { "s" },
+        team.crashes,
+        if team.crashes == 1 { "" } else { "es" },
+        timefmt::duration(team.uptime_secs + current_secs),
+        team.mtbc_secs(current_secs)
+            .map(timefmt::duration)
+            .unwrap_or_else(|| "—".to_string())
+    )
+}
+
+/// Builds the member table for a set of rows.
+fn member_table(rows: &[MemberRow]) -> Table {
+    let mut table = tables::new(vec![
+            "Member",
+            "Role",
+            "Status",
+            "Started",
+            "Uptime",
+            "Last activity",
+            "PID",
+        ]);
+    for row in rows {
+        table.add_row(vec![
+            row.member.as_str(),
+            &row.role,
+            row.status,
+            &row.started,
+            &row.uptime,
+            &row.activity,
+            &row.pid,
+        ]);
+    }
+    table
 }
 
 /// Runs a ralph command in the given workspace and returns stdout.
@@ -227,101 +724,170 @@ fn run_ralph_cmd(workspace: &std::path::Path, args: &[&str]) -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-/// Formats an ISO 8601 timestamp for display, stripping sub-seconds.
-fn format_timestamp(ts: &str) -> String {
-    // Try to parse and reformat for display
-    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(ts) {
-        dt.format("%Y-%m-%d %H:%M:%S").to_string()
-    } else {
-        ts.to_string()
-    }
+/// Returns when the member last wrote to its log file, if it has one.
+fn last_activity(team_name: &str, member: &str) -> Option<SystemTime> {
+    let path = daemon::member_log_path(team_name, member).ok()?;
+    fs::metadata(path).ok()?.modified().ok()
+}
+
+/// Returns the seconds elapsed since an ISO 8601 start timestamp.
+fn uptime_secs(started_at: &str) -> Option<u64> {
+    timefmt::parse(started_at).map(|t| timefmt::elapsed(t, chrono::Utc::now()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    // ── read_member_role ──────────────────────────────────────────
-
     #[test]
-    fn read_member_role_from_yaml() {
-        let tmp = tempfile::tempdir().unwrap();
-        let member_dir = tmp.path().join("architect-alice");
-        fs::create_dir(&member_dir).unwrap();
-        fs::write(
-            member_dir.join("botminter.yml"),
-            "role: architect\n",
-        )
-        .unwrap();
+    fn uptime_secs_invalid_timestamp() {
+        assert_eq!(uptime_secs("not-a-date"), None);
+    }
 
-        let role = read_member_role(tmp.path(), "architect-alice");
-        assert_eq!(role, "architect");
+    // ── grouping and sorting ──────────────────────────────────────
+
+    fn row(
+        member: &str,
+        role: &str,
+        status: &'static str,
+        uptime: Option<u64>,
+        projects: &[&str],
+    ) -> MemberRow {
+        MemberRow {
+            member: member.to_string(),
+            role: role.to_string(),
+            status,
+            started: "—".to_string(),
+            uptime: "—".to_string(),
+            uptime_secs: uptime,
+            activity: "—".to_string(),
+            pid: "—".to_string(),
+            projects: projects.iter().map(|p| p.to_string()).collect(),
+        }
     }
 
-    #[test]
-    fn read_member_role_yaml_with_extra_fields() {
-        let tmp = tempfile::tempdir().unwrap();
-        let member_dir = tmp.path().join("po-bob");
-        fs::create_dir(&member_dir).unwrap();
-        fs::write(
-            member_dir.join("botminter.yml"),
-            "role: product-owner\nschema_version: '0.3'\n",
-        )
-        .unwrap();
+    fn names(rows: &[MemberRow]) -> Vec<&str> {
+        rows.iter().map(|r| r.member.as_str()).collect()
+    }
 
-        let role = read_member_role(tmp.path(), "po-bob");
-        assert_eq!(role, "product-owner");
+    #[test]
+    fn view_parse_rejects_unknown_values() {
+        let view = View::parse(false, false, Some("project"), "uptime").unwrap();
+        assert_eq!(view.group_by, Some(GroupBy::Project));
+        assert_eq!(view.sort, SortBy::Uptime);
+
+        let err = View::parse(false, false, Some("team"), "name").unwrap_err();
+        assert!(err.to_string().contains("role, project"), "{}", err);
+        let err = View::parse(false, false, None, "pid").unwrap_err();
+        assert!(err.to_string().contains("name, role, status, uptime"), "{}", err);
     }
 
     #[test]
-    fn read_member_role_fallback_no_yaml() {
-        let tmp = tempfile::tempdir().unwrap();
-        // Dir exists but no botminter.yml
-        fs::create_dir(tmp.path().join("architect-alice")).unwrap();
+    fn sort_rows_by_status_and_uptime() {
+        let mut rows = vec![
+            row("dev-a", "dev", "stopped", None, &[]),
+            row("dev-b", "dev", "running", Some(10), &[]),
+            row("qe-c", "qe", "crashed", None, &[]),
+            row("arch-d", "arch", "running", Some(500), &[]),
+        ];
+        sort_rows(&mut rows, SortBy::Status);
+        assert_eq!(names(&rows), vec!["arch-d", "dev-b", "qe-c", "dev-a"]);
+        sort_rows(&mut rows, SortBy::Uptime);
+        assert_eq!(names(&rows), vec!["arch-d", "dev-b", "dev-a", "qe-c"]);
+        sort_rows(&mut rows, SortBy::Role);
+        assert_eq!(names(&rows), vec!["arch-d", "dev-a", "dev-b", "qe-c"]);
+    }
 
-        let role = read_member_role(tmp.path(), "architect-alice");
-        assert_eq!(role, "architect");
+    #[test]
+    fn group_rows_by_project_lists_unassigned_last() {
+        let rows = vec![
+            row("dev-a", "dev", "running", Some(1), &["app", "lib"]),
+            row("po-b", "po", "stopped", None, &[]),
+            row("qe-c", "qe", "stopped", None, &["lib"]),
+        ];
+        let groups = group_rows(&rows, GroupBy::Project);
+        let summary: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|(name, rows)| (name.as_str(), names(rows)))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("app", vec!["dev-a"]),
+                ("lib", vec!["dev-a", "qe-c"]),
+                (NO_PROJECT, vec!["po-b"]),
+            ]
+        );
+        assert_eq!(
+            group_summary(GroupBy::Project, "lib", &groups[1].1),
+            "Project: lib (2 members: 1 running, 1 stopped)"
+        );
     }
 
     #[test]
-    fn read_member_role_fallback_no_role_field() {
+    fn read_member_projects_narrows_to_assigned() {
         let tmp = tempfile::tempdir().unwrap();
-        let member_dir = tmp.path().join("po-bob");
-        fs::create_dir(&member_dir).unwrap();
-        // YAML exists but has no 'role' field
+        let team_projects = vec!["app".to_string(), "lib".to_string()];
+        fs::create_dir_all(tmp.path().join("dev-a")).unwrap();
+        fs::create_dir_all(tmp.path().join("qe-b")).unwrap();
         fs::write(
-            member_dir.join("botminter.yml"),
-            "schema_version: '0.3'\n",
+            tmp.path().join("qe-b/botminter.yml"),
+            "role: qe\nprojects: [lib, gone]\n",
         )
         .unwrap();
 
-        let role = read_member_role(tmp.path(), "po-bob");
-        assert_eq!(role, "po");
+        assert_eq!(read_member_projects(tmp.path(), "dev-a", &team_projects), team_projects);
+        assert_eq!(read_member_projects(tmp.path(), "qe-b", &team_projects), vec!["lib"]);
     }
 
-    // ── format_timestamp ──────────────────────────────────────────
-
     #[test]
-    fn format_timestamp_rfc3339() {
-        let result = format_timestamp("2026-02-21T10:30:00+00:00");
-        assert_eq!(result, "2026-02-21 10:30:00");
-    }
-
-    #[test]
-    fn format_timestamp_with_offset() {
-        let result = format_timestamp("2026-02-21T12:30:00+02:00");
-        assert_eq!(result, "2026-02-21 12:30:00");
-    }
-
-    #[test]
-    fn format_timestamp_unparseable_passthrough() {
-        let result = format_timestamp("not-a-timestamp");
-        assert_eq!(result, "not-a-timestamp");
+    fn stats_summary_formats_totals() {
+        let team = MemberStats {
+            runs: 3,
+            crashes: 2,
+            uptime_secs: 4 * 3600,
+            first_started_at: Some("2026-03-01T10:00:00+00:00".to_string()),
+            last_crash_at: None,
+        };
+        assert_eq!(
+            stats_summary(&team, 2 * 3600),
+            "Stats since 2026-03-01 10:00:00 UTC: 3 runs, 2 crashes, uptime 6h 00m, MTBC 3h 00m"
+        );
+        assert_eq!(
+            stats_summary(&MemberStats::default(), 0),
+            "Stats: 0 runs, 0 crashes, uptime 0s, MTBC —"
+        );
     }
 
     #[test]
-    fn format_timestamp_empty_passthrough() {
-        let result = format_timestamp("");
-        assert_eq!(result, "");
+    fn show_pod_reports_k8s_members_by_pod_phase() {
+        let topo: topology::Topology = serde_json::from_value(serde_json::json!({
+            "formation": "k8s",
+            "created_at": "2026-10-01T00:00:00Z",
+            "members": {
+                "dev-a": {"status": "running", "endpoint": {"type": "k8s",
+                    "namespace": "ns", "pod": "dev-a-1", "container": "ralph", "context": "kind"}},
+                "qe-b": {"status": "stopped", "endpoint": {"type": "local",
+                    "pid": 1, "workspace": "/ws"}},
+            },
+        }))
+        .unwrap();
+        let pending = |_: &topology::Endpoint| topology::Probe::Alive("pending".to_string());
+
+        let mut pod = row("dev-a", "dev", "stopped", None, &[]);
+        show_pod(&mut pod, Some(&topo), pending);
+        assert_eq!((pod.status, pod.pid.as_str()), ("pending", "pod/dev-a-1"));
+
+        let mut gone = row("dev-a", "dev", "stopped", None, &[]);
+        show_pod(&mut gone, Some(&topo), |_| topology::Probe::Gone);
+        assert_eq!(gone.status, "stopped");
+
+        // Local members and members running here keep their rows
+        let mut local = row("qe-b", "qe", "stopped", None, &[]);
+        show_pod(&mut local, Some(&topo), pending);
+        assert_eq!((local.status, local.pid.as_str()), ("stopped", "—"));
+        let mut running = row("dev-a", "dev", "running", Some(5), &[]);
+        show_pod(&mut running, Some(&topo), pending);
+        assert_eq!(running.status, "running");
     }
 }
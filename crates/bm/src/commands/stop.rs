@@ -1,105 +1,409 @@
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{bail, Result};
+use tracing::debug;
 
-use crate::config;
-use crate::state;
+use crate::commands::daemon;
+use crate::config::{self, TeamEntry};
+use crate::pre_stop;
+use crate::procutil;
+use crate::state::{self, RunOutcome};
 use crate::topology;
 
-/// Maximum seconds to wait for graceful stop before giving up per member.
-const GRACEFUL_TIMEOUT_SECS: u64 = 60;
+/// Default seconds to wait for a member to exit after asking it to stop.
+pub const DEFAULT_GRACEFUL_TIMEOUT_SECS: u64 = 60;
 
-/// Handles `bm stop [-t team] [--force]`.
-pub fn run(team_flag: Option<&str>, force: bool) -> Result<()> {
+/// Seconds between progress lines while draining.
+const DRAIN_PROGRESS_SECS: u64 = 10;
+
+/// Seconds to wait after SIGTERM before escalating to SIGKILL.
+const TERM_GRACE_SECS: u64 = 10;
+
+/// How `bm stop` ends member processes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopMode {
+    /// Ask each member in turn to stop, giving up on one that doesn't exit
+    /// within `timeout` seconds.
+    Graceful { timeout: u64 },
+    /// Ask all members to finish their current iteration, wait up to
+    /// `timeout` seconds, then terminate the ones still running.
+    Drain { timeout: u64 },
+    /// Terminate immediately.
+    Force,
+}
+
+/// A running member being stopped.
+#[derive(Debug, Clone)]
+struct Member {
+    key: String,
+    name: String,
+    pid: u32,
+    workspace: PathBuf,
+}
+
+/// A member running as a pod, from the team's topology.
+#[derive(Debug, Clone)]
+struct Pod {
+    name: String,
+    namespace: String,
+    pod: String,
+    context: String,
+}
+
+/// How a drained member ended up stopping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Drained {
+    /// Exited on its own.
+    Finished,
+    /// Still running at the timeout; terminated with SIGTERM.
+    Terminated,
+    /// Ignored SIGTERM as well; killed.
+    Killed,
+}
+
+/// How stopping one team's members went.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Stopped {
+    /// Members recorded as running, dead or alive.
+    found: usize,
+    stopped: u32,
+    /// Of `stopped`, members that had already exited.
+    exited: u32,
+    /// Drained members terminated after the timeout.
+    terminated: u32,
+    errors: u32,
+}
+
+/// Handles `bm stop [-t team] [--force | --drain] [--graceful-timeout secs]`.
+pub fn run(team_flag: Option<&str>, mode: StopMode) -> Result<()> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, team_flag)?;
+
+    let Stopped {
+        found,
+        stopped,
+        terminated,
+        errors,
+        ..
+    } = stop_members(&cfg.workzone, team, mode)?;
+    if found == 0 {
+        println!("No members running for team '{}'.", team.name);
+        return Ok(());
+    }
+
+    if terminated > 0 {
+        println!(
+            "\nStopped {} member(s) ({} terminated after the drain timeout), {} error(s).",
+            stopped, terminated, errors
+        );
+    } else {
+        println!(
+            "\nStopped {} member(s), {} error(s).",
+            stopped, errors
+        );
+    }
+
+    if errors > 0 {
+        bail!(
+            "Some members could not be stopped gracefully. \
+             Use `bm stop --drain` to escalate after the timeout, or `bm stop -f` to force-kill."
+        );
+    }
+    Ok(())
+}
+
+/// Handles `bm stop --all`: stops every registered team's daemon, then its
+/// members, sweeping up after and dropping the ones that already died.
+/// Prints one line per team and keeps going past a team that fails.
+pub fn run_all(mode: StopMode) -> Result<()> {
+    let cfg = config::load()?;
+    if cfg.teams.is_empty() {
+        println!("No teams registered. Run `bm init` to create one.");
+        return Ok(());
+    }
+
+    // The daemon gets as long to finish its run as members get to stop
+    let drain = match mode {
+        StopMode::Force => None,
+        StopMode::Graceful { timeout } | StopMode::Drain { timeout } => {
+            Some(Duration::from_secs(timeout))
+        }
+    };
+
+    let mut failed = 0;
+    for team in &cfg.teams {
+        let mut done = Vec::new();
+        let mut problems = Vec::new();
+        match daemon::shutdown(team, drain) {
+            Ok(true) => done.push("daemon stopped".to_string()),
+            Ok(false) => {}
+            Err(e) => problems.push(format!("daemon not stopped: {:#}", e)),
+        }
+        match stop_members(&cfg.workzone, team, mode) {
+            Ok(s) => {
+                if s.stopped > s.exited {
+                    done.push(format!("{} member(s) stopped", s.stopped - s.exited));
+                }
+                if s.exited > 0 {
+                    done.push(format!(
+                        "{} stale entr{} cleaned",
+                        s.exited,
+                        if s.exited == 1 { "y" } else { "ies" }
+                    ));
+                }
+                if s.errors > 0 {
+                    problems.push(format!("{} member(s) still running", s.errors));
+                }
+            }
+            Err(e) => problems.push(format!("members not stopped: {:#}", e)),
+        }
+
+        if !problems.is_empty() {
+            failed += 1;
+        }
+        done.extend(problems);
+        if done.is_empty() {
+            done.push("nothing running".to_string());
+        }
+        println!("{}: {}", team.name, done.join(", "));
+    }
+
+    if failed > 0 {
+        bail!(
+            "{} team(s) not fully stopped. Retry with `bm stop --all --drain`, or `bm stop --all -f` to force-kill.",
+            failed
+        );
+    }
+    Ok(())
+}
+
+/// Stops the team's members recorded in state and the pods its topology
+/// places, then removes the topology file if all of them stopped. Progress
+/// goes to stderr.
+fn stop_members(workzone: &Path, team: &TeamEntry, mode: StopMode) -> Result<Stopped> {
     let team_name = &team.name;
 
-    let mut runtime_state = state::load()?;
+    let runtime_state = state::load()?;
 
     // Find running members for this team
     let team_prefix = format!("{}/", team_name);
-    let running: Vec<(String, u32, std::path::PathBuf)> = runtime_state
+    let mut running: Vec<Member> = runtime_state
         .members
         .iter()
         .filter(|(key, _)| key.starts_with(&team_prefix))
-        .map(|(key, rt)| (key.clone(), rt.pid, rt.workspace.clone()))
+        .map(|(key, rt)| Member {
+            key: key.clone(),
+            name: key.strip_prefix(&team_prefix).unwrap_or(key).to_string(),
+            pid: rt.pid,
+            workspace: rt.workspace.clone(),
+        })
         .collect();
+    running.sort_by(|a, b| a.name.cmp(&b.name));
 
-    if running.is_empty() {
-        println!("No members running for team '{}'.", team_name);
-        return Ok(());
+    // Members a non-local formation placed are only in the topology
+    let topo_path = topology::topology_path(workzone, team_name);
+    let mut pods: Vec<Pod> = topology::load(&topo_path)?
+        .map(|topo| {
+            topo.members
+                .into_iter()
+                .filter(|(_, m)| m.status != "stopped")
+                .filter_map(|(name, m)| match m.endpoint {
+                    topology::Endpoint::K8s {
+                        namespace,
+                        pod,
+                        context,
+                        ..
+                    } => Some(Pod {
+                        name,
+                        namespace,
+                        pod,
+                        context,
+                    }),
+                    topology::Endpoint::Local { .. } => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    pods.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let found = running.len() + pods.len();
+    if found == 0 {
+        return Ok(Stopped::default());
     }
 
     let mut stopped = 0u32;
+    let mut exited = 0u32;
+    let mut terminated = 0u32;
     let mut errors = 0u32;
+    let mut alive: Vec<Member> = Vec::new();
 
-    for (key, pid, workspace) in &running {
-        let member_name = key.strip_prefix(&team_prefix).unwrap_or(key);
-
-        if !state::is_alive(*pid) {
-            eprint!("{}... already exited", member_name);
+    for member in running {
+        if !procutil::is_alive(member.pid) {
+            eprint!("{}... already exited", member.name);
             eprintln!();
-            runtime_state.members.remove(key);
-            state::save(&runtime_state)?;
+            sweep(&member);
+            remove_member(&member.key, RunOutcome::Crashed)?;
             stopped += 1;
-            continue;
+            exited += 1;
+        } else {
+            alive.push(member);
         }
+    }
 
-        if force {
-            eprint!("Stopping {} (force)... ", member_name);
-            force_stop(*pid);
-            runtime_state.members.remove(key);
-            state::save(&runtime_state)?;
-            eprintln!("done");
-            stopped += 1;
-        } else {
-            eprint!("Stopping {}... ", member_name);
-            match graceful_stop(workspace, *pid) {
-                Ok(()) => {
-                    runtime_state.members.remove(key);
-                    state::save(&runtime_state)?;
-                    eprintln!("done");
-                    stopped += 1;
+    match mode {
+        StopMode::Force => {
+            for member in &alive {
+                run_pre_stop(team, member);
+                eprint!("Stopping {} (force)... ", member.name);
+                force_stop(member.pid);
+                eprintln!("done");
+                sweep(member);
+                remove_member(&member.key, RunOutcome::Stopped)?;
+                stopped += 1;
+            }
+        }
+        StopMode::Graceful { timeout } => {
+            for member in &alive {
+                eprint!("Stopping {}... ", member.name);
+                match graceful_stop(&member.workspace, member.pid, timeout) {
+                    Ok(()) => {
+                        eprintln!("done");
+                        sweep(member);
+                        remove_member(&member.key, RunOutcome::Stopped)?;
+                        stopped += 1;
+                    }
+                    Err(e) => {
+                        eprintln!("failed: {}", e);
+                        eprintln!(
+                            "  Hint: try `bm stop --drain` or `bm stop -f`, or check workspace at {}",
+                            member.workspace.display()
+                        );
+                        errors += 1;
+                    }
                 }
-                Err(e) => {
-                    eprintln!("failed: {}", e);
-                    eprintln!(
-                        "  Hint: try `bm stop -f` to force-kill, or check workspace at {}",
-                        workspace.display()
-                    );
-                    errors += 1;
+            }
+        }
+        StopMode::Drain { timeout } => {
+            let results = drain(&alive, timeout, request_stop, |m| run_pre_stop(team, m));
+            for (member, drained) in results {
+                sweep(&member);
+                remove_member(&member.key, RunOutcome::Stopped)?;
+                stopped += 1;
+                if drained != Drained::Finished {
+                    terminated += 1;
                 }
             }
         }
     }
 
-    println!(
-        "\nStopped {} member(s), {} error(s).",
-        stopped, errors
-    );
+    let grace = match mode {
+        StopMode::Force => None,
+        StopMode::Graceful { timeout } | StopMode::Drain { timeout } => Some(timeout),
+    };
+    for pod in &pods {
+        eprint!("Stopping {} (pod {})... ", pod.name, pod.pod);
+        match topology::stop_pod(&pod.namespace, &pod.pod, &pod.context, grace) {
+            Ok(topology::PodStop::Scaled(deployment)) => {
+                eprintln!("done (deployment {} scaled to 0)", deployment);
+                stopped += 1;
+            }
+            Ok(topology::PodStop::Deleted) => {
+                eprintln!("done (pod deleted)");
+                stopped += 1;
+            }
+            Ok(topology::PodStop::AlreadyGone) => {
+                eprintln!("already gone");
+                stopped += 1;
+            }
+            Err(e) => {
+                eprintln!("failed: {:#}", e);
+                errors += 1;
+            }
+        }
+    }
 
-    if errors > 0 {
+    // Remove topology file after all members stopped
+    if errors == 0 && topo_path.exists() {
+        topology::remove(&topo_path)?;
+    }
+
+    Ok(Stopped {
+        found,
+        stopped,
+        exited,
+        terminated,
+        errors,
+    })
+}
+
+/// Stops one running member: asks it to finish its iteration, waits up to
+/// `timeout` seconds, then runs its `pre_stop` hook and terminates it.
+/// Returns a one-line description of how it stopped.
+pub fn stop_member(team: &TeamEntry, member_name: &str, timeout: u64) -> Result<String> {
+    let key = format!("{}/{}", team.name, member_name);
+    let runtime_state = state::load()?;
+    let Some(rt) = runtime_state.members.get(&key) else {
         bail!(
-            "Some members could not be stopped gracefully. \
-             Use `bm stop -f` to force-kill."
+            "{} is not running in team '{}'. Run `bm status -t {}` to see who is.",
+            member_name,
+            team.name,
+            team.name
         );
+    };
+    let member = Member {
+        key: key.clone(),
+        name: member_name.to_string(),
+        pid: rt.pid,
+        workspace: rt.workspace.clone(),
+    };
+
+    if !procutil::is_alive(member.pid) {
+        sweep(&member);
+        remove_member(&key, RunOutcome::Crashed)?;
+        return Ok(format!("{} had already exited", member_name));
     }
+    let results = drain(std::slice::from_ref(&member), timeout, request_stop, |m| run_pre_stop(team, m));
+    sweep(&member);
+    remove_member(&key, RunOutcome::Stopped)?;
+    Ok(match results.first().map(|(_, drained)| *drained) {
+        Some(Drained::Terminated) => format!(
+            "{} was still running after {}s and was terminated",
+            member_name, timeout
+        ),
+        Some(Drained::Killed) => format!("{} ignored SIGTERM and was killed", member_name),
+        _ => format!("{} finished its iteration and stopped", member_name),
+    })
+}
 
-    // Remove topology file after all members stopped
-    let topo_path = topology::topology_path(&cfg.workzone, team_name);
-    if topo_path.exists() {
-        topology::remove(&topo_path)?;
+/// Graceful stop: ask the member to stop, then poll for exit.
+fn graceful_stop(workspace: &Path, pid: u32, timeout: u64) -> Result<()> {
+    request_stop(workspace)?;
+
+    // Poll for process exit
+    for _ in 0..timeout {
+        if !procutil::is_alive(pid) {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(1));
     }
 
-    Ok(())
+    bail!("Process {} did not exit after {}s.", pid, timeout);
+}
+
+/// Runs the member's `pre_stop` hook, if any, and reports how it went.
+fn run_pre_stop(team: &TeamEntry, member: &Member) {
+    if let Some(report) = pre_stop::run_for_member(team, &member.name, &member.workspace) {
+        eprintln!("  {}: {}", member.name, report);
+    }
 }
 
-/// Graceful stop: run `ralph loops stop` in the workspace, then poll for exit.
-fn graceful_stop(workspace: &std::path::Path, pid: u32) -> Result<()> {
-    // Try ralph loops stop
+/// Runs `ralph loops stop` in the workspace, which lets the current loop
+/// iteration finish before Ralph exits.
+fn request_stop(workspace: &Path) -> Result<()> {
+    debug!("Running ralph loops stop in {}", workspace.display());
     let output = Command::new("ralph")
         .args(["loops", "stop"])
         .current_dir(workspace)
@@ -113,29 +417,189 @@ fn graceful_stop(workspace: &std::path::Path, pid: u32) -> Result<()> {
         Err(e) => {
             bail!("Failed to run ralph loops stop: {}", e);
         }
-        Ok(_) => {}
+        Ok(_) => Ok(()),
     }
+}
 
-    // Poll for process exit
-    for _ in 0..GRACEFUL_TIMEOUT_SECS {
-        if !state::is_alive(pid) {
-            return Ok(());
+/// Asks every member to stop at once, reports progress while they finish
+/// their iterations, and terminates those still running after `timeout`
+/// seconds, calling `before_terminate` on each first. Returns every member
+/// with how it stopped.
+fn drain(
+    members: &[Member],
+    timeout: u64,
+    request: impl Fn(&Path) -> Result<()>,
+    before_terminate: impl Fn(&Member),
+) -> Vec<(Member, Drained)> {
+    for member in members {
+        match request(&member.workspace) {
+            Ok(()) => eprintln!("Draining {} (PID {})", member.name, member.pid),
+            Err(e) => eprintln!(
+                "Draining {} (PID {}): {:#}; it will be terminated after {}s",
+                member.name, member.pid, e, timeout
+            ),
+        }
+    }
+
+    let start = Instant::now();
+    let mut waiting: Vec<Member> = members.to_vec();
+    let mut results: Vec<(Member, Drained)> = Vec::new();
+    let mut last_progress = 0;
+
+    while !waiting.is_empty() {
+        let elapsed = start.elapsed().as_secs();
+        let (exited, still): (Vec<Member>, Vec<Member>) =
+            waiting.into_iter().partition(|m| !procutil::is_alive(m.pid));
+        waiting = still;
+        for member in exited {
+            eprintln!("  {}: finished after {}s", member.name, elapsed);
+            results.push((member, Drained::Finished));
+        }
+        if waiting.is_empty() || elapsed >= timeout {
+            break;
+        }
+        if elapsed >= last_progress + DRAIN_PROGRESS_SECS {
+            last_progress = elapsed;
+            let names: Vec<&str> = waiting.iter().map(|m| m.name.as_str()).collect();
+            eprintln!(
+                "  Waiting on {} ({}s/{}s)",
+                names.join(", "),
+                elapsed,
+                timeout
+            );
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+
+    // Escalate: SIGTERM everyone left, then SIGKILL holdouts
+    for member in &waiting {
+        eprintln!(
+            "  {}: still running after {}s, terminating",
+            member.name, timeout
+        );
+        before_terminate(member);
+        procutil::terminate_group(member.pid);
+    }
+    let deadline = Instant::now() + Duration::from_secs(TERM_GRACE_SECS);
+    while waiting.iter().any(|m| procutil::is_alive(m.pid)) && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(250));
+    }
+    for member in waiting {
+        if procutil::is_alive(member.pid) {
+            eprintln!("  {}: ignored SIGTERM, killing", member.name);
+            procutil::kill_group(member.pid);
+            results.push((member, Drained::Killed));
+        } else {
+            results.push((member, Drained::Terminated));
         }
-        thread::sleep(Duration::from_secs(1));
     }
 
-    bail!(
-        "Process {} did not exit after {}s. Use `bm stop -f` to force-kill.",
-        pid,
-        GRACEFUL_TIMEOUT_SECS
-    );
+    results
 }
 
-/// Force stop: send SIGTERM to the process.
+/// Force stop: ask the member's process group to terminate (SIGTERM on Unix).
 fn force_stop(pid: u32) {
-    unsafe {
-        libc::kill(pid as i32, libc::SIGTERM);
-    }
+    debug!("Terminating process group {}", pid);
+    procutil::terminate_group(pid);
     // Brief wait for cleanup
-    thread::sleep(Duration::from_millis(500));
+    procutil::wait_for_exit(pid, Duration::from_millis(500));
+}
+
+/// Ends the processes a stopped member left in its process group (Ralph's
+/// `claude` and `git` children, say) and reports them.
+fn sweep(member: &Member) {
+    let pids: Vec<String> = procutil::sweep_group(member.pid, Duration::from_secs(TERM_GRACE_SECS))
+        .into_iter()
+        .filter(|pid| *pid != member.pid)
+        .map(|pid| pid.to_string())
+        .collect();
+    if !pids.is_empty() {
+        eprintln!(
+            "  {}: {} process(es) outlived it and were terminated: {}",
+            member.name,
+            pids.len(),
+            pids.join(", ")
+        );
+    }
+}
+
+/// Removes a member's entry from state.json under the state lock, recording
+/// how its run ended.
+fn remove_member(key: &str, outcome: RunOutcome) -> Result<()> {
+    let now = chrono::Utc::now();
+    state::update(|s| {
+        match outcome {
+            RunOutcome::Crashed => s.end_crashed_run(key, state::last_log_write(key), now),
+            RunOutcome::Stopped => s.end_run(key, outcome, now),
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Child;
+
+    /// Spawns `sleep` as a stand-in member; a thread reaps it once it exits
+    /// so it doesn't linger as a zombie that still looks alive.
+    fn spawn_member(name: &str) -> (Member, thread::JoinHandle<()>) {
+        let mut child: Child = Command::new("sleep").arg("30").spawn().unwrap();
+        let member = Member {
+            key: format!("t/{}", name),
+            name: name.to_string(),
+            pid: child.id(),
+            workspace: PathBuf::from(format!("/tmp/{}", name)),
+        };
+        let reaper = thread::spawn(move || {
+            let _ = child.wait();
+        });
+        (member, reaper)
+    }
+
+    #[test]
+    fn drain_waits_for_members_that_finish() {
+        let (member, reaper) = spawn_member("dev-a");
+        let pid = member.pid;
+        let start = Instant::now();
+        // The stop request makes the member exit, as Ralph does after its iteration
+        let results = drain(
+            &[member],
+            5,
+            |_| {
+                procutil::terminate(pid);
+                Ok(())
+            },
+            |m| panic!("{} finished; no hook expected", m.name),
+        );
+        reaper.join().unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].1, Drained::Finished);
+        assert!(start.elapsed().as_secs() < 5, "should not wait for the timeout");
+    }
+
+    #[test]
+    fn drain_terminates_members_still_running_at_timeout() {
+        let (a, reaper_a) = spawn_member("dev-a");
+        let (b, reaper_b) = spawn_member("dev-b");
+        let hooked = std::cell::RefCell::new(Vec::new());
+        let results = drain(
+            &[a, b],
+            1,
+            |_| bail!("ralph not installed"),
+            |m| hooked.borrow_mut().push(m.name.clone()),
+        );
+        reaper_a.join().unwrap();
+        reaper_b.join().unwrap();
+
+        assert_eq!(hooked.into_inner(), vec!["dev-a", "dev-b"]);
+
+        let outcomes: Vec<(&str, Drained)> =
+            results.iter().map(|(m, d)| (m.name.as_str(), *d)).collect();
+        assert_eq!(
+            outcomes,
+            vec![("dev-a", Drained::Terminated), ("dev-b", Drained::Terminated)]
+        );
+    }
 }
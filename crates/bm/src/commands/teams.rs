@@ -1,12 +1,34 @@
+use std::fmt;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
-use anyhow::{Context, Result};
-use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL_CONDENSED, Table};
-use serde::Deserialize;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
 
-use crate::commands::init::run_git;
+use crate::commands::daemon;
+use crate::commands::init::{self, run_git};
+use crate::commands::stop::{self, StopMode};
 use crate::config;
+use crate::daemon_settings;
+use crate::dry_run::{Plan, Step};
+use crate::github_app::{self, TeamTokenSource};
+use crate::github_hooks;
+use crate::logging;
+use crate::member_templates;
+use crate::members;
+use crate::parse;
+use crate::pre_stop;
+use crate::procutil;
 use crate::profile;
+use crate::profile_checksums;
+use crate::profile_lint::Severity;
+use crate::state;
+use crate::store;
+use crate::tables;
+use crate::team_verify;
+use crate::tunnel;
+use crate::watchdog;
 use crate::workspace;
 
 /// Minimal manifest for reading project count.
@@ -18,24 +40,7 @@ struct TeamManifest {
 
 /// Counts member directories under `team_repo/team/`.
 fn count_members(team_repo: &std::path::Path) -> usize {
-    let members_dir = team_repo.join("team");
-    if !members_dir.is_dir() {
-        return 0;
-    }
-    fs::read_dir(&members_dir)
-        .map(|entries| {
-            entries
-                .flatten()
-                .filter(|e| {
-                    e.file_type().map(|ft| ft.is_dir()).unwrap_or(false)
-                        && !e
-                            .file_name()
-                            .to_string_lossy()
-                            .starts_with('.')
-                })
-                .count()
-        })
-        .unwrap_or(0)
+    members::list_names(&team_repo.join("team")).map_or(0, |names| names.len())
 }
 
 /// Reads project count from botminter.yml in the team repo.
@@ -58,11 +63,7 @@ pub fn list() -> Result<()> {
         return Ok(());
     }
 
-    let mut table = Table::new();
-    table
-        .load_preset(UTF8_FULL_CONDENSED)
-        .apply_modifier(UTF8_ROUND_CORNERS)
-        .set_header(vec!["Team", "Profile", "GitHub", "Members", "Projects", "Default"]);
+    let mut table = tables::new(vec!["Team", "Profile", "GitHub", "Members", "Projects", "Default"]);
 
     for team in &cfg.teams {
         let is_default = cfg.default_team.as_ref() == Some(&team.name);
@@ -80,7 +81,7 @@ pub fn list() -> Result<()> {
         ]);
     }
 
-    println!("{table}");
+    println!("{}", tables::render(&table));
     Ok(())
 }
 
@@ -98,42 +99,40 @@ pub fn show(name: Option<&str>, team_flag: Option<&str>) -> Result<()> {
     if !team.github_repo.is_empty() {
         println!("GitHub: {}", team.github_repo);
     }
+    if !team.extra_repos.is_empty() {
+        println!("Also works in: {}", team.extra_repos.join(", "));
+    }
     println!("Path: {}", team.path.display());
     println!("Default: {}", if is_default { "yes" } else { "no" });
 
     // Members section
     let members_dir = team_repo.join("team");
-    let mut members: Vec<(String, String)> = Vec::new();
-    if members_dir.is_dir() {
-        for entry in fs::read_dir(&members_dir)? {
-            let entry = entry?;
-            if !entry.file_type()?.is_dir() {
-                continue;
-            }
-            let name = entry.file_name().to_string_lossy().to_string();
-            if name.starts_with('.') {
-                continue;
-            }
-            let role = read_member_role(&members_dir, &name);
-            members.push((name, role));
-        }
-    }
-    members.sort_by(|a, b| a.0.cmp(&b.0));
+    let members = if members_dir.is_dir() {
+        members::list(&members_dir)?
+    } else {
+        Vec::new()
+    };
 
     println!();
     if members.is_empty() {
         println!("Members: none");
     } else {
         println!("Members:");
-        let mut table = Table::new();
-        table
-            .load_preset(UTF8_FULL_CONDENSED)
-            .apply_modifier(UTF8_ROUND_CORNERS)
-            .set_header(vec!["Name", "Role"]);
-        for (name, role) in &members {
-            table.add_row(vec![name.as_str(), role.as_str()]);
+        let mut table = tables::new(vec!["Name", "Role"]);
+        for member in &members {
+            table.add_row(vec![member.name.as_str(), member.role.as_str()]);
+        }
+        println!("{}", tables::render(&table));
+    }
+
+    // Role capacity: flag roles declared with a `min` the team doesn't meet
+    if let Ok(contents) = fs::read_to_string(team_repo.join("botminter.yml")) {
+        if let Ok(manifest) = serde_yml::from_str::<profile::ProfileManifest>(&contents) {
+            let names: Vec<String> = members.iter().map(|m| m.name.clone()).collect();
+            for warning in profile::understaffed_roles(&manifest.roles, &names) {
+                println!("Warning: {}", warning);
+            }
         }
-        println!("{table}");
     }
 
     // Projects section
@@ -143,155 +142,1184 @@ pub fn show(name: Option<&str>, team_flag: Option<&str>) -> Result<()> {
         println!("Projects: none");
     } else {
         println!("Projects:");
-        let mut table = Table::new();
-        table
-            .load_preset(UTF8_FULL_CONDENSED)
-            .apply_modifier(UTF8_ROUND_CORNERS)
-            .set_header(vec!["Name", "Fork URL"]);
+        let mut table = tables::new(vec!["Name", "Fork URL"]);
         for proj in &projects {
             table.add_row(vec![proj.name.as_str(), proj.fork_url.as_str()]);
         }
-        println!("{table}");
+        println!("{}", tables::render(&table));
     }
 
     Ok(())
 }
 
-/// Reads the role from a member's botminter.yml, falling back to dir-name inference.
-fn read_member_role(members_dir: &std::path::Path, member_dir_name: &str) -> String {
-    let manifest_path = members_dir.join(member_dir_name).join("botminter.yml");
-    if let Ok(contents) = fs::read_to_string(&manifest_path) {
-        if let Ok(manifest) = serde_yml::from_str::<MemberManifest>(&contents) {
-            if let Some(role) = manifest.role {
-                return role;
+/// Returns true for an `owner/name` repo slug.
+pub(crate) fn is_repo_slug(repo: &str) -> bool {
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    matches!(repo.split_once('/'), Some((owner, name)) if valid(owner) && valid(name))
+}
+
+/// Handles `bm teams verify [-t team]` — checks the team repo against its
+/// profile and fails if any errors are found.
+pub fn verify(team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let team_repo = team.path.join("team");
+
+    let findings = team_verify::verify(&team_repo, &team.profile)?;
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+
+    for f in &findings {
+        let label = match f.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        println!("{}: {}: {}", label, f.file, f.message);
+    }
+    if findings.is_empty() {
+        println!("✓ Team '{}': repo matches the {} profile", team.name, team.profile);
+    } else {
+        println!("\n{} error(s), {} warning(s)", errors, warnings);
+    }
+
+    if errors > 0 {
+        bail!(
+            "Team repo for '{}' has {} error(s) (at {})",
+            team.name,
+            errors,
+            team_repo.display()
+        );
+    }
+    Ok(())
+}
+
+/// Handles `bm teams add-repo <owner/name> [-t team]` — adds a repo the team
+/// works in: creates the team's labels there, links it to the project board,
+/// and records it so the daemon watches it.
+pub fn add_repo(repo: &str, team_flag: Option<&str>) -> Result<()> {
+    if !is_repo_slug(repo) {
+        bail!("'{}' is not a GitHub repo. Use owner/name, e.g. my-org/api.", repo);
+    }
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?.clone();
+    if team.github_repo.is_empty() {
+        bail!(
+            "Team '{}' has no GitHub repo of its own. Set github_repo in \
+             ~/.botminter/config.yml first.",
+            team.name
+        );
+    }
+    if team.repos().iter().any(|r| r.eq_ignore_ascii_case(repo)) {
+        bail!("Team '{}' already works in {}.", team.name, repo);
+    }
+    let board_owner = team.github_repo.split('/').next().unwrap_or_default();
+    let repo_owner = repo.split('/').next().unwrap_or_default();
+    if team.credentials.github_app.is_some() && !board_owner.eq_ignore_ascii_case(repo_owner) {
+        bail!(
+            "Team '{}' authenticates as a GitHub App, and its tokens only cover repos owned by \
+             {}, so {} can't be added.",
+            team.name,
+            board_owner,
+            repo
+        );
+    }
+    let gh_token = team.credentials.gh_token.as_deref();
+    verify_repo_access(repo, gh_token)?;
+
+    // The profile's labels plus one per project, as on the team repo
+    let team_repo = team.path.join("team");
+    let manifest_path = team_repo.join("botminter.yml");
+    let manifest: profile::ProfileManifest = {
+        let contents = fs::read_to_string(&manifest_path)
+            .context("Failed to read team repo's botminter.yml")?;
+        parse::yaml(&manifest_path, &contents)?
+    };
+    let mut labels = manifest.labels.clone();
+    for project in &manifest.projects {
+        labels.push(profile::LabelDef {
+            name: format!("project/{}", project.name),
+            color: "BFD4F2".to_string(),
+            description: format!("Issues for the {} project", project.name),
+        });
+    }
+    init::bootstrap_labels(repo, &labels, gh_token).with_context(|| {
+        format!(
+            "Failed to create the team's labels on {}. Make sure the token has Issues (Write) \
+             permission there",
+            repo
+        )
+    })?;
+    println!("Created {} label(s) on {}", labels.len(), repo);
+
+    // Boards only link repos of their own owner
+    if board_owner.eq_ignore_ascii_case(repo_owner) {
+        match init::find_project_number(board_owner, &team.name, gh_token)
+            .and_then(|number| link_board(number, board_owner, repo, gh_token))
+        {
+            Ok(()) => println!("Linked {} to the '{} Board' project", repo, team.name),
+            Err(e) => eprintln!(
+                "Warning: could not link {} to the team's project board: {:#}",
+                repo, e
+            ),
+        }
+    }
+
+    config::update(|cfg| {
+        let entry = cfg
+            .teams
+            .iter_mut()
+            .find(|t| t.name == team.name)
+            .context("Team disappeared from config.yml")?;
+        entry.extra_repos.push(repo.to_string());
+        Ok(())
+    })?;
+
+    println!("Added {} to team '{}'.", repo, team.name);
+    println!(
+        "A poll-mode daemon picks it up on its next poll. In webhook mode, run \
+         `bm webhooks setup -t {} --url <public-url>` or restart a --tunnel daemon.",
+        team.name
+    );
+    Ok(())
+}
+
+/// Handles `bm teams remove-repo <owner/name> [-t team]`.
+pub fn remove_repo(repo: &str, team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    if team.github_repo.eq_ignore_ascii_case(repo) {
+        bail!(
+            "{} is the team repo of '{}' and can't be removed. `bm teams remove-repo` only \
+             drops repos added with `bm teams add-repo`.",
+            repo,
+            team.name
+        );
+    }
+    if !team.extra_repos.iter().any(|r| r.eq_ignore_ascii_case(repo)) {
+        bail!(
+            "Team '{}' doesn't work in {}. Its repos: {}",
+            team.name,
+            repo,
+            team.repos().join(", ")
+        );
+    }
+    let team_name = team.name.clone();
+    config::update(|cfg| {
+        if let Some(entry) = cfg.teams.iter_mut().find(|t| t.name == team_name) {
+            entry.extra_repos.retain(|r| !r.eq_ignore_ascii_case(repo));
+        }
+        Ok(())
+    })?;
+    println!("Team '{}' no longer works in {}.", team_name, repo);
+    println!(
+        "Its labels and any webhooks were left in place; delete webhooks under \
+         Settings → Webhooks."
+    );
+    Ok(())
+}
+
+/// One step of `bm teams destroy`, in the order they run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Teardown {
+    /// Stop the daemon, its tunnel, and the tunnel's webhooks.
+    StopDaemon,
+    /// Stop running members and the token refresher.
+    StopMembers,
+    /// Delete the team's webhooks from these repos added with add-repo.
+    DeleteWebhooks(Vec<String>),
+    /// Delete the `<team> Board` project.
+    DeleteProject { owner: String, title: String },
+    /// Delete the team repo on GitHub.
+    DeleteRepo(String),
+    /// Delete the team directory: the team repo clone and all workspaces.
+    RemoveDir(PathBuf),
+    /// Delete runtime files, logs, and member tokens under `~/.botminter`.
+    RemoveFiles(Vec<PathBuf>),
+    /// Drop the team from config.yml.
+    Unregister,
+}
+
+impl Teardown {
+    /// What the step does, for the plan shown before anything is removed.
+    fn describe(&self) -> String {
+        match self {
+            Teardown::StopDaemon => "Stop the daemon".to_string(),
+            Teardown::StopMembers => "Stop running members".to_string(),
+            Teardown::DeleteWebhooks(repos) => {
+                format!("Delete the team's webhooks from {}", repos.join(", "))
             }
+            Teardown::DeleteProject { owner, title } => {
+                format!("Delete the '{}' project of {}", title, owner)
+            }
+            Teardown::DeleteRepo(repo) => format!("Delete the GitHub repo {}", repo),
+            Teardown::RemoveDir(dir) => format!("Delete {} and all workspaces in it", dir.display()),
+            Teardown::RemoveFiles(files) => format!(
+                "Delete {} runtime file(s), log(s), and token(s) under ~/.botminter",
+                files.len()
+            ),
+            Teardown::Unregister => "Remove the team from ~/.botminter/config.yml".to_string(),
         }
     }
-    member_dir_name
-        .split('-')
-        .next()
-        .unwrap_or("unknown")
-        .to_string()
+
+    /// Whether the step acts on GitHub. Those may fail without stopping the
+    /// local teardown; what they leave behind is reported at the end.
+    fn is_remote(&self) -> bool {
+        matches!(
+            self,
+            Teardown::DeleteWebhooks(_) | Teardown::DeleteProject { .. } | Teardown::DeleteRepo(_)
+        )
+    }
 }
 
-/// Minimal member manifest for reading role.
-#[derive(Debug, Deserialize)]
-struct MemberManifest {
-    #[serde(default)]
-    role: Option<String>,
+/// The steps that remove `team`. `files` are its files under `~/.botminter`.
+fn teardown_plan(
+    team: &config::TeamEntry,
+    keep_github: bool,
+    daemon_running: bool,
+    files: Vec<PathBuf>,
+) -> Vec<Teardown> {
+    let mut steps = Vec::new();
+    if daemon_running {
+        steps.push(Teardown::StopDaemon);
+    }
+    steps.push(Teardown::StopMembers);
+    if !keep_github && !team.github_repo.is_empty() {
+        if !team.extra_repos.is_empty() {
+            steps.push(Teardown::DeleteWebhooks(team.extra_repos.clone()));
+        }
+        steps.push(Teardown::DeleteProject {
+            owner: team.github_repo.split('/').next().unwrap_or_default().to_string(),
+            title: format!("{} Board", team.name),
+        });
+        steps.push(Teardown::DeleteRepo(team.github_repo.clone()));
+    }
+    if team.path.exists() {
+        steps.push(Teardown::RemoveDir(team.path.clone()));
+    }
+    if !files.is_empty() {
+        steps.push(Teardown::RemoveFiles(files));
+    }
+    steps.push(Teardown::Unregister);
+    steps
+}
+
+/// The team's files under `~/.botminter` that exist: daemon runtime files,
+/// its local settings override, logs with their archives, and member tokens.
+fn local_files(team: &config::TeamEntry) -> Result<Vec<PathBuf>> {
+    let name = &team.name;
+    let mut files = vec![
+        daemon::pid_path(name)?,
+        daemon::config_path(name)?,
+        daemon::poll_state_path(name)?,
+        daemon::queue_path(name)?,
+        daemon::deliveries_path(name)?,
+        daemon::digest_path(name)?,
+        daemon::trigger_path(name)?,
+        daemon::replay_path(name)?,
+        daemon::drain_path(name)?,
+        daemon::runs_path(name)?,
+        daemon::events_dir(name)?,
+        daemon::schedules_path(name)?,
+        watchdog::heartbeat_path(name)?,
+        daemon_settings::local_override_path(name)?,
+        github_app::refresher_pid_path(name)?,
+        config::config_dir()?.join("gh").join(name),
+    ];
+    files.retain(|f| f.exists());
+
+    let mut logs = vec![daemon::log_path(name)?, tunnel::log_path(name)?];
+    let members = members::list_names(&team.path.join("team").join("team")).unwrap_or_default();
+    for member in &members {
+        logs.push(daemon::member_log_path(name, member)?);
+        logs.push(pre_stop::log_path(name, member)?);
+    }
+    for log in &logs {
+        files.extend(logging::log_and_archives(log));
+    }
+    Ok(files)
+}
+
+/// Handles `bm teams destroy <name> [--keep-github] [--dry-run] [--yes]` —
+/// the inverse of `bm init`: stops the team, deletes its GitHub repo, board,
+/// and webhooks, its workspaces and local files, and unregisters it.
+pub fn destroy(name: &str, keep_github: bool, dry_run: bool, yes: bool) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, Some(name))?.clone();
+    if team.path == cfg.workzone {
+        bail!(
+            "Team '{}' lives directly in the workzone ({}); refusing to delete it. \
+             Remove its entry from ~/.botminter/config.yml by hand.",
+            team.name,
+            team.path.display()
+        );
+    }
+
+    let daemon_running = daemon::pid_path(&team.name)?.exists();
+    let steps = teardown_plan(&team, keep_github, daemon_running, local_files(&team)?);
+    println!("Destroying team '{}' will:", team.name);
+    for step in &steps {
+        println!("  - {}", step.describe());
+    }
+    if keep_github && !team.github_repo.is_empty() {
+        println!("{}, its project board, and webhooks are kept.", team.github_repo);
+    }
+    if dry_run {
+        if let Some(Teardown::RemoveFiles(files)) =
+            steps.iter().find(|s| matches!(s, Teardown::RemoveFiles(_)))
+        {
+            println!("\nFiles under ~/.botminter:");
+            for file in files {
+                println!("  {}", file.display());
+            }
+        }
+        println!("\nDry run: nothing was removed.");
+        return Ok(());
+    }
+
+    if !yes {
+        let typed: String = cliclack::input(format!("Type '{}' to confirm", team.name))
+            .interact()?;
+        if typed.trim() != team.name {
+            bail!("Confirmation didn't match '{}'. Nothing was removed.", team.name);
+        }
+    }
+
+    let mut leftovers = Vec::new();
+    for step in &steps {
+        match run_teardown_step(&team, step) {
+            Ok(()) => println!("{}: done", step.describe()),
+            Err(e) if step.is_remote() => {
+                eprintln!("{}: failed: {:#}", step.describe(), e);
+                leftovers.push(step.describe());
+            }
+            Err(e) => {
+                return Err(e.context(format!(
+                    "Stopped destroying team '{}' at: {}",
+                    team.name,
+                    step.describe()
+                )))
+            }
+        }
+    }
+
+    if !leftovers.is_empty() {
+        bail!(
+            "Team '{}' was removed locally, but these GitHub steps failed; do them by hand:\n  {}",
+            team.name,
+            leftovers.join("\n  ")
+        );
+    }
+    println!("\nTeam '{}' destroyed.", team.name);
+    Ok(())
+}
+
+/// Whether any of the team's members are running, per `state.json`.
+fn members_running(team_name: &str) -> Result<bool> {
+    let prefix = format!("{}/", team_name);
+    Ok(state::load()?
+        .members
+        .iter()
+        .any(|(key, rt)| key.starts_with(&prefix) && procutil::is_alive(rt.pid)))
+}
+
+/// Handles `bm teams remove <name> [--stop] [--purge] [--yes]` — unregisters
+/// a team without touching GitHub. The team directory is kept unless
+/// `--purge` is given.
+pub fn remove(name: &str, stop_running: bool, purge: bool, yes: bool) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, Some(name))?.clone();
+
+    let daemon_running = daemon::pid_path(&team.name)?.exists();
+    let running = daemon_running || members_running(&team.name)?;
+    if running && !stop_running {
+        bail!(
+            "Team '{}' is running. Stop it with `bm stop -t {}` (and `bm daemon stop -t {}`), \
+             or pass --stop.",
+            team.name,
+            team.name,
+            team.name
+        );
+    }
+    if purge && team.path == cfg.workzone {
+        bail!(
+            "Team '{}' lives directly in the workzone ({}); refusing to purge it.",
+            team.name,
+            team.path.display()
+        );
+    }
+
+    let mut steps = Vec::new();
+    if daemon_running {
+        steps.push(Teardown::StopDaemon);
+    }
+    if running {
+        steps.push(Teardown::StopMembers);
+    }
+    if purge {
+        if team.path.exists() {
+            steps.push(Teardown::RemoveDir(team.path.clone()));
+        }
+        let files = local_files(&team)?;
+        if !files.is_empty() {
+            steps.push(Teardown::RemoveFiles(files));
+        }
+    }
+    steps.push(Teardown::Unregister);
+
+    if purge && !yes {
+        let confirmed = cliclack::confirm(format!(
+            "Delete {} and all workspaces in it?",
+            team.path.display()
+        ))
+        .initial_value(false)
+        .interact()?;
+        if !confirmed {
+            bail!("Nothing was removed.");
+        }
+    }
+
+    for step in &steps {
+        run_teardown_step(&team, step).with_context(|| {
+            format!("Stopped removing team '{}' at: {}", team.name, step.describe())
+        })?;
+        println!("{}: done", step.describe());
+    }
+
+    println!("\nTeam '{}' removed.", team.name);
+    if !purge && team.path.exists() {
+        println!("Its files are kept at {}.", team.path.display());
+    }
+    if !team.github_repo.is_empty() {
+        println!("{} on GitHub was left untouched.", team.github_repo);
+    }
+    if cfg.default_team.as_deref() == Some(team.name.as_str()) {
+        let others: Vec<&str> = cfg
+            .teams
+            .iter()
+            .filter(|t| t.name != team.name)
+            .map(|t| t.name.as_str())
+            .collect();
+        if !others.is_empty() {
+            println!(
+                "There is no default team now; pick one with `bm teams set-default <name>` ({}).",
+                others.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Handles `bm teams set-default <name>`.
+pub fn set_default(name: &str) -> Result<()> {
+    config::update(|cfg| {
+        let team = config::resolve_team(cfg, Some(name))?.name.clone();
+        cfg.default_team = Some(team);
+        Ok(())
+    })?;
+    println!("Default team is now '{}'.", name);
+    Ok(())
+}
+
+/// Carries out one teardown step.
+fn run_teardown_step(team: &config::TeamEntry, step: &Teardown) -> Result<()> {
+    let gh_token = team.credentials.gh_token.as_deref();
+    match step {
+        Teardown::StopDaemon => daemon::stop(Some(&team.name), None),
+        Teardown::StopMembers => {
+            stop::run(Some(&team.name), StopMode::Force)?;
+            let pid_file = github_app::refresher_pid_path(&team.name)?;
+            if let Some(pid) = fs::read_to_string(&pid_file)
+                .ok()
+                .and_then(|s| s.trim().parse::<u32>().ok())
+            {
+                if procutil::is_alive(pid) {
+                    procutil::terminate(pid);
+                }
+            }
+            Ok(())
+        }
+        Teardown::DeleteWebhooks(repos) => {
+            // Hooks set up by `bm webhooks setup` share their URL with the team repo's
+            let token = TeamTokenSource::default().token(team)?;
+            let urls: Vec<String> = github_hooks::list(&team.github_repo, token.as_deref())?
+                .iter()
+                .map(|h| h.url().to_string())
+                .filter(|u| !u.is_empty())
+                .collect();
+            for repo in repos {
+                for hook in github_hooks::list(repo, token.as_deref())? {
+                    if urls.iter().any(|u| u == hook.url()) {
+                        github_hooks::delete(repo, token.as_deref(), hook.id)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        Teardown::DeleteProject { owner, .. } => {
+            let number = init::find_project_number(owner, &team.name, gh_token)?;
+            run_gh(
+                &["project", "delete", &number.to_string(), "--owner", owner],
+                gh_token,
+            )
+        }
+        Teardown::DeleteRepo(repo) => run_gh(&["repo", "delete", repo, "--yes"], gh_token)
+            .context("Deleting a repo needs the delete_repo scope: gh auth refresh -s delete_repo"),
+        Teardown::RemoveDir(dir) => fs::remove_dir_all(dir)
+            .with_context(|| format!("Failed to delete {}", dir.display())),
+        Teardown::RemoveFiles(files) => {
+            for file in files {
+                let removed = if file.is_dir() {
+                    fs::remove_dir_all(file)
+                } else {
+                    fs::remove_file(file)
+                };
+                match removed {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                        return Err(e).with_context(|| format!("Failed to delete {}", file.display()))
+                    }
+                    _ => {}
+                }
+            }
+            Ok(())
+        }
+        Teardown::Unregister => {
+            // With the SQLite backend these records are not among the files
+            let store = store::open()?;
+            store.remove(&store::daemon_key(&team.name))?;
+            store.remove(&store::poll_key(&team.name))?;
+            store.remove_runs(&team.name)?;
+            config::update(|cfg| {
+                cfg.teams.retain(|t| t.name != team.name);
+                if cfg.default_team.as_deref() == Some(team.name.as_str()) {
+                    cfg.default_team = None;
+                }
+                Ok(())
+            })
+        }
+    }
+}
+
+/// Runs a `gh` command, failing with its stderr.
+fn run_gh(args: &[&str], gh_token: Option<&str>) -> Result<()> {
+    let mut cmd = Command::new("gh");
+    cmd.args(args);
+    if let Some(token) = gh_token {
+        cmd.env("GH_TOKEN", token);
+    }
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run `gh {}`", args.join(" ")))?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Checks that `repo` exists and the token can see it.
+fn verify_repo_access(repo: &str, gh_token: Option<&str>) -> Result<()> {
+    let mut cmd = Command::new("gh");
+    cmd.args(["repo", "view", repo, "--json", "name"]);
+    if let Some(token) = gh_token {
+        cmd.env("GH_TOKEN", token);
+    }
+    let output = cmd.output().context("Failed to run `gh repo view`")?;
+    if !output.status.success() {
+        bail!(
+            "Cannot access {}: {}\nCheck the name and that the team's token can read it.",
+            repo,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Links `repo` to the project board, so it shows under the repo's Projects tab.
+fn link_board(number: u64, owner: &str, repo: &str, gh_token: Option<&str>) -> Result<()> {
+    let mut cmd = Command::new("gh");
+    cmd.args(["project", "link", &number.to_string(), "--owner", owner, "--repo", repo]);
+    if let Some(token) = gh_token {
+        cmd.env("GH_TOKEN", token);
+    }
+    let output = cmd.output().context("Failed to run `gh project link`")?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Fetches origin and returns how many commits the team repo is behind its
+/// upstream, or `None` if it can't tell (offline, or no upstream).
+fn fetch_behind(team_repo: &Path) -> Option<u32> {
+    run_git(team_repo, &["fetch", "--quiet"]).ok()?;
+    workspace::git_summary(team_repo)?
+        .ahead_behind
+        .map(|(_, behind)| behind)
 }
 
-/// Handles `bm teams sync [--push] [-t team]` — provisions and reconciles workspaces.
-pub fn sync(push: bool, team_flag: Option<&str>) -> Result<()> {
+/// Handles `bm teams pull [--rebase] [-t team]` — brings in team repo changes
+/// pushed from another machine (e.g. members hired by another operator).
+///
+/// Fast-forwards when there are no local commits. With local commits, rebases
+/// onto origin only if `rebase` is set; a rebase that conflicts is aborted so
+/// the team repo is left as it was.
+pub fn pull(rebase: bool, team_flag: Option<&str>) -> Result<()> {
     let cfg = config::load()?;
     let team = config::resolve_team(&cfg, team_flag)?;
     let team_repo = team.path.join("team");
 
+    let summary = workspace::git_summary(&team_repo).with_context(|| {
+        format!("Team repo at {} is not a git repository", team_repo.display())
+    })?;
+    if summary.dirty {
+        bail!(
+            "Team repo at {} has uncommitted changes. Commit or stash them before pulling.",
+            team_repo.display()
+        );
+    }
+
+    run_git(&team_repo, &["fetch"])?;
+    let Some((ahead, behind)) = workspace::git_summary(&team_repo).and_then(|s| s.ahead_behind)
+    else {
+        bail!(
+            "Branch '{}' of the team repo has no upstream. Set one with \
+             `git -C {} branch --set-upstream-to origin/{}`.",
+            summary.branch,
+            team_repo.display(),
+            summary.branch
+        );
+    };
+
+    if behind == 0 {
+        println!("Team '{}' is up to date with origin.", team.name);
+        if ahead > 0 {
+            println!(
+                "{} local commit(s) not pushed yet. Run `bm teams sync --push` to push them.",
+                ahead
+            );
+        }
+        return Ok(());
+    }
+
+    let before = members::list_names(&team_repo.join("team")).unwrap_or_default();
+    if ahead == 0 {
+        run_git(&team_repo, &["merge", "--ff-only", "@{upstream}"])?;
+    } else if rebase {
+        rebase_onto_upstream(&team_repo)?;
+    } else {
+        bail!(
+            "Team repo has diverged from origin ({} local, {} remote commit(s)). \
+             Re-run with `bm teams pull --rebase` to replay local commits on top of origin.",
+            ahead,
+            behind
+        );
+    }
+
+    println!("Pulled {} commit(s) into team '{}'.", behind, team.name);
+    let after = members::list_names(&team_repo.join("team")).unwrap_or_default();
+    let hired: Vec<&str> = after
+        .iter()
+        .filter(|m| !before.contains(m))
+        .map(String::as_str)
+        .collect();
+    let fired: Vec<&str> = before
+        .iter()
+        .filter(|m| !after.contains(m))
+        .map(String::as_str)
+        .collect();
+    if !hired.is_empty() {
+        println!("New members: {}", hired.join(", "));
+    }
+    if !fired.is_empty() {
+        println!("Removed members: {}", fired.join(", "));
+    }
+    println!("Run `bm teams sync` to update workspaces.");
+    Ok(())
+}
+
+/// Rebases local commits onto the upstream branch. On conflict, aborts the
+/// rebase and reports the conflicting files.
+fn rebase_onto_upstream(team_repo: &Path) -> Result<()> {
+    if run_git(team_repo, &["rebase", "@{upstream}"]).is_ok() {
+        return Ok(());
+    }
+    let conflicts =
+        workspace::git_cmd_output(team_repo, &["diff", "--name-only", "--diff-filter=U"])
+            .unwrap_or_default();
+    run_git(team_repo, &["rebase", "--abort"])?;
+    let files: Vec<&str> = conflicts.lines().filter(|l| !l.is_empty()).collect();
+    bail!(
+        "Rebase onto origin conflicts in:\n  {}\n\
+         The rebase was aborted and the team repo is unchanged. \
+         Resolve by hand with `git -C {} pull --rebase`.",
+        if files.is_empty() {
+            "(unknown files)".to_string()
+        } else {
+            files.join("\n  ")
+        },
+        team_repo.display()
+    );
+}
+
+/// Fills in template variables that reached members' PROMPT.md or CLAUDE.md
+/// after they were hired (e.g. via `bm upgrade`), committing the result so
+/// workspaces pick it up. Returns the filled-in files, relative to the team
+/// repo.
+fn render_member_templates(
+    team_repo: &Path,
+    team_name: &str,
+    github_repo: &str,
+) -> Result<Vec<String>> {
+    let members_dir = team_repo.join("team");
+    let mut changed = Vec::new();
+    for member in members::list_names(&members_dir).unwrap_or_default() {
+        let member_dir = members_dir.join(&member);
+        let vars = member_templates::vars(&member_dir, team_name, github_repo)?;
+        let rendered = member_templates::render_member_files(&member_dir, &vars)?;
+        if rendered.is_empty() {
+            continue;
+        }
+        for file in rendered {
+            changed.push(format!("team/{}/{}", member, file));
+        }
+        // Pristine templates are re-recorded in the member's checksums
+        if member_dir.join(profile_checksums::CHECKSUMS_FILE).exists() {
+            changed.push(format!("team/{}/{}", member, profile_checksums::CHECKSUMS_FILE));
+        }
+    }
+    if changed.is_empty() {
+        return Ok(Vec::new());
+    }
+    changed.sort();
+    // Commit only these paths, leaving anything else the user has staged alone
+    let mut args = vec!["commit", "-m", "chore: fill in member template variables", "--"];
+    args.extend(changed.iter().map(String::as_str));
+    run_git(team_repo, &args)?;
+    changed.retain(|p| !p.ends_with(profile_checksums::CHECKSUMS_FILE));
+    Ok(changed)
+}
+
+/// Records the template fills `render_member_templates` would commit.
+fn plan_member_templates(team_repo: &Path, plan: &mut Plan) {
+    let members_dir = team_repo.join("team");
+    let mut files = Vec::new();
+    for member in members::list_names(&members_dir).unwrap_or_default() {
+        for file in member_templates::unrendered_files(&members_dir.join(&member)) {
+            files.push(format!("team/{}/{}", member, file));
+        }
+    }
+    if files.is_empty() {
+        return;
+    }
+    for file in &files {
+        plan.push(Step::WriteFile(team_repo.join(file)));
+    }
+    plan.push(Step::Commit {
+        repo: team_repo.to_path_buf(),
+        message: "chore: fill in member template variables".to_string(),
+    });
+}
+
+/// Records what syncing one workspace would do: clone it, or pull its
+/// repos and restore the files that drifted.
+fn plan_workspace(
+    plan: &mut Plan,
+    team_repo: &Path,
+    ws: &Path,
+    exists: bool,
+    member_dir_name: &str,
+    proj: Option<&profile::ProjectDef>,
+    drift: &[workspace::Drift],
+) {
+    if exists {
+        plan.push(Step::Run {
+            command: "git pull".to_string(),
+            dir: ws.join(".botminter"),
+        });
+        if proj.is_some() {
+            plan.push(Step::Run {
+                command: "git pull".to_string(),
+                dir: ws.to_path_buf(),
+            });
+        }
+        for d in drift {
+            plan.push(Step::WriteFile(ws.join(&d.path)));
+        }
+        return;
+    }
+    plan.push(Step::CreateDir(ws.to_path_buf()));
+    match proj {
+        Some(p) => {
+            plan.push(Step::Run {
+                command: format!("git clone {}", p.fork_url),
+                dir: ws.to_path_buf(),
+            });
+            plan.push(Step::Run {
+                command: format!("git checkout {}", member_dir_name),
+                dir: ws.to_path_buf(),
+            });
+        }
+        None => plan.push(Step::Run {
+            command: "git init -b main".to_string(),
+            dir: ws.to_path_buf(),
+        }),
+    }
+    plan.push(Step::Run {
+        command: format!("git clone {} .botminter", team_repo.display()),
+        dir: ws.to_path_buf(),
+    });
+    for file in ["PROMPT.md", "CLAUDE.md", "ralph.yml", ".gitignore"] {
+        plan.push(Step::WriteFile(ws.join(file)));
+    }
+    plan.push(Step::CreateDir(ws.join(".claude")));
+}
+
+/// What `bm teams sync` did with one workspace.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum SyncAction {
+    Created,
+    Updated,
+    /// `--check` compared it without changing anything.
+    Checked,
+    /// `--check` found no workspace.
+    Missing,
+    /// Creating it failed, typically because the project's fork could not
+    /// be cloned.
+    Failed { error: String, fork_url: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkspaceSync {
+    /// The member, or `member/project` in project mode.
+    pub label: String,
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub action: SyncAction,
+    /// How the workspace had drifted from the team repo before the sync.
+    pub drift: Vec<workspace::Drift>,
+}
+
+/// What `bm teams sync` did.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Synced {
+    pub team: String,
+    /// Whether this was a `--check` run, which changes nothing.
+    pub check: bool,
+    pub pushed: bool,
+    /// Member files whose template variables were filled in and committed,
+    /// relative to the team repo.
+    pub templates_filled: Vec<String>,
+    pub workspaces: Vec<WorkspaceSync>,
+}
+
+impl Synced {
+    fn count(&self, matches: fn(&SyncAction) -> bool) -> usize {
+        self.workspaces.iter().filter(|w| matches(&w.action)).count()
+    }
+
+    pub fn created(&self) -> usize {
+        self.count(|a| *a == SyncAction::Created)
+    }
+
+    pub fn updated(&self) -> usize {
+        self.count(|a| *a == SyncAction::Updated)
+    }
+
+    /// Workspaces that are missing or drifted from the team repo.
+    pub fn drifted(&self) -> usize {
+        self.workspaces
+            .iter()
+            .filter(|w| w.action == SyncAction::Missing || !w.drift.is_empty())
+            .count()
+    }
+
+    /// Fails if a workspace could not be created or, with `--check`, if any
+    /// workspace drifted or is missing.
+    pub fn ensure_ok(&self) -> Result<()> {
+        if self.check && self.drifted() > 0 {
+            bail!(
+                "{} of {} workspace(s) drifted from the team repo. Run `bm teams sync` to restore them.",
+                self.drifted(),
+                self.workspaces.len()
+            );
+        }
+        let failures: Vec<String> = self
+            .workspaces
+            .iter()
+            .filter_map(|w| match &w.action {
+                SyncAction::Failed { fork_url, .. } => Some(format!("{} ({})", w.label, fork_url)),
+                _ => None,
+            })
+            .collect();
+        if !failures.is_empty() {
+            bail!(
+                "{} workspace(s) failed to sync:\n  {}",
+                failures.len(),
+                failures.join("\n  ")
+            );
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Synced {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for path in &self.templates_filled {
+            writeln!(f, "Filled in template variables in {}", path)?;
+        }
+        if self.workspaces.is_empty() {
+            return writeln!(f, "No members hired. Run `bm hire <role>` first.");
+        }
+        for ws in &self.workspaces {
+            match &ws.action {
+                SyncAction::Missing => writeln!(f, "{}: workspace not created", ws.label)?,
+                SyncAction::Failed { error, .. } => {
+                    writeln!(f, "Error: {}: {}", ws.label, error)?
+                }
+                _ => {}
+            }
+            if !ws.drift.is_empty() {
+                writeln!(f, "Drift in {}:", ws.label)?;
+                for d in &ws.drift {
+                    writeln!(f, "  {}: {}", d.path.display(), d.kind)?;
+                }
+            }
+        }
+        if self.check {
+            if self.drifted() == 0 {
+                writeln!(
+                    f,
+                    "All {} workspace(s) match the team repo.",
+                    self.workspaces.len()
+                )?;
+            }
+            return Ok(());
+        }
+        let total = self.created() + self.updated();
+        writeln!(
+            f,
+            "Synced {} workspace{} ({} created, {} updated)",
+            total,
+            if total == 1 { "" } else { "s" },
+            self.created(),
+            self.updated(),
+        )
+    }
+}
+
+/// Handles `bm teams sync [--push] [--check] [--member m] [--project p] [-t team]` —
+/// provisions and reconciles workspaces, optionally only those of one member
+/// and/or one project. With `check`, only reports drift.
+pub fn sync(
+    push: bool,
+    check: bool,
+    member_filter: Option<&str>,
+    project_filter: Option<&str>,
+    team_flag: Option<&str>,
+) -> Result<Synced> {
+    sync_with(push, check, member_filter, project_filter, team_flag, None)
+}
+
+/// Handles `bm teams sync --dry-run`: lists the template commits, push, and
+/// workspace clones and refreshes a sync would make.
+pub fn sync_plan(
+    push: bool,
+    member_filter: Option<&str>,
+    project_filter: Option<&str>,
+    team_flag: Option<&str>,
+) -> Result<Plan> {
+    let mut plan = Plan::default();
+    sync_with(push, false, member_filter, project_filter, team_flag, Some(&mut plan))?;
+    Ok(plan)
+}
+
+fn sync_with(
+    push: bool,
+    check: bool,
+    member_filter: Option<&str>,
+    project_filter: Option<&str>,
+    team_flag: Option<&str>,
+    mut plan: Option<&mut Plan>,
+) -> Result<Synced> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let team_repo = team.path.join("team");
+    let link_mode = cfg.workspace.clone().unwrap_or_default().link_mode;
+
     // Schema version guard
     let manifest_path = team_repo.join("botminter.yml");
     let manifest: profile::ProfileManifest = {
         let contents = fs::read_to_string(&manifest_path)
             .context("Failed to read team repo's botminter.yml")?;
-        serde_yml::from_str(&contents).context("Failed to parse botminter.yml")?
+        parse::yaml(&manifest_path, &contents)?
     };
     profile::check_schema_version(&team.profile, &manifest.schema_version)?;
+    let mut summary = Synced {
+        team: team.name.clone(),
+        check,
+        pushed: push,
+        ..Synced::default()
+    };
+
+    // Changes pushed from another machine aren't picked up until pulled
+    if let Some(behind) = fetch_behind(&team_repo).filter(|&b| b > 0) {
+        if push {
+            bail!(
+                "Team repo is {} commit(s) behind origin, so the push would be rejected. \
+                 Run `bm teams pull` first.",
+                behind
+            );
+        }
+        eprintln!(
+            "Warning: team repo is {} commit(s) behind origin. \
+             Run `bm teams pull` to pick up changes made from other machines.",
+            behind
+        );
+    }
+
+    if let Some(plan) = plan.as_deref_mut() {
+        plan_member_templates(&team_repo, plan);
+        if push {
+            plan.push(Step::Push { repo: team_repo.clone() });
+        }
+    } else if !check {
+        summary.templates_filled =
+            render_member_templates(&team_repo, &team.name, &team.github_repo)?;
+    }
 
     // Optional push
-    if push {
+    if push && plan.is_none() {
         run_git(&team_repo, &["push"])?;
     }
 
     // Discover hired members (scan team/team/ dir)
     let members_dir = team_repo.join("team");
-    let mut members: Vec<String> = Vec::new();
-    if members_dir.is_dir() {
-        for entry in fs::read_dir(&members_dir)? {
-            let entry = entry?;
-            if entry.file_type()?.is_dir() {
-                let name = entry.file_name().to_string_lossy().to_string();
-                members.push(name);
-            }
-        }
-    }
-    members.sort();
+    let mut members = if members_dir.is_dir() {
+        members::list_names(&members_dir)?
+    } else {
+        Vec::new()
+    };
 
     if members.is_empty() {
-        println!("No members hired. Run `bm hire <role>` first.");
-        return Ok(());
+        return Ok(summary);
     }
 
-    let projects = &manifest.projects;
-    let mut created = 0u32;
-    let mut updated = 0u32;
-    let mut failures: Vec<String> = Vec::new();
+    if let Some(m) = member_filter {
+        if !members.iter().any(|name| name == m) {
+            anyhow::bail!(
+                "Member '{}' not found in team '{}'. Hired members: {}",
+                m,
+                team.name,
+                members.join(", ")
+            );
+        }
+        members.retain(|name| name == m);
+    }
 
-    for member_dir_name in &members {
+    let mut projects = manifest.projects.clone();
+    if let Some(p) = project_filter {
         if projects.is_empty() {
-            // No-project mode: workspace at {team.path}/{member_dir}/
-            let ws = team.path.join(member_dir_name);
-            let gh = Some(team.github_repo.as_str());
-            if ws.join(".botminter").is_dir() {
-                workspace::sync_workspace(&ws, member_dir_name, None, false, gh)?;
-                updated += 1;
+            anyhow::bail!(
+                "Team '{}' has no projects, so --project doesn't apply. \
+                 Add one with `bm projects add <fork-url>`.",
+                team.name
+            );
+        }
+        if !projects.iter().any(|proj| proj.name == p) {
+            let names: Vec<&str> = projects.iter().map(|proj| proj.name.as_str()).collect();
+            anyhow::bail!(
+                "Project '{}' not found in team '{}'. Available projects: {}",
+                p,
+                team.name,
+                names.join(", ")
+            );
+        }
+        projects.retain(|proj| proj.name == p);
+    }
+
+    for member_dir_name in &members {
+        // No-project mode: workspace at {team.path}/{member_dir}/.
+        // Project mode: one workspace per member × project.
+        let targets: Vec<(String, PathBuf, Option<&profile::ProjectDef>)> = if projects.is_empty()
+        {
+            vec![(member_dir_name.clone(), team.path.join(member_dir_name), None)]
+        } else {
+            projects
+                .iter()
+                .map(|proj| {
+                    (
+                        format!("{}/{}", member_dir_name, proj.name),
+                        team.path.join(member_dir_name).join(&proj.name),
+                        Some(proj),
+                    )
+                })
+                .collect()
+        };
+        let gh = Some(team.github_repo.as_str());
+        for (label, ws, proj) in targets {
+            let exists = ws.join(".botminter").is_dir();
+            let drift = if exists {
+                workspace::detect_drift(&ws, member_dir_name, link_mode)
             } else {
-                workspace::create_workspace(&team_repo, &team.path, member_dir_name, None, gh)?;
-                created += 1;
+                Vec::new()
+            };
+            if let Some(plan) = plan.as_deref_mut() {
+                plan_workspace(plan, &team_repo, &ws, exists, member_dir_name, proj, &drift);
+                continue;
             }
-        } else {
-            // Project mode: one workspace per member × project
-            let gh = Some(team.github_repo.as_str());
-            for proj in projects {
-                let ws = team.path.join(member_dir_name).join(&proj.name);
-                if ws.join(".botminter").is_dir() {
-                    workspace::sync_workspace(
-                        &ws,
-                        member_dir_name,
-                        Some(&proj.name),
-                        true,
-                        gh,
-                    )?;
-                    updated += 1;
+            let action = if check {
+                if exists {
+                    SyncAction::Checked
                 } else {
-                    match workspace::create_workspace(
-                        &team_repo,
-                        &team.path,
-                        member_dir_name,
-                        Some((&proj.name, &proj.fork_url)),
-                        gh,
-                    ) {
-                        Ok(()) => created += 1,
-                        Err(e) => {
-                            eprintln!(
-                                "Error: {}/{}: {}",
-                                member_dir_name, proj.name, e
-                            );
-                            failures.push(format!(
-                                "{}/{} ({})",
-                                member_dir_name, proj.name, proj.fork_url
-                            ));
-                        }
-                    }
+                    SyncAction::Missing
                 }
-            }
+            } else if exists {
+                let project = proj.map(|p| p.name.as_str());
+                workspace::sync_workspace(
+                    &ws,
+                    member_dir_name,
+                    project,
+                    proj.is_some(),
+                    gh,
+                    link_mode,
+                )?;
+                SyncAction::Updated
+            } else {
+                let project = proj.map(|p| (p.name.as_str(), p.fork_url.as_str()));
+                let created = workspace::create_workspace(
+                    &team_repo,
+                    &team.path,
+                    member_dir_name,
+                    project,
+                    gh,
+                    link_mode,
+                );
+                // A project's fork may be unreachable; that fails only its workspace
+                match (created, proj) {
+                    (Ok(()), _) => SyncAction::Created,
+                    (Err(e), Some(p)) => SyncAction::Failed {
+                        error: e.to_string(),
+                        fork_url: p.fork_url.clone(),
+                    },
+                    (Err(e), None) => return Err(e),
+                }
+            };
+            summary.workspaces.push(WorkspaceSync {
+                label,
+                path: ws,
+                action,
+                drift,
+            });
         }
     }
 
-    let total = created + updated;
-    println!(
-        "Synced {} workspace{} ({} created, {} updated)",
-        total,
-        if total == 1 { "" } else { "s" },
-        created,
-        updated,
-    );
-
-    if !failures.is_empty() {
-        anyhow::bail!(
-            "{} workspace(s) failed to sync:\n  {}",
-            failures.len(),
-            failures.join("\n  ")
-        );
-    }
-
-    Ok(())
+    Ok(summary)
 }
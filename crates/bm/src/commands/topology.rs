@@ -0,0 +1,90 @@
+use anyhow::Result;
+
+use crate::config;
+use crate::procutil;
+use crate::state;
+use crate::topology::{self, Change, Endpoint};
+
+/// Handles `bm topology refresh [-t team]` — re-derives topology.json from
+/// what is actually running, after crashes or changes made outside `bm`.
+pub fn refresh(team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let topo_path = topology::topology_path(&cfg.workzone, &team.name);
+
+    let Some(mut topo) = topology::load(&topo_path)? else {
+        println!(
+            "No topology for team '{}'. It is written by `bm start`.",
+            team.name
+        );
+        return Ok(());
+    };
+
+    // Local members started since the topology was written
+    let mut running = Vec::new();
+    if topo.formation == "local" {
+        let team_prefix = format!("{}/", team.name);
+        for (key, rt) in &state::load()?.members {
+            if let Some(name) = key.strip_prefix(&team_prefix) {
+                if procutil::is_alive(rt.pid) {
+                    running.push((
+                        name.to_string(),
+                        Endpoint::Local {
+                            pid: rt.pid,
+                            workspace: rt.workspace.clone(),
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    let changes = topology::refresh(&mut topo, running, topology::probe);
+
+    println!("Team: {} (formation: {})", team.name, topo.formation);
+    let (mut updated, mut added, mut removed, mut unverified) = (0, 0, 0, 0);
+    for (name, change) in &changes {
+        let status = topo.members.get(name).map(|m| m.status.as_str());
+        match change {
+            Change::Unchanged => println!("  {}: {}", name, status.unwrap_or("")),
+            Change::Updated { from } => {
+                updated += 1;
+                println!("  {}: {} -> {}", name, from, status.unwrap_or(""));
+            }
+            Change::Added => {
+                added += 1;
+                println!("  {}: added ({})", name, status.unwrap_or(""));
+            }
+            Change::Removed => {
+                removed += 1;
+                println!("  {}: gone, removed", name);
+            }
+            Change::Unverified(reason) => {
+                unverified += 1;
+                println!(
+                    "  {}: {} (unverified: {})",
+                    name,
+                    status.unwrap_or(""),
+                    reason
+                );
+            }
+        }
+    }
+
+    if topo.members.is_empty() {
+        topology::remove(&topo_path)?;
+        println!("\nNo members left running; removed {}.", topo_path.display());
+    } else {
+        topology::save(&topo_path, &topo)?;
+        println!(
+            "\nRefreshed {} member(s): {} updated, {} added, {} removed, {} unverified.",
+            topo.members.len(),
+            updated,
+            added,
+            removed,
+            unverified
+        );
+    }
+
+    Ok(())
+}
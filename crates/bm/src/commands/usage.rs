@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use chrono::{Duration, NaiveDate, Utc};
+
+use crate::config;
+use crate::members;
+use crate::tables;
+use crate::usage::{self, Usage};
+
+/// Handles `bm usage [-t team] [--since <date>]`.
+/// Reads new member log output into the usage ledger, then prints the
+/// team's totals, a per-member breakdown, and a per-day breakdown.
+pub fn run(team_flag: Option<&str>, since: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let since = since
+        .map(|s| parse_since(s, Utc::now().date_naive()))
+        .transpose()?;
+
+    let members_dir = team.path.join("team").join("team");
+    let names = members::list_names(&members_dir).unwrap_or_default();
+    let ledger = usage::collect(&team.name, &names)?;
+
+    let empty = BTreeMap::new();
+    let days = ledger.teams.get(&team.name).unwrap_or(&empty);
+    let mut by_member: BTreeMap<&str, Usage> = BTreeMap::new();
+    let mut by_day: BTreeMap<NaiveDate, Usage> = BTreeMap::new();
+    for (member, member_days) in days {
+        for (day, usage) in member_days {
+            if since.is_some_and(|s| *day < s) {
+                continue;
+            }
+            by_member.entry(member).or_default().add(usage);
+            by_day.entry(*day).or_default().add(usage);
+        }
+    }
+
+    let period = match since {
+        Some(s) => format!("since {}", s),
+        None => "all time".to_string(),
+    };
+    if by_member.is_empty() {
+        println!("No usage recorded for team '{}' ({}).", team.name, period);
+        println!("Usage is read from member logs, which only daemon runs write.");
+        return Ok(());
+    }
+
+    let mut total = Usage::default();
+    by_member.values().for_each(|u| total.add(u));
+    println!(
+        "Team '{}', {}: {} session(s), {} tokens, {}",
+        team.name,
+        period,
+        total.sessions,
+        tokens(total.total_tokens()),
+        cost(total.cost_usd)
+    );
+    println!();
+
+    let mut table = tables::new(vec![
+        "Member",
+        "Role",
+        "Sessions",
+        "Input",
+        "Output",
+        "Cache write",
+        "Cache read",
+        "Cost",
+    ]);
+    for (member, u) in &by_member {
+        let role = if names.iter().any(|n| n == member) {
+            members::read_role(&members_dir, member)
+        } else {
+            "(dismissed)".to_string()
+        };
+        let mut row = vec![member.to_string(), role];
+        row.extend(columns(u));
+        table.add_row(row);
+    }
+    println!("{}", tables::render(&table));
+    println!();
+
+    let mut table = tables::new(vec![
+        "Day",
+        "Sessions",
+        "Input",
+        "Output",
+        "Cache write",
+        "Cache read",
+        "Cost",
+    ]);
+    for (day, u) in &by_day {
+        let mut row = vec![day.to_string()];
+        row.extend(columns(u));
+        table.add_row(row);
+    }
+    println!("{}", tables::render(&table));
+    Ok(())
+}
+
+/// The Sessions through Cost cells of a breakdown row.
+fn columns(u: &Usage) -> Vec<String> {
+    vec![
+        u.sessions.to_string(),
+        tokens(u.input_tokens),
+        tokens(u.output_tokens),
+        tokens(u.cache_write_tokens),
+        tokens(u.cache_read_tokens),
+        cost(u.cost_usd),
+    ]
+}
+
+/// `812`, `34.5k`, `1.2M`.
+fn tokens(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.1}k", n as f64 / 1_000.0)
+    } else {
+        n.to_string()
+    }
+}
+
+fn cost(usd: f64) -> String {
+    format!("${:.2}", usd)
+}
+
+/// Parses `--since`: a date (`2026-03-01`) or a number of days back
+/// (`7d`, counting today as one).
+pub fn parse_since(s: &str, today: NaiveDate) -> Result<NaiveDate> {
+    if let Some(days) = s.strip_suffix('d') {
+        match days.parse::<i64>() {
+            Ok(n) if n > 0 => return Ok(today - Duration::days(n - 1)),
+            _ => bail!(
+                "Invalid --since '{}': expected a positive number of days, like 7d",
+                s
+            ),
+        }
+    }
+    match NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        Ok(date) => Ok(date),
+        Err(_) => bail!(
+            "Invalid --since '{}': expected a date (2026-03-01) or days (7d)",
+            s
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_since_takes_dates_and_days() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        assert_eq!(
+            parse_since("2026-03-01", today).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()
+        );
+        assert_eq!(parse_since("1d", today).unwrap(), today);
+        assert_eq!(
+            parse_since("7d", today).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 3, 4).unwrap()
+        );
+        assert!(parse_since("0d", today).is_err());
+        assert!(parse_since("last week", today).is_err());
+    }
+
+    #[test]
+    fn token_counts_are_compact() {
+        assert_eq!(tokens(812), "812");
+        assert_eq!(tokens(34_500), "34.5k");
+        assert_eq!(tokens(1_260_000), "1.3M");
+        assert_eq!(cost(0.5), "$0.50");
+    }
+}
@@ -0,0 +1,254 @@
+use anyhow::{bail, Result};
+
+use crate::commands::daemon::{self, DEFAULT_WEBHOOK_PATH};
+use crate::config::{self, TeamEntry};
+use crate::daemon_settings;
+use crate::github_app::TeamTokenSource;
+use crate::github_hooks::{self, Delivery, Hook};
+use crate::tables;
+
+/// Deliveries inspected per hook by `bm webhooks status`.
+const RECENT_DELIVERIES: u32 = 20;
+
+/// Returns the team's webhook secret, or explains how to set one.
+pub(crate) fn require_secret(team: &TeamEntry, what: &str) -> Result<String> {
+    match team.credentials.webhook_secret {
+        Some(ref secret) if !secret.is_empty() => Ok(secret.clone()),
+        _ => bail!(
+            "{} needs a webhook secret so the endpoint only accepts GitHub's deliveries. \
+             Set teams[].credentials.webhook_secret for '{}' in ~/.botminter/config.yml.",
+            what,
+            team.name
+        ),
+    }
+}
+
+/// The team's repos, primary first.
+fn require_repos(team: &TeamEntry) -> Result<Vec<String>> {
+    if team.github_repo.is_empty() {
+        bail!("No GitHub repo configured for team '{}'", team.name);
+    }
+    Ok(team.repos())
+}
+
+/// Checks `url` and appends `default_path` when it has no path, since the
+/// daemon only accepts deliveries on its configured path.
+fn webhook_url(url: &str, default_path: &str) -> Result<String> {
+    let Some((_, rest)) = url
+        .split_once("://")
+        .filter(|(scheme, _)| matches!(*scheme, "http" | "https"))
+    else {
+        bail!("Invalid webhook URL '{}'. It must start with https:// (or http://).", url);
+    };
+    match rest.split_once('/') {
+        None if !rest.is_empty() => Ok(format!("{}{}", url, default_path)),
+        Some((host, "")) if !host.is_empty() => {
+            Ok(format!("{}{}", url.trim_end_matches('/'), default_path))
+        }
+        Some((host, _)) if !host.is_empty() => Ok(url.to_string()),
+        _ => bail!("Invalid webhook URL '{}': no host", url),
+    }
+}
+
+/// Handles `bm webhooks setup [-t team] --url <public-url>` — creates the
+/// webhook on each of the team's repos, or updates the one already pointing
+/// at `url`.
+pub fn setup(team_flag: Option<&str>, url: &str) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let repos = require_repos(team)?;
+    let secret = require_secret(team, "`bm webhooks setup`")?;
+
+    let path = daemon::read_daemon_config(&team.name)
+        .map(|c| c.listener.path)
+        .unwrap_or_else(|| DEFAULT_WEBHOOK_PATH.to_string());
+    let url = webhook_url(url, &path)?;
+    if url.starts_with("http://") {
+        eprintln!("Note: GitHub will deliver over plain HTTP. Prefer an https:// URL.");
+    }
+    let events = daemon_settings::load(&team.path.join("team"), &team.name)?.relevant_events();
+
+    let token = TeamTokenSource::default().token(team)?;
+    for repo in &repos {
+        let hooks = github_hooks::list(repo, token.as_deref())?;
+        match hooks.iter().find(|h| h.url() == url) {
+            Some(hook) => {
+                github_hooks::update(repo, token.as_deref(), hook.id, &url, &secret, &events)?;
+                println!("Updated webhook #{} on {}", hook.id, repo);
+            }
+            None => {
+                let id = github_hooks::create(repo, token.as_deref(), &url, &secret, &events)?;
+                println!("Created webhook #{} on {}", id, repo);
+            }
+        }
+    }
+    println!("  URL:    {}", url);
+    println!("  Events: {}", events.join(", "));
+    println!("Run `bm webhooks status` after the next event to check deliveries.");
+    Ok(())
+}
+
+/// Problems worth flagging for one hook.
+fn hook_warnings(hook: &Hook, deliveries: &[Delivery], wanted: &[String]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if !hook.active {
+        warnings.push("inactive; GitHub is not delivering to it".to_string());
+    }
+    if !hook.is_signed() {
+        warnings.push("no secret; the daemon can't verify its deliveries".to_string());
+    }
+    let missing: Vec<&str> = wanted
+        .iter()
+        .filter(|e| !hook.events.iter().any(|h| h == *e || h == "*"))
+        .map(|e| e.as_str())
+        .collect();
+    if !missing.is_empty() {
+        warnings.push(format!("not subscribed to {}", missing.join(", ")));
+    }
+    if let Some(last) = deliveries.first() {
+        if !last.succeeded() {
+            warnings.push(format!(
+                "last delivery ({}, {}) got HTTP {}",
+                last.event, last.delivered_at, last.status_code
+            ));
+        }
+    }
+    warnings
+}
+
+/// "18/20 ok" style summary of recent deliveries.
+fn delivery_summary(deliveries: &[Delivery]) -> String {
+    if deliveries.is_empty() {
+        return "none yet".to_string();
+    }
+    let ok = deliveries.iter().filter(|d| d.succeeded()).count();
+    format!("{}/{} ok", ok, deliveries.len())
+}
+
+fn last_response(hook: &Hook) -> String {
+    match hook.last_response {
+        Some(ref r) => match (r.code, r.status.as_deref()) {
+            (Some(code), _) => code.to_string(),
+            (None, Some(status)) => status.to_string(),
+            (None, None) => "—".to_string(),
+        },
+        None => "—".to_string(),
+    }
+}
+
+/// Handles `bm webhooks status [-t team]` — lists each team repo's webhooks
+/// with their recent delivery results.
+pub fn status(team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let repos = require_repos(team)?;
+    let wanted = daemon_settings::load(&team.path.join("team"), &team.name)?.relevant_events();
+
+    let token = TeamTokenSource::default().token(team)?;
+    let mut warnings = Vec::new();
+    for repo in &repos {
+        let hooks = github_hooks::list(repo, token.as_deref())?;
+        if hooks.is_empty() {
+            println!("No webhooks on {}.", repo);
+            warnings.push(format!(
+                "{} gets no deliveries. Run `bm webhooks setup -t {} --url <public-url>`.",
+                repo, team.name
+            ));
+            continue;
+        }
+        print_hooks(repo, &hooks, token.as_deref(), &wanted, &mut warnings);
+    }
+
+    for w in &warnings {
+        println!("Warning: {}", w);
+    }
+    if warnings.is_empty() {
+        println!("All webhooks healthy.");
+    }
+    Ok(())
+}
+
+/// Prints one repo's webhook table, collecting its warnings.
+fn print_hooks(
+    repo: &str,
+    hooks: &[Hook],
+    token: Option<&str>,
+    wanted: &[String],
+    warnings: &mut Vec<String>,
+) {
+
+    let mut table = tables::new(vec!["ID", "URL", "Active", "Events", "Last response", "Recent deliveries"]);
+    for hook in hooks {
+        let deliveries =
+            match github_hooks::deliveries(repo, token, hook.id, RECENT_DELIVERIES) {
+                Ok(d) => d,
+                Err(e) => {
+                    warnings.push(format!("#{}: {:#}", hook.id, e));
+                    Vec::new()
+                }
+            };
+        table.add_row(vec![
+            format!("#{}", hook.id),
+            hook.url().to_string(),
+            if hook.active { "yes" } else { "no" }.to_string(),
+            hook.events.join(", "),
+            last_response(hook),
+            delivery_summary(&deliveries),
+        ]);
+        for w in hook_warnings(hook, &deliveries, wanted) {
+            warnings.push(format!("#{}: {}", hook.id, w));
+        }
+    }
+
+    println!("Webhooks on {}:", repo);
+    println!("{}", tables::render(&table));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn webhook_url_appends_daemon_path_when_missing() {
+        assert_eq!(
+            webhook_url("https://bm.example.com", "/webhook").unwrap(),
+            "https://bm.example.com/webhook"
+        );
+        assert_eq!(
+            webhook_url("https://bm.example.com/", "/hooks/gh").unwrap(),
+            "https://bm.example.com/hooks/gh"
+        );
+        assert_eq!(
+            webhook_url("https://bm.example.com/custom", "/webhook").unwrap(),
+            "https://bm.example.com/custom"
+        );
+        for bad in ["bm.example.com", "ftp://x/y", "https://", "https:///webhook"] {
+            assert!(webhook_url(bad, "/webhook").is_err(), "{} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn hook_warnings_flag_unhealthy_hooks() {
+        let hook: Hook = serde_json::from_str(
+            r#"{"id":1,"active":false,"events":["issues"],"config":{"url":"https://h"}}"#,
+        )
+        .unwrap();
+        let deliveries: Vec<Delivery> = serde_json::from_str(
+            r#"[{"delivered_at":"2026-03-01T10:00:00Z","status_code":401,"event":"issues"},
+                {"delivered_at":"2026-03-01T09:00:00Z","status_code":200,"event":"issues"}]"#,
+        )
+        .unwrap();
+        let wanted = vec!["issues".to_string(), "pull_request".to_string()];
+        let warnings = hook_warnings(&hook, &deliveries, &wanted);
+        assert_eq!(warnings.len(), 4, "{:?}", warnings);
+        assert!(warnings[2].contains("not subscribed to pull_request"));
+        assert!(warnings[3].contains("HTTP 401"));
+        assert_eq!(delivery_summary(&deliveries), "1/2 ok");
+
+        let healthy: Hook = serde_json::from_str(
+            r#"{"id":2,"active":true,"events":["*"],"config":{"url":"https://h","secret":"********"}}"#,
+        )
+        .unwrap();
+        assert!(hook_warnings(&healthy, &deliveries[1..], &wanted).is_empty());
+    }
+}
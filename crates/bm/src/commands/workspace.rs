@@ -0,0 +1,112 @@
+use anyhow::{bail, Result};
+
+use crate::commands::start::{resolve_member_status, MemberStatus};
+use crate::config::{self, TeamEntry};
+use crate::disk_quota;
+use crate::snapshots;
+use crate::state;
+use crate::tables;
+use crate::timefmt;
+
+/// Handles `bm workspace snapshot <member> [--no-git-objects] [-t team]`.
+/// Archives the member's workspace under `~/.botminter/snapshots/`.
+pub fn snapshot(member: &str, git_objects: bool, team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    let member_dir = team.path.join(member);
+    if !member_dir.is_dir() {
+        bail!(
+            "Member '{}' has no workspace in team '{}'. Run `bm teams sync` to provision it.",
+            member,
+            team.name
+        );
+    }
+
+    let state = state::load().unwrap_or_default();
+    if matches!(
+        resolve_member_status(&state, &team.name, member),
+        MemberStatus::Running { .. }
+    ) {
+        eprintln!(
+            "Note: {} is running; files it writes meanwhile may be caught mid-change.",
+            member
+        );
+    }
+
+    let dir = snapshots::snapshots_dir(&team.name, member)?;
+    let snapshot = snapshots::create(&member_dir, &dir, git_objects)?;
+    println!(
+        "Saved snapshot {} of {} ({}{})",
+        snapshot.id,
+        member,
+        disk_quota::format_bytes(snapshot.bytes),
+        if git_objects { "" } else { ", without git objects" }
+    );
+    println!("Restore it with `bm workspace restore {} {}`", member, snapshot.id);
+    Ok(())
+}
+
+/// Handles `bm workspace snapshots <member> [-t team]`.
+pub fn list(member: &str, team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+
+    let snapshots = snapshots::list(&snapshots::snapshots_dir(&team.name, member)?)?;
+    if snapshots.is_empty() {
+        println!(
+            "No snapshots of {}. Take one with `bm workspace snapshot {}`.",
+            member, member
+        );
+        return Ok(());
+    }
+
+    let mut table = tables::new(vec!["Snapshot", "Taken", "Size", "Git objects"]);
+    for s in &snapshots {
+        table.add_row(vec![
+            s.id.clone(),
+            timefmt::timestamp(s.created_at),
+            disk_quota::format_bytes(s.bytes),
+            if s.git_objects { "yes" } else { "no" }.to_string(),
+        ]);
+    }
+    println!("{}", tables::render(&table));
+    Ok(())
+}
+
+/// Handles `bm workspace restore <member> <snapshot-id> [-t team]`.
+/// Replaces the member's workspace with the snapshot. Refuses while the
+/// member is running.
+pub fn restore(member: &str, id: &str, team_flag: Option<&str>) -> Result<()> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, team_flag)?;
+    ensure_stopped(team, member)?;
+
+    let dir = snapshots::snapshots_dir(&team.name, member)?;
+    let snapshot = snapshots::find(&dir, id)?;
+    let member_dir = team.path.join(member);
+    snapshots::restore(&dir, &snapshot, &member_dir)?;
+    println!(
+        "Restored {} to snapshot {} (taken {})",
+        member,
+        snapshot.id,
+        timefmt::timestamp(snapshot.created_at)
+    );
+    if !snapshot.git_objects {
+        println!("Git objects were kept from the workspace; run `git fsck` there if history looks off.");
+    }
+    Ok(())
+}
+
+/// Fails if `member` is running, as restoring under it would race its writes.
+fn ensure_stopped(team: &TeamEntry, member: &str) -> Result<()> {
+    let state = state::load().unwrap_or_default();
+    if let MemberStatus::Running { pid, .. } = resolve_member_status(&state, &team.name, member) {
+        bail!(
+            "Member '{}' is running (PID {}). Stop it first with `bm stop -t {}`.",
+            member,
+            pid,
+            team.name
+        );
+    }
+    Ok(())
+}
@@ -6,7 +6,12 @@ use clap_complete::ArgValueCandidates;
 
 use crate::cli::Cli;
 use crate::config::{self, BotminterConfig, TeamEntry};
+use crate::commands::{daemon, knowledge};
+use crate::daemon_settings;
+use crate::event_log;
 use crate::formation;
+use crate::members;
+use crate::parse;
 use crate::profile;
 
 /// Shared context for completion resolution.
@@ -59,7 +64,7 @@ impl CompletionContext {
     pub fn member_names(&self) -> Vec<String> {
         self.team_repo
             .as_ref()
-            .and_then(|repo| list_member_dirs(repo).ok())
+            .and_then(|repo| members::list_names(&repo.join("team")).ok())
             .unwrap_or_default()
     }
 
@@ -71,11 +76,58 @@ impl CompletionContext {
             .unwrap_or_default()
     }
 
-    /// Formation names from the default team's repo.
-    pub fn formation_names(&self) -> Vec<String> {
+    /// Repos added to the default team with `bm teams add-repo`.
+    pub fn extra_repo_names(&self) -> Vec<String> {
+        self.team
+            .as_ref()
+            .map(|t| t.extra_repos.clone())
+            .unwrap_or_default()
+    }
+
+    /// Board view names from the default team's manifest.
+    pub fn view_names(&self) -> Vec<String> {
         self.team_repo
+            .as_ref()
+            .and_then(|repo| list_view_names(repo).ok())
+            .unwrap_or_default()
+    }
+
+    /// Formation names from the default team's repo, plus those its profile
+    /// ships (which `bm start` explains how to add).
+    pub fn formation_names(&self) -> Vec<String> {
+        let mut names = self
+            .team_repo
             .as_ref()
             .and_then(|repo| formation::list_formations(repo).ok())
+            .unwrap_or_default();
+        if let Some(ref team) = self.team {
+            names.extend(profile::list_formations(&team.profile));
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Knowledge and invariant file paths in the default team's repo, as
+    /// `bm knowledge show` and `edit` take them.
+    pub fn knowledge_paths(&self) -> Vec<String> {
+        self.team_repo
+            .as_ref()
+            .map(|repo| {
+                knowledge::scoped_files(repo, None)
+                    .into_iter()
+                    .map(|(_, path)| path)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Recorded webhook delivery IDs of the default team, newest first.
+    pub fn event_ids(&self) -> Vec<String> {
+        self.team
+            .as_ref()
+            .and_then(|t| daemon::events_dir(&t.name).ok())
+            .map(|dir| event_log::load(&dir).into_iter().rev().map(|e| e.id).collect())
             .unwrap_or_default()
     }
 }
@@ -94,19 +146,31 @@ pub fn build_cli_with_completions() -> clap::Command {
     let members = ctx.member_names();
     let projects = ctx.project_names();
     let formations = ctx.formation_names();
+    let extra_repos = ctx.extra_repo_names();
+    let views = ctx.view_names();
+    let event_ids = ctx.event_ids();
+    let knowledge_paths = ctx.knowledge_paths();
 
     let daemon_modes: Vec<String> = vec!["webhook".into(), "poll".into()];
+    let tunnel_providers: Vec<String> = vec!["ngrok".into(), "cloudflared".into()];
+    let daemon_config_keys: Vec<String> = daemon_settings::CONFIG_KEYS
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
     let knowledge_scopes: Vec<String> = vec![
         "team".into(),
         "project".into(),
         "member".into(),
         "member-project".into(),
     ];
+    let status_groups: Vec<String> = vec!["role".into(), "project".into()];
+    let status_sorts: Vec<String> =
+        vec!["name".into(), "role".into(), "status".into(), "uptime".into()];
 
     Cli::command()
         // ── hire ──────────────────────────────────────────────
         .mut_subcommand("hire", |c| {
-            c.mut_arg("role", |a| a.add(make(roles)))
+            c.mut_arg("role", |a| a.add(make(roles.clone())))
                 .mut_arg("team", |a| a.add(make(teams.clone())))
         })
         // ── start ─────────────────────────────────────────────
@@ -118,9 +182,42 @@ pub fn build_cli_with_completions() -> clap::Command {
         .mut_subcommand("stop", |c| {
             c.mut_arg("team", |a| a.add(make(teams.clone())))
         })
+        // ── pause / resume ────────────────────────────────────
+        .mut_subcommand("pause", |c| {
+            c.mut_arg("team", |a| a.add(make(teams.clone())))
+        })
+        .mut_subcommand("resume", |c| {
+            c.mut_arg("team", |a| a.add(make(teams.clone())))
+        })
         // ── status ────────────────────────────────────────────
         .mut_subcommand("status", |c| {
             c.mut_arg("team", |a| a.add(make(teams.clone())))
+                .mut_arg("group_by", |a| a.add(make(status_groups)))
+                .mut_arg("sort", |a| a.add(make(status_sorts)))
+        })
+        // ── dashboard ─────────────────────────────────────────
+        .mut_subcommand("dashboard", |c| {
+            c.mut_arg("team", |a| a.add(make(teams.clone())))
+        })
+        // ── exec ──────────────────────────────────────────────
+        .mut_subcommand("exec", |c| {
+            c.mut_arg("member", |a| a.add(make(members.clone())))
+                .mut_arg("team", |a| a.add(make(teams.clone())))
+        })
+        // ── workspace ─────────────────────────────────────────
+        .mut_subcommand("workspace", |c| {
+            c.mut_subcommand("snapshot", |s| {
+                s.mut_arg("member", |a| a.add(make(members.clone())))
+                    .mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+            .mut_subcommand("snapshots", |s| {
+                s.mut_arg("member", |a| a.add(make(members.clone())))
+                    .mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+            .mut_subcommand("restore", |s| {
+                s.mut_arg("member", |a| a.add(make(members.clone())))
+                    .mut_arg("team", |a| a.add(make(teams.clone())))
+            })
         })
         // ── teams ─────────────────────────────────────────────
         .mut_subcommand("teams", |c| {
@@ -128,9 +225,30 @@ pub fn build_cli_with_completions() -> clap::Command {
                 s.mut_arg("name", |a| a.add(make(teams.clone())))
                     .mut_arg("team", |a| a.add(make(teams.clone())))
             })
+            .mut_subcommand("pull", |s| {
+                s.mut_arg("team", |a| a.add(make(teams.clone())))
+            })
             .mut_subcommand("sync", |s| {
+                s.mut_arg("member", |a| a.add(make(members.clone())))
+                    .mut_arg("project", |a| a.add(make(projects.clone())))
+                    .mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+            .mut_subcommand("add-repo", |s| {
                 s.mut_arg("team", |a| a.add(make(teams.clone())))
             })
+            .mut_subcommand("remove-repo", |s| {
+                s.mut_arg("repo", |a| a.add(make(extra_repos)))
+                    .mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+            .mut_subcommand("remove", |s| {
+                s.mut_arg("name", |a| a.add(make(teams.clone())))
+            })
+            .mut_subcommand("set-default", |s| {
+                s.mut_arg("name", |a| a.add(make(teams.clone())))
+            })
+            .mut_subcommand("destroy", |s| {
+                s.mut_arg("name", |a| a.add(make(teams.clone())))
+            })
         })
         // ── members ───────────────────────────────────────────
         .mut_subcommand("members", |c| {
@@ -138,7 +256,7 @@ pub fn build_cli_with_completions() -> clap::Command {
                 s.mut_arg("team", |a| a.add(make(teams.clone())))
             })
             .mut_subcommand("show", |s| {
-                s.mut_arg("member", |a| a.add(make(members)))
+                s.mut_arg("member", |a| a.add(make(members.clone())))
                     .mut_arg("team", |a| a.add(make(teams.clone())))
             })
         })
@@ -147,10 +265,17 @@ pub fn build_cli_with_completions() -> clap::Command {
             c.mut_subcommand("list", |s| {
                 s.mut_arg("team", |a| a.add(make(teams.clone())))
             })
+            .mut_subcommand("describe", |s| {
+                s.mut_arg("role", |a| a.add(make(roles)))
+                    .mut_arg("team", |a| a.add(make(teams.clone())))
+            })
         })
         // ── profiles ──────────────────────────────────────────
         .mut_subcommand("profiles", |c| {
             c.mut_subcommand("describe", |s| {
+                s.mut_arg("profile", |a| a.add(make(profiles.clone())))
+            })
+            .mut_subcommand("export", |s| {
                 s.mut_arg("profile", |a| a.add(make(profiles)))
             })
         })
@@ -160,7 +285,7 @@ pub fn build_cli_with_completions() -> clap::Command {
                 s.mut_arg("team", |a| a.add(make(teams.clone())))
             })
             .mut_subcommand("show", |s| {
-                s.mut_arg("project", |a| a.add(make(projects)))
+                s.mut_arg("project", |a| a.add(make(projects.clone())))
                     .mut_arg("team", |a| a.add(make(teams.clone())))
             })
             .mut_subcommand("add", |s| {
@@ -169,6 +294,31 @@ pub fn build_cli_with_completions() -> clap::Command {
             .mut_subcommand("sync", |s| {
                 s.mut_arg("team", |a| a.add(make(teams.clone())))
             })
+            .mut_subcommand("seed", |s| {
+                s.mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+        })
+        // ── board ─────────────────────────────────────────────
+        .mut_subcommand("board", |c| {
+            c.mut_subcommand("view", |s| {
+                s.mut_arg("view", |a| a.add(make(views)))
+                    .mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+        })
+        // ── state ─────────────────────────────────────────────
+        .mut_subcommand("state", |c| {
+            c.mut_subcommand("history", |s| {
+                s.mut_arg("member", |a| a.add(make(members.clone())))
+                    .mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+        })
+        // ── usage ─────────────────────────────────────────────
+        .mut_subcommand("usage", |c| {
+            c.mut_arg("team", |a| a.add(make(teams.clone())))
+        })
+        // ── audit ─────────────────────────────────────────────
+        .mut_subcommand("audit", |c| {
+            c.mut_arg("team", |a| a.add(make(teams.clone())))
         })
         // ── knowledge ─────────────────────────────────────────
         .mut_subcommand("knowledge", |c| {
@@ -176,17 +326,38 @@ pub fn build_cli_with_completions() -> clap::Command {
                 .mut_arg("scope", |a| a.add(make(knowledge_scopes.clone())))
                 .mut_subcommand("list", |s| {
                     s.mut_arg("team", |a| a.add(make(teams.clone())))
-                        .mut_arg("scope", |a| a.add(make(knowledge_scopes)))
+                        .mut_arg("scope", |a| a.add(make(knowledge_scopes.clone())))
                 })
                 .mut_subcommand("show", |s| {
+                    s.mut_arg("path", |a| a.add(make(knowledge_paths.clone())))
+                        .mut_arg("team", |a| a.add(make(teams.clone())))
+                })
+                .mut_subcommand("add", |s| {
+                    s.mut_arg("scope", |a| a.add(make(knowledge_scopes.clone())))
+                        .mut_arg("project", |a| a.add(make(projects)))
+                        .mut_arg("member", |a| a.add(make(members.clone())))
+                        .mut_arg("team", |a| a.add(make(teams.clone())))
+                })
+                .mut_subcommand("edit", |s| {
+                    s.mut_arg("path", |a| a.add(make(knowledge_paths)))
+                        .mut_arg("team", |a| a.add(make(teams.clone())))
+                })
+                .mut_subcommand("grep", |s| {
                     s.mut_arg("team", |a| a.add(make(teams.clone())))
+                        .mut_arg("scope", |a| a.add(make(knowledge_scopes)))
                 })
         })
         // ── daemon ────────────────────────────────────────────
         .mut_subcommand("daemon", |c| {
             c.mut_subcommand("start", |s| {
+                s.mut_arg("team", |a| a.add(make(teams.clone())))
+                    .mut_arg("mode", |a| a.add(make(daemon_modes.clone())))
+                    .mut_arg("tunnel", |a| a.add(make(tunnel_providers.clone())))
+            })
+            .mut_subcommand("restart", |s| {
                 s.mut_arg("team", |a| a.add(make(teams.clone())))
                     .mut_arg("mode", |a| a.add(make(daemon_modes)))
+                    .mut_arg("tunnel", |a| a.add(make(tunnel_providers)))
             })
             .mut_subcommand("stop", |s| {
                 s.mut_arg("team", |a| a.add(make(teams.clone())))
@@ -194,6 +365,70 @@ pub fn build_cli_with_completions() -> clap::Command {
             .mut_subcommand("status", |s| {
                 s.mut_arg("team", |a| a.add(make(teams.clone())))
             })
+            .mut_subcommand("history", |s| {
+                s.mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+            .mut_subcommand("simulate", |s| {
+                s.mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+            .mut_subcommand("config", |c| {
+                c.mut_subcommand("get", |s| {
+                    s.mut_arg("team", |a| a.add(make(teams.clone())))
+                        .mut_arg("key", |a| a.add(make(daemon_config_keys.clone())))
+                })
+                .mut_subcommand("set", |s| {
+                    s.mut_arg("team", |a| a.add(make(teams.clone())))
+                        .mut_arg("key", |a| a.add(make(daemon_config_keys.clone())))
+                })
+                .mut_subcommand("unset", |s| {
+                    s.mut_arg("team", |a| a.add(make(teams.clone())))
+                        .mut_arg("key", |a| a.add(make(daemon_config_keys)))
+                })
+            })
+            .mut_subcommand("events", |c| {
+                c.mut_subcommand("list", |s| {
+                    s.mut_arg("team", |a| a.add(make(teams.clone())))
+                })
+                .mut_subcommand("show", |s| {
+                    s.mut_arg("team", |a| a.add(make(teams.clone())))
+                        .mut_arg("id", |a| a.add(make(event_ids.clone())))
+                })
+                .mut_subcommand("replay", |s| {
+                    s.mut_arg("team", |a| a.add(make(teams.clone())))
+                        .mut_arg("id", |a| a.add(make(event_ids)))
+                })
+            })
+        })
+        // ── chatops ───────────────────────────────────────────
+        .mut_subcommand("chatops", |c| {
+            c.mut_arg("team", |a| a.add(make(teams.clone())))
+        })
+        // ── webhooks ──────────────────────────────────────────
+        .mut_subcommand("webhooks", |c| {
+            c.mut_subcommand("setup", |s| {
+                s.mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+            .mut_subcommand("status", |s| {
+                s.mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+        })
+        // ── notify ────────────────────────────────────────────
+        .mut_subcommand("notify", |c| {
+            c.mut_subcommand("test", |s| {
+                s.mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+        })
+        // ── topology ──────────────────────────────────────────
+        .mut_subcommand("topology", |c| {
+            c.mut_subcommand("refresh", |s| {
+                s.mut_arg("team", |a| a.add(make(teams.clone())))
+            })
+        })
+        // ── diag ──────────────────────────────────────────────
+        .mut_subcommand("diag", |c| {
+            c.mut_subcommand("bundle", |s| {
+                s.mut_arg("team", |a| a.add(make(teams)))
+            })
         })
 }
 
@@ -210,35 +445,28 @@ fn make(values: Vec<String>) -> ArgValueCandidates {
     })
 }
 
-/// List member directories under `team_repo/team/`.
-fn list_member_dirs(team_repo: &Path) -> anyhow::Result<Vec<String>> {
-    let members_dir = team_repo.join("team");
-    if !members_dir.is_dir() {
-        return Ok(Vec::new());
-    }
-    let mut names = Vec::new();
-    for entry in std::fs::read_dir(&members_dir)? {
-        let entry = entry?;
-        if entry.file_type()?.is_dir() {
-            let name = entry.file_name().to_string_lossy().to_string();
-            if !name.starts_with('.') {
-                names.push(name);
-            }
-        }
+/// Read the team repo's botminter.yml manifest, if it has one.
+fn read_manifest(team_repo: &Path) -> anyhow::Result<Option<profile::ProfileManifest>> {
+    let manifest_path = team_repo.join("botminter.yml");
+    if !manifest_path.exists() {
+        return Ok(None);
     }
-    names.sort();
-    Ok(names)
+    let contents = std::fs::read_to_string(&manifest_path)?;
+    Ok(Some(parse::yaml(&manifest_path, &contents)?))
 }
 
 /// List project names from the team repo's botminter.yml manifest.
 fn list_project_names(team_repo: &Path) -> anyhow::Result<Vec<String>> {
-    let manifest_path = team_repo.join("botminter.yml");
-    if !manifest_path.exists() {
-        return Ok(Vec::new());
-    }
-    let contents = std::fs::read_to_string(&manifest_path)?;
-    let manifest: profile::ProfileManifest = serde_yml::from_str(&contents)?;
-    Ok(manifest.projects.iter().map(|p| p.name.clone()).collect())
+    Ok(read_manifest(team_repo)?
+        .map(|m| m.projects.iter().map(|p| p.name.clone()).collect())
+        .unwrap_or_default())
+}
+
+/// List board view names from the team repo's botminter.yml manifest.
+fn list_view_names(team_repo: &Path) -> anyhow::Result<Vec<String>> {
+    Ok(read_manifest(team_repo)?
+        .map(|m| m.views.iter().map(|v| v.name.clone()).collect())
+        .unwrap_or_default())
 }
 
 #[cfg(test)]
@@ -264,6 +492,7 @@ mod tests {
         assert!(ctx.role_names().is_empty());
         assert!(ctx.member_names().is_empty());
         assert!(ctx.project_names().is_empty());
+        assert!(ctx.knowledge_paths().is_empty());
         assert!(ctx.formation_names().is_empty());
     }
 
@@ -281,14 +510,22 @@ mod tests {
     fn context_with_config_returns_team_names() {
         let ctx = CompletionContext {
             config: Some(BotminterConfig {
+                version: config::CONFIG_VERSION,
                 workzone: PathBuf::from("/tmp"),
                 default_team: None,
+                token_expiry_warn_days: None,
+                logs: None,
+                timestamps: None,
+                workspace: None,
+                state_backend: None,
                 teams: vec![
                     TeamEntry {
                         name: "alpha".into(),
                         path: PathBuf::from("/tmp/alpha"),
                         profile: "scrum".into(),
                         github_repo: String::new(),
+                        extra_repos: Vec::new(),
+                        budget: None,
                         credentials: Credentials::default(),
                     },
                     TeamEntry {
@@ -296,6 +533,8 @@ mod tests {
                         path: PathBuf::from("/tmp/beta"),
                         profile: "scrum-compact".into(),
                         github_repo: String::new(),
+                        extra_repos: Vec::new(),
+                        budget: None,
                         credentials: Credentials::default(),
                     },
                 ],
@@ -316,6 +555,8 @@ mod tests {
                 path: PathBuf::from("/tmp/test"),
                 profile: "scrum".into(),
                 github_repo: String::new(),
+                extra_repos: Vec::new(),
+                budget: None,
                 credentials: Credentials::default(),
             }),
             team_repo: None,
@@ -384,14 +625,37 @@ projects:
         assert_eq!(formations, vec!["k8s", "local"]);
     }
 
+    #[test]
+    fn formation_names_include_profile_formations() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("formations/local")).unwrap();
+        std::fs::create_dir_all(tmp.path().join("formations/staging")).unwrap();
+
+        let ctx = CompletionContext {
+            config: None,
+            team: Some(TeamEntry {
+                name: "t".to_string(),
+                path: PathBuf::from("/unused"),
+                profile: "scrum".to_string(),
+                github_repo: String::new(),
+                extra_repos: Vec::new(),
+                budget: None,
+                credentials: Credentials::default(),
+            }),
+            team_repo: Some(tmp.path().to_path_buf()),
+        };
+        assert_eq!(ctx.formation_names(), vec!["k8s", "local", "staging"]);
+    }
+
     /// Guard test: verifies that `build_cli_with_completions` covers every
     /// subcommand in the Command enum. This uses an exhaustive match so adding
     /// a new variant without updating completions causes a compile error.
     #[test]
     fn all_commands_covered_by_completions() {
         use crate::cli::{
-            Command, DaemonCommand, KnowledgeCommand, MembersCommand, ProfilesCommand,
-            ProjectsCommand, RolesCommand, TeamsCommand,
+            BoardCommand, Command, CompletionsCommand, ConfigCommand, DaemonCommand, DaemonConfigCommand, DaemonEventsCommand,
+            DiagCommand, KnowledgeCommand, MembersCommand, NotifyCommand, ProfilesCommand, ProjectsCommand, RolesCommand,
+            StateCommand, TeamsCommand, TopologyCommand, WebhooksCommand, WorkspaceCommand,
         };
 
         // This exhaustive match ensures that if a new Command variant is
@@ -399,15 +663,32 @@ projects:
         // it only verifies the variant exists and can be named.
         fn _assert_variant_exists(cmd: &Command) {
             match cmd {
-                Command::Init => {}
+                Command::Init { .. } => {}
+                Command::Quickstart { .. } => {}
                 Command::Hire { .. } => {}
                 Command::Start { .. } => {}
                 Command::Stop { .. } => {}
+                Command::Pause { .. } => {}
+                Command::Resume { .. } => {}
                 Command::Status { .. } => {}
+                Command::Dashboard { .. } => {}
+                Command::Exec { .. } => {}
+                Command::Workspace { command } => match command {
+                    WorkspaceCommand::Snapshot { .. } => {}
+                    WorkspaceCommand::Snapshots { .. } => {}
+                    WorkspaceCommand::Restore { .. } => {}
+                },
                 Command::Teams { command } => match command {
                     TeamsCommand::List => {}
                     TeamsCommand::Show { .. } => {}
+                    TeamsCommand::Pull { .. } => {}
                     TeamsCommand::Sync { .. } => {}
+                    TeamsCommand::Verify { .. } => {}
+                    TeamsCommand::AddRepo { .. } => {}
+                    TeamsCommand::RemoveRepo { .. } => {}
+                    TeamsCommand::Remove { .. } => {}
+                    TeamsCommand::SetDefault { .. } => {}
+                    TeamsCommand::Destroy { .. } => {}
                 },
                 Command::Members { command } => match command {
                     MembersCommand::List { .. } => {}
@@ -415,29 +696,81 @@ projects:
                 },
                 Command::Roles { command } => match command {
                     RolesCommand::List { .. } => {}
+                    RolesCommand::Describe { .. } => {}
                 },
                 Command::Profiles { command } => match command {
                     ProfilesCommand::List => {}
                     ProfilesCommand::Describe { .. } => {}
+                    ProfilesCommand::Validate { .. } => {}
+                    ProfilesCommand::Export { .. } => {}
+                    ProfilesCommand::Lint { .. } => {}
                 },
                 Command::Projects { command } => match command {
                     ProjectsCommand::List { .. } => {}
                     ProjectsCommand::Show { .. } => {}
                     ProjectsCommand::Add { .. } => {}
                     ProjectsCommand::Sync { .. } => {}
+                    ProjectsCommand::Seed { .. } => {}
+                },
+                Command::Board { command } => match command {
+                    BoardCommand::View { .. } => {}
+                },
+                Command::Config { command } => match command {
+                    ConfigCommand::Get { .. } => {}
+                    ConfigCommand::Set { .. } => {}
+                    ConfigCommand::Unset { .. } => {}
+                },
+                Command::State { command } => match command {
+                    StateCommand::History { .. } => {}
                 },
+                Command::Usage { .. } => {}
+                Command::Audit { .. } => {}
                 Command::Knowledge { command, .. } => match command {
                     Some(KnowledgeCommand::List { .. }) => {}
                     Some(KnowledgeCommand::Show { .. }) => {}
+                    Some(KnowledgeCommand::Add { .. }) => {}
+                    Some(KnowledgeCommand::Edit { .. }) => {}
+                    Some(KnowledgeCommand::Grep { .. }) => {}
                     None => {}
                 },
                 Command::Daemon { command } => match command {
                     DaemonCommand::Start { .. } => {}
+                    DaemonCommand::Restart { .. } => {}
                     DaemonCommand::Stop { .. } => {}
                     DaemonCommand::Status { .. } => {}
+                    DaemonCommand::History { .. } => {}
+                    DaemonCommand::Simulate { .. } => {}
+                    DaemonCommand::Config { command } => match command {
+                        DaemonConfigCommand::Get { .. } => {}
+                        DaemonConfigCommand::Set { .. } => {}
+                        DaemonConfigCommand::Unset { .. } => {}
+                    },
+                    DaemonCommand::Events { command } => match command {
+                        DaemonEventsCommand::List { .. } => {}
+                        DaemonEventsCommand::Show { .. } => {}
+                        DaemonEventsCommand::Replay { .. } => {}
+                    },
+                },
+                Command::Chatops { .. } => {}
+                Command::Webhooks { command } => match command {
+                    WebhooksCommand::Setup { .. } => {}
+                    WebhooksCommand::Status { .. } => {}
+                },
+                Command::Notify { command } => match command {
+                    NotifyCommand::Test { .. } => {}
+                },
+                Command::Topology { command } => match command {
+                    TopologyCommand::Refresh { .. } => {}
+                },
+                Command::Diag { command } => match command {
+                    DiagCommand::Bundle { .. } => {}
                 },
                 Command::DaemonRun { .. } => {}
-                Command::Completions { .. } => {}
+                Command::TokenRefresh { .. } => {}
+                Command::Completions { command, .. } => match command {
+                    Some(CompletionsCommand::Install { .. }) => {}
+                    None => {}
+                },
             }
         }
 
@@ -453,5 +786,12 @@ projects:
         assert!(cmd.find_subcommand("daemon").is_some());
         assert!(cmd.find_subcommand("knowledge").is_some());
         assert!(cmd.find_subcommand("teams").is_some());
+        assert!(cmd.find_subcommand("chatops").is_some());
+        assert!(cmd.find_subcommand("webhooks").is_some());
+        assert!(cmd.find_subcommand("notify").is_some());
+        assert!(cmd.find_subcommand("topology").is_some());
+        assert!(cmd.find_subcommand("diag").is_some());
+        assert!(cmd.find_subcommand("workspace").is_some());
+        assert!(cmd.find_subcommand("audit").is_some());
     }
 }
@@ -1,43 +1,198 @@
 use std::fs;
-use std::os::unix::fs::PermissionsExt;
+use std::io::Write as _;
 use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::budget::Budget;
+use crate::filelock::FileLock;
+use crate::logging;
+use crate::notify;
+use crate::parse;
+use crate::store;
+use crate::timefmt;
+use crate::workspace;
 
 const CONFIG_DIR: &str = ".botminter";
 const CONFIG_FILE: &str = "config.yml";
 const CONFIG_PERMISSIONS: u32 = 0o600;
 
+/// Schema version of config.yml written by this build. Bump it, and add a
+/// step to [`MIGRATIONS`], whenever a field changes shape.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// One schema migration, rewriting the parsed YAML in place.
+type Migration = fn(&mut serde_yml::Mapping) -> Result<()>;
+
+/// `MIGRATIONS[n]` turns a version `n` config into version `n + 1`.
+const MIGRATIONS: [Migration; CONFIG_VERSION as usize] = [migrate_v0_to_v1];
+
 /// Top-level botminter configuration stored at ~/.botminter/config.yml.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct BotminterConfig {
+    /// Schema version; configs written before versioning have none (0).
+    #[serde(default)]
+    pub version: u32,
     pub workzone: PathBuf,
     pub default_team: Option<String>,
+    /// Days before GitHub token expiry to start warning (default: 14).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_expiry_warn_days: Option<u32>,
+    /// Rotation and retention of the daemon and member logs (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logs: Option<logging::Retention>,
+    /// Time zone for timestamps in command output: `utc` (default) or `local`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<timefmt::Zone>,
+    /// How workspaces surface team repo files (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<workspace::Settings>,
+    /// Where runtime state, poll state, and run history are kept: `file`
+    /// (default) or `sqlite`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_backend: Option<store::Backend>,
     #[serde(default)]
     pub teams: Vec<TeamEntry>,
 }
 
 /// A registered team.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct TeamEntry {
     pub name: String,
     pub path: PathBuf,
     pub profile: String,
     pub github_repo: String,
+    /// More repos (`owner/name`) the team works in, beyond `github_repo`. The
+    /// daemon watches them too, and they get the team's labels.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_repos: Vec<String>,
+    /// Spending caps; launches stop once one is reached (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Budget>,
     pub credentials: Credentials,
 }
 
+impl TeamEntry {
+    /// Every repo the team works in: `github_repo` first, then `extra_repos`.
+    pub fn repos(&self) -> Vec<String> {
+        let mut repos: Vec<String> = Vec::new();
+        for repo in std::iter::once(&self.github_repo).chain(&self.extra_repos) {
+            if !repo.is_empty() && !repos.contains(repo) {
+                repos.push(repo.clone());
+            }
+        }
+        repos
+    }
+}
+
 /// Stored credentials for a team (tokens).
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
 pub struct Credentials {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gh_token: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub telegram_bot_token: Option<String>,
+    /// Telegram chat members notify and ask for approval (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telegram_chat_id: Option<i64>,
+    /// Slack bot token (`xoxb-…`), for teams on Slack instead of Telegram
+    /// (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slack_bot_token: Option<String>,
+    /// Slack channel id members notify and ask for approval in (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slack_channel: Option<String>,
     /// Webhook secret for daemon webhook verification (optional).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub webhook_secret: Option<String>,
+    /// GitHub App used to mint scoped per-member tokens (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_app: Option<GitHubApp>,
+    /// Mail server for crash, error, and digest notifications (optional).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smtp: Option<Smtp>,
+    /// More places notifications go: Telegram, Slack, HTTP, desktop.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notifiers: Vec<notify::Notifier>,
+}
+
+/// GitHub App installation credentials.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct GitHubApp {
+    pub app_id: String,
+    pub installation_id: u64,
+    /// Path to the App's PEM private key.
+    pub private_key_path: PathBuf,
+}
+
+/// SMTP server and recipients for a team's notification emails.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Smtp {
+    /// Host name of the mail server.
+    pub server: String,
+    /// Defaults to 587 with STARTTLS, 465 with TLS, and 25 without.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Sender address.
+    pub from: String,
+    /// Recipient addresses.
+    pub to: Vec<String>,
+    /// Notifications to mail; when empty, all but `run_failed`,
+    /// `daemon_start`, and `daemon_stop`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notify_on: Vec<notify::Kind>,
+    /// Local time (`HH:MM`) the daily digest goes out (default: 08:00).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest_at: Option<String>,
+}
+
+/// How the connection to the mail server is secured.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpTls {
+    /// Upgrade a plain connection with STARTTLS, failing if the server can't.
+    #[default]
+    Starttls,
+    /// Connect over TLS from the start (SMTPS).
+    Tls,
+    /// No encryption, e.g. for a relay on localhost.
+    None,
+}
+
+impl Smtp {
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(match self.tls {
+            SmtpTls::Starttls => 587,
+            SmtpTls::Tls => 465,
+            SmtpTls::None => 25,
+        })
+    }
+
+    /// Whether notifications of `kind` should be mailed.
+    pub fn wants(&self, kind: notify::Kind) -> bool {
+        notify::routes(&self.notify_on, kind)
+    }
+
+    /// When the daily digest is due, local time.
+    pub fn digest_time(&self) -> Result<chrono::NaiveTime> {
+        let at = self.digest_at.as_deref().unwrap_or("08:00");
+        chrono::NaiveTime::parse_from_str(at, "%H:%M").with_context(|| {
+            format!("Invalid smtp.digest_at '{}'. Use 24-hour HH:MM, e.g. 08:00.", at)
+        })
+    }
 }
 
 /// Returns the path to the config directory (~/.botminter/).
@@ -56,22 +211,151 @@ pub fn load() -> Result<BotminterConfig> {
     load_from(&config_path()?)
 }
 
-/// Loads the config from a specific path.
+/// Loads the config from a specific path. A config written by an older bm
+/// is migrated to [`CONFIG_VERSION`] and saved back, keeping the original
+/// as `config.yml.v<N>.bak`.
 pub fn load_from(path: &Path) -> Result<BotminterConfig> {
     if !path.exists() {
         bail!("No teams configured. Run `bm init` first.");
     }
+    debug!("Loading config from {}", path.display());
 
     // Check file permissions and warn if not 0600
     check_permissions(path);
 
+    let (config, from) = read(path)?;
+    timefmt::set_zone(config.timestamps.unwrap_or_default());
+    if from == CONFIG_VERSION {
+        return Ok(config);
+    }
+
+    // Re-read under the lock in case another bm migrated it meanwhile
+    let _lock = FileLock::acquire(path)?;
+    let (config, from) = read(path)?;
+    if from < CONFIG_VERSION {
+        if let Err(e) = save_migrated(path, from, &config) {
+            eprintln!(
+                "Warning: could not save the migrated config to {}: {:#}",
+                path.display(),
+                e
+            );
+        }
+    }
+    Ok(config)
+}
+
+/// Parses and validates config.yml, migrating older schemas in memory.
+/// Returns the config and the version it was written at.
+fn read(path: &Path) -> Result<(BotminterConfig, u32)> {
     let contents =
         fs::read_to_string(path).context("Failed to read config file")?;
 
-    let config: BotminterConfig =
-        serde_yml::from_str(&contents).context("Failed to parse config file")?;
+    // On broken YAML the typed parse points at the problem more precisely
+    let Ok(raw) = serde_yml::from_str::<serde_yml::Value>(&contents) else {
+        let config: BotminterConfig = parse::yaml(path, &contents)?;
+        return validated(path, config, CONFIG_VERSION);
+    };
+    let from = match raw.get("version") {
+        None => 0,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .with_context(|| format!("Invalid version in {}: expected a number", path.display()))?,
+    };
+    if from > CONFIG_VERSION {
+        bail!(
+            "{} is config version {}, but this bm only understands up to version {}. \
+             Upgrade bm, or restore the backup the newer bm left next to it.",
+            path.display(),
+            from,
+            CONFIG_VERSION
+        );
+    }
+
+    let config: BotminterConfig = if from == CONFIG_VERSION {
+        parse::yaml(path, &contents)?
+    } else {
+        let migrated = migrate(raw, from)
+            .with_context(|| format!("Failed to migrate {} from version {}", path.display(), from))?;
+        let migrated = serde_yml::to_string(&migrated).context("Failed to serialize config")?;
+        match parse::yaml(path, &migrated) {
+            Ok(config) => config,
+            // Point at the user's file, not the rewritten one, when the problem was already there
+            Err(e) => match parse::yaml::<BotminterConfig>(path, &contents) {
+                Err(original) => return Err(original.into()),
+                Ok(_) => {
+                    return Err(anyhow::Error::new(e)
+                        .context(format!("After migrating from config version {}", from)))
+                }
+            },
+        }
+    };
+    validated(path, config, from)
+}
 
-    Ok(config)
+/// Checks settings serde can't, passing the config and its version through.
+fn validated(path: &Path, config: BotminterConfig, from: u32) -> Result<(BotminterConfig, u32)> {
+    if let Some(ref logs) = config.logs {
+        logs.validate()
+            .with_context(|| format!("Invalid logs settings in {}", path.display()))?;
+    }
+    for team in &config.teams {
+        if let Some(ref budget) = team.budget {
+            budget.validate().with_context(|| {
+                format!("Invalid budget for team '{}' in {}", team.name, path.display())
+            })?;
+        }
+        for notifier in &team.credentials.notifiers {
+            notifier.validate().with_context(|| {
+                format!("Invalid notifier for team '{}' in {}", team.name, path.display())
+            })?;
+        }
+    }
+    Ok((config, from))
+}
+
+/// Runs the migrations from version `from` up to [`CONFIG_VERSION`].
+fn migrate(raw: serde_yml::Value, from: u32) -> Result<serde_yml::Value> {
+    let serde_yml::Value::Mapping(mut map) = raw else {
+        bail!("Expected a mapping at the top level");
+    };
+    for (version, step) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        step(&mut map).with_context(|| format!("Migration to version {}", version + 1))?;
+        map.insert("version".into(), (version as u64 + 1).into());
+    }
+    Ok(serde_yml::Value::Mapping(map))
+}
+
+/// Version 0 is every config written before versioning. Its fields are the
+/// same as version 1's, so only the version is added.
+fn migrate_v0_to_v1(_config: &mut serde_yml::Mapping) -> Result<()> {
+    Ok(())
+}
+
+/// Returns where the version `from` original is kept after migrating `path`.
+pub fn backup_path(path: &Path, from: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".v{}.bak", from));
+    PathBuf::from(name)
+}
+
+/// Copies the old config aside, with the same 0600 permissions, and saves
+/// the migrated one in its place.
+fn save_migrated(path: &Path, from: u32, config: &BotminterConfig) -> Result<()> {
+    let backup = backup_path(path, from);
+    fs::copy(path, &backup)
+        .with_context(|| format!("Failed to back up config to {}", backup.display()))?;
+    set_mode(&backup, CONFIG_PERMISSIONS)
+        .context("Failed to set config backup permissions to 0600")?;
+    save_to(path, config)?;
+    eprintln!(
+        "Migrated {} from version {} to {}; the original is at {}",
+        path.display(),
+        from,
+        CONFIG_VERSION,
+        backup.display()
+    );
+    Ok(())
 }
 
 /// Saves the config to disk with 0600 permissions.
@@ -87,16 +371,210 @@ pub fn save_to(path: &Path, config: &BotminterConfig) -> Result<()> {
     }
 
     let contents = serde_yml::to_string(config).context("Failed to serialize config")?;
-    fs::write(path, contents).context("Failed to write config file")?;
 
-    // Set file permissions to 0600 (owner read/write only)
-    let perms = fs::Permissions::from_mode(CONFIG_PERMISSIONS);
-    fs::set_permissions(path, perms)
+    // Written to a private temp file, then renamed into place, so a failed
+    // write never leaves a truncated config behind
+    let tmp_path = path.with_extension("yml.tmp");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, CONFIG_PERMISSIONS);
+    let mut file = options
+        .open(&tmp_path)
+        .context("Failed to write config file")?;
+    file.write_all(contents.as_bytes())
+        .context("Failed to write config file")?;
+
+    // Set file permissions to 0600 (owner read/write only); a stale temp
+    // file keeps the mode it was created with
+    set_mode(&tmp_path, CONFIG_PERMISSIONS)
         .context("Failed to set config file permissions to 0600")?;
+    fs::rename(&tmp_path, path).context("Failed to write config file")?;
+
+    Ok(())
+}
+
+/// Acquires the exclusive lock guarding config.yml.
+///
+/// Hold the returned guard across a load-modify-save cycle so that concurrent
+/// `bm` processes don't overwrite each other's edits.
+pub fn lock() -> Result<FileLock> {
+    FileLock::acquire(&config_path()?)
+}
+
+/// Runs a locked load-modify-save cycle on config.yml.
+pub fn update<T>(f: impl FnOnce(&mut BotminterConfig) -> Result<T>) -> Result<T> {
+    update_at(&config_path()?, f)
+}
+
+/// Runs a locked load-modify-save cycle on a specific config file.
+pub fn update_at<T>(
+    path: &Path,
+    f: impl FnOnce(&mut BotminterConfig) -> Result<T>,
+) -> Result<T> {
+    let _lock = FileLock::acquire(path)?;
+    check_permissions(path);
+    let (mut config, from) = read(path)?;
+    if from < CONFIG_VERSION {
+        save_migrated(path, from, &config)?;
+    }
+    let result = f(&mut config)?;
+    save_to(path, &config)?;
+    Ok(result)
+}
 
+// ── Dotted keys (`bm config`) ───────────────────────────────────────
+
+/// Reads the setting at a dotted `key`, e.g. `timestamps` or
+/// `teams.my-team.credentials.gh_token`. Under `teams`, a segment names a
+/// team. Returns `None` if the setting is not set.
+pub fn get_key(config: &BotminterConfig, key: &str) -> Result<Option<serde_yml::Value>> {
+    use serde_yml::Value;
+    let root = serde_yml::to_value(config).context("Failed to serialize config")?;
+    let mut node = &root;
+    for (i, segment) in key_segments(key)?.into_iter().enumerate() {
+        let next = match node {
+            Value::Mapping(map) => map.get(segment),
+            Value::Sequence(teams) => Some(find_team_value(teams.iter(), segment)?),
+            _ => None,
+        };
+        match next {
+            Some(next) if !next.is_null() => node = next,
+            _ if i == 0 && !TOP_LEVEL_KEYS.contains(&segment) => {
+                bail!("Unknown config key '{}'", segment)
+            }
+            _ => return Ok(None),
+        }
+    }
+    Ok(Some(node.clone()))
+}
+
+/// Sets the setting at a dotted `key` (see [`get_key`]). `value` is read
+/// as YAML, so numbers, `true`, and `[a, b]` lists work; a value the field
+/// won't take that way is tried as a plain string. The result must still
+/// be a valid config.
+pub fn set_key(config: &mut BotminterConfig, key: &str, value: &str) -> Result<()> {
+    use serde_yml::Value;
+    let segments = editable_segments(key)?;
+    let text = Value::String(value.to_string());
+    let parsed = serde_yml::from_str::<Value>(value).unwrap_or_else(|_| text.clone());
+    let retry = parsed != text;
+    *config = match edit_key(config, &segments, Some(parsed)) {
+        Err(_) if retry => edit_key(config, &segments, Some(text))?.0,
+        result => result?.0,
+    };
     Ok(())
 }
 
+/// Removes the setting at a dotted `key` (see [`get_key`]). Returns false
+/// if it was not set. A required setting can't be removed.
+pub fn unset_key(config: &mut BotminterConfig, key: &str) -> Result<bool> {
+    let (updated, removed) = edit_key(config, &editable_segments(key)?, None)?;
+    *config = updated;
+    Ok(removed)
+}
+
+/// Top-level keys of config.yml, for telling unknown keys from unset ones.
+const TOP_LEVEL_KEYS: &[&str] = &[
+    "version",
+    "workzone",
+    "default_team",
+    "token_expiry_warn_days",
+    "logs",
+    "timestamps",
+    "workspace",
+    "state_backend",
+    "teams",
+];
+
+/// Splits a dotted key.
+fn key_segments(key: &str) -> Result<Vec<&str>> {
+    let segments: Vec<&str> = key.split('.').collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        bail!("Invalid config key '{}'", key);
+    }
+    Ok(segments)
+}
+
+/// Splits a dotted key to change, refusing the keys `bm` manages itself.
+fn editable_segments(key: &str) -> Result<Vec<&str>> {
+    let segments = key_segments(key)?;
+    match segments.as_slice() {
+        ["version", ..] => bail!("version is managed by bm"),
+        ["teams"] | ["teams", _] => bail!(
+            "Teams are added with `bm init` and removed with `bm teams remove`; \
+             set one of a team's settings, e.g. teams.<team>.github_repo"
+        ),
+        ["teams", _, "name"] => bail!("A team's name can't be changed"),
+        _ => Ok(segments),
+    }
+}
+
+/// Finds a team in the serialized `teams` list by name.
+fn find_team_value<'a, T>(mut teams: impl Iterator<Item = T>, name: &str) -> Result<T>
+where
+    T: std::ops::Deref<Target = serde_yml::Value> + 'a,
+{
+    teams
+        .find(|t| t.get("name").and_then(serde_yml::Value::as_str) == Some(name))
+        .with_context(|| format!("Team '{}' not found", name))
+}
+
+/// Applies an edit to a copy of `config`: sets the key to `value`, or
+/// removes it when `value` is `None`. Returns the validated result and
+/// whether anything was removed.
+fn edit_key(
+    config: &BotminterConfig,
+    segments: &[&str],
+    value: Option<serde_yml::Value>,
+) -> Result<(BotminterConfig, bool)> {
+    use serde_yml::{Mapping, Value};
+    let key = segments.join(".");
+    let mut root = serde_yml::to_value(config).context("Failed to serialize config")?;
+    let (last, parents) = segments.split_last().context("Empty config key")?;
+
+    let mut node = &mut root;
+    for (i, segment) in parents.iter().enumerate() {
+        if node.is_null() && value.is_some() {
+            *node = Value::Mapping(Mapping::new());
+        }
+        node = match node {
+            Value::Mapping(map) => {
+                let segment = Value::String(segment.to_string());
+                if value.is_none() && !map.contains_key(&segment) {
+                    return Ok((config.clone(), false));
+                }
+                map.entry(segment).or_insert(Value::Null)
+            }
+            Value::Sequence(teams) => find_team_value(teams.iter_mut(), segment)?,
+            Value::Null => return Ok((config.clone(), false)),
+            _ => bail!("{} is not a section", segments[..=i].join(".")),
+        };
+    }
+    if node.is_null() && value.is_some() {
+        *node = Value::Mapping(Mapping::new());
+    }
+    let Value::Mapping(map) = node else {
+        bail!("{} is not a section", parents.join("."));
+    };
+    let removed = match value {
+        Some(value) => {
+            map.insert(Value::String(last.to_string()), value);
+            false
+        }
+        None => map
+            .remove(*last)
+            .is_some_and(|old| !old.is_null()),
+    };
+
+    let path = Path::new(CONFIG_FILE);
+    let updated: BotminterConfig = serde_yml::from_value(root)
+        .map_err(|e| anyhow::anyhow!("{}", e))
+        .with_context(|| format!("Invalid value for {}", key))?;
+    let (updated, _) = validated(path, updated, CONFIG_VERSION)?;
+    Ok((updated, removed))
+}
+
 /// Resolves which team to operate on: explicit flag > default_team > error.
 pub fn resolve_team<'a>(
     config: &'a BotminterConfig,
@@ -126,8 +604,24 @@ pub fn resolve_team<'a>(
         })
 }
 
+/// Sets the permission bits of `path`, e.g. 0600 for files holding
+/// secrets. Does nothing on Windows, which has no permission bits; files
+/// under the user profile are private to the user there.
+#[cfg(unix)]
+pub fn set_mode(path: &Path, mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+pub fn set_mode(_path: &Path, _mode: u32) -> std::io::Result<()> {
+    Ok(())
+}
+
 /// Checks config file permissions and prints a warning if not 0600.
+#[cfg(unix)]
 fn check_permissions(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
     if let Ok(metadata) = fs::metadata(path) {
         let mode = metadata.permissions().mode() & 0o777;
         if mode != CONFIG_PERMISSIONS {
@@ -143,8 +637,14 @@ fn check_permissions(path: &Path) {
     }
 }
 
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) {}
+
 #[cfg(test)]
 mod tests {
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
     use super::*;
 
     /// Creates a config file path inside a temp directory (no env var mutation).
@@ -158,17 +658,31 @@ mod tests {
         let path = test_config_path(tmp.path());
 
         let config = BotminterConfig {
+            version: CONFIG_VERSION,
             workzone: PathBuf::from("/tmp/workspaces"),
             default_team: Some("my-team".to_string()),
+            token_expiry_warn_days: None,
+            logs: None,
+            timestamps: None,
+            workspace: None,
+            state_backend: None,
             teams: vec![TeamEntry {
                 name: "my-team".to_string(),
                 path: PathBuf::from("/tmp/workspaces/my-team"),
                 profile: "scrum".to_string(),
                 github_repo: "org/my-team".to_string(),
+                extra_repos: Vec::new(),
+                budget: None,
                 credentials: Credentials {
                     gh_token: Some("ghp_test123".to_string()),
                     telegram_bot_token: None,
+                    telegram_chat_id: None,
+                    slack_bot_token: None,
+                    slack_channel: None,
                     webhook_secret: None,
+                    github_app: None,
+                    smtp: None,
+                    notifiers: Vec::new(),
                 },
             }],
         };
@@ -198,14 +712,125 @@ mod tests {
         assert!(err.contains("bm init"));
     }
 
+    #[test]
+    fn unversioned_config_is_migrated_with_backup() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = test_config_path(tmp.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let original = "workzone: /tmp\ndefault_team: alpha\nteams: []\n";
+        fs::write(&path, original).unwrap();
+
+        let config = load_from(&path).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.default_team.as_deref(), Some("alpha"));
+
+        let backup = backup_path(&path, 0);
+        assert_eq!(fs::read_to_string(&backup).unwrap(), original);
+        #[cfg(unix)]
+        assert_eq!(fs::metadata(&backup).unwrap().permissions().mode() & 0o777, 0o600);
+        let saved = fs::read_to_string(&path).unwrap();
+        assert!(saved.starts_with(&format!("version: {}\n", CONFIG_VERSION)), "{}", saved);
+
+        // Already current: loads without touching the backup again
+        fs::remove_file(&backup).unwrap();
+        load_from(&path).unwrap();
+        assert!(!backup.exists());
+    }
+
+    #[test]
+    fn newer_config_version_is_refused() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = test_config_path(tmp.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "version: 99\nworkzone: /tmp\ndefault_team: null\n").unwrap();
+        let err = format!("{:#}", load_from(&path).unwrap_err());
+        assert!(err.contains("config version 99"), "{}", err);
+        assert!(err.contains("Upgrade bm"), "{}", err);
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = test_config_path(tmp.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            "version: 1\nworkzone: /tmp\ndefault_team: null\nteams:\n  - name: a\n    path: /tmp/a\n    \
+             profile: scrum\n    github_repo: o/a\n    credentials:\n      gh_tokn: x\n",
+        )
+        .unwrap();
+        let err = format!("{:#}", load_from(&path).unwrap_err());
+        assert!(err.contains("unknown field `gh_tokn`"), "{}", err);
+        assert!(err.contains("line 10"), "{}", err);
+
+        // Same mistake in an unversioned config is reported against the file as written
+        let unversioned = fs::read_to_string(&path).unwrap().replace("version: 1\n", "");
+        fs::write(&path, unversioned).unwrap();
+        let err = format!("{:#}", load_from(&path).unwrap_err());
+        assert!(err.contains("line 9"), "{}", err);
+        assert!(!backup_path(&path, 0).exists());
+    }
+
+    #[test]
+    fn logs_section_is_parsed_and_validated() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = test_config_path(tmp.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        fs::write(
+            &path,
+            "workzone: /tmp\ndefault_team: null\nlogs:\n  max_size: 50Mi\n  keep: 3\n",
+        )
+        .unwrap();
+        let logs = load_from(&path).unwrap().logs.unwrap();
+        assert_eq!(logs.max_size_bytes().unwrap(), 50 << 20);
+        assert_eq!(logs.keep(), 3);
+        assert_eq!(logs.max_age(), None);
+
+        fs::write(&path, "workzone: /tmp\ndefault_team: null\nlogs:\n  max_size: lots\n").unwrap();
+        let err = format!("{:#}", load_from(&path).unwrap_err());
+        assert!(err.contains("Invalid logs settings"), "{}", err);
+    }
+
+    #[test]
+    fn workspace_link_mode_is_parsed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = test_config_path(tmp.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        fs::write(&path, "workzone: /tmp\ndefault_team: null\n").unwrap();
+        assert_eq!(load_from(&path).unwrap().workspace, None);
+        fs::write(
+            &path,
+            "workzone: /tmp\ndefault_team: null\nworkspace:\n  link_mode: copy\n",
+        )
+        .unwrap();
+        let settings = load_from(&path).unwrap().workspace.unwrap();
+        assert_eq!(settings.link_mode, workspace::LinkMode::Copy);
+
+        fs::write(
+            &path,
+            "workzone: /tmp\ndefault_team: null\nworkspace:\n  link_mode: junction\n",
+        )
+        .unwrap();
+        assert!(load_from(&path).is_err());
+    }
+
+    #[cfg(unix)]
     #[test]
     fn config_file_has_0600_permissions() {
         let tmp = tempfile::tempdir().unwrap();
         let path = test_config_path(tmp.path());
 
         let config = BotminterConfig {
+            version: CONFIG_VERSION,
             workzone: PathBuf::from("/tmp/ws"),
             default_team: None,
+            token_expiry_warn_days: None,
+            logs: None,
+            timestamps: None,
+            workspace: None,
+            state_backend: None,
             teams: vec![],
         };
         save_to(&path, &config).unwrap();
@@ -215,17 +840,88 @@ mod tests {
         assert_eq!(mode, 0o600, "Config file should have 0600 permissions");
     }
 
+    #[test]
+    fn update_at_modifies_and_saves() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = test_config_path(tmp.path());
+        let config = BotminterConfig {
+            version: CONFIG_VERSION,
+            workzone: PathBuf::from("/tmp/ws"),
+            default_team: None,
+            token_expiry_warn_days: None,
+            logs: None,
+            timestamps: None,
+            workspace: None,
+            state_backend: None,
+            teams: vec![],
+        };
+        save_to(&path, &config).unwrap();
+
+        update_at(&path, |cfg| {
+            cfg.default_team = Some("other".to_string());
+            Ok(())
+        })
+        .unwrap();
+
+        let loaded = load_from(&path).unwrap();
+        assert_eq!(loaded.default_team, Some("other".to_string()));
+        #[cfg(unix)]
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn repos_lists_github_repo_first_without_duplicates() {
+        let mut team: TeamEntry = serde_yml::from_str(
+            "name: t\npath: /tmp/t\nprofile: scrum\ngithub_repo: org/team\ncredentials: {}\n",
+        )
+        .unwrap();
+        assert!(team.extra_repos.is_empty());
+        assert_eq!(team.repos(), vec!["org/team"]);
+
+        team.extra_repos = vec!["org/api".into(), "org/team".into(), "org/web".into()];
+        assert_eq!(team.repos(), vec!["org/team", "org/api", "org/web"]);
+        team.github_repo.clear();
+        assert_eq!(team.repos(), vec!["org/api", "org/team", "org/web"]);
+    }
+
+    #[test]
+    fn smtp_defaults_follow_tls_mode() {
+        let smtp: Smtp =
+            serde_yml::from_str("server: mail.example.com\nfrom: bm@x.io\nto: [ops@x.io]\n")
+                .unwrap();
+        assert_eq!(smtp.tls, SmtpTls::Starttls);
+        assert_eq!(smtp.port(), 587);
+        assert!(smtp.wants(notify::Kind::Crash) && smtp.wants(notify::Kind::Digest));
+        assert_eq!(smtp.digest_time().unwrap().to_string(), "08:00:00");
+
+        let smtp: Smtp = serde_yml::from_str(
+            "server: m\ntls: tls\nfrom: a\nto: [b]\nnotify_on: [crash]\ndigest_at: '7am'\n",
+        )
+        .unwrap();
+        assert_eq!(smtp.port(), 465);
+        assert!(smtp.wants(notify::Kind::Crash) && !smtp.wants(notify::Kind::Error));
+        assert!(smtp.digest_time().unwrap_err().to_string().contains("HH:MM"));
+    }
+
     #[test]
     fn resolve_team_with_flag() {
         let config = BotminterConfig {
+            version: CONFIG_VERSION,
             workzone: PathBuf::from("/tmp"),
             default_team: Some("default".to_string()),
+            token_expiry_warn_days: None,
+            logs: None,
+            timestamps: None,
+            workspace: None,
+            state_backend: None,
             teams: vec![
                 TeamEntry {
                     name: "default".to_string(),
                     path: PathBuf::from("/tmp/default"),
                     profile: "scrum-compact".to_string(),
                     github_repo: "".to_string(),
+                    extra_repos: Vec::new(),
+                    budget: None,
                     credentials: Credentials::default(),
                 },
                 TeamEntry {
@@ -233,6 +929,8 @@ mod tests {
                     path: PathBuf::from("/tmp/other"),
                     profile: "scrum".to_string(),
                     github_repo: "".to_string(),
+                    extra_repos: Vec::new(),
+                    budget: None,
                     credentials: Credentials::default(),
                 },
             ],
@@ -247,13 +945,21 @@ mod tests {
     #[test]
     fn resolve_team_uses_default() {
         let config = BotminterConfig {
+            version: CONFIG_VERSION,
             workzone: PathBuf::from("/tmp"),
             default_team: Some("my-team".to_string()),
+            token_expiry_warn_days: None,
+            logs: None,
+            timestamps: None,
+            workspace: None,
+            state_backend: None,
             teams: vec![TeamEntry {
                 name: "my-team".to_string(),
                 path: PathBuf::from("/tmp/my-team"),
                 profile: "scrum".to_string(),
                 github_repo: "".to_string(),
+                extra_repos: Vec::new(),
+                budget: None,
                 credentials: Credentials::default(),
             }],
         };
@@ -265,8 +971,14 @@ mod tests {
     #[test]
     fn resolve_team_no_default_no_flag_errors() {
         let config = BotminterConfig {
+            version: CONFIG_VERSION,
             workzone: PathBuf::from("/tmp"),
             default_team: None,
+            token_expiry_warn_days: None,
+            logs: None,
+            timestamps: None,
+            workspace: None,
+            state_backend: None,
             teams: vec![],
         };
 
@@ -279,13 +991,21 @@ mod tests {
     #[test]
     fn resolve_team_nonexistent_errors() {
         let config = BotminterConfig {
+            version: CONFIG_VERSION,
             workzone: PathBuf::from("/tmp"),
             default_team: None,
+            token_expiry_warn_days: None,
+            logs: None,
+            timestamps: None,
+            workspace: None,
+            state_backend: None,
             teams: vec![TeamEntry {
                 name: "exists".to_string(),
                 path: PathBuf::from("/tmp/exists"),
                 profile: "scrum-compact".to_string(),
                 github_repo: "".to_string(),
+                extra_repos: Vec::new(),
+                budget: None,
                 credentials: Credentials::default(),
             }],
         };
@@ -296,4 +1016,93 @@ mod tests {
         assert!(err.contains("nope"));
         assert!(err.contains("exists"));
     }
+
+    #[test]
+    fn dotted_keys_get_set_and_unset() {
+        let mut config = BotminterConfig {
+            version: CONFIG_VERSION,
+            workzone: PathBuf::from("/tmp"),
+            default_team: None,
+            token_expiry_warn_days: None,
+            logs: None,
+            timestamps: None,
+            workspace: None,
+            state_backend: None,
+            teams: vec![TeamEntry {
+                name: "alpha".to_string(),
+                path: PathBuf::from("/tmp/alpha"),
+                profile: "scrum".to_string(),
+                github_repo: "org/alpha".to_string(),
+                extra_repos: Vec::new(),
+                budget: None,
+                credentials: Credentials::default(),
+            }],
+        };
+
+        set_key(&mut config, "teams.alpha.credentials.telegram_bot_token", "123:abc").unwrap();
+        set_key(&mut config, "teams.alpha.credentials.telegram_chat_id", "42").unwrap();
+        // Digits still make a string for a string field
+        set_key(&mut config, "teams.alpha.credentials.gh_token", "0123").unwrap();
+        set_key(&mut config, "teams.alpha.extra_repos", "[org/api]").unwrap();
+        set_key(&mut config, "logs.keep", "3").unwrap();
+        let creds = &config.teams[0].credentials;
+        assert_eq!(creds.telegram_bot_token.as_deref(), Some("123:abc"));
+        assert_eq!(creds.telegram_chat_id, Some(42));
+        assert_eq!(creds.gh_token.as_deref(), Some("0123"));
+        assert_eq!(config.teams[0].extra_repos, vec!["org/api".to_string()]);
+        assert_eq!(
+            get_key(&config, "teams.alpha.github_repo").unwrap(),
+            Some(serde_yml::Value::String("org/alpha".to_string()))
+        );
+        assert_eq!(get_key(&config, "default_team").unwrap(), None);
+        assert!(get_key(&config, "colour").is_err());
+        assert!(get_key(&config, "teams.beta.path").is_err());
+
+        assert!(unset_key(&mut config, "teams.alpha.credentials.gh_token").unwrap());
+        assert!(!unset_key(&mut config, "teams.alpha.credentials.gh_token").unwrap());
+        assert!(!unset_key(&mut config, "teams.alpha.budget.daily_usd").unwrap());
+        assert!(config.teams[0].credentials.gh_token.is_none());
+
+        let before = config.clone();
+        for (key, value) in [
+            ("colour", "red"),
+            ("timestamps", "mars"),
+            ("logs.keep", "lots"),
+            ("version", "9"),
+            ("teams.alpha", "x"),
+            ("teams.alpha.name", "beta"),
+            ("teams.beta.github_repo", "org/beta"),
+            ("workzone.path", "/tmp"),
+        ] {
+            assert!(set_key(&mut config, key, value).is_err(), "{}", key);
+        }
+        assert!(unset_key(&mut config, "workzone").is_err());
+        assert_eq!(serde_yml::to_string(&config).unwrap(), serde_yml::to_string(&before).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn save_keeps_the_config_private() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = test_config_path(tmp.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let config = BotminterConfig {
+            version: CONFIG_VERSION,
+            workzone: PathBuf::from("/tmp"),
+            default_team: None,
+            token_expiry_warn_days: None,
+            logs: None,
+            timestamps: None,
+            workspace: None,
+            state_backend: None,
+            teams: vec![],
+        };
+        save_to(&path, &config).unwrap();
+        #[cfg(unix)]
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(!path.with_extension("yml.tmp").exists());
+    }
 }
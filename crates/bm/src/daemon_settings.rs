@@ -0,0 +1,1131 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::disk_quota::DiskQuota;
+use crate::logging::LogFormat;
+use crate::parse;
+use crate::schedule::{self, Schedule};
+use crate::trigger_filter::{Expr, TriggerEvent};
+
+/// Name of the daemon settings file at the root of the team repo.
+pub const SETTINGS_FILE: &str = "daemon.yml";
+
+/// Highest `daemon.yml` version this build understands.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Default daemon mode when neither flags nor settings choose one.
+pub const DEFAULT_MODE: &str = "webhook";
+
+/// Default webhook listener port.
+pub const DEFAULT_PORT: u16 = 8484;
+
+/// Default poll interval in seconds.
+pub const DEFAULT_INTERVAL_SECS: u64 = 60;
+
+/// Default debounce window in seconds for coalescing event bursts.
+pub const DEFAULT_DEBOUNCE_SECS: u64 = 5;
+
+/// GitHub event types that trigger member launches when no filter is configured.
+pub const DEFAULT_EVENTS: &[&str] = &["issues", "issue_comment", "pull_request"];
+
+/// Settings `bm daemon config` reads and changes, named by their path in
+/// `daemon.yml`. Routing, disk quotas, schedules, and `filters.triggers` are
+/// edited in the file directly.
+pub const CONFIG_KEYS: &[&str] = &[
+    "mode",
+    "port",
+    "interval_secs",
+    "debounce_secs",
+    "max_concurrent_members",
+    "role_priority",
+    "filters.events",
+    "filters.expr",
+    "quiet_hours",
+    "chatops.allowed_users",
+    "chatops.slack_users",
+    "log_format",
+];
+
+/// Daemon behavior settings.
+///
+/// The same shape is used for the shared `daemon.yml` in the team repo and for
+/// the operator-local override at `~/.botminter/daemon-<team>.yml`. Every field
+/// is optional so a layer only needs to mention what it changes.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DaemonSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+
+    /// Default daemon mode: `webhook` or `poll`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+
+    /// Default webhook listener port.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+
+    /// Default poll interval in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
+
+    /// Seconds without new events before a burst is dispatched as one run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debounce_secs: Option<u64>,
+
+    /// Most members a one-shot run has running at once; the rest wait for
+    /// a slot. Unset means no limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_members: Option<usize>,
+
+    /// Roles whose members a one-shot run launches first, in this order.
+    /// Members of unlisted roles follow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role_priority: Option<Vec<String>>,
+
+    /// Which events are considered relevant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filters: Option<EventFilters>,
+
+    /// Event type → roles to launch. Unrouted events launch every member.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing: Option<BTreeMap<String, Vec<String>>>,
+
+    /// Window during which no members are launched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<QuietHours>,
+
+    /// Soft limit on each member workspace's disk usage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_quota: Option<DiskQuota>,
+
+    /// Who may control the team from Telegram (`bm chatops`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chatops: Option<Chatops>,
+
+    /// Daemon log file format: `text` or `json`. Read when the daemon starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_format: Option<LogFormat>,
+
+    /// Runs launched at cron times rather than by events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedules: Option<Vec<Schedule>>,
+}
+
+/// Event filters applied before launching members.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EventFilters {
+    /// Event types (webhook naming, e.g. `issue_comment`) that trigger
+    /// launches; `*` matches every type. Empty means [`DEFAULT_EVENTS`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
+
+    /// Expression an event of a listed type must also satisfy, e.g.
+    /// `"kind/story" in labels && author != "bot"`. See [`crate::trigger_filter`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expr: Option<String>,
+
+    /// More event types to trigger on, each with its own conditions. An event
+    /// whose type has entries here must satisfy one of them, even when its
+    /// type is also in `events`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<Trigger>,
+}
+
+/// One entry under `filters.triggers`. Every condition given must hold; an
+/// entry with none matches every event of its type.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Trigger {
+    /// Event type, named as in `events` (e.g. `push`, `workflow_run`).
+    pub event: String,
+    /// Actions to accept, e.g. `completed` for `workflow_run`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<String>,
+    /// Branch patterns to accept; `*` matches any run of characters, so
+    /// `release/*` matches `release/1.2`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<String>,
+    /// Labels to accept; the issue or pull request needs at least one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+}
+
+impl Trigger {
+    /// Checks an event of this entry's type against its conditions.
+    pub fn matches(&self, event: &TriggerEvent) -> bool {
+        (self.actions.is_empty() || self.actions.contains(&event.action))
+            && (self.branches.is_empty()
+                || self.branches.iter().any(|b| branch_matches(b, &event.branch)))
+            && (self.labels.is_empty() || self.labels.iter().any(|l| event.labels.contains(l)))
+    }
+}
+
+/// `bm chatops` settings.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Chatops {
+    /// Telegram user ids whose commands are carried out.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_users: Vec<i64>,
+    /// Slack member ids (`U0123ABCD`) whose commands are carried out.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slack_users: Vec<String>,
+}
+
+/// A daily quiet-hours window in UTC (`HH:MM`). May wrap past midnight.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    /// Returns true if `now` falls inside the window. Start is inclusive, end exclusive.
+    pub fn contains(&self, now: chrono::NaiveTime) -> Result<bool> {
+        let start = parse_hhmm(&self.start)?;
+        let end = parse_hhmm(&self.end)?;
+        Ok(if start <= end {
+            now >= start && now < end
+        } else {
+            now >= start || now < end
+        })
+    }
+}
+
+impl DaemonSettings {
+    /// Layers `over` on top of `self`. Each top-level key set in `over` replaces
+    /// the value from `self` wholesale.
+    pub fn overlay(self, over: DaemonSettings) -> DaemonSettings {
+        DaemonSettings {
+            version: over.version.or(self.version),
+            mode: over.mode.or(self.mode),
+            port: over.port.or(self.port),
+            interval_secs: over.interval_secs.or(self.interval_secs),
+            debounce_secs: over.debounce_secs.or(self.debounce_secs),
+            max_concurrent_members: over.max_concurrent_members.or(self.max_concurrent_members),
+            role_priority: over.role_priority.or(self.role_priority),
+            filters: over.filters.or(self.filters),
+            routing: over.routing.or(self.routing),
+            quiet_hours: over.quiet_hours.or(self.quiet_hours),
+            disk_quota: over.disk_quota.or(self.disk_quota),
+            chatops: over.chatops.or(self.chatops),
+            log_format: over.log_format.or(self.log_format),
+            schedules: over.schedules.or(self.schedules),
+        }
+    }
+
+    /// Effective daemon mode.
+    pub fn mode(&self) -> &str {
+        self.mode.as_deref().unwrap_or(DEFAULT_MODE)
+    }
+
+    /// Effective webhook port.
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(DEFAULT_PORT)
+    }
+
+    /// Effective poll interval in seconds.
+    pub fn interval_secs(&self) -> u64 {
+        self.interval_secs.unwrap_or(DEFAULT_INTERVAL_SECS)
+    }
+
+    /// Effective daemon log format.
+    pub fn log_format(&self) -> LogFormat {
+        self.log_format.unwrap_or_default()
+    }
+
+    /// Effective debounce window in seconds.
+    pub fn debounce_secs(&self) -> u64 {
+        self.debounce_secs.unwrap_or(DEFAULT_DEBOUNCE_SECS)
+    }
+
+    /// Where members of `role` go in a one-shot run's launch order: their
+    /// position in `role_priority`, or after every listed role.
+    pub fn role_rank(&self, role: &str) -> usize {
+        let priority = self.role_priority.as_deref().unwrap_or_default();
+        priority
+            .iter()
+            .position(|r| r == role)
+            .unwrap_or(priority.len())
+    }
+
+    /// Returns the configured relevant event types (or the defaults), then
+    /// the types `filters.triggers` adds.
+    pub fn relevant_events(&self) -> Vec<String> {
+        let mut events: Vec<String> = match &self.filters {
+            Some(f) if !f.events.is_empty() => f.events.clone(),
+            _ => DEFAULT_EVENTS.iter().map(|e| e.to_string()).collect(),
+        };
+        for trigger in self.trigger_entries() {
+            if !events.contains(&trigger.event) {
+                events.push(trigger.event.clone());
+            }
+        }
+        events
+    }
+
+    /// Configured `filters.triggers` entries.
+    pub fn trigger_entries(&self) -> &[Trigger] {
+        self.filters.as_ref().map_or(&[], |f| &f.triggers)
+    }
+
+    /// Checks an event against the `filters.triggers` entries for its type;
+    /// true when there are none.
+    pub fn passes_triggers(&self, event: &TriggerEvent) -> bool {
+        let mut entries = self
+            .trigger_entries()
+            .iter()
+            .filter(|t| event_matches(&t.event, &event.event))
+            .peekable();
+        entries.peek().is_none() || entries.any(|t| t.matches(event))
+    }
+
+    /// Checks an event type against the configured filter (or the defaults).
+    pub fn is_relevant(&self, event_type: &str) -> bool {
+        self.relevant_events()
+            .iter()
+            .any(|e| event_matches(e, event_type))
+    }
+
+    /// Returns the parsed `filters.expr`, if one is configured.
+    pub fn filter_expr(&self) -> Result<Option<Expr>> {
+        self.filters
+            .as_ref()
+            .and_then(|f| f.expr.as_deref())
+            .map(Expr::parse)
+            .transpose()
+            .context("Invalid filters.expr")
+    }
+
+    /// Checks an event against the event type filter, the `filters.triggers`
+    /// entries for its type, and, when one is set, the filter expression.
+    pub fn triggers(&self, event: &TriggerEvent) -> bool {
+        if !self.is_relevant(&event.event) || !self.passes_triggers(event) {
+            return false;
+        }
+        match self.filter_expr() {
+            Ok(Some(expr)) => expr.eval(event),
+            Ok(None) => true,
+            // validate() rejects bad expressions when settings are loaded
+            Err(_) => false,
+        }
+    }
+
+    /// Configured schedules.
+    pub fn schedules(&self) -> &[Schedule] {
+        self.schedules.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the roles to launch for a batch of event types, or `None` when
+    /// every member should run (no routing, or any event without a route).
+    /// A schedule's event goes to the schedule's roles, when it lists any.
+    pub fn roles_for_events(&self, event_types: &[&str]) -> Option<Vec<String>> {
+        let mut roles: Vec<String> = Vec::new();
+        for event_type in event_types {
+            let scheduled = event_type
+                .strip_prefix(schedule::EVENT_PREFIX)
+                .and_then(|name| self.schedules().iter().find(|s| s.name == name))
+                .filter(|s| !s.roles.is_empty());
+            let route = match scheduled {
+                Some(s) => &s.roles,
+                None => {
+                    self.routing
+                        .as_ref()?
+                        .iter()
+                        .find(|(configured, _)| event_matches(configured, event_type))?
+                        .1
+                }
+            };
+            for role in route {
+                if !roles.contains(role) {
+                    roles.push(role.clone());
+                }
+            }
+        }
+        Some(roles)
+    }
+
+    /// Telegram user ids allowed to run `bm chatops` commands.
+    pub fn chatops_users(&self) -> &[i64] {
+        self.chatops.as_ref().map_or(&[], |c| &c.allowed_users)
+    }
+
+    /// Slack member ids allowed to run `bm chatops` commands.
+    pub fn chatops_slack_users(&self) -> &[String] {
+        self.chatops.as_ref().map_or(&[], |c| &c.slack_users)
+    }
+
+    /// Returns true if launches should currently be suppressed.
+    pub fn in_quiet_hours(&self, now: chrono::NaiveTime) -> bool {
+        match &self.quiet_hours {
+            Some(q) => q.contains(now).unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /// Returns the value this layer sets for `key`, formatted the way
+    /// [`DaemonSettings::set`] takes it.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(match check_key(key)? {
+            "mode" => self.mode.clone(),
+            "port" => self.port.map(|p| p.to_string()),
+            "interval_secs" => self.interval_secs.map(|s| s.to_string()),
+            "debounce_secs" => self.debounce_secs.map(|s| s.to_string()),
+            "max_concurrent_members" => self.max_concurrent_members.map(|n| n.to_string()),
+            "role_priority" => self.role_priority.as_ref().map(|r| r.join(",")),
+            "filters.events" => self
+                .filters
+                .as_ref()
+                .filter(|f| !f.events.is_empty())
+                .map(|f| f.events.join(",")),
+            "filters.expr" => self.filters.as_ref().and_then(|f| f.expr.clone()),
+            "quiet_hours" => self
+                .quiet_hours
+                .as_ref()
+                .map(|q| format!("{}-{}", q.start, q.end)),
+            "log_format" => self.log_format.map(|f| f.to_string()),
+            "chatops.allowed_users" => self
+                .chatops
+                .as_ref()
+                .filter(|c| !c.allowed_users.is_empty())
+                .map(|c| {
+                    c.allowed_users
+                        .iter()
+                        .map(|u| u.to_string())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                }),
+            _ => self
+                .chatops
+                .as_ref()
+                .filter(|c| !c.slack_users.is_empty())
+                .map(|c| c.slack_users.join(",")),
+        })
+    }
+
+    /// Returns the effective value of `key`: this layer's, else the built-in
+    /// default (`None` when the setting is off by default).
+    pub fn effective(&self, key: &str) -> Result<Option<String>> {
+        if let Some(value) = self.get(key)? {
+            return Ok(Some(value));
+        }
+        Ok(match key {
+            "mode" => Some(self.mode().to_string()),
+            "port" => Some(self.port().to_string()),
+            "interval_secs" => Some(self.interval_secs().to_string()),
+            "debounce_secs" => Some(self.debounce_secs().to_string()),
+            "filters.events" => Some(self.relevant_events().join(",")),
+            "log_format" => Some(self.log_format().to_string()),
+            _ => None,
+        })
+    }
+
+    /// Sets `key` from its command-line form: a number, a comma-separated
+    /// list for `role_priority`, `filters.events`, and the `chatops` user
+    /// lists, an expression
+    /// for `filters.expr`, or `HH:MM-HH:MM` for `quiet_hours`.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let number = |value: &str| -> Result<u64> {
+            value
+                .parse()
+                .with_context(|| format!("{} must be a whole number, got '{}'", key, value))
+        };
+        let list = |value: &str| -> Vec<String> {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        match check_key(key)? {
+            "mode" => {
+                validate_mode(value)?;
+                self.mode = Some(value.to_string());
+            }
+            "port" => {
+                let port = value
+                    .parse()
+                    .with_context(|| format!("port must be 1-65535, got '{}'", value))?;
+                self.port = Some(port);
+            }
+            "interval_secs" => {
+                let secs = number(value)?;
+                if secs == 0 {
+                    bail!("interval_secs must be at least 1");
+                }
+                self.interval_secs = Some(secs);
+            }
+            "debounce_secs" => self.debounce_secs = Some(number(value)?),
+            "max_concurrent_members" => {
+                let max = number(value)?;
+                if max == 0 {
+                    bail!("max_concurrent_members must be at least 1");
+                }
+                self.max_concurrent_members = Some(max as usize);
+            }
+            "role_priority" => {
+                let roles = list(value);
+                if roles.is_empty() {
+                    bail!("role_priority needs at least one role");
+                }
+                self.role_priority = Some(roles);
+            }
+            "filters.events" => {
+                let events = list(value);
+                if events.is_empty() {
+                    bail!("filters.events needs at least one event type");
+                }
+                self.filters.get_or_insert_with(Default::default).events = events;
+            }
+            "filters.expr" => {
+                Expr::parse(value).context("Invalid filters.expr")?;
+                self.filters.get_or_insert_with(Default::default).expr = Some(value.to_string());
+            }
+            "quiet_hours" => {
+                let Some((start, end)) = value.split_once('-') else {
+                    bail!("quiet_hours must look like 22:00-06:00, got '{}'", value);
+                };
+                let q = QuietHours {
+                    start: start.trim().to_string(),
+                    end: end.trim().to_string(),
+                };
+                parse_hhmm(&q.start)?;
+                parse_hhmm(&q.end)?;
+                self.quiet_hours = Some(q);
+            }
+            "log_format" => self.log_format = Some(value.parse()?),
+            "chatops.allowed_users" => {
+                let allowed_users = list(value)
+                    .iter()
+                    .map(|u| {
+                        u.parse()
+                            .with_context(|| format!("'{}' is not a Telegram user id", u))
+                    })
+                    .collect::<Result<_>>()?;
+                self.chatops.get_or_insert_with(Default::default).allowed_users = allowed_users;
+            }
+            _ => {
+                let slack_users = list(value);
+                if let Some(bad) = slack_users.iter().find(|u| !is_slack_member_id(u)) {
+                    bail!(
+                        "'{}' is not a Slack member id; copy it from the person's profile \
+                         (e.g. U0123ABCD)",
+                        bad
+                    );
+                }
+                self.chatops.get_or_insert_with(Default::default).slack_users = slack_users;
+            }
+        }
+        if self.chatops == Some(Chatops::default()) {
+            self.chatops = None;
+        }
+        Ok(())
+    }
+
+    /// Removes `key` from this layer. Returns false if it wasn't set.
+    pub fn unset(&mut self, key: &str) -> Result<bool> {
+        let was_set = self.get(key)?.is_some();
+        match check_key(key)? {
+            "mode" => self.mode = None,
+            "port" => self.port = None,
+            "interval_secs" => self.interval_secs = None,
+            "debounce_secs" => self.debounce_secs = None,
+            "max_concurrent_members" => self.max_concurrent_members = None,
+            "role_priority" => self.role_priority = None,
+            "filters.events" => {
+                if let Some(f) = self.filters.as_mut() {
+                    f.events.clear();
+                }
+            }
+            "filters.expr" => {
+                if let Some(f) = self.filters.as_mut() {
+                    f.expr = None;
+                }
+            }
+            "quiet_hours" => self.quiet_hours = None,
+            "log_format" => self.log_format = None,
+            "chatops.allowed_users" => {
+                if let Some(c) = self.chatops.as_mut() {
+                    c.allowed_users.clear();
+                }
+            }
+            _ => {
+                if let Some(c) = self.chatops.as_mut() {
+                    c.slack_users.clear();
+                }
+            }
+        }
+        if self.filters == Some(EventFilters::default()) {
+            self.filters = None;
+        }
+        if self.chatops == Some(Chatops::default()) {
+            self.chatops = None;
+        }
+        Ok(was_set)
+    }
+
+    /// Validates values that serde cannot check on its own.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(v) = self.version {
+            if v > SETTINGS_VERSION {
+                bail!(
+                    "daemon settings version {} is newer than this bm supports ({}). \
+                     Upgrade bm to use this team's daemon.yml.",
+                    v,
+                    SETTINGS_VERSION
+                );
+            }
+        }
+        if let Some(ref mode) = self.mode {
+            validate_mode(mode)?;
+        }
+        if self.max_concurrent_members == Some(0) {
+            bail!("max_concurrent_members must be at least 1");
+        }
+        self.filter_expr()?;
+        for trigger in self.trigger_entries() {
+            if trigger.event.is_empty() || trigger.event == "*" {
+                bail!("filters.triggers entries need an event type (not '*')");
+            }
+            if trigger.branches.iter().any(|b| b.is_empty()) {
+                bail!("Empty branch pattern in filters.triggers for '{}'", trigger.event);
+            }
+        }
+        if let Some(ref q) = self.quiet_hours {
+            parse_hhmm(&q.start)?;
+            parse_hhmm(&q.end)?;
+        }
+        if let Some(ref quota) = self.disk_quota {
+            quota.limit_bytes()?;
+        }
+        for (i, s) in self.schedules().iter().enumerate() {
+            s.validate()?;
+            if self.schedules()[..i].iter().any(|other| other.name == s.name) {
+                bail!("Duplicate schedule name '{}'", s.name);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validates a daemon mode string.
+pub fn validate_mode(mode: &str) -> Result<()> {
+    if mode != "webhook" && mode != "poll" {
+        bail!("Invalid daemon mode '{}'. Use 'webhook' or 'poll'.", mode);
+    }
+    Ok(())
+}
+
+/// Returns the path of the shared settings file in a team repo.
+pub fn team_settings_path(team_repo: &Path) -> PathBuf {
+    team_repo.join(SETTINGS_FILE)
+}
+
+/// Returns the path of the operator-local override (`~/.botminter/daemon-<team>.yml`).
+pub fn local_override_path(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("daemon-{}.yml", team_name)))
+}
+
+/// Loads the effective settings for a team.
+///
+/// Precedence (highest first): command-line flags (applied by the caller),
+/// the local override, the team repo's `daemon.yml`, built-in defaults.
+pub fn load(team_repo: &Path, team_name: &str) -> Result<DaemonSettings> {
+    load_layered(
+        &team_settings_path(team_repo),
+        &local_override_path(team_name)?,
+    )
+}
+
+/// Loads and overlays the two settings layers from explicit paths.
+pub fn load_layered(team_file: &Path, local_file: &Path) -> Result<DaemonSettings> {
+    let shared = load_layer(team_file)?.unwrap_or_default();
+    let local = load_layer(local_file)?.unwrap_or_default();
+    Ok(shared.overlay(local))
+}
+
+/// Reads a single settings file. Returns `None` if the file doesn't exist.
+pub fn load_layer(path: &Path) -> Result<Option<DaemonSettings>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read daemon settings at {}", path.display()))?;
+    let settings: DaemonSettings = parse::yaml(path, &contents)?;
+    settings
+        .validate()
+        .with_context(|| format!("Invalid daemon settings at {}", path.display()))?;
+    Ok(Some(settings))
+}
+
+/// Writes a single settings file.
+pub fn save_layer(path: &Path, settings: &DaemonSettings) -> Result<()> {
+    let contents =
+        serde_yml::to_string(settings).context("Failed to serialize daemon settings")?;
+    fs::write(path, contents)
+        .with_context(|| format!("Failed to write daemon settings at {}", path.display()))
+}
+
+/// Returns `key` if `bm daemon config` knows it.
+/// Whether `id` looks like a Slack member id: `U` or `W` and then capital
+/// letters and digits.
+fn is_slack_member_id(id: &str) -> bool {
+    id.len() >= 3
+        && id.starts_with(['U', 'W'])
+        && id.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+fn check_key(key: &str) -> Result<&'static str> {
+    CONFIG_KEYS
+        .iter()
+        .find(|k| **k == key)
+        .copied()
+        .with_context(|| {
+            format!(
+                "Unknown daemon setting '{}'. Settings: {}",
+                key,
+                CONFIG_KEYS.join(", ")
+            )
+        })
+}
+
+/// Compares a configured event name against an incoming event type.
+///
+/// Webhook headers use snake_case (`issue_comment`); the events API uses
+/// PascalCase with an `Event` suffix (`IssueCommentEvent`). `*` matches
+/// every event type.
+pub fn event_matches(configured: &str, event_type: &str) -> bool {
+    if configured == "*" {
+        return true;
+    }
+    let configured = configured.to_lowercase();
+    let normalized = event_type.to_lowercase();
+    let compact = configured.replace('_', "");
+    normalized == configured
+        || normalized == compact
+        || normalized == format!("{}event", compact)
+}
+
+/// Matches a branch against a pattern where `*` stands for any run of
+/// characters, `/` included. Events without a branch match no pattern.
+pub fn branch_matches(pattern: &str, branch: &str) -> bool {
+    if branch.is_empty() {
+        return false;
+    }
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = branch.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+fn parse_hhmm(s: &str) -> Result<chrono::NaiveTime> {
+    chrono::NaiveTime::parse_from_str(s, "%H:%M")
+        .with_context(|| format!("Invalid time '{}' in quiet_hours (expected HH:MM)", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+
+    fn t(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn defaults_when_empty() {
+        let s = DaemonSettings::default();
+        assert_eq!(s.mode(), "webhook");
+        assert_eq!(s.port(), 8484);
+        assert_eq!(s.interval_secs(), 60);
+        assert_eq!(s.debounce_secs(), 5);
+        assert!(s.is_relevant("issues"));
+        assert!(s.is_relevant("PullRequestEvent"));
+        assert!(!s.is_relevant("push"));
+        assert!(s.roles_for_events(&["issues"]).is_none());
+        assert!(!s.in_quiet_hours(t(3, 0)));
+    }
+
+    #[test]
+    fn missing_files_yield_defaults() {
+        let tmp = tempfile::tempdir().unwrap();
+        let s = load_layered(&tmp.path().join("a.yml"), &tmp.path().join("b.yml")).unwrap();
+        assert_eq!(s, DaemonSettings::default());
+    }
+
+    #[test]
+    fn local_override_wins_over_team_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let team = tmp.path().join("daemon.yml");
+        let local = tmp.path().join("daemon-my-team.yml");
+        fs::write(
+            &team,
+            "version: 1\nmode: poll\ninterval_secs: 120\nfilters:\n  events: [issues]\n",
+        )
+        .unwrap();
+        fs::write(&local, "interval_secs: 30\n").unwrap();
+
+        let s = load_layered(&team, &local).unwrap();
+        assert_eq!(s.mode(), "poll");
+        assert_eq!(s.interval_secs(), 30);
+        assert!(s.is_relevant("IssuesEvent"));
+        assert!(!s.is_relevant("issue_comment"));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("daemon.yml");
+        fs::write(&path, "mdoe: poll\n").unwrap();
+        let err = load_layer(&path).unwrap_err();
+        assert!(format!("{:#}", err).contains("mdoe"));
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let s = DaemonSettings {
+            version: Some(SETTINGS_VERSION + 1),
+            ..Default::default()
+        };
+        assert!(s.validate().unwrap_err().to_string().contains("Upgrade bm"));
+    }
+
+    #[test]
+    fn invalid_mode_is_rejected() {
+        let s = DaemonSettings {
+            mode: Some("push".to_string()),
+            ..Default::default()
+        };
+        assert!(s.validate().is_err());
+    }
+
+    #[test]
+    fn config_keys_round_trip() {
+        let mut s = DaemonSettings::default();
+        assert_eq!(s.get("mode").unwrap(), None);
+        assert_eq!(s.effective("mode").unwrap().as_deref(), Some("webhook"));
+        assert_eq!(
+            s.effective("filters.events").unwrap().as_deref(),
+            Some("issues,issue_comment,pull_request")
+        );
+        assert_eq!(s.effective("quiet_hours").unwrap(), None);
+
+        s.set("mode", "poll").unwrap();
+        s.set("filters.events", "issues, push").unwrap();
+        s.set("quiet_hours", "22:00-06:00").unwrap();
+        s.set("chatops.allowed_users", "1,2").unwrap();
+        s.set("chatops.slack_users", "U0123ABCD").unwrap();
+        s.set("log_format", "json").unwrap();
+        assert_eq!(s.mode(), "poll");
+        assert_eq!(s.log_format(), LogFormat::Json);
+        assert!(s.is_relevant("PushEvent"));
+        assert!(s.in_quiet_hours(t(23, 0)));
+        assert_eq!(s.chatops_users(), [1, 2]);
+        assert_eq!(s.chatops_slack_users(), ["U0123ABCD"]);
+        assert!(s.unset("chatops.allowed_users").unwrap());
+        assert_eq!(s.get("chatops.slack_users").unwrap().as_deref(), Some("U0123ABCD"));
+        assert!(s.unset("chatops.slack_users").unwrap());
+        assert_eq!(s.chatops, None);
+        assert_eq!(s.get("filters.events").unwrap().as_deref(), Some("issues,push"));
+        assert_eq!(s.get("quiet_hours").unwrap().as_deref(), Some("22:00-06:00"));
+
+        assert!(s.unset("mode").unwrap());
+        assert!(!s.unset("mode").unwrap());
+        assert_eq!(s.mode(), "webhook");
+    }
+
+    #[test]
+    fn config_set_rejects_bad_values() {
+        let mut s = DaemonSettings::default();
+        assert!(s.set("mode", "push").is_err());
+        assert!(s.set("port", "70000").is_err());
+        assert!(s.set("interval_secs", "0").is_err());
+        assert!(s.set("filters.events", " , ").is_err());
+        assert!(s.set("quiet_hours", "22:00").is_err());
+        assert!(s.set("chatops.allowed_users", "bob").is_err());
+        assert!(s.set("chatops.slack_users", "@bob").is_err());
+        assert!(s.set("log_format", "xml").is_err());
+        assert!(s.set("max_concurrent_members", "0").is_err());
+        assert!(s.set("role_priority", ",").is_err());
+        let err = s.set("routing", "x").unwrap_err().to_string();
+        assert!(err.contains("Unknown daemon setting 'routing'"), "{}", err);
+        assert_eq!(s, DaemonSettings::default());
+    }
+
+    #[test]
+    fn role_priority_ranks_listed_roles_first() {
+        let mut s = DaemonSettings::default();
+        assert_eq!(s.role_rank("dev"), s.role_rank("qe"));
+
+        s.set("role_priority", "lead, dev").unwrap();
+        s.set("max_concurrent_members", "2").unwrap();
+        assert_eq!(s.get("role_priority").unwrap().as_deref(), Some("lead,dev"));
+        assert_eq!(s.max_concurrent_members, Some(2));
+        assert_eq!(s.role_rank("lead"), 0);
+        assert_eq!(s.role_rank("dev"), 1);
+        assert_eq!(s.role_rank("qe"), 2);
+        assert_eq!(s.role_rank("reviewer"), 2);
+    }
+
+    #[test]
+    fn save_layer_round_trips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("daemon.yml");
+        let mut s = DaemonSettings {
+            version: Some(SETTINGS_VERSION),
+            ..Default::default()
+        };
+        s.set("filters.events", "issues").unwrap();
+        save_layer(&path, &s).unwrap();
+        assert_eq!(load_layer(&path).unwrap(), Some(s));
+    }
+
+    #[test]
+    fn routing_selects_roles() {
+        let mut routing = BTreeMap::new();
+        routing.insert("pull_request".to_string(), vec!["qe".to_string()]);
+        routing.insert(
+            "issue_comment".to_string(),
+            vec!["dev".to_string(), "qe".to_string()],
+        );
+        let s = DaemonSettings {
+            routing: Some(routing),
+            ..Default::default()
+        };
+
+        assert_eq!(s.roles_for_events(&["PullRequestEvent"]), Some(vec!["qe".to_string()]));
+        assert_eq!(
+            s.roles_for_events(&["pull_request", "issue_comment"]),
+            Some(vec!["qe".to_string(), "dev".to_string()])
+        );
+        // Any unrouted event means everyone runs
+        assert_eq!(s.roles_for_events(&["pull_request", "issues"]), None);
+    }
+
+    #[test]
+    fn schedules_route_to_their_roles() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("daemon.yml");
+        fs::write(
+            &path,
+            "schedules:\n  - name: grooming\n    cron: \"0 2 * * *\"\n    roles: [po]\n  \
+             - name: sweep\n    cron: \"@hourly\"\nrouting:\n  issues: [dev]\n",
+        )
+        .unwrap();
+        let s = load_layer(&path).unwrap().unwrap();
+
+        assert_eq!(
+            s.roles_for_events(&["schedule:grooming", "issues"]),
+            Some(vec!["po".to_string(), "dev".to_string()])
+        );
+        // A schedule without roles runs everyone, like an unrouted event
+        assert_eq!(s.roles_for_events(&["schedule:sweep"]), None);
+
+        fs::write(
+            &path,
+            "schedules:\n  - {name: a, cron: \"@daily\"}\n  - {name: a, cron: \"@hourly\"}\n",
+        )
+        .unwrap();
+        let err = format!("{:#}", load_layer(&path).unwrap_err());
+        assert!(err.contains("Duplicate schedule name 'a'"), "{}", err);
+        fs::write(&path, "schedules:\n  - {name: a, cron: \"0 25 * * *\"}\n").unwrap();
+        assert!(load_layer(&path).is_err());
+    }
+
+    #[test]
+    fn triggers_add_event_types_with_their_own_conditions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("daemon.yml");
+        fs::write(
+            &path,
+            "filters:\n  triggers:\n    - event: push\n      branches: [main, \"release/*\"]\n\
+             \x20   - event: workflow_run\n      actions: [completed]\n\
+             \x20   - event: issues\n      labels: [kind/story]\n\
+             \x20   - event: release\n",
+        )
+        .unwrap();
+        let s = load_layer(&path).unwrap().unwrap();
+        assert_eq!(
+            s.relevant_events(),
+            vec!["issues", "issue_comment", "pull_request", "push", "workflow_run", "release"]
+        );
+
+        let event = |event: &str, action: &str, branch: &str, labels: &[&str]| TriggerEvent {
+            event: event.to_string(),
+            action: action.to_string(),
+            branch: branch.to_string(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(s.triggers(&event("push", "", "main", &[])));
+        assert!(s.triggers(&event("PushEvent", "", "release/1.2", &[])));
+        assert!(!s.triggers(&event("push", "", "feature/x", &[])));
+        assert!(s.triggers(&event("workflow_run", "completed", "", &[])));
+        assert!(!s.triggers(&event("workflow_run", "requested", "", &[])));
+        assert!(s.triggers(&event("release", "published", "main", &[])));
+        // A default type with a trigger entry must satisfy it
+        assert!(s.triggers(&event("issues", "opened", "", &["kind/story"])));
+        assert!(!s.triggers(&event("issues", "opened", "", &["kind/bug"])));
+        assert!(s.triggers(&event("issue_comment", "created", "", &[])));
+
+        fs::write(&path, "filters:\n  triggers:\n    - event: \"*\"\n").unwrap();
+        assert!(load_layer(&path).is_err());
+        fs::write(&path, "filters:\n  triggers:\n    - event: push\n      tags: [v1]\n").unwrap();
+        assert!(load_layer(&path).is_err());
+    }
+
+    #[test]
+    fn branch_patterns() {
+        assert!(branch_matches("main", "main"));
+        assert!(!branch_matches("main", "main2"));
+        assert!(branch_matches("release/*", "release/1.2"));
+        assert!(!branch_matches("release/*", "hotfix/1.2"));
+        assert!(branch_matches("*", "anything/at/all"));
+        assert!(branch_matches("feat-*-fix", "feat-login-fix"));
+        assert!(!branch_matches("a*a", "a"));
+        assert!(!branch_matches("*", ""));
+    }
+
+    #[test]
+    fn quiet_hours_same_day_window() {
+        let q = QuietHours {
+            start: "12:00".to_string(),
+            end: "13:30".to_string(),
+        };
+        assert!(q.contains(t(12, 0)).unwrap());
+        assert!(q.contains(t(13, 29)).unwrap());
+        assert!(!q.contains(t(13, 30)).unwrap());
+        assert!(!q.contains(t(8, 0)).unwrap());
+    }
+
+    #[test]
+    fn quiet_hours_wrapping_midnight() {
+        let q = QuietHours {
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+        };
+        assert!(q.contains(t(23, 0)).unwrap());
+        assert!(q.contains(t(2, 0)).unwrap());
+        assert!(!q.contains(t(7, 0)).unwrap());
+        assert!(!q.contains(t(15, 0)).unwrap());
+    }
+
+    #[test]
+    fn quiet_hours_bad_time_is_rejected() {
+        let s = DaemonSettings {
+            quiet_hours: Some(QuietHours {
+                start: "25:00".to_string(),
+                end: "07:00".to_string(),
+            }),
+            ..Default::default()
+        };
+        assert!(s.validate().is_err());
+    }
+
+    #[test]
+    fn disk_quota_parses_and_validates() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("daemon.yml");
+        fs::write(&file, "disk_quota:\n  limit: 10Gi\n  action: block\n").unwrap();
+        let quota = load_layer(&file).unwrap().unwrap().disk_quota.unwrap();
+        assert_eq!(quota.limit_bytes().unwrap(), 10 << 30);
+        assert_eq!(quota.action, crate::disk_quota::Action::Block);
+
+        fs::write(&file, "disk_quota:\n  limit: 10GB\n").unwrap();
+        assert!(load_layer(&file).is_err());
+        fs::write(&file, "disk_quota:\n  limit: 10Gi\n  action: kill\n").unwrap();
+        assert!(load_layer(&file).is_err());
+    }
+
+    #[test]
+    fn chatops_users_come_from_the_top_layer() {
+        let tmp = tempfile::tempdir().unwrap();
+        let team = tmp.path().join("daemon.yml");
+        let local = tmp.path().join("daemon-my-team.yml");
+        assert!(DaemonSettings::default().chatops_users().is_empty());
+
+        fs::write(&team, "chatops:\n  allowed_users: [111, 222]\n").unwrap();
+        let s = load_layered(&team, &local).unwrap();
+        assert_eq!(s.chatops_users(), &[111, 222]);
+
+        fs::write(&local, "chatops:\n  allowed_users: [333]\n").unwrap();
+        let s = load_layered(&team, &local).unwrap();
+        assert_eq!(s.chatops_users(), &[333]);
+
+        fs::write(&local, "chatops:\n  users: [333]\n").unwrap();
+        assert!(load_layered(&team, &local).is_err());
+    }
+
+    #[test]
+    fn event_matching_formats() {
+        assert!(event_matches("issue_comment", "issue_comment"));
+        assert!(event_matches("issue_comment", "IssueCommentEvent"));
+        assert!(event_matches("workflow_run", "WorkflowRunEvent"));
+        assert!(!event_matches("issues", "push"));
+        assert!(event_matches("*", "PushEvent"));
+    }
+
+    #[test]
+    fn filter_expression_narrows_relevant_events() {
+        let mut s = DaemonSettings::default();
+        s.set("filters.expr", r#""kind/story" in labels && author != "bot""#)
+            .unwrap();
+        // No events list: the default event types still apply
+        assert_eq!(s.get("filters.events").unwrap(), None);
+        assert!(s.is_relevant("issues"));
+
+        let mut story = TriggerEvent {
+            event: "issues".to_string(),
+            author: "alice".to_string(),
+            labels: vec!["kind/story".to_string()],
+            ..Default::default()
+        };
+        assert!(s.triggers(&story));
+        story.author = "bot".to_string();
+        assert!(!s.triggers(&story));
+        story.author = "alice".to_string();
+        story.event = "push".to_string();
+        assert!(!s.triggers(&story));
+
+        s.set("filters.events", "*").unwrap();
+        assert!(s.triggers(&story));
+        assert!(s.filters.as_ref().unwrap().expr.is_some());
+
+        assert!(s.unset("filters.expr").unwrap());
+        assert!(s.unset("filters.events").unwrap());
+        assert_eq!(s.filters, None);
+
+        let err = format!("{:#}", s.set("filters.expr", "lables == \"x\"").unwrap_err());
+        assert!(err.contains("Unknown field 'lables'"), "{}", err);
+    }
+
+    #[test]
+    fn invalid_filter_expression_in_file_is_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("daemon.yml");
+        fs::write(&path, "filters:\n  expr: 'event == '\n").unwrap();
+        let err = format!("{:#}", load_layer(&path).unwrap_err());
+        assert!(err.contains("Invalid filters.expr"), "{}", err);
+    }
+}
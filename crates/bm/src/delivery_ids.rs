@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+/// How many delivery IDs are remembered. GitHub redelivers within hours,
+/// so this covers far more than a day's deliveries for a typical team.
+pub const CAPACITY: usize = 1000;
+
+/// `X-GitHub-Delivery` IDs of the webhook deliveries the daemon has
+/// handled, most recent last.
+///
+/// GitHub retries a delivery it thinks failed, and an operator can redeliver
+/// one from the repo settings; each would otherwise queue another member
+/// run. Persisted after every change so a restarted daemon still knows what
+/// it handled; once full, the least recently seen ID is dropped.
+#[derive(Debug)]
+pub struct DeliveryIds {
+    path: PathBuf,
+    ids: VecDeque<String>,
+    capacity: usize,
+}
+
+impl DeliveryIds {
+    /// Loads the set at `path`; a missing or unreadable file starts empty.
+    pub fn load(path: &Path) -> DeliveryIds {
+        Self::with_capacity(path, CAPACITY)
+    }
+
+    fn with_capacity(path: &Path, capacity: usize) -> DeliveryIds {
+        let mut ids: VecDeque<String> = fs::read_to_string(path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default();
+        while ids.len() > capacity {
+            ids.pop_front();
+        }
+        DeliveryIds {
+            path: path.to_path_buf(),
+            ids,
+            capacity,
+        }
+    }
+
+    /// Returns true if delivery `id` was handled before, marking it as
+    /// recently seen.
+    pub fn seen(&mut self, id: &str) -> Result<bool> {
+        let Some(at) = self.ids.iter().position(|i| i == id) else {
+            return Ok(false);
+        };
+        if at + 1 != self.ids.len() {
+            let id = self.ids.remove(at).unwrap_or_default();
+            self.ids.push_back(id);
+            self.save()?;
+        }
+        Ok(true)
+    }
+
+    /// Remembers delivery `id` as handled.
+    pub fn insert(&mut self, id: &str) -> Result<()> {
+        if self.seen(id)? {
+            return Ok(());
+        }
+        self.ids.push_back(id.to_string());
+        while self.ids.len() > self.capacity {
+            self.ids.pop_front();
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let contents =
+            serde_json::to_string(&self.ids).context("Failed to serialize delivery IDs")?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, contents)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to replace {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remembers_deliveries_across_loads() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("deliveries.json");
+
+        let mut ids = DeliveryIds::load(&path);
+        assert!(!ids.seen("a1").unwrap());
+        ids.insert("a1").unwrap();
+        assert!(ids.seen("a1").unwrap());
+
+        let mut reloaded = DeliveryIds::load(&path);
+        assert!(reloaded.seen("a1").unwrap());
+        assert!(!reloaded.seen("b2").unwrap());
+    }
+
+    #[test]
+    fn drops_the_least_recently_seen_when_full() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("deliveries.json");
+
+        let mut ids = DeliveryIds::with_capacity(&path, 2);
+        ids.insert("a").unwrap();
+        ids.insert("b").unwrap();
+        // A redelivery of "a" makes "b" the oldest
+        assert!(ids.seen("a").unwrap());
+        ids.insert("c").unwrap();
+
+        let mut reloaded = DeliveryIds::with_capacity(&path, 2);
+        assert!(reloaded.seen("a").unwrap());
+        assert!(reloaded.seen("c").unwrap());
+        assert!(!reloaded.seen("b").unwrap());
+    }
+}
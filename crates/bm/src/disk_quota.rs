@@ -0,0 +1,143 @@
+//! Per-workspace disk quotas (`disk_quota:` in `daemon.yml`).
+//!
+//! A runaway loop that keeps writing build artifacts can fill the disk the
+//! whole team shares. The quota is soft: usage is measured before each launch
+//! (daemon runs and `bm start`) and by `bm status`, and a member over quota is
+//! either launched with a warning or not launched at all.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::formation;
+
+/// What happens when a workspace is over its quota.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    /// Launch anyway and warn.
+    #[default]
+    Warn,
+    /// Don't launch the member until usage drops below the quota.
+    Block,
+}
+
+/// Quota applied to every member workspace of the team.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DiskQuota {
+    /// Size limit (`512Mi`, `10Gi`, `20G`, or bytes).
+    pub limit: String,
+    #[serde(default)]
+    pub action: Action,
+}
+
+impl DiskQuota {
+    /// The limit in bytes.
+    pub fn limit_bytes(&self) -> Result<u64> {
+        formation::parse_memory(&self.limit).map_err(|_| {
+            anyhow!(
+                "Invalid disk quota '{}'. Use bytes or a suffix such as 512Mi, 10Gi, or 20G.",
+                self.limit
+            )
+        })
+    }
+
+    /// Measures `workspace` and returns the overage, if it is over quota.
+    pub fn check(&self, workspace: &Path) -> Result<Option<Overage>> {
+        let limit = self.limit_bytes()?;
+        let used = usage(workspace);
+        Ok((used > limit).then_some(Overage { used, limit }))
+    }
+}
+
+/// A workspace's usage above its quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overage {
+    pub used: u64,
+    pub limit: u64,
+}
+
+impl Overage {
+    /// "12.4 GiB used, quota 10.0 GiB"
+    pub fn describe(&self) -> String {
+        format!("{} used, quota {}", format_bytes(self.used), format_bytes(self.limit))
+    }
+}
+
+/// Disk space allocated under `path`, like `du -s`. Symlinks are not
+/// followed and unreadable entries are skipped.
+pub fn usage(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    let own = allocated(&meta);
+    if !meta.is_dir() {
+        return own;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return own;
+    };
+    own + entries.flatten().map(|e| usage(&e.path())).sum::<u64>()
+}
+
+/// Bytes allocated to one entry: its 512-byte blocks on Unix, its length on
+/// Windows, where std doesn't report allocation.
+#[cfg(unix)]
+fn allocated(meta: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated(meta: &fs::Metadata) -> u64 {
+    meta.len()
+}
+
+/// Formats a byte count with binary units, one decimal place.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_reports_workspaces_over_quota() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path();
+        fs::create_dir_all(ws.join("target/debug")).unwrap();
+        fs::write(ws.join("target/debug/big"), vec![1u8; 256 * 1024]).unwrap();
+        assert!(usage(ws) >= 256 * 1024);
+
+        let quota = |limit: &str| DiskQuota {
+            limit: limit.to_string(),
+            action: Action::Block,
+        };
+        let over = quota("100Ki").check(ws).unwrap().unwrap();
+        assert_eq!(over.limit, 100 * 1024);
+        assert!(over.describe().ends_with("quota 100.0 KiB"), "{}", over.describe());
+        assert_eq!(quota("1Gi").check(ws).unwrap(), None);
+        assert!(quota("10GB").check(ws).is_err());
+    }
+
+    #[test]
+    fn format_bytes_picks_a_readable_unit() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(10 * (1 << 30)), "10.0 GiB");
+    }
+}
@@ -0,0 +1,112 @@
+//! What a `--dry-run` would have done.
+//!
+//! Mutating commands take an `Option<&mut Plan>`: with a plan, each change
+//! they would make is recorded on it instead of carried out, after the same
+//! checks a real run does.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// One change a command would make.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    CreateDir(PathBuf),
+    WriteFile(PathBuf),
+    RemoveFile(PathBuf),
+    Commit { repo: PathBuf, message: String },
+    Push { repo: PathBuf },
+    /// A process started, as its command line and working directory.
+    Run { command: String, dir: PathBuf },
+    /// A change to bm's runtime state (state.json or bm.db), described.
+    UpdateState(String),
+    /// A GitHub API call, described.
+    GitHub(String),
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::CreateDir(p) => write!(f, "create directory {}", p.display()),
+            Step::WriteFile(p) => write!(f, "write {}", p.display()),
+            Step::RemoveFile(p) => write!(f, "remove {}", p.display()),
+            Step::Commit { repo, message } => {
+                write!(f, "commit \"{}\" in {}", message, repo.display())
+            }
+            Step::Push { repo } => write!(f, "push {}", repo.display()),
+            Step::Run { command, dir } => write!(f, "run `{}` in {}", command, dir.display()),
+            Step::UpdateState(change) => write!(f, "update bm state: {}", change),
+            Step::GitHub(call) => write!(f, "GitHub: {}", call),
+        }
+    }
+}
+
+/// The steps a command would take, in order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Plan {
+    pub steps: Vec<Step>,
+    /// Things left alone, with why (a member already running, a role at
+    /// its limit).
+    pub skipped: Vec<String>,
+}
+
+impl Plan {
+    pub fn push(&mut self, step: Step) {
+        self.steps.push(step);
+    }
+
+    pub fn skip(&mut self, note: String) {
+        self.skipped.push(note);
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.steps.is_empty() {
+            writeln!(f, "Nothing to do.")?;
+        } else {
+            writeln!(f, "Would:")?;
+            for step in &self.steps {
+                writeln!(f, "  - {}", step)?;
+            }
+        }
+        if !self.skipped.is_empty() {
+            writeln!(f, "Would skip:")?;
+            for note in &self.skipped {
+                writeln!(f, "  - {}", note)?;
+            }
+        }
+        writeln!(f, "\nDry run: nothing was changed.")
+    }
+}
+
+/// A command's program and arguments as a shell would show them.
+pub fn command_line(cmd: &Command) -> String {
+    std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|a| a.to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_lists_steps_then_skips() {
+        let mut plan = Plan::default();
+        plan.push(Step::CreateDir(PathBuf::from("/ws/arch-01")));
+        plan.push(Step::Commit {
+            repo: PathBuf::from("/ws/team"),
+            message: "feat: hire".into(),
+        });
+        plan.skip("dev-01: already running (PID 7)".into());
+        assert_eq!(
+            plan.to_string(),
+            "Would:\n  - create directory /ws/arch-01\n  - commit \"feat: hire\" in /ws/team\n\
+             Would skip:\n  - dev-01: already running (PID 7)\n\nDry run: nothing was changed.\n"
+        );
+        assert!(Plan::default().to_string().starts_with("Nothing to do."));
+    }
+}
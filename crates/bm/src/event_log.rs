@@ -0,0 +1,221 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Oldest deliveries are dropped once the log holds this many.
+pub const MAX_EVENTS: usize = 100;
+
+/// Replaces the value of every payload key that looks like a credential.
+pub const REDACTED: &str = "[redacted]";
+
+/// Key fragments that mark a payload value as a credential.
+const SECRET_KEYS: &[&str] = &["token", "secret", "password", "authorization", "private_key"];
+
+/// How a delivery's signature checked out.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Signature {
+    Valid,
+    Invalid,
+    /// Accepted without a check (`--insecure-no-signature`).
+    Unchecked,
+}
+
+impl Signature {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Signature::Valid => "valid",
+            Signature::Invalid => "invalid",
+            Signature::Unchecked => "unchecked",
+        }
+    }
+}
+
+/// One webhook delivery, as recorded under `daemon-<team>-events/`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EventRecord {
+    /// The `X-GitHub-Delivery` ID, or a local one when the header is missing.
+    pub id: String,
+    pub received_at: DateTime<Utc>,
+    /// The `X-GitHub-Event` header; empty if missing.
+    pub event: String,
+    pub signature: Signature,
+    /// What the daemon did with the delivery, e.g. `queued` or why it was
+    /// ignored.
+    pub verdict: String,
+    /// The JSON body with credential-like values replaced by [`REDACTED`];
+    /// null if the body was not JSON.
+    pub payload: Value,
+}
+
+/// Replaces credential-like values in `payload`, at any depth.
+pub fn redact(payload: &mut Value) {
+    match payload {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if key == "key" || SECRET_KEYS.iter().any(|s| key.contains(s)) {
+                    if !value.is_null() {
+                        *value = Value::String(REDACTED.to_string());
+                    }
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Records a delivery in the log at `dir`, dropping the oldest beyond
+/// [`MAX_EVENTS`]. The payload is redacted before it is written.
+pub fn record(dir: &Path, mut record: EventRecord) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create event log at {}", dir.display()))?;
+    redact(&mut record.payload);
+    let contents =
+        serde_json::to_string_pretty(&record).context("Failed to serialize event record")?;
+    let path = dir.join(format!("{}.json", file_stem(&record.id)));
+    write_private(&path, &contents)
+        .with_context(|| format!("Failed to write event record at {}", path.display()))?;
+
+    let events = load(dir);
+    for old in &events[..events.len().saturating_sub(MAX_EVENTS)] {
+        let _ = fs::remove_file(dir.join(format!("{}.json", file_stem(&old.id))));
+    }
+    Ok(())
+}
+
+/// Reads the log at `dir`, oldest first. Unreadable records are skipped.
+pub fn load(dir: &Path) -> Vec<EventRecord> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut events: Vec<EventRecord> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "json"))
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter_map(|c| serde_json::from_str(&c).ok())
+        .collect();
+    events.sort_by(|a, b| a.received_at.cmp(&b.received_at).then(a.id.cmp(&b.id)));
+    events
+}
+
+/// Finds a delivery by its ID or a unique prefix of it.
+pub fn find(dir: &Path, id: &str) -> Result<EventRecord> {
+    let mut matches: Vec<EventRecord> =
+        load(dir).into_iter().filter(|e| e.id.starts_with(id)).collect();
+    if let Some(exact) = matches.iter().position(|e| e.id == id) {
+        return Ok(matches.swap_remove(exact));
+    }
+    match matches.len() {
+        0 => bail!("No recorded event '{}'. See `bm daemon events list`.", id),
+        1 => Ok(matches.remove(0)),
+        n => bail!("'{}' matches {} recorded events; give more of the ID", id, n),
+    }
+}
+
+/// Keeps an ID safe to use as a file name.
+fn file_stem(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(contents.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn delivery(n: i64) -> EventRecord {
+        EventRecord {
+            id: format!("d-{:03}", n),
+            received_at: Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+                + chrono::Duration::seconds(n),
+            event: "issues".to_string(),
+            signature: Signature::Valid,
+            verdict: "queued".to_string(),
+            payload: serde_json::json!({"action": "opened"}),
+        }
+    }
+
+    #[test]
+    fn record_round_trips_and_drops_oldest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("events");
+        assert!(load(&dir).is_empty());
+
+        for n in 0..MAX_EVENTS as i64 + 2 {
+            record(&dir, delivery(n)).unwrap();
+        }
+        let events = load(&dir);
+        assert_eq!(events.len(), MAX_EVENTS);
+        assert_eq!(events[0], delivery(2));
+        assert_eq!(events[MAX_EVENTS - 1], delivery(MAX_EVENTS as i64 + 1));
+    }
+
+    #[test]
+    fn payloads_are_redacted() {
+        let mut payload = serde_json::json!({
+            "action": "created",
+            "hook": {"config": {"secret": "s3cret", "url": "https://example.com"}},
+            "installation": {"access_token": "ghs_abc", "id": 7},
+            "key": {"key": "ssh-ed25519 AAAA"},
+            "comments": [{"body": "hi", "Authorization": "Bearer x"}],
+            "password": null
+        });
+        redact(&mut payload);
+        assert_eq!(payload["hook"]["config"]["secret"], REDACTED);
+        assert_eq!(payload["hook"]["config"]["url"], "https://example.com");
+        assert_eq!(payload["installation"]["access_token"], REDACTED);
+        assert_eq!(payload["installation"]["id"], 7);
+        assert_eq!(payload["key"], REDACTED);
+        assert_eq!(payload["comments"][0]["Authorization"], REDACTED);
+        assert_eq!(payload["comments"][0]["body"], "hi");
+        assert!(payload["password"].is_null());
+    }
+
+    #[test]
+    fn find_accepts_unique_prefixes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let mut a = delivery(0);
+        a.id = "abc-1".to_string();
+        let mut b = delivery(1);
+        b.id = "abd-2".to_string();
+        let mut c = delivery(2);
+        c.id = "../evil".to_string();
+        for r in [a.clone(), b.clone(), c.clone()] {
+            record(dir, r).unwrap();
+        }
+        assert_eq!(find(dir, "abc").unwrap(), a);
+        assert_eq!(find(dir, "abd-2").unwrap(), b);
+        assert_eq!(find(dir, "../evil").unwrap(), c);
+        assert!(dir.join("___evil.json").exists());
+        assert!(find(dir, "ab").is_err());
+        assert!(find(dir, "zzz").is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A burst that keeps extending the debounce window still dispatches after
+/// this many windows, so a steady stream of events can't starve members.
+const MAX_WINDOWS: i64 = 6;
+
+/// Relevant events waiting for (or consumed by) a one-shot member run.
+///
+/// Persisted after every change so events survive daemon restarts: pending
+/// events are dispatched after the restart, and events taken by a run that
+/// never completed (e.g. interrupted by `bm daemon stop`) are queued again.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct QueueState {
+    /// Distinct event types waiting to be dispatched, in arrival order.
+    #[serde(default)]
+    pub pending: Vec<String>,
+    /// When the oldest pending event arrived.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_at: Option<DateTime<Utc>>,
+    /// When the newest pending event arrived.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_at: Option<DateTime<Utc>>,
+    /// Event types taken by the run currently in flight.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub in_flight: Vec<String>,
+}
+
+/// The daemon's persisted event queue.
+#[derive(Debug)]
+pub struct EventQueue {
+    path: PathBuf,
+    state: QueueState,
+}
+
+impl EventQueue {
+    /// Loads the queue at `path`, re-queueing events from an interrupted run.
+    pub fn load(path: &Path) -> EventQueue {
+        let mut state = Self::load_snapshot(path);
+        if !state.in_flight.is_empty() {
+            let interrupted = std::mem::take(&mut state.in_flight);
+            for event in interrupted {
+                if !state.pending.contains(&event) {
+                    state.pending.push(event);
+                }
+            }
+            // Already waited once; dispatch without another debounce window.
+            state.first_at = None;
+            state.last_at = None;
+        }
+        EventQueue {
+            path: path.to_path_buf(),
+            state,
+        }
+    }
+
+    /// Reads the queue contents as stored, without re-queueing (for display).
+    pub fn load_snapshot(path: &Path) -> QueueState {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns the current queue contents.
+    pub fn state(&self) -> &QueueState {
+        &self.state
+    }
+
+    /// Adds an event, coalescing it with pending events of the same type.
+    pub fn push(&mut self, event_type: &str, now: DateTime<Utc>) -> Result<()> {
+        if !self.state.pending.iter().any(|e| e == event_type) {
+            self.state.pending.push(event_type.to_string());
+        }
+        if self.state.first_at.is_none() {
+            self.state.first_at = Some(now);
+        }
+        self.state.last_at = Some(now);
+        self.save()
+    }
+
+    /// Returns true if pending events should be dispatched: no event has
+    /// arrived for `debounce_secs`, or the burst has run for too long.
+    pub fn ready(&self, now: DateTime<Utc>, debounce_secs: u64) -> bool {
+        if self.state.pending.is_empty() {
+            return false;
+        }
+        let window = chrono::Duration::seconds(debounce_secs as i64);
+        let quiet = self.state.last_at.is_none_or(|last| now - last >= window);
+        let overdue = self
+            .state
+            .first_at
+            .is_none_or(|first| now - first >= window * MAX_WINDOWS as i32);
+        quiet || overdue
+    }
+
+    /// Moves pending events to the in-flight run and returns them.
+    pub fn take(&mut self) -> Result<Vec<String>> {
+        let events = std::mem::take(&mut self.state.pending);
+        self.state.in_flight = events.clone();
+        self.state.first_at = None;
+        self.state.last_at = None;
+        self.save()?;
+        Ok(events)
+    }
+
+    /// Marks the in-flight run as finished.
+    pub fn complete(&mut self) -> Result<()> {
+        self.state.in_flight.clear();
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let contents =
+            serde_json::to_string_pretty(&self.state).context("Failed to serialize event queue")?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, contents)
+            .with_context(|| format!("Failed to write event queue at {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to write event queue at {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap() + chrono::Duration::seconds(secs)
+    }
+
+    #[test]
+    fn push_coalesces_duplicate_types() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut q = EventQueue::load(&tmp.path().join("queue.json"));
+        q.push("issues", at(0)).unwrap();
+        q.push("issue_comment", at(1)).unwrap();
+        q.push("issues", at(2)).unwrap();
+        assert_eq!(q.state().pending, vec!["issues", "issue_comment"]);
+        assert_eq!(q.state().first_at, Some(at(0)));
+        assert_eq!(q.state().last_at, Some(at(2)));
+    }
+
+    #[test]
+    fn ready_after_quiet_window() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut q = EventQueue::load(&tmp.path().join("queue.json"));
+        assert!(!q.ready(at(0), 5), "empty queue is never ready");
+        q.push("issues", at(0)).unwrap();
+        q.push("pull_request", at(3)).unwrap();
+        assert!(!q.ready(at(6), 5), "window restarts on each event");
+        assert!(q.ready(at(8), 5));
+        assert!(q.ready(at(0), 0), "zero window dispatches immediately");
+    }
+
+    #[test]
+    fn ready_when_burst_runs_too_long() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut q = EventQueue::load(&tmp.path().join("queue.json"));
+        for t in 0..30 {
+            q.push("issue_comment", at(t)).unwrap();
+        }
+        assert!(q.ready(at(30), 5), "continuous burst dispatches after 6 windows");
+    }
+
+    #[test]
+    fn take_and_complete_round_trip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("queue.json");
+        let mut q = EventQueue::load(&path);
+        q.push("issues", at(0)).unwrap();
+        assert_eq!(q.take().unwrap(), vec!["issues"]);
+        assert!(q.state().pending.is_empty());
+        assert_eq!(q.state().in_flight, vec!["issues"]);
+        q.complete().unwrap();
+        assert_eq!(EventQueue::load(&path).state(), &QueueState::default());
+    }
+
+    #[test]
+    fn load_requeues_interrupted_run() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("queue.json");
+        let mut q = EventQueue::load(&path);
+        q.push("issues", at(0)).unwrap();
+        q.take().unwrap();
+        q.push("pull_request", at(10)).unwrap();
+
+        // Daemon restarts before the run completes
+        let q = EventQueue::load(&path);
+        assert_eq!(q.state().pending, vec!["pull_request", "issues"]);
+        assert!(q.state().in_flight.is_empty());
+        assert!(q.ready(at(10), 5), "re-queued events don't wait again");
+    }
+}
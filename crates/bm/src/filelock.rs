@@ -0,0 +1,210 @@
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+
+/// How long to wait for another bm process to release a lock.
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// An exclusive lock held on a sidecar `<file>.lock`: an advisory `flock` on
+/// Unix, a `LockFileEx` byte-range lock on Windows.
+///
+/// The lock lives on a separate file because the protected files are replaced
+/// via rename, which would orphan a lock held on the old inode. The lock is
+/// released when the guard is dropped (or the process exits).
+#[derive(Debug)]
+pub struct FileLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Acquires the lock guarding `target`, waiting up to [`LOCK_TIMEOUT`].
+    pub fn acquire(target: &Path) -> Result<FileLock> {
+        Self::acquire_with_timeout(target, LOCK_TIMEOUT)
+    }
+
+    /// Acquires the lock guarding `target`, waiting up to `timeout`.
+    pub fn acquire_with_timeout(target: &Path, timeout: Duration) -> Result<FileLock> {
+        let path = lock_path(target);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let locked =
+                imp::try_lock(&file).with_context(|| format!("Failed to lock {}", path.display()))?;
+            if locked {
+                return Ok(FileLock { file, path });
+            }
+            if Instant::now() >= deadline {
+                bail!(
+                    "Timed out after {}s waiting for {}: another bm process holds the lock. \
+                     Retry once it finishes.",
+                    timeout.as_secs(),
+                    path.display()
+                );
+            }
+            thread::sleep(RETRY_INTERVAL);
+        }
+    }
+
+    /// Returns the path of the lock file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        imp::unlock(&self.file);
+    }
+}
+
+/// Returns the sidecar lock file path for `target` (e.g. `state.json.lock`).
+fn lock_path(target: &Path) -> PathBuf {
+    let mut name = target
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    name.push(".lock");
+    target.with_file_name(name)
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::fs::File;
+    use std::io;
+    use std::os::unix::io::AsRawFd;
+
+    /// Takes the lock without waiting. Returns false if someone else holds it.
+    pub fn try_lock(file: &File) -> io::Result<bool> {
+        // Safety: flock on a valid, owned file descriptor.
+        let rc = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+        if rc == 0 {
+            return Ok(true);
+        }
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() == Some(libc::EWOULDBLOCK) {
+            Ok(false)
+        } else {
+            Err(err)
+        }
+    }
+
+    pub fn unlock(file: &File) {
+        // Safety: unlocking the descriptor we locked in `try_lock`.
+        unsafe {
+            libc::flock(file.as_raw_fd(), libc::LOCK_UN);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::fs::File;
+    use std::io;
+    use std::os::windows::io::AsRawHandle;
+
+    use windows_sys::Win32::Foundation::{ERROR_LOCK_VIOLATION, HANDLE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        LockFileEx, UnlockFileEx, LOCKFILE_EXCLUSIVE_LOCK, LOCKFILE_FAIL_IMMEDIATELY,
+    };
+    use windows_sys::Win32::System::IO::OVERLAPPED;
+
+    /// Takes the lock without waiting. Returns false if someone else holds it.
+    pub fn try_lock(file: &File) -> io::Result<bool> {
+        // Safety: locking the whole range of a valid, owned handle; the
+        // zeroed OVERLAPPED sets the range's start to offset 0.
+        let ok = unsafe {
+            let mut overlapped: OVERLAPPED = std::mem::zeroed();
+            LockFileEx(
+                file.as_raw_handle() as HANDLE,
+                LOCKFILE_EXCLUSIVE_LOCK | LOCKFILE_FAIL_IMMEDIATELY,
+                0,
+                u32::MAX,
+                u32::MAX,
+                &mut overlapped,
+            )
+        };
+        if ok != 0 {
+            return Ok(true);
+        }
+        let err = io::Error::last_os_error();
+        if err.raw_os_error() == Some(ERROR_LOCK_VIOLATION as i32) {
+            Ok(false)
+        } else {
+            Err(err)
+        }
+    }
+
+    pub fn unlock(file: &File) {
+        // Safety: unlocking the range we locked in `try_lock`.
+        unsafe {
+            let mut overlapped: OVERLAPPED = std::mem::zeroed();
+            UnlockFileEx(
+                file.as_raw_handle() as HANDLE,
+                0,
+                u32::MAX,
+                u32::MAX,
+                &mut overlapped,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_path_appends_suffix() {
+        assert_eq!(
+            lock_path(Path::new("/tmp/x/state.json")),
+            PathBuf::from("/tmp/x/state.json.lock")
+        );
+    }
+
+    #[test]
+    fn acquire_creates_lock_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let target = tmp.path().join("state.json");
+        let lock = FileLock::acquire(&target).unwrap();
+        assert!(lock.path().exists());
+    }
+
+    #[test]
+    fn second_acquire_times_out_while_held() {
+        let tmp = tempfile::tempdir().unwrap();
+        let target = tmp.path().join("config.yml");
+        let _held = FileLock::acquire(&target).unwrap();
+
+        // Both lock kinds are per open file (description on Unix, handle on
+        // Windows), so a second open in the same process contends just like
+        // another process would.
+        let err = FileLock::acquire_with_timeout(&target, Duration::from_millis(100))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("another bm process holds the lock"), "{}", err);
+    }
+
+    #[test]
+    fn lock_released_on_drop() {
+        let tmp = tempfile::tempdir().unwrap();
+        let target = tmp.path().join("state.json");
+        drop(FileLock::acquire(&target).unwrap());
+        FileLock::acquire_with_timeout(&target, Duration::from_millis(100)).unwrap();
+    }
+}
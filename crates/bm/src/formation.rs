@@ -1,9 +1,14 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::parse;
+use crate::priority::{self, IoClass, Priority};
+use crate::profile;
+
 /// Formation config parsed from `formation.yml`.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FormationConfig {
@@ -19,6 +24,99 @@ pub struct FormationConfig {
     /// Formation manager configuration (only for non-local types).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub manager: Option<ManagerConfig>,
+
+    /// Resource limits keyed by role name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resources: BTreeMap<String, RoleResources>,
+
+    /// Binaries the formation needs on PATH beyond those its type implies
+    /// (e.g. `podman`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires: Vec<String>,
+}
+
+/// Resource limits for every member of a role, or for one member.
+///
+/// `cpu` and `memory` use Kubernetes quantity notation (`500m`, `1.5`,
+/// `512Mi`, `2G`) so the same values work for every formation type. `nice`
+/// and `ionice` only apply to local members.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct RoleResources {
+    /// CPU limit in cores.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<String>,
+    /// Memory limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+    /// Maximum number of this role's members running at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_loops: Option<u32>,
+    /// CPU niceness, 0 to 19.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+    /// I/O scheduling class: `idle`, `best-effort`, or `best-effort:<0-7>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ionice: Option<String>,
+}
+
+impl RoleResources {
+    /// CPU limit in cores, if set.
+    pub fn cpu_cores(&self) -> Result<Option<f64>> {
+        self.cpu.as_deref().map(parse_cpu).transpose()
+    }
+
+    /// Memory limit in bytes, if set.
+    pub fn memory_bytes(&self) -> Result<Option<u64>> {
+        self.memory.as_deref().map(parse_memory).transpose()
+    }
+
+    /// Scheduling priority from `nice` and `ionice`.
+    pub fn priority(&self) -> Result<Priority> {
+        Ok(Priority {
+            nice: self.nice.map(priority::check_nice).transpose()?,
+            io: self.ionice.as_deref().map(IoClass::parse).transpose()?,
+        })
+    }
+}
+
+/// Parses a CPU quantity (`2`, `1.5`, `500m`) into cores.
+pub fn parse_cpu(value: &str) -> Result<f64> {
+    let cores = match value.strip_suffix('m') {
+        Some(milli) => milli.parse::<f64>().map(|m| m / 1000.0),
+        None => value.parse::<f64>(),
+    };
+    match cores {
+        Ok(c) if c > 0.0 && c.is_finite() => Ok(c),
+        _ => bail!(
+            "Invalid CPU limit '{}'. Use cores (e.g. 2, 1.5) or millicores (e.g. 500m).",
+            value
+        ),
+    }
+}
+
+/// Parses a memory quantity (`512Mi`, `2Gi`, `1G`, `1048576`) into bytes.
+pub fn parse_memory(value: &str) -> Result<u64> {
+    const UNITS: &[(&str, u64)] = &[
+        ("Ki", 1 << 10),
+        ("Mi", 1 << 20),
+        ("Gi", 1 << 30),
+        ("Ti", 1 << 40),
+        ("K", 1_000),
+        ("M", 1_000_000),
+        ("G", 1_000_000_000),
+        ("T", 1_000_000_000_000),
+    ];
+    let (number, multiplier) = UNITS
+        .iter()
+        .find_map(|(suffix, m)| value.strip_suffix(suffix).map(|n| (n, *m)))
+        .unwrap_or((value, 1));
+    match number.parse::<u64>() {
+        Ok(n) if n > 0 => Ok(n.saturating_mul(multiplier)),
+        _ => bail!(
+            "Invalid memory limit '{}'. Use bytes or a suffix such as 512Mi, 2Gi, or 1G.",
+            value
+        ),
+    }
 }
 
 /// K8s-specific formation settings.
@@ -47,6 +145,57 @@ impl FormationConfig {
     pub fn is_local(&self) -> bool {
         self.formation_type == "local"
     }
+
+    /// Binaries this formation needs on PATH: those its type implies, then
+    /// its `requires`.
+    pub fn required_tools(&self) -> Vec<String> {
+        let implied: &[&str] = match self.formation_type.as_str() {
+            "k8s" => &["kubectl"],
+            _ => &[],
+        };
+        let mut tools: Vec<String> = implied.iter().map(|t| t.to_string()).collect();
+        for tool in &self.requires {
+            if !tools.contains(tool) {
+                tools.push(tool.clone());
+            }
+        }
+        tools
+    }
+
+    /// Fails, with install hints, if any required binary is missing from PATH.
+    pub fn check_tools(&self) -> Result<()> {
+        let missing: Vec<String> = self
+            .required_tools()
+            .into_iter()
+            .filter(|tool| which::which(tool).is_err())
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let hints: Vec<String> = missing
+            .iter()
+            .map(|tool| match install_hint(tool) {
+                Some(url) => format!("  - {} (install: {})", tool, url),
+                None => format!("  - {}", tool),
+            })
+            .collect();
+        bail!(
+            "Formation '{}' needs these tools on PATH, which are missing:\n{}",
+            self.name,
+            hints.join("\n")
+        );
+    }
+}
+
+/// Where to get a tool formations commonly need.
+fn install_hint(tool: &str) -> Option<&'static str> {
+    match tool {
+        "kubectl" => Some("https://kubernetes.io/docs/tasks/tools/"),
+        "podman" => Some("https://podman.io/docs/installation"),
+        "docker" => Some("https://docs.docker.com/get-docker/"),
+        "kind" => Some("https://kind.sigs.k8s.io/docs/user/quick-start/#installation"),
+        _ => None,
+    }
 }
 
 /// Resolves the formations directory for a team repo.
@@ -82,16 +231,36 @@ pub fn load(team_repo: &Path, formation_name: &str) -> Result<FormationConfig> {
         )
     })?;
 
-    let config: FormationConfig = serde_yml::from_str(&contents).with_context(|| {
-        format!(
-            "Failed to parse formation config at {}",
-            config_path.display()
-        )
-    })?;
+    let config: FormationConfig = parse::yaml(&config_path, &contents)?;
+    for (role, resources) in &config.resources {
+        resources
+            .cpu_cores()
+            .and(resources.memory_bytes())
+            .and(resources.priority())
+            .with_context(|| format!("In {} (role '{}')", config_path.display(), role))?;
+    }
 
     Ok(config)
 }
 
+/// Finds the resource limits that apply to a member directory
+/// (`{role}-{name}`): an entry for the member itself, otherwise the longest
+/// matching role.
+pub fn resources_for<'a>(
+    resources: &'a BTreeMap<String, RoleResources>,
+    member_dir: &str,
+) -> Option<(&'a str, &'a RoleResources)> {
+    resources
+        .iter()
+        .filter(|(role, _)| {
+            member_dir
+                .strip_prefix(role.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+        })
+        .max_by_key(|(role, _)| role.len())
+        .map(|(role, r)| (role.as_str(), r))
+}
+
 /// Lists available formation names in the team repo.
 pub fn list_formations(team_repo: &Path) -> Result<Vec<String>> {
     let dir = formations_dir(team_repo);
@@ -113,6 +282,47 @@ pub fn list_formations(team_repo: &Path) -> Result<Vec<String>> {
     Ok(names)
 }
 
+/// Checks that `name` is a formation a team on `profile_name` can use,
+/// telling a formation the profile ships but the team repo lacks (a repo
+/// created before the profile had it) apart from one that doesn't exist.
+pub fn check_available(team_repo: &Path, profile_name: &str, name: &str) -> Result<()> {
+    if formations_dir(team_repo).join(name).is_dir() {
+        return Ok(());
+    }
+    let in_repo = list_formations(team_repo).unwrap_or_default();
+    let in_profile = profile::list_formations(profile_name);
+    if in_profile.iter().any(|f| f == name) {
+        bail!(
+            "Formation '{}' ships with the '{}' profile but is missing from the team repo, \
+             which predates it. Copy it in with `bm profiles export {} <dir>`, then commit \
+             <dir>/formations/{} to formations/{} in the team repo.",
+            name,
+            profile_name,
+            profile_name,
+            name,
+            name
+        );
+    }
+    let mut known = in_repo;
+    for f in in_profile {
+        if !known.contains(&f) {
+            known.push(f);
+        }
+    }
+    if known.is_empty() {
+        bail!(
+            "Formation '{}' not found. Neither the team repo nor the '{}' profile has formations.",
+            name,
+            profile_name
+        );
+    }
+    bail!(
+        "Formation '{}' not found. Known formations: {}",
+        name,
+        known.join(", ")
+    );
+}
+
 /// Resolves the formation to use for `bm start`.
 ///
 /// Resolution order:
@@ -331,6 +541,80 @@ manager:
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn parse_cpu_accepts_cores_and_millicores() {
+        assert_eq!(parse_cpu("2").unwrap(), 2.0);
+        assert_eq!(parse_cpu("1.5").unwrap(), 1.5);
+        assert_eq!(parse_cpu("500m").unwrap(), 0.5);
+        for bad in ["", "0", "-1", "two", "5x"] {
+            assert!(parse_cpu(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn parse_memory_accepts_binary_and_decimal_suffixes() {
+        assert_eq!(parse_memory("512Mi").unwrap(), 512 << 20);
+        assert_eq!(parse_memory("2Gi").unwrap(), 2 << 30);
+        assert_eq!(parse_memory("1G").unwrap(), 1_000_000_000);
+        assert_eq!(parse_memory("4096").unwrap(), 4096);
+        for bad in ["", "0Mi", "1.5Gi", "2GB", "lots"] {
+            assert!(parse_memory(bad).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn load_validates_role_resources() {
+        let tmp = tempfile::tempdir().unwrap();
+        create_formation(
+            tmp.path(),
+            "local",
+            "name: local\ndescription: Local\ntype: local\nresources:\n  dev:\n    cpu: \"1.5\"\n    memory: 2Gi\n    max_concurrent_loops: 2\n",
+        );
+        let config = load(tmp.path(), "local").unwrap();
+        let dev = &config.resources["dev"];
+        assert_eq!(dev.cpu_cores().unwrap(), Some(1.5));
+        assert_eq!(dev.memory_bytes().unwrap(), Some(2 << 30));
+        assert_eq!(dev.max_concurrent_loops, Some(2));
+
+        create_formation(
+            tmp.path(),
+            "bad",
+            "name: bad\ndescription: Bad\ntype: local\nresources:\n  dev:\n    memory: 2GB\n",
+        );
+        let err = format!("{:#}", load(tmp.path(), "bad").unwrap_err());
+        assert!(err.contains("role 'dev'") && err.contains("2GB"), "{}", err);
+
+        create_formation(
+            tmp.path(),
+            "nice",
+            "name: nice\ndescription: Nice\ntype: local\nresources:\n  dev:\n    nice: 10\n    ionice: idle\n  qe:\n    ionice: realtime\n",
+        );
+        let err = format!("{:#}", load(tmp.path(), "nice").unwrap_err());
+        assert!(err.contains("role 'qe'") && err.contains("realtime"), "{}", err);
+    }
+
+    #[test]
+    fn resources_for_matches_longest_role_prefix() {
+        let mut resources = BTreeMap::new();
+        resources.insert("human".to_string(), RoleResources::default());
+        resources.insert(
+            "human-assistant".to_string(),
+            RoleResources {
+                max_concurrent_loops: Some(1),
+                ..Default::default()
+            },
+        );
+        let (role, r) = resources_for(&resources, "human-assistant-bob").unwrap();
+        assert_eq!((role, r.max_concurrent_loops), ("human-assistant", Some(1)));
+        assert_eq!(resources_for(&resources, "human-alice").unwrap().0, "human");
+        assert!(resources_for(&resources, "humanoid-01").is_none());
+
+        // A member's own entry wins over its role's
+        resources.insert("human-alice".to_string(), RoleResources::default());
+        assert_eq!(resources_for(&resources, "human-alice").unwrap().0, "human-alice");
+        assert_eq!(resources_for(&resources, "human-alicia").unwrap().0, "human");
+    }
+
     #[test]
     fn k8s_namespace_prefix_default() {
         let tmp = tempfile::tempdir().unwrap();
@@ -348,4 +632,49 @@ k8s:
         let k8s = config.k8s.unwrap();
         assert_eq!(k8s.namespace_prefix, "botminter");
     }
+
+    #[test]
+    fn required_tools_combine_type_and_requires() {
+        let tmp = tempfile::tempdir().unwrap();
+        let content = r#"
+name: k8s
+description: K8s
+type: k8s
+requires: [kind, kubectl, bm-no-such-tool]
+k8s:
+  context: kind-test
+  image: test:latest
+"#;
+        create_formation(tmp.path(), "k8s", content);
+
+        let config = load(tmp.path(), "k8s").unwrap();
+        assert_eq!(
+            config.required_tools(),
+            vec!["kubectl", "kind", "bm-no-such-tool"]
+        );
+        let err = config.check_tools().unwrap_err().to_string();
+        assert!(err.contains("- bm-no-such-tool"), "{}", err);
+    }
+
+    #[test]
+    fn check_available_points_at_profile_formations() {
+        let tmp = tempfile::tempdir().unwrap();
+        create_formation(
+            tmp.path(),
+            "local",
+            "name: local\ndescription: Local\ntype: local\n",
+        );
+
+        assert!(check_available(tmp.path(), "scrum", "local").is_ok());
+
+        let err = check_available(tmp.path(), "scrum", "k8s")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("bm profiles export scrum"), "{}", err);
+
+        let err = check_available(tmp.path(), "scrum", "nope")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Known formations: local, k8s"), "{}", err);
+    }
 }
@@ -0,0 +1,617 @@
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, GitHubApp, TeamEntry};
+use crate::parse;
+use crate::procutil;
+use crate::state;
+
+/// Refresh member tokens once they have less than this many seconds left.
+/// Installation tokens live for one hour.
+pub const REFRESH_MARGIN_SECS: i64 = 15 * 60;
+
+/// How often background refreshers check member tokens.
+pub const REFRESH_CHECK_SECS: u64 = 60;
+
+const TOKEN_FILE: &str = "token.json";
+const HOSTS_FILE: &str = "hosts.yml";
+
+/// An installation access token as returned by the GitHub API.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallationToken {
+    pub token: String,
+    /// ISO 8601 expiry timestamp.
+    pub expires_at: String,
+    /// Repos (`owner/name`) the token is scoped to.
+    #[serde(default)]
+    pub repositories: Vec<String>,
+}
+
+impl InstallationToken {
+    /// Returns true if the token expires within [`REFRESH_MARGIN_SECS`] of `now`.
+    pub fn needs_refresh(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        match chrono::DateTime::parse_from_rfc3339(&self.expires_at) {
+            Ok(exp) => (exp.with_timezone(&chrono::Utc) - now).num_seconds() < REFRESH_MARGIN_SECS,
+            Err(_) => true,
+        }
+    }
+}
+
+/// How a member process authenticates to GitHub.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemberAuth {
+    /// The team's shared token, passed as `GH_TOKEN`.
+    Token(String),
+    /// A per-member gh config directory holding a scoped installation token.
+    /// The directory is refreshed in place, so long runs keep working.
+    ConfigDir(PathBuf),
+}
+
+impl MemberAuth {
+    /// Returns the environment changes for a member command: `Some` sets a
+    /// variable, `None` removes it.
+    pub fn env_vars(&self) -> Vec<(&'static str, Option<String>)> {
+        match self {
+            MemberAuth::Token(token) => vec![("GH_TOKEN", Some(token.clone()))],
+            MemberAuth::ConfigDir(dir) => vec![
+                ("GH_CONFIG_DIR", Some(dir.to_string_lossy().to_string())),
+                ("GH_TOKEN", None),
+            ],
+        }
+    }
+
+    /// Sets the environment on a member command.
+    pub fn apply(&self, cmd: &mut Command) {
+        for (name, value) in self.env_vars() {
+            match value {
+                Some(value) => cmd.env(name, value),
+                None => cmd.env_remove(name),
+            };
+        }
+    }
+}
+
+/// Resolves the GitHub auth for a member.
+///
+/// With a GitHub App configured, mints (or reuses) an installation token scoped
+/// to the repos the member needs. Otherwise falls back to the team `gh_token`.
+/// Returns `None` if the team has neither.
+pub fn resolve_member_auth(team: &TeamEntry, member: &str) -> Result<Option<MemberAuth>> {
+    if let Some(ref app) = team.credentials.github_app {
+        let dir = ensure_member_token(team, app, member)?;
+        return Ok(Some(MemberAuth::ConfigDir(dir)));
+    }
+    Ok(team.credentials.gh_token.clone().map(MemberAuth::Token))
+}
+
+/// Supplies the token for a team's own API calls (the daemon's event polling).
+///
+/// With a GitHub App configured, mints an installation token scoped to the team's
+/// repos and keeps it in memory, minting a new one shortly before it expires.
+/// Otherwise hands out the team `gh_token`.
+#[derive(Debug, Default)]
+pub struct TeamTokenSource {
+    cached: Option<InstallationToken>,
+}
+
+impl TeamTokenSource {
+    /// Returns the token to use for `team`, or `None` if it has no credentials.
+    pub fn token(&mut self, team: &TeamEntry) -> Result<Option<String>> {
+        let app = match team.credentials.github_app {
+            Some(ref app) => app,
+            None => return Ok(team.credentials.gh_token.clone()),
+        };
+        let repos = team.repos();
+        if let Some(ref cached) = self.cached {
+            if !cached.needs_refresh(chrono::Utc::now()) && cached.repositories == repos {
+                return Ok(Some(cached.token.clone()));
+            }
+        }
+        let token = mint_installation_token(app, &repos)
+            .with_context(|| format!("Failed to mint GitHub App token for team '{}'", team.name))?;
+        let value = token.token.clone();
+        self.cached = Some(token);
+        Ok(Some(value))
+    }
+}
+
+/// Returns the gh config directory for a member (`~/.botminter/gh/<team>/<member>/`).
+pub fn member_gh_dir(team_name: &str, member: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join("gh").join(team_name).join(member))
+}
+
+/// Ensures the member has a valid scoped token on disk, minting one if needed.
+/// Returns the member's gh config directory.
+pub fn ensure_member_token(team: &TeamEntry, app: &GitHubApp, member: &str) -> Result<PathBuf> {
+    let dir = member_gh_dir(&team.name, member)?;
+    let repos = member_repos(team, member)?;
+    if let Some(existing) = read_token(&dir) {
+        if !existing.needs_refresh(chrono::Utc::now()) && existing.repositories == repos {
+            return Ok(dir);
+        }
+    }
+    let token = mint_installation_token(app, &repos)
+        .with_context(|| format!("Failed to mint GitHub App token for {}", member))?;
+    write_token(&dir, &token)?;
+    Ok(dir)
+}
+
+/// Outcome of refreshing a team's member tokens.
+#[derive(Debug, Default)]
+pub struct Refresh {
+    /// Members whose tokens were refreshed, sorted.
+    pub refreshed: Vec<String>,
+    /// Members whose tokens couldn't be refreshed, sorted, with the error.
+    pub failed: Vec<(String, anyhow::Error)>,
+}
+
+/// Refreshes every member token of a team that is close to expiry. A member
+/// whose refresh fails is recorded in [`Refresh::failed`] and doesn't hold up
+/// the rest.
+pub fn refresh_team_tokens(team_name: &str) -> Result<Refresh> {
+    let cfg = config::load()?;
+    let team = config::resolve_team(&cfg, Some(team_name))?;
+    let app = match team.credentials.github_app {
+        Some(ref app) => app,
+        None => return Ok(Refresh::default()),
+    };
+
+    let base = config::config_dir()?.join("gh").join(team_name);
+    if !base.is_dir() {
+        return Ok(Refresh::default());
+    }
+
+    let now = chrono::Utc::now();
+    let mut members: Vec<(String, PathBuf)> = fs::read_dir(&base)?
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|entry| (entry.file_name().to_string_lossy().to_string(), entry.path()))
+        .collect();
+    members.sort();
+
+    let mut refresh = Refresh::default();
+    for (member, dir) in members {
+        let stale = match read_token(&dir) {
+            Some(t) => t.needs_refresh(now),
+            None => true,
+        };
+        if !stale {
+            continue;
+        }
+        match ensure_member_token(team, app, &member) {
+            Ok(_) => refresh.refreshed.push(member),
+            Err(e) => refresh.failed.push((member, e)),
+        }
+    }
+    Ok(refresh)
+}
+
+/// Returns the PID file path for a team's token refresher.
+pub fn refresher_pid_path(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("token-refresh-{}.pid", team_name)))
+}
+
+/// Spawns the background token refresher for members started by `bm start`,
+/// unless one is already running for the team.
+pub fn spawn_refresher(team_name: &str) -> Result<()> {
+    let pid_file = refresher_pid_path(team_name)?;
+    if let Ok(pid_str) = fs::read_to_string(&pid_file) {
+        if let Ok(pid) = pid_str.trim().parse::<u32>() {
+            if procutil::is_alive(pid) {
+                return Ok(());
+            }
+        }
+    }
+
+    let exe = std::env::current_exe().context("Failed to determine bm executable path")?;
+    let child = Command::new(exe)
+        .args(["token-refresh", "--team", team_name])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to spawn token refresher")?;
+    fs::write(&pid_file, child.id().to_string())?;
+    config::set_mode(&pid_file, 0o600)?;
+    Ok(())
+}
+
+/// Runs the token refresher loop (hidden `bm token-refresh` command).
+///
+/// Keeps member tokens fresh while any member of the team is running and exits
+/// once they have all stopped.
+pub fn run_refresher(team_name: &str) -> Result<()> {
+    let prefix = format!("{}/", team_name);
+    loop {
+        let runtime = state::load().unwrap_or_default();
+        let any_alive = runtime
+            .members
+            .iter()
+            .any(|(key, rt)| key.starts_with(&prefix) && procutil::is_alive(rt.pid));
+        if !any_alive {
+            break;
+        }
+        match refresh_team_tokens(team_name) {
+            Ok(refresh) => {
+                for (member, e) in &refresh.failed {
+                    eprintln!("Token refresh failed for {}: {:#}", member, e);
+                }
+            }
+            Err(e) => eprintln!("Token refresh failed for team '{}': {:#}", team_name, e),
+        }
+        thread::sleep(Duration::from_secs(REFRESH_CHECK_SECS));
+    }
+    let _ = fs::remove_file(refresher_pid_path(team_name)?);
+    Ok(())
+}
+
+/// Returns the repos (`owner/name`) a member needs: the team's repos plus the
+/// project forks it works on.
+///
+/// A member's `botminter.yml` may list `projects:` to narrow the set; without
+/// it the member gets every project in the team manifest.
+pub fn member_repos(team: &TeamEntry, member: &str) -> Result<Vec<String>> {
+    let team_repo = team.path.join("team");
+    let mut repos = team.repos();
+
+    let manifest_path = team_repo.join("botminter.yml");
+    let projects: Vec<crate::profile::ProjectDef> = match fs::read_to_string(&manifest_path) {
+        Ok(contents) => {
+            let manifest: serde_yml::Value = parse::yaml(&manifest_path, &contents)?;
+            serde_yml::from_value(manifest["projects"].clone()).unwrap_or_default()
+        }
+        Err(_) => Vec::new(),
+    };
+
+    let assigned = read_member_projects(&team_repo.join("team").join(member));
+    for project in &projects {
+        if let Some(ref names) = assigned {
+            if !names.contains(&project.name) {
+                continue;
+            }
+        }
+        match parse_github_repo(&project.fork_url) {
+            Some(repo) if !repos.contains(&repo) => repos.push(repo),
+            Some(_) => {}
+            None => bail!(
+                "Project '{}' fork URL '{}' is not a GitHub repo; \
+                 GitHub App tokens can only be scoped to GitHub repos",
+                project.name,
+                project.fork_url
+            ),
+        }
+    }
+    Ok(repos)
+}
+
+/// Reads the optional `projects:` list from a member's botminter.yml.
+fn read_member_projects(member_dir: &Path) -> Option<Vec<String>> {
+    #[derive(Deserialize)]
+    struct MemberProjects {
+        projects: Option<Vec<String>>,
+    }
+    let contents = fs::read_to_string(member_dir.join("botminter.yml")).ok()?;
+    serde_yml::from_str::<MemberProjects>(&contents).ok()?.projects
+}
+
+/// Extracts `owner/name` from a GitHub HTTPS or SSH URL.
+pub fn parse_github_repo(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("git@github.com:"))
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))?;
+    let rest = rest.trim_end_matches('/');
+    let rest = rest.strip_suffix(".git").unwrap_or(rest);
+    let mut parts = rest.split('/');
+    let owner = parts.next().filter(|s| !s.is_empty())?;
+    let name = parts.next().filter(|s| !s.is_empty())?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(format!("{}/{}", owner, name))
+}
+
+/// Builds the RS256-signed JWT that authenticates as the GitHub App.
+/// Signing is delegated to `openssl` so the key never leaves disk.
+pub fn app_jwt(app: &GitHubApp) -> Result<String> {
+    if !app.private_key_path.exists() {
+        bail!(
+            "GitHub App private key not found at {}",
+            app.private_key_path.display()
+        );
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256","typ":"JWT"}"#);
+    // Backdate to tolerate clock drift; GitHub caps lifetime at 10 minutes.
+    let claims = serde_json::json!({ "iat": now - 60, "exp": now + 540, "iss": app.app_id });
+    let payload = URL_SAFE_NO_PAD.encode(claims.to_string());
+    let signing_input = format!("{}.{}", header, payload);
+
+    let mut child = Command::new("openssl")
+        .args(["dgst", "-sha256", "-sign"])
+        .arg(&app.private_key_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run openssl (required to sign GitHub App JWTs)")?;
+    child
+        .stdin
+        .take()
+        .context("Failed to open openssl stdin")?
+        .write_all(signing_input.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("openssl failed to sign GitHub App JWT: {}", stderr.trim());
+    }
+
+    Ok(format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(&output.stdout)))
+}
+
+/// Mints an installation token restricted to `repos` (`owner/name`).
+pub fn mint_installation_token(app: &GitHubApp, repos: &[String]) -> Result<InstallationToken> {
+    let names = repo_names_for_installation(repos)?;
+    let jwt = app_jwt(app)?;
+    let body = serde_json::json!({ "repositories": names });
+
+    let mut child = Command::new("gh")
+        .args([
+            "api",
+            "--method",
+            "POST",
+            &format!("/app/installations/{}/access_tokens", app.installation_id),
+            "--input",
+            "-",
+        ])
+        // gh sends GH_TOKEN as the Authorization header. Passing the JWT there
+        // rather than as an `-H` argument keeps it out of `ps`.
+        .env("GH_TOKEN", &jwt)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run gh api")?;
+    child
+        .stdin
+        .take()
+        .context("Failed to open gh stdin")?
+        .write_all(body.to_string().as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("GitHub rejected the installation token request: {}", stderr.trim());
+    }
+
+    #[derive(Deserialize)]
+    struct Response {
+        token: String,
+        expires_at: String,
+    }
+    let resp: Response = serde_json::from_slice(&output.stdout)
+        .context("Failed to parse installation token response")?;
+    Ok(InstallationToken {
+        token: resp.token,
+        expires_at: resp.expires_at,
+        repositories: repos.to_vec(),
+    })
+}
+
+/// The token API takes bare repo names, all owned by the installation's account.
+fn repo_names_for_installation(repos: &[String]) -> Result<Vec<String>> {
+    let mut owner: Option<&str> = None;
+    let mut names = Vec::new();
+    for repo in repos {
+        let (o, n) = repo
+            .split_once('/')
+            .with_context(|| format!("Invalid repo '{}' (expected owner/name)", repo))?;
+        match owner {
+            Some(existing) if existing != o => bail!(
+                "Cannot scope one installation token to repos of different owners ({} and {}). \
+                 Install the GitHub App on a single organization that owns all team repos.",
+                existing,
+                o
+            ),
+            _ => owner = Some(o),
+        }
+        names.push(n.to_string());
+    }
+    Ok(names)
+}
+
+/// Reads the cached token for a member gh dir, if any.
+fn read_token(dir: &Path) -> Option<InstallationToken> {
+    let contents = fs::read_to_string(dir.join(TOKEN_FILE)).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Writes the token cache and a gh `hosts.yml` pointing at it, both 0600.
+/// Files are replaced atomically so running gh processes never see a partial file.
+fn write_token(dir: &Path, token: &InstallationToken) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    config::set_mode(dir, 0o700)?;
+
+    let hosts = format!(
+        "github.com:\n    oauth_token: {}\n    git_protocol: https\n",
+        token.token
+    );
+    write_private(&dir.join(HOSTS_FILE), &hosts)?;
+    write_private(
+        &dir.join(TOKEN_FILE),
+        &serde_json::to_string_pretty(token).context("Failed to serialize token")?,
+    )?;
+    Ok(())
+}
+
+fn write_private(path: &Path, contents: &str) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, contents).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    config::set_mode(&tmp, 0o600)?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+    use crate::config::Credentials;
+
+    fn team_at(path: &Path) -> TeamEntry {
+        TeamEntry {
+            name: "my-team".to_string(),
+            path: path.to_path_buf(),
+            profile: "scrum".to_string(),
+            github_repo: "org/my-team".to_string(),
+            extra_repos: Vec::new(),
+            budget: None,
+            credentials: Credentials::default(),
+        }
+    }
+
+    fn write_manifest(team_dir: &Path) {
+        let team_repo = team_dir.join("team");
+        fs::create_dir_all(team_repo.join("team/dev-alice")).unwrap();
+        fs::create_dir_all(team_repo.join("team/qe-bob")).unwrap();
+        fs::write(
+            team_repo.join("botminter.yml"),
+            "name: scrum\nprojects:\n  - name: app\n    fork_url: https://github.com/org/app.git\n  - name: lib\n    fork_url: git@github.com:org/lib.git\n",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn parse_github_repo_formats() {
+        assert_eq!(parse_github_repo("https://github.com/org/app.git"), Some("org/app".into()));
+        assert_eq!(parse_github_repo("https://github.com/org/app"), Some("org/app".into()));
+        assert_eq!(parse_github_repo("git@github.com:org/app.git"), Some("org/app".into()));
+        assert_eq!(parse_github_repo("https://gitlab.com/org/app.git"), None);
+        assert_eq!(parse_github_repo("https://github.com/org"), None);
+    }
+
+    #[test]
+    fn member_repos_defaults_to_all_projects() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_manifest(tmp.path());
+        let repos = member_repos(&team_at(tmp.path()), "dev-alice").unwrap();
+        assert_eq!(repos, vec!["org/my-team", "org/app", "org/lib"]);
+
+        let mut team = team_at(tmp.path());
+        team.extra_repos = vec!["org/api".to_string(), "org/app".to_string()];
+        let repos = member_repos(&team, "dev-alice").unwrap();
+        assert_eq!(repos, vec!["org/my-team", "org/api", "org/app", "org/lib"]);
+    }
+
+    #[test]
+    fn member_repos_respects_member_projects() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_manifest(tmp.path());
+        fs::write(
+            tmp.path().join("team/team/qe-bob/botminter.yml"),
+            "role: qe\nprojects: [lib]\n",
+        )
+        .unwrap();
+        let repos = member_repos(&team_at(tmp.path()), "qe-bob").unwrap();
+        assert_eq!(repos, vec!["org/my-team", "org/lib"]);
+    }
+
+    #[test]
+    fn repo_names_require_single_owner() {
+        let names =
+            repo_names_for_installation(&["org/a".to_string(), "org/b".to_string()]).unwrap();
+        assert_eq!(names, vec!["a", "b"]);
+
+        let err = repo_names_for_installation(&["org/a".to_string(), "other/b".to_string()])
+            .unwrap_err();
+        assert!(err.to_string().contains("different owners"));
+    }
+
+    #[test]
+    fn token_refresh_window() {
+        let now = chrono::Utc::now();
+        let fresh = InstallationToken {
+            token: "ghs_x".to_string(),
+            expires_at: (now + chrono::Duration::minutes(50)).to_rfc3339(),
+            repositories: vec![],
+        };
+        let expiring = InstallationToken {
+            expires_at: (now + chrono::Duration::minutes(5)).to_rfc3339(),
+            ..fresh.clone()
+        };
+        assert!(!fresh.needs_refresh(now));
+        assert!(expiring.needs_refresh(now));
+    }
+
+    #[test]
+    fn write_token_creates_private_hosts_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("gh/my-team/dev-alice");
+        let token = InstallationToken {
+            token: "ghs_secret".to_string(),
+            expires_at: "2026-02-21T10:00:00Z".to_string(),
+            repositories: vec!["org/my-team".to_string()],
+        };
+        write_token(&dir, &token).unwrap();
+
+        let hosts = fs::read_to_string(dir.join("hosts.yml")).unwrap();
+        assert!(hosts.contains("oauth_token: ghs_secret"));
+        #[cfg(unix)]
+        assert_eq!(
+            fs::metadata(dir.join("hosts.yml")).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+        assert_eq!(read_token(&dir).unwrap().repositories, vec!["org/my-team"]);
+    }
+
+    #[test]
+    fn team_token_source_reuses_fresh_token() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut team = team_at(tmp.path());
+        team.credentials.gh_token = Some("ghp_pat".to_string());
+        let mut source = TeamTokenSource::default();
+        assert_eq!(source.token(&team).unwrap().as_deref(), Some("ghp_pat"));
+
+        // With an app configured, a fresh cached token is used without minting
+        team.credentials.github_app = Some(GitHubApp {
+            app_id: "1".to_string(),
+            installation_id: 2,
+            private_key_path: tmp.path().join("missing.pem"),
+        });
+        source.cached = Some(InstallationToken {
+            token: "ghs_cached".to_string(),
+            expires_at: (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339(),
+            repositories: vec!["org/my-team".to_string()],
+        });
+        assert_eq!(source.token(&team).unwrap().as_deref(), Some("ghs_cached"));
+
+        // An expiring token is re-minted (which fails here: no key on disk)
+        source.cached.as_mut().unwrap().expires_at = chrono::Utc::now().to_rfc3339();
+        assert!(source.token(&team).is_err());
+    }
+
+    #[test]
+    fn member_auth_config_dir_clears_gh_token() {
+        let mut cmd = Command::new("true");
+        cmd.env("GH_TOKEN", "inherited");
+        MemberAuth::ConfigDir(PathBuf::from("/tmp/gh")).apply(&mut cmd);
+        let envs: Vec<_> = cmd.get_envs().collect();
+        assert!(envs.contains(&(std::ffi::OsStr::new("GH_TOKEN"), None)));
+        assert!(envs.contains(&(
+            std::ffi::OsStr::new("GH_CONFIG_DIR"),
+            Some(std::ffi::OsStr::new("/tmp/gh"))
+        )));
+    }
+}
@@ -0,0 +1,231 @@
+//! Repository webhooks via the GitHub API (`gh api repos/<repo>/hooks`).
+//!
+//! Used by `bm webhooks` and by `bm daemon start --tunnel`. Creating, editing,
+//! and listing hooks needs admin rights on the repo.
+
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use tracing::debug;
+
+/// Hint appended to errors from hook API calls.
+pub const ADMIN_HINT: &str =
+    "Managing webhooks needs admin rights on the repo (classic tokens: the admin:repo_hook \
+     scope; GitHub Apps: the \"Webhooks\" repository permission).";
+
+/// A repository webhook, as listed by the API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Hook {
+    pub id: u64,
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(default)]
+    pub config: HookConfig,
+    #[serde(default)]
+    pub last_response: Option<LastResponse>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HookConfig {
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Masked (`********`) when a secret is set.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+/// Outcome of a hook's most recent delivery.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LastResponse {
+    #[serde(default)]
+    pub code: Option<u16>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// One delivery attempt of a hook.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Delivery {
+    pub delivered_at: String,
+    #[serde(default)]
+    pub status_code: u16,
+    #[serde(default)]
+    pub event: String,
+}
+
+impl Delivery {
+    pub fn succeeded(&self) -> bool {
+        (200..300).contains(&self.status_code)
+    }
+}
+
+impl Hook {
+    pub fn url(&self) -> &str {
+        self.config.url.as_deref().unwrap_or("")
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.config.secret.as_deref().is_some_and(|s| !s.is_empty())
+    }
+}
+
+/// Runs `gh api` with `args`, feeding `input` on stdin. Returns stdout.
+pub(crate) fn gh_api(token: Option<&str>, args: &[&str], input: Option<&str>) -> Result<String> {
+    debug!("Running gh api {}", args.join(" "));
+    let mut cmd = Command::new("gh");
+    cmd.arg("api").args(args);
+    if let Some(token) = token {
+        cmd.env("GH_TOKEN", token);
+    }
+    // Payloads carry the secret, so they go over stdin, never the command line
+    let mut child = cmd
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run gh api")?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin
+            .write_all(input.as_bytes())
+            .context("Failed to send request body to gh")?;
+    }
+    let output = child.wait_with_output().context("Failed to run gh api")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("gh api failed: {}", stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Body for creating or editing a hook.
+fn payload(url: &str, secret: &str, events: &[String]) -> String {
+    serde_json::json!({
+        "name": "web",
+        "active": true,
+        "events": events,
+        "config": {
+            "url": url,
+            "content_type": "json",
+            "secret": secret,
+            "insecure_ssl": "0",
+        },
+    })
+    .to_string()
+}
+
+/// Lists the webhooks of `repo`.
+pub fn list(repo: &str, token: Option<&str>) -> Result<Vec<Hook>> {
+    let out = gh_api(token, &[&format!("repos/{}/hooks", repo)], None)
+        .with_context(|| format!("Failed to list webhooks of {}. {}", repo, ADMIN_HINT))?;
+    serde_json::from_str(&out).context("Failed to parse webhook list from GitHub")
+}
+
+/// Creates a hook delivering `events` to `url`. Returns its ID.
+pub fn create(
+    repo: &str,
+    token: Option<&str>,
+    url: &str,
+    secret: &str,
+    events: &[String],
+) -> Result<u64> {
+    let path = format!("repos/{}/hooks", repo);
+    let out = gh_api(
+        token,
+        &["--method", "POST", &path, "--input", "-", "--jq", ".id"],
+        Some(&payload(url, secret, events)),
+    )
+    .with_context(|| format!("Failed to create webhook on {}. {}", repo, ADMIN_HINT))?;
+    out.trim()
+        .parse()
+        .with_context(|| format!("Unexpected webhook ID from gh: '{}'", out.trim()))
+}
+
+/// Points hook `id` at `url` with a new secret and event list, and activates it.
+pub fn update(
+    repo: &str,
+    token: Option<&str>,
+    id: u64,
+    url: &str,
+    secret: &str,
+    events: &[String],
+) -> Result<()> {
+    let path = format!("repos/{}/hooks/{}", repo, id);
+    gh_api(
+        token,
+        &["--method", "PATCH", &path, "--input", "-", "--silent"],
+        Some(&payload(url, secret, events)),
+    )
+    .with_context(|| format!("Failed to update webhook #{} on {}. {}", id, repo, ADMIN_HINT))?;
+    Ok(())
+}
+
+/// Deletes hook `id`.
+pub fn delete(repo: &str, token: Option<&str>, id: u64) -> Result<()> {
+    let path = format!("repos/{}/hooks/{}", repo, id);
+    gh_api(token, &["--method", "DELETE", &path], None)?;
+    Ok(())
+}
+
+/// The most recent `limit` deliveries of hook `id`, newest first.
+pub fn deliveries(repo: &str, token: Option<&str>, id: u64, limit: u32) -> Result<Vec<Delivery>> {
+    let path = format!("repos/{}/hooks/{}/deliveries?per_page={}", repo, id, limit);
+    let out = gh_api(token, &[&path], None)
+        .with_context(|| format!("Failed to read deliveries of webhook #{}", id))?;
+    serde_json::from_str(&out).context("Failed to parse webhook deliveries from GitHub")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_carries_secret_and_events() {
+        let body: serde_json::Value = serde_json::from_str(&payload(
+            "https://x.trycloudflare.com/webhook",
+            "s3cret",
+            &["issues".to_string(), "pull_request".to_string()],
+        ))
+        .unwrap();
+        assert_eq!(body["name"], "web");
+        assert_eq!(body["active"], true);
+        assert_eq!(body["events"], serde_json::json!(["issues", "pull_request"]));
+        assert_eq!(body["config"]["url"], "https://x.trycloudflare.com/webhook");
+        assert_eq!(body["config"]["secret"], "s3cret");
+        assert_eq!(body["config"]["content_type"], "json");
+    }
+
+    #[test]
+    fn parses_hooks_and_deliveries() {
+        let hooks: Vec<Hook> = serde_json::from_str(
+            r#"[{"id":7,"name":"web","active":true,"events":["issues"],
+                 "config":{"url":"https://h/webhook","content_type":"json","secret":"********"},
+                 "last_response":{"code":502,"status":"failed","message":"Bad gateway"}},
+                {"id":8,"active":false,"events":["push"],"config":{"url":"https://o"},
+                 "last_response":{"code":null,"status":"unused","message":null}}]"#,
+        )
+        .unwrap();
+        assert_eq!(hooks[0].url(), "https://h/webhook");
+        assert!(hooks[0].is_signed());
+        assert_eq!(hooks[0].last_response.as_ref().unwrap().code, Some(502));
+        assert!(!hooks[1].is_signed());
+        assert_eq!(hooks[1].last_response.as_ref().unwrap().code, None);
+
+        let deliveries: Vec<Delivery> = serde_json::from_str(
+            r#"[{"id":1,"delivered_at":"2026-03-01T10:00:00Z","status":"OK","status_code":200,"event":"issues"},
+                {"id":2,"delivered_at":"2026-03-01T09:00:00Z","status":"Invalid HTTP Response: 401","status_code":401,"event":"issue_comment"}]"#,
+        )
+        .unwrap();
+        assert!(deliveries[0].succeeded());
+        assert!(!deliveries[1].succeeded());
+    }
+}
@@ -0,0 +1,100 @@
+//! JSON `POST`s for the Slack and HTTP notifiers.
+//!
+//! Like the SMTP and Telegram clients this shells out to `curl`, with the
+//! URL and headers on stdin so webhook URLs and tokens never show up in the
+//! process list.
+
+use std::collections::BTreeMap;
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+/// Seconds allowed for one request.
+const MAX_TIME_SECS: u64 = 15;
+
+/// Posts `payload` to `url` with the extra `headers`, failing on an error
+/// status.
+pub fn post_json(url: &str, headers: &BTreeMap<String, String>, payload: &Value) -> Result<()> {
+    if which::which("curl").is_err() {
+        bail!("curl not found on PATH; it is needed to send notifications.");
+    }
+    let mut child = Command::new("curl")
+        .args([
+            "-sS",
+            "--fail",
+            "--max-time",
+            &MAX_TIME_SECS.to_string(),
+            "-K",
+            "-",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(curl_config(url, headers, payload).as_bytes())?;
+    }
+    let output = child.wait_with_output().context("Failed to run curl")?;
+    if !output.status.success() {
+        bail!(
+            "POST to {} failed: {}",
+            host(url),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// curl options for one request, read with `-K -`.
+fn curl_config(url: &str, headers: &BTreeMap<String, String>, payload: &Value) -> String {
+    let mut lines = vec![
+        format!("url = {}", quote(url)),
+        format!("header = {}", quote("Content-Type: application/json")),
+    ];
+    for (name, value) in headers {
+        lines.push(format!(
+            "header = {}",
+            quote(&format!("{}: {}", name, value))
+        ));
+    }
+    lines.push(format!("data-binary = {}", quote(&payload.to_string())));
+    lines.join("\n") + "\n"
+}
+
+/// Quotes a value for a curl config file.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The scheme and host of `url`, for errors that mustn't show its secret path.
+fn host(url: &str) -> &str {
+    let start = url.find("://").map_or(0, |i| i + 3);
+    url[start..]
+        .find('/')
+        .map_or(url, |end| &url[..start + end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curl_config_quotes_headers_and_body() {
+        let headers = BTreeMap::from([("Authorization".to_string(), "Bearer t0k".to_string())]);
+        let payload = serde_json::json!({ "text": "line one\n\"two\"" });
+        assert_eq!(
+            curl_config("https://example.com/hook", &headers, &payload),
+            "url = \"https://example.com/hook\"\n\
+             header = \"Content-Type: application/json\"\n\
+             header = \"Authorization: Bearer t0k\"\n\
+             data-binary = \"{\\\"text\\\":\\\"line one\\\\n\\\\\\\"two\\\\\\\"\\\"}\"\n"
+        );
+        assert_eq!(
+            host("https://hooks.slack.com/services/T/B/x"),
+            "https://hooks.slack.com"
+        );
+    }
+}
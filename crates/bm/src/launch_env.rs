@@ -0,0 +1,341 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::config::Credentials;
+use crate::github_app::MemberAuth;
+use crate::parse;
+
+/// Variables whose values never appear in launch records.
+const SECRET_VARS: &[&str] = &[
+    "GH_TOKEN",
+    "RALPH_TELEGRAM_BOT_TOKEN",
+    "RALPH_SLACK_BOT_TOKEN",
+];
+
+/// Name suffixes marking an `env:` override as a secret, redacted in launch
+/// records even when its value is written out literally.
+const SECRET_SUFFIXES: &[&str] = &["_TOKEN", "_KEY", "_SECRET"];
+
+/// Recorded value for a variable removed from the member's environment.
+pub const UNSET: &str = "<unset>";
+
+/// The environment bm injects into a member's ralph process.
+///
+/// Built once per launch so the same list drives both the command and the
+/// audit record of what the member received.
+#[derive(Debug, Clone)]
+pub struct MemberEnv {
+    vars: Vec<(String, Option<String>)>,
+    /// Overrides redacted in launch records: those whose values came from
+    /// `${VAR}` expansion, or whose names look like secrets.
+    redacted: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct EnvSection {
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+}
+
+/// Reads the `env:` section of the member manifest in `member_dir`.
+pub fn read_overrides(member_dir: &Path) -> Result<BTreeMap<String, String>> {
+    let path = member_dir.join("botminter.yml");
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let section: EnvSection = parse::yaml(&path, &contents)?;
+    for name in section.env.keys() {
+        let valid = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && name.starts_with(|c: char| !c.is_ascii_digit());
+        if !valid {
+            bail!("env in {} has invalid variable name '{}'", path.display(), name);
+        }
+    }
+    Ok(section.env)
+}
+
+impl MemberEnv {
+    /// Builds the environment for a member launch from its GitHub auth and
+    /// the team's Telegram and Slack credentials.
+    pub fn new(auth: &MemberAuth, credentials: &Credentials) -> MemberEnv {
+        // Unset CLAUDECODE to avoid nested-Claude issues
+        let mut vars = vec![("CLAUDECODE".to_string(), None)];
+        vars.extend(auth.env_vars().into_iter().map(|(k, v)| (k.to_string(), v)));
+        if let Some(ref token) = credentials.telegram_bot_token {
+            vars.push(("RALPH_TELEGRAM_BOT_TOKEN".to_string(), Some(token.clone())));
+        }
+        if let Some(chat_id) = credentials.telegram_chat_id {
+            vars.push(("RALPH_TELEGRAM_CHAT_ID".to_string(), Some(chat_id.to_string())));
+        }
+        if let Some(ref token) = credentials.slack_bot_token {
+            vars.push(("RALPH_SLACK_BOT_TOKEN".to_string(), Some(token.clone())));
+        }
+        if let Some(ref channel) = credentials.slack_channel {
+            vars.push(("RALPH_SLACK_CHANNEL".to_string(), Some(channel.clone())));
+        }
+        MemberEnv {
+            vars,
+            redacted: Vec::new(),
+        }
+    }
+
+    /// Adds a member's `env:` overrides. `${VAR}` in a value expands from
+    /// the operator's environment, then from the team's stored credentials
+    /// (`GH_TOKEN`, `TELEGRAM_BOT_TOKEN`, `SLACK_BOT_TOKEN`, `WEBHOOK_SECRET`).
+    /// Variables bm sets itself can't be overridden.
+    pub fn with_overrides(
+        mut self,
+        overrides: &BTreeMap<String, String>,
+        credentials: &Credentials,
+    ) -> Result<MemberEnv> {
+        for (name, value) in overrides {
+            if self.vars.iter().any(|(n, _)| n == name) {
+                bail!("env: {} is set by bm and can't be overridden", name);
+            }
+            let lookup = |var: &str| {
+                std::env::var(var)
+                    .ok()
+                    .or_else(|| credential(credentials, var))
+            };
+            let expanded = expand(value, lookup)
+                .with_context(|| format!("env: can't expand {}", name))?;
+            if value.contains("${") || looks_secret(name) {
+                self.redacted.push(name.clone());
+            }
+            self.vars.push((name.clone(), Some(expanded)));
+        }
+        Ok(self)
+    }
+
+    /// Applies the environment to a member command.
+    pub fn apply(&self, cmd: &mut Command) {
+        for (name, value) in &self.vars {
+            match value {
+                Some(value) => cmd.env(name, value),
+                None => cmd.env_remove(name),
+            };
+        }
+    }
+
+    /// Returns the injected variables with secrets redacted, for run records.
+    pub fn audit(&self) -> BTreeMap<String, String> {
+        self.vars
+            .iter()
+            .map(|(name, value)| {
+                let shown = match value {
+                    None => UNSET.to_string(),
+                    Some(v) if SECRET_VARS.contains(&name.as_str()) => redact(v),
+                    Some(v) if self.redacted.contains(name) => redact(v),
+                    Some(v) => v.clone(),
+                };
+                (name.clone(), shown)
+            })
+            .collect()
+    }
+
+    /// Formats the audit record as a single `NAME=value` line.
+    pub fn audit_line(&self) -> String {
+        self.audit()
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Returns true if an override named `name` likely holds a secret: it ends in
+/// `_TOKEN`, `_KEY`, or `_SECRET`, or mentions `PASSWORD` (any case).
+fn looks_secret(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    SECRET_SUFFIXES.iter().any(|s| upper.ends_with(s)) || upper.contains("PASSWORD")
+}
+
+/// A team credential by the name `${VAR}` references use for it.
+fn credential(credentials: &Credentials, var: &str) -> Option<String> {
+    match var {
+        "GH_TOKEN" => credentials.gh_token.clone(),
+        "TELEGRAM_BOT_TOKEN" => credentials.telegram_bot_token.clone(),
+        "SLACK_BOT_TOKEN" => credentials.slack_bot_token.clone(),
+        "WEBHOOK_SECRET" => credentials.webhook_secret.clone(),
+        _ => None,
+    }
+}
+
+/// Replaces each `${VAR}` in `value` with `lookup(VAR)`. Fails on an unset
+/// variable or an unclosed reference; a `$` not followed by `{` is kept.
+fn expand(value: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            bail!("unclosed '${{' in '{}'", value);
+        };
+        let var = &after[..end];
+        match lookup(var) {
+            Some(v) => out.push_str(&v),
+            None => bail!("${{{}}} is not set in the environment or the team's credentials", var),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Redacts a secret, keeping enough to tell whether it is set and which one it
+/// is: compare the fingerprint with `printf %s "$TOKEN" | sha256sum`.
+pub fn redact(value: &str) -> String {
+    if value.is_empty() {
+        return "<empty>".to_string();
+    }
+    let digest = hex::encode(Sha256::digest(value.as_bytes()));
+    format!("<redacted sha256:{}>", &digest[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn telegram(token: Option<&str>, chat_id: Option<i64>) -> Credentials {
+        Credentials {
+            telegram_bot_token: token.map(String::from),
+            telegram_chat_id: chat_id,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn audit_redacts_secrets() {
+        let env = MemberEnv::new(
+            &MemberAuth::Token("ghp_secret".to_string()),
+            &telegram(Some("123:abc"), Some(-100123)),
+        );
+        let audit = env.audit();
+        assert_eq!(audit["RALPH_TELEGRAM_CHAT_ID"], "-100123");
+        assert_eq!(audit["CLAUDECODE"], UNSET);
+        assert!(audit["GH_TOKEN"].starts_with("<redacted sha256:"));
+        assert!(audit["RALPH_TELEGRAM_BOT_TOKEN"].starts_with("<redacted sha256:"));
+        assert!(!env.audit_line().contains("ghp_secret"));
+        assert!(!env.audit_line().contains("123:abc"));
+    }
+
+    #[test]
+    fn slack_credentials_reach_the_member() {
+        let credentials = Credentials {
+            slack_bot_token: Some("xoxb-1-2".to_string()),
+            slack_channel: Some("C0123ABCD".to_string()),
+            ..Default::default()
+        };
+        let env = MemberEnv::new(&MemberAuth::Token("t".to_string()), &credentials);
+        let audit = env.audit();
+        assert_eq!(audit["RALPH_SLACK_CHANNEL"], "C0123ABCD");
+        assert_eq!(audit["RALPH_SLACK_BOT_TOKEN"], redact("xoxb-1-2"));
+        assert_eq!(credential(&credentials, "SLACK_BOT_TOKEN").as_deref(), Some("xoxb-1-2"));
+    }
+
+    #[test]
+    fn audit_shows_config_dir_and_missing_telegram() {
+        let env = MemberEnv::new(
+            &MemberAuth::ConfigDir(PathBuf::from("/tmp/gh/t/m")),
+            &Credentials::default(),
+        );
+        let audit = env.audit();
+        assert_eq!(audit["GH_CONFIG_DIR"], "/tmp/gh/t/m");
+        assert_eq!(audit["GH_TOKEN"], UNSET);
+        assert!(!audit.contains_key("RALPH_TELEGRAM_BOT_TOKEN"));
+        assert!(!audit.contains_key("RALPH_TELEGRAM_CHAT_ID"));
+    }
+
+    #[test]
+    fn overrides_expand_from_credentials_and_are_redacted() {
+        let credentials = Credentials {
+            webhook_secret: Some("whsec".to_string()),
+            ..Default::default()
+        };
+        let overrides = BTreeMap::from([
+            ("FEATURE_X".to_string(), "on".to_string()),
+            ("HOOK_KEY".to_string(), "k-${WEBHOOK_SECRET}".to_string()),
+            ("SENTRY_TOKEN".to_string(), "literal-token".to_string()),
+            ("db_password_file".to_string(), "hunter2".to_string()),
+            ("MONKEY".to_string(), "banana".to_string()),
+        ]);
+        let env = MemberEnv::new(&MemberAuth::Token("t".to_string()), &credentials)
+            .with_overrides(&overrides, &credentials)
+            .unwrap();
+        let mut cmd = Command::new("true");
+        env.apply(&mut cmd);
+        let envs: Vec<_> = cmd.get_envs().collect();
+        assert!(envs.contains(&(
+            std::ffi::OsStr::new("HOOK_KEY"),
+            Some(std::ffi::OsStr::new("k-whsec"))
+        )));
+        let audit = env.audit();
+        assert_eq!(audit["FEATURE_X"], "on");
+        assert_eq!(audit["HOOK_KEY"], redact("k-whsec"));
+        // Literal values are redacted too when the name looks like a secret
+        assert_eq!(audit["SENTRY_TOKEN"], redact("literal-token"));
+        assert_eq!(audit["db_password_file"], redact("hunter2"));
+        assert_eq!(audit["MONKEY"], "banana");
+    }
+
+    #[test]
+    fn overrides_reject_bm_vars_and_unset_references() {
+        let env = MemberEnv::new(&MemberAuth::Token("t".to_string()), &Credentials::default());
+        let gh = BTreeMap::from([("GH_TOKEN".to_string(), "x".to_string())]);
+        let err = env.clone().with_overrides(&gh, &Credentials::default()).unwrap_err();
+        assert!(format!("{:#}", err).contains("GH_TOKEN is set by bm"));
+
+        let unset = BTreeMap::from([("KEY".to_string(), "${BM_TEST_SURELY_UNSET}".to_string())]);
+        let err = env.with_overrides(&unset, &Credentials::default()).unwrap_err();
+        assert!(format!("{:#}", err).contains("${BM_TEST_SURELY_UNSET} is not set"), "{:#}", err);
+    }
+
+    #[test]
+    fn expand_substitutes_references() {
+        let lookup = |var: &str| (var == "A").then(|| "1".to_string());
+        assert_eq!(expand("x${A}y$z", lookup).unwrap(), "x1y$z");
+        assert!(expand("${A", lookup).is_err());
+        assert!(expand("${B}", lookup).is_err());
+    }
+
+    #[test]
+    fn read_overrides_validates_names() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(read_overrides(tmp.path()).unwrap().is_empty());
+        let manifest = tmp.path().join("botminter.yml");
+        fs::write(&manifest, "role: dev\nenv:\n  API_KEY: \"${KEY}\"\n").unwrap();
+        assert_eq!(read_overrides(tmp.path()).unwrap()["API_KEY"], "${KEY}");
+        fs::write(&manifest, "env:\n  1BAD: x\n").unwrap();
+        assert!(read_overrides(tmp.path()).is_err());
+    }
+
+    #[test]
+    fn redact_marks_empty_values() {
+        assert_eq!(redact(""), "<empty>");
+        assert_eq!(redact("x"), redact("x"));
+        assert_ne!(redact("x"), redact("y"));
+    }
+
+    #[test]
+    fn apply_sets_and_removes() {
+        let env = MemberEnv::new(&MemberAuth::Token("t".to_string()), &telegram(Some("tg"), None));
+        let mut cmd = Command::new("true");
+        env.apply(&mut cmd);
+        let envs: Vec<_> = cmd.get_envs().collect();
+        assert!(envs.contains(&(std::ffi::OsStr::new("CLAUDECODE"), None)));
+        assert!(envs.contains(&(
+            std::ffi::OsStr::new("RALPH_TELEGRAM_BOT_TOKEN"),
+            Some(std::ffi::OsStr::new("tg"))
+        )));
+    }
+}
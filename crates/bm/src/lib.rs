@@ -1,10 +1,59 @@
+pub mod audit;
+pub mod board;
+pub mod budget;
+pub mod cgroup;
 pub mod cli;
 pub mod commands;
 pub mod completions;
 pub mod config;
+pub mod daemon_settings;
+pub mod delivery_ids;
+pub mod disk_quota;
+pub mod dry_run;
+pub mod event_log;
+pub mod event_queue;
+pub mod filelock;
 pub mod formation;
+pub mod github_app;
+pub mod github_hooks;
+pub mod http_post;
+pub mod launch_env;
+pub mod logging;
+pub mod mcp;
+pub mod member_templates;
+pub mod members;
+pub mod notify;
+pub mod parse;
+pub mod pre_stop;
+pub mod preflight;
+pub mod priority;
+pub mod procutil;
 pub mod profile;
+pub mod profile_checksums;
+pub mod profile_lint;
+pub mod project_views;
+pub mod rate_limit;
+pub mod readiness;
+pub mod repo_settings;
+pub mod repo_templates;
+pub mod run_history;
+pub mod schedule;
 pub mod session;
+pub mod slack;
+pub mod smtp;
+pub mod snapshots;
 pub mod state;
+pub mod state_history;
+pub mod store;
+pub mod tables;
+pub mod team_verify;
+pub mod telegram;
+pub mod timefmt;
+pub mod token_expiry;
 pub mod topology;
+pub mod trigger_filter;
+pub mod tunnel;
+pub mod usage;
+pub mod watchdog;
 pub mod workspace;
+pub mod workspace_templates;
@@ -0,0 +1,544 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, MakeWriter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+/// Environment variable holding the log filter, in `tracing` `EnvFilter`
+/// syntax (e.g. `debug`, or `info,bm::commands::daemon=trace`). Overrides
+/// `--verbose`.
+pub const ENV_VAR: &str = "BM_LOG";
+
+/// Level commands log at without `--verbose` or `BM_LOG`: warnings only, so
+/// normal output is unchanged.
+const CLI_DEFAULT: &str = "warn";
+
+/// Level the daemon logs at without `--verbose` or `BM_LOG`.
+const DAEMON_DEFAULT: &str = "info";
+
+/// Log size that triggers rotation when `logs.max_size` is not set.
+const DEFAULT_MAX_SIZE: &str = "10Mi";
+
+/// Compressed archives kept per log when `logs.keep` is not set.
+const DEFAULT_KEEP: usize = 5;
+
+/// Serializes rotations, so threads logging at once don't shuffle the same
+/// archives.
+static ROTATION: Mutex<()> = Mutex::new(());
+
+/// `--verbose` count given to this process, remembered so `bm daemon start`
+/// can hand it to the daemon it spawns.
+static VERBOSE: AtomicU8 = AtomicU8::new(0);
+
+/// Format of the daemon log file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// `[2026-02-22T10:30:01Z] [INFO] message key=value`, one line per entry.
+    #[default]
+    Text,
+    /// One JSON object per line, with the span context (team, member, event).
+    Json,
+}
+
+/// Rotation and retention for the daemon and member logs, from the `logs:`
+/// section of `config.yml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Retention {
+    /// Rotate a log once it grows past this size: bytes or a suffix such as
+    /// `10Mi` (default: 10Mi).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<String>,
+    /// Also rotate a log once it is this many days old (default: never).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+    /// Compressed archives to keep per log (default: 5). `0` discards rotated
+    /// logs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep: Option<usize>,
+}
+
+impl Retention {
+    /// Size in bytes past which a log is rotated.
+    pub fn max_size_bytes(&self) -> Result<u64> {
+        let size = self.max_size.as_deref().unwrap_or(DEFAULT_MAX_SIZE);
+        crate::formation::parse_memory(size).map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid logs.max_size '{}'. Use bytes or a suffix such as 512Ki or 10Mi.",
+                size
+            )
+        })
+    }
+
+    /// Age past which a log is rotated, if any.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age_days.map(|d| Duration::from_secs(d * 24 * 60 * 60))
+    }
+
+    /// Number of compressed archives kept per log.
+    pub fn keep(&self) -> usize {
+        self.keep.unwrap_or(DEFAULT_KEEP)
+    }
+
+    /// Checks values serde cannot.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_size_bytes()? == 0 {
+            bail!("logs.max_size must be greater than zero");
+        }
+        if self.max_age_days == Some(0) {
+            bail!("logs.max_age_days must be at least 1");
+        }
+        Ok(())
+    }
+
+    /// True if a log of `size` bytes, started `age` ago, should be rotated.
+    fn due(&self, size: u64, age: Option<Duration>) -> bool {
+        let max_size = self.max_size_bytes().unwrap_or(u64::MAX);
+        let too_old = match (age, self.max_age()) {
+            (Some(age), Some(max)) => age >= max,
+            _ => false,
+        };
+        (size > max_size || too_old) && size > 0
+    }
+}
+
+/// Returns the path of the `n`th compressed archive of `log`
+/// (`daemon-alpha.log` → `daemon-alpha.log.1.gz`).
+pub fn archive_path(log: &Path, n: usize) -> PathBuf {
+    let mut name = log.as_os_str().to_owned();
+    name.push(format!(".{}.gz", n));
+    PathBuf::from(name)
+}
+
+/// Returns `log` and every file rotation left next to it (archives and any
+/// half-finished `.rotating`/`.tmp` files) that exist on disk.
+pub fn log_and_archives(log: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(name)) = (log.parent(), log.file_name()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|e| {
+            let n = e.file_name().to_string_lossy().to_string();
+            n == name.to_string_lossy() || n.starts_with(&prefix)
+        })
+        .map(|e| e.path())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Rotates `log` if it is past the size or age limit in `retention`. The log
+/// is compressed to `<log>.1.gz`, older archives shift up by one, and those
+/// beyond `keep` are deleted. Returns true if the log was rotated.
+pub fn rotate_if_due(log: &Path, retention: &Retention) -> Result<bool> {
+    let Ok(meta) = fs::metadata(log) else {
+        return Ok(false);
+    };
+    if !retention.due(meta.len(), log_age(log, &meta)) {
+        return Ok(false);
+    }
+    let _guard = ROTATION.lock().unwrap_or_else(|e| e.into_inner());
+    // Another thread may have rotated it while we waited
+    if !fs::metadata(log).is_ok_and(|m| retention.due(m.len(), log_age(log, &m))) {
+        return Ok(false);
+    }
+
+    // Move the live log aside first so writers start a fresh file right away
+    let mut pending = log.as_os_str().to_owned();
+    pending.push(".rotating");
+    let pending = PathBuf::from(pending);
+    fs::rename(log, &pending)
+        .with_context(|| format!("Failed to rotate {}", log.display()))?;
+
+    let keep = retention.keep();
+    let mut n = keep + 1;
+    while archive_path(log, n).exists() {
+        let _ = fs::remove_file(archive_path(log, n));
+        n += 1;
+    }
+    for n in (1..keep).rev() {
+        let from = archive_path(log, n);
+        if from.exists() {
+            fs::rename(&from, archive_path(log, n + 1))
+                .with_context(|| format!("Failed to rotate {}", from.display()))?;
+        }
+    }
+    if keep > 0 {
+        compress(&pending, &archive_path(log, 1))?;
+    }
+    fs::remove_file(&pending)
+        .with_context(|| format!("Failed to remove {}", pending.display()))?;
+    Ok(true)
+}
+
+/// How long ago the current log was started: its creation time where the
+/// filesystem records one, else the time of the last rotation.
+fn log_age(log: &Path, meta: &fs::Metadata) -> Option<Duration> {
+    let started = meta
+        .created()
+        .or_else(|_| fs::metadata(archive_path(log, 1)).and_then(|m| m.modified()))
+        .ok()?;
+    SystemTime::now().duration_since(started).ok()
+}
+
+/// Gzips `src` into `dst`, writing through a temporary file.
+fn compress(src: &Path, dst: &Path) -> Result<()> {
+    let mut tmp = dst.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut input =
+        fs::File::open(src).with_context(|| format!("Failed to read {}", src.display()))?;
+    let output =
+        fs::File::create(&tmp).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    let mut encoder = GzEncoder::new(output, Compression::default());
+    io::copy(&mut input, &mut encoder)
+        .and_then(|_| encoder.finish())
+        .with_context(|| format!("Failed to compress {}", src.display()))?;
+    fs::rename(&tmp, dst).with_context(|| format!("Failed to write {}", dst.display()))
+}
+
+impl FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<LogFormat> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => bail!("Invalid log format '{}'. Use 'text' or 'json'.", s),
+        }
+    }
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LogFormat::Text => "text",
+            LogFormat::Json => "json",
+        })
+    }
+}
+
+/// Returns the filter to log with: `BM_LOG` if set, else one derived from
+/// the `--verbose` count, else `default`.
+pub fn directives(verbose: u8, default: &str) -> String {
+    match std::env::var(ENV_VAR) {
+        Ok(v) if !v.trim().is_empty() => v,
+        _ => match verbose {
+            0 => default.to_string(),
+            1 => "debug".to_string(),
+            _ => "trace".to_string(),
+        },
+    }
+}
+
+/// Returns the filter a daemon spawned by this process should log with, if
+/// it differs from what the daemon would pick up on its own.
+pub fn daemon_directives() -> Option<String> {
+    match VERBOSE.load(Ordering::Relaxed) {
+        0 => None,
+        verbose => Some(directives(verbose, DAEMON_DEFAULT)),
+    }
+}
+
+/// Sets up logging for a command: events at the configured level go to
+/// stderr, without timestamps.
+pub fn init_cli(verbose: u8) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(io::stderr)
+        .without_time()
+        .with_target(false);
+    let _ = tracing_subscriber::registry()
+        .with(filter(&directives(verbose, CLI_DEFAULT), CLI_DEFAULT))
+        .with(layer)
+        .try_init();
+}
+
+/// Sets up logging for the daemon event loop: events go to the team's
+/// daemon log file, rotated per `retention`.
+pub fn init_daemon(log_file: PathBuf, format: LogFormat, retention: Retention) {
+    let filter = filter(&directives(0, DAEMON_DEFAULT), DAEMON_DEFAULT);
+    let writer = LogFile {
+        path: log_file,
+        retention,
+    };
+    let registry = tracing_subscriber::registry().with(filter);
+    let _ = match format {
+        LogFormat::Text => registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .event_format(TextFormat)
+                    .with_ansi(false)
+                    .with_writer(writer),
+            )
+            .try_init(),
+        LogFormat::Json => registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(true)
+                    .with_ansi(false)
+                    .with_writer(writer),
+            )
+            .try_init(),
+    };
+}
+
+/// Parses a filter, falling back to `default` (with a warning) when it is
+/// malformed.
+fn filter(directives: &str, default: &str) -> EnvFilter {
+    EnvFilter::try_new(directives).unwrap_or_else(|e| {
+        eprintln!("Ignoring invalid {} '{}': {}", ENV_VAR, directives, e);
+        EnvFilter::new(default)
+    })
+}
+
+/// The daemon's text log line: timestamp, level, message, and any fields the
+/// event records. Span context is left to the JSON format.
+struct TextFormat;
+
+impl<S, N> FormatEvent<S, N> for TextFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        write!(
+            writer,
+            "[{}] [{}] ",
+            chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+            event.metadata().level()
+        )?;
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+/// Appends each log entry to a file, rotating it first once it is past the
+/// retention limits.
+struct LogFile {
+    path: PathBuf,
+    retention: Retention,
+}
+
+impl<'a> MakeWriter<'a> for LogFile {
+    type Writer = Box<dyn io::Write>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        if let Err(e) = rotate_if_due(&self.path, &self.retention) {
+            // Logging the failure would recurse into this writer
+            eprintln!("{:#}", e);
+        }
+        match fs::OpenOptions::new().create(true).append(true).open(&self.path) {
+            Ok(f) => Box::new(f),
+            Err(_) => Box::new(io::sink()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_format_parses_and_displays() {
+        assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert_eq!(LogFormat::Text.to_string(), "text");
+        let err = "xml".parse::<LogFormat>().unwrap_err();
+        assert!(err.to_string().contains("'text' or 'json'"), "{}", err);
+    }
+
+    #[test]
+    fn text_format_writes_level_message_and_fields() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("daemon.log");
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .event_format(TextFormat)
+            .with_writer(LogFile {
+                path: path.clone(),
+                retention: Retention::default(),
+            })
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            let _member = tracing::info_span!("member", member = "dev-01").entered();
+            tracing::warn!(pid = 42, "dev-01: exited");
+        });
+
+        let log = fs::read_to_string(&path).unwrap();
+        let line = log.lines().next().unwrap();
+        assert!(line.starts_with('['), "{}", line);
+        assert!(line.ends_with("] [WARN] dev-01: exited pid=42"), "{}", line);
+    }
+
+    #[test]
+    fn json_format_carries_span_context() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("daemon.log");
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_writer(LogFile {
+                path: path.clone(),
+                retention: Retention::default(),
+            })
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            let _team = tracing::info_span!("daemon", team = "alpha").entered();
+            let _member = tracing::info_span!("member", member = "dev-01").entered();
+            tracing::info!("launched");
+        });
+
+        let log = fs::read_to_string(&path).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(log.lines().next().unwrap()).unwrap();
+        assert_eq!(entry["level"], "INFO");
+        assert_eq!(entry["fields"]["message"], "launched");
+        assert_eq!(entry["span"]["member"], "dev-01");
+        assert_eq!(entry["spans"][0]["team"], "alpha");
+    }
+
+    fn gunzip(path: &Path) -> String {
+        let mut out = String::new();
+        io::Read::read_to_string(
+            &mut flate2::read::GzDecoder::new(fs::File::open(path).unwrap()),
+            &mut out,
+        )
+        .unwrap();
+        out
+    }
+
+    #[test]
+    fn rotation_compresses_and_keeps_newest_archives() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("daemon-alpha.log");
+        let retention = Retention {
+            max_size: Some("8".to_string()),
+            keep: Some(2),
+            ..Default::default()
+        };
+
+        fs::write(&log, "short").unwrap();
+        assert!(!rotate_if_due(&log, &retention).unwrap());
+        for n in 1..=3 {
+            fs::write(&log, format!("rotation number {}\n", n)).unwrap();
+            assert!(rotate_if_due(&log, &retention).unwrap());
+            assert!(!log.exists());
+        }
+
+        assert_eq!(gunzip(&archive_path(&log, 1)), "rotation number 3\n");
+        assert_eq!(gunzip(&archive_path(&log, 2)), "rotation number 2\n");
+        assert!(!archive_path(&log, 3).exists());
+        assert!(!rotate_if_due(&log, &retention).unwrap());
+
+        fs::write(&log, "").unwrap();
+        fs::write(tmp.path().join("daemon-alpha-2.log"), "").unwrap();
+        assert_eq!(
+            log_and_archives(&log),
+            vec![log.clone(), archive_path(&log, 1), archive_path(&log, 2)]
+        );
+    }
+
+    #[test]
+    fn rotation_with_keep_zero_discards_the_log() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("member-alpha-dev-01.log");
+        fs::write(&log, "0123456789").unwrap();
+        fs::write(archive_path(&log, 1), "left over from keep: 5").unwrap();
+        let retention = Retention {
+            max_size: Some("4".to_string()),
+            keep: Some(0),
+            ..Default::default()
+        };
+        assert!(rotate_if_due(&log, &retention).unwrap());
+        assert_eq!(fs::read_dir(tmp.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn retention_rotates_on_size_or_age() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let r = Retention {
+            max_size: Some("1Ki".to_string()),
+            max_age_days: Some(7),
+            ..Default::default()
+        };
+        assert!(!r.due(100, Some(day)));
+        assert!(r.due(2048, Some(day)));
+        assert!(r.due(100, Some(day * 7)));
+        assert!(!r.due(100, None));
+        // An empty log is never rotated, however old
+        assert!(!r.due(0, Some(day * 30)));
+
+        let defaults = Retention::default();
+        assert_eq!(defaults.keep(), 5);
+        assert!(!defaults.due(100, Some(day * 365)));
+        assert!(defaults.due(11 << 20, None));
+    }
+
+    #[test]
+    fn retention_rejects_bad_values() {
+        let bad_size = Retention {
+            max_size: Some("10MB".to_string()),
+            ..Default::default()
+        };
+        assert!(bad_size.validate().unwrap_err().to_string().contains("logs.max_size"));
+        let zero_age = Retention {
+            max_age_days: Some(0),
+            ..Default::default()
+        };
+        assert!(zero_age.validate().is_err());
+        assert!(Retention::default().validate().is_ok());
+    }
+
+    #[test]
+    fn daemon_writer_rotates_past_the_limit() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("daemon.log");
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .event_format(TextFormat)
+            .with_writer(LogFile {
+                path: path.clone(),
+                retention: Retention {
+                    max_size: Some("64".to_string()),
+                    ..Default::default()
+                },
+            })
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("first entry, long enough to pass the size limit");
+            tracing::info!("second entry");
+        });
+
+        assert!(gunzip(&archive_path(&path, 1)).contains("first entry"));
+        let log = fs::read_to_string(&path).unwrap();
+        assert!(log.contains("second entry") && !log.contains("first entry"), "{}", log);
+    }
+}
@@ -1,25 +1,65 @@
+use std::time::Duration;
+
 use anyhow::Result;
 use clap::Parser;
 use clap_complete::CompleteEnv;
 
 use bm::cli::{
-    Cli, Command, DaemonCommand, KnowledgeCommand, MembersCommand, ProfilesCommand,
-    ProjectsCommand, RolesCommand, TeamsCommand,
+    BoardCommand, Cli, Command, CompletionsCommand, ConfigCommand, DaemonCommand, DaemonConfigCommand, DaemonEventsCommand,
+    DiagCommand, KnowledgeCommand, MembersCommand, NotifyCommand, ProfilesCommand,
+    ProjectsCommand, RolesCommand, StateCommand, TeamsCommand, TopologyCommand, WebhooksCommand,
+    WorkspaceCommand,
 };
 use bm::commands;
+use bm::commands::daemon::{Listener, ListenerFlags, TlsFiles};
 use bm::completions;
+use bm::trigger_filter::TriggerEvent;
+use bm::tunnel::Provider;
 
 fn main() -> Result<()> {
     CompleteEnv::with_factory(completions::build_cli_with_completions).complete();
 
     let cli = Cli::parse();
+    bm::tables::set_plain(cli.plain);
+    // The daemon logs to its own file once it has read its settings
+    if !matches!(cli.command, Command::DaemonRun { .. }) {
+        bm::logging::init_cli(cli.verbose);
+    }
+
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    let action = bm::audit::Action::of(&cli.command, &argv);
+    let result = run(cli.command);
+    if let Some(action) = action {
+        action.record(&result);
+    }
+    result
+}
 
-    match cli.command {
-        Command::Init => commands::init::run()?,
+fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Init {
+            from_existing,
+            name,
+        } => match from_existing {
+            Some(repo) => commands::init::join_existing(&repo, name.as_deref())?,
+            None => commands::init::run()?,
+        },
+        Command::Quickstart {
+            name,
+            owner,
+            no_run,
+        } => commands::quickstart::run(name.as_deref(), owner.as_deref(), no_run)?,
 
         Command::Profiles { command } => match command {
             ProfilesCommand::List => commands::profiles::list()?,
             ProfilesCommand::Describe { profile } => commands::profiles::describe(&profile)?,
+            ProfilesCommand::Validate { target } => {
+                commands::profiles::validate(target.as_deref())?
+            }
+            ProfilesCommand::Export { profile, dir } => {
+                commands::profiles::export(&profile, &dir)?
+            }
+            ProfilesCommand::Lint { path, json } => commands::profiles::lint(&path, json)?,
         },
 
         Command::Teams { command } => match command {
@@ -27,13 +67,75 @@ fn main() -> Result<()> {
             TeamsCommand::Show { name, team } => {
                 commands::teams::show(name.as_deref(), team.as_deref())?;
             }
-            TeamsCommand::Sync { push, team } => {
-                commands::teams::sync(push, team.as_deref())?;
+            TeamsCommand::Pull { rebase, team } => {
+                commands::teams::pull(rebase, team.as_deref())?;
+            }
+            TeamsCommand::Sync {
+                push,
+                check,
+                dry_run,
+                member,
+                project,
+                team,
+            } => {
+                if dry_run {
+                    let plan = commands::teams::sync_plan(
+                        push,
+                        member.as_deref(),
+                        project.as_deref(),
+                        team.as_deref(),
+                    )?;
+                    print!("{}", plan);
+                } else {
+                    let synced = commands::teams::sync(
+                        push,
+                        check,
+                        member.as_deref(),
+                        project.as_deref(),
+                        team.as_deref(),
+                    )?;
+                    print!("{}", synced);
+                    synced.ensure_ok()?;
+                }
+            }
+            TeamsCommand::Verify { team } => commands::teams::verify(team.as_deref())?,
+            TeamsCommand::AddRepo { repo, team } => {
+                commands::teams::add_repo(&repo, team.as_deref())?;
             }
+            TeamsCommand::RemoveRepo { repo, team } => {
+                commands::teams::remove_repo(&repo, team.as_deref())?;
+            }
+            TeamsCommand::Remove {
+                name,
+                stop,
+                purge,
+                yes,
+            } => commands::teams::remove(&name, stop, purge, yes)?,
+            TeamsCommand::SetDefault { name } => commands::teams::set_default(&name)?,
+            TeamsCommand::Destroy {
+                name,
+                keep_github,
+                dry_run,
+                yes,
+            } => commands::teams::destroy(&name, keep_github, dry_run, yes)?,
         },
 
-        Command::Hire { role, name, team } => {
-            commands::hire::run(&role, name.as_deref(), team.as_deref())?;
+        Command::Hire {
+            role,
+            name,
+            adopt_existing,
+            dry_run,
+            team,
+        } => {
+            if dry_run {
+                let plan =
+                    commands::hire::plan(&role, name.as_deref(), adopt_existing, team.as_deref())?;
+                print!("{}", plan);
+            } else {
+                let hired =
+                    commands::hire::run(&role, name.as_deref(), adopt_existing, team.as_deref())?;
+                print!("{}", hired);
+            }
         }
 
         Command::Members { command } => match command {
@@ -49,6 +151,9 @@ fn main() -> Result<()> {
             RolesCommand::List { team } => {
                 commands::roles::list(team.as_deref())?;
             }
+            RolesCommand::Describe { role, full, team } => {
+                commands::roles::describe(&role, full, team.as_deref())?;
+            }
         },
 
         Command::Projects { command } => match command {
@@ -58,14 +163,45 @@ fn main() -> Result<()> {
             ProjectsCommand::Show { project, team } => {
                 commands::projects::show(&project, team.as_deref())?;
             }
-            ProjectsCommand::Add { url, team } => {
-                commands::projects::add(&url, team.as_deref())?;
+            ProjectsCommand::Add { url, dry_run, team } => {
+                if dry_run {
+                    print!("{}", commands::projects::add_plan(&url, team.as_deref())?);
+                } else {
+                    commands::projects::add(&url, team.as_deref())?;
+                }
+            }
+            ProjectsCommand::Sync { team, create_views, check } => {
+                commands::projects::sync(team.as_deref(), create_views, check)?;
+            }
+            ProjectsCommand::Seed { team, dry_run } => {
+                commands::projects::seed(team.as_deref(), dry_run)?;
+            }
+        },
+
+        Command::Board { command } => match command {
+            BoardCommand::View { view, open, team } => {
+                commands::board::view(view.as_deref(), open, team.as_deref())?;
+            }
+        },
+
+        Command::Config { command } => match command {
+            ConfigCommand::Get { key, team } => commands::config::get(&key, team.as_deref())?,
+            ConfigCommand::Set { key, value, team } => {
+                commands::config::set(&key, Some(&value), team.as_deref())?
             }
-            ProjectsCommand::Sync { team } => {
-                commands::projects::sync(team.as_deref())?;
+            ConfigCommand::Unset { key, team } => {
+                commands::config::set(&key, None, team.as_deref())?
             }
         },
 
+        Command::State { command } => match command {
+            StateCommand::History {
+                member,
+                limit,
+                team,
+            } => commands::state::history(member.as_deref(), limit, team.as_deref())?,
+        },
+
         Command::Knowledge {
             command,
             team,
@@ -80,6 +216,50 @@ fn main() -> Result<()> {
                 let team_flag = t.as_deref().or(team.as_deref());
                 commands::knowledge::show(&path, team_flag)?;
             }
+            Some(KnowledgeCommand::Add {
+                scope: add_scope,
+                name,
+                project,
+                member,
+                invariant,
+                from_file,
+                edit,
+                team: t,
+            }) => {
+                let team_flag = t.as_deref().or(team.as_deref());
+                let target = commands::knowledge::AddTarget {
+                    project: project.as_deref(),
+                    member: member.as_deref(),
+                    invariant,
+                };
+                let source = match (from_file, edit) {
+                    (Some(path), _) => commands::knowledge::AddSource::File(path),
+                    (None, true) => commands::knowledge::AddSource::Editor,
+                    (None, false) => commands::knowledge::AddSource::Template,
+                };
+                commands::knowledge::add(&add_scope, &name, &target, source, team_flag)?;
+            }
+            Some(KnowledgeCommand::Edit { path, team: t }) => {
+                let team_flag = t.as_deref().or(team.as_deref());
+                commands::knowledge::edit(&path, team_flag)?;
+            }
+            Some(KnowledgeCommand::Grep {
+                pattern,
+                ignore_case,
+                context,
+                json,
+                team: t,
+                scope: s,
+            }) => {
+                let team_flag = t.as_deref().or(team.as_deref());
+                let scope_flag = s.as_deref().or(scope.as_deref());
+                let options = commands::knowledge::GrepOptions {
+                    ignore_case,
+                    context,
+                    json,
+                };
+                commands::knowledge::grep(&pattern, &options, team_flag, scope_flag)?;
+            }
             None => {
                 commands::knowledge::interactive(team.as_deref(), scope.as_deref())?;
             }
@@ -91,14 +271,191 @@ fn main() -> Result<()> {
                 mode,
                 port,
                 interval,
+                bind,
+                path,
+                tls_cert,
+                tls_key,
+                tunnel,
+                insecure_no_signature,
+                dry_run,
+            } => {
+                let listener = ListenerFlags {
+                    bind,
+                    path,
+                    tls_cert,
+                    tls_key,
+                    insecure_no_signature,
+                };
+                let tunnel = tunnel.as_deref().map(Provider::parse).transpose()?;
+                if dry_run {
+                    let plan = commands::daemon::start_plan(
+                        team.as_deref(),
+                        mode.as_deref(),
+                        port,
+                        interval,
+                        &listener,
+                        tunnel,
+                    )?;
+                    print!("{}", plan);
+                } else {
+                    let started = commands::daemon::start(
+                        team.as_deref(),
+                        mode.as_deref(),
+                        port,
+                        interval,
+                        &listener,
+                        tunnel,
+                    )?;
+                    print!("{}", started);
+                }
+            }
+            DaemonCommand::Restart {
+                team,
+                mode,
+                port,
+                interval,
+                bind,
+                path,
+                tls_cert,
+                tls_key,
+                tunnel,
+                insecure_no_signature,
+            } => {
+                let listener = ListenerFlags {
+                    bind,
+                    path,
+                    tls_cert,
+                    tls_key,
+                    insecure_no_signature,
+                };
+                let tunnel = tunnel.as_deref().map(Provider::parse).transpose()?;
+                let started = commands::daemon::restart(
+                    team.as_deref(),
+                    mode.as_deref(),
+                    port,
+                    interval,
+                    &listener,
+                    tunnel,
+                )?;
+                print!("{}", started);
+            }
+            DaemonCommand::Stop {
+                team,
+                drain_timeout,
+                no_drain,
+            } => {
+                let drain = (!no_drain).then(|| Duration::from_secs(drain_timeout));
+                commands::daemon::stop(team.as_deref(), drain)?;
+            }
+            DaemonCommand::Status { team, all } => {
+                if all {
+                    commands::daemon::status_all()?;
+                } else {
+                    commands::daemon::status(team.as_deref())?;
+                }
+            }
+            DaemonCommand::History { team, limit } => {
+                commands::daemon::history(team.as_deref(), limit)?;
+            }
+            DaemonCommand::Simulate {
+                event,
+                team,
+                action,
+                labels,
+                author,
+                repo,
+                branch,
+                payload,
+                expr,
             } => {
-                commands::daemon::start(team.as_deref(), &mode, port, interval)?;
+                let event = TriggerEvent {
+                    event,
+                    action: action.unwrap_or_default(),
+                    author: author.unwrap_or_default(),
+                    repo: repo.unwrap_or_default(),
+                    branch: branch.unwrap_or_default(),
+                    labels,
+                };
+                commands::daemon::simulate(
+                    team.as_deref(),
+                    event,
+                    payload.as_deref(),
+                    expr.as_deref(),
+                )?;
+            }
+            DaemonCommand::Config { command } => match command {
+                DaemonConfigCommand::Get { key, team } => {
+                    commands::daemon::config_get(team.as_deref(), key.as_deref())?;
+                }
+                DaemonConfigCommand::Set {
+                    key,
+                    value,
+                    team,
+                    shared,
+                } => {
+                    commands::daemon::config_set(team.as_deref(), &key, Some(&value), shared)?;
+                }
+                DaemonConfigCommand::Unset { key, team, shared } => {
+                    commands::daemon::config_set(team.as_deref(), &key, None, shared)?;
+                }
+            },
+            DaemonCommand::Events { command } => match command {
+                DaemonEventsCommand::List { team, limit } => {
+                    commands::daemon::events_list(team.as_deref(), limit)?;
+                }
+                DaemonEventsCommand::Show { id, team } => {
+                    commands::daemon::events_show(team.as_deref(), &id)?;
+                }
+                DaemonEventsCommand::Replay { id, team } => {
+                    commands::daemon::events_replay(team.as_deref(), &id)?;
+                }
+            },
+        },
+
+        Command::Webhooks { command } => match command {
+            WebhooksCommand::Setup { team, url } => {
+                commands::webhooks::setup(team.as_deref(), &url)?;
             }
-            DaemonCommand::Stop { team } => {
-                commands::daemon::stop(team.as_deref())?;
+            WebhooksCommand::Status { team } => {
+                commands::webhooks::status(team.as_deref())?;
             }
-            DaemonCommand::Status { team } => {
-                commands::daemon::status(team.as_deref())?;
+        },
+
+        Command::Notify { command } => match command {
+            NotifyCommand::Test { team } => {
+                commands::notify::test(team.as_deref())?;
+            }
+        },
+
+        Command::Workspace { command } => match command {
+            WorkspaceCommand::Snapshot {
+                member,
+                no_git_objects,
+                team,
+            } => commands::workspace::snapshot(&member, !no_git_objects, team.as_deref())?,
+            WorkspaceCommand::Snapshots { member, team } => {
+                commands::workspace::list(&member, team.as_deref())?
+            }
+            WorkspaceCommand::Restore {
+                member,
+                snapshot_id,
+                team,
+            } => commands::workspace::restore(&member, &snapshot_id, team.as_deref())?,
+        },
+
+        Command::Topology { command } => match command {
+            TopologyCommand::Refresh { team } => {
+                commands::topology::refresh(team.as_deref())?;
+            }
+        },
+
+        Command::Chatops { team } => {
+            commands::chatops::run(team.as_deref())?;
+        }
+
+        Command::Diag { command } => match command {
+            DiagCommand::Bundle { team, output, logs } => {
+                commands::diag::bundle(team.as_deref(), output.as_deref(), logs)?;
             }
         },
 
@@ -107,22 +464,138 @@ fn main() -> Result<()> {
             mode,
             port,
             interval,
+            bind,
+            path,
+            tls_cert,
+            tls_key,
+            insecure_no_signature,
         } => {
-            commands::daemon::run_daemon(&team, &mode, port, interval)?;
+            let tls = tls_cert
+                .zip(tls_key)
+                .map(|(cert, key)| TlsFiles { cert, key });
+            let listener = Listener {
+                bind,
+                path,
+                tls,
+                insecure_no_signature,
+            };
+            commands::daemon::run_daemon(&team, &mode, port, interval, listener)?;
+        }
+
+        Command::TokenRefresh { team } => {
+            bm::github_app::run_refresher(&team)?;
         }
 
-        Command::Start { team, formation } => {
-            commands::start::run(team.as_deref(), formation.as_deref())?;
+        Command::Start {
+            team,
+            formation,
+            migrate,
+            force,
+            override_budget,
+            dry_run,
+        } => {
+            if let (true, Some(formation)) = (migrate, formation.as_deref()) {
+                let migrated =
+                    commands::start::migrate(team.as_deref(), formation, force, override_budget)?;
+                print!("{}", migrated);
+            } else if dry_run {
+                let plan = commands::start::plan(
+                    team.as_deref(),
+                    formation.as_deref(),
+                    force,
+                    override_budget,
+                )?;
+                print!("{}", plan);
+            } else {
+                let started = commands::start::run(
+                    team.as_deref(),
+                    formation.as_deref(),
+                    force,
+                    override_budget,
+                )?;
+                print!("{}", started);
+                started.ensure_ok()?;
+            }
+        }
+        Command::Stop {
+            team,
+            force,
+            drain,
+            graceful_timeout,
+            all,
+        } => {
+            let mode = if force {
+                commands::stop::StopMode::Force
+            } else if drain {
+                commands::stop::StopMode::Drain {
+                    timeout: graceful_timeout,
+                }
+            } else {
+                commands::stop::StopMode::Graceful {
+                    timeout: graceful_timeout,
+                }
+            };
+            if all {
+                commands::stop::run_all(mode)?;
+            } else {
+                commands::stop::run(team.as_deref(), mode)?;
+            }
+        }
+        Command::Status {
+            team,
+            verbose,
+            watch,
+            interval,
+            stats,
+            group_by,
+            sort,
+            all,
+        } => {
+            let view =
+                commands::status::View::parse(verbose, stats, group_by.as_deref(), &sort)?;
+            if all {
+                commands::status::run_all(view.sort)?;
+            } else if watch {
+                commands::status::watch(team.as_deref(), view, interval)?;
+            } else {
+                commands::status::run(team.as_deref(), view)?;
+            }
         }
-        Command::Stop { team, force } => {
-            commands::stop::run(team.as_deref(), force)?;
+        Command::Pause { team } => {
+            commands::pause::pause(team.as_deref())?;
         }
-        Command::Status { team, verbose } => {
-            commands::status::run(team.as_deref(), verbose)?;
+        Command::Resume { team } => {
+            commands::pause::resume(team.as_deref())?;
         }
-        Command::Completions { shell } => {
-            commands::completions::run(shell)?;
+        Command::Usage { team, since } => {
+            commands::usage::run(team.as_deref(), since.as_deref())?;
         }
+        Command::Audit { since, team, limit } => {
+            commands::audit::run(since.as_deref(), team.as_deref(), limit)?;
+        }
+        Command::Dashboard { team } => {
+            commands::dashboard::run(team.as_deref())?;
+        }
+        Command::Exec {
+            member,
+            command,
+            team,
+        } => {
+            let code = commands::exec::run(&member, &command, team.as_deref())?;
+            if code != 0 {
+                std::process::exit(code);
+            }
+        }
+        Command::Completions { command, shell } => match command {
+            Some(CompletionsCommand::Install { shell }) => {
+                commands::completions::install(shell)?;
+            }
+            None => {
+                if let Some(shell) = shell {
+                    commands::completions::run(shell)?;
+                }
+            }
+        },
     }
 
     Ok(())
@@ -0,0 +1,335 @@
+//! MCP servers for member workspaces.
+//!
+//! The team repo's `botminter.yml` and each member's `botminter.yml` may carry
+//! an `mcp:` section keyed by server name:
+//!
+//! ```yaml
+//! mcp:
+//!   github:
+//!     command: github-mcp-server
+//!     args: [stdio]
+//!     env:
+//!       GITHUB_PERSONAL_ACCESS_TOKEN: "${GH_TOKEN}"
+//!     allowed_tools: [get_issue, list_issues]
+//! ```
+//!
+//! Member entries replace team entries of the same name, and `enabled: false`
+//! drops one. `bm teams sync` writes the result to the workspace's `.mcp.json`
+//! and enables the servers and their tools in `.claude/settings.json`, which
+//! Claude (and so Ralph) picks up on its next run.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::parse;
+
+/// Workspace file holding the rendered server definitions.
+pub const MCP_JSON: &str = ".mcp.json";
+
+/// Prefix of the Claude permission rules for MCP tools.
+const RULE_PREFIX: &str = "mcp__";
+
+/// One MCP server declaration.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct McpServer {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Passed to the server as-is; Claude expands `${VAR}` references.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Tools agents may call without prompting. Empty allows every tool.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_tools: Vec<String>,
+    /// `false` in a member manifest drops a team-wide server.
+    #[serde(default = "default_enabled", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+/// Servers by name.
+pub type Servers = BTreeMap<String, McpServer>;
+
+/// Names end up in tool IDs (`mcp__<server>__<tool>`), so keep them simple.
+fn is_identifier(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Checks server and tool names, and that enabled servers have a command.
+pub fn validate(servers: &Servers) -> Result<()> {
+    for (name, server) in servers {
+        if !is_identifier(name) {
+            bail!(
+                "Invalid MCP server name '{}'. Use letters, digits, '-' and '_'.",
+                name
+            );
+        }
+        if server.enabled && server.command.trim().is_empty() {
+            bail!("MCP server '{}' has no command", name);
+        }
+        if let Some(tool) = server.allowed_tools.iter().find(|t| !is_identifier(t)) {
+            bail!(
+                "Invalid tool name '{}' in allowed_tools of MCP server '{}'",
+                tool,
+                name
+            );
+        }
+        if server.env.keys().any(|k| k.is_empty()) {
+            bail!("MCP server '{}' has an empty env variable name", name);
+        }
+    }
+    Ok(())
+}
+
+/// Overlays member servers on team servers and drops disabled ones. `None`
+/// when neither manifest has an `mcp:` section, so bm leaves the workspace's
+/// MCP config alone.
+pub fn merge(team: Option<&Servers>, member: Option<&Servers>) -> Option<Servers> {
+    if team.is_none() && member.is_none() {
+        return None;
+    }
+    let mut servers = team.cloned().unwrap_or_default();
+    servers.extend(member.cloned().unwrap_or_default());
+    servers.retain(|_, s| s.enabled);
+    Some(servers)
+}
+
+/// The `.mcp.json` document for `servers`.
+fn mcp_json(servers: &Servers) -> Value {
+    let defs: serde_json::Map<String, Value> = servers
+        .iter()
+        .map(|(name, s)| {
+            let mut def = json!({ "command": s.command, "args": s.args });
+            if !s.env.is_empty() {
+                def["env"] = json!(s.env);
+            }
+            (name.clone(), def)
+        })
+        .collect();
+    json!({ "mcpServers": defs })
+}
+
+/// Permission rules allowing the servers' tools.
+fn allow_rules(servers: &Servers) -> Vec<String> {
+    let mut rules = Vec::new();
+    for (name, s) in servers {
+        if s.allowed_tools.is_empty() {
+            rules.push(format!("{}{}", RULE_PREFIX, name));
+        }
+        for tool in &s.allowed_tools {
+            rules.push(format!("{}{}__{}", RULE_PREFIX, name, tool));
+        }
+    }
+    rules
+}
+
+/// Updates Claude settings for `servers`: enables them and replaces every
+/// `mcp__` allow rule. Other settings are kept.
+fn merge_settings(existing: Option<Value>, servers: &Servers) -> Value {
+    let mut settings = match existing {
+        Some(v @ Value::Object(_)) => v,
+        _ => json!({}),
+    };
+    settings["enabledMcpjsonServers"] = json!(servers.keys().collect::<Vec<_>>());
+
+    if !settings["permissions"].is_object() {
+        settings["permissions"] = json!({});
+    }
+    let mut allow: Vec<Value> = settings["permissions"]["allow"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|rule| !rule.as_str().is_some_and(|r| r.starts_with(RULE_PREFIX)))
+        .collect();
+    allow.extend(allow_rules(servers).into_iter().map(Value::String));
+    settings["permissions"]["allow"] = Value::Array(allow);
+    settings
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct McpSection {
+    #[serde(default)]
+    mcp: Option<Servers>,
+}
+
+/// Reads and validates the `mcp:` section of a manifest, if any.
+fn read_section(path: &Path) -> Result<Option<Servers>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let section: McpSection = parse::yaml(path, &contents)?;
+    if let Some(ref servers) = section.mcp {
+        validate(servers).with_context(|| format!("Invalid mcp section in {}", path.display()))?;
+    }
+    Ok(section.mcp)
+}
+
+fn write_if_changed(path: &Path, contents: &str) -> Result<()> {
+    if fs::read_to_string(path).ok().as_deref() == Some(contents) {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Renders the team's and member's MCP servers into the workspace. Returns
+/// the generated paths outside `.claude/`, for git excludes.
+/// `template_outputs` are the paths the role's workspace templates wrote.
+pub fn render_mcp_config(
+    ws_root: &Path,
+    member_dir_name: &str,
+    template_outputs: &[String],
+) -> Result<Vec<String>> {
+    let bm_dir = ws_root.join(".botminter");
+    let team = read_section(&bm_dir.join("botminter.yml"))?;
+    let member = read_section(&bm_dir.join("team").join(member_dir_name).join("botminter.yml"))?;
+    let Some(servers) = merge(team.as_ref(), member.as_ref()) else {
+        return Ok(Vec::new());
+    };
+    if template_outputs.iter().any(|p| Path::new(p) == Path::new(MCP_JSON)) {
+        bail!(
+            "{} declares a workspace template for {} and the team uses `mcp:`. \
+             Remove one of them.",
+            member_dir_name,
+            MCP_JSON
+        );
+    }
+
+    let rendered = serde_json::to_string_pretty(&mcp_json(&servers))? + "\n";
+    write_if_changed(&ws_root.join(MCP_JSON), &rendered)?;
+
+    let settings_path = ws_root.join(".claude").join("settings.json");
+    let existing = fs::read_to_string(&settings_path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+    let settings = serde_json::to_string_pretty(&merge_settings(existing, &servers))? + "\n";
+    write_if_changed(&settings_path, &settings)?;
+
+    Ok(vec![MCP_JSON.to_string()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(command: &str, tools: &[&str]) -> McpServer {
+        McpServer {
+            command: command.to_string(),
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            allowed_tools: tools.iter().map(|t| t.to_string()).collect(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn member_servers_override_and_disable_team_servers() {
+        let team = Servers::from([
+            ("github".to_string(), server("github-mcp", &[])),
+            ("docs".to_string(), server("docs-mcp", &[])),
+        ]);
+        let mut off = server("", &[]);
+        off.enabled = false;
+        let member = Servers::from([
+            ("github".to_string(), server("github-mcp", &["get_issue"])),
+            ("docs".to_string(), off),
+            ("db".to_string(), server("db-mcp", &[])),
+        ]);
+
+        let merged = merge(Some(&team), Some(&member)).unwrap();
+        assert_eq!(merged.keys().collect::<Vec<_>>(), vec!["db", "github"]);
+        assert_eq!(merged["github"].allowed_tools, vec!["get_issue"]);
+        assert_eq!(merge(None, None), None);
+        assert_eq!(merge(Some(&Servers::new()), None), Some(Servers::new()));
+    }
+
+    #[test]
+    fn validate_rejects_bad_names_and_missing_commands() {
+        assert!(validate(&Servers::from([("gh".to_string(), server("x", &["a_b"]))])).is_ok());
+        for (name, s) in [
+            ("has space", server("x", &[])),
+            ("gh", server(" ", &[])),
+            ("gh", server("x", &["bad tool"])),
+        ] {
+            assert!(
+                validate(&Servers::from([(name.to_string(), s.clone())])).is_err(),
+                "{} {:?} should be rejected",
+                name,
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn settings_keep_unrelated_rules_and_replace_mcp_rules() {
+        let servers = Servers::from([
+            ("github".to_string(), server("g", &["get_issue", "list_issues"])),
+            ("docs".to_string(), server("d", &[])),
+        ]);
+        let existing = json!({
+            "model": "opus",
+            "permissions": { "allow": ["Bash(git:*)", "mcp__old__tool"] }
+        });
+        let settings = merge_settings(Some(existing), &servers);
+        assert_eq!(settings["model"], "opus");
+        assert_eq!(settings["enabledMcpjsonServers"], json!(["docs", "github"]));
+        assert_eq!(
+            settings["permissions"]["allow"],
+            json!([
+                "Bash(git:*)",
+                "mcp__docs",
+                "mcp__github__get_issue",
+                "mcp__github__list_issues"
+            ])
+        );
+    }
+
+    #[test]
+    fn render_writes_mcp_json_and_settings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path();
+        fs::create_dir_all(ws.join(".botminter/team/dev-bob")).unwrap();
+        fs::write(
+            ws.join(".botminter/botminter.yml"),
+            "name: t\nmcp:\n  github:\n    command: github-mcp-server\n    args: [stdio]\n    \
+             env:\n      TOKEN: \"${GH_TOKEN}\"\n",
+        )
+        .unwrap();
+        fs::write(ws.join(".botminter/team/dev-bob/botminter.yml"), "role: dev\n").unwrap();
+
+        let written = render_mcp_config(ws, "dev-bob", &[]).unwrap();
+        assert_eq!(written, vec![MCP_JSON]);
+        let mcp: Value =
+            serde_json::from_str(&fs::read_to_string(ws.join(MCP_JSON)).unwrap()).unwrap();
+        assert_eq!(mcp["mcpServers"]["github"]["command"], "github-mcp-server");
+        assert_eq!(mcp["mcpServers"]["github"]["args"], json!(["stdio"]));
+        assert_eq!(mcp["mcpServers"]["github"]["env"]["TOKEN"], "${GH_TOKEN}");
+        let settings: Value = serde_json::from_str(
+            &fs::read_to_string(ws.join(".claude/settings.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(settings["permissions"]["allow"], json!(["mcp__github"]));
+
+        // A template for the same file is a conflict
+        let err = render_mcp_config(ws, "dev-bob", &[MCP_JSON.to_string()]).unwrap_err();
+        assert!(err.to_string().contains("Remove one of them"), "{}", err);
+    }
+}
@@ -0,0 +1,166 @@
+//! Template variables in a member's `PROMPT.md` and `CLAUDE.md`.
+//!
+//! Role skeletons can refer to the member they are hired as:
+//!
+//! ```markdown
+//! You are {{ member_name }}, the {{ role }} of team {{ team_name }}.
+//! Issues live in {{ github_repo }}.
+//! ```
+//!
+//! `bm hire` fills the placeholders in before committing the member, and
+//! `bm teams sync` fills in any that arrive later, e.g. from `bm upgrade`.
+//! Syntax and error handling are those of workspace templates.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::parse;
+use crate::profile_checksums::{self, ProfileChecksums};
+use crate::workspace_templates;
+
+/// Member files the template pass applies to.
+pub const TEMPLATED_FILES: &[&str] = &["PROMPT.md", "CLAUDE.md"];
+
+#[derive(Debug, Deserialize, Default)]
+struct MemberManifest {
+    name: Option<String>,
+    role: Option<String>,
+}
+
+/// Builds the variables for the member in `member_dir` (a directory under
+/// the team repo's `team/`), reading its name and role from `botminter.yml`.
+pub fn vars(
+    member_dir: &Path,
+    team_name: &str,
+    github_repo: &str,
+) -> Result<BTreeMap<&'static str, String>> {
+    let member_dir_name = member_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let manifest_path = member_dir.join("botminter.yml");
+    let manifest: MemberManifest = match fs::read_to_string(&manifest_path) {
+        Ok(contents) => parse::yaml(&manifest_path, &contents)?,
+        Err(_) => MemberManifest::default(),
+    };
+    let role = manifest.role.unwrap_or_default();
+    let member_name = manifest.name.unwrap_or_else(|| {
+        member_dir_name
+            .strip_prefix(&format!("{}-", role))
+            .unwrap_or(&member_dir_name)
+            .to_string()
+    });
+
+    Ok(BTreeMap::from([
+        ("member_name", member_name),
+        ("member_dir", member_dir_name),
+        ("role", role),
+        ("team_name", team_name.to_string()),
+        ("github_repo", github_repo.to_string()),
+    ]))
+}
+
+/// The member's templated files that still contain placeholders, i.e. those
+/// `render_member_files` would rewrite.
+pub fn unrendered_files(member_dir: &Path) -> Vec<&'static str> {
+    TEMPLATED_FILES
+        .iter()
+        .copied()
+        .filter(|name| {
+            fs::read_to_string(member_dir.join(name)).is_ok_and(|c| c.contains("{{"))
+        })
+        .collect()
+}
+
+/// Fills in the placeholders of the member's templated files, rewriting only
+/// those that contain any. Returns the names of the files rewritten.
+///
+/// A file that still matched its profile checksum is recorded with its
+/// rendered contents, so `bm upgrade` keeps treating it as unmodified.
+pub fn render_member_files(
+    member_dir: &Path,
+    vars: &BTreeMap<&'static str, String>,
+) -> Result<Vec<String>> {
+    let checksums = ProfileChecksums::load(member_dir)?;
+    let mut rendered_files = Vec::new();
+    let mut pristine = Vec::new();
+
+    for name in TEMPLATED_FILES {
+        let path = member_dir.join(name);
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if !contents.contains("{{") {
+            continue;
+        }
+        let rendered = workspace_templates::render(&contents, vars)
+            .with_context(|| format!("Failed to render {}", path.display()))?;
+        fs::write(&path, &rendered)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        let was_pristine = checksums.as_ref().is_some_and(|c| {
+            c.files.get(*name) == Some(&profile_checksums::checksum(contents.as_bytes()))
+        });
+        if was_pristine {
+            pristine.push((*name, rendered));
+        }
+        rendered_files.push(name.to_string());
+    }
+
+    if !pristine.is_empty() {
+        profile_checksums::update(member_dir, |checksums| {
+            for (name, rendered) in &pristine {
+                checksums.record(Path::new(name), rendered.as_bytes());
+            }
+        })?;
+    }
+    Ok(rendered_files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile_checksums::FileOrigin;
+
+    #[test]
+    fn render_member_files_fills_in_member_details() {
+        let tmp = tempfile::tempdir().unwrap();
+        let member_dir = tmp.path().join("human-assistant-bob");
+        fs::create_dir_all(&member_dir).unwrap();
+        fs::write(member_dir.join("botminter.yml"), "role: human-assistant\n").unwrap();
+        let prompt = "I am {{ member_name }} ({{role}}) on {{ team_name }}, see {{github_repo}}.";
+        fs::write(member_dir.join("PROMPT.md"), prompt).unwrap();
+        fs::write(member_dir.join("CLAUDE.md"), "# Nothing to fill in").unwrap();
+        let mut checksums = ProfileChecksums::new("scrum", "1", Some("human-assistant"));
+        checksums.record(Path::new("PROMPT.md"), prompt.as_bytes());
+        checksums.save(&member_dir).unwrap();
+
+        let vars = vars(&member_dir, "my-team", "org/repo").unwrap();
+        let rendered = render_member_files(&member_dir, &vars).unwrap();
+
+        assert_eq!(rendered, vec!["PROMPT.md"]);
+        assert_eq!(
+            fs::read_to_string(member_dir.join("PROMPT.md")).unwrap(),
+            "I am bob (human-assistant) on my-team, see org/repo."
+        );
+        let checksums = ProfileChecksums::load(&member_dir).unwrap().unwrap();
+        assert_eq!(
+            checksums.check(&member_dir),
+            vec![("PROMPT.md".to_string(), FileOrigin::Pristine)]
+        );
+        assert!(render_member_files(&member_dir, &vars).unwrap().is_empty());
+    }
+
+    #[test]
+    fn render_member_files_rejects_unknown_variables() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("CLAUDE.md"), "{{ member }}").unwrap();
+        let vars = vars(tmp.path(), "t", "o/r").unwrap();
+        let err = render_member_files(tmp.path(), &vars).unwrap_err();
+        assert!(format!("{:#}", err).contains("Unknown template variable 'member'"), "{:#}", err);
+    }
+}
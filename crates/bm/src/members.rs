@@ -0,0 +1,331 @@
+//! Member discovery: the members hired into a team repo (`team/team/<member>/`)
+//! and the workspaces they run in (`<workzone>/<team>/<member>/[<project>/]`).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::parse;
+
+/// A hired member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    /// Directory name under `team/team/`, e.g. `dev-bob`.
+    pub name: String,
+    /// Role from the member's `botminter.yml`, or inferred from the name.
+    pub role: String,
+}
+
+/// How a member's workspace is laid out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceKind {
+    /// A fork clone of the named project at `<member>/<project>/`.
+    Project(String),
+    /// A plain repo at `<member>/`, for teams without projects.
+    NoProject,
+}
+
+/// A member's workspace on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    pub path: PathBuf,
+    pub kind: WorkspaceKind,
+}
+
+/// Minimal member manifest for reading role.
+#[derive(Debug, Deserialize)]
+struct MemberManifest {
+    #[serde(default)]
+    role: Option<String>,
+}
+
+/// Lists member directory names under `members_dir`, sorted, skipping hidden
+/// entries.
+pub fn list_names(members_dir: &Path) -> Result<Vec<String>> {
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(members_dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        dirs.push(name);
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Lists the members under `members_dir` with their roles, sorted by name.
+/// An unreadable manifest falls back to the inferred role.
+pub fn list(members_dir: &Path) -> Result<Vec<Member>> {
+    Ok(list_names(members_dir)?
+        .into_iter()
+        .map(|name| Member {
+            role: read_role(members_dir, &name),
+            name,
+        })
+        .collect())
+}
+
+/// Loads one member, failing if its `botminter.yml` can't be read or parsed.
+pub fn load(members_dir: &Path, name: &str) -> Result<Member> {
+    let manifest_path = members_dir.join(name).join("botminter.yml");
+    let role = if manifest_path.exists() {
+        let contents = fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+        let manifest: MemberManifest = parse::yaml(&manifest_path, &contents)?;
+        manifest.role.unwrap_or_else(|| infer_role(name))
+    } else {
+        infer_role(name)
+    };
+    Ok(Member {
+        name: name.to_string(),
+        role,
+    })
+}
+
+/// Reads the role from a member's botminter.yml, falling back to dir-name inference.
+pub fn read_role(members_dir: &Path, name: &str) -> String {
+    let manifest_path = members_dir.join(name).join("botminter.yml");
+    fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|contents| serde_yml::from_str::<MemberManifest>(&contents).ok())
+        .and_then(|manifest| manifest.role)
+        .unwrap_or_else(|| infer_role(name))
+}
+
+/// Infers the role from a member dir name by taking everything before the first '-'.
+pub fn infer_role(name: &str) -> String {
+    name.split('-').next().unwrap_or("unknown").to_string()
+}
+
+/// Lists a member's workspaces under `team_ws_base`: one per project at
+/// `{team_ws_base}/{member}/{project}/`, sorted by project, or else the
+/// no-project workspace at `{team_ws_base}/{member}/`. A workspace is a
+/// directory with a `.botminter/` marker; hidden directories are skipped.
+pub fn workspaces(team_ws_base: &Path, name: &str) -> Vec<Workspace> {
+    let member_ws = team_ws_base.join(name);
+    let mut projects: Vec<String> = fs::read_dir(&member_ws)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|project| !project.starts_with('.'))
+        .filter(|project| member_ws.join(project).join(".botminter").is_dir())
+        .collect();
+    projects.sort();
+
+    if projects.is_empty() && member_ws.join(".botminter").is_dir() {
+        return vec![Workspace {
+            path: member_ws,
+            kind: WorkspaceKind::NoProject,
+        }];
+    }
+    projects
+        .into_iter()
+        .map(|project| Workspace {
+            path: member_ws.join(&project),
+            kind: WorkspaceKind::Project(project),
+        })
+        .collect()
+}
+
+/// Finds a member's workspace: the first of [`workspaces`], i.e. the
+/// alphabetically first project's, or the no-project one.
+pub fn find_workspace(team_ws_base: &Path, name: &str) -> Option<Workspace> {
+    workspaces(team_ws_base, name).into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── list_names / list / load ──────────────────────────────────
+
+    #[test]
+    fn list_names_returns_sorted_dirs_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        // Create directories
+        fs::create_dir(tmp.path().join("bob")).unwrap();
+        fs::create_dir(tmp.path().join("alice")).unwrap();
+        fs::create_dir(tmp.path().join(".hidden")).unwrap();
+        // Create a plain file
+        fs::write(tmp.path().join("file.txt"), "hello").unwrap();
+
+        let result = list_names(tmp.path()).unwrap();
+        assert_eq!(result, vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn list_names_empty_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let result = list_names(tmp.path()).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn list_reads_and_infers_roles() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir(tmp.path().join("architect-alice")).unwrap();
+        fs::write(tmp.path().join("architect-alice/botminter.yml"), "role: architect\n").unwrap();
+        fs::create_dir(tmp.path().join("po-bob")).unwrap();
+        fs::write(tmp.path().join("po-bob/botminter.yml"), "role: [broken\n").unwrap();
+
+        let members = list(tmp.path()).unwrap();
+        assert_eq!(
+            members,
+            [
+                Member {
+                    name: "architect-alice".to_string(),
+                    role: "architect".to_string()
+                },
+                Member {
+                    name: "po-bob".to_string(),
+                    role: "po".to_string()
+                },
+            ]
+        );
+        assert!(load(tmp.path(), "po-bob").is_err());
+        assert_eq!(load(tmp.path(), "architect-alice").unwrap(), members[0]);
+    }
+
+    // ── read_role ─────────────────────────────────────────────────
+
+    #[test]
+    fn read_role_from_yaml() {
+        let tmp = tempfile::tempdir().unwrap();
+        let member_dir = tmp.path().join("architect-alice");
+        fs::create_dir(&member_dir).unwrap();
+        fs::write(member_dir.join("botminter.yml"), "role: architect\n").unwrap();
+
+        assert_eq!(read_role(tmp.path(), "architect-alice"), "architect");
+    }
+
+    #[test]
+    fn read_role_yaml_with_extra_fields() {
+        let tmp = tempfile::tempdir().unwrap();
+        let member_dir = tmp.path().join("po-bob");
+        fs::create_dir(&member_dir).unwrap();
+        fs::write(
+            member_dir.join("botminter.yml"),
+            "role: product-owner\nschema_version: '0.3'\n",
+        )
+        .unwrap();
+
+        assert_eq!(read_role(tmp.path(), "po-bob"), "product-owner");
+    }
+
+    #[test]
+    fn read_role_fallback_no_yaml() {
+        let tmp = tempfile::tempdir().unwrap();
+        // Dir exists but no botminter.yml
+        fs::create_dir(tmp.path().join("architect-alice")).unwrap();
+
+        assert_eq!(read_role(tmp.path(), "architect-alice"), "architect");
+    }
+
+    #[test]
+    fn read_role_fallback_no_role_field() {
+        let tmp = tempfile::tempdir().unwrap();
+        let member_dir = tmp.path().join("po-bob");
+        fs::create_dir(&member_dir).unwrap();
+        // YAML exists but has no 'role' field
+        fs::write(member_dir.join("botminter.yml"), "schema_version: '0.3'\n").unwrap();
+
+        assert_eq!(read_role(tmp.path(), "po-bob"), "po");
+    }
+
+    // ── infer_role ────────────────────────────────────────────────
+
+    #[test]
+    fn infer_role_takes_prefix_before_dash() {
+        assert_eq!(infer_role("architect-alice"), "architect");
+        assert_eq!(infer_role("po-bob-senior"), "po");
+        assert_eq!(infer_role("superman"), "superman");
+        assert_eq!(infer_role(""), "");
+    }
+
+    // ── find_workspace / workspaces ───────────────────────────────
+
+    #[test]
+    fn find_workspace_project_mode() {
+        let tmp = tempfile::tempdir().unwrap();
+        let team_ws_base = tmp.path();
+        // Create: team_ws_base/member/project/.botminter/
+        let project_dir = team_ws_base.join("member").join("my-project");
+        fs::create_dir_all(project_dir.join(".botminter")).unwrap();
+
+        let result = find_workspace(team_ws_base, "member");
+        assert_eq!(
+            result,
+            Some(Workspace {
+                path: project_dir,
+                kind: WorkspaceKind::Project("my-project".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn find_workspace_no_project_mode() {
+        let tmp = tempfile::tempdir().unwrap();
+        let team_ws_base = tmp.path();
+        // Create: team_ws_base/member/.botminter/ (no project subdir)
+        let member_dir = team_ws_base.join("member");
+        fs::create_dir_all(member_dir.join(".botminter")).unwrap();
+
+        let result = find_workspace(team_ws_base, "member");
+        assert_eq!(
+            result,
+            Some(Workspace {
+                path: member_dir,
+                kind: WorkspaceKind::NoProject,
+            })
+        );
+    }
+
+    #[test]
+    fn find_workspace_missing_member_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let result = find_workspace(tmp.path(), "nonexistent");
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn find_workspace_no_botminter_marker() {
+        let tmp = tempfile::tempdir().unwrap();
+        // Create member dir without .botminter/
+        fs::create_dir_all(tmp.path().join("member")).unwrap();
+
+        let result = find_workspace(tmp.path(), "member");
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn workspaces_are_sorted_and_skip_hidden_dirs() {
+        let tmp = tempfile::tempdir().unwrap();
+        for dir in ["arch/beta/.botminter", "arch/alpha/.botminter", "arch/.git/.botminter"] {
+            fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        }
+        fs::create_dir_all(tmp.path().join("arch/notes")).unwrap();
+
+        let kinds: Vec<WorkspaceKind> = workspaces(tmp.path(), "arch")
+            .into_iter()
+            .map(|ws| ws.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                WorkspaceKind::Project("alpha".to_string()),
+                WorkspaceKind::Project("beta".to_string())
+            ]
+        );
+        let first = find_workspace(tmp.path(), "arch").unwrap();
+        assert_eq!(first.path, tmp.path().join("arch/alpha"));
+        assert!(workspaces(tmp.path(), "missing").is_empty());
+    }
+}
@@ -0,0 +1,349 @@
+//! Notifications for people who don't watch the team's chat: member crashes,
+//! launch errors, failed runs, budget caps, daemon starts and stops, and a
+//! daily digest from the daemon.
+//!
+//! A team opts in by configuring backends in its credentials: an `smtp`
+//! server, and any number of `notifiers` (Telegram, Slack through a bot or an
+//! incoming webhook, a generic HTTP endpoint, or desktop notifications). Each backend
+//! picks the kinds it wants with `notify_on`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::TeamEntry;
+use crate::http_post;
+use crate::slack;
+use crate::smtp;
+use crate::telegram;
+
+/// What a notification is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    /// A member exited with an error.
+    Crash,
+    /// The daemon couldn't launch a member.
+    Error,
+    /// The daily summary of members and daemon.
+    Digest,
+    /// A one-shot run ended with a crash or launch error, summed up in one
+    /// message.
+    RunFailed,
+    /// The team reached a budget cap.
+    Budget,
+    /// The team's GitHub token entered the expiry warning window, or expired.
+    TokenExpiring,
+    /// The daemon started.
+    DaemonStart,
+    /// The daemon stopped.
+    DaemonStop,
+}
+
+impl Kind {
+    /// Every kind, in the order they are listed to users.
+    pub const ALL: [Kind; 8] = [
+        Kind::Crash,
+        Kind::Error,
+        Kind::Digest,
+        Kind::RunFailed,
+        Kind::Budget,
+        Kind::TokenExpiring,
+        Kind::DaemonStart,
+        Kind::DaemonStop,
+    ];
+
+    /// Whether a backend without `notify_on` gets this kind. Run summaries
+    /// repeat crash and error notices, and daemon starts and stops are
+    /// routine, so those are opt-in.
+    pub fn on_by_default(self) -> bool {
+        !matches!(self, Kind::RunFailed | Kind::DaemonStart | Kind::DaemonStop)
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Kind::Crash => "crash",
+            Kind::Error => "error",
+            Kind::Digest => "digest",
+            Kind::RunFailed => "run_failed",
+            Kind::Budget => "budget",
+            Kind::TokenExpiring => "token_expiring",
+            Kind::DaemonStart => "daemon_start",
+            Kind::DaemonStop => "daemon_stop",
+        })
+    }
+}
+
+/// Whether a backend routing `notify_on` wants notifications of `kind`.
+pub fn routes(notify_on: &[Kind], kind: Kind) -> bool {
+    if notify_on.is_empty() {
+        kind.on_by_default()
+    } else {
+        notify_on.contains(&kind)
+    }
+}
+
+/// A message about a team, ready to go out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub team: String,
+    pub kind: Kind,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Notification {
+    /// Prefixes `summary` with the team so mail from several teams sorts apart.
+    pub fn new(team_name: &str, kind: Kind, summary: &str, body: String) -> Notification {
+        Notification {
+            team: team_name.to_string(),
+            kind,
+            subject: format!("[bm {}] {}", team_name, summary),
+            body,
+        }
+    }
+}
+
+/// Where a notifier delivers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    /// The team's Telegram chat, through its bot.
+    Telegram,
+    /// A Slack incoming webhook, or the team's Slack channel through its bot.
+    Slack,
+    /// A JSON `POST` to any URL.
+    Http,
+    /// A desktop notification on this machine.
+    Desktop,
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Channel::Telegram => "telegram",
+            Channel::Slack => "slack",
+            Channel::Http => "http",
+            Channel::Desktop => "desktop",
+        })
+    }
+}
+
+/// One entry of a team's `notifiers` list.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Notifier {
+    #[serde(rename = "type")]
+    pub channel: Channel,
+    /// The Slack webhook URL (without one, Slack notifiers post with the
+    /// team's bot), or the endpoint for `http`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Extra request headers for `http`, e.g. `Authorization`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Notifications to deliver; when empty, all but `run_failed`,
+    /// `daemon_start`, and `daemon_stop`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notify_on: Vec<Kind>,
+}
+
+impl Notifier {
+    /// Checks the notifier has the settings its channel needs, and no others.
+    pub fn validate(&self) -> Result<()> {
+        let takes_url = matches!(self.channel, Channel::Slack | Channel::Http);
+        match self.url {
+            None if self.channel == Channel::Http => {
+                bail!("A {} notifier needs a url", self.channel)
+            }
+            Some(_) if !takes_url => bail!("A {} notifier takes no url", self.channel),
+            Some(ref url) if !url.starts_with("https://") && !url.starts_with("http://") => {
+                bail!(
+                    "Invalid {} notifier url '{}': expected http(s)://",
+                    self.channel,
+                    url
+                )
+            }
+            _ => {}
+        }
+        if !self.headers.is_empty() && self.channel != Channel::Http {
+            bail!("Only http notifiers take headers, not {}", self.channel);
+        }
+        Ok(())
+    }
+
+    pub fn wants(&self, kind: Kind) -> bool {
+        routes(&self.notify_on, kind)
+    }
+
+    /// Delivers `notification` regardless of `notify_on`.
+    pub fn deliver(&self, team: &TeamEntry, notification: &Notification) -> Result<()> {
+        match self.channel {
+            Channel::Telegram => {
+                let (Some(token), Some(chat_id)) = (
+                    team.credentials.telegram_bot_token.as_deref(),
+                    team.credentials.telegram_chat_id,
+                ) else {
+                    bail!(
+                        "Team '{}' has no telegram_bot_token and telegram_chat_id to notify with",
+                        team.name
+                    );
+                };
+                let text = format!("{}\n\n{}", notification.subject, notification.body);
+                telegram::send_message(token, chat_id, &text)
+            }
+            Channel::Slack => {
+                let text = format!("*{}*\n{}", notification.subject, notification.body);
+                if self.url.is_some() {
+                    let payload = serde_json::json!({ "text": text });
+                    return http_post::post_json(self.url(), &BTreeMap::new(), &payload);
+                }
+                let (Some(token), Some(channel)) = (
+                    team.credentials.slack_bot_token.as_deref(),
+                    team.credentials.slack_channel.as_deref(),
+                ) else {
+                    bail!(
+                        "Team '{}' has no slack_bot_token and slack_channel to notify with, \
+                         and the notifier has no webhook url",
+                        team.name
+                    );
+                };
+                slack::post_message(token, channel, &text)
+            }
+            Channel::Http => {
+                let payload = serde_json::json!({
+                    "team": notification.team,
+                    "kind": notification.kind,
+                    "subject": notification.subject,
+                    "body": notification.body,
+                    "at": chrono::Utc::now().to_rfc3339(),
+                });
+                http_post::post_json(self.url(), &self.headers, &payload)
+            }
+            Channel::Desktop => desktop(&notification.subject, &notification.body),
+        }
+    }
+
+    fn url(&self) -> &str {
+        self.url.as_deref().unwrap_or_default()
+    }
+}
+
+/// Shows a desktop notification with `notify-send`, or `osascript` on macOS.
+fn desktop(subject: &str, body: &str) -> Result<()> {
+    let mut cmd = if cfg!(target_os = "macos") {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut cmd = Command::new("osascript");
+        cmd.args([
+            "-e",
+            &format!(
+                "display notification {} with title {}",
+                quote(body),
+                quote(subject)
+            ),
+        ]);
+        cmd
+    } else {
+        let mut cmd = Command::new("notify-send");
+        cmd.args(["--app-name", "bm", subject, body]);
+        cmd
+    };
+    let program = cmd.get_program().to_string_lossy().to_string();
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to run {}; is it installed?", program))?;
+    if !output.status.success() {
+        bail!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Whether the team has a backend that wants notifications of `kind`.
+pub fn wanted(team: &TeamEntry, kind: Kind) -> bool {
+    team.credentials
+        .smtp
+        .as_ref()
+        .is_some_and(|s| s.wants(kind))
+        || team.credentials.notifiers.iter().any(|n| n.wants(kind))
+}
+
+/// Sends `notification` through each of the team's backends that wants its
+/// kind. Returns whether any did; a backend failing doesn't stop the others,
+/// but fails the send.
+pub fn send(team: &TeamEntry, notification: &Notification) -> Result<bool> {
+    let mut sent = false;
+    let mut failures = Vec::new();
+    if let Some(ref smtp) = team.credentials.smtp {
+        if smtp.wants(notification.kind) {
+            match smtp::send(smtp, &notification.subject, &notification.body) {
+                Ok(()) => sent = true,
+                Err(e) => failures.push(format!("smtp: {:#}", e)),
+            }
+        }
+    }
+    for notifier in &team.credentials.notifiers {
+        if notifier.wants(notification.kind) {
+            match notifier.deliver(team, notification) {
+                Ok(()) => sent = true,
+                Err(e) => failures.push(format!("{}: {:#}", notifier.channel, e)),
+            }
+        }
+    }
+    if !failures.is_empty() {
+        bail!("{}", failures.join("; "));
+    }
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routing_defaults_leave_out_routine_kinds() {
+        assert!(routes(&[], Kind::Crash) && routes(&[], Kind::Budget));
+        assert!(routes(&[], Kind::TokenExpiring));
+        assert!(!routes(&[], Kind::DaemonStart) && !routes(&[], Kind::RunFailed));
+        let only = [Kind::DaemonStop];
+        assert!(routes(&only, Kind::DaemonStop) && !routes(&only, Kind::Crash));
+    }
+
+    #[test]
+    fn notifiers_parse_and_validate() {
+        let notifiers: Vec<Notifier> = serde_yml::from_str(
+            "- type: slack\n  url: https://hooks.slack.com/services/T/B/x\n  notify_on: [crash, daemon_stop]\n\
+             - type: http\n  url: https://example.com/hook\n  headers: {Authorization: Bearer t}\n\
+             - type: desktop\n",
+        )
+        .unwrap();
+        assert_eq!(notifiers[0].notify_on, vec![Kind::Crash, Kind::DaemonStop]);
+        assert!(notifiers.iter().all(|n| n.validate().is_ok()));
+
+        let bot: Notifier = serde_yml::from_str("type: slack\n").unwrap();
+        assert!(bot.validate().is_ok());
+        let bad: Notifier = serde_yml::from_str("type: http\n").unwrap();
+        assert!(bad
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("needs a url"));
+        let bad: Notifier = serde_yml::from_str("type: slack\nurl: x\nheaders: {A: b}\n").unwrap();
+        assert!(bad
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("expected http(s)"));
+        let bad: Notifier = serde_yml::from_str("type: desktop\nurl: https://x\n").unwrap();
+        assert!(bad.validate().is_err());
+        assert!(serde_yml::from_str::<Notifier>("type: pager\n").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+/// A YAML or JSON document that failed to deserialize.
+///
+/// Displays the file, the 1-based line and column when the parser knows
+/// them, and the offending source line with a caret under the column.
+#[derive(Debug)]
+pub struct ParseError {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    snippet: Option<String>,
+}
+
+impl ParseError {
+    fn new(path: &Path, contents: &str, message: String, location: Option<(usize, usize)>) -> Self {
+        // Both parsers append the location to their message; we show it separately
+        let message = match message.rfind(" at line ") {
+            Some(i) if message[i..].contains(" column ") => message[..i].to_string(),
+            _ => message,
+        };
+        let location = location.filter(|&(line, _)| line > 0);
+        ParseError {
+            path: path.to_path_buf(),
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+            message,
+            snippet: location.and_then(|(line, column)| snippet(contents, line, column)),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed to parse {}", self.path.display())?;
+        match (self.line, self.column) {
+            (Some(line), Some(column)) if column > 0 => {
+                write!(f, " at line {}, column {}", line, column)?
+            }
+            (Some(line), _) => write!(f, " at line {}", line)?,
+            _ => {}
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(ref snippet) = self.snippet {
+            write!(f, "\n{}", snippet)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Deserializes YAML read from `path`.
+pub fn yaml<T: DeserializeOwned>(path: &Path, contents: &str) -> Result<T, ParseError> {
+    serde_yml::from_str(contents).map_err(|e| {
+        let location = e.location().map(|l| (l.line(), l.column()));
+        ParseError::new(path, contents, e.to_string(), location)
+    })
+}
+
+/// Deserializes JSON read from `path`.
+pub fn json<T: DeserializeOwned>(path: &Path, contents: &str) -> Result<T, ParseError> {
+    serde_json::from_str(contents).map_err(|e| {
+        ParseError::new(path, contents, e.to_string(), Some((e.line(), e.column())))
+    })
+}
+
+/// Renders the source line with a gutter, and a caret under `column` (if known).
+fn snippet(contents: &str, line: usize, column: usize) -> Option<String> {
+    let source = contents.lines().nth(line - 1)?;
+    let gutter = line.to_string();
+    let mut out = format!("{} | {}", gutter, source);
+    if column > 0 {
+        // Keep tabs so the caret lines up under them
+        let pad: String = source
+            .chars()
+            .take(column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        out.push_str(&format!("\n{} | {}^", " ".repeat(gutter.len()), pad));
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Config {
+        name: String,
+        port: u16,
+    }
+
+    #[test]
+    fn yaml_error_has_location_and_snippet() {
+        let err = yaml::<Config>(Path::new("/t/config.yml"), "name: a\nport: hello\n").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(7)));
+        assert_eq!(
+            err.to_string(),
+            "Failed to parse /t/config.yml at line 2, column 7: \
+             port: invalid type: string \"hello\", expected u16\n\
+             2 | port: hello\n  |       ^"
+        );
+    }
+
+    #[test]
+    fn json_error_has_location_and_snippet() {
+        let contents = "{\"name\": \"a\",\n \"port\": 1,,}";
+        let err = json::<Config>(Path::new("state.json"), contents).unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(12)));
+        assert!(err.message == "key must be a string", "{}", err.message);
+        assert!(err.to_string().ends_with("2 |  \"port\": 1,,}\n  |            ^"), "{}", err);
+    }
+
+    #[test]
+    fn json_error_at_end_of_empty_input() {
+        let err = json::<Config>(Path::new("state.json"), "").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to parse state.json at line 1: EOF while parsing a value"
+        );
+    }
+
+    #[test]
+    fn parses_valid_documents() {
+        let cfg: Config = yaml(Path::new("c.yml"), "name: a\nport: 80\n").unwrap();
+        assert_eq!(cfg.port, 80);
+        let cfg: Config = json(Path::new("c.json"), r#"{"name":"a","port":81}"#).unwrap();
+        assert_eq!(cfg.port, 81);
+    }
+}
@@ -0,0 +1,228 @@
+//! Member shutdown hooks (`pre_stop:` in a member's `botminter.yml`).
+//!
+//! ```yaml
+//! pre_stop:
+//!   command: git switch -c "wip/$BM_MEMBER" && git commit -am wip && git push -u origin HEAD
+//!   timeout_secs: 60
+//! ```
+//!
+//! bm runs the command with `sh -c` in the member's workspace right before it
+//! sends the member SIGTERM: `bm stop --force`, `bm stop --drain` for members
+//! still running at the timeout, and a daemon shutting down mid-run. Members
+//! that finish their iteration and exit on their own skip it.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, TeamEntry};
+use crate::github_app::{self, MemberAuth};
+use crate::parse;
+use crate::procutil;
+
+/// Seconds a hook may run when the manifest doesn't say.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// A member's shutdown hook.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PreStop {
+    /// Shell command, run with `sh -c` in the workspace.
+    pub command: String,
+    /// Seconds before the hook and everything it started are killed.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct PreStopSection {
+    #[serde(default)]
+    pre_stop: Option<PreStop>,
+}
+
+/// How a hook run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Succeeded,
+    /// Exited non-zero (`None` if killed by a signal).
+    Failed(Option<i32>),
+    TimedOut,
+}
+
+/// Reads the `pre_stop:` section of the member manifest in `member_dir`.
+pub fn read(member_dir: &Path) -> Result<Option<PreStop>> {
+    let path = member_dir.join("botminter.yml");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let section: PreStopSection = parse::yaml(&path, &contents)?;
+    if let Some(ref hook) = section.pre_stop {
+        if hook.command.trim().is_empty() {
+            bail!("pre_stop in {} has an empty command", path.display());
+        }
+    }
+    Ok(section.pre_stop)
+}
+
+/// Returns the hook log path (`~/.botminter/logs/pre-stop-<team>-<member>.log`).
+pub fn log_path(team_name: &str, member: &str) -> Result<PathBuf> {
+    let logs_dir = config::config_dir()?.join("logs");
+    fs::create_dir_all(&logs_dir)?;
+    Ok(logs_dir.join(format!("pre-stop-{}-{}.log", team_name, member)))
+}
+
+/// Runs `hook` in `workspace`, appending its output to `log`. The member's
+/// GitHub auth is passed on so the hook can push or comment.
+pub fn run(
+    hook: &PreStop,
+    workspace: &Path,
+    team_name: &str,
+    member: &str,
+    auth: Option<&MemberAuth>,
+    log: &Path,
+) -> Result<Outcome> {
+    let mut log_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log)
+        .with_context(|| format!("Failed to open {}", log.display()))?;
+    writeln!(
+        log_file,
+        "── {} pre_stop: {}",
+        chrono::Utc::now().to_rfc3339(),
+        hook.command
+    )?;
+    let log_err = log_file.try_clone().context("Failed to clone log file handle")?;
+
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", &hook.command])
+        .current_dir(workspace)
+        .env("BM_TEAM_NAME", team_name)
+        .env("BM_MEMBER", member)
+        .stdin(Stdio::null())
+        .stdout(log_file)
+        .stderr(log_err);
+    if let Some(auth) = auth {
+        auth.apply(&mut cmd);
+    }
+    // Own process group, so a timeout also ends whatever the shell started
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("Failed to run pre_stop in {}", workspace.display()))?;
+
+    let deadline = Instant::now() + Duration::from_secs(hook.timeout_secs);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(if status.success() {
+                Outcome::Succeeded
+            } else {
+                Outcome::Failed(status.code())
+            });
+        }
+        if Instant::now() >= deadline {
+            procutil::kill_group(child.id());
+            let _ = child.wait();
+            return Ok(Outcome::TimedOut);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Runs the member's hook, if it has one, and describes how it went.
+/// Problems are reported, never raised: the member is stopped regardless.
+pub fn run_for_member(team: &TeamEntry, member: &str, workspace: &Path) -> Option<String> {
+    let member_dir = team.path.join("team").join("team").join(member);
+    let hook = match read(&member_dir) {
+        Ok(hook) => hook?,
+        Err(e) => return Some(format!("pre_stop hook not run: {:#}", e)),
+    };
+    let log = match log_path(&team.name, member) {
+        Ok(log) => log,
+        Err(e) => return Some(format!("pre_stop hook not run: {:#}", e)),
+    };
+    let auth = github_app::resolve_member_auth(team, member).ok().flatten();
+
+    let start = Instant::now();
+    let report = match run(&hook, workspace, &team.name, member, auth.as_ref(), &log) {
+        Ok(Outcome::Succeeded) => {
+            format!("pre_stop hook finished in {}s", start.elapsed().as_secs())
+        }
+        Ok(Outcome::Failed(code)) => format!(
+            "pre_stop hook failed ({}); see {}",
+            code.map_or("killed by a signal".to_string(), |c| format!("exit {}", c)),
+            log.display()
+        ),
+        Ok(Outcome::TimedOut) => format!(
+            "pre_stop hook timed out after {}s and was killed; see {}",
+            hook.timeout_secs,
+            log.display()
+        ),
+        Err(e) => format!("pre_stop hook failed to run: {:#}", e),
+    };
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(command: &str, timeout_secs: u64) -> PreStop {
+        PreStop {
+            command: command.to_string(),
+            timeout_secs,
+        }
+    }
+
+    #[test]
+    fn read_parses_the_pre_stop_section() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(read(tmp.path()).unwrap(), None);
+
+        fs::write(
+            tmp.path().join("botminter.yml"),
+            "role: dev\npre_stop:\n  command: git stash\n",
+        )
+        .unwrap();
+        assert_eq!(read(tmp.path()).unwrap(), Some(hook("git stash", 30)));
+
+        fs::write(tmp.path().join("botminter.yml"), "pre_stop:\n  command: \" \"\n").unwrap();
+        assert!(read(tmp.path()).is_err());
+        fs::write(tmp.path().join("botminter.yml"), "pre_stop:\n  comand: x\n").unwrap();
+        assert!(read(tmp.path()).is_err());
+    }
+
+    #[test]
+    fn run_reports_outcome_and_logs_output() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("hook.log");
+        let run_hook = |command: &str, timeout: u64| {
+            run(&hook(command, timeout), tmp.path(), "t", "dev-bob", None, &log).unwrap()
+        };
+
+        assert_eq!(run_hook("echo \"$BM_MEMBER\" > who; echo saved", 5), Outcome::Succeeded);
+        assert_eq!(fs::read_to_string(tmp.path().join("who")).unwrap(), "dev-bob\n");
+        assert_eq!(run_hook("exit 3", 5), Outcome::Failed(Some(3)));
+
+        let start = Instant::now();
+        assert_eq!(run_hook("sleep 30", 1), Outcome::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let logged = fs::read_to_string(&log).unwrap();
+        assert!(logged.contains("pre_stop: exit 3"), "{}", logged);
+        assert!(logged.contains("saved"), "{}", logged);
+    }
+}
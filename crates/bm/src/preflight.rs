@@ -0,0 +1,254 @@
+//! Checks `bm start` runs before handing a non-local formation to its
+//! manager, so a missing tool, an unreachable cluster, or a skewed clock
+//! surfaces as a specific error instead of a failed manager session.
+
+use std::process::{Command, Output};
+
+use anyhow::{bail, Result};
+
+use crate::config::TeamEntry;
+use crate::formation::{FormationConfig, K8sConfig};
+
+/// Largest tolerated difference between the local clock and GitHub's. GitHub
+/// rejects App JWTs whose `iat` lies in its future, and bm backdates them by
+/// a minute.
+const MAX_CLOCK_SKEW_SECS: i64 = 60;
+
+/// Seconds kubectl may take to reach the API server.
+const KUBECTL_TIMEOUT: &str = "--request-timeout=10s";
+
+/// Namespace the team's pods are deployed into (`{namespace_prefix}-{team}`).
+pub fn k8s_namespace(k8s: &K8sConfig, team_name: &str) -> String {
+    format!("{}-{}", k8s.namespace_prefix, team_name).to_lowercase()
+}
+
+/// Runs every check that applies to `formation` and reports all problems at
+/// once.
+pub fn check_formation(formation: &FormationConfig, team: &TeamEntry) -> Result<()> {
+    let mut problems = Vec::new();
+    if formation.formation_type == "k8s" {
+        match formation.k8s {
+            Some(ref k8s) => check_k8s(k8s, &team.name, &formation.name, &mut problems),
+            None => problems.push(format!(
+                "formations/{}/formation.yml has no `k8s:` section (context, image).",
+                formation.name
+            )),
+        }
+    }
+    check_clock(team.credentials.gh_token.as_deref(), &mut problems);
+
+    if !problems.is_empty() {
+        let list: Vec<String> = problems.iter().map(|p| format!("  - {}", p)).collect();
+        bail!(
+            "Formation '{}' is not ready to deploy:\n{}",
+            formation.name,
+            list.join("\n")
+        );
+    }
+    eprintln!("Preflight checks passed for formation '{}'.", formation.name);
+    Ok(())
+}
+
+fn kubectl(context: Option<&str>, args: &[&str]) -> std::io::Result<Output> {
+    let mut cmd = Command::new("kubectl");
+    if let Some(context) = context {
+        cmd.args(["--context", context]);
+    }
+    cmd.args(args).output()
+}
+
+fn stderr_of(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).trim().to_string()
+}
+
+fn check_k8s(k8s: &K8sConfig, team_name: &str, formation: &str, problems: &mut Vec<String>) {
+    if which::which("kubectl").is_err() {
+        problems.push(
+            "kubectl not found on PATH. Install it from https://kubernetes.io/docs/tasks/tools/"
+                .to_string(),
+        );
+        return;
+    }
+    let context = k8s.context.as_str();
+
+    // The later checks all go through the context, so stop at the first failure
+    let contexts = match kubectl(None, &["config", "get-contexts", "-o", "name"]) {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).to_string(),
+        Ok(o) => {
+            problems.push(format!("Could not read your kubeconfig: {}", stderr_of(&o)));
+            return;
+        }
+        Err(e) => {
+            problems.push(format!("Failed to run kubectl: {}", e));
+            return;
+        }
+    };
+    if !contexts.lines().any(|c| c.trim() == context) {
+        let available: Vec<&str> = contexts
+            .lines()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect();
+        let available = if available.is_empty() {
+            "none".to_string()
+        } else {
+            available.join(", ")
+        };
+        problems.push(format!(
+            "Kube context '{}' is not in your kubeconfig (available: {}). \
+             Set k8s.context in formations/{}/formation.yml.",
+            context, available, formation
+        ));
+        return;
+    }
+    match kubectl(Some(context), &["get", "--raw", "/version", KUBECTL_TIMEOUT]) {
+        Ok(o) if o.status.success() => {}
+        Ok(o) => {
+            problems.push(format!(
+                "Cluster for context '{}' is unreachable: {}. Check that it is running \
+                 (`kubectl --context {} cluster-info`).",
+                context,
+                stderr_of(&o),
+                context
+            ));
+            return;
+        }
+        Err(e) => {
+            problems.push(format!("Failed to run kubectl: {}", e));
+            return;
+        }
+    }
+
+    let namespace = k8s_namespace(k8s, team_name);
+    let exists = kubectl(Some(context), &["get", "namespace", &namespace, KUBECTL_TIMEOUT])
+        .is_ok_and(|o| o.status.success());
+    let mut needed = vec![("create", "pods"), ("create", "secrets")];
+    if !exists {
+        needed.insert(0, ("create", "namespaces"));
+    }
+    for (verb, resource) in needed {
+        let mut args = vec!["auth", "can-i", verb, resource];
+        if resource != "namespaces" {
+            args.extend(["-n", namespace.as_str()]);
+        }
+        let allowed = kubectl(Some(context), &args)
+            .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).trim() == "yes");
+        if !allowed {
+            let scope = if resource == "namespaces" {
+                format!("(namespace '{}' does not exist yet)", namespace)
+            } else {
+                format!("in namespace '{}'", namespace)
+            };
+            problems.push(format!(
+                "Context '{}' may not {} {} {}. Ask a cluster admin to grant it.",
+                context, verb, resource, scope
+            ));
+        }
+    }
+
+    check_image(&k8s.image, context, problems);
+}
+
+/// Looks for the image in its registry, then in the local image store
+/// (e.g. kind clusters loaded with `kind load docker-image`).
+fn check_image(image: &str, context: &str, problems: &mut Vec<String>) {
+    let Some(engine) = ["docker", "podman"].into_iter().find(|b| which::which(b).is_ok()) else {
+        eprintln!(
+            "Note: neither docker nor podman is installed; not checking that image {} exists.",
+            image
+        );
+        return;
+    };
+    let remote = Command::new(engine).args(["manifest", "inspect", image]).output();
+    if remote.as_ref().is_ok_and(|o| o.status.success()) {
+        return;
+    }
+    let local = Command::new(engine).args(["image", "inspect", image]).output();
+    if local.is_ok_and(|o| o.status.success()) {
+        if let Some(cluster) = context.strip_prefix("kind-") {
+            eprintln!(
+                "Note: image {} exists only locally. Make sure it is loaded into the cluster \
+                 (`kind load docker-image {} --name {}`).",
+                image, image, cluster
+            );
+        }
+        return;
+    }
+    let reason = match remote {
+        Ok(ref o) => stderr_of(o),
+        Err(e) => e.to_string(),
+    };
+    problems.push(format!(
+        "Image {} was not found in its registry or locally ({}). Push it, run `{} login` \
+         for a private registry, or fix k8s.image.",
+        image, reason, engine
+    ));
+}
+
+/// Compares the local clock with the `Date` header of a GitHub API response.
+/// Skipped when gh can't reach GitHub.
+fn check_clock(gh_token: Option<&str>, problems: &mut Vec<String>) {
+    let mut cmd = Command::new("gh");
+    cmd.args(["api", "--include", "rate_limit"]);
+    if let Some(token) = gh_token {
+        cmd.env("GH_TOKEN", token);
+    }
+    let Ok(output) = cmd.output() else {
+        return;
+    };
+    if !output.status.success() {
+        return;
+    }
+    let Some(skew) = clock_skew(&String::from_utf8_lossy(&output.stdout), chrono::Utc::now())
+    else {
+        return;
+    };
+    if skew.abs() > MAX_CLOCK_SKEW_SECS {
+        problems.push(format!(
+            "This machine's clock is {}s {} GitHub's. Tokens bm mints are rejected when the \
+             clocks disagree; sync it with NTP (e.g. `sudo timedatectl set-ntp true`).",
+            skew.abs(),
+            if skew > 0 { "ahead of" } else { "behind" }
+        ));
+    }
+}
+
+/// Seconds the local clock (`now`) is ahead of the `Date` header in an HTTP
+/// response head.
+fn clock_skew(response: &str, now: chrono::DateTime<chrono::Utc>) -> Option<i64> {
+    let date = response
+        .lines()
+        .take_while(|l| !l.trim().is_empty())
+        .find_map(|l| {
+            let (name, value) = l.split_once(':')?;
+            name.eq_ignore_ascii_case("date").then(|| value.trim())
+        })?;
+    let remote = chrono::DateTime::parse_from_rfc2822(date).ok()?;
+    Some((now - remote.with_timezone(&chrono::Utc)).num_seconds())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_skew_reads_the_date_header() {
+        let response = "HTTP/2.0 200 OK\nContent-Type: application/json\n\
+                        Date: Fri, 16 Oct 2026 10:00:00 GMT\n\n{\"date\": \"ignored\"}";
+        let now = chrono::DateTime::parse_from_rfc3339("2026-10-16T10:02:30Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(clock_skew(response, now), Some(150));
+        assert_eq!(clock_skew("HTTP/2.0 200 OK\n\n{}", now), None);
+    }
+
+    #[test]
+    fn namespace_combines_prefix_and_team() {
+        let k8s = K8sConfig {
+            context: "kind-botminter".to_string(),
+            image: "ghcr.io/owner/ralph:latest".to_string(),
+            namespace_prefix: "BotMinter".to_string(),
+        };
+        assert_eq!(k8s_namespace(&k8s, "my-team"), "botminter-my-team");
+    }
+}
@@ -0,0 +1,188 @@
+//! CPU niceness and I/O scheduling class for locally launched members.
+//!
+//! Unlike cgroup limits these need no privileges, since a process may
+//! always lower its own priority. Both are set in the child just before it
+//! execs, so every process a member spawns inherits them.
+
+use std::fmt;
+use std::process::Command;
+
+use anyhow::{bail, Result};
+
+/// Lowest CPU priority a process can ask for.
+pub const MAX_NICE: i32 = 19;
+
+/// I/O scheduling class for a member's processes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoClass {
+    /// Disk access only when no other process wants it.
+    Idle,
+    /// The default class, at a level from 0 (highest) to 7 (lowest).
+    BestEffort(u8),
+}
+
+impl IoClass {
+    /// Parses `idle`, `best-effort`, or `best-effort:<0-7>`.
+    pub fn parse(value: &str) -> Result<IoClass> {
+        match value.split_once(':') {
+            None if value == "idle" => return Ok(IoClass::Idle),
+            None if value == "best-effort" => return Ok(IoClass::BestEffort(4)),
+            Some(("best-effort", level)) => {
+                if let Ok(level @ 0..=7) = level.parse::<u8>() {
+                    return Ok(IoClass::BestEffort(level));
+                }
+            }
+            _ => {}
+        }
+        bail!(
+            "Invalid ionice '{}'. Use idle, best-effort, or best-effort:<0-7>.",
+            value
+        );
+    }
+
+    /// The value `ioprio_set(2)` takes for this class.
+    #[cfg(target_os = "linux")]
+    fn ioprio(self) -> libc::c_int {
+        const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+        match self {
+            IoClass::BestEffort(level) => (2 << IOPRIO_CLASS_SHIFT) | level as libc::c_int,
+            IoClass::Idle => 3 << IOPRIO_CLASS_SHIFT,
+        }
+    }
+}
+
+impl fmt::Display for IoClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IoClass::Idle => f.write_str("idle"),
+            IoClass::BestEffort(level) => write!(f, "best-effort:{}", level),
+        }
+    }
+}
+
+/// Checks a niceness value. Members can only be made to yield, so it runs
+/// from 0 (normal) to 19; a member already running nicer keeps its value.
+pub fn check_nice(nice: i32) -> Result<i32> {
+    if !(0..=MAX_NICE).contains(&nice) {
+        bail!(
+            "Invalid nice {}. Use 0 (normal) to {} (lowest priority).",
+            nice,
+            MAX_NICE
+        );
+    }
+    Ok(nice)
+}
+
+/// Scheduling priority for one member process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Priority {
+    pub nice: Option<i32>,
+    pub io: Option<IoClass>,
+}
+
+impl Priority {
+    pub fn is_empty(&self) -> bool {
+        self.nice.is_none() && self.io.is_none()
+    }
+
+    /// Makes `cmd` run at this priority. I/O classes only take effect on
+    /// Linux; elsewhere they are ignored.
+    pub fn apply(&self, cmd: &mut Command) {
+        if self.is_empty() {
+            return;
+        }
+        imp::apply(*self, cmd);
+    }
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    use super::Priority;
+
+    pub fn apply(priority: Priority, cmd: &mut Command) {
+        // Safety: the closure runs between fork and exec and only makes
+        // async-signal-safe system calls.
+        unsafe {
+            cmd.pre_exec(move || {
+                // Never raise the priority bm itself was started with
+                if let Some(nice) = priority.nice {
+                    if nice > libc::getpriority(libc::PRIO_PROCESS, 0)
+                        && libc::setpriority(libc::PRIO_PROCESS, 0, nice) != 0
+                    {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                #[cfg(target_os = "linux")]
+                if let Some(io) = priority.io {
+                    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+                    if libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, io.ioprio()) != 0
+                    {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::process::Command;
+
+    use super::Priority;
+
+    pub fn apply(_priority: Priority, _cmd: &mut Command) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn io_class_parses_idle_and_best_effort_levels() {
+        assert_eq!(IoClass::parse("idle").unwrap(), IoClass::Idle);
+        assert_eq!(
+            IoClass::parse("best-effort").unwrap(),
+            IoClass::BestEffort(4)
+        );
+        assert_eq!(
+            IoClass::parse("best-effort:7").unwrap(),
+            IoClass::BestEffort(7)
+        );
+        for bad in ["", "realtime", "best-effort:8", "idle:3", "best-effort:"] {
+            assert!(IoClass::parse(bad).is_err(), "{:?} should be rejected", bad);
+        }
+        assert_eq!(IoClass::BestEffort(2).to_string(), "best-effort:2");
+    }
+
+    #[test]
+    fn check_nice_only_lowers_priority() {
+        assert_eq!(check_nice(10).unwrap(), 10);
+        assert!(check_nice(-5).is_err());
+        assert!(check_nice(20).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn apply_sets_niceness_of_the_child() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "cut -d' ' -f19 /proc/self/stat"]);
+        Priority {
+            nice: Some(7),
+            io: Some(IoClass::Idle),
+        }
+        .apply(&mut cmd);
+        let out = cmd.output().unwrap();
+        assert!(out.status.success(), "{:?}", out);
+        let own = unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) };
+        let expected = own.max(7);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout).trim(),
+            expected.to_string()
+        );
+    }
+}
@@ -0,0 +1,434 @@
+//! Platform-neutral process utilities: liveness checks, termination,
+//! shutdown/reload signal handling for the daemon, and waiting on processes
+//! and ports.
+//!
+//! Unix uses signals via `libc`; Windows uses process handles and console
+//! control events via `windows-sys`. Windows has no graceful termination
+//! request for arbitrary processes, so [`terminate`] and [`kill`] both end
+//! the process immediately there.
+
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::process::Command;
+use std::sync::atomic::AtomicBool;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+/// How often the `wait_for_*` helpers poll.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Set when the process is asked to shut down (SIGTERM/SIGINT, or a console
+/// close/Ctrl+C event on Windows). Only set once [`install_signal_handlers`]
+/// has been called.
+pub static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Set when the process is asked to reload its configuration (SIGHUP).
+/// Never set on Windows, which has no equivalent signal.
+pub static RELOAD_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Returns true if a process with the given PID exists.
+pub fn is_alive(pid: u32) -> bool {
+    imp::is_alive(pid)
+}
+
+/// Asks a process to exit (SIGTERM on Unix). Errors are ignored: the
+/// process may already be gone.
+pub fn terminate(pid: u32) {
+    imp::terminate(pid)
+}
+
+/// Ends a process immediately (SIGKILL on Unix).
+pub fn kill(pid: u32) {
+    imp::kill(pid)
+}
+
+/// Ends a process group started with the given PID as its leader, so
+/// children of a shell command go too. Just the process on Windows.
+pub fn kill_group(pgid: u32) {
+    imp::kill_group(pgid)
+}
+
+/// Asks a process group to exit, like [`terminate`] for every process in
+/// it. Falls back to the process alone if it doesn't lead a group, as
+/// members launched before they got their own session don't.
+pub fn terminate_group(pgid: u32) {
+    imp::terminate_group(pgid)
+}
+
+/// Starts the command in a new session (`setsid` on Unix), so the child
+/// leads its own process group and [`terminate_group`] reaches everything
+/// it spawns. Does nothing on Windows.
+pub fn detach(cmd: &mut Command) {
+    imp::detach(cmd)
+}
+
+/// Returns the live processes of a process group, leader included.
+/// Always empty on Windows.
+pub fn group_processes(pgid: u32) -> Vec<u32> {
+    imp::group_processes(pgid)
+}
+
+/// Ends whatever is left of a process group whose leader has exited or
+/// been asked to: SIGTERM, then SIGKILL for those still there after
+/// `grace`. Returns the PIDs it found, so callers can report survivors.
+pub fn sweep_group(pgid: u32, grace: Duration) -> Vec<u32> {
+    let left = group_processes(pgid);
+    if left.is_empty() {
+        return left;
+    }
+    terminate_group(pgid);
+    let deadline = Instant::now() + grace;
+    while !group_processes(pgid).is_empty() && Instant::now() < deadline {
+        thread::sleep(POLL_INTERVAL);
+    }
+    if !group_processes(pgid).is_empty() {
+        kill_group(pgid);
+    }
+    left
+}
+
+/// Asks a process to reload its configuration (SIGHUP on Unix). Does
+/// nothing on Windows, which has no equivalent signal.
+pub fn reload(pid: u32) {
+    imp::reload(pid)
+}
+
+/// Waits up to `timeout` for a process to exit. Returns whether it did.
+pub fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while is_alive(pid) {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    true
+}
+
+/// Waits up to `timeout` for `addr` to accept TCP connections. Returns
+/// whether it did.
+pub fn wait_for_port(addr: SocketAddr, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if TcpStream::connect_timeout(&addr, POLL_INTERVAL).is_ok() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Returns a loopback TCP port that was free when checked.
+pub fn free_port() -> Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
+}
+
+/// Routes shutdown and reload requests to [`SHUTDOWN_REQUESTED`] and
+/// [`RELOAD_REQUESTED`].
+pub fn install_signal_handlers() {
+    imp::install_signal_handlers()
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+    use std::sync::atomic::Ordering;
+
+    use super::{RELOAD_REQUESTED, SHUTDOWN_REQUESTED};
+
+    pub fn is_alive(pid: u32) -> bool {
+        // Safety: kill with signal 0 only checks existence, sends no signal.
+        unsafe { libc::kill(pid as i32, 0) == 0 }
+    }
+
+    pub fn terminate(pid: u32) {
+        unsafe {
+            libc::kill(pid as i32, libc::SIGTERM);
+        }
+    }
+
+    pub fn kill(pid: u32) {
+        unsafe {
+            libc::kill(pid as i32, libc::SIGKILL);
+        }
+    }
+
+    pub fn kill_group(pgid: u32) {
+        signal_group(pgid, libc::SIGKILL)
+    }
+
+    pub fn terminate_group(pgid: u32) {
+        signal_group(pgid, libc::SIGTERM)
+    }
+
+    fn signal_group(pgid: u32, signal: libc::c_int) {
+        unsafe {
+            if libc::kill(-(pgid as i32), signal) != 0 {
+                libc::kill(pgid as i32, signal);
+            }
+        }
+    }
+
+    pub fn detach(cmd: &mut Command) {
+        // Safety: setsid is async-signal-safe, so it may run between fork and exec.
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn group_processes(pgid: u32) -> Vec<u32> {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        let mut pids: Vec<u32> = entries
+            .filter_map(|e| e.ok()?.file_name().to_str()?.parse::<u32>().ok())
+            .filter(|pid| {
+                // Fields after the parenthesized command: state, ppid, pgrp, ...
+                std::fs::read_to_string(format!("/proc/{}/stat", pid))
+                    .ok()
+                    .and_then(|stat| {
+                        let mut fields = stat.rsplit_once(')')?.1.split_whitespace();
+                        let state = fields.next()?;
+                        let pgrp = fields.nth(1)?.parse::<u32>().ok()?;
+                        Some(state != "Z" && pgrp == pgid)
+                    })
+                    .unwrap_or(false)
+            })
+            .collect();
+        pids.sort();
+        pids
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn group_processes(pgid: u32) -> Vec<u32> {
+        let Ok(output) = Command::new("ps").args(["-A", "-o", "pid=,pgid=,stat="]).output() else {
+            return Vec::new();
+        };
+        let mut pids: Vec<u32> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let pid = fields.next()?.parse::<u32>().ok()?;
+                let pgrp = fields.next()?.parse::<u32>().ok()?;
+                let zombie = fields.next()?.starts_with('Z');
+                (pgrp == pgid && !zombie).then_some(pid)
+            })
+            .collect();
+        pids.sort();
+        pids
+    }
+
+    pub fn reload(pid: u32) {
+        unsafe {
+            libc::kill(pid as i32, libc::SIGHUP);
+        }
+    }
+
+    pub fn install_signal_handlers() {
+        unsafe {
+            libc::signal(libc::SIGTERM, on_shutdown as *const () as libc::sighandler_t);
+            libc::signal(libc::SIGINT, on_shutdown as *const () as libc::sighandler_t);
+            libc::signal(libc::SIGHUP, on_reload as *const () as libc::sighandler_t);
+        }
+    }
+
+    extern "C" fn on_shutdown(_sig: libc::c_int) {
+        SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+    }
+
+    extern "C" fn on_reload(_sig: libc::c_int) {
+        RELOAD_REQUESTED.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(windows)]
+mod imp {
+    use std::sync::atomic::Ordering;
+
+    use windows_sys::core::BOOL;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, STILL_ACTIVE};
+    use windows_sys::Win32::System::Console::{
+        SetConsoleCtrlHandler, CTRL_BREAK_EVENT, CTRL_CLOSE_EVENT, CTRL_C_EVENT,
+        CTRL_SHUTDOWN_EVENT,
+    };
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, TerminateProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+        PROCESS_TERMINATE,
+    };
+
+    use super::SHUTDOWN_REQUESTED;
+
+    /// Opens `pid` with `access`, runs `f` on the handle, and closes it.
+    fn with_handle<T>(pid: u32, access: u32, f: impl FnOnce(HANDLE) -> T) -> Option<T> {
+        let handle = unsafe { OpenProcess(access, 0, pid) };
+        if handle.is_null() {
+            return None;
+        }
+        let result = f(handle);
+        unsafe {
+            CloseHandle(handle);
+        }
+        Some(result)
+    }
+
+    pub fn is_alive(pid: u32) -> bool {
+        with_handle(pid, PROCESS_QUERY_LIMITED_INFORMATION, |handle| {
+            let mut code: u32 = 0;
+            let ok = unsafe { GetExitCodeProcess(handle, &mut code) };
+            ok != 0 && code == STILL_ACTIVE as u32
+        })
+        .unwrap_or(false)
+    }
+
+    pub fn terminate(pid: u32) {
+        kill(pid)
+    }
+
+    pub fn kill(pid: u32) {
+        with_handle(pid, PROCESS_TERMINATE, |handle| unsafe {
+            TerminateProcess(handle, 1);
+        });
+    }
+
+    pub fn kill_group(pgid: u32) {
+        kill(pgid)
+    }
+
+    pub fn terminate_group(pgid: u32) {
+        kill(pgid)
+    }
+
+    pub fn detach(_cmd: &mut std::process::Command) {}
+
+    pub fn group_processes(_pgid: u32) -> Vec<u32> {
+        Vec::new()
+    }
+
+    pub fn reload(_pid: u32) {}
+
+    pub fn install_signal_handlers() {
+        unsafe {
+            SetConsoleCtrlHandler(Some(on_console_event), 1);
+        }
+    }
+
+    unsafe extern "system" fn on_console_event(event: u32) -> BOOL {
+        match event {
+            CTRL_C_EVENT | CTRL_BREAK_EVENT | CTRL_CLOSE_EVENT | CTRL_SHUTDOWN_EVENT => {
+                SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+                1
+            }
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Child;
+
+    /// Spawns a child that stays alive for a while on any platform.
+    fn long_running_child() -> Child {
+        #[cfg(unix)]
+        let mut cmd = Command::new("sleep");
+        #[cfg(unix)]
+        cmd.arg("30");
+        #[cfg(windows)]
+        let mut cmd = Command::new("cmd");
+        #[cfg(windows)]
+        cmd.args(["/C", "ping", "-n", "30", "127.0.0.1"]);
+        cmd.spawn().expect("failed to spawn child")
+    }
+
+    /// Waits briefly for `child` to exit, returning whether it did.
+    fn exits_soon(child: &mut Child) -> bool {
+        for _ in 0..50 {
+            if let Ok(Some(_)) = child.try_wait() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        false
+    }
+
+    #[test]
+    fn terminate_ends_child() {
+        let mut child = long_running_child();
+        assert!(is_alive(child.id()));
+        terminate(child.id());
+        assert!(exits_soon(&mut child), "child should exit after terminate");
+    }
+
+    #[test]
+    fn kill_ends_child() {
+        let mut child = long_running_child();
+        kill(child.id());
+        assert!(exits_soon(&mut child), "child should exit after kill");
+    }
+
+    #[test]
+    fn terminate_and_kill_ignore_missing_process() {
+        terminate(4_000_000);
+        kill(4_000_000);
+    }
+
+    #[test]
+    fn is_alive_current_process() {
+        assert!(is_alive(std::process::id()));
+    }
+
+    #[test]
+    fn is_alive_nonexistent_pid() {
+        // Avoid PID 0: on Unix kill(0, 0) checks the calling process's group,
+        // so use a very high PID unlikely to exist.
+        assert!(!is_alive(4_000_000));
+    }
+
+    #[test]
+    fn wait_for_exit_times_out_then_sees_exit() {
+        let mut child = long_running_child();
+        assert!(!wait_for_exit(child.id(), Duration::from_millis(200)));
+        kill(child.id());
+        child.wait().unwrap();
+        assert!(wait_for_exit(child.id(), Duration::from_secs(5)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sweep_group_ends_what_the_leader_left_behind() {
+        // The shell leads its own group and exits, leaving a sleep behind
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "sleep 30 >/dev/null & echo $!"]);
+        cmd.stdout(std::process::Stdio::piped());
+        detach(&mut cmd);
+        let child = cmd.spawn().unwrap();
+        let pgid = child.id();
+        let output = child.wait_with_output().unwrap();
+        let orphan: u32 = String::from_utf8_lossy(&output.stdout).trim().parse().unwrap();
+        assert_eq!(group_processes(pgid), vec![orphan]);
+
+        assert_eq!(sweep_group(pgid, Duration::from_secs(5)), vec![orphan]);
+        assert!(sweep_group(pgid, Duration::from_secs(5)).is_empty());
+    }
+
+    #[test]
+    fn wait_for_port_sees_listener() {
+        let port = free_port().unwrap();
+        let addr: SocketAddr = ([127, 0, 0, 1], port).into();
+        assert!(!wait_for_port(addr, Duration::from_millis(200)));
+        let _listener = TcpListener::bind(addr).unwrap();
+        assert!(wait_for_port(addr, Duration::from_secs(5)));
+    }
+}
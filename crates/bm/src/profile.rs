@@ -5,6 +5,10 @@ use anyhow::{Context, Result, bail};
 use include_dir::{Dir, include_dir};
 use serde::{Deserialize, Serialize};
 
+use crate::mcp;
+use crate::parse;
+use crate::profile_checksums::ProfileChecksums;
+
 static PROFILES: Dir<'static> = include_dir!("$CARGO_MANIFEST_DIR/../../profiles");
 
 /// Profile manifest parsed from botminter.yml
@@ -25,12 +29,87 @@ pub struct ProfileManifest {
     pub projects: Vec<ProjectDef>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub views: Vec<ViewDef>,
+    /// Issues `bm projects seed` creates on a new team's repo.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seed_issues: Vec<SeedIssue>,
+    /// Team-wide MCP servers for member workspaces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp: Option<mcp::Servers>,
+    /// Issue forms pushed to the work repos as `.github/ISSUE_TEMPLATE/*.md`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issue_templates: Vec<IssueTemplate>,
+    /// Body pushed to the work repos as `.github/PULL_REQUEST_TEMPLATE.md`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_template: Option<String>,
+    /// Merge strategy and branch protection `bm projects sync` applies to
+    /// the work repos.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_settings: Option<RepoSettings>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RoleDef {
     pub name: String,
     pub description: String,
+    /// Fewest members the team needs in this role.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<u32>,
+    /// Most members the team may hire into this role.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<u32>,
+    /// Prefixes of the statuses this role works (e.g., ["arch"] owns
+    /// "arch:design", "arch:plan", etc.). Empty means the whole board.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefixes: Vec<String>,
+}
+
+impl RoleDef {
+    /// Describes the declared member count (e.g. "exactly 1", "1-3", "at most 3"),
+    /// or `None` if the role is unconstrained.
+    pub fn capacity(&self) -> Option<String> {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min == max => Some(format!("exactly {}", min)),
+            (Some(min), Some(max)) => Some(format!("{}-{}", min, max)),
+            (Some(min), None) => Some(format!("at least {}", min)),
+            (None, Some(max)) => Some(format!("at most {}", max)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Returns the profile role a member dir (`{role}-{name}`) was hired into.
+/// The longest matching role wins, so `human-assistant-01` is never
+/// counted as a `human` member.
+pub fn role_of_member_dir<'a>(roles: &'a [RoleDef], member_dir_name: &str) -> Option<&'a str> {
+    roles
+        .iter()
+        .map(|r| r.name.as_str())
+        .filter(|role| {
+            member_dir_name
+                .strip_prefix(role)
+                .is_some_and(|rest| rest.starts_with('-'))
+        })
+        .max_by_key(|role| role.len())
+}
+
+/// Returns a warning for each role with fewer members than its `min`.
+pub fn understaffed_roles(roles: &[RoleDef], member_dir_names: &[String]) -> Vec<String> {
+    roles
+        .iter()
+        .filter_map(|role| {
+            let min = role.min?;
+            let count = member_dir_names
+                .iter()
+                .filter(|m| role_of_member_dir(roles, m) == Some(role.name.as_str()))
+                .count() as u32;
+            (count < min).then(|| {
+                format!(
+                    "Role '{}' needs at least {} member(s), has {}. Hire with: bm hire {}",
+                    role.name, min, count, role.name
+                )
+            })
+        })
+        .collect()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -52,6 +131,198 @@ pub struct ProjectDef {
     pub fork_url: String,
 }
 
+/// A starter issue for a new team's backlog.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SeedIssue {
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    /// Names of labels declared in the manifest (or `project/<name>`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// Project board status to start in, one of the manifest's statuses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+/// An issue template offered when filing an issue on a work repo.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IssueTemplate {
+    /// Shown in GitHub's template chooser; the file is named after it.
+    pub name: String,
+    /// One-line description under the name in the chooser.
+    pub about: String,
+    /// Prefilled issue title.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+    /// Names of labels declared in the manifest, applied on filing.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// Prefilled issue body (Markdown).
+    #[serde(default)]
+    pub body: String,
+}
+
+/// Settings applied to each work repo through the GitHub API.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct RepoSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge: Option<MergeSettings>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branch_protection: Vec<BranchProtection>,
+}
+
+/// Which merge buttons a repo offers. Settings left out are not changed.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct MergeSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_squash: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_merge_commit: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_rebase: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delete_branch_on_merge: Option<bool>,
+}
+
+/// Protection for one branch: changes land only through pull requests.
+/// Applying it replaces the branch's whole protection rule.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BranchProtection {
+    pub branch: String,
+    /// Approvals a pull request needs (0 to 6).
+    #[serde(default = "default_required_reviews")]
+    pub required_reviews: u8,
+    #[serde(default)]
+    pub dismiss_stale_reviews: bool,
+    #[serde(default)]
+    pub require_code_owner_reviews: bool,
+    /// Status check names that must pass before merging.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_checks: Vec<String>,
+    /// Hold admins to the same rules.
+    #[serde(default)]
+    pub enforce_admins: bool,
+}
+
+fn default_required_reviews() -> u8 {
+    1
+}
+
+impl IssueTemplate {
+    /// File name under `.github/ISSUE_TEMPLATE/`: the name lowercased, with
+    /// runs of other characters turned into `-`.
+    pub fn file_name(&self) -> String {
+        let slug = self
+            .name
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        format!("{}.md", slug)
+    }
+}
+
+impl ProfileManifest {
+    /// Describes each role that can never be staffed: one whose `min` is
+    /// above its `max`.
+    pub fn role_problems(&self) -> Vec<String> {
+        self.roles
+            .iter()
+            .filter_map(|role| match (role.min, role.max) {
+                (Some(min), Some(max)) if min > max => Some(format!(
+                    "role '{}' has min {} above max {}",
+                    role.name, min, max
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Describes each issue template whose labels aren't declared or whose
+    /// file name is empty or shared with another template.
+    pub fn issue_template_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut files: Vec<String> = Vec::new();
+        for template in &self.issue_templates {
+            let file = template.file_name();
+            if file == ".md" {
+                problems.push(format!(
+                    "issue template '{}' needs a name with letters or digits",
+                    template.name
+                ));
+            } else if files.contains(&file) {
+                problems.push(format!(
+                    "issue template '{}' would overwrite another template's {}",
+                    template.name, file
+                ));
+            }
+            files.push(file);
+            for label in &template.labels {
+                if !self.labels.iter().any(|l| &l.name == label) {
+                    problems.push(format!(
+                        "issue template '{}' has label '{}', which is not a declared label",
+                        template.name, label
+                    ));
+                }
+            }
+        }
+        problems
+    }
+
+    /// Describes each problem with `repo_settings`: a review count GitHub
+    /// won't accept, or a branch protected twice.
+    pub fn repo_settings_problems(&self) -> Vec<String> {
+        let Some(settings) = &self.repo_settings else {
+            return Vec::new();
+        };
+        let mut problems = Vec::new();
+        let mut branches: Vec<&str> = Vec::new();
+        for rule in &settings.branch_protection {
+            if rule.branch.trim().is_empty() {
+                problems.push("branch protection rule has no branch".to_string());
+            } else if branches.contains(&rule.branch.as_str()) {
+                problems.push(format!("branch '{}' is protected more than once", rule.branch));
+            }
+            branches.push(&rule.branch);
+            if rule.required_reviews > 6 {
+                problems.push(format!(
+                    "branch '{}' requires {} reviews; GitHub allows 0 to 6",
+                    rule.branch, rule.required_reviews
+                ));
+            }
+        }
+        problems
+    }
+
+    /// Describes each seed issue label or status the manifest doesn't declare.
+    pub fn seed_issue_problems(&self) -> Vec<String> {
+        let known_label = |name: &str| {
+            self.labels.iter().any(|l| l.name == name)
+                || self.projects.iter().any(|p| name == format!("project/{}", p.name))
+        };
+        let mut problems = Vec::new();
+        for issue in &self.seed_issues {
+            for label in issue.labels.iter().filter(|l| !known_label(l)) {
+                problems.push(format!(
+                    "seed issue '{}' has label '{}', which is not a declared label",
+                    issue.title, label
+                ));
+            }
+            if let Some(ref status) = issue.status {
+                if !self.statuses.iter().any(|s| &s.name == status) {
+                    problems.push(format!(
+                        "seed issue '{}' has status '{}', which is not a declared status",
+                        issue.title, status
+                    ));
+                }
+            }
+        }
+        problems
+    }
+}
+
 /// Defines a role-based view for the GitHub Project board.
 /// Each view maps to a subset of statuses via prefix matching.
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -120,12 +391,220 @@ pub fn read_manifest(name: &str) -> Result<ProfileManifest> {
         .contents_utf8()
         .context("Profile manifest is not valid UTF-8")?;
 
-    let manifest: ProfileManifest =
-        serde_yml::from_str(contents).context("Failed to parse profile manifest")?;
+    let manifest: ProfileManifest = parse::yaml(Path::new(&path), contents)?;
 
     Ok(manifest)
 }
 
+/// Reads a named profile's manifest, rejecting unknown fields.
+pub fn read_manifest_strict(name: &str) -> Result<ProfileManifest> {
+    let path = format!("{}/botminter.yml", name);
+    let contents = PROFILES
+        .get_file(&path)
+        .and_then(|f| f.contents_utf8())
+        .with_context(|| format!("Profile '{}' has no readable botminter.yml", name))?;
+    parse_manifest_strict(Path::new(&path), contents)
+}
+
+/// Parses a manifest, rejecting fields the schema doesn't know (typos such
+/// as `shema_version` that lenient parsing silently drops).
+///
+/// Commands that only read a manifest stay lenient so a team repo written by
+/// a newer `bm` still loads; `bm profiles validate` and `bm init` use this.
+pub fn parse_manifest_strict(path: &Path, contents: &str) -> Result<ProfileManifest> {
+    let manifest = parse_manifest_fields(path, contents)?;
+    if let Some(problem) = manifest.role_problems().into_iter().next() {
+        bail!("Invalid roles in {}: {}", path.display(), problem);
+    }
+    Ok(manifest)
+}
+
+/// Like [`parse_manifest_strict`], but leaves checks across values, such as
+/// role capacities, to the caller; `bm profiles lint` reports those next to
+/// its other findings.
+pub fn parse_manifest_fields(path: &Path, contents: &str) -> Result<ProfileManifest> {
+    parse::yaml::<strict::Manifest>(path, contents)?;
+    let manifest: ProfileManifest = parse::yaml(path, contents)?;
+    if let Some(ref servers) = manifest.mcp {
+        mcp::validate(servers)
+            .with_context(|| format!("Invalid mcp section in {}", path.display()))?;
+    }
+    Ok(manifest)
+}
+
+/// Field-name mirrors of the manifest types that reject unknown fields.
+/// Values are checked by the regular types; keep the field lists in sync.
+mod strict {
+    use std::collections::BTreeMap;
+
+    use serde::de::IgnoredAny;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    pub struct Manifest {
+        name: IgnoredAny,
+        display_name: IgnoredAny,
+        description: IgnoredAny,
+        version: IgnoredAny,
+        schema_version: IgnoredAny,
+        #[serde(default)]
+        roles: Vec<Role>,
+        #[serde(default)]
+        labels: Vec<Label>,
+        #[serde(default)]
+        statuses: Vec<Status>,
+        #[serde(default)]
+        projects: Vec<Project>,
+        #[serde(default)]
+        views: Vec<View>,
+        #[serde(default)]
+        seed_issues: Vec<SeedIssue>,
+        #[serde(default)]
+        mcp: Option<BTreeMap<String, McpServer>>,
+        #[serde(default)]
+        issue_templates: Vec<IssueTemplate>,
+        #[serde(default)]
+        pr_template: IgnoredAny,
+        #[serde(default)]
+        repo_settings: Option<RepoSettings>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct RepoSettings {
+        #[serde(default)]
+        merge: Option<MergeSettings>,
+        #[serde(default)]
+        branch_protection: Vec<BranchProtection>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct MergeSettings {
+        #[serde(default)]
+        allow_squash: IgnoredAny,
+        #[serde(default)]
+        allow_merge_commit: IgnoredAny,
+        #[serde(default)]
+        allow_rebase: IgnoredAny,
+        #[serde(default)]
+        delete_branch_on_merge: IgnoredAny,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct BranchProtection {
+        branch: IgnoredAny,
+        #[serde(default)]
+        required_reviews: IgnoredAny,
+        #[serde(default)]
+        dismiss_stale_reviews: IgnoredAny,
+        #[serde(default)]
+        require_code_owner_reviews: IgnoredAny,
+        #[serde(default)]
+        required_checks: IgnoredAny,
+        #[serde(default)]
+        enforce_admins: IgnoredAny,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct Role {
+        name: IgnoredAny,
+        description: IgnoredAny,
+        #[serde(default)]
+        min: IgnoredAny,
+        #[serde(default)]
+        max: IgnoredAny,
+        #[serde(default)]
+        prefixes: IgnoredAny,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct Label {
+        name: IgnoredAny,
+        color: IgnoredAny,
+        description: IgnoredAny,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct Status {
+        name: IgnoredAny,
+        description: IgnoredAny,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct Project {
+        name: IgnoredAny,
+        fork_url: IgnoredAny,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct McpServer {
+        #[serde(default)]
+        command: IgnoredAny,
+        #[serde(default)]
+        args: IgnoredAny,
+        #[serde(default)]
+        env: IgnoredAny,
+        #[serde(default)]
+        allowed_tools: IgnoredAny,
+        #[serde(default)]
+        enabled: IgnoredAny,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct View {
+        name: IgnoredAny,
+        prefixes: IgnoredAny,
+        #[serde(default)]
+        also_include: IgnoredAny,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct IssueTemplate {
+        name: IgnoredAny,
+        about: IgnoredAny,
+        #[serde(default)]
+        title: IgnoredAny,
+        #[serde(default)]
+        labels: IgnoredAny,
+        #[serde(default)]
+        body: IgnoredAny,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct SeedIssue {
+        title: IgnoredAny,
+        #[serde(default)]
+        body: IgnoredAny,
+        #[serde(default)]
+        labels: IgnoredAny,
+        #[serde(default)]
+        status: IgnoredAny,
+    }
+}
+
 /// Lists the role names available in a profile by reading its members/ subdirectory.
 pub fn list_roles(name: &str) -> Result<Vec<String>> {
     let members_path = format!("{}/members", name);
@@ -144,9 +623,83 @@ pub fn list_roles(name: &str) -> Result<Vec<String>> {
     Ok(roles)
 }
 
+/// Lists the files in a role's member skeleton, relative to the skeleton root
+/// and sorted. `.gitkeep` placeholders are left out.
+pub fn list_skeleton_files(profile_name: &str, role: &str) -> Result<Vec<String>> {
+    let dir = skeleton_dir(profile_name, role)?;
+    let mut files = Vec::new();
+    collect_files(dir, dir.path(), &mut files);
+    files.retain(|f| !f.ends_with(".gitkeep"));
+    files.sort();
+    Ok(files)
+}
+
+/// Reads `file` (relative to the skeleton root) from a role's member
+/// skeleton, or `None` if the skeleton has no such file.
+pub fn read_skeleton_file(profile_name: &str, role: &str, file: &str) -> Result<Option<String>> {
+    let dir = skeleton_dir(profile_name, role)?;
+    Ok(dir
+        .get_file(dir.path().join(file))
+        .and_then(|f| f.contents_utf8())
+        .map(str::to_string))
+}
+
+fn skeleton_dir(profile_name: &str, role: &str) -> Result<&'static Dir<'static>> {
+    PROFILES
+        .get_dir(format!("{}/members/{}", profile_name, role))
+        .with_context(|| {
+            let roles = list_roles(profile_name).unwrap_or_default().join(", ");
+            format!(
+                "Role '{}' not available in profile '{}'. Available roles: {}",
+                role, profile_name, roles
+            )
+        })
+}
+
+fn collect_files(dir: &Dir<'_>, root: &Path, out: &mut Vec<String>) {
+    for file in dir.files() {
+        let rel = file.path().strip_prefix(root).unwrap_or(file.path());
+        out.push(rel.to_string_lossy().to_string());
+    }
+    for sub in dir.dirs() {
+        collect_files(sub, root, out);
+    }
+}
+
+/// Lists the formations a profile ships in its formations/ subdirectory.
+/// Empty for profiles without formations (schema v1).
+pub fn list_formations(name: &str) -> Vec<String> {
+    let Some(dir) = PROFILES.get_dir(format!("{}/formations", name)) else {
+        return Vec::new();
+    };
+    let mut formations: Vec<String> = dir
+        .dirs()
+        .map(|d| d.path().file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    formations.sort();
+    formations
+}
+
+/// Whether any role in the profile talks to the human over Telegram
+/// (`RObot.enabled` in its `ralph.yml`).
+pub fn uses_telegram(name: &str) -> Result<bool> {
+    for role in list_roles(name)? {
+        let path = format!("{}/members/{}/ralph.yml", name, role);
+        let Some(contents) = PROFILES.get_file(&path).and_then(|f| f.contents_utf8()) else {
+            continue;
+        };
+        let ralph: serde_yml::Value = parse::yaml(Path::new(&path), contents)?;
+        if ralph["RObot"]["enabled"].as_bool() == Some(true) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 /// Extracts a profile's team-repo content to the target directory.
 /// Copies everything from the embedded profile EXCEPT `members/` and `.schema/`
 /// (members are extracted on demand via `extract_member_to`; schema is internal).
+/// Records what was written in the target's `.profile-checksums.yml`.
 pub fn extract_profile_to(profile_name: &str, target: &Path) -> Result<()> {
     let profile_dir = PROFILES
         .get_dir(profile_name)
@@ -158,8 +711,11 @@ pub fn extract_profile_to(profile_name: &str, target: &Path) -> Result<()> {
             )
         })?;
 
+    let manifest = read_manifest(profile_name)?;
+    let mut checksums = ProfileChecksums::new(profile_name, &manifest.version, None);
+
     let root_path = profile_dir.path().to_path_buf();
-    extract_dir_recursive(profile_dir, target, &root_path, &|rel_path| {
+    extract_dir_recursive(profile_dir, target, &root_path, &mut checksums, &|rel_path| {
         // rel_path is relative to the profile root, e.g. "members/architect/..."
         let first = rel_path
             .components()
@@ -168,11 +724,31 @@ pub fn extract_profile_to(profile_name: &str, target: &Path) -> Result<()> {
         matches!(first.as_deref(), Some("members") | Some(".schema"))
     })?;
 
-    Ok(())
+    checksums.save(target)
+}
+
+/// Copies an embedded profile to `target` exactly as embedded, including
+/// `members/` and `.schema/`, so the copy can be edited and diffed against
+/// upstream. Returns the number of files written.
+pub fn export_profile_to(profile_name: &str, target: &Path) -> Result<usize> {
+    let profile_dir = PROFILES.get_dir(profile_name).with_context(|| {
+        let available = list_profiles().join(", ");
+        format!(
+            "Profile '{}' not found. Available profiles: {}",
+            profile_name, available
+        )
+    })?;
+
+    // Only used to count files; an export is not tracked like a team repo
+    let mut written = ProfileChecksums::new(profile_name, "", None);
+    let root_path = profile_dir.path().to_path_buf();
+    extract_dir_recursive(profile_dir, target, &root_path, &mut written, &|_| false)?;
+    Ok(written.files.len())
 }
 
 /// Extracts a member skeleton from the embedded profile into the target directory.
-/// Copies the contents of `profiles/{profile}/members/{role}/` to `target/`.
+/// Copies the contents of `profiles/{profile}/members/{role}/` to `target/` and
+/// records what was written in the target's `.profile-checksums.yml`.
 pub fn extract_member_to(profile_name: &str, role: &str, target: &Path) -> Result<()> {
     let member_path = format!("{}/members/{}", profile_name, role);
     let member_dir = PROFILES.get_dir(&member_path).with_context(|| {
@@ -183,19 +759,24 @@ pub fn extract_member_to(profile_name: &str, role: &str, target: &Path) -> Resul
         )
     })?;
 
+    let manifest = read_manifest(profile_name)?;
+    let mut checksums = ProfileChecksums::new(profile_name, &manifest.version, Some(role));
+
     let root_path = member_dir.path().to_path_buf();
-    extract_dir_recursive(member_dir, target, &root_path, &|_| false)?;
-    Ok(())
+    extract_dir_recursive(member_dir, target, &root_path, &mut checksums, &|_| false)?;
+    checksums.save(target)
 }
 
 /// Recursively extracts files from an embedded Dir to a filesystem path.
 /// `root_path` is the path of the root directory being extracted (used to compute
 /// relative paths for target files). The `skip` predicate receives the path relative
-/// to `root_path` and returns true to skip that entry.
+/// to `root_path` and returns true to skip that entry. Every written file is
+/// recorded in `checksums`.
 fn extract_dir_recursive(
     dir: &Dir<'_>,
     base_target: &Path,
     root_path: &Path,
+    checksums: &mut ProfileChecksums,
     skip: &dyn Fn(&Path) -> bool,
 ) -> Result<()> {
     // Extract files directly in this directory
@@ -221,6 +802,7 @@ fn extract_dir_recursive(
         fs::write(&target_path, file.contents()).with_context(|| {
             format!("Failed to write {}", target_path.display())
         })?;
+        checksums.record(rel, file.contents());
     }
 
     // Recurse into subdirectories
@@ -234,7 +816,7 @@ fn extract_dir_recursive(
             continue;
         }
 
-        extract_dir_recursive(sub_dir, base_target, root_path, skip)?;
+        extract_dir_recursive(sub_dir, base_target, root_path, checksums, skip)?;
     }
 
     Ok(())
@@ -307,7 +889,7 @@ mod tests {
         assert_eq!(manifest.schema_version, "1.0");
         assert!(!manifest.description.is_empty());
         assert_eq!(manifest.roles.len(), 2);
-        assert!(manifest.labels.len() > 0);
+        assert!(!manifest.labels.is_empty());
     }
 
     #[test]
@@ -328,6 +910,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn role_capacity_descriptions() {
+        let role = |min, max| RoleDef {
+            name: "dev".into(),
+            description: String::new(),
+            min,
+            max,
+            prefixes: Vec::new(),
+        };
+        assert_eq!(role(Some(1), Some(1)).capacity().as_deref(), Some("exactly 1"));
+        assert_eq!(role(Some(1), Some(3)).capacity().as_deref(), Some("1-3"));
+        assert_eq!(role(Some(2), None).capacity().as_deref(), Some("at least 2"));
+        assert_eq!(role(None, Some(3)).capacity().as_deref(), Some("at most 3"));
+        assert_eq!(role(None, None).capacity(), None);
+    }
+
+    #[test]
+    fn role_of_member_dir_prefers_longest_role() {
+        let roles: Vec<RoleDef> = ["human", "human-assistant"]
+            .iter()
+            .map(|name| RoleDef {
+                name: name.to_string(),
+                description: String::new(),
+                min: None,
+                max: None,
+                prefixes: Vec::new(),
+            })
+            .collect();
+        assert_eq!(role_of_member_dir(&roles, "human-assistant-01"), Some("human-assistant"));
+        assert_eq!(role_of_member_dir(&roles, "human-bob"), Some("human"));
+        assert_eq!(role_of_member_dir(&roles, "humanoid-01"), None);
+    }
+
+    #[test]
+    fn understaffed_roles_flags_roles_below_min() {
+        let manifest = read_manifest("scrum").unwrap();
+        let hired = vec!["architect-01".to_string(), "architect-02".to_string()];
+        let warnings = understaffed_roles(&manifest.roles, &hired);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'human-assistant'"), "got: {:?}", warnings);
+
+        let hired = vec!["architect-01".to_string(), "human-assistant-bob".to_string()];
+        assert!(understaffed_roles(&manifest.roles, &hired).is_empty());
+    }
+
     #[test]
     fn read_manifest_labels_have_required_fields() {
         let manifest = read_manifest("scrum").unwrap();
@@ -349,6 +976,121 @@ mod tests {
         assert!(err.contains("scrum-compact-telegram"));
     }
 
+    #[test]
+    fn read_manifest_strict_accepts_embedded_profiles() {
+        for name in list_profiles() {
+            read_manifest_strict(&name)
+                .unwrap_or_else(|e| panic!("profile '{}' fails strict validation: {:#}", name, e));
+        }
+    }
+
+    #[test]
+    fn parse_manifest_strict_rejects_typos() {
+        let base = "name: t\ndisplay_name: T\ndescription: d\nversion: '1'\n";
+        let path = Path::new("botminter.yml");
+
+        let typo = format!("{}shema_version: '1.0'\nschema_version: '1.0'\n", base);
+        let err = format!("{:#}", parse_manifest_strict(path, &typo).unwrap_err());
+        assert!(err.contains("unknown field `shema_version`"), "{}", err);
+        assert!(err.contains("at line 5"), "{}", err);
+        // Lenient parsing still accepts it
+        assert!(parse::yaml::<ProfileManifest>(path, &typo).is_ok());
+
+        let nested = format!(
+            "{}schema_version: '1.0'\nroles:\n  - name: dev\n    description: d\n    mxa: 2\n",
+            base
+        );
+        let err = format!("{:#}", parse_manifest_strict(path, &nested).unwrap_err());
+        assert!(err.contains("unknown field `mxa`"), "{}", err);
+
+        let inverted = format!(
+            "{}schema_version: '1.0'\nroles:\n  - name: dev\n    description: d\n    min: 3\n    max: 1\n",
+            base
+        );
+        let err = format!("{:#}", parse_manifest_strict(path, &inverted).unwrap_err());
+        assert!(err.contains("role 'dev' has min 3 above max 1"), "{}", err);
+
+        let mcp = format!(
+            "{}schema_version: '1.0'\nmcp:\n  gh:\n    command: x\n    allowed_tool: [a]\n",
+            base
+        );
+        let err = format!("{:#}", parse_manifest_strict(path, &mcp).unwrap_err());
+        assert!(err.contains("unknown field `allowed_tool`"), "{}", err);
+        let unnamed = format!("{}schema_version: '1.0'\nmcp:\n  gh:\n    args: [a]\n", base);
+        let err = format!("{:#}", parse_manifest_strict(path, &unnamed).unwrap_err());
+        assert!(err.contains("has no command"), "{}", err);
+
+        let valid = format!("{}schema_version: '1.0'\n", base);
+        assert_eq!(parse_manifest_strict(path, &valid).unwrap().name, "t");
+    }
+
+    #[test]
+    fn seed_issue_problems_accepts_project_labels() {
+        let mut manifest = read_manifest("scrum").unwrap();
+        manifest.projects.push(ProjectDef {
+            name: "hypershift".to_string(),
+            fork_url: "https://github.com/o/hypershift".to_string(),
+        });
+        manifest.seed_issues = vec![SeedIssue {
+            title: "Kickoff".to_string(),
+            body: String::new(),
+            labels: vec!["kind/epic".to_string(), "project/hypershift".to_string()],
+            status: Some("po:triage".to_string()),
+        }];
+        assert!(manifest.seed_issue_problems().is_empty());
+
+        manifest.seed_issues[0].labels.push("project/other".to_string());
+        assert_eq!(
+            manifest.seed_issue_problems(),
+            ["seed issue 'Kickoff' has label 'project/other', which is not a declared label"]
+        );
+    }
+
+    #[test]
+    fn issue_template_problems_flags_clashes_and_unknown_labels() {
+        let mut manifest = read_manifest("scrum").unwrap();
+        let template = |name: &str, labels: &[&str]| IssueTemplate {
+            name: name.to_string(),
+            about: "a".to_string(),
+            title: String::new(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            body: String::new(),
+        };
+        manifest.issue_templates = vec![template("Epic", &["kind/epic"])];
+        assert!(manifest.issue_template_problems().is_empty());
+
+        manifest.issue_templates.push(template("epic!", &["kind/nope"]));
+        manifest.issue_templates.push(template("??", &[]));
+        assert_eq!(
+            manifest.issue_template_problems(),
+            [
+                "issue template 'epic!' would overwrite another template's epic.md",
+                "issue template 'epic!' has label 'kind/nope', which is not a declared label",
+                "issue template '??' needs a name with letters or digits",
+            ]
+        );
+    }
+
+    #[test]
+    fn repo_settings_problems_flags_duplicate_branches_and_review_counts() {
+        let mut manifest = read_manifest("scrum").unwrap();
+        assert!(manifest.repo_settings_problems().is_empty());
+
+        manifest.repo_settings = Some(
+            serde_yml::from_str(
+                "branch_protection:\n  - branch: main\n  - branch: main\n    required_reviews: 7\n",
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            manifest.repo_settings_problems(),
+            [
+                "branch 'main' is protected more than once",
+                "branch 'main' requires 7 reviews; GitHub allows 0 to 6",
+            ]
+        );
+    }
+
     #[test]
     fn list_roles_returns_expected_for_rh_scrum() {
         let roles = list_roles("scrum").unwrap();
@@ -439,6 +1181,26 @@ mod tests {
         assert!(!tmp.path().join(".schema").exists());
     }
 
+    #[test]
+    fn uses_telegram_follows_robot_setting() {
+        assert!(uses_telegram("scrum").unwrap());
+        assert!(uses_telegram("scrum-compact-telegram").unwrap());
+        assert!(!uses_telegram("scrum-compact").unwrap());
+    }
+
+    #[test]
+    fn export_profile_copies_everything() {
+        let tmp = tempfile::tempdir().unwrap();
+        let files = export_profile_to("scrum", tmp.path()).unwrap();
+
+        assert!(tmp.path().join("botminter.yml").exists());
+        assert!(tmp.path().join("members/architect/PROMPT.md").exists());
+        assert!(tmp.path().join(".schema").is_dir());
+        assert!(!tmp.path().join(".profile-checksums.yml").exists());
+        assert!(files > 10, "only {} files", files);
+        assert!(export_profile_to("nonexistent", tmp.path()).is_err());
+    }
+
     #[test]
     fn extract_member_copies_skeleton() {
         let tmp = tempfile::tempdir().unwrap();
@@ -451,6 +1213,40 @@ mod tests {
         assert!(tmp.path().join("ralph.yml").exists());
     }
 
+    #[test]
+    fn extract_records_profile_checksums() {
+        use crate::profile_checksums::FileOrigin;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let team_repo = tmp.path().join("team");
+        let member_dir = tmp.path().join("architect-01");
+        extract_profile_to("scrum", &team_repo).unwrap();
+        extract_member_to("scrum", "architect", &member_dir).unwrap();
+
+        let team = ProfileChecksums::load(&team_repo).unwrap().unwrap();
+        assert_eq!(team.profile, "scrum");
+        assert_eq!(team.version, read_manifest("scrum").unwrap().version);
+        assert!(team.role.is_none());
+        assert!(team.files.contains_key("PROCESS.md"));
+        assert!(team.files.contains_key("skills/knowledge-manager/SKILL.md"));
+        assert!(!team.files.keys().any(|f| f.starts_with("members/")));
+        assert!(team
+            .check(&team_repo)
+            .iter()
+            .all(|(_, origin)| *origin == FileOrigin::Pristine));
+
+        let member = ProfileChecksums::load(&member_dir).unwrap().unwrap();
+        assert_eq!(member.role.as_deref(), Some("architect"));
+        assert!(member.files.contains_key("ralph.yml"));
+
+        // Re-extracting produces a byte-identical checksums file
+        let first = fs::read(member_dir.join(crate::profile_checksums::CHECKSUMS_FILE)).unwrap();
+        let again = tmp.path().join("architect-02");
+        extract_member_to("scrum", "architect", &again).unwrap();
+        let second = fs::read(again.join(crate::profile_checksums::CHECKSUMS_FILE)).unwrap();
+        assert_eq!(first, second);
+    }
+
     #[test]
     fn extract_member_invalid_role_errors() {
         let tmp = tempfile::tempdir().unwrap();
@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::parse;
+
+/// Written at the root of every extraction (the team repo and each member dir).
+pub const CHECKSUMS_FILE: &str = ".profile-checksums.yml";
+
+/// Records which files an extraction wrote and what they contained.
+///
+/// Keys are `/`-separated paths relative to the extraction root, so the
+/// serialized file is identical for identical profile content on any machine.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ProfileChecksums {
+    /// Profile the files were extracted from.
+    pub profile: String,
+    /// Profile `version` from its botminter.yml at extraction time.
+    pub version: String,
+    /// Role skeleton, for member extractions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// File path → `sha256:<hex>` of the extracted contents.
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+/// How a recorded file compares to what was extracted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileOrigin {
+    /// Contents match the profile.
+    Pristine,
+    /// Edited since extraction.
+    Modified,
+    /// Deleted since extraction.
+    Missing,
+}
+
+impl ProfileChecksums {
+    pub fn new(profile: &str, version: &str, role: Option<&str>) -> Self {
+        ProfileChecksums {
+            profile: profile.to_string(),
+            version: version.to_string(),
+            role: role.map(str::to_string),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Records `contents` as the profile origin of `rel_path`.
+    pub fn record(&mut self, rel_path: &Path, contents: &[u8]) {
+        self.files.insert(key(rel_path), checksum(contents));
+    }
+
+    /// Stops tracking `rel_path` (e.g. a template that was replaced).
+    pub fn forget(&mut self, rel_path: &Path) {
+        self.files.remove(&key(rel_path));
+    }
+
+    /// Loads the checksums written at `root`, or `None` if the extraction
+    /// predates them.
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(CHECKSUMS_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let checksums = parse::yaml(&path, &contents)?;
+        Ok(Some(checksums))
+    }
+
+    /// Writes the checksums to `root`.
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = root.join(CHECKSUMS_FILE);
+        let contents =
+            serde_yml::to_string(self).context("Failed to serialize profile checksums")?;
+        fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Compares every recorded file under `root` against its extracted contents.
+    pub fn check(&self, root: &Path) -> Vec<(String, FileOrigin)> {
+        self.files
+            .iter()
+            .map(|(rel, expected)| {
+                let origin = match fs::read(root.join(rel)) {
+                    Ok(contents) if checksum(&contents) == *expected => FileOrigin::Pristine,
+                    Ok(_) => FileOrigin::Modified,
+                    Err(_) => FileOrigin::Missing,
+                };
+                (rel.clone(), origin)
+            })
+            .collect()
+    }
+}
+
+/// Updates the checksums at `root`, if present, via `f`.
+pub fn update(root: &Path, f: impl FnOnce(&mut ProfileChecksums)) -> Result<()> {
+    if let Some(mut checksums) = ProfileChecksums::load(root)? {
+        f(&mut checksums);
+        checksums.save(root)?;
+    }
+    Ok(())
+}
+
+/// Returns `sha256:<hex>` for `contents`.
+pub fn checksum(contents: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(contents)))
+}
+
+fn key(rel_path: &Path) -> String {
+    rel_path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_classifies_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("knowledge")).unwrap();
+
+        let mut checksums = ProfileChecksums::new("scrum", "1.0.0", None);
+        for (rel, body) in [("a.md", "a"), ("knowledge/b.md", "b"), ("c.md", "c")] {
+            fs::write(root.join(rel), body).unwrap();
+            checksums.record(Path::new(rel), body.as_bytes());
+        }
+        fs::write(root.join("knowledge/b.md"), "edited").unwrap();
+        fs::remove_file(root.join("c.md")).unwrap();
+
+        assert_eq!(
+            checksums.check(root),
+            vec![
+                ("a.md".to_string(), FileOrigin::Pristine),
+                ("c.md".to_string(), FileOrigin::Missing),
+                ("knowledge/b.md".to_string(), FileOrigin::Modified),
+            ]
+        );
+    }
+
+    #[test]
+    fn save_is_deterministic_and_round_trips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut first = ProfileChecksums::new("scrum", "1.0.0", Some("architect"));
+        first.record(Path::new("z.md"), b"z");
+        first.record(Path::new("a.md"), b"a");
+        let mut second = ProfileChecksums::new("scrum", "1.0.0", Some("architect"));
+        second.record(Path::new("a.md"), b"a");
+        second.record(Path::new("z.md"), b"z");
+
+        first.save(tmp.path()).unwrap();
+        let saved = fs::read_to_string(tmp.path().join(CHECKSUMS_FILE)).unwrap();
+        second.save(tmp.path()).unwrap();
+        assert_eq!(fs::read_to_string(tmp.path().join(CHECKSUMS_FILE)).unwrap(), saved);
+
+        let loaded = ProfileChecksums::load(tmp.path()).unwrap().unwrap();
+        assert_eq!(loaded, first);
+        assert!(ProfileChecksums::load(&tmp.path().join("none")).unwrap().is_none());
+    }
+}
@@ -0,0 +1,376 @@
+//! Static checks for a profile directory (`bm profiles lint <path>`).
+//!
+//! Goes beyond the manifest schema: role skeletons, label colors, how
+//! statuses, views, and seed issues line up, formation configs, and
+//! `daemon.yml`. Every problem found is reported rather than stopping at the
+//! first one.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::daemon_settings;
+use crate::formation;
+use crate::profile::{self, ProfileManifest};
+
+/// Files every role skeleton under `members/<role>/` must provide.
+pub const SKELETON_FILES: &[&str] = &["PROMPT.md", "ralph.yml", "CLAUDE.md"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The profile won't work as intended.
+    Error,
+    /// Likely a mistake, but harmless at runtime.
+    Warning,
+}
+
+/// One problem, located by its path relative to the profile root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub file: String,
+    pub message: String,
+}
+
+/// Findings collected while checking, in the order found.
+#[derive(Default)]
+pub(crate) struct Findings(pub(crate) Vec<Finding>);
+
+impl Findings {
+    pub(crate) fn error(&mut self, file: &str, message: impl Into<String>) {
+        self.push(Severity::Error, file, message.into());
+    }
+
+    pub(crate) fn warning(&mut self, file: &str, message: impl Into<String>) {
+        self.push(Severity::Warning, file, message.into());
+    }
+
+    fn push(&mut self, severity: Severity, file: &str, message: String) {
+        self.0.push(Finding {
+            severity,
+            file: file.to_string(),
+            message,
+        });
+    }
+}
+
+/// Lints the profile at `dir`. Errs only if `dir` isn't a directory.
+pub fn lint(dir: &Path) -> Result<Vec<Finding>> {
+    if !dir.is_dir() {
+        bail!("{} is not a directory", dir.display());
+    }
+    let mut findings = Findings::default();
+
+    match read_manifest(dir) {
+        Ok(manifest) => {
+            lint_roles(dir, &manifest, &mut findings);
+            lint_labels(&manifest, &mut findings);
+            lint_statuses(&manifest, &mut findings);
+            for problem in manifest
+                .role_problems()
+                .into_iter()
+                .chain(manifest.seed_issue_problems())
+                .chain(manifest.issue_template_problems())
+                .chain(manifest.repo_settings_problems())
+            {
+                findings.error("botminter.yml", problem);
+            }
+        }
+        Err(e) => findings.error("botminter.yml", format!("{:#}", e)),
+    }
+    lint_formations(dir, &mut findings);
+
+    let daemon_yml = daemon_settings::team_settings_path(dir);
+    if let Err(e) = daemon_settings::load_layer(&daemon_yml) {
+        findings.error(daemon_settings::SETTINGS_FILE, format!("{:#}", e));
+    }
+    Ok(findings.0)
+}
+
+fn read_manifest(dir: &Path) -> Result<ProfileManifest> {
+    let path = dir.join("botminter.yml");
+    if !path.exists() {
+        bail!("missing; every profile needs a botminter.yml at its root");
+    }
+    let contents = fs::read_to_string(&path)?;
+    profile::parse_manifest_fields(Path::new("botminter.yml"), &contents)
+}
+
+/// Names listed more than once, in order of first repetition.
+fn duplicates<'a>(names: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut seen = BTreeSet::new();
+    let mut dups = Vec::new();
+    for name in names {
+        if !seen.insert(name) && !dups.contains(&name) {
+            dups.push(name);
+        }
+    }
+    dups
+}
+
+fn lint_roles(dir: &Path, manifest: &ProfileManifest, findings: &mut Findings) {
+    for name in duplicates(manifest.roles.iter().map(|r| r.name.as_str())) {
+        findings.error("botminter.yml", format!("role '{}' is declared more than once", name));
+    }
+    for role in &manifest.roles {
+        let skeleton = format!("members/{}", role.name);
+        if !dir.join(&skeleton).is_dir() {
+            findings.error(
+                &skeleton,
+                format!("missing; role '{}' has no member skeleton", role.name),
+            );
+            continue;
+        }
+        for file in SKELETON_FILES {
+            let rel = format!("{}/{}", skeleton, file);
+            if !dir.join(&rel).is_file() {
+                findings.error(&rel, "missing from the role skeleton");
+            }
+        }
+        let ralph_yml = format!("{}/ralph.yml", skeleton);
+        if let Ok(contents) = fs::read_to_string(dir.join(&ralph_yml)) {
+            if let Err(e) = serde_yml::from_str::<serde_yml::Value>(&contents) {
+                findings.error(&ralph_yml, format!("not valid YAML: {}", e));
+            }
+        }
+    }
+
+    let Ok(entries) = fs::read_dir(dir.join("members")) else {
+        return;
+    };
+    let mut extra: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.'))
+        .filter(|name| !manifest.roles.iter().any(|r| &r.name == name))
+        .collect();
+    extra.sort();
+    for name in extra {
+        findings.warning(
+            &format!("members/{}", name),
+            "not a role declared in botminter.yml, so it can't be hired",
+        );
+    }
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 6 && color.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn lint_labels(manifest: &ProfileManifest, findings: &mut Findings) {
+    for name in duplicates(manifest.labels.iter().map(|l| l.name.as_str())) {
+        findings.error("botminter.yml", format!("label '{}' is declared more than once", name));
+    }
+    for label in &manifest.labels {
+        if !is_hex_color(&label.color) {
+            findings.error(
+                "botminter.yml",
+                format!(
+                    "label '{}' has color '{}'; use six hex digits without '#', e.g. 0E8A16",
+                    label.name, label.color
+                ),
+            );
+        }
+    }
+}
+
+fn lint_statuses(manifest: &ProfileManifest, findings: &mut Findings) {
+    let statuses = &manifest.statuses;
+    for name in duplicates(statuses.iter().map(|s| s.name.as_str())) {
+        findings.error("botminter.yml", format!("status '{}' is declared more than once", name));
+    }
+    if manifest.views.is_empty() {
+        return;
+    }
+
+    for view in &manifest.views {
+        for prefix in &view.prefixes {
+            let matches = statuses
+                .iter()
+                .any(|s| s.name.starts_with(&format!("{}:", prefix)));
+            if !matches {
+                findings.error(
+                    "botminter.yml",
+                    format!(
+                        "view '{}' has prefix '{}', which matches no status",
+                        view.name, prefix
+                    ),
+                );
+            }
+        }
+        for extra in &view.also_include {
+            if !statuses.iter().any(|s| &s.name == extra) {
+                findings.warning(
+                    "botminter.yml",
+                    format!(
+                        "view '{}' includes '{}', which is not a declared status",
+                        view.name, extra
+                    ),
+                );
+            }
+        }
+    }
+
+    let covered = |prefix: &str| {
+        manifest
+            .views
+            .iter()
+            .any(|v| v.prefixes.iter().any(|p| p == prefix))
+    };
+    let uncovered: BTreeSet<&str> = statuses
+        .iter()
+        .filter_map(|s| s.name.split_once(':').map(|(prefix, _)| prefix))
+        .filter(|prefix| !covered(prefix))
+        .collect();
+    for prefix in uncovered {
+        findings.warning(
+            "botminter.yml",
+            format!("statuses with prefix '{}:' appear in no view", prefix),
+        );
+    }
+}
+
+pub(crate) fn lint_formations(dir: &Path, findings: &mut Findings) {
+    let Ok(names) = formation::list_formations(dir) else {
+        return;
+    };
+    let formations_dir = formation::formations_dir(dir);
+    for name in names {
+        let rel = format!("formations/{}/formation.yml", name);
+        let config = match formation::load(dir, &name) {
+            Ok(config) => config,
+            Err(e) => {
+                findings.error(&rel, format!("{:#}", e));
+                continue;
+            }
+        };
+        if config.name != name {
+            findings.error(
+                &rel,
+                format!("name '{}' must match its directory '{}'", config.name, name),
+            );
+        }
+        match config.formation_type.as_str() {
+            "local" => {}
+            "k8s" if config.k8s.is_none() => {
+                findings.error(&rel, "type k8s needs a `k8s:` section (context, image)")
+            }
+            "k8s" => {}
+            other => findings.error(&rel, format!("unknown type '{}'; use local or k8s", other)),
+        }
+        if config.is_local() {
+            continue;
+        }
+        let Some(ref manager) = config.manager else {
+            findings.error(&rel, "non-local formations need a `manager:` section");
+            continue;
+        };
+        let base = formations_dir.join(&name);
+        for (field, path, want_dir) in [
+            ("prompt", &manager.prompt, false),
+            ("ralph_yml", &manager.ralph_yml, false),
+            ("hats_dir", &manager.hats_dir, true),
+        ] {
+            let target = base.join(path);
+            let present = if want_dir { target.is_dir() } else { target.is_file() };
+            if !present {
+                findings.error(
+                    &rel,
+                    format!("manager.{} points at {}, which does not exist", field, path),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profiles_root() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../profiles")
+    }
+
+    #[test]
+    fn embedded_profiles_have_no_errors() {
+        for name in profile::list_profiles() {
+            let findings = lint(&profiles_root().join(&name)).unwrap();
+            let errors: Vec<_> = findings
+                .iter()
+                .filter(|f| f.severity == Severity::Error)
+                .collect();
+            assert!(errors.is_empty(), "profile '{}': {:?}", name, errors);
+        }
+    }
+
+    #[test]
+    fn lint_reports_broken_profiles() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::write(
+            dir.join("botminter.yml"),
+            "name: t\ndisplay_name: T\ndescription: d\nversion: \"1\"\nschema_version: \"1.0\"\n\
+             roles:\n  - name: dev\n    description: d\n    min: 2\n    max: 1\n\
+             labels:\n  - name: kind/epic\n    color: \"#0E8A16\"\n    description: d\n\
+             statuses:\n  - name: dev:ready\n    description: d\n  - name: qe:verify\n    description: d\n\
+             views:\n  - name: Dev\n    prefixes: [dev, ops]\n    also_include: [done]\n\
+             seed_issues:\n  - title: Kickoff\n    labels: [kind/epic, kind/nope]\n\
+             \x20   status: po:triage\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("members/dev")).unwrap();
+        fs::write(dir.join("members/dev/PROMPT.md"), "x").unwrap();
+        fs::write(dir.join("members/dev/ralph.yml"), "a: [").unwrap();
+        fs::create_dir_all(dir.join("members/qa")).unwrap();
+        fs::create_dir_all(dir.join("formations/k8s")).unwrap();
+        fs::write(
+            dir.join("formations/k8s/formation.yml"),
+            "name: kube\ndescription: d\ntype: k8s\n",
+        )
+        .unwrap();
+
+        let findings = lint(dir).unwrap();
+        let messages: Vec<String> = findings
+            .iter()
+            .map(|f| format!("{:?} {}: {}", f.severity, f.file, f.message))
+            .collect();
+        let expected = [
+            "Error botminter.yml: role 'dev' has min 2 above max 1",
+            "Error members/dev/CLAUDE.md: missing from the role skeleton",
+            "Error members/dev/ralph.yml: not valid YAML",
+            "Warning members/qa: not a role declared",
+            "Error botminter.yml: label 'kind/epic' has color '#0E8A16'",
+            "Error botminter.yml: view 'Dev' has prefix 'ops', which matches no status",
+            "Warning botminter.yml: view 'Dev' includes 'done'",
+            "Warning botminter.yml: statuses with prefix 'qe:' appear in no view",
+            "Error botminter.yml: seed issue 'Kickoff' has label 'kind/nope', which is not",
+            "Error botminter.yml: seed issue 'Kickoff' has status 'po:triage', which is not",
+            "Error formations/k8s/formation.yml: name 'kube' must match its directory 'k8s'",
+            "Error formations/k8s/formation.yml: type k8s needs a `k8s:` section",
+            "Error formations/k8s/formation.yml: non-local formations need a `manager:` section",
+        ];
+        for want in expected {
+            assert!(
+                messages.iter().any(|m| m.starts_with(want)),
+                "missing '{}' in {:#?}",
+                want,
+                messages
+            );
+        }
+        assert_eq!(messages.len(), expected.len(), "{:#?}", messages);
+    }
+
+    #[test]
+    fn lint_reports_missing_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let findings = lint(tmp.path()).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].file, "botminter.yml");
+        assert!(lint(&tmp.path().join("nope")).is_err());
+    }
+}
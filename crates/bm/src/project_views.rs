@@ -0,0 +1,207 @@
+//! Project board views via the GitHub API.
+//!
+//! Used by `bm projects sync --create-views`. Views are read through GraphQL
+//! (`ProjectV2.views`), which has no mutation for adding one, so new views go
+//! through the REST `projectsV2/{number}/views` endpoint. Neither API can
+//! change the filter of an existing view.
+
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use tracing::debug;
+
+const VIEWS_QUERY: &str = "\
+query($owner: String!, $number: Int!) {
+  repositoryOwner(login: $owner) {
+    __typename
+    ... on User { databaseId }
+    ... on ProjectV2Owner {
+      projectV2(number: $number) {
+        views(first: 100) { nodes { name filter layout } }
+      }
+    }
+  }
+}";
+
+/// A view of a project board.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct View {
+    pub name: String,
+    #[serde(default)]
+    pub filter: Option<String>,
+    /// `BOARD_LAYOUT`, `TABLE_LAYOUT`, or `ROADMAP_LAYOUT`.
+    #[serde(default)]
+    pub layout: String,
+}
+
+/// A project board and its current views.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Board {
+    /// REST path of the board's owner: `orgs/<login>` or `users/<id>`.
+    owner_path: String,
+    pub number: u64,
+    pub views: Vec<View>,
+}
+
+/// What syncing a wanted view would do.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Create,
+    Unchanged,
+    /// A view of that name exists with this other filter.
+    FilterDiffers(String),
+}
+
+impl Board {
+    /// Compares the board's view named `name` with the wanted `filter`.
+    pub fn change(&self, name: &str, filter: &str) -> Change {
+        match self.views.iter().find(|v| v.name == name) {
+            None => Change::Create,
+            Some(view) => {
+                let current = view.filter.as_deref().unwrap_or_default();
+                if normalize(current) == normalize(filter) {
+                    Change::Unchanged
+                } else {
+                    Change::FilterDiffers(current.to_string())
+                }
+            }
+        }
+    }
+}
+
+/// Collapses whitespace so cosmetic differences don't count as drift.
+fn normalize(filter: &str) -> String {
+    filter.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Runs `gh` with `args`, feeding `input` on stdin. Returns stdout.
+fn gh(token: Option<&str>, args: &[&str], input: Option<&str>) -> Result<String> {
+    debug!("Running gh {}", args.join(" "));
+    let mut cmd = Command::new("gh");
+    cmd.args(args);
+    if let Some(token) = token {
+        cmd.env("GH_TOKEN", token);
+    }
+    let mut child = cmd
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run gh api")?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin
+            .write_all(input.as_bytes())
+            .context("Failed to send request body to gh")?;
+    }
+    let output = child.wait_with_output().context("Failed to run gh api")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("gh api failed: {}", stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Loads project `number` of `owner` with its views.
+pub fn load(owner: &str, number: u64, token: Option<&str>) -> Result<Board> {
+    let out = gh(
+        token,
+        &[
+            "api",
+            "graphql",
+            "-f",
+            &format!("query={}", VIEWS_QUERY),
+            "-f",
+            &format!("owner={}", owner),
+            "-F",
+            &format!("number={}", number),
+        ],
+        None,
+    )
+    .context("Failed to read the project board's views. The token needs the project scope")?;
+    parse_board(&out, owner, number)
+}
+
+fn parse_board(json: &str, owner: &str, number: u64) -> Result<Board> {
+    let response: serde_json::Value =
+        serde_json::from_str(json).context("Failed to parse project views from GitHub")?;
+    let account = &response["data"]["repositoryOwner"];
+    let owner_path = match account["__typename"].as_str() {
+        Some("Organization") => format!("orgs/{}", owner),
+        Some("User") => match account["databaseId"].as_u64() {
+            Some(id) => format!("users/{}", id),
+            None => bail!("GitHub didn't return an id for user {}", owner),
+        },
+        _ => bail!("No GitHub organization or user named {}", owner),
+    };
+    let project = &account["projectV2"];
+    if project.is_null() {
+        bail!("{} has no project #{}", owner, number);
+    }
+    let views = serde_json::from_value(project["views"]["nodes"].clone())
+        .context("Failed to parse project views from GitHub")?;
+    Ok(Board {
+        owner_path,
+        number,
+        views,
+    })
+}
+
+/// Adds a board-layout view named `name` showing items that match `filter`.
+pub fn create(board: &Board, name: &str, filter: &str, token: Option<&str>) -> Result<()> {
+    let path = format!("{}/projectsV2/{}/views", board.owner_path, board.number);
+    let body = serde_json::json!({ "name": name, "layout": "board", "filter": filter });
+    gh(
+        token,
+        &["api", "--method", "POST", &path, "--input", "-", "--silent"],
+        Some(&body.to_string()),
+    )
+    .with_context(|| format!("Failed to create view '{}'", name))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_board_reads_views_and_owner_kind() {
+        let json = r#"{"data":{"repositoryOwner":{"__typename":"Organization",
+            "projectV2":{"views":{"nodes":[
+              {"name":"View 1","filter":null,"layout":"TABLE_LAYOUT"},
+              {"name":"PO","filter":"status:po:triage,done","layout":"BOARD_LAYOUT"}]}}}}}"#;
+        let board = parse_board(json, "acme", 4).unwrap();
+        assert_eq!(board.owner_path, "orgs/acme");
+        assert_eq!(board.views.len(), 2);
+        assert_eq!(board.views[1].filter.as_deref(), Some("status:po:triage,done"));
+
+        let user = r#"{"data":{"repositoryOwner":{"__typename":"User","databaseId":42,
+            "projectV2":{"views":{"nodes":[]}}}}}"#;
+        assert_eq!(parse_board(user, "ada", 1).unwrap().owner_path, "users/42");
+
+        let missing = r#"{"data":{"repositoryOwner":{"__typename":"User","databaseId":42,
+            "projectV2":null}}}"#;
+        let err = parse_board(missing, "ada", 9).unwrap_err().to_string();
+        assert!(err.contains("ada has no project #9"), "{}", err);
+    }
+
+    #[test]
+    fn change_compares_filters_by_name() {
+        let json = r#"{"data":{"repositoryOwner":{"__typename":"Organization",
+            "projectV2":{"views":{"nodes":[
+              {"name":"PO","filter":"status:po:triage,done ","layout":"BOARD_LAYOUT"},
+              {"name":"Dev","filter":"status:dev:ready","layout":"BOARD_LAYOUT"}]}}}}}"#;
+        let board = parse_board(json, "acme", 4).unwrap();
+        assert_eq!(board.change("PO", "status:po:triage,done"), Change::Unchanged);
+        assert_eq!(
+            board.change("Dev", "status:dev:ready,done"),
+            Change::FilterDiffers("status:dev:ready".to_string())
+        );
+        assert_eq!(board.change("QE", "status:qe:test"), Change::Create);
+    }
+}
@@ -0,0 +1,241 @@
+//! GitHub API rate limits for poll mode.
+//!
+//! Poll mode reads the events API through `gh api --include`, which prints
+//! each response's status line and headers ahead of its body. The daemon
+//! keeps the rate-limit headers of the latest response in its poll state,
+//! waits for the reset when the budget runs out, and backs off exponentially
+//! on secondary rate limits, honoring `Retry-After` when GitHub sends it.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::timefmt;
+
+/// First wait after a secondary rate limit without `Retry-After`; GitHub
+/// asks for at least a minute.
+const BACKOFF_BASE_SECS: u64 = 60;
+
+/// Longest wait after repeated secondary rate limits.
+const BACKOFF_MAX_SECS: u64 = 15 * 60;
+
+/// One HTTP response as printed by `gh api --include`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    /// Header values by lowercased name.
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl Response {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+/// Splits `gh api --include` output into its responses, one per page.
+pub fn parse_included(output: &str) -> Vec<Response> {
+    let mut responses: Vec<Response> = Vec::new();
+    let mut in_headers = false;
+    for line in output.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with("HTTP/") {
+            let status = line
+                .split_whitespace()
+                .nth(1)
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0);
+            responses.push(Response {
+                status,
+                ..Response::default()
+            });
+            in_headers = true;
+            continue;
+        }
+        let Some(response) = responses.last_mut() else {
+            continue;
+        };
+        if in_headers {
+            match line.split_once(':') {
+                Some((name, value)) => {
+                    response
+                        .headers
+                        .insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+                }
+                None => in_headers = false,
+            }
+        } else {
+            response.body.push_str(line);
+            response.body.push('\n');
+        }
+    }
+    responses
+}
+
+/// The request budget GitHub reported with a response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    /// When the budget refills (RFC 3339).
+    pub reset_at: String,
+}
+
+impl RateLimit {
+    /// Reads the `x-ratelimit-*` headers, if the response has them.
+    pub fn from_response(response: &Response) -> Option<RateLimit> {
+        let number = |name: &str| response.header(name)?.parse::<u64>().ok();
+        let reset = DateTime::from_timestamp(number("x-ratelimit-reset")? as i64, 0)?;
+        Some(RateLimit {
+            limit: number("x-ratelimit-limit")?,
+            remaining: number("x-ratelimit-remaining")?,
+            reset_at: reset.to_rfc3339(),
+        })
+    }
+
+    /// Seconds until the budget refills, at least one.
+    fn secs_to_reset(&self, now: DateTime<Utc>) -> u64 {
+        timefmt::parse(&self.reset_at)
+            .map(|reset| (reset - now).num_seconds().max(1) as u64)
+            .unwrap_or(BACKOFF_BASE_SECS)
+    }
+
+    /// A wait until the reset when fewer than `requests` are left, so the
+    /// next round of polls doesn't run into the limit halfway.
+    pub fn too_low_for(&self, requests: u64, now: DateTime<Utc>) -> Option<Limited> {
+        if self.remaining >= requests || self.secs_to_reset(now) <= 1 {
+            return None;
+        }
+        Some(Limited {
+            wait_secs: self.secs_to_reset(now),
+            reason: format!(
+                "rate limit nearly used up ({} of {} requests left)",
+                self.remaining, self.limit
+            ),
+            secondary: false,
+        })
+    }
+}
+
+/// A request GitHub refused for exceeding a rate limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limited {
+    /// How long to wait before polling again.
+    pub wait_secs: u64,
+    pub reason: String,
+    /// Whether it was a secondary limit, which backs off further each time
+    /// it is hit in a row.
+    pub secondary: bool,
+}
+
+/// Works out whether `response` was refused for a rate limit. `throttled`
+/// counts the secondary limits already hit in a row. Other refusals, such as
+/// a missing permission, are not rate limits.
+pub fn limited(response: &Response, throttled: u32, now: DateTime<Utc>) -> Option<Limited> {
+    if response.status != 403 && response.status != 429 {
+        return None;
+    }
+    if let Some(secs) = response
+        .header("retry-after")
+        .and_then(|v| v.parse::<u64>().ok())
+    {
+        return Some(Limited {
+            wait_secs: secs.max(1),
+            reason: "secondary rate limit (Retry-After)".to_string(),
+            secondary: true,
+        });
+    }
+    if let Some(rate) = RateLimit::from_response(response).filter(|r| r.remaining == 0) {
+        return Some(Limited {
+            wait_secs: rate.secs_to_reset(now),
+            reason: format!("rate limit of {} requests used up", rate.limit),
+            secondary: false,
+        });
+    }
+    if response.status == 429 || response.body.contains("secondary rate limit") {
+        return Some(Limited {
+            wait_secs: backoff_secs(throttled),
+            reason: "secondary rate limit".to_string(),
+            secondary: true,
+        });
+    }
+    None
+}
+
+/// Exponential backoff after `throttled` secondary limits in a row: one
+/// minute, doubling up to fifteen.
+pub fn backoff_secs(throttled: u32) -> u64 {
+    (BACKOFF_BASE_SECS << throttled.min(8)).min(BACKOFF_MAX_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_771_668_000, 0).unwrap()
+    }
+
+    #[test]
+    fn parse_included_splits_pages_and_headers() {
+        let out = "HTTP/2.0 200 OK\r\nX-Ratelimit-Limit: 5000\r\nX-Ratelimit-Remaining: 4990\r\n\
+                   X-Ratelimit-Reset: 1771669800\r\n\r\n[{\"id\":\"2\"}]\n\
+                   HTTP/2.0 200 OK\r\nX-Ratelimit-Remaining: 4989\r\n\r\n[{\"id\":\"1\"}]\n";
+        let responses = parse_included(out);
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].status, 200);
+        assert_eq!(responses[0].header("x-ratelimit-remaining"), Some("4990"));
+        assert_eq!(responses[0].body.trim(), "[{\"id\":\"2\"}]");
+        assert_eq!(responses[1].body.trim(), "[{\"id\":\"1\"}]");
+
+        let rate = RateLimit::from_response(&responses[0]).unwrap();
+        assert_eq!((rate.limit, rate.remaining), (5000, 4990));
+        assert_eq!(rate.secs_to_reset(now()), 1800);
+        assert!(RateLimit::from_response(&responses[1]).is_none());
+        assert!(rate.too_low_for(3, now()).is_none());
+        assert_eq!(rate.too_low_for(5000, now()).unwrap().wait_secs, 1800);
+    }
+
+    #[test]
+    fn limited_tells_primary_and_secondary_limits_apart() {
+        let response = |status: u16, headers: &[(&str, &str)], body: &str| Response {
+            status,
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            body: body.to_string(),
+        };
+
+        let retry = response(403, &[("retry-after", "30")], "");
+        assert_eq!(limited(&retry, 0, now()).unwrap().wait_secs, 30);
+
+        let exhausted = response(
+            403,
+            &[
+                ("x-ratelimit-limit", "5000"),
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset", "1771668600"),
+            ],
+            "{\"message\":\"API rate limit exceeded\"}",
+        );
+        let l = limited(&exhausted, 3, now()).unwrap();
+        assert_eq!((l.wait_secs, l.secondary), (600, false));
+
+        let secondary = response(
+            403,
+            &[("x-ratelimit-remaining", "4000")],
+            "{\"message\":\"You have exceeded a secondary rate limit\"}",
+        );
+        assert_eq!(limited(&secondary, 0, now()).unwrap().wait_secs, 60);
+        assert_eq!(limited(&secondary, 2, now()).unwrap().wait_secs, 240);
+        assert_eq!(limited(&secondary, 9, now()).unwrap().wait_secs, 900);
+
+        // Not rate limits
+        let forbidden = response(403, &[], "{\"message\":\"Resource not accessible\"}");
+        assert!(limited(&forbidden, 0, now()).is_none());
+        assert!(limited(&response(200, &[("retry-after", "5")], ""), 0, now()).is_none());
+    }
+}
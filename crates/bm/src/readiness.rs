@@ -0,0 +1,356 @@
+//! Member readiness probes (`readiness:` in a member's `botminter.yml`).
+//!
+//! ```yaml
+//! readiness:
+//!   file: .ralph/events.jsonl
+//!   log_pattern: "Iteration 1"
+//!   http_port: 8089
+//!   timeout_secs: 60
+//! ```
+//!
+//! A live PID only says ralph hasn't exited yet. With a probe, `bm start`
+//! and the daemon report a member started once every check it declares
+//! passes: the file was touched since launch, the pattern was logged since
+//! launch, the port answers HTTP with a 2xx or 3xx. Roles declare the probe
+//! in their skeleton's `.botminter.yml`, so every member hired into the
+//! role gets it.
+
+use std::fs;
+use std::io::{Read as _, Seek as _, SeekFrom, Write as _};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::parse;
+
+/// Seconds a member may take to become ready when the manifest doesn't say.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What a member must show before it counts as started.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Readiness {
+    /// File, relative to the workspace, that must be modified after launch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// Text that must appear in the member log after launch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_pattern: Option<String>,
+    /// Loopback port that must answer `GET http_path` with a 2xx or 3xx.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_port: Option<u16>,
+    #[serde(default = "default_http_path")]
+    pub http_path: String,
+    /// Seconds after launch before a member that isn't ready is unhealthy.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_http_path() -> String {
+    "/".to_string()
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct ReadinessSection {
+    #[serde(default)]
+    readiness: Option<Readiness>,
+}
+
+/// One launch of a member, as its probe sees it.
+#[derive(Debug, Clone)]
+pub struct Launch {
+    workspace: PathBuf,
+    log: PathBuf,
+    /// Length of the log before launch; only what comes after is searched.
+    log_offset: u64,
+    /// Launch time in whole seconds, as coarse file systems record mtimes.
+    since: u64,
+    started: Instant,
+}
+
+impl Launch {
+    /// Marks a launch about to happen in `workspace`, logging to `log`.
+    pub fn new(workspace: &Path, log: &Path) -> Self {
+        Launch {
+            workspace: workspace.to_path_buf(),
+            log: log.to_path_buf(),
+            log_offset: fs::metadata(log).map(|m| m.len()).unwrap_or(0),
+            since: epoch_secs(SystemTime::now()),
+            started: Instant::now(),
+        }
+    }
+
+    /// The member log the launch writes to.
+    pub fn log(&self) -> &Path {
+        &self.log
+    }
+
+    /// Time since launch.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+/// How waiting for a member to become ready ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Probe {
+    Ready,
+    /// The member exited (or stopped being waited on) first.
+    Exited,
+    /// The timeout ran out; the checks still failing, described.
+    TimedOut(String),
+}
+
+impl Readiness {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+
+    /// Runs every check once. `Err` describes the ones not passing yet.
+    pub fn check(&self, launch: &Launch) -> std::result::Result<(), String> {
+        let mut pending = Vec::new();
+        if let Some(ref file) = self.file {
+            let touched = fs::metadata(launch.workspace.join(file))
+                .and_then(|m| m.modified())
+                .is_ok_and(|t| epoch_secs(t) >= launch.since);
+            if !touched {
+                pending.push(format!("{} not touched", file.display()));
+            }
+        }
+        if let Some(ref pattern) = self.log_pattern {
+            if !log_contains(&launch.log, launch.log_offset, pattern) {
+                pending.push(format!("\"{}\" not logged", pattern));
+            }
+        }
+        if let Some(port) = self.http_port {
+            if let Err(e) = http_ok(port, &self.http_path) {
+                pending.push(e);
+            }
+        }
+        if pending.is_empty() {
+            Ok(())
+        } else {
+            Err(pending.join(", "))
+        }
+    }
+
+    /// Polls the checks until they pass, `alive` turns false, or the
+    /// timeout since launch runs out.
+    pub fn wait(&self, launch: &Launch, mut alive: impl FnMut() -> bool) -> Probe {
+        loop {
+            let Err(pending) = self.check(launch) else {
+                return Probe::Ready;
+            };
+            if !alive() {
+                return Probe::Exited;
+            }
+            if launch.elapsed() >= self.timeout() {
+                return Probe::TimedOut(pending);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Reads the `readiness:` section of the member manifest in `member_dir`.
+pub fn read(member_dir: &Path) -> Result<Option<Readiness>> {
+    let path = member_dir.join("botminter.yml");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let section: ReadinessSection = parse::yaml(&path, &contents)?;
+    if let Some(ref probe) = section.readiness {
+        if probe.file.is_none() && probe.log_pattern.is_none() && probe.http_port.is_none() {
+            bail!(
+                "readiness in {} needs at least one of file, log_pattern, or http_port",
+                path.display()
+            );
+        }
+        if probe.file.as_ref().is_some_and(|f| f.is_absolute()) {
+            bail!("readiness.file in {} must be relative to the workspace", path.display());
+        }
+        if probe.log_pattern.as_ref().is_some_and(|p| p.is_empty()) {
+            bail!("readiness.log_pattern in {} is empty", path.display());
+        }
+        if !probe.http_path.starts_with('/') {
+            bail!("readiness.http_path in {} must start with '/'", path.display());
+        }
+    }
+    Ok(section.readiness)
+}
+
+fn epoch_secs(t: SystemTime) -> u64 {
+    t.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Whether `pattern` appears in `log` past `offset`. A log shorter than
+/// `offset` was rotated, so it is searched from the start.
+fn log_contains(log: &Path, offset: u64, pattern: &str) -> bool {
+    let Ok(mut file) = fs::File::open(log) else {
+        return false;
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = if len < offset { 0 } else { offset };
+    let mut tail = Vec::new();
+    if file.seek(SeekFrom::Start(start)).is_err() || file.read_to_end(&mut tail).is_err() {
+        return false;
+    }
+    String::from_utf8_lossy(&tail).contains(pattern)
+}
+
+/// Sends `GET path` to the loopback `port`. `Err` describes why it isn't
+/// ready.
+fn http_ok(port: u16, path: &str) -> std::result::Result<(), String> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let not_answering = |_| format!("port {} not answering", port);
+    let mut stream = TcpStream::connect_timeout(&addr, Duration::from_secs(1)).map_err(not_answering)?;
+    stream.set_read_timeout(Some(Duration::from_secs(2))).map_err(not_answering)?;
+    write!(
+        stream,
+        "GET {} HTTP/1.0\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n",
+        path, port
+    )
+    .map_err(not_answering)?;
+    let mut head = [0u8; 64];
+    let n = stream.read(&mut head).map_err(not_answering)?;
+    let status = String::from_utf8_lossy(&head[..n])
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok());
+    match status {
+        Some(code) if (200..400).contains(&code) => Ok(()),
+        Some(code) => Err(format!("GET {} on port {} returned {}", path, port, code)),
+        None => Err(format!("port {} didn't answer HTTP", port)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    fn probe() -> Readiness {
+        Readiness {
+            file: None,
+            log_pattern: None,
+            http_port: None,
+            http_path: default_http_path(),
+            timeout_secs: 1,
+        }
+    }
+
+    #[test]
+    fn read_parses_the_readiness_section() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(read(tmp.path()).unwrap(), None);
+
+        fs::write(
+            tmp.path().join("botminter.yml"),
+            "role: dev\nreadiness:\n  file: .ralph/ready\n",
+        )
+        .unwrap();
+        let parsed = read(tmp.path()).unwrap().unwrap();
+        assert_eq!(parsed.file, Some(PathBuf::from(".ralph/ready")));
+        assert_eq!(parsed.http_path, "/");
+        assert_eq!(parsed.timeout_secs, DEFAULT_TIMEOUT_SECS);
+
+        for bad in [
+            "readiness:\n  timeout_secs: 5\n",
+            "readiness:\n  file: /etc/ready\n",
+            "readiness:\n  http_port: 80\n  http_path: health\n",
+            "readiness:\n  fiel: x\n",
+        ] {
+            fs::write(tmp.path().join("botminter.yml"), bad).unwrap();
+            assert!(read(tmp.path()).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn checks_only_count_what_happened_after_launch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("member.log");
+        fs::write(&log, "ready from last run\n").unwrap();
+        let launch = Launch::new(tmp.path(), &log);
+
+        let mut probe = probe();
+        probe.file = Some(PathBuf::from("touched"));
+        probe.log_pattern = Some("ready".to_string());
+        assert_eq!(
+            probe.check(&launch),
+            Err("touched not touched, \"ready\" not logged".to_string())
+        );
+
+        fs::write(tmp.path().join("touched"), "").unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&log)
+            .unwrap()
+            .write_all(b"ready\n")
+            .unwrap();
+        assert_eq!(probe.check(&launch), Ok(()));
+
+        // A rotated log is searched from the start
+        fs::write(&log, "ready\n").unwrap();
+        assert_eq!(probe.check(&launch), Ok(()));
+    }
+
+    #[test]
+    fn http_check_wants_a_success_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            for reply in ["HTTP/1.1 503 Unavailable\r\n\r\n", "HTTP/1.1 204 No Content\r\n\r\n"] {
+                let (mut conn, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 256];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = conn.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                conn.write_all(reply.as_bytes()).unwrap();
+            }
+        });
+
+        let mut probe = probe();
+        probe.http_port = Some(port);
+        let launch = Launch::new(Path::new("."), Path::new("missing.log"));
+        assert_eq!(
+            probe.check(&launch),
+            Err(format!("GET / on port {} returned 503", port))
+        );
+        assert_eq!(probe.check(&launch), Ok(()));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn wait_stops_on_exit_or_timeout() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut probe = probe();
+        probe.file = Some(PathBuf::from("never"));
+        let launch = Launch::new(tmp.path(), &tmp.path().join("log"));
+
+        assert_eq!(probe.wait(&launch, || false), Probe::Exited);
+        assert_eq!(
+            probe.wait(&launch, || true),
+            Probe::TimedOut("never not touched".to_string())
+        );
+        assert!(launch.elapsed() < Duration::from_secs(3));
+    }
+}
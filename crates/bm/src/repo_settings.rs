@@ -0,0 +1,351 @@
+//! Repo settings from the profile — merge strategy and branch protection —
+//! applied to the team's work repos via the GitHub API.
+//!
+//! `bm projects sync` reads each repo's current settings, reports what
+//! differs from the profile, and changes only that; `--check` stops after
+//! the report. Protection rules and merge buttons the profile doesn't
+//! mention are left alone.
+
+use std::fmt;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::github_hooks::gh_api;
+use crate::profile::{BranchProtection, MergeSettings, RepoSettings};
+
+/// One setting on a repo that differs from the profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    /// What differs, e.g. `main: required reviews`.
+    pub setting: String,
+    pub current: String,
+    pub wanted: String,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} (profile: {})", self.setting, self.current, self.wanted)
+    }
+}
+
+impl Drift {
+    fn new(setting: impl Into<String>, current: impl fmt::Display, wanted: impl fmt::Display) -> Self {
+        Drift {
+            setting: setting.into(),
+            current: current.to_string(),
+            wanted: wanted.to_string(),
+        }
+    }
+}
+
+/// Lists how `repo` differs from `settings`, changing nothing.
+pub fn check(repo: &str, settings: &RepoSettings, token: Option<&str>) -> Result<Vec<Drift>> {
+    let mut drift = Vec::new();
+    if let Some(ref merge) = settings.merge {
+        drift.extend(merge_drift(merge, &read_merge(repo, token)?));
+    }
+    for rule in &settings.branch_protection {
+        let current = read_protection(repo, &rule.branch, token)?;
+        drift.extend(protection_drift(rule, current.as_ref()));
+    }
+    Ok(drift)
+}
+
+/// Brings `repo` in line with `settings`. Returns what it changed.
+pub fn apply(repo: &str, settings: &RepoSettings, token: Option<&str>) -> Result<Vec<Drift>> {
+    let mut changed = Vec::new();
+    if let Some(ref merge) = settings.merge {
+        let drift = merge_drift(merge, &read_merge(repo, token)?);
+        if !drift.is_empty() {
+            write_merge(repo, merge, token)?;
+            changed.extend(drift);
+        }
+    }
+    for rule in &settings.branch_protection {
+        let current = read_protection(repo, &rule.branch, token)?;
+        let drift = protection_drift(rule, current.as_ref());
+        if !drift.is_empty() {
+            write_protection(repo, rule, current.as_ref(), token)?;
+            changed.extend(drift);
+        }
+    }
+    Ok(changed)
+}
+
+/// A repo's merge settings as `GET repos/<repo>` returns them. They are
+/// missing when the token can't administer the repo.
+#[derive(Debug, Default, Deserialize)]
+struct MergeState {
+    allow_squash_merge: Option<bool>,
+    allow_merge_commit: Option<bool>,
+    allow_rebase_merge: Option<bool>,
+    delete_branch_on_merge: Option<bool>,
+}
+
+/// A branch's protection as `GET .../branches/<branch>/protection` returns it.
+#[derive(Debug, Default, Deserialize)]
+struct ProtectionState {
+    #[serde(default)]
+    required_status_checks: Option<StatusChecks>,
+    #[serde(default)]
+    enforce_admins: Option<Enabled>,
+    #[serde(default)]
+    required_pull_request_reviews: Option<Reviews>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StatusChecks {
+    #[serde(default)]
+    strict: bool,
+    #[serde(default)]
+    contexts: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Enabled {
+    enabled: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Reviews {
+    #[serde(default)]
+    dismiss_stale_reviews: bool,
+    #[serde(default)]
+    require_code_owner_reviews: bool,
+    #[serde(default)]
+    required_approving_review_count: u8,
+}
+
+fn merge_drift(wanted: &MergeSettings, current: &MergeState) -> Vec<Drift> {
+    [
+        ("allow squash merging", wanted.allow_squash, current.allow_squash_merge),
+        ("allow merge commits", wanted.allow_merge_commit, current.allow_merge_commit),
+        ("allow rebase merging", wanted.allow_rebase, current.allow_rebase_merge),
+        ("delete branch on merge", wanted.delete_branch_on_merge, current.delete_branch_on_merge),
+    ]
+    .into_iter()
+    .filter_map(|(setting, wanted, current)| {
+        let wanted = wanted?;
+        (current != Some(wanted)).then(|| {
+            let current = current.map_or("unknown".to_string(), |c| c.to_string());
+            Drift::new(setting, current, wanted)
+        })
+    })
+    .collect()
+}
+
+fn protection_drift(wanted: &BranchProtection, current: Option<&ProtectionState>) -> Vec<Drift> {
+    let branch = &wanted.branch;
+    let Some(current) = current else {
+        return vec![Drift::new(format!("{}: protection", branch), "none", "pull requests required")];
+    };
+    let Some(ref reviews) = current.required_pull_request_reviews else {
+        return vec![Drift::new(
+            format!("{}: pull requests", branch),
+            "not required",
+            "required",
+        )];
+    };
+    let mut drift = Vec::new();
+    let mut compare = |setting: &str, current: String, wanted: String| {
+        if current != wanted {
+            drift.push(Drift::new(format!("{}: {}", branch, setting), current, wanted));
+        }
+    };
+    compare(
+        "required reviews",
+        reviews.required_approving_review_count.to_string(),
+        wanted.required_reviews.to_string(),
+    );
+    compare(
+        "dismiss stale reviews",
+        reviews.dismiss_stale_reviews.to_string(),
+        wanted.dismiss_stale_reviews.to_string(),
+    );
+    compare(
+        "require code owner reviews",
+        reviews.require_code_owner_reviews.to_string(),
+        wanted.require_code_owner_reviews.to_string(),
+    );
+    let mut checks = current
+        .required_status_checks
+        .as_ref()
+        .map(|c| c.contexts.clone())
+        .unwrap_or_default();
+    checks.sort();
+    let mut wanted_checks = wanted.required_checks.clone();
+    wanted_checks.sort();
+    compare("required checks", list(&checks), list(&wanted_checks));
+    compare(
+        "enforce for admins",
+        current.enforce_admins.as_ref().is_some_and(|e| e.enabled).to_string(),
+        wanted.enforce_admins.to_string(),
+    );
+    drift
+}
+
+fn list(items: &[String]) -> String {
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+fn read_merge(repo: &str, token: Option<&str>) -> Result<MergeState> {
+    let out = gh_api(token, &[&format!("repos/{}", repo)], None)
+        .with_context(|| format!("Failed to read the settings of {}", repo))?;
+    serde_json::from_str(&out).with_context(|| format!("Unexpected response for {}", repo))
+}
+
+fn write_merge(repo: &str, merge: &MergeSettings, token: Option<&str>) -> Result<()> {
+    let mut body = serde_json::Map::new();
+    for (field, value) in [
+        ("allow_squash_merge", merge.allow_squash),
+        ("allow_merge_commit", merge.allow_merge_commit),
+        ("allow_rebase_merge", merge.allow_rebase),
+        ("delete_branch_on_merge", merge.delete_branch_on_merge),
+    ] {
+        if let Some(value) = value {
+            body.insert(field.to_string(), value.into());
+        }
+    }
+    let endpoint = format!("repos/{}", repo);
+    gh_api(
+        token,
+        &["--method", "PATCH", &endpoint, "--input", "-", "--silent"],
+        Some(&serde_json::Value::Object(body).to_string()),
+    )
+    .with_context(|| {
+        format!(
+            "Failed to update the merge settings of {}. The token needs Administration (Write) permission.",
+            repo
+        )
+    })?;
+    Ok(())
+}
+
+/// Reads `branch`'s protection, or `None` if it isn't protected.
+fn read_protection(repo: &str, branch: &str, token: Option<&str>) -> Result<Option<ProtectionState>> {
+    let endpoint = format!("repos/{}/branches/{}/protection", repo, branch);
+    let out = match gh_api(token, &[&endpoint], None) {
+        Ok(out) => out,
+        Err(e) if format!("{:#}", e).contains("Branch not protected") => return Ok(None),
+        Err(e) => {
+            return Err(e).with_context(|| {
+                format!("Failed to read the protection of {} on {}", branch, repo)
+            })
+        }
+    };
+    serde_json::from_str(&out)
+        .map(Some)
+        .with_context(|| format!("Unexpected response for {} protection on {}", branch, repo))
+}
+
+/// Replaces `rule.branch`'s protection with `rule`, keeping whether checks
+/// must run against the latest base branch.
+fn write_protection(
+    repo: &str,
+    rule: &BranchProtection,
+    current: Option<&ProtectionState>,
+    token: Option<&str>,
+) -> Result<()> {
+    let strict = current
+        .and_then(|c| c.required_status_checks.as_ref())
+        .is_some_and(|c| c.strict);
+    let checks = if rule.required_checks.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::json!({ "strict": strict, "contexts": rule.required_checks })
+    };
+    let body = serde_json::json!({
+        "required_status_checks": checks,
+        "enforce_admins": rule.enforce_admins,
+        "required_pull_request_reviews": {
+            "dismiss_stale_reviews": rule.dismiss_stale_reviews,
+            "require_code_owner_reviews": rule.require_code_owner_reviews,
+            "required_approving_review_count": rule.required_reviews,
+        },
+        "restrictions": null,
+    });
+    let endpoint = format!("repos/{}/branches/{}/protection", repo, rule.branch);
+    gh_api(
+        token,
+        &["--method", "PUT", &endpoint, "--input", "-", "--silent"],
+        Some(&body.to_string()),
+    )
+    .with_context(|| {
+        format!(
+            "Failed to protect {} on {}. The token needs Administration (Write) permission.",
+            rule.branch, repo
+        )
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule() -> BranchProtection {
+        serde_yml::from_str("branch: main\nrequired_checks: [test, lint]\n").unwrap()
+    }
+
+    #[test]
+    fn merge_drift_covers_only_declared_settings() {
+        let wanted = MergeSettings {
+            allow_squash: Some(true),
+            allow_merge_commit: Some(false),
+            ..Default::default()
+        };
+        let current = MergeState {
+            allow_squash_merge: Some(true),
+            allow_merge_commit: Some(true),
+            allow_rebase_merge: Some(true),
+            delete_branch_on_merge: None,
+        };
+        assert_eq!(
+            merge_drift(&wanted, &current),
+            [Drift::new("allow merge commits", true, false)]
+        );
+        assert_eq!(
+            merge_drift(&wanted, &MergeState::default())[0].to_string(),
+            "allow squash merging: unknown (profile: true)"
+        );
+    }
+
+    #[test]
+    fn protection_drift_compares_each_setting() {
+        let rule = rule();
+        assert_eq!(
+            protection_drift(&rule, None),
+            [Drift::new("main: protection", "none", "pull requests required")]
+        );
+        assert_eq!(
+            protection_drift(&rule, Some(&ProtectionState::default()))[0].setting,
+            "main: pull requests"
+        );
+
+        let current: ProtectionState = serde_json::from_str(
+            r#"{"required_status_checks": {"strict": true, "contexts": ["lint", "test"]},
+                "enforce_admins": {"enabled": false},
+                "required_pull_request_reviews": {"required_approving_review_count": 1}}"#,
+        )
+        .unwrap();
+        assert!(protection_drift(&rule, Some(&current)).is_empty());
+
+        let stricter: BranchProtection = serde_yml::from_str(
+            "branch: main\nrequired_reviews: 2\nenforce_admins: true\n",
+        )
+        .unwrap();
+        assert_eq!(
+            protection_drift(&stricter, Some(&current)),
+            [
+                Drift::new("main: required reviews", 1, 2),
+                Drift::new("main: required checks", "lint, test", "none"),
+                Drift::new("main: enforce for admins", false, true),
+            ]
+        );
+    }
+}
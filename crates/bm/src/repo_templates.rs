@@ -0,0 +1,249 @@
+//! Issue and pull request templates from the profile, pushed to the team's
+//! work repos via the GitHub contents API (`gh api repos/<repo>/contents`).
+//!
+//! Used by `bm init` and `bm projects sync`. Files are committed to each
+//! repo's default branch, only when their content changed. Templates dropped
+//! from the profile are left in place.
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+
+use crate::config::TeamEntry;
+use crate::github_app::parse_github_repo;
+use crate::github_hooks::gh_api;
+use crate::profile::{IssueTemplate, ProfileManifest};
+
+/// Commit message for template writes.
+const COMMIT_MESSAGE: &str = "chore: sync issue and PR templates from the botminter profile";
+
+/// A file to keep in sync on each work repo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateFile {
+    /// Path in the repo, e.g. `.github/ISSUE_TEMPLATE/epic.md`.
+    pub path: String,
+    pub contents: String,
+}
+
+/// What pushing one file did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// The template files the manifest declares.
+pub fn files(manifest: &ProfileManifest) -> Vec<TemplateFile> {
+    let mut files: Vec<TemplateFile> = manifest
+        .issue_templates
+        .iter()
+        .map(|t| TemplateFile {
+            path: format!(".github/ISSUE_TEMPLATE/{}", t.file_name()),
+            contents: render_issue_template(t),
+        })
+        .collect();
+    if let Some(ref body) = manifest.pr_template {
+        files.push(TemplateFile {
+            path: ".github/PULL_REQUEST_TEMPLATE.md".to_string(),
+            contents: with_trailing_newline(body),
+        });
+    }
+    files
+}
+
+/// The repos work is filed on: the team repo, `extra_repos`, and every
+/// project fork on GitHub.
+pub fn work_repos(team: &TeamEntry, manifest: &ProfileManifest) -> Vec<String> {
+    let mut repos = team.repos();
+    for project in &manifest.projects {
+        if let Some(repo) = parse_github_repo(&project.fork_url) {
+            if !repos.contains(&repo) {
+                repos.push(repo);
+            }
+        }
+    }
+    repos
+}
+
+/// Pushes the manifest's templates to each of the team's work repos.
+/// Returns each repo with what happened there; one repo failing doesn't
+/// stop the others.
+pub fn sync(
+    team: &TeamEntry,
+    manifest: &ProfileManifest,
+    token: Option<&str>,
+) -> Vec<(String, Result<Vec<Change>>)> {
+    let files = files(manifest);
+    if files.is_empty() {
+        return Vec::new();
+    }
+    work_repos(team, manifest)
+        .into_iter()
+        .map(|repo| {
+            let result = push(&repo, &files, token);
+            (repo, result)
+        })
+        .collect()
+}
+
+/// Describes a repo's changes, e.g. `2 created, 1 updated` or `up to date`.
+pub fn summary(changes: &[Change]) -> String {
+    let count = |kind| changes.iter().filter(|&&c| c == kind).count();
+    let parts: Vec<String> = [(Change::Created, "created"), (Change::Updated, "updated")]
+        .into_iter()
+        .filter(|&(kind, _)| count(kind) > 0)
+        .map(|(kind, label)| format!("{} {}", count(kind), label))
+        .collect();
+    if parts.is_empty() {
+        "up to date".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+/// Writes `files` to `repo`'s default branch, skipping those already up to
+/// date. Returns what happened to each file, in order.
+pub fn push(repo: &str, files: &[TemplateFile], token: Option<&str>) -> Result<Vec<Change>> {
+    let mut changes = Vec::new();
+    for file in files {
+        let current = read(repo, &file.path, token)?;
+        let change = match current {
+            Some((ref contents, _)) if contents == &file.contents => Change::Unchanged,
+            Some((_, sha)) => {
+                write(repo, file, Some(&sha), token)?;
+                Change::Updated
+            }
+            None => {
+                write(repo, file, None, token)?;
+                Change::Created
+            }
+        };
+        changes.push(change);
+    }
+    Ok(changes)
+}
+
+/// Front matter GitHub reads from a Markdown issue template.
+#[derive(Serialize)]
+struct FrontMatter<'a> {
+    name: &'a str,
+    about: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    title: &'a str,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    labels: &'a [String],
+}
+
+fn render_issue_template(template: &IssueTemplate) -> String {
+    let front = serde_yml::to_string(&FrontMatter {
+        name: &template.name,
+        about: &template.about,
+        title: &template.title,
+        labels: &template.labels,
+    })
+    .expect("template front matter serializes");
+    format!("---\n{}---\n\n{}", front, with_trailing_newline(&template.body))
+}
+
+fn with_trailing_newline(text: &str) -> String {
+    let text = text.trim_end();
+    if text.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", text)
+    }
+}
+
+/// A file as the contents API returns it.
+#[derive(Deserialize)]
+struct Contents {
+    sha: String,
+    #[serde(default)]
+    content: String,
+}
+
+/// Reads `path` from `repo`'s default branch: its text and blob SHA, or
+/// `None` if it doesn't exist.
+fn read(repo: &str, path: &str, token: Option<&str>) -> Result<Option<(String, String)>> {
+    let endpoint = format!("repos/{}/contents/{}", repo, path);
+    let out = match gh_api(token, &[&endpoint], None) {
+        Ok(out) => out,
+        Err(e) if format!("{:#}", e).contains("HTTP 404") => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {} on {}", path, repo)),
+    };
+    let file: Contents = serde_json::from_str(&out)
+        .with_context(|| format!("Unexpected response for {} on {}", path, repo))?;
+    let encoded: String = file.content.split_whitespace().collect();
+    let bytes = STANDARD
+        .decode(encoded)
+        .with_context(|| format!("Failed to decode {} on {}", path, repo))?;
+    Ok(Some((String::from_utf8_lossy(&bytes).into_owned(), file.sha)))
+}
+
+/// Creates or (given the current blob `sha`) replaces `file` on `repo`.
+fn write(repo: &str, file: &TemplateFile, sha: Option<&str>, token: Option<&str>) -> Result<()> {
+    let endpoint = format!("repos/{}/contents/{}", repo, file.path);
+    let mut body = serde_json::json!({
+        "message": COMMIT_MESSAGE,
+        "content": STANDARD.encode(&file.contents),
+    });
+    if let Some(sha) = sha {
+        body["sha"] = sha.into();
+    }
+    gh_api(
+        token,
+        &["--method", "PUT", &endpoint, "--input", "-", "--silent"],
+        Some(&body.to_string()),
+    )
+    .with_context(|| {
+        format!(
+            "Failed to write {} on {}. The token needs Contents (Write) permission.",
+            file.path, repo
+        )
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(yaml: &str) -> ProfileManifest {
+        serde_yml::from_str(&format!(
+            "name: t\ndisplay_name: T\ndescription: d\nversion: '1'\nschema_version: '1.0'\n{}",
+            yaml
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn files_render_front_matter_and_pr_template() {
+        let m = manifest(
+            "issue_templates:\n  - name: Bug report\n    about: Something broke\n    \
+             title: '[Bug] '\n    labels: [kind/bug]\n    body: |\n      ## Steps\n\
+             pr_template: \"## Summary\\n\\n\"\n",
+        );
+        let files = files(&m);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, ".github/ISSUE_TEMPLATE/bug-report.md");
+        assert_eq!(
+            files[0].contents,
+            "---\nname: Bug report\nabout: Something broke\ntitle: '[Bug] '\nlabels:\n- kind/bug\n---\n\n## Steps\n"
+        );
+        assert_eq!(files[1].path, ".github/PULL_REQUEST_TEMPLATE.md");
+        assert_eq!(files[1].contents, "## Summary\n");
+
+        assert!(super::files(&manifest("")).is_empty());
+    }
+
+    #[test]
+    fn summary_counts_changes() {
+        assert_eq!(
+            summary(&[Change::Created, Change::Unchanged, Change::Created, Change::Updated]),
+            "2 created, 1 updated"
+        );
+        assert_eq!(summary(&[Change::Unchanged]), "up to date");
+    }
+}
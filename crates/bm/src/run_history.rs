@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Oldest runs are dropped once the history holds this many.
+pub const MAX_RUNS: usize = 200;
+
+/// One daemon one-shot run, as recorded in `daemon-<team>-runs.json`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RunRecord {
+    /// Event types that triggered the run.
+    pub events: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// Members launched, in launch order.
+    #[serde(default)]
+    pub members: Vec<MemberRun>,
+    /// Members that could not be launched, and launch-wide failures.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+    /// True if the run was cut short by daemon shutdown.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub interrupted: bool,
+}
+
+/// A member launched by a run and how it ended.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MemberRun {
+    pub name: String,
+    /// Exit status, e.g. `exit status: 0`, or `terminated` on shutdown.
+    pub status: String,
+    /// True if the member exited successfully (or was stopped on shutdown).
+    pub ok: bool,
+}
+
+impl RunRecord {
+    /// True if every member succeeded and nothing failed to launch.
+    pub fn ok(&self) -> bool {
+        self.errors.is_empty() && self.members.iter().all(|m| m.ok)
+    }
+}
+
+/// Reads the run history at `path`, oldest first. A missing or unreadable
+/// file is an empty history.
+pub fn load(path: &Path) -> Vec<RunRecord> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// Appends a run to the history at `path`, dropping the oldest runs beyond
+/// [`MAX_RUNS`].
+pub fn append(path: &Path, record: RunRecord) -> Result<()> {
+    let mut runs = load(path);
+    runs.push(record);
+    if runs.len() > MAX_RUNS {
+        runs.drain(..runs.len() - MAX_RUNS);
+    }
+    let contents =
+        serde_json::to_string_pretty(&runs).context("Failed to serialize run history")?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, contents)
+        .with_context(|| format!("Failed to write run history at {}", tmp.display()))?;
+    fs::rename(&tmp, path)
+        .with_context(|| format!("Failed to write run history at {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn run(n: i64) -> RunRecord {
+        let at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap() + chrono::Duration::minutes(n);
+        RunRecord {
+            events: vec!["issues".to_string()],
+            started_at: at,
+            finished_at: at + chrono::Duration::seconds(30),
+            members: vec![MemberRun {
+                name: "dev-01".to_string(),
+                status: "exit status: 0".to_string(),
+                ok: true,
+            }],
+            errors: Vec::new(),
+            interrupted: false,
+        }
+    }
+
+    #[test]
+    fn append_round_trips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("runs.json");
+        assert!(load(&path).is_empty());
+
+        append(&path, run(0)).unwrap();
+        let mut failed = run(1);
+        failed.members[0].status = "exit status: 1".to_string();
+        failed.members[0].ok = false;
+        append(&path, failed.clone()).unwrap();
+
+        let runs = load(&path);
+        assert_eq!(runs, vec![run(0), failed]);
+        assert!(runs[0].ok());
+        assert!(!runs[1].ok());
+    }
+
+    #[test]
+    fn append_drops_oldest_beyond_max() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("runs.json");
+        for n in 0..MAX_RUNS as i64 + 3 {
+            append(&path, run(n)).unwrap();
+        }
+        let runs = load(&path);
+        assert_eq!(runs.len(), MAX_RUNS);
+        assert_eq!(runs[0], run(3));
+        assert_eq!(runs[MAX_RUNS - 1], run(MAX_RUNS as i64 + 2));
+    }
+
+    #[test]
+    fn launch_errors_fail_the_run() {
+        let mut r = run(0);
+        r.errors.push("dev-01: failed to launch".to_string());
+        assert!(!r.ok());
+    }
+}
@@ -0,0 +1,447 @@
+//! Scheduled daemon runs.
+//!
+//! `schedules:` in the daemon settings launches members at cron times
+//! (UTC), e.g. nightly grooming at 02:00, alongside webhook and poll
+//! triggers. A due schedule is queued like any other event, as
+//! `schedule:<name>`, so it waits out quiet hours and pauses the same way.
+//!
+//! Each schedule's next fire time is persisted, so a restart neither fires
+//! a schedule twice nor forgets one that came due while the daemon was
+//! down; a missed schedule fires once when the daemon is back.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the queued event a schedule fires.
+pub const EVENT_PREFIX: &str = "schedule:";
+
+/// How far ahead [`Cron::next_after`] looks before giving up.
+const SEARCH_DAYS: i64 = 366 * 5;
+
+/// One entry under `schedules:`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Schedule {
+    /// Names the schedule in logs, run history, and `bm daemon status`.
+    pub name: String,
+    /// Five-field cron expression in UTC, or `@hourly`, `@daily`,
+    /// `@weekly`, `@monthly`.
+    pub cron: String,
+    /// Roles to launch; empty launches every member, unless `routing`
+    /// has a route for `schedule:<name>`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+}
+
+impl Schedule {
+    /// The event this schedule queues when it fires.
+    pub fn event(&self) -> String {
+        format!("{}{}", EVENT_PREFIX, self.name)
+    }
+
+    /// Checks the name and cron expression.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() || self.name.contains(|c: char| c.is_whitespace() || c == ',') {
+            bail!(
+                "Invalid schedule name '{}': use a non-empty name without spaces or commas",
+                self.name
+            );
+        }
+        let cron = Cron::parse(&self.cron)
+            .with_context(|| format!("Invalid cron expression for schedule '{}'", self.name))?;
+        if cron.next_after(Utc::now()).is_none() {
+            bail!("Schedule '{}' ({}) never fires", self.name, self.cron);
+        }
+        Ok(())
+    }
+}
+
+/// A parsed five-field cron expression: minute, hour, day of month, month,
+/// day of week (0-7, Sunday is 0 or 7). Fields take `*`, numbers, ranges
+/// (`1-5`), steps (`*/15`, `0-30/10`), and comma-separated lists. As in
+/// classic cron, when both day fields are restricted a day matching either
+/// one fires; if either starts with `*`, a day must match both.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cron {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl Cron {
+    pub fn parse(expr: &str) -> Result<Cron> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            bail!(
+                "expected 5 fields (minute hour day month weekday), got {} in '{}'",
+                fields.len(),
+                expr
+            );
+        };
+        let weekdays = parse_field(weekday, "weekday", 0, 7)?;
+        // 7 is Sunday too
+        let weekdays = (weekdays | weekdays >> 7) & 0x7f;
+        Ok(Cron {
+            minutes: parse_field(minute, "minute", 0, 59)?,
+            hours: parse_field(hour, "hour", 0, 23)?,
+            days: parse_field(day, "day", 1, 31)?,
+            months: parse_field(month, "month", 1, 12)?,
+            weekdays,
+            any_day: day.starts_with('*'),
+            any_weekday: weekday.starts_with('*'),
+        })
+    }
+
+    /// The first time after `after` (to the minute) that the expression
+    /// matches, or `None` if it never does.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(SEARCH_DAYS);
+        let mut t = start;
+        while t < limit {
+            if !bit(self.months, t.month()) {
+                let (y, m) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(y, m, 1)?
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+            } else if !self.day_matches(t) {
+                t = (t.date_naive() + Duration::days(1))
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+            } else if !bit(self.hours, t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+            } else if !bit(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, t: DateTime<Utc>) -> bool {
+        let day = bit(self.days, t.day());
+        let weekday = bit(self.weekdays, t.weekday().num_days_from_sunday());
+        // A field starting with `*` (plain or stepped) restricts alongside
+        // the other, as in classic cron
+        if self.any_day || self.any_weekday {
+            day && weekday
+        } else {
+            day || weekday
+        }
+    }
+}
+
+fn bit(set: u64, n: u32) -> bool {
+    set & (1 << n) != 0
+}
+
+/// Parses one cron field into a bit set of the values it matches.
+fn parse_field(field: &str, what: &str, min: u32, max: u32) -> Result<u64> {
+    let number = |s: &str| -> Result<u32> {
+        let n: u32 = s
+            .parse()
+            .with_context(|| format!("invalid {} '{}'", what, s))?;
+        if n < min || n > max {
+            bail!("{} {} is out of range {}-{}", what, n, min, max);
+        }
+        Ok(n)
+    };
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .with_context(|| format!("invalid step '{}' in {} field", step, what))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (lo, hi) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((lo, hi)) => (number(lo)?, number(hi)?),
+                None if step > 1 => (number(range)?, max),
+                None => {
+                    let n = number(range)?;
+                    (n, n)
+                }
+            },
+        };
+        if lo > hi {
+            bail!("invalid {} range '{}'", what, range);
+        }
+        for n in (lo..=hi).step_by(step as usize) {
+            set |= 1 << n;
+        }
+    }
+    Ok(set)
+}
+
+/// When a schedule fires next, as persisted.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct NextFire {
+    /// The expression `at` was computed from; a changed expression is
+    /// rescheduled from scratch.
+    pub cron: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Tracks each schedule's next fire time in a JSON file.
+#[derive(Debug)]
+pub struct Scheduler {
+    path: PathBuf,
+    next: BTreeMap<String, NextFire>,
+}
+
+impl Scheduler {
+    /// Loads the persisted fire times at `path`; missing or unreadable
+    /// means none.
+    pub fn load(path: &Path) -> Scheduler {
+        Scheduler {
+            path: path.to_path_buf(),
+            next: Self::load_snapshot(path),
+        }
+    }
+
+    /// Reads the persisted fire times without tracking them (for display).
+    pub fn load_snapshot(path: &Path) -> BTreeMap<String, NextFire> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns the schedules due at `now` and moves each one to its next
+    /// fire time. New or changed schedules start counting from `now`, and
+    /// entries for removed schedules are dropped.
+    pub fn due<'a>(
+        &mut self,
+        schedules: &'a [Schedule],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<&'a Schedule>> {
+        let mut due = Vec::new();
+        let mut changed = false;
+        for schedule in schedules {
+            let Ok(cron) = Cron::parse(&schedule.cron) else {
+                continue;
+            };
+            match self.next.get(&schedule.name) {
+                Some(next) if next.cron == schedule.cron && now < next.at => continue,
+                Some(next) if next.cron == schedule.cron => due.push(schedule),
+                _ => {}
+            }
+            changed = true;
+            match cron.next_after(now) {
+                Some(at) => {
+                    let cron = schedule.cron.clone();
+                    self.next
+                        .insert(schedule.name.clone(), NextFire { cron, at });
+                }
+                None => {
+                    self.next.remove(&schedule.name);
+                }
+            }
+        }
+        let before = self.next.len();
+        self.next
+            .retain(|name, _| schedules.iter().any(|s| s.name == *name));
+        if changed || self.next.len() != before {
+            self.save()?;
+        }
+        Ok(due)
+    }
+
+    fn save(&self) -> Result<()> {
+        let contents =
+            serde_json::to_string_pretty(&self.next).context("Failed to serialize schedules")?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, contents)
+            .with_context(|| format!("Failed to write schedules at {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to write schedules at {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn schedule(name: &str, cron: &str) -> Schedule {
+        Schedule {
+            name: name.to_string(),
+            cron: cron.to_string(),
+            roles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn next_after_walks_fields() {
+        let nightly = Cron::parse("0 2 * * *").unwrap();
+        assert_eq!(
+            nightly.next_after(at(2026, 3, 1, 1, 59)),
+            Some(at(2026, 3, 1, 2, 0))
+        );
+        assert_eq!(
+            nightly.next_after(at(2026, 3, 1, 2, 0)),
+            Some(at(2026, 3, 2, 2, 0))
+        );
+
+        let quarter = Cron::parse("*/15 9-17 * * 1-5").unwrap();
+        // Saturday 2026-03-07 → Monday 09:00
+        assert_eq!(
+            quarter.next_after(at(2026, 3, 7, 12, 0)),
+            Some(at(2026, 3, 9, 9, 0))
+        );
+        assert_eq!(
+            quarter.next_after(at(2026, 3, 9, 9, 7)),
+            Some(at(2026, 3, 9, 9, 15))
+        );
+
+        let new_year = Cron::parse("@monthly").unwrap();
+        assert_eq!(
+            new_year.next_after(at(2026, 12, 5, 0, 0)),
+            Some(at(2027, 1, 1, 0, 0))
+        );
+
+        // Restricted day and weekday: either matches (the 13th, or Friday)
+        let either = Cron::parse("0 0 13 * 5").unwrap();
+        assert_eq!(
+            either.next_after(at(2026, 3, 1, 0, 0)),
+            Some(at(2026, 3, 6, 0, 0))
+        );
+        // A stepped star still restricts: odd days, then even weekdays
+        let odd_days = Cron::parse("0 0 */2 * *").unwrap();
+        assert_eq!(
+            odd_days.next_after(at(2026, 3, 1, 0, 0)),
+            Some(at(2026, 3, 3, 0, 0))
+        );
+        assert_eq!(
+            odd_days.next_after(at(2026, 3, 31, 0, 0)),
+            Some(at(2026, 4, 1, 0, 0))
+        );
+        // Sunday, Tuesday, Thursday, Saturday
+        let every_other = Cron::parse("0 0 * * */2").unwrap();
+        assert_eq!(
+            every_other.next_after(at(2026, 3, 1, 0, 0)),
+            Some(at(2026, 3, 3, 0, 0))
+        );
+        assert_eq!(
+            every_other.next_after(at(2026, 3, 7, 0, 0)),
+            Some(at(2026, 3, 8, 0, 0))
+        );
+        // With a stepped star in one field, a day must match both
+        let odd_mondays = Cron::parse("0 0 */2 * 1").unwrap();
+        assert_eq!(
+            odd_mondays.next_after(at(2026, 3, 1, 0, 0)),
+            Some(at(2026, 3, 9, 0, 0))
+        );
+        assert_eq!(
+            Cron::parse("0 0 * * 7").unwrap(),
+            Cron::parse("0 0 * * 0").unwrap()
+        );
+        assert_eq!(
+            Cron::parse("0 0 31 2 *")
+                .unwrap()
+                .next_after(at(2026, 1, 1, 0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_rejects_bad_fields() {
+        for bad in [
+            "0 2 * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "x * * * *",
+        ] {
+            assert!(Cron::parse(bad).is_err(), "{} should be rejected", bad);
+        }
+        assert!(schedule("nightly", "0 0 30 2 *").validate().is_err());
+        assert!(schedule("two words", "@daily").validate().is_err());
+    }
+
+    #[test]
+    fn due_fires_once_and_survives_restart() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("schedules.json");
+        let schedules = vec![schedule("nightly", "0 2 * * *")];
+
+        let mut scheduler = Scheduler::load(&path);
+        assert!(scheduler
+            .due(&schedules, at(2026, 3, 1, 1, 0))
+            .unwrap()
+            .is_empty());
+        assert!(scheduler
+            .due(&schedules, at(2026, 3, 1, 1, 59))
+            .unwrap()
+            .is_empty());
+        let due = scheduler.due(&schedules, at(2026, 3, 1, 2, 0)).unwrap();
+        assert_eq!(due, vec![&schedules[0]]);
+
+        // A restart later that minute doesn't fire it again
+        let mut scheduler = Scheduler::load(&path);
+        assert!(scheduler
+            .due(&schedules, at(2026, 3, 1, 2, 0))
+            .unwrap()
+            .is_empty());
+
+        // Down over the next fire time: fires once when back
+        let mut scheduler = Scheduler::load(&path);
+        assert_eq!(
+            scheduler
+                .due(&schedules, at(2026, 3, 4, 9, 0))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            Scheduler::load_snapshot(&path)["nightly"].at,
+            at(2026, 3, 5, 2, 0)
+        );
+
+        // A changed expression starts over; removed schedules are dropped
+        let changed = vec![schedule("nightly", "0 3 * * *")];
+        assert!(scheduler
+            .due(&changed, at(2026, 3, 5, 2, 30))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            Scheduler::load_snapshot(&path)["nightly"].at,
+            at(2026, 3, 5, 3, 0)
+        );
+        scheduler.due(&[], at(2026, 3, 5, 2, 30)).unwrap();
+        assert!(Scheduler::load_snapshot(&path).is_empty());
+    }
+}
@@ -84,6 +84,32 @@ pub fn oneshot_ralph_session(
     Ok(status)
 }
 
+/// Open a file in the user's editor and block until it exits.
+///
+/// Uses `$VISUAL`, then `$EDITOR`, then `vi`. The variable may carry
+/// arguments (e.g. `code --wait`), so it is run through `sh`.
+pub fn edit_file(path: &Path) -> Result<()> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", editor))
+        .arg(&editor)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to launch editor '{}'", editor))?;
+
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", editor, status);
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
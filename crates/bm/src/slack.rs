@@ -0,0 +1,241 @@
+//! Slack Web API calls, for teams that talk on Slack instead of Telegram:
+//! `bm notify` posts to the team channel, and `bm chatops` reads commands
+//! there and replies.
+//!
+//! Requests go through `curl` with the bot token in a header on stdin, so it
+//! never shows up in the process list. `BM_SLACK_API_URL` points bm at
+//! another API base, such as the mock the end-to-end tests run.
+
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+const API_BASE: &str = "https://slack.com/api";
+
+/// Longest message text Slack accepts, in characters.
+const MAX_MESSAGE_CHARS: usize = 40_000;
+
+/// The bot a token belongs to (`auth.test`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bot {
+    /// Member id of the bot user, as it appears in mentions (`<@U…>`).
+    pub user_id: String,
+    pub user: String,
+    /// Name of the Slack workspace.
+    pub team: String,
+}
+
+/// A message a person posted in a channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    /// Slack member id of the sender, e.g. `U0123ABCD`.
+    pub user: String,
+    pub text: String,
+    /// Timestamp that identifies the message in its channel.
+    pub ts: String,
+}
+
+fn api_base() -> String {
+    std::env::var("BM_SLACK_API_URL").unwrap_or_else(|_| API_BASE.to_string())
+}
+
+/// Calls a Web API method with form `params` and returns the response.
+fn call(token: &str, method: &str, params: &[(&str, &str)]) -> Result<Value> {
+    if which::which("curl").is_err() {
+        bail!("curl not found on PATH; it is needed to reach the Slack API.");
+    }
+    let mut child = Command::new("curl")
+        .args(["-sS", "--max-time", "15", "-K", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let form: Vec<String> = params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, percent_encode(v)))
+            .collect();
+        writeln!(stdin, "url = \"{}/{}\"", api_base(), method)?;
+        writeln!(stdin, "header = \"Authorization: Bearer {}\"", token)?;
+        writeln!(stdin, "data = \"{}\"", form.join("&"))?;
+    }
+    let output = child.wait_with_output().context("Failed to run curl")?;
+    if output.stdout.is_empty() {
+        bail!(
+            "Could not reach the Slack API: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    parse_response(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Checks a Web API response, turning `"ok": false` into an actionable error.
+fn parse_response(body: &str) -> Result<Value> {
+    let response: Value =
+        serde_json::from_str(body).context("Slack returned a response that is not JSON")?;
+    if response["ok"].as_bool() == Some(true) {
+        return Ok(response);
+    }
+    let error = response["error"].as_str().unwrap_or("unknown_error");
+    match error {
+        "invalid_auth" | "not_authed" | "account_inactive" | "token_revoked" => bail!(
+            "Slack rejected the bot token ({}). Copy the Bot User OAuth Token \
+             (xoxb-…) from the app's OAuth & Permissions page.",
+            error
+        ),
+        "channel_not_found" => bail!(
+            "Slack can't find slack_channel ({}). Use the channel id (e.g. C0123ABCD), \
+             and invite the bot if the channel is private.",
+            error
+        ),
+        "not_in_channel" => bail!(
+            "The bot is not in slack_channel ({}). Invite it with /invite @<bot>.",
+            error
+        ),
+        "missing_scope" => bail!(
+            "The Slack app lacks a scope it needs ({}; needed: {}). Add it under \
+             OAuth & Permissions and reinstall the app.",
+            error,
+            response["needed"].as_str().unwrap_or("unknown")
+        ),
+        "ratelimited" => bail!(
+            "Slack is rate limiting this bot ({}); try again shortly.",
+            error
+        ),
+        _ => bail!("Slack API error: {}", error),
+    }
+}
+
+/// Verifies `token` and returns the bot it belongs to.
+pub fn auth_test(token: &str) -> Result<Bot> {
+    let response = call(token, "auth.test", &[])?;
+    let field = |name: &str| response[name].as_str().unwrap_or_default().to_string();
+    if field("user_id").is_empty() {
+        bail!("Unexpected auth.test response from Slack");
+    }
+    Ok(Bot {
+        user_id: field("user_id"),
+        user: field("user"),
+        team: field("team"),
+    })
+}
+
+/// Posts `text` to `channel`, cut to Slack's length limit.
+pub fn post_message(token: &str, channel: &str, text: &str) -> Result<()> {
+    let text: String = text.chars().take(MAX_MESSAGE_CHARS).collect();
+    call(
+        token,
+        "chat.postMessage",
+        &[("channel", channel), ("text", &text)],
+    )
+    .map(|_| ())
+}
+
+/// Timestamp of the newest message in `channel`, to read from with
+/// [`messages_after`] so older messages are skipped. `"0"` for an empty
+/// channel.
+pub fn latest_ts(token: &str, channel: &str) -> Result<String> {
+    let response = call(
+        token,
+        "conversations.history",
+        &[("channel", channel), ("limit", "1")],
+    )?;
+    Ok(response["messages"][0]["ts"]
+        .as_str()
+        .unwrap_or("0")
+        .to_string())
+}
+
+/// Messages people posted in `channel` after `oldest`, oldest first.
+pub fn messages_after(token: &str, channel: &str, oldest: &str) -> Result<Vec<Message>> {
+    let response = call(
+        token,
+        "conversations.history",
+        &[("channel", channel), ("oldest", oldest), ("limit", "100")],
+    )?;
+    Ok(messages_in(&response))
+}
+
+/// Percent-encodes `s` for a form value.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Messages from people in a `conversations.history` response, oldest first.
+/// Bot posts, joins, and edits (anything with a `subtype`) are left out.
+fn messages_in(response: &Value) -> Vec<Message> {
+    let messages = response["messages"].as_array().cloned().unwrap_or_default();
+    let mut out: Vec<Message> = messages
+        .iter()
+        .filter(|m| m.get("subtype").is_none() && m.get("bot_id").is_none())
+        .filter_map(|m| {
+            Some(Message {
+                user: m["user"].as_str()?.to_string(),
+                text: m["text"].as_str()?.to_string(),
+                ts: m["ts"].as_str()?.to_string(),
+            })
+        })
+        .collect();
+    out.sort_by(|a, b| ts_key(&a.ts).total_cmp(&ts_key(&b.ts)));
+    out
+}
+
+fn ts_key(ts: &str) -> f64 {
+    ts.parse().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_response_explains_errors() {
+        let ok = parse_response(r#"{"ok":true,"user_id":"U1"}"#).unwrap();
+        assert_eq!(ok["user_id"], "U1");
+
+        let err = parse_response(r#"{"ok":false,"error":"invalid_auth"}"#).unwrap_err();
+        assert!(err.to_string().contains("xoxb-"), "{}", err);
+        let err = parse_response(r#"{"ok":false,"error":"not_in_channel"}"#).unwrap_err();
+        assert!(err.to_string().contains("/invite"), "{}", err);
+        let err =
+            parse_response(r#"{"ok":false,"error":"missing_scope","needed":"channels:history"}"#)
+                .unwrap_err();
+        assert!(err.to_string().contains("channels:history"), "{}", err);
+        assert!(parse_response("<html>").is_err());
+    }
+
+    #[test]
+    fn messages_in_keeps_posts_by_people_oldest_first() {
+        let response: Value = serde_json::from_str(
+            r#"{"ok": true, "messages": [
+              {"type": "message", "user": "U2", "text": "<@UBOT> stop dev-01", "ts": "1700000010.000200"},
+              {"type": "message", "subtype": "channel_join", "user": "U3", "text": "joined", "ts": "1700000009.000100"},
+              {"type": "message", "bot_id": "B1", "user": "UBOT", "text": "Paused.", "ts": "1700000008.000100"},
+              {"type": "message", "user": "U1", "text": "<@UBOT> status", "ts": "1700000002.000100"}
+            ]}"#,
+        )
+        .unwrap();
+        let messages = messages_in(&response);
+        let got: Vec<(&str, &str)> = messages
+            .iter()
+            .map(|m| (m.user.as_str(), m.text.as_str()))
+            .collect();
+        assert_eq!(
+            got,
+            vec![("U1", "<@UBOT> status"), ("U2", "<@UBOT> stop dev-01")]
+        );
+        assert_eq!(percent_encode("a b/ç&"), "a%20b%2F%C3%A7%26");
+    }
+}
@@ -0,0 +1,223 @@
+//! Mails notifications through a team's SMTP server.
+//!
+//! `curl` already speaks SMTP, STARTTLS, and AUTH, so this shells out to it.
+//! Its options, login included, go on stdin so the password never shows up
+//! in the process list; the message itself is handed over as a temp file.
+
+use std::fs;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use chrono::{DateTime, Local};
+
+use crate::config::{Smtp, SmtpTls};
+
+/// Seconds allowed for connecting and handing over one message.
+const MAX_TIME_SECS: u64 = 30;
+
+/// Sends a plain-text mail to the server's configured recipients.
+pub fn send(smtp: &Smtp, subject: &str, body: &str) -> Result<()> {
+    if smtp.to.is_empty() {
+        bail!("smtp.to lists no recipients. Add at least one address to the team's smtp block.");
+    }
+    if smtp.tls == SmtpTls::None && smtp.password.is_some() {
+        bail!(
+            "Refusing to send the SMTP password to {} unencrypted. Set smtp.tls to starttls \
+             or tls, or drop the login for a relay that doesn't need one.",
+            smtp.server
+        );
+    }
+    if which::which("curl").is_err() {
+        bail!("curl not found on PATH; it is needed to send mail.");
+    }
+
+    let now = Local::now();
+    let message_path = std::env::temp_dir().join(format!(
+        "bm-mail-{}-{}.eml",
+        std::process::id(),
+        now.timestamp_nanos_opt().unwrap_or_default()
+    ));
+    fs::write(&message_path, message(smtp, subject, body, now))
+        .with_context(|| format!("Failed to write {}", message_path.display()))?;
+    let result = run_curl(smtp, &message_path);
+    let _ = fs::remove_file(&message_path);
+    result
+}
+
+fn run_curl(smtp: &Smtp, message_path: &Path) -> Result<()> {
+    let mut child = Command::new("curl")
+        .args(["-sS", "--max-time", &MAX_TIME_SECS.to_string(), "-K", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(curl_config(smtp, message_path).as_bytes())?;
+    }
+    let output = child.wait_with_output().context("Failed to run curl")?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let hint = match output.status.code() {
+        Some(67) => " The server rejected the login; check smtp.username and smtp.password.",
+        Some(6) | Some(7) | Some(28) => {
+            " Check smtp.server and smtp.port, and that this machine can reach them."
+        }
+        Some(35) | Some(60) | Some(64) => {
+            " The TLS handshake failed; check that smtp.tls matches what the server expects \
+             on this port."
+        }
+        _ => "",
+    };
+    bail!(
+        "Failed to send mail via {}:{}: {}{}",
+        smtp.server,
+        smtp.port(),
+        String::from_utf8_lossy(&output.stderr).trim(),
+        hint
+    );
+}
+
+/// The server URL: `smtps://` for TLS from the start, else `smtp://`.
+fn url(smtp: &Smtp) -> String {
+    let scheme = match smtp.tls {
+        SmtpTls::Tls => "smtps",
+        SmtpTls::Starttls | SmtpTls::None => "smtp",
+    };
+    format!("{}://{}:{}", scheme, smtp.server, smtp.port())
+}
+
+/// curl options for one message, read with `-K -`.
+fn curl_config(smtp: &Smtp, message_path: &Path) -> String {
+    let mut lines = vec![format!("url = {}", quote(&url(smtp)))];
+    if smtp.tls == SmtpTls::Starttls {
+        lines.push("ssl-reqd".to_string());
+    }
+    lines.push(format!("mail-from = {}", quote(&smtp.from)));
+    for to in &smtp.to {
+        lines.push(format!("mail-rcpt = {}", quote(to)));
+    }
+    if let Some(ref user) = smtp.username {
+        let login = format!("{}:{}", user, smtp.password.as_deref().unwrap_or_default());
+        lines.push(format!("user = {}", quote(&login)));
+    }
+    lines.push(format!("upload-file = {}", quote(&message_path.to_string_lossy())));
+    lines.join("\n") + "\n"
+}
+
+/// Quotes a value for a curl config file.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The mail as sent: headers, a blank line, and the body, with CRLF endings.
+fn message(smtp: &Smtp, subject: &str, body: &str, date: DateTime<Local>) -> String {
+    let headers = [
+        ("From", smtp.from.clone()),
+        ("To", smtp.to.join(", ")),
+        ("Subject", encode_header(subject)),
+        ("Date", date.to_rfc2822()),
+        ("MIME-Version", "1.0".to_string()),
+        ("Content-Type", "text/plain; charset=utf-8".to_string()),
+        ("Content-Transfer-Encoding", "8bit".to_string()),
+    ];
+    let mut out = String::new();
+    for (name, value) in headers {
+        out.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    out.push_str("\r\n");
+    for line in body.lines() {
+        out.push_str(line);
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Keeps header text on one line, encoding non-ASCII text per RFC 2047.
+fn encode_header(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_ascii() {
+        text
+    } else {
+        format!("=?UTF-8?B?{}?=", STANDARD.encode(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn smtp(tls: SmtpTls) -> Smtp {
+        Smtp {
+            server: "mail.example.com".to_string(),
+            port: None,
+            tls,
+            username: Some("bm".to_string()),
+            password: Some("pa\"ss".to_string()),
+            from: "bm@example.com".to_string(),
+            to: vec!["ops@example.com".to_string(), "lead@example.com".to_string()],
+            notify_on: Vec::new(),
+            digest_at: None,
+        }
+    }
+
+    #[test]
+    fn curl_config_requires_starttls_and_quotes_the_login() {
+        let config = curl_config(&smtp(SmtpTls::Starttls), Path::new("/tmp/m.eml"));
+        assert_eq!(
+            config,
+            "url = \"smtp://mail.example.com:587\"\n\
+             ssl-reqd\n\
+             mail-from = \"bm@example.com\"\n\
+             mail-rcpt = \"ops@example.com\"\n\
+             mail-rcpt = \"lead@example.com\"\n\
+             user = \"bm:pa\\\"ss\"\n\
+             upload-file = \"/tmp/m.eml\"\n"
+        );
+    }
+
+    #[test]
+    fn url_follows_tls_mode_and_port() {
+        assert_eq!(url(&smtp(SmtpTls::Tls)), "smtps://mail.example.com:465");
+        let mut relay = smtp(SmtpTls::None);
+        relay.port = Some(2525);
+        assert_eq!(url(&relay), "smtp://mail.example.com:2525");
+        assert!(!curl_config(&relay, Path::new("/m")).contains("ssl-reqd"));
+    }
+
+    #[test]
+    fn send_refuses_password_without_tls() {
+        let err = send(&smtp(SmtpTls::None), "s", "b").unwrap_err().to_string();
+        assert!(err.contains("unencrypted"), "{}", err);
+    }
+
+    #[test]
+    fn message_has_headers_and_crlf_body() {
+        let date = DateTime::parse_from_rfc3339("2026-03-01T08:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Local);
+        let mail = message(
+            &smtp(SmtpTls::Starttls),
+            "[bm alpha] dev-01\ncrashed",
+            "line one\nline two",
+            date,
+        );
+        let (headers, body) = mail.split_once("\r\n\r\n").unwrap();
+        assert!(headers.contains("From: bm@example.com\r\n"), "{}", headers);
+        assert!(headers.contains("To: ops@example.com, lead@example.com\r\n"));
+        assert!(headers.contains("Subject: [bm alpha] dev-01 crashed\r\n"));
+        assert!(headers.contains("Content-Type: text/plain; charset=utf-8"));
+        assert_eq!(body, "line one\r\nline two\r\n");
+    }
+
+    #[test]
+    fn encode_header_handles_non_ascii() {
+        assert_eq!(encode_header("plain"), "plain");
+        assert_eq!(encode_header("café"), "=?UTF-8?B?Y2Fmw6k=?=");
+    }
+}
@@ -0,0 +1,325 @@
+//! Member workspace snapshots.
+//!
+//! `bm workspace snapshot` archives a member's workspace directory
+//! (`<workzone>/<team>/<member>/`, all of its projects) to
+//! `~/.botminter/snapshots/<team>/<member>/<id>.tar.gz`, with a `<id>.json`
+//! next to it describing the snapshot. `bm workspace restore` swaps the
+//! directory for the archive's contents, so an agent's workspace can be
+//! rolled back after it goes off the rails.
+//!
+//! Snapshots taken without git objects leave out every `.git/objects`
+//! directory; restoring one keeps the objects the workspace has now, which
+//! still hold the snapshot's commits unless git has pruned them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+/// A snapshot's description, stored as `<id>.json` beside its archive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Taken-at timestamp, `YYYYMMDD-HHMMSS`, with a `-N` suffix when two
+    /// land in the same second.
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    /// Whether the archive holds the workspace's `.git/objects`.
+    pub git_objects: bool,
+    /// Size of the archive in bytes.
+    pub bytes: u64,
+}
+
+/// Where the snapshots of a team's member are kept.
+pub fn snapshots_dir(team_name: &str, member: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?
+        .join("snapshots")
+        .join(team_name)
+        .join(member))
+}
+
+/// The archive of snapshot `id` in `dir`.
+pub fn archive_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.tar.gz", id))
+}
+
+/// Archives `member_dir` into `dir` as a new snapshot. Symlinks are stored
+/// as links, not followed. Without `git_objects`, `.git/objects`
+/// directories are left out.
+pub fn create(member_dir: &Path, dir: &Path, git_objects: bool) -> Result<Snapshot> {
+    if !member_dir.is_dir() {
+        bail!("Workspace {} does not exist", member_dir.display());
+    }
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    let created_at = Utc::now();
+    let stamp = created_at.format("%Y%m%d-%H%M%S").to_string();
+    let mut id = stamp.clone();
+    let mut n = 2;
+    while dir.join(format!("{}.json", id)).exists() || archive_path(dir, &id).exists() {
+        id = format!("{}-{}", stamp, n);
+        n += 1;
+    }
+
+    let path = archive_path(dir, &id);
+    let written = write_archive(member_dir, &path, git_objects);
+    if let Err(e) = written {
+        let _ = fs::remove_file(&path);
+        return Err(e);
+    }
+
+    let snapshot = Snapshot {
+        id,
+        created_at,
+        git_objects,
+        bytes: fs::metadata(&path)?.len(),
+    };
+    let meta = serde_json::to_string_pretty(&snapshot)?;
+    let meta_path = dir.join(format!("{}.json", snapshot.id));
+    fs::write(&meta_path, meta)
+        .with_context(|| format!("Failed to write {}", meta_path.display()))?;
+    Ok(snapshot)
+}
+
+/// Writes the tar.gz of `member_dir` to `path`.
+fn write_archive(member_dir: &Path, path: &Path, git_objects: bool) -> Result<()> {
+    let file =
+        fs::File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    tar.follow_symlinks(false);
+    append_dir(&mut tar, member_dir, Path::new(""), git_objects)?;
+    tar.into_inner()
+        .and_then(|gz| gz.finish())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Adds the entries under `root/rel` to the archive, depth first.
+fn append_dir(
+    tar: &mut tar::Builder<GzEncoder<fs::File>>,
+    root: &Path,
+    rel: &Path,
+    git_objects: bool,
+) -> Result<()> {
+    let dir = root.join(rel);
+    let mut entries: Vec<_> = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .collect::<std::io::Result<_>>()?;
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let name = rel.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if !git_objects && file_type.is_dir() && is_git_objects(&name) {
+            continue;
+        }
+        let full = entry.path();
+        if file_type.is_dir() {
+            tar.append_dir(&name, &full)
+                .with_context(|| format!("Failed to add {}", full.display()))?;
+            append_dir(tar, root, &name, git_objects)?;
+        } else {
+            tar.append_path_with_name(&full, &name)
+                .with_context(|| format!("Failed to add {}", full.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether `rel` is a repository's object store (`.../.git/objects`).
+fn is_git_objects(rel: &Path) -> bool {
+    rel.ends_with(".git/objects")
+}
+
+/// Lists the snapshots in `dir`, oldest first. Descriptions that can't be
+/// read, or whose archive is gone, are skipped.
+pub fn list(dir: &Path) -> Result<Vec<Snapshot>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut snapshots: Vec<Snapshot> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| serde_json::from_str::<Snapshot>(&fs::read_to_string(&p).ok()?).ok())
+        .filter(|s| archive_path(dir, &s.id).is_file())
+        .collect();
+    snapshots.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+    Ok(snapshots)
+}
+
+/// Finds snapshot `id` in `dir`.
+pub fn find(dir: &Path, id: &str) -> Result<Snapshot> {
+    let snapshots = list(dir)?;
+    if let Some(snapshot) = snapshots.iter().find(|s| s.id == id) {
+        return Ok(snapshot.clone());
+    }
+    if snapshots.is_empty() {
+        bail!("Snapshot '{}' not found; there are no snapshots", id);
+    }
+    let ids: Vec<&str> = snapshots.iter().map(|s| s.id.as_str()).collect();
+    bail!("Snapshot '{}' not found. Snapshots: {}", id, ids.join(", "))
+}
+
+/// Replaces `member_dir` with the contents of `snapshot`. The archive is
+/// unpacked next to the workspace first, so a failure leaves the workspace
+/// as it was. For snapshots without git objects, the workspace's current
+/// `.git/objects` directories are carried over.
+pub fn restore(dir: &Path, snapshot: &Snapshot, member_dir: &Path) -> Result<()> {
+    let name = member_dir
+        .file_name()
+        .with_context(|| format!("Invalid workspace path {}", member_dir.display()))?
+        .to_string_lossy()
+        .to_string();
+    let parent = member_dir
+        .parent()
+        .with_context(|| format!("Invalid workspace path {}", member_dir.display()))?;
+    fs::create_dir_all(parent)?;
+    let staging = parent.join(format!(".{}.restore-{}", name, snapshot.id));
+    let previous = parent.join(format!(".{}.pre-restore", name));
+    for leftover in [&staging, &previous] {
+        if leftover.exists() {
+            fs::remove_dir_all(leftover)
+                .with_context(|| format!("Failed to remove {}", leftover.display()))?;
+        }
+    }
+
+    let path = archive_path(dir, &snapshot.id);
+    let unpacked = fs::File::open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))
+        .and_then(|file| {
+            let mut archive = tar::Archive::new(GzDecoder::new(file));
+            archive.set_preserve_permissions(true);
+            archive.set_preserve_mtime(true);
+            archive
+                .unpack(&staging)
+                .with_context(|| format!("Failed to unpack {}", path.display()))
+        });
+    if let Err(e) = unpacked {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    if member_dir.exists() {
+        if !snapshot.git_objects {
+            for objects in find_git_objects(member_dir, Path::new("")) {
+                let target = staging.join(&objects);
+                if target.parent().is_some_and(|p| p.is_dir()) && !target.exists() {
+                    fs::rename(member_dir.join(&objects), &target).with_context(|| {
+                        format!("Failed to keep {}", member_dir.join(&objects).display())
+                    })?;
+                }
+            }
+        }
+        fs::rename(member_dir, &previous)
+            .with_context(|| format!("Failed to move {} aside", member_dir.display()))?;
+    }
+    fs::rename(&staging, member_dir)
+        .with_context(|| format!("Failed to move the restored workspace into {}", member_dir.display()))?;
+    if previous.exists() {
+        fs::remove_dir_all(&previous)
+            .with_context(|| format!("Failed to remove {}", previous.display()))?;
+    }
+    Ok(())
+}
+
+/// The `.git/objects` directories under `root/rel`, relative to `root`.
+fn find_git_objects(root: &Path, rel: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    for entry in fs::read_dir(root.join(rel)).into_iter().flatten().flatten() {
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let name = rel.join(entry.file_name());
+        if is_git_objects(&name) {
+            found.push(name);
+        } else {
+            found.extend(find_git_objects(root, &name));
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(root: &Path) -> PathBuf {
+        let ws = root.join("dev-01");
+        fs::create_dir_all(ws.join("proj/.git/objects/ab")).unwrap();
+        fs::write(ws.join("proj/.git/objects/ab/cdef"), "blob").unwrap();
+        fs::write(ws.join("proj/.git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(ws.join("proj/PROMPT.md"), "original").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("PROMPT.md", ws.join("proj/CLAUDE.md")).unwrap();
+        ws
+    }
+
+    #[test]
+    fn restore_rolls_the_workspace_back() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = workspace(tmp.path());
+        let dir = tmp.path().join("snapshots");
+
+        let snapshot = create(&ws, &dir, true).unwrap();
+        assert!(snapshot.git_objects);
+        assert_eq!(list(&dir).unwrap(), vec![snapshot.clone()]);
+
+        fs::write(ws.join("proj/PROMPT.md"), "off the rails").unwrap();
+        fs::write(ws.join("proj/junk.txt"), "junk").unwrap();
+        restore(&dir, &snapshot, &ws).unwrap();
+
+        assert_eq!(fs::read_to_string(ws.join("proj/PROMPT.md")).unwrap(), "original");
+        assert!(!ws.join("proj/junk.txt").exists());
+        assert!(ws.join("proj/.git/objects/ab/cdef").is_file());
+        #[cfg(unix)]
+        assert_eq!(
+            fs::read_link(ws.join("proj/CLAUDE.md")).unwrap(),
+            PathBuf::from("PROMPT.md")
+        );
+        // Nothing left beside the workspace
+        let mut names: Vec<String> = fs::read_dir(tmp.path())
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["dev-01", "snapshots"]);
+    }
+
+    #[test]
+    fn snapshot_without_git_objects_keeps_the_current_ones() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = workspace(tmp.path());
+        let dir = tmp.path().join("snapshots");
+
+        let snapshot = create(&ws, &dir, false).unwrap();
+        fs::write(ws.join("proj/.git/objects/ab/newer"), "blob").unwrap();
+        fs::write(ws.join("proj/PROMPT.md"), "changed").unwrap();
+        restore(&dir, &snapshot, &ws).unwrap();
+
+        assert_eq!(fs::read_to_string(ws.join("proj/PROMPT.md")).unwrap(), "original");
+        assert!(ws.join("proj/.git/objects/ab/cdef").is_file());
+        assert!(ws.join("proj/.git/objects/ab/newer").is_file());
+        assert!(ws.join("proj/.git/HEAD").is_file());
+    }
+
+    #[test]
+    fn snapshots_in_the_same_second_get_distinct_ids() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = workspace(tmp.path());
+        let dir = tmp.path().join("snapshots");
+
+        let first = create(&ws, &dir, false).unwrap();
+        let second = create(&ws, &dir, false).unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(find(&dir, &second.id).unwrap(), second);
+        let err = find(&dir, "nope").unwrap_err().to_string();
+        assert!(err.contains(&first.id), "{}", err);
+    }
+}
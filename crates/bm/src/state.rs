@@ -1,20 +1,184 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 use crate::config;
+use crate::parse;
+use crate::procutil;
+use crate::state_history;
+use crate::store::{self, FileStore, Store, STATE_KEY};
+use crate::timefmt;
 
 const STATE_FILE: &str = "state.json";
 
 /// Runtime state tracking PIDs of running Ralph processes.
-/// Stored at `~/.botminter/state.json`.
-#[derive(Debug, Serialize, Deserialize, Default)]
+/// Stored at `~/.botminter/state.json`, or in the [`store`] config.yml
+/// selects.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct RuntimeState {
     #[serde(default)]
     pub members: HashMap<String, MemberRuntime>,
+    /// Run history per member, keyed like `members`. Kept after a member
+    /// stops so uptime and crash counts accumulate across runs.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub stats: HashMap<String, MemberStats>,
+    /// Paused teams, by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub paused: BTreeMap<String, Pause>,
+}
+
+/// A paused team: its daemon launches no members and `bm start` refuses
+/// without `--force`. Members already running carry on.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Pause {
+    /// When the team was paused (ISO 8601).
+    pub at: String,
+    /// Who paused it: a user name, or a chat handle for chatops.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub by: String,
+}
+
+impl Pause {
+    /// `since <time> by <who>`.
+    pub fn describe(&self) -> String {
+        let mut note = format!("since {}", timefmt::rfc3339(&self.at));
+        if !self.by.is_empty() {
+            note.push_str(&format!(" by {}", self.by));
+        }
+        note
+    }
+}
+
+/// Cumulative uptime and crash counts for one member.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct MemberStats {
+    /// Completed runs, whether stopped or crashed.
+    pub runs: u32,
+    /// Runs that ended without `bm stop` (or, for daemon runs, with a
+    /// non-zero exit).
+    pub crashes: u32,
+    /// Seconds spent running across completed runs.
+    pub uptime_secs: u64,
+    /// When the first recorded run started (ISO 8601).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_started_at: Option<String>,
+    /// When the most recent crash happened, as best known (ISO 8601): see
+    /// [`RuntimeState::end_crashed_run`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_crash_at: Option<String>,
+}
+
+impl MemberStats {
+    /// Mean time between crashes in seconds, given `extra_secs` of uptime
+    /// from a run still in progress. `None` until the first crash.
+    pub fn mtbc_secs(&self, extra_secs: u64) -> Option<u64> {
+        (self.crashes > 0).then(|| (self.uptime_secs + extra_secs) / self.crashes as u64)
+    }
+
+    /// Adds another member's history to this one (for team totals).
+    pub fn merge(&mut self, other: &MemberStats) {
+        self.runs += other.runs;
+        self.crashes += other.crashes;
+        self.uptime_secs += other.uptime_secs;
+        self.first_started_at = min_timestamp(&self.first_started_at, &other.first_started_at);
+        self.last_crash_at = match (&self.last_crash_at, &other.last_crash_at) {
+            (Some(a), Some(b)) => Some(later(a, b).to_string()),
+            (a, b) => a.clone().or_else(|| b.clone()),
+        };
+    }
+}
+
+/// How a member's run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// Stopped on request.
+    Stopped,
+    /// Died on its own.
+    Crashed,
+}
+
+impl RuntimeState {
+    /// Removes a running member and records the finished run in its stats.
+    pub fn end_run(&mut self, key: &str, outcome: RunOutcome, now: DateTime<Utc>) {
+        if let Some(rt) = self.members.remove(key) {
+            self.record_run(key, &rt.started_at, now, outcome);
+        }
+    }
+
+    /// Removes a member whose process was found dead and records the crash.
+    ///
+    /// The run is taken to end at `last_seen`, the member's last sign of life
+    /// (see [`last_log_write`]), when that falls within the run, so the time
+    /// before anyone noticed counts toward neither uptime nor MTBC. Otherwise
+    /// it ends at `now`.
+    pub fn end_crashed_run(
+        &mut self,
+        key: &str,
+        last_seen: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) {
+        let Some(rt) = self.members.remove(key) else {
+            return;
+        };
+        let started = DateTime::parse_from_rfc3339(&rt.started_at).map(|t| t.with_timezone(&Utc));
+        let ended = match (last_seen, started) {
+            (Some(seen), Ok(start)) if seen >= start && seen <= now => seen,
+            _ => now,
+        };
+        self.record_run(key, &rt.started_at, ended, RunOutcome::Crashed);
+    }
+
+    /// Records a run that started at `started_at` (ISO 8601) and ended at `ended_at`.
+    pub fn record_run(
+        &mut self,
+        key: &str,
+        started_at: &str,
+        ended_at: DateTime<Utc>,
+        outcome: RunOutcome,
+    ) {
+        let stats = self.stats.entry(key.to_string()).or_default();
+        stats.runs += 1;
+        if let Ok(start) = DateTime::parse_from_rfc3339(started_at) {
+            let secs = (ended_at - start.with_timezone(&Utc)).num_seconds();
+            stats.uptime_secs += secs.max(0) as u64;
+            stats.first_started_at =
+                min_timestamp(&stats.first_started_at, &Some(started_at.to_string()));
+        }
+        if outcome == RunOutcome::Crashed {
+            stats.crashes += 1;
+            stats.last_crash_at = Some(ended_at.to_rfc3339());
+        }
+    }
+}
+
+/// Returns when the member under `key` (`<team>/<member>`) last wrote to its
+/// log, or `None` if it has no log.
+pub fn last_log_write(key: &str) -> Option<DateTime<Utc>> {
+    let (team, member) = key.split_once('/')?;
+    let log = crate::commands::daemon::member_log_path(team, member).ok()?;
+    let modified = fs::metadata(log).and_then(|m| m.modified()).ok()?;
+    Some(modified.into())
+}
+
+/// Returns the later of two ISO 8601 timestamps.
+fn later<'a>(a: &'a str, b: &'a str) -> &'a str {
+    match (DateTime::parse_from_rfc3339(a), DateTime::parse_from_rfc3339(b)) {
+        (Ok(x), Ok(y)) if y > x => b,
+        _ => a,
+    }
+}
+
+/// Returns the earlier of two optional ISO 8601 timestamps.
+fn min_timestamp(a: &Option<String>, b: &Option<String>) -> Option<String> {
+    match (a, b) {
+        (Some(x), Some(y)) => Some(if later(x, y) == x { y.clone() } else { x.clone() }),
+        _ => a.clone().or_else(|| b.clone()),
+    }
 }
 
 /// Runtime info for a single running member.
@@ -23,6 +187,23 @@ pub struct MemberRuntime {
     pub pid: u32,
     pub started_at: String, // ISO 8601
     pub workspace: PathBuf,
+    /// Environment injected at launch, secrets redacted.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// How the member's readiness probe went; absent once it passed, or
+    /// when its role declares none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<Health>,
+}
+
+/// A live member that hasn't passed its readiness probe.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Health {
+    /// Launched; the probe is still being waited on.
+    Starting,
+    /// The probe timed out; `reason` lists the checks still failing.
+    Unhealthy { reason: String },
 }
 
 /// Returns the path to state.json.
@@ -30,9 +211,14 @@ fn state_path() -> Result<PathBuf> {
     Ok(config::config_dir()?.join(STATE_FILE))
 }
 
-/// Loads runtime state from disk. Returns empty state if file is missing.
+/// Loads runtime state from the configured store. Returns empty state if
+/// none is recorded.
 pub fn load() -> Result<RuntimeState> {
-    load_from(&state_path()?)
+    let store = store::open()?;
+    match store.read(STATE_KEY)? {
+        Some(contents) => Ok(parse::json(&store.location(STATE_KEY), &contents)?),
+        None => Ok(RuntimeState::default()),
+    }
 }
 
 /// Loads runtime state from a specific path.
@@ -41,14 +227,14 @@ pub fn load_from(path: &Path) -> Result<RuntimeState> {
         return Ok(RuntimeState::default());
     }
     let contents = fs::read_to_string(path).context("Failed to read state.json")?;
-    let state: RuntimeState =
-        serde_json::from_str(&contents).context("Failed to parse state.json")?;
+    let state: RuntimeState = parse::json(path, &contents)?;
     Ok(state)
 }
 
-/// Saves runtime state atomically (write to temp file, then rename).
+/// Saves runtime state to the configured store atomically.
 pub fn save(state: &RuntimeState) -> Result<()> {
-    save_to(&state_path()?, state)
+    let contents = serde_json::to_string_pretty(state).context("Failed to serialize state")?;
+    store::open()?.update(STATE_KEY, &mut |_| Ok(contents.clone()))
 }
 
 /// Saves runtime state to a specific path atomically.
@@ -68,23 +254,99 @@ pub fn save_to(path: &Path, state: &RuntimeState) -> Result<()> {
     Ok(())
 }
 
-/// Checks if a process with the given PID is alive using `kill(pid, 0)`.
-pub fn is_alive(pid: u32) -> bool {
-    // Safety: kill with signal 0 only checks existence, sends no signal.
-    unsafe { libc::kill(pid as i32, 0) == 0 }
+/// Runs a locked load-modify-save cycle on the runtime state in the
+/// configured store.
+///
+/// Concurrent `bm` processes can't clobber each other's changes: the file
+/// backend holds an exclusive lock for the duration, SQLite a transaction.
+/// The state is saved only if `f` succeeds.
+pub fn update<T>(f: impl FnOnce(&mut RuntimeState) -> Result<T>) -> Result<T> {
+    update_in(store::open()?.as_ref(), STATE_KEY, &state_path()?, f)
+}
+
+/// Runs a locked load-modify-save cycle on a specific state file. Runs that
+/// start or end are appended to the state history next to it.
+pub fn update_at<T>(path: &Path, f: impl FnOnce(&mut RuntimeState) -> Result<T>) -> Result<T> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let key = path.file_stem().unwrap_or_default().to_string_lossy();
+    update_in(&FileStore::new(dir), &key, path, f)
+}
+
+/// Runs the load-modify-save cycle on the state under `key` in `store`,
+/// recording transitions in the history next to `path` (the state file's
+/// path in the file backend).
+fn update_in<T>(
+    store: &dyn Store,
+    key: &str,
+    path: &Path,
+    f: impl FnOnce(&mut RuntimeState) -> Result<T>,
+) -> Result<T> {
+    let mut f = Some(f);
+    let mut result = None;
+    let mut entries = Vec::new();
+    store.update(key, &mut |contents| {
+        let mut state = match contents {
+            Some(contents) => parse::json(&store.location(key), &contents)?,
+            None => RuntimeState::default(),
+        };
+        let before = state.clone();
+        let f = f.take().context("State update ran twice")?;
+        result = Some(f(&mut state)?);
+        entries = state_history::transitions(&before, &state, Utc::now());
+        serde_json::to_string_pretty(&state).context("Failed to serialize state")
+    })?;
+    if let Err(e) = state_history::append(&state_history::history_path(path), &entries) {
+        warn!("Failed to record state history: {:#}", e);
+    }
+    result.context("State update did not run")
+}
+
+/// Pauses `team`, recording who asked. Returns false if it was already
+/// paused.
+pub fn pause(team: &str, by: &str) -> Result<bool> {
+    update(|s| {
+        if s.paused.contains_key(team) {
+            return Ok(false);
+        }
+        let pause = Pause {
+            at: Utc::now().to_rfc3339(),
+            by: by.to_string(),
+        };
+        s.paused.insert(team.to_string(), pause);
+        Ok(true)
+    })
 }
 
-/// Removes entries for dead processes from state. Returns the keys that were cleaned.
+/// Lifts a pause. Returns false if `team` wasn't paused.
+pub fn resume(team: &str) -> Result<bool> {
+    update(|s| Ok(s.paused.remove(team).is_some()))
+}
+
+/// Describes the pause (`since <time> by <who>`) if `team` is paused.
+pub fn paused(team: &str) -> Option<String> {
+    load().ok()?.paused.get(team).map(Pause::describe)
+}
+
+/// Removes entries for dead processes from state, recording each as a crash
+/// dated to the member's last log write. Returns the keys that were cleaned.
 pub fn cleanup_stale(state: &mut RuntimeState) -> Vec<String> {
+    cleanup_stale_at(state, last_log_write, Utc::now())
+}
+
+fn cleanup_stale_at(
+    state: &mut RuntimeState,
+    last_seen: impl Fn(&str) -> Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Vec<String> {
     let stale: Vec<String> = state
         .members
         .iter()
-        .filter(|(_, rt)| !is_alive(rt.pid))
+        .filter(|(_, rt)| !procutil::is_alive(rt.pid))
         .map(|(key, _)| key.clone())
         .collect();
 
     for key in &stale {
-        state.members.remove(key);
+        state.end_crashed_run(key, last_seen(key), now);
     }
 
     stale
@@ -106,6 +368,8 @@ mod tests {
                 pid: 12345,
                 started_at: "2026-02-20T10:00:00Z".to_string(),
                 workspace: PathBuf::from("/tmp/ws/arch-01"),
+                env: Default::default(),
+                health: None,
             },
         );
 
@@ -127,6 +391,20 @@ mod tests {
         assert!(state.members.is_empty());
     }
 
+    #[test]
+    fn load_accepts_records_without_env() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("state.json");
+        fs::write(
+            &path,
+            r#"{"members":{"t/m":{"pid":1,"started_at":"2026-02-20T10:00:00Z","workspace":"/tmp/ws"}}}"#,
+        )
+        .unwrap();
+
+        let state = load_from(&path).unwrap();
+        assert!(state.members["t/m"].env.is_empty());
+    }
+
     #[test]
     fn atomic_write_leaves_no_tmp() {
         let tmp = tempfile::tempdir().unwrap();
@@ -141,17 +419,50 @@ mod tests {
     }
 
     #[test]
-    fn is_alive_current_process() {
-        // Our own PID should be alive
-        let pid = std::process::id();
-        assert!(is_alive(pid));
+    fn update_at_persists_changes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("state.json");
+
+        update_at(&path, |state| {
+            state.members.insert(
+                "team/dev-01".to_string(),
+                MemberRuntime {
+                    pid: 42,
+                    started_at: "2026-02-20T10:00:00Z".to_string(),
+                    workspace: PathBuf::from("/tmp/ws/dev-01"),
+                    env: Default::default(),
+                    health: None,
+                },
+            );
+            Ok(())
+        })
+        .unwrap();
+
+        let loaded = load_from(&path).unwrap();
+        assert_eq!(loaded.members["team/dev-01"].pid, 42);
     }
 
     #[test]
-    fn is_alive_nonexistent_pid() {
-        // PID 0 is the kernel scheduler — kill(0, 0) checks the calling process's group,
-        // so use a very high PID unlikely to exist.
-        assert!(!is_alive(4_000_000));
+    fn update_at_skips_save_on_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("state.json");
+
+        let result: Result<()> = update_at(&path, |state| {
+            state.members.insert(
+                "team/dev-01".to_string(),
+                MemberRuntime {
+                    pid: 42,
+                    started_at: "2026-02-20T10:00:00Z".to_string(),
+                    workspace: PathBuf::from("/tmp/ws/dev-01"),
+                    env: Default::default(),
+                    health: None,
+                },
+            );
+            anyhow::bail!("boom")
+        });
+
+        assert!(result.is_err());
+        assert!(!path.exists(), "Failed update must not write state");
     }
 
     #[test]
@@ -163,6 +474,8 @@ mod tests {
                 pid: 4_000_000, // unlikely to exist
                 started_at: "2026-01-01T00:00:00Z".to_string(),
                 workspace: PathBuf::from("/tmp/dead"),
+                env: Default::default(),
+                health: None,
             },
         );
         state.members.insert(
@@ -171,14 +484,134 @@ mod tests {
                 pid: std::process::id(), // current process, definitely alive
                 started_at: "2026-01-01T00:00:00Z".to_string(),
                 workspace: PathBuf::from("/tmp/alive"),
+                env: Default::default(),
+                health: None,
             },
         );
 
-        let stale = cleanup_stale(&mut state);
+        // Last heard from an hour in, noticed a day later
+        let last_seen = |key: &str| {
+            assert_eq!(key, "dead-member");
+            Some(at("2026-01-01T01:00:00Z"))
+        };
+        let stale = cleanup_stale_at(&mut state, last_seen, at("2026-01-02T00:00:00Z"));
 
         assert_eq!(stale.len(), 1);
         assert!(stale.contains(&"dead-member".to_string()));
         assert_eq!(state.members.len(), 1);
         assert!(state.members.contains_key("alive-member"));
+        let stats = &state.stats["dead-member"];
+        assert_eq!((stats.crashes, stats.uptime_secs), (1, 3600));
+        assert_eq!(stats.last_crash_at.as_deref(), Some("2026-01-01T01:00:00+00:00"));
+        assert!(!state.stats.contains_key("alive-member"));
+    }
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn end_run_accumulates_uptime_and_crashes() {
+        let mut state = RuntimeState::default();
+        for (started, ended, outcome) in [
+            ("2026-03-01T10:00:00Z", "2026-03-01T11:00:00Z", RunOutcome::Crashed),
+            ("2026-03-01T12:00:00Z", "2026-03-01T15:00:00Z", RunOutcome::Stopped),
+            ("2026-03-02T09:00:00Z", "2026-03-02T11:00:00Z", RunOutcome::Crashed),
+        ] {
+            state.members.insert(
+                "team/dev-a".to_string(),
+                MemberRuntime {
+                    pid: 4_000_000,
+                    started_at: started.to_string(),
+                    workspace: PathBuf::from("/tmp/ws"),
+                    env: Default::default(),
+                    health: None,
+                },
+            );
+            state.end_run("team/dev-a", outcome, at(ended));
+        }
+
+        assert!(state.members.is_empty());
+        let stats = &state.stats["team/dev-a"];
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.crashes, 2);
+        assert_eq!(stats.uptime_secs, 6 * 3600);
+        assert_eq!(stats.first_started_at.as_deref(), Some("2026-03-01T10:00:00Z"));
+        assert_eq!(stats.last_crash_at.as_deref(), Some("2026-03-02T11:00:00+00:00"));
+        assert_eq!(stats.mtbc_secs(0), Some(3 * 3600));
+        assert_eq!(stats.mtbc_secs(2 * 3600), Some(4 * 3600));
+        assert_eq!(MemberStats::default().mtbc_secs(100), None);
+    }
+
+    #[test]
+    fn end_crashed_run_dates_the_crash_to_the_last_sign_of_life() {
+        let stale = |started: &str| MemberRuntime {
+            pid: 4_000_000,
+            started_at: started.to_string(),
+            workspace: PathBuf::from("/tmp/ws"),
+            env: Default::default(),
+            health: None,
+        };
+        let mut state = RuntimeState::default();
+        let now = at("2026-03-01T18:00:00Z");
+
+        // Died an hour in; noticed seven hours later
+        state.members.insert("t/a".to_string(), stale("2026-03-01T10:00:00Z"));
+        state.end_crashed_run("t/a", Some(at("2026-03-01T11:00:00Z")), now);
+        let stats = &state.stats["t/a"];
+        assert_eq!((stats.crashes, stats.uptime_secs), (1, 3600));
+        assert_eq!(stats.last_crash_at.as_deref(), Some("2026-03-01T11:00:00+00:00"));
+
+        // A log last written before the run started (or never) says nothing
+        // about this run
+        for last_seen in [Some(at("2026-03-01T09:00:00Z")), None] {
+            state.members.insert("t/b".to_string(), stale("2026-03-01T17:00:00Z"));
+            state.end_crashed_run("t/b", last_seen, now);
+        }
+        assert_eq!(state.stats["t/b"].uptime_secs, 2 * 3600);
+        assert!(state.members.is_empty());
+    }
+
+    #[test]
+    fn merge_combines_member_stats() {
+        let mut state = RuntimeState::default();
+        state.record_run(
+            "t/a",
+            "2026-03-02T00:00:00Z",
+            at("2026-03-02T01:00:00Z"),
+            RunOutcome::Crashed,
+        );
+        state.record_run(
+            "t/b",
+            "2026-03-01T00:00:00Z",
+            at("2026-03-01T02:00:00Z"),
+            RunOutcome::Stopped,
+        );
+
+        let mut team = MemberStats::default();
+        for stats in state.stats.values() {
+            team.merge(stats);
+        }
+        assert_eq!((team.runs, team.crashes, team.uptime_secs), (2, 1, 3 * 3600));
+        assert_eq!(team.first_started_at.as_deref(), Some("2026-03-01T00:00:00Z"));
+        assert_eq!(team.last_crash_at.as_deref(), Some("2026-03-02T01:00:00+00:00"));
+    }
+
+    #[test]
+    fn stats_round_trip_and_default_when_absent() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("state.json");
+        fs::write(&path, r#"{"members":{}}"#).unwrap();
+        assert!(load_from(&path).unwrap().stats.is_empty());
+
+        let mut state = RuntimeState::default();
+        state.record_run(
+            "t/a",
+            "2026-03-01T00:00:00Z",
+            at("2026-03-01T00:10:00Z"),
+            RunOutcome::Stopped,
+        );
+        save_to(&path, &state).unwrap();
+        assert_eq!(load_from(&path).unwrap().stats, state.stats);
     }
 }
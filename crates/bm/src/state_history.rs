@@ -0,0 +1,316 @@
+//! Member state history for post-mortems.
+//!
+//! Every change to `state.json` that starts or ends a member's run appends
+//! an entry to `~/.botminter/state-history.jsonl`, and the daemon appends a
+//! snapshot of its team's live members every few minutes. Each entry
+//! carries the team's running members at that moment, so `bm state
+//! history` can tell when a member started, crashed, or was stopped, and
+//! when a crashed member was last seen alive.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::procutil;
+use crate::state::{self, MemberRuntime, RuntimeState};
+
+const HISTORY_FILE: &str = "state-history.jsonl";
+
+/// Once the history file grows past this, its older half is dropped.
+const MAX_BYTES: u64 = 1024 * 1024;
+
+/// How often the daemon records a snapshot of its team's members.
+pub const SNAPSHOT_INTERVAL_SECS: u64 = 300;
+
+/// What an entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Event {
+    Started,
+    /// The run ended on request, or a daemon run exited cleanly.
+    Stopped,
+    /// The run ended on its own, or a daemon run exited with an error.
+    Crashed,
+    /// Periodic record of which members are alive.
+    Snapshot,
+}
+
+impl std::fmt::Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Event::Started => "started",
+            Event::Stopped => "stopped",
+            Event::Crashed => "crashed",
+            Event::Snapshot => "snapshot",
+        })
+    }
+}
+
+/// One line of the history file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// When it happened (RFC 3339).
+    pub at: String,
+    pub event: Event,
+    pub team: String,
+    /// The member whose run started or ended; absent for snapshots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    /// When the run that ended had started (RFC 3339), if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    /// The team's running members afterwards, by name, with their PIDs.
+    #[serde(default)]
+    pub running: BTreeMap<String, u32>,
+}
+
+/// The history file kept next to the state file at `state_path`.
+pub fn history_path(state_path: &Path) -> PathBuf {
+    state_path.with_file_name(HISTORY_FILE)
+}
+
+/// Splits a state key (`team/member`) into its team and member.
+fn split_key(key: &str) -> (&str, &str) {
+    key.split_once('/').unwrap_or(("", key))
+}
+
+/// The team's members in `members`, by name, with their PIDs.
+fn running(members: &HashMap<String, MemberRuntime>, team: &str) -> BTreeMap<String, u32> {
+    members
+        .iter()
+        .filter(|(key, _)| split_key(key).0 == team)
+        .map(|(key, rt)| (split_key(key).1.to_string(), rt.pid))
+        .collect()
+}
+
+/// The runs that started or ended between `before` and `after`.
+pub fn transitions(before: &RuntimeState, after: &RuntimeState, now: DateTime<Utc>) -> Vec<Entry> {
+    let crashes = |state: &RuntimeState, key: &str| state.stats.get(key).map_or(0, |s| s.crashes);
+    let runs = |state: &RuntimeState, key: &str| state.stats.get(key).map_or(0, |s| s.runs);
+    let entry = |event, key: &str, pid, started_at: Option<&str>| {
+        let (team, member) = split_key(key);
+        Entry {
+            at: now.to_rfc3339(),
+            event,
+            team: team.to_string(),
+            member: Some(member.to_string()),
+            pid,
+            started_at: started_at.map(str::to_string),
+            running: running(&after.members, team),
+        }
+    };
+    let ended = |key: &str| {
+        if crashes(after, key) > crashes(before, key) {
+            Event::Crashed
+        } else {
+            Event::Stopped
+        }
+    };
+
+    let mut entries = Vec::new();
+    for (key, rt) in &before.members {
+        if after.members.get(key).is_none_or(|a| a.pid != rt.pid) {
+            entries.push(entry(ended(key), key, Some(rt.pid), Some(&rt.started_at)));
+        }
+    }
+    // Runs recorded without a state entry: the daemon's one-shot launches
+    for key in after.stats.keys() {
+        if !before.members.contains_key(key) && runs(after, key) > runs(before, key) {
+            entries.push(entry(ended(key), key, None, None));
+        }
+    }
+    for (key, rt) in &after.members {
+        if before.members.get(key).is_none_or(|b| b.pid != rt.pid) {
+            entries.push(entry(
+                Event::Started,
+                key,
+                Some(rt.pid),
+                Some(&rt.started_at),
+            ));
+        }
+    }
+    entries
+}
+
+/// A snapshot of `team`'s members in `state` that are still alive.
+pub fn snapshot(state: &RuntimeState, team: &str, now: DateTime<Utc>) -> Entry {
+    let mut alive = running(&state.members, team);
+    alive.retain(|_, pid| procutil::is_alive(*pid));
+    Entry {
+        at: now.to_rfc3339(),
+        event: Event::Snapshot,
+        team: team.to_string(),
+        member: None,
+        pid: None,
+        started_at: None,
+        running: alive,
+    }
+}
+
+/// Appends a snapshot of `team`'s live members to
+/// `~/.botminter/state-history.jsonl`.
+pub fn record_snapshot(team: &str) -> Result<()> {
+    let state = state::load()?;
+    let path = config::config_dir()?.join(HISTORY_FILE);
+    append(&path, &[snapshot(&state, team, Utc::now())])
+}
+
+/// Appends `entries` to the history file at `path`, trimming it when it
+/// has grown too large.
+pub fn append(path: &Path, entries: &[Entry]) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry).context("Failed to serialize history")?);
+        lines.push('\n');
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.write_all(lines.as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    if file.metadata().map(|m| m.len()).unwrap_or(0) > MAX_BYTES {
+        trim(path)?;
+    }
+    Ok(())
+}
+
+/// Drops the older half of the history file.
+fn trim(path: &Path) -> Result<()> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut kept = lines[lines.len() / 2..].join("\n");
+    kept.push('\n');
+    let tmp = path.with_extension("jsonl.tmp");
+    fs::write(&tmp, kept).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+/// Reads the history file at `path`, oldest first, skipping lines that
+/// don't parse. Missing means empty.
+pub fn load_from(path: &Path) -> Result<Vec<Entry>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Reads `~/.botminter/state-history.jsonl`.
+pub fn load() -> Result<Vec<Entry>> {
+    load_from(&config::config_dir()?.join(HISTORY_FILE))
+}
+
+/// When `member` of `team` was last seen alive before `at`: the latest
+/// earlier entry listing it as running.
+pub fn last_seen<'a>(entries: &'a [Entry], team: &str, member: &str, at: &str) -> Option<&'a str> {
+    let at = DateTime::parse_from_rfc3339(at).ok()?;
+    entries
+        .iter()
+        .filter(|e| e.team == team && e.running.contains_key(member))
+        .filter(|e| DateTime::parse_from_rfc3339(&e.at).is_ok_and(|t| t < at))
+        .map(|e| e.at.as_str())
+        .next_back()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::RunOutcome;
+
+    fn runtime(pid: u32) -> MemberRuntime {
+        MemberRuntime {
+            pid,
+            started_at: "2026-02-20T10:00:00Z".to_string(),
+            workspace: PathBuf::from("/tmp/ws"),
+            env: Default::default(),
+            health: None,
+        }
+    }
+
+    #[test]
+    fn transitions_cover_starts_stops_and_crashes() {
+        let now = DateTime::parse_from_rfc3339("2026-02-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut before = RuntimeState::default();
+        before.members.insert("t/arch-01".to_string(), runtime(10));
+        before.members.insert("t/dev-01".to_string(), runtime(11));
+
+        let mut after = before.clone();
+        after.end_run("t/arch-01", RunOutcome::Crashed, now);
+        after.end_run("t/dev-01", RunOutcome::Stopped, now);
+        after.members.insert("t/qe-01".to_string(), runtime(12));
+        after.record_run("t/po-01", "2026-02-20T11:00:00Z", now, RunOutcome::Stopped);
+
+        let mut got: Vec<(String, Event, Option<u32>)> = transitions(&before, &after, now)
+            .into_iter()
+            .map(|e| (e.member.unwrap(), e.event, e.pid))
+            .collect();
+        got.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            got,
+            vec![
+                ("arch-01".to_string(), Event::Crashed, Some(10)),
+                ("dev-01".to_string(), Event::Stopped, Some(11)),
+                ("po-01".to_string(), Event::Stopped, None),
+                ("qe-01".to_string(), Event::Started, Some(12)),
+            ]
+        );
+        let entry = &transitions(&before, &after, now)[0];
+        assert_eq!(entry.running, BTreeMap::from([("qe-01".to_string(), 12)]));
+    }
+
+    #[test]
+    fn append_load_and_last_seen() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = history_path(&tmp.path().join("state.json"));
+        let entry = |at: &str, event, running: &[&str]| Entry {
+            at: at.to_string(),
+            event,
+            team: "t".to_string(),
+            member: None,
+            pid: None,
+            started_at: None,
+            running: running.iter().map(|m| (m.to_string(), 1)).collect(),
+        };
+        append(
+            &path,
+            &[
+                entry("2026-02-20T10:00:00Z", Event::Started, &["arch-01"]),
+                entry("2026-02-20T10:05:00Z", Event::Snapshot, &["arch-01"]),
+                entry("2026-02-20T10:10:00Z", Event::Snapshot, &[]),
+            ],
+        )
+        .unwrap();
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "not json\n").unwrap();
+
+        let entries = load_from(&path).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            last_seen(&entries, "t", "arch-01", "2026-02-20T10:12:00Z"),
+            Some("2026-02-20T10:05:00Z")
+        );
+        assert_eq!(
+            last_seen(&entries, "t", "arch-01", "2026-02-20T10:00:00Z"),
+            None
+        );
+    }
+}
@@ -0,0 +1,486 @@
+//! Where bm keeps its runtime records.
+//!
+//! Runtime state (`state.json`), each daemon's config, poll state, and run
+//! history live either in JSON files under `~/.botminter` (the
+//! default) or, with `state_backend: sqlite` in config.yml, in one SQLite
+//! database at `~/.botminter/bm.db`. The database gives every update a
+//! transaction, so the daemon and the CLI can write at once, and keeps run
+//! history in a table that can be queried with `sqlite3`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::filelock::{FileLock, LOCK_TIMEOUT};
+use crate::run_history::{self, RunRecord, MAX_RUNS};
+
+/// File name of the SQLite store under `~/.botminter`.
+pub const DB_FILE: &str = "bm.db";
+
+/// Key of the runtime state document.
+pub const STATE_KEY: &str = "state";
+
+/// Key of a running daemon's config document: its start flags and PID.
+pub fn daemon_key(team_name: &str) -> String {
+    format!("daemon-{}", team_name)
+}
+
+/// Key of a daemon's poll state document.
+pub fn poll_key(team_name: &str) -> String {
+    format!("daemon-{}-poll", team_name)
+}
+
+/// Which store holds the runtime records (`state_backend` in config.yml).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// One JSON file per document under `~/.botminter`.
+    #[default]
+    File,
+    /// A single SQLite database, `~/.botminter/bm.db`.
+    Sqlite,
+}
+
+/// Storage for runtime records. Documents are JSON strings under keys named
+/// after the file that holds them in the file backend, less `.json`.
+pub trait Store {
+    /// Where the document under `key` lives, for messages.
+    fn location(&self, key: &str) -> PathBuf;
+
+    /// Reads the document under `key`, if there is one.
+    fn read(&self, key: &str) -> Result<Option<String>>;
+
+    /// Replaces the document under `key` with what `f` makes of the current
+    /// one. No other bm process writes the document in between, and nothing
+    /// is written if `f` fails.
+    fn update(
+        &self,
+        key: &str,
+        f: &mut dyn FnMut(Option<String>) -> Result<String>,
+    ) -> Result<()>;
+
+    /// Deletes the document under `key`, if there is one.
+    fn remove(&self, key: &str) -> Result<()>;
+
+    /// Appends a run to `team_name`'s history, dropping the oldest runs
+    /// beyond [`MAX_RUNS`].
+    fn append_run(&self, team_name: &str, record: &RunRecord) -> Result<()>;
+
+    /// Reads `team_name`'s run history, oldest first.
+    fn runs(&self, team_name: &str) -> Result<Vec<RunRecord>>;
+
+    /// Deletes `team_name`'s run history.
+    fn remove_runs(&self, team_name: &str) -> Result<()>;
+}
+
+/// Opens the store config.yml selects under `~/.botminter`.
+pub fn open() -> Result<Box<dyn Store>> {
+    let dir = config::config_dir()?;
+    open_in(&dir, configured_backend(&dir))
+}
+
+/// Opens the `backend` store under `dir`.
+pub fn open_in(dir: &Path, backend: Backend) -> Result<Box<dyn Store>> {
+    Ok(match backend {
+        Backend::File => Box::new(FileStore::new(dir)),
+        Backend::Sqlite => Box::new(SqliteStore::open(dir)?),
+    })
+}
+
+/// Reads `state_backend` straight from config.yml: records are touched far
+/// more often than the config is loaded, and by commands that need no
+/// team. A missing or unreadable setting is the file backend;
+/// `config::load` reports invalid values.
+fn configured_backend(dir: &Path) -> Backend {
+    fs::read_to_string(dir.join("config.yml"))
+        .ok()
+        .and_then(|c| serde_yml::from_str::<serde_yml::Value>(&c).ok())
+        .and_then(|v| v.get("state_backend").cloned())
+        .and_then(|v| serde_yml::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+// ── File backend ────────────────────────────────────────────────────
+
+/// One JSON file per document, each written atomically under a
+/// [`FileLock`].
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(dir: &Path) -> FileStore {
+        FileStore {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// The file holding the document under `key`.
+    pub fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    fn runs_path(&self, team_name: &str) -> PathBuf {
+        self.dir.join(format!("daemon-{}-runs.json", team_name))
+    }
+}
+
+impl Store for FileStore {
+    fn location(&self, key: &str) -> PathBuf {
+        self.path(key)
+    }
+
+    fn read(&self, key: &str) -> Result<Option<String>> {
+        let path = self.path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+        fs::read_to_string(&path)
+            .map(Some)
+            .with_context(|| format!("Failed to read {}", path.display()))
+    }
+
+    fn update(
+        &self,
+        key: &str,
+        f: &mut dyn FnMut(Option<String>) -> Result<String>,
+    ) -> Result<()> {
+        let path = self.path(key);
+        let _lock = FileLock::acquire(&path)?;
+        let contents = f(self.read(key)?)?;
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+
+        // Atomic write: temp file → rename
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, contents)
+            .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, &path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        remove_file(&self.path(key))
+    }
+
+    fn append_run(&self, team_name: &str, record: &RunRecord) -> Result<()> {
+        run_history::append(&self.runs_path(team_name), record.clone())
+    }
+
+    fn runs(&self, team_name: &str) -> Result<Vec<RunRecord>> {
+        Ok(run_history::load(&self.runs_path(team_name)))
+    }
+
+    fn remove_runs(&self, team_name: &str) -> Result<()> {
+        remove_file(&self.runs_path(team_name))
+    }
+}
+
+fn remove_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+// ── SQLite backend ──────────────────────────────────────────────────
+
+/// `daemon-<team><suffix>.json` files that stay files with either backend,
+/// so an import leaves them alone.
+const FILE_ONLY_SUFFIXES: &[&str] = &["-queue", "-deliveries", "-schedules"];
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS documents (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS runs (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        team TEXT NOT NULL,
+        events TEXT NOT NULL,
+        started_at TEXT NOT NULL,
+        finished_at TEXT NOT NULL,
+        ok INTEGER NOT NULL,
+        record TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS runs_by_team ON runs (team, id);
+";
+
+/// Every document and run history in one SQLite database.
+#[derive(Debug)]
+pub struct SqliteStore {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Opens `bm.db` under `dir`. A new database imports the JSON records
+    /// already in `dir`, which are left in place.
+    pub fn open(dir: &Path) -> Result<SqliteStore> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(DB_FILE);
+        let created = !path.exists();
+        let conn = Connection::open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        conn.busy_timeout(LOCK_TIMEOUT)?;
+        // WAL lets the CLI read while the daemon writes
+        conn.pragma_update(None, "journal_mode", "wal")?;
+        conn.execute_batch(SCHEMA)
+            .with_context(|| format!("Failed to set up {}", path.display()))?;
+        let store = SqliteStore { path, conn };
+        if created {
+            restrict_permissions(&store.path);
+            store
+                .import(dir)
+                .with_context(|| format!("Failed to import records into {}", store.path.display()))?;
+        }
+        Ok(store)
+    }
+
+    /// Imports `state.json`, daemon configs, poll states, and run histories
+    /// from `dir`.
+    fn import(&self, dir: &Path) -> Result<()> {
+        let files = FileStore::new(dir);
+        self.transaction(|| {
+            // Another bm may have created the database and imported first
+            let count: i64 = self.conn.query_row(
+                "SELECT (SELECT COUNT(*) FROM documents) + (SELECT COUNT(*) FROM runs)",
+                [],
+                |row| row.get(0),
+            )?;
+            if count > 0 {
+                return Ok(());
+            }
+            for entry in fs::read_dir(dir)?.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let Some(key) = name.strip_suffix(".json") else {
+                    continue;
+                };
+                let daemon_doc = key.strip_prefix("daemon-");
+                if let Some(team) = daemon_doc.and_then(|k| k.strip_suffix("-runs")) {
+                    for record in files.runs(team)? {
+                        self.insert_run(team, &record)?;
+                    }
+                } else if key == STATE_KEY
+                    || daemon_doc.is_some_and(|k| !FILE_ONLY_SUFFIXES.iter().any(|s| k.ends_with(s)))
+                {
+                    if let Some(contents) = files.read(key)? {
+                        self.write(key, &contents)?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Runs `f` in a write transaction, taken up front so that concurrent
+    /// writers wait rather than fail.
+    fn transaction<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        self.conn.execute_batch("BEGIN IMMEDIATE")?;
+        match f() {
+            Ok(value) => {
+                self.conn.execute_batch("COMMIT")?;
+                Ok(value)
+            }
+            Err(e) => {
+                let _ = self.conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
+    fn write(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO documents (key, value, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+            params![key, value, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn insert_run(&self, team_name: &str, record: &RunRecord) -> Result<()> {
+        let json = serde_json::to_string(record).context("Failed to serialize run")?;
+        self.conn.execute(
+            "INSERT INTO runs (team, events, started_at, finished_at, ok, record)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                team_name,
+                record.events.join(","),
+                record.started_at.to_rfc3339(),
+                record.finished_at.to_rfc3339(),
+                record.ok(),
+                json
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+impl Store for SqliteStore {
+    fn location(&self, _key: &str) -> PathBuf {
+        self.path.clone()
+    }
+
+    fn read(&self, key: &str) -> Result<Option<String>> {
+        self.conn
+            .query_row("SELECT value FROM documents WHERE key = ?1", [key], |row| row.get(0))
+            .optional()
+            .with_context(|| format!("Failed to read '{}' from {}", key, self.path.display()))
+    }
+
+    fn update(
+        &self,
+        key: &str,
+        f: &mut dyn FnMut(Option<String>) -> Result<String>,
+    ) -> Result<()> {
+        self.transaction(|| {
+            let value = f(self.read(key)?)?;
+            self.write(key, &value)
+        })
+        .with_context(|| format!("Failed to update '{}' in {}", key, self.path.display()))
+    }
+
+    fn remove(&self, key: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM documents WHERE key = ?1", [key])?;
+        Ok(())
+    }
+
+    fn append_run(&self, team_name: &str, record: &RunRecord) -> Result<()> {
+        self.transaction(|| {
+            self.insert_run(team_name, record)?;
+            self.conn.execute(
+                "DELETE FROM runs WHERE team = ?1 AND id NOT IN
+                 (SELECT id FROM runs WHERE team = ?1 ORDER BY id DESC LIMIT ?2)",
+                params![team_name, MAX_RUNS as i64],
+            )?;
+            Ok(())
+        })
+        .with_context(|| format!("Failed to record run in {}", self.path.display()))
+    }
+
+    fn runs(&self, team_name: &str) -> Result<Vec<RunRecord>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT record FROM runs WHERE team = ?1 ORDER BY id")?;
+        let rows = stmt.query_map([team_name], |row| row.get::<_, String>(0))?;
+        // Like the file backend, skip what no longer parses
+        Ok(rows
+            .flatten()
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect())
+    }
+
+    fn remove_runs(&self, team_name: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM runs WHERE team = ?1", [team_name])?;
+        Ok(())
+    }
+}
+
+/// The database holds member environments and poll ETags; keep it private.
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_history::MemberRun;
+    use chrono::TimeZone;
+
+    fn run(n: i64) -> RunRecord {
+        let at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap() + chrono::Duration::minutes(n);
+        RunRecord {
+            events: vec!["issues".to_string()],
+            started_at: at,
+            finished_at: at + chrono::Duration::seconds(30),
+            members: vec![MemberRun {
+                name: "dev-01".to_string(),
+                status: "exit status: 0".to_string(),
+                ok: true,
+            }],
+            errors: Vec::new(),
+            interrupted: false,
+        }
+    }
+
+    #[test]
+    fn backends_store_documents_and_runs_alike() {
+        for backend in [Backend::File, Backend::Sqlite] {
+            let tmp = tempfile::tempdir().unwrap();
+            let store = open_in(tmp.path(), backend).unwrap();
+
+            assert_eq!(store.read("state").unwrap(), None);
+            store.update("state", &mut |cur| Ok(format!("{}+a", cur.unwrap_or_default()))).unwrap();
+            store.update("state", &mut |cur| Ok(format!("{}+b", cur.unwrap_or_default()))).unwrap();
+            assert_eq!(store.read("state").unwrap().as_deref(), Some("+a+b"), "{:?}", backend);
+
+            let failed = store.update("state", &mut |_| anyhow::bail!("boom"));
+            assert!(failed.is_err());
+            assert_eq!(store.read("state").unwrap().as_deref(), Some("+a+b"), "{:?}", backend);
+
+            store.remove("state").unwrap();
+            store.remove("state").unwrap();
+            assert_eq!(store.read("state").unwrap(), None);
+
+            for n in 0..MAX_RUNS as i64 + 2 {
+                store.append_run("alpha", &run(n)).unwrap();
+            }
+            store.append_run("beta", &run(0)).unwrap();
+            let runs = store.runs("alpha").unwrap();
+            assert_eq!(runs.len(), MAX_RUNS, "{:?}", backend);
+            assert_eq!(runs[0], run(2));
+            store.remove_runs("alpha").unwrap();
+            assert!(store.runs("alpha").unwrap().is_empty());
+            assert_eq!(store.runs("beta").unwrap(), vec![run(0)]);
+        }
+    }
+
+    #[test]
+    fn new_database_imports_json_records() {
+        let tmp = tempfile::tempdir().unwrap();
+        let files = FileStore::new(tmp.path());
+        files.update(STATE_KEY, &mut |_| Ok(r#"{"members":{}}"#.to_string())).unwrap();
+        files.update(&poll_key("alpha"), &mut |_| Ok("{}".to_string())).unwrap();
+        files.update(&daemon_key("alpha"), &mut |_| Ok(r#"{"pid":1}"#.to_string())).unwrap();
+        files.update("daemon-alpha-queue", &mut |_| Ok("{}".to_string())).unwrap();
+        files.append_run("alpha", &run(0)).unwrap();
+        files.append_run("alpha", &run(1)).unwrap();
+
+        let db = SqliteStore::open(tmp.path()).unwrap();
+        assert_eq!(db.read(STATE_KEY).unwrap().as_deref(), Some(r#"{"members":{}}"#));
+        assert_eq!(db.read(&poll_key("alpha")).unwrap().as_deref(), Some("{}"));
+        assert_eq!(db.read(&daemon_key("alpha")).unwrap().as_deref(), Some(r#"{"pid":1}"#));
+        assert_eq!(db.read("daemon-alpha-queue").unwrap(), None);
+        assert_eq!(db.runs("alpha").unwrap(), vec![run(0), run(1)]);
+
+        // Only a new database imports
+        files.append_run("alpha", &run(2)).unwrap();
+        drop(db);
+        let db = SqliteStore::open(tmp.path()).unwrap();
+        assert_eq!(db.runs("alpha").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn backend_comes_from_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(configured_backend(tmp.path()), Backend::File);
+        fs::write(tmp.path().join("config.yml"), "workzone: /tmp\nstate_backend: sqlite\n").unwrap();
+        assert_eq!(configured_backend(tmp.path()), Backend::Sqlite);
+    }
+}
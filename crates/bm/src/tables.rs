@@ -0,0 +1,105 @@
+//! Tables as printed to users.
+//!
+//! Every command builds its tables through here so they look the same
+//! everywhere: Unicode box drawing by default, or plain ASCII — no box
+//! drawing, dashes, or check marks — with the global `--plain` flag, for
+//! logs, screen readers, and scripts. Columns wrap to fit the terminal, or
+//! `$COLUMNS` when set.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use comfy_table::{
+    modifiers::UTF8_ROUND_CORNERS,
+    presets::{ASCII_FULL_CONDENSED, UTF8_FULL_CONDENSED},
+    ContentArrangement, Row, Table,
+};
+
+/// Whether output is ASCII-only; set from `--plain`.
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Switches tables (and [`text`]) to ASCII-only output.
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+/// Whether `--plain` is in effect.
+pub fn plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// A table with `header`, styled for the current mode.
+pub fn new<T: Into<Row>>(header: T) -> Table {
+    styled(header, plain())
+}
+
+fn styled<T: Into<Row>>(header: T, plain: bool) -> Table {
+    let mut table = Table::new();
+    if plain {
+        table.load_preset(ASCII_FULL_CONDENSED);
+    } else {
+        table
+            .load_preset(UTF8_FULL_CONDENSED)
+            .apply_modifier(UTF8_ROUND_CORNERS);
+    }
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(header);
+    if let Some(width) = columns() {
+        table.set_width(width);
+    }
+    table
+}
+
+/// Renders `table`, with its cells passed through [`text`].
+pub fn render(table: &Table) -> String {
+    text(&table.to_string())
+}
+
+/// `s` as is, or with `--plain` its symbols spelled in ASCII.
+pub fn text(s: &str) -> String {
+    if plain() {
+        ascii_symbols(s)
+    } else {
+        s.to_string()
+    }
+}
+
+/// Swaps the symbols bm prints for ASCII characters of the same width, so
+/// rendered tables stay aligned. Other text, such as non-English names, is
+/// left alone.
+fn ascii_symbols(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '—' | '–' | '─' => '-',
+            '✔' | '✓' => '*',
+            '✗' | '✘' => 'x',
+            '→' => '>',
+            '…' => '.',
+            '⚠' => '!',
+            c => c,
+        })
+        .collect()
+}
+
+/// Width from `$COLUMNS`, for wrapping when stdout isn't a terminal.
+fn columns() -> Option<u16> {
+    std::env::var("COLUMNS").ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_tables_are_ascii() {
+        let mut table = styled(vec!["Team", "Default"], true);
+        table.add_row(vec!["alpha", "✔"]);
+        table.add_row(vec!["beta", "—"]);
+        let out = ascii_symbols(&table.to_string());
+
+        assert!(out.is_ascii(), "{}", out);
+        assert!(out.contains("| alpha | *       |"), "{}", out);
+        assert!(out.contains("| beta  | -       |"), "{}", out);
+        assert_eq!(ascii_symbols("Zoë → done"), "Zoë > done");
+    }
+}
@@ -0,0 +1,329 @@
+//! Integrity checks for a team repo (`bm teams verify`).
+//!
+//! Where `bm profiles lint` checks a profile, this checks what a team made of
+//! one: the top-level files, finalized member directories, a directory for
+//! every project in the manifest, the formation `bm start` uses by default,
+//! and which profile-extracted files were since edited or deleted. Findings reuse the lint's format and are located relative to the
+//! team repo root.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use crate::formation;
+use crate::members;
+use crate::parse;
+use crate::profile::{self, ProfileManifest};
+use crate::profile_checksums::{FileOrigin, ProfileChecksums};
+use crate::profile_lint::{self, Finding, Findings};
+
+/// Files every team repo carries at its root.
+pub const TOP_LEVEL_FILES: &[&str] = &["botminter.yml", "PROCESS.md", "CLAUDE.md"];
+
+/// Directories every team repo carries at its root.
+pub const TOP_LEVEL_DIRS: &[&str] = &["team", "projects"];
+
+/// Files every hired member's directory must provide.
+pub const MEMBER_FILES: &[&str] = &["botminter.yml", "PROMPT.md", "ralph.yml"];
+
+/// Formation `bm start` picks when none is given.
+const DEFAULT_FORMATION: &str = "local";
+
+/// Verifies the team repo at `team_repo` against `profile_name`. Errs only if
+/// `team_repo` isn't a directory.
+pub fn verify(team_repo: &Path, profile_name: &str) -> Result<Vec<Finding>> {
+    if !team_repo.is_dir() {
+        bail!("{} is not a directory", team_repo.display());
+    }
+    let mut findings = Findings::default();
+
+    for file in TOP_LEVEL_FILES {
+        if !team_repo.join(file).is_file() {
+            findings.error(file, "missing from the team repo root");
+        }
+    }
+    for dir in TOP_LEVEL_DIRS {
+        if !team_repo.join(dir).is_dir() {
+            findings.error(dir, "missing from the team repo root");
+        }
+    }
+
+    let manifest = read_manifest(team_repo, profile_name, &mut findings);
+    verify_members(team_repo, manifest.as_ref(), &mut findings);
+    if let Some(ref manifest) = manifest {
+        verify_projects(team_repo, manifest, &mut findings);
+    }
+    verify_formations(team_repo, &mut findings);
+    verify_checksums(team_repo, &mut findings);
+
+    Ok(findings.0)
+}
+
+/// Reads the team's botminter.yml and checks it belongs to `profile_name` at
+/// a schema this `bm` carries.
+fn read_manifest(
+    team_repo: &Path,
+    profile_name: &str,
+    findings: &mut Findings,
+) -> Option<ProfileManifest> {
+    let path = team_repo.join("botminter.yml");
+    let contents = fs::read_to_string(&path).ok()?;
+    let manifest: ProfileManifest = match parse::yaml(Path::new("botminter.yml"), &contents) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            findings.error("botminter.yml", format!("{:#}", e));
+            return None;
+        }
+    };
+    if manifest.name != profile_name {
+        findings.error(
+            "botminter.yml",
+            format!(
+                "names profile '{}', but the team is registered with '{}'",
+                manifest.name, profile_name
+            ),
+        );
+    } else if let Err(e) = profile::check_schema_version(profile_name, &manifest.schema_version) {
+        findings.error("botminter.yml", format!("{:#}", e));
+    }
+    Some(manifest)
+}
+
+fn verify_members(team_repo: &Path, manifest: Option<&ProfileManifest>, findings: &mut Findings) {
+    let Ok(names) = members::list_names(&team_repo.join("team")) else {
+        return;
+    };
+    for name in names {
+        let rel = format!("team/{}", name);
+        check_member_dir(&team_repo.join(&rel), &rel, findings);
+
+        if let Some(manifest) = manifest {
+            if profile::role_of_member_dir(&manifest.roles, &name).is_none() {
+                findings.warning(&rel, "does not start with a role declared in botminter.yml");
+            }
+        }
+    }
+}
+
+/// Checks that the member directory at `dir` (`rel` in findings) is
+/// finalized: its files are present, `botminter.yml` has a `name:` key, and
+/// `ralph.yml` parses.
+pub(crate) fn check_member_dir(dir: &Path, rel: &str, findings: &mut Findings) {
+    for file in MEMBER_FILES {
+        let file_rel = format!("{}/{}", rel, file);
+        if dir.join(file).is_file() {
+            continue;
+        }
+        if *file == "botminter.yml" && dir.join(".botminter.yml").is_file() {
+            findings.error(
+                &file_rel,
+                "missing; the member was never finalized (.botminter.yml is still the \
+                 profile template). Re-hire the member, or rename it and add a `name:` key",
+            );
+        } else {
+            findings.error(&file_rel, "missing from the member directory");
+        }
+    }
+
+    let manifest_rel = format!("{}/botminter.yml", rel);
+    if let Ok(contents) = fs::read_to_string(dir.join("botminter.yml")) {
+        match parse::yaml::<serde_yml::Value>(Path::new(&manifest_rel), &contents) {
+            Ok(value) if value.get("name").and_then(|n| n.as_str()).is_none() => {
+                findings.error(&manifest_rel, "has no `name:` key")
+            }
+            Ok(_) => {}
+            Err(e) => findings.error(&manifest_rel, format!("{:#}", e)),
+        }
+    }
+    let ralph_rel = format!("{}/ralph.yml", rel);
+    if let Ok(contents) = fs::read_to_string(dir.join("ralph.yml")) {
+        if let Err(e) = serde_yml::from_str::<serde_yml::Value>(&contents) {
+            findings.error(&ralph_rel, format!("not valid YAML: {}", e));
+        }
+    }
+}
+
+fn verify_projects(team_repo: &Path, manifest: &ProfileManifest, findings: &mut Findings) {
+    for project in &manifest.projects {
+        let rel = format!("projects/{}", project.name);
+        if !team_repo.join(&rel).is_dir() {
+            findings.error(
+                &rel,
+                format!(
+                    "missing; project '{}' is listed in botminter.yml. Create {}/knowledge/ \
+                     and {}/invariants/ with a .gitkeep in each",
+                    project.name, rel, rel
+                ),
+            );
+        }
+    }
+
+    let Ok(entries) = fs::read_dir(team_repo.join("projects")) else {
+        return;
+    };
+    let mut extra: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.'))
+        .filter(|name| !manifest.projects.iter().any(|p| &p.name == name))
+        .collect();
+    extra.sort();
+    for name in extra {
+        findings.warning(
+            &format!("projects/{}", name),
+            "not a project listed in botminter.yml",
+        );
+    }
+}
+
+fn verify_formations(team_repo: &Path, findings: &mut Findings) {
+    if !formation::formations_dir(team_repo).is_dir() {
+        return;
+    }
+    let default_dir = format!("formations/{}", DEFAULT_FORMATION);
+    if !team_repo.join(&default_dir).is_dir() {
+        findings.error(
+            &default_dir,
+            format!(
+                "missing; `bm start` uses the '{}' formation when none is given",
+                DEFAULT_FORMATION
+            ),
+        );
+    }
+    profile_lint::lint_formations(team_repo, findings);
+}
+
+/// Warns about every file that drifted from what the profile extracted, at
+/// the team repo root and in each member directory. Extractions without a
+/// checksums file are skipped, as are deletions already reported as errors.
+/// `botminter.yml` is left out: `bm` rewrites it itself (projects, members,
+/// credentials), so an edit there isn't drift.
+fn verify_checksums(team_repo: &Path, findings: &mut Findings) {
+    let mut roots = vec![(team_repo.to_path_buf(), String::new())];
+    if let Ok(names) = members::list_names(&team_repo.join("team")) {
+        for name in names {
+            roots.push((team_repo.join("team").join(&name), format!("team/{}/", name)));
+        }
+    }
+    for (root, prefix) in roots {
+        let Ok(Some(checksums)) = ProfileChecksums::load(&root) else {
+            continue;
+        };
+        for (rel, origin) in checksums.check(&root) {
+            if rel == "botminter.yml" {
+                continue;
+            }
+            let file = format!("{}{}", prefix, rel);
+            let reported = findings
+                .0
+                .iter()
+                .any(|f| file == f.file || file.starts_with(&format!("{}/", f.file)));
+            let what = match origin {
+                FileOrigin::Pristine => continue,
+                FileOrigin::Modified => "edited",
+                FileOrigin::Missing if reported => continue,
+                FileOrigin::Missing => "deleted",
+            };
+            findings.warning(
+                &file,
+                format!(
+                    "{} since it was extracted from the {} profile (version {})",
+                    what, checksums.profile, checksums.version
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::profile_lint::Severity;
+
+    fn scaffold(dir: &Path) {
+        profile::extract_profile_to("scrum-compact", dir).unwrap();
+        fs::create_dir_all(dir.join("team")).unwrap();
+        fs::create_dir_all(dir.join("projects")).unwrap();
+        let member = dir.join("team/superman-alice");
+        fs::create_dir_all(&member).unwrap();
+        profile::extract_member_to("scrum-compact", "superman", &member).unwrap();
+        crate::commands::init::finalize_member_manifest(&member, "alice").unwrap();
+    }
+
+    #[test]
+    fn freshly_scaffolded_repo_verifies_clean() {
+        let tmp = tempfile::tempdir().unwrap();
+        scaffold(tmp.path());
+        let findings = verify(tmp.path(), "scrum-compact").unwrap();
+        assert!(findings.is_empty(), "{:#?}", findings);
+    }
+
+    #[test]
+    fn verify_reports_broken_repos() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        scaffold(dir);
+        fs::remove_file(dir.join("PROCESS.md")).unwrap();
+        let manifest = fs::read_to_string(dir.join("botminter.yml")).unwrap();
+        fs::write(
+            dir.join("botminter.yml"),
+            format!(
+                "{}projects:\n  - name: api\n    fork_url: https://github.com/o/api\n",
+                manifest
+            ),
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("projects/web")).unwrap();
+
+        let raw = dir.join("team/superman-bob");
+        fs::create_dir_all(&raw).unwrap();
+        profile::extract_member_to("scrum-compact", "superman", &raw).unwrap();
+        fs::remove_file(raw.join("PROMPT.md")).unwrap();
+        fs::write(raw.join("ralph.yml"), "a: [").unwrap();
+        fs::create_dir_all(dir.join("team/intern-carol")).unwrap();
+        fs::remove_dir_all(dir.join("formations/local")).unwrap();
+        fs::write(dir.join("CLAUDE.md"), "edited").unwrap();
+        fs::remove_file(dir.join("team/superman-alice/CLAUDE.md")).unwrap();
+
+        let findings = verify(dir, "scrum-compact").unwrap();
+        let messages: Vec<String> = findings
+            .iter()
+            .map(|f| format!("{:?} {}: {}", f.severity, f.file, f.message))
+            .collect();
+        let expected = [
+            "Error PROCESS.md: missing from the team repo root",
+            "Error team/intern-carol/botminter.yml: missing from the member directory",
+            "Error team/intern-carol/PROMPT.md: missing from the member directory",
+            "Error team/intern-carol/ralph.yml: missing from the member directory",
+            "Warning team/intern-carol: does not start with a role",
+            "Error team/superman-bob/botminter.yml: missing; the member was never finalized",
+            "Error team/superman-bob/PROMPT.md: missing from the member directory",
+            "Error team/superman-bob/ralph.yml: not valid YAML",
+            "Error projects/api: missing; project 'api' is listed",
+            "Warning projects/web: not a project listed in botminter.yml",
+            "Error formations/local: missing; `bm start` uses the 'local' formation",
+            "Warning CLAUDE.md: edited since it was extracted from the scrum-compact profile",
+            "Warning team/superman-alice/CLAUDE.md: deleted since it was extracted",
+            "Warning team/superman-bob/ralph.yml: edited since it was extracted",
+        ];
+        for want in expected {
+            assert!(
+                messages.iter().any(|m| m.starts_with(want)),
+                "missing '{}' in {:#?}",
+                want,
+                messages
+            );
+        }
+        assert_eq!(messages.len(), expected.len(), "{:#?}", messages);
+        assert!(findings.iter().any(|f| f.severity == Severity::Error));
+
+        let findings = verify(dir, "scrum").unwrap();
+        assert!(findings
+            .iter()
+            .any(|f| f.file == "botminter.yml" && f.message.contains("registered with 'scrum'")));
+        assert!(verify(&dir.join("nope"), "scrum").is_err());
+    }
+}
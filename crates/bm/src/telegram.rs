@@ -0,0 +1,309 @@
+//! Telegram Bot API calls: `bm init` verifies a bot token and finds the chat
+//! members should talk to, and `bm chatops` reads commands and replies.
+//!
+//! Requests go through `curl`, with the URL passed on stdin so the token
+//! never shows up in the process list.
+
+use std::io::Write as _;
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+const API_BASE: &str = "https://api.telegram.org";
+
+/// The bot a token belongs to (`getMe`).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Bot {
+    pub id: i64,
+    pub first_name: String,
+    #[serde(default)]
+    pub username: String,
+}
+
+/// A chat the bot has seen a message in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chat {
+    pub id: i64,
+    /// `private`, `group`, `supergroup`, or `channel`.
+    pub kind: String,
+    /// Person or group name, for picking the right chat.
+    pub label: String,
+}
+
+/// A text message the bot received.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub chat_id: i64,
+    /// Telegram user id of the sender.
+    pub from_id: i64,
+    /// `@username`, or the sender's first name if they have none.
+    pub from: String,
+    pub text: String,
+}
+
+/// Messages read by [`get_messages`], and the offset that acknowledges them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Updates {
+    pub messages: Vec<Message>,
+    pub next_offset: i64,
+}
+
+/// Longest message Telegram accepts, in characters.
+const MAX_MESSAGE_CHARS: usize = 4096;
+
+/// Calls a Bot API method and returns its `result`.
+fn call(token: &str, method: &str, max_time_secs: u64) -> Result<Value> {
+    if which::which("curl").is_err() {
+        bail!("curl not found on PATH; it is needed to reach the Telegram Bot API.");
+    }
+    let mut child = Command::new("curl")
+        .args(["-sS", "--max-time", &max_time_secs.to_string(), "-K", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "url = \"{}/bot{}/{}\"", API_BASE, token, method)?;
+    }
+    let output = child.wait_with_output().context("Failed to run curl")?;
+    if output.stdout.is_empty() {
+        bail!(
+            "Could not reach the Telegram Bot API: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    parse_response(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Unwraps a Bot API response, turning `"ok": false` into an actionable error.
+fn parse_response(body: &str) -> Result<Value> {
+    let response: Value =
+        serde_json::from_str(body).context("Telegram returned a response that is not JSON")?;
+    if response["ok"].as_bool() == Some(true) {
+        return Ok(response["result"].clone());
+    }
+    let description = response["description"].as_str().unwrap_or("unknown error");
+    match response["error_code"].as_i64() {
+        Some(401) | Some(404) => bail!(
+            "Telegram rejected the bot token ({}). Copy it again from @BotFather.",
+            description
+        ),
+        Some(409) if !description.contains("webhook") => bail!(
+            "Another process is reading this bot's updates ({}). \
+             Telegram allows one reader per bot token: stop the other reader or use a separate bot.",
+            description
+        ),
+        Some(409) => bail!(
+            "The bot has a webhook set, so its updates can't be read ({}). \
+             Enter the chat id manually, or remove the webhook with the deleteWebhook method.",
+            description
+        ),
+        code => bail!(
+            "Telegram API error{}: {}",
+            code.map(|c| format!(" {}", c)).unwrap_or_default(),
+            description
+        ),
+    }
+}
+
+/// Verifies `token` and returns the bot it belongs to.
+pub fn get_me(token: &str) -> Result<Bot> {
+    let result = call(token, "getMe", 15)?;
+    serde_json::from_value(result).context("Unexpected getMe response from Telegram")
+}
+
+/// Waits up to `wait_secs` for messages to the bot and returns the chats
+/// they came from. Updates read here are acknowledged, so members don't
+/// pick up the discovery message later.
+pub fn discover_chats(token: &str, wait_secs: u64) -> Result<Vec<Chat>> {
+    let method = format!("getUpdates?timeout={}", wait_secs);
+    let updates = call(token, &method, wait_secs + 10)?;
+    let updates = updates.as_array().cloned().unwrap_or_default();
+
+    if let Some(last) = updates.iter().filter_map(|u| u["update_id"].as_i64()).max() {
+        let _ = call(token, &format!("getUpdates?offset={}&timeout=0", last + 1), 15);
+    }
+    Ok(chats_in(&updates))
+}
+
+/// Acknowledges every update waiting for the bot without reading it, and
+/// returns the offset for [`get_messages`].
+pub fn skip_pending(token: &str) -> Result<i64> {
+    let updates = call(token, "getUpdates?offset=-1&timeout=0", 15)?;
+    let last = updates
+        .as_array()
+        .and_then(|u| u.iter().filter_map(|u| u["update_id"].as_i64()).max());
+    Ok(last.map_or(0, |id| id + 1))
+}
+
+/// Waits up to `wait_secs` for updates from `offset` on and returns the text
+/// messages among them. Pass `next_offset` to the next call to acknowledge them.
+pub fn get_messages(token: &str, offset: i64, wait_secs: u64) -> Result<Updates> {
+    let method = format!("getUpdates?offset={}&timeout={}", offset, wait_secs);
+    let updates = call(token, &method, wait_secs + 10)?;
+    let updates = updates.as_array().cloned().unwrap_or_default();
+    let next_offset = updates
+        .iter()
+        .filter_map(|u| u["update_id"].as_i64())
+        .max()
+        .map_or(offset, |id| id + 1);
+    Ok(Updates {
+        messages: messages_in(&updates),
+        next_offset,
+    })
+}
+
+/// Sends `text` to `chat_id`, cut to Telegram's length limit.
+pub fn send_message(token: &str, chat_id: i64, text: &str) -> Result<()> {
+    let text: String = text.chars().take(MAX_MESSAGE_CHARS).collect();
+    let method = format!("sendMessage?chat_id={}&text={}", chat_id, percent_encode(&text));
+    call(token, &method, 15).map(|_| ())
+}
+
+/// Percent-encodes `s` for a URL query value.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Text messages in a list of updates, oldest first.
+fn messages_in(updates: &[Value]) -> Vec<Message> {
+    updates
+        .iter()
+        .filter_map(|update| {
+            let message = &update["message"];
+            let text = message["text"].as_str()?;
+            let from = &message["from"];
+            Some(Message {
+                chat_id: message["chat"]["id"].as_i64()?,
+                from_id: from["id"].as_i64()?,
+                from: match from["username"].as_str() {
+                    Some(user) => format!("@{}", user),
+                    None => from["first_name"].as_str().unwrap_or("someone").to_string(),
+                },
+                text: text.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Distinct chats in a list of updates, most recent first.
+fn chats_in(updates: &[Value]) -> Vec<Chat> {
+    let mut chats: Vec<Chat> = Vec::new();
+    for update in updates.iter().rev() {
+        let chat = ["message", "edited_message", "channel_post", "my_chat_member"]
+            .iter()
+            .find_map(|key| update[*key]["chat"].as_object());
+        let Some(chat) = chat else { continue };
+        let Some(id) = chat.get("id").and_then(Value::as_i64) else {
+            continue;
+        };
+        if chats.iter().any(|c| c.id == id) {
+            continue;
+        }
+        let kind = chat
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or("private")
+            .to_string();
+        let field = |name: &str| chat.get(name).and_then(Value::as_str).unwrap_or("");
+        let label = if kind == "private" {
+            let name = format!("{} {}", field("first_name"), field("last_name"));
+            match field("username") {
+                "" => name.trim().to_string(),
+                user => format!("{} (@{})", name.trim(), user),
+            }
+        } else {
+            format!("{} ({})", field("title"), kind)
+        };
+        chats.push(Chat { id, kind, label });
+    }
+    chats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_response_explains_errors() {
+        let ok = parse_response(r#"{"ok":true,"result":{"id":7,"first_name":"Bot"}}"#).unwrap();
+        assert_eq!(ok["id"], 7);
+
+        let err = parse_response(r#"{"ok":false,"error_code":401,"description":"Unauthorized"}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("@BotFather"), "{}", err);
+        let err = parse_response(
+            r#"{"ok":false,"error_code":409,"description":"Conflict: can't use getUpdates method while webhook is active"}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("deleteWebhook"), "{}", err);
+        let err = parse_response(
+            r#"{"ok":false,"error_code":409,"description":"Conflict: terminated by other getUpdates request"}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("one reader per bot token"), "{}", err);
+        assert!(parse_response("<html>").is_err());
+    }
+
+    #[test]
+    fn chats_in_lists_distinct_chats_newest_first() {
+        let updates: Vec<Value> = serde_json::from_str(
+            r#"[
+              {"update_id": 1, "message": {"chat": {"id": 42, "type": "private",
+                "first_name": "Ada", "last_name": "L", "username": "ada"}, "text": "hi"}},
+              {"update_id": 2, "my_chat_member": {"chat": {"id": -100123,
+                "type": "supergroup", "title": "Team room"}}},
+              {"update_id": 3, "message": {"chat": {"id": 42, "type": "private",
+                "first_name": "Ada"}, "text": "again"}},
+              {"update_id": 4, "callback_query": {"id": "x"}}
+            ]"#,
+        )
+        .unwrap();
+        let chats = chats_in(&updates);
+        let got: Vec<(i64, &str)> = chats.iter().map(|c| (c.id, c.label.as_str())).collect();
+        assert_eq!(got, vec![(42, "Ada"), (-100123, "Team room (supergroup)")]);
+
+        let bot: Bot =
+            serde_json::from_value(serde_json::json!({"id": 7, "first_name": "Bot"})).unwrap();
+        assert_eq!(bot.username, "");
+    }
+
+    #[test]
+    fn messages_in_keeps_text_messages_with_a_sender() {
+        let updates: Vec<Value> = serde_json::from_str(
+            r#"[
+              {"update_id": 1, "message": {"chat": {"id": -100}, "text": "/status",
+                "from": {"id": 42, "first_name": "Ada", "username": "ada"}}},
+              {"update_id": 2, "message": {"chat": {"id": -100}, "sticker": {},
+                "from": {"id": 42, "first_name": "Ada"}}},
+              {"update_id": 3, "edited_message": {"chat": {"id": -100}, "text": "/pause",
+                "from": {"id": 42, "first_name": "Ada"}}},
+              {"update_id": 4, "message": {"chat": {"id": 7}, "text": "/stop dev-01",
+                "from": {"id": 7, "first_name": "Grace"}}}
+            ]"#,
+        )
+        .unwrap();
+        let messages = messages_in(&updates);
+        let got: Vec<(i64, i64, &str, &str)> = messages
+            .iter()
+            .map(|m| (m.chat_id, m.from_id, m.from.as_str(), m.text.as_str()))
+            .collect();
+        assert_eq!(
+            got,
+            vec![(-100, 42, "@ada", "/status"), (7, 7, "Grace", "/stop dev-01")]
+        );
+        assert_eq!(percent_encode("a b/ç&"), "a%20b%2F%C3%A7%26");
+    }
+}
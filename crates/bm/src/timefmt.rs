@@ -0,0 +1,127 @@
+//! Timestamps and durations as shown to users.
+//!
+//! Every command renders times through here so they read the same
+//! everywhere: `2026-02-21 10:30:00 UTC`, or local time when config.yml sets
+//! `timestamps: local`, and durations as `42s`, `5m 03s`, `2h 07m`, `3d 04h`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Time zone timestamps are shown in (`timestamps:` in config.yml).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Zone {
+    /// `2026-02-21 10:30:00 UTC`.
+    #[default]
+    Utc,
+    /// The machine's time zone, with its offset: `2026-02-21 12:30:00 +02:00`.
+    Local,
+}
+
+/// Whether timestamps render in local time; set from config.yml on load.
+static LOCAL: AtomicBool = AtomicBool::new(false);
+
+/// Sets the zone [`timestamp`] renders in.
+pub fn set_zone(zone: Zone) {
+    LOCAL.store(zone == Zone::Local, Ordering::Relaxed);
+}
+
+/// The zone [`timestamp`] renders in.
+pub fn zone() -> Zone {
+    if LOCAL.load(Ordering::Relaxed) {
+        Zone::Local
+    } else {
+        Zone::Utc
+    }
+}
+
+/// Formats a duration compactly: `42s`, `5m 03s`, `2h 07m`, `3d 04h`.
+pub fn duration(secs: u64) -> String {
+    let (d, h, m, s) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    if d > 0 {
+        format!("{}d {:02}h", d, h)
+    } else if h > 0 {
+        format!("{}h {:02}m", h, m)
+    } else if m > 0 {
+        format!("{}m {:02}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
+/// `5m 03s ago`.
+pub fn ago(secs: u64) -> String {
+    format!("{} ago", duration(secs))
+}
+
+/// Whole seconds from `then` to `now`; zero if `then` is later.
+pub fn elapsed(then: DateTime<Utc>, now: DateTime<Utc>) -> u64 {
+    (now - then).num_seconds().max(0) as u64
+}
+
+/// Parses a stored RFC 3339 timestamp.
+pub fn parse(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Renders `t` in the configured zone.
+pub fn timestamp(t: DateTime<Utc>) -> String {
+    timestamp_in(t, zone())
+}
+
+/// Renders `t` in `zone`, to the second.
+pub fn timestamp_in(t: DateTime<Utc>, zone: Zone) -> String {
+    match zone {
+        Zone::Utc => t.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        Zone::Local => t
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S %:z")
+            .to_string(),
+    }
+}
+
+/// Renders a stored RFC 3339 timestamp in the configured zone. Anything that
+/// doesn't parse is shown as is.
+pub fn rfc3339(ts: &str) -> String {
+    parse(ts).map(timestamp).unwrap_or_else(|| ts.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_units() {
+        assert_eq!(duration(42), "42s");
+        assert_eq!(duration(303), "5m 03s");
+        assert_eq!(duration(2 * 3600 + 7 * 60 + 9), "2h 07m");
+        assert_eq!(duration(3 * 86_400 + 4 * 3600), "3d 04h");
+        assert_eq!(ago(0), "0s ago");
+    }
+
+    #[test]
+    fn elapsed_never_goes_negative() {
+        let t = parse("2026-02-21T10:30:00Z").unwrap();
+        assert_eq!(elapsed(t, t + chrono::Duration::seconds(90)), 90);
+        assert_eq!(elapsed(t + chrono::Duration::seconds(90), t), 0);
+    }
+
+    #[test]
+    fn timestamps_normalize_offsets_to_utc() {
+        let t = parse("2026-02-21T12:30:00.123+02:00").unwrap();
+        assert_eq!(timestamp_in(t, Zone::Utc), "2026-02-21 10:30:00 UTC");
+        let local = timestamp_in(t, Zone::Local);
+        let offset = t.with_timezone(&Local).format("%:z").to_string();
+        assert!(local.ends_with(&offset), "{}", local);
+    }
+
+    #[test]
+    fn unparseable_timestamps_pass_through() {
+        assert_eq!(rfc3339("not-a-timestamp"), "not-a-timestamp");
+        assert_eq!(rfc3339(""), "");
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::{self, TeamEntry};
+use crate::timefmt;
+
+/// Warn this many days before a token expires unless config.yml says otherwise.
+pub const DEFAULT_WARN_DAYS: u32 = 14;
+
+/// Re-query GitHub for a token's expiry at most this often.
+const CACHE_TTL_HOURS: i64 = 6;
+
+const CACHE_FILE: &str = "token-expiry.json";
+
+/// Response header GitHub sets on requests made with expiring PATs.
+const EXPIRY_HEADER: &str = "github-authentication-token-expiration";
+
+/// What GitHub reports about a token's lifetime.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TokenExpiry {
+    /// The token has no expiry date.
+    Never,
+    /// The token expires at the given time.
+    At { expires_at: DateTime<Utc> },
+}
+
+/// Cached expiry lookup, keyed by team name.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CacheEntry {
+    /// SHA-256 of the token, so a rotated token invalidates the entry.
+    token_sha256: String,
+    checked_at: DateTime<Utc>,
+    expiry: TokenExpiry,
+}
+
+/// How close a token is to expiring, least urgent first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    /// Outside the warning window, or never expires.
+    Valid,
+    /// Expires within the warning window.
+    Expiring,
+    /// Already expired.
+    Expired,
+}
+
+/// A warning about a token that expires soon or has expired.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    pub stage: Stage,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Returns a warning for the team's `gh_token` if it expires within the
+/// configured window (or has already expired). Lookup failures are treated as
+/// "nothing to report" so a flaky network never blocks a command.
+pub fn check_team(team: &TeamEntry, warn_days: Option<u32>) -> Option<Warning> {
+    team_warning(team, warn_days).ok().flatten()
+}
+
+/// Like [`check_team`], but tells a token that's fine (`Ok(None)`) from one
+/// whose expiry couldn't be looked up.
+pub fn team_warning(team: &TeamEntry, warn_days: Option<u32>) -> Result<Option<Warning>> {
+    let Some(token) = team.credentials.gh_token.as_deref() else {
+        return Ok(None);
+    };
+    let expiry = cached_expiry(&team.name, token)?;
+    Ok(expiry_warning(
+        &team.name,
+        &expiry,
+        Utc::now(),
+        warn_days.unwrap_or(DEFAULT_WARN_DAYS),
+    ))
+}
+
+/// Where `expiry` stands at `now` with a `warn_days` warning window.
+pub fn stage(expiry: &TokenExpiry, now: DateTime<Utc>, warn_days: u32) -> Stage {
+    let TokenExpiry::At { expires_at } = expiry else {
+        return Stage::Valid;
+    };
+    let remaining = *expires_at - now;
+    if remaining.num_seconds() <= 0 {
+        Stage::Expired
+    } else if remaining.num_days() < i64::from(warn_days) {
+        Stage::Expiring
+    } else {
+        Stage::Valid
+    }
+}
+
+/// Formats a warning if `expiry` falls within `warn_days` of `now`.
+pub fn expiry_warning(
+    team_name: &str,
+    expiry: &TokenExpiry,
+    now: DateTime<Utc>,
+    warn_days: u32,
+) -> Option<Warning> {
+    let TokenExpiry::At { expires_at } = *expiry else {
+        return None;
+    };
+    let stage = stage(expiry, now, warn_days);
+    let message = match stage {
+        Stage::Valid => return None,
+        Stage::Expired => format!(
+            "GitHub token for team '{}' expired on {}. Members cannot reach GitHub until \
+             it is replaced in ~/.botminter/config.yml.",
+            team_name,
+            expires_at.format("%Y-%m-%d")
+        ),
+        Stage::Expiring => {
+            let when = match (expires_at - now).num_days() {
+                0 => "in less than a day".to_string(),
+                1 => "in 1 day".to_string(),
+                n => format!("in {} days", n),
+            };
+            format!(
+                "GitHub token for team '{}' expires {} ({}). Rotate it in ~/.botminter/config.yml.",
+                team_name,
+                when,
+                timefmt::timestamp(expires_at)
+            )
+        }
+    };
+    Some(Warning { stage, message })
+}
+
+/// Decides when the daemon notifies about a token: once when it enters the
+/// warning window and once when it expires, not on every check. A token
+/// back outside the window, i.e. a rotated one, starts over.
+#[derive(Debug, Default)]
+pub struct Notices {
+    notified: Option<Stage>,
+}
+
+impl Notices {
+    /// Records the token's current stage. Returns true if it crossed a
+    /// threshold since the last notice, so a notification is due.
+    pub fn crossed(&mut self, stage: Stage) -> bool {
+        if stage == Stage::Valid {
+            self.notified = None;
+            return false;
+        }
+        if self.notified.is_some_and(|notified| notified >= stage) {
+            return false;
+        }
+        self.notified = Some(stage);
+        true
+    }
+}
+
+/// Looks up the token's expiry, reusing a recent cached answer when possible.
+fn cached_expiry(team_name: &str, token: &str) -> Result<TokenExpiry> {
+    let path = cache_path()?;
+    let digest = hex::encode(Sha256::digest(token.as_bytes()));
+    let mut cache = load_cache(&path);
+
+    if let Some(entry) = cache.get(team_name) {
+        let fresh = Utc::now() - entry.checked_at < chrono::Duration::hours(CACHE_TTL_HOURS);
+        if fresh && entry.token_sha256 == digest {
+            return Ok(entry.expiry.clone());
+        }
+    }
+
+    let expiry = query_expiry(token)?;
+    cache.insert(
+        team_name.to_string(),
+        CacheEntry {
+            token_sha256: digest,
+            checked_at: Utc::now(),
+            expiry: expiry.clone(),
+        },
+    );
+    if let Ok(contents) = serde_json::to_string_pretty(&cache) {
+        let _ = fs::write(&path, contents);
+    }
+    Ok(expiry)
+}
+
+/// Asks GitHub for the token's expiry via the (rate-limit free) `/rate_limit` endpoint.
+pub fn query_expiry(token: &str) -> Result<TokenExpiry> {
+    let output = Command::new("gh")
+        .args(["api", "--include", "/rate_limit"])
+        .env("GH_TOKEN", token)
+        .output()
+        .context("Failed to run gh api")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("gh api /rate_limit failed: {}", stderr.trim());
+    }
+    parse_expiry_headers(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Extracts the expiry from `gh api --include` output (headers, blank line, body).
+pub fn parse_expiry_headers(output: &str) -> Result<TokenExpiry> {
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case(EXPIRY_HEADER) {
+                return parse_expiry_value(value.trim())
+                    .map(|expires_at| TokenExpiry::At { expires_at });
+            }
+        }
+    }
+    Ok(TokenExpiry::Never)
+}
+
+/// GitHub formats the header as `2026-04-01 12:00:00 UTC` (sometimes `+0000`).
+fn parse_expiry_value(value: &str) -> Result<DateTime<Utc>> {
+    if let Some(naive) = value.strip_suffix(" UTC") {
+        let dt = NaiveDateTime::parse_from_str(naive, "%Y-%m-%d %H:%M:%S")
+            .with_context(|| format!("Unrecognized token expiry '{}'", value))?;
+        return Ok(dt.and_utc());
+    }
+    DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S %z")
+        .map(|dt| dt.with_timezone(&Utc))
+        .with_context(|| format!("Unrecognized token expiry '{}'", value))
+}
+
+fn cache_path() -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(CACHE_FILE))
+}
+
+fn load_cache(path: &Path) -> HashMap<String, CacheEntry> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_expiry_header() {
+        let output = "HTTP/2.0 200 OK\r\nContent-Type: application/json\r\n\
+                      Github-Authentication-Token-Expiration: 2026-04-01 12:00:00 UTC\r\n\r\n{}";
+        assert_eq!(
+            parse_expiry_headers(output).unwrap(),
+            TokenExpiry::At { expires_at: at(2026, 4, 1) }
+        );
+    }
+
+    #[test]
+    fn parses_offset_expiry_header() {
+        let output = "github-authentication-token-expiration: 2026-04-01 14:00:00 +0200\n\n{}";
+        assert_eq!(
+            parse_expiry_headers(output).unwrap(),
+            TokenExpiry::At { expires_at: at(2026, 4, 1) }
+        );
+    }
+
+    #[test]
+    fn missing_header_means_no_expiry() {
+        let output = "HTTP/2.0 200 OK\nContent-Type: application/json\n\n{\"x\": \"github-authentication-token-expiration: 1\"}";
+        assert_eq!(parse_expiry_headers(output).unwrap(), TokenExpiry::Never);
+    }
+
+    #[test]
+    fn warning_inside_window() {
+        let expiry = TokenExpiry::At { expires_at: at(2026, 4, 10) };
+        let warning = expiry_warning("t", &expiry, at(2026, 4, 1), 14).unwrap();
+        assert_eq!(warning.stage, Stage::Expiring);
+        assert!(warning.message.contains("expires in 9 days"), "{}", warning);
+    }
+
+    #[test]
+    fn no_warning_outside_window() {
+        let expiry = TokenExpiry::At { expires_at: at(2026, 6, 1) };
+        assert!(expiry_warning("t", &expiry, at(2026, 4, 1), 14).is_none());
+        assert!(expiry_warning("t", &TokenExpiry::Never, at(2026, 4, 1), 14).is_none());
+    }
+
+    #[test]
+    fn warning_when_expired() {
+        let expiry = TokenExpiry::At { expires_at: at(2026, 3, 1) };
+        let warning = expiry_warning("t", &expiry, at(2026, 4, 1), 14).unwrap();
+        assert_eq!(warning.stage, Stage::Expired);
+        assert!(warning.message.contains("expired on 2026-03-01"), "{}", warning);
+    }
+
+    #[test]
+    fn notices_fire_once_per_threshold() {
+        let expiry = TokenExpiry::At { expires_at: at(2026, 4, 10) };
+        let mut notices = Notices::default();
+        let mut fired = Vec::new();
+        // Daily checks from well before the window until after expiry
+        for day in 1..=40 {
+            let now = at(2026, 3, 1) + chrono::Duration::days(day);
+            let stage = stage(&expiry, now, 14);
+            if notices.crossed(stage) {
+                fired.push((now.date_naive().to_string(), stage));
+            }
+        }
+        assert_eq!(
+            fired,
+            vec![
+                ("2026-03-28".to_string(), Stage::Expiring),
+                ("2026-04-10".to_string(), Stage::Expired),
+            ]
+        );
+
+        // A rotated token that later nears expiry is notified about again
+        assert!(!notices.crossed(Stage::Valid));
+        assert!(notices.crossed(Stage::Expiring));
+    }
+}
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 use std::fs;
-use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::config;
+use crate::parse;
+use crate::procutil;
+
 /// Topology file describing where team members are running.
 /// Lives at `{workzone}/{team_name}/topology.json`.
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -38,6 +42,232 @@ pub enum Endpoint {
     },
 }
 
+/// What checking an endpoint found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Probe {
+    /// Still there, with its current status (e.g. "running", "pending").
+    Alive(String),
+    /// The process exited, or the pod finished or no longer exists.
+    Gone,
+    /// Couldn't tell (e.g. cluster unreachable); the entry is left alone.
+    Unknown(String),
+}
+
+/// How [`refresh`] changed one member's entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Status confirmed as recorded.
+    Unchanged,
+    /// Status changed from the recorded one.
+    Updated { from: String },
+    /// Found running but missing from the topology.
+    Added,
+    /// Endpoint is gone; entry dropped.
+    Removed,
+    /// Endpoint couldn't be checked; entry kept as is.
+    Unverified(String),
+}
+
+/// Checks where an endpoint actually stands.
+pub fn probe(endpoint: &Endpoint) -> Probe {
+    match endpoint {
+        Endpoint::Local { pid, .. } => {
+            if procutil::is_alive(*pid) {
+                Probe::Alive("running".to_string())
+            } else {
+                Probe::Gone
+            }
+        }
+        Endpoint::K8s {
+            namespace,
+            pod,
+            context,
+            ..
+        } => probe_pod(namespace, pod, context),
+    }
+}
+
+/// Asks kubectl for the pod's phase.
+fn probe_pod(namespace: &str, pod: &str, context: &str) -> Probe {
+    let output = Command::new("kubectl")
+        .args(["--context", context, "-n", namespace, "get", "pod", pod])
+        .args(["-o", "jsonpath={.status.phase}"])
+        .output();
+    let output = match output {
+        Ok(o) => o,
+        Err(e) => return Probe::Unknown(format!("failed to run kubectl: {}", e)),
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("NotFound") {
+            return Probe::Gone;
+        }
+        return Probe::Unknown(format!("kubectl failed: {}", stderr.trim()));
+    }
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "Succeeded" | "Failed" => Probe::Gone,
+        "" => Probe::Unknown("kubectl reported no pod phase".to_string()),
+        phase => Probe::Alive(phase.to_lowercase()),
+    }
+}
+
+/// How [`stop_pod`] stopped a member's pod.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PodStop {
+    /// The pod's Deployment was scaled to zero, so it isn't recreated.
+    Scaled(String),
+    /// The pod has no Deployment and was deleted.
+    Deleted,
+    /// The pod no longer exists.
+    AlreadyGone,
+}
+
+/// Stops a member's pod: scales its Deployment to zero when it has one,
+/// otherwise deletes the pod. With `grace` set, waits up to that many
+/// seconds for the pod to terminate; without, deletes it immediately.
+pub fn stop_pod(namespace: &str, pod: &str, context: &str, grace: Option<u64>) -> Result<PodStop> {
+    let kubectl = |args: &[&str]| kubectl(context, namespace, args);
+    let replica_set = match kubectl(&[
+        "get",
+        "pod",
+        pod,
+        "-o",
+        "jsonpath={.metadata.ownerReferences[?(@.kind==\"ReplicaSet\")].name}",
+    ]) {
+        Ok(name) => name,
+        Err(e) if is_not_found(&e) => return Ok(PodStop::AlreadyGone),
+        Err(e) => return Err(e),
+    };
+    let deployment = if replica_set.is_empty() {
+        String::new()
+    } else {
+        kubectl(&[
+            "get",
+            "replicaset",
+            &replica_set,
+            "-o",
+            "jsonpath={.metadata.ownerReferences[?(@.kind==\"Deployment\")].name}",
+        ])?
+    };
+
+    let target = format!("pod/{}", pod);
+    let timeout = grace.map(|secs| format!("--timeout={}s", secs));
+    let result = if !deployment.is_empty() {
+        kubectl(&["scale", "deployment", &deployment, "--replicas=0"])?;
+        match timeout {
+            Some(ref timeout) => kubectl(&["wait", "--for=delete", &target, timeout]),
+            None => kubectl(&["delete", &target, "--grace-period=0", "--force"]),
+        }
+    } else {
+        match timeout {
+            Some(ref timeout) => kubectl(&["delete", &target, timeout]),
+            None => kubectl(&["delete", &target, "--grace-period=0", "--force"]),
+        }
+    };
+    match result {
+        Ok(_) => {}
+        // Gone between the lookup and now, or already deleted by the scale
+        Err(e) if is_not_found(&e) => {}
+        Err(e) => {
+            return Err(e).with_context(|| format!("Pod {} in {} didn't stop", pod, namespace))
+        }
+    }
+
+    Ok(if deployment.is_empty() {
+        PodStop::Deleted
+    } else {
+        PodStop::Scaled(deployment)
+    })
+}
+
+/// Runs kubectl against a member's context and namespace, returning stdout.
+fn kubectl(context: &str, namespace: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new("kubectl")
+        .args(["--context", context, "-n", namespace])
+        .args(args)
+        .output()
+        .context("Failed to run kubectl")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "kubectl {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn is_not_found(e: &anyhow::Error) -> bool {
+    format!("{:#}", e).contains("NotFound")
+}
+
+/// Re-derives member statuses by probing every endpoint, dropping members
+/// whose endpoint is gone. `running` lists members known to be running
+/// locally (from state.json); those missing from the topology are added.
+///
+/// Returns each member's change, sorted by name.
+pub fn refresh(
+    topo: &mut Topology,
+    running: Vec<(String, Endpoint)>,
+    probe: impl Fn(&Endpoint) -> Probe,
+) -> Vec<(String, Change)> {
+    let mut changes = Vec::new();
+    let mut gone = Vec::new();
+
+    for (name, member) in topo.members.iter_mut() {
+        let change = match probe(&member.endpoint) {
+            Probe::Alive(status) if status == member.status => Change::Unchanged,
+            Probe::Alive(status) => {
+                let from = std::mem::replace(&mut member.status, status);
+                Change::Updated { from }
+            }
+            Probe::Gone => {
+                gone.push(name.clone());
+                Change::Removed
+            }
+            Probe::Unknown(reason) => Change::Unverified(reason),
+        };
+        changes.push((name.clone(), change));
+    }
+    for name in gone {
+        topo.members.remove(&name);
+    }
+
+    for (name, endpoint) in running {
+        if topo.members.contains_key(&name) || changes.iter().any(|(n, _)| *n == name) {
+            continue;
+        }
+        topo.members.insert(
+            name.clone(),
+            MemberTopology {
+                status: "running".to_string(),
+                endpoint,
+            },
+        );
+        changes.push((name, Change::Added));
+    }
+
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    changes
+}
+
+/// Carries over the members of `previous` that `current` doesn't place,
+/// marked `stopped`, so members a formation switch left behind stay
+/// visible. Returns their names, sorted.
+pub fn merge(current: &mut Topology, previous: Topology) -> Vec<String> {
+    let mut kept = Vec::new();
+    for (name, mut member) in previous.members {
+        if current.members.contains_key(&name) {
+            continue;
+        }
+        member.status = "stopped".to_string();
+        current.members.insert(name.clone(), member);
+        kept.push(name);
+    }
+    kept.sort();
+    kept
+}
+
 /// Returns the topology file path for a team.
 pub fn topology_path(workzone: &Path, team_name: &str) -> PathBuf {
     workzone.join(team_name).join("topology.json")
@@ -49,8 +279,7 @@ pub fn load(path: &Path) -> Result<Option<Topology>> {
         return Ok(None);
     }
     let contents = fs::read_to_string(path).context("Failed to read topology file")?;
-    let topo: Topology =
-        serde_json::from_str(&contents).context("Failed to parse topology file")?;
+    let topo: Topology = parse::json(path, &contents)?;
     Ok(Some(topo))
 }
 
@@ -68,8 +297,7 @@ pub fn save(path: &Path, topo: &Topology) -> Result<()> {
     fs::write(&tmp_path, contents).context("Failed to write temp topology file")?;
 
     // Set permissions before rename (0600 — contains PIDs, paths)
-    let perms = fs::Permissions::from_mode(0o600);
-    fs::set_permissions(&tmp_path, perms).context("Failed to set topology file permissions")?;
+    config::set_mode(&tmp_path, 0o600).context("Failed to set topology file permissions")?;
 
     fs::rename(&tmp_path, path).context("Failed to rename temp topology file")?;
 
@@ -186,8 +414,11 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[cfg(unix)]
     #[test]
     fn topology_file_has_0600_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
         let tmp = tempfile::tempdir().unwrap();
         let path = tmp.path().join("topology.json");
 
@@ -238,6 +469,80 @@ mod tests {
         assert_eq!(result, PathBuf::from("/home/user/workzone/my-team/topology.json"));
     }
 
+    #[test]
+    fn refresh_updates_removes_and_adds_members() {
+        let mut topo = sample_local_topology();
+        topo.members.insert(
+            "dev-bob".to_string(),
+            MemberTopology {
+                status: "running".to_string(),
+                endpoint: Endpoint::Local {
+                    pid: 222,
+                    workspace: PathBuf::from("/tmp/ws/dev-bob"),
+                },
+            },
+        );
+        let pod = sample_k8s_topology().members.remove("dev-bob").unwrap();
+        topo.members.insert("qe-carol".to_string(), pod);
+        let started = vec![
+            (
+                "dev-dan".to_string(),
+                Endpoint::Local {
+                    pid: 333,
+                    workspace: PathBuf::from("/tmp/ws/dev-dan"),
+                },
+            ),
+            // Stale state entry for a member that just died
+            (
+                "dev-bob".to_string(),
+                Endpoint::Local {
+                    pid: 222,
+                    workspace: PathBuf::from("/tmp/ws/dev-bob"),
+                },
+            ),
+        ];
+
+        let changes = refresh(&mut topo, started, |endpoint| match endpoint {
+            Endpoint::Local { pid: 12345, .. } => Probe::Alive("running".to_string()),
+            Endpoint::Local { .. } => Probe::Gone,
+            Endpoint::K8s { .. } => Probe::Alive("pending".to_string()),
+        });
+
+        assert_eq!(
+            changes,
+            vec![
+                ("architect-alice".to_string(), Change::Unchanged),
+                ("dev-bob".to_string(), Change::Removed),
+                ("dev-dan".to_string(), Change::Added),
+                (
+                    "qe-carol".to_string(),
+                    Change::Updated {
+                        from: "running".to_string()
+                    }
+                ),
+            ]
+        );
+        assert!(!topo.members.contains_key("dev-bob"));
+        assert_eq!(topo.members["qe-carol"].status, "pending");
+        assert_eq!(topo.members["dev-dan"].status, "running");
+    }
+
+    #[test]
+    fn refresh_keeps_unverifiable_members() {
+        let mut topo = sample_k8s_topology();
+        let changes = refresh(&mut topo, Vec::new(), |_| {
+            Probe::Unknown("cluster unreachable".to_string())
+        });
+        assert_eq!(
+            changes,
+            vec![(
+                "dev-bob".to_string(),
+                Change::Unverified("cluster unreachable".to_string())
+            )]
+        );
+        assert_eq!(topo.members["dev-bob"].status, "running");
+    }
+
     #[test]
     fn mixed_endpoint_topology() {
         let tmp = tempfile::tempdir().unwrap();
@@ -286,4 +591,21 @@ mod tests {
             Endpoint::K8s { .. }
         ));
     }
+
+    #[test]
+    fn merge_keeps_members_the_new_formation_left_behind() {
+        let mut current = sample_k8s_topology();
+        let mut previous = sample_local_topology();
+        previous.members.insert(
+            "dev-bob".to_string(),
+            sample_local_topology().members["architect-alice"].clone(),
+        );
+
+        assert_eq!(merge(&mut current, previous), ["architect-alice"]);
+        assert_eq!(current.formation, "k8s");
+        assert!(matches!(current.members["dev-bob"].endpoint, Endpoint::K8s { .. }));
+        let alice = &current.members["architect-alice"];
+        assert_eq!(alice.status, "stopped");
+        assert!(matches!(alice.endpoint, Endpoint::Local { pid: 12345, .. }));
+    }
 }
@@ -0,0 +1,592 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+/// Fields a filter expression can refer to. `labels` is a list; the rest
+/// are strings.
+pub const FIELDS: &[&str] = &["event", "action", "author", "repo", "branch", "labels"];
+
+/// An incoming GitHub event reduced to the fields filter expressions see.
+///
+/// Webhook deliveries and Events API entries normalize to the same shape:
+/// `event` uses webhook naming (`issue_comment`, not `IssueCommentEvent`),
+/// `author` is whoever triggered the event, `branch` is the branch it
+/// concerns (see [`branch_of`]), and `labels` are the labels of the issue or
+/// pull request it concerns.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TriggerEvent {
+    pub event: String,
+    pub action: String,
+    pub author: String,
+    pub repo: String,
+    pub branch: String,
+    pub labels: Vec<String>,
+}
+
+impl TriggerEvent {
+    /// Builds an event from a webhook delivery's `X-GitHub-Event` header and
+    /// JSON payload.
+    pub fn from_webhook(event_type: &str, payload: &Value) -> TriggerEvent {
+        let subject = payload.get("issue").or_else(|| payload.get("pull_request"));
+        TriggerEvent {
+            event: normalize_event_type(event_type),
+            action: str_at(payload, &["action"]),
+            author: str_at(payload, &["sender", "login"]),
+            repo: str_at(payload, &["repository", "full_name"]),
+            branch: branch_of(payload),
+            labels: subject.map(label_names).unwrap_or_default(),
+        }
+    }
+
+    /// One-line description for logs and `bm daemon simulate`.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        for (name, value) in [
+            ("action", &self.action),
+            ("author", &self.author),
+            ("repo", &self.repo),
+            ("branch", &self.branch),
+        ] {
+            if !value.is_empty() {
+                parts.push(format!("{}: {}", name, value));
+            }
+        }
+        if !self.labels.is_empty() {
+            parts.push(format!("labels: {}", self.labels.join(", ")));
+        }
+        if parts.is_empty() {
+            self.event.clone()
+        } else {
+            format!("{} ({})", self.event, parts.join(", "))
+        }
+    }
+}
+
+/// Converts an Events API type (`IssueCommentEvent`) to webhook naming
+/// (`issue_comment`). Webhook names pass through unchanged.
+pub fn normalize_event_type(event_type: &str) -> String {
+    let Some(base) = event_type.strip_suffix("Event") else {
+        return event_type.to_string();
+    };
+    let mut out = String::new();
+    for (i, c) in base.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Returns the branch a payload concerns: what a push or branch creation
+/// updated, the base of a pull request, the branch a workflow ran on, or the
+/// branch a release was cut from. Empty for events without one.
+pub fn branch_of(payload: &Value) -> String {
+    let candidates: [&[&str]; 4] = [
+        &["ref"],
+        &["pull_request", "base", "ref"],
+        &["workflow_run", "head_branch"],
+        &["release", "target_commitish"],
+    ];
+    let branch = candidates
+        .iter()
+        .map(|path| str_at(payload, path))
+        .find(|b| !b.is_empty())
+        .unwrap_or_default();
+    if branch.starts_with("refs/tags/") || str_at(payload, &["ref_type"]) == "tag" {
+        return String::new();
+    }
+    branch.trim_start_matches("refs/heads/").to_string()
+}
+
+/// Returns the names of the `labels` array of an issue or pull request.
+pub fn label_names(subject: &Value) -> Vec<String> {
+    subject
+        .get("labels")
+        .and_then(Value::as_array)
+        .map(|labels| {
+            labels
+                .iter()
+                .filter_map(|l| l.get("name").and_then(Value::as_str))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn str_at(value: &Value, path: &[&str]) -> String {
+    path.iter()
+        .try_fold(value, |v, key| v.get(key))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// A parsed filter expression, e.g.
+/// `event == "issues" && "kind/story" in labels && author != "bot"`.
+///
+/// Supports `==`, `!=`, `in` (list membership, or substring when the right
+/// side is a string), `&&`, `||`, `!`, parentheses, and list literals
+/// (`author in ["alice", "bob"]`). Field names and operand types are checked
+/// when parsing, so a parsed expression always evaluates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr(Node);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Or(Box<Node>, Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Eq(Operand, Operand),
+    Ne(Operand, Operand),
+    In(Operand, Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Field(&'static str),
+    Str(String),
+    List(Vec<String>),
+}
+
+impl Operand {
+    fn is_list(&self) -> bool {
+        matches!(self, Operand::List(_) | Operand::Field("labels"))
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Operand::Field(f) => f.to_string(),
+            Operand::Str(s) => format!("{:?}", s),
+            Operand::List(_) => "a list".to_string(),
+        }
+    }
+}
+
+enum Resolved<'a> {
+    Str(&'a str),
+    List(&'a [String]),
+}
+
+impl Expr {
+    /// Parses and type-checks an expression.
+    pub fn parse(src: &str) -> Result<Expr> {
+        let tokens = tokenize(src)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let node = parser.or()?;
+        if let Some((tok, at)) = parser.tokens.get(parser.pos) {
+            bail!("Unexpected {} at position {}", tok.describe(), at + 1);
+        }
+        Ok(Expr(node))
+    }
+
+    /// Evaluates the expression against an event.
+    pub fn eval(&self, event: &TriggerEvent) -> bool {
+        eval(&self.0, event)
+    }
+}
+
+fn eval(node: &Node, event: &TriggerEvent) -> bool {
+    match node {
+        Node::Or(a, b) => eval(a, event) || eval(b, event),
+        Node::And(a, b) => eval(a, event) && eval(b, event),
+        Node::Not(a) => !eval(a, event),
+        Node::Eq(a, b) => str_of(a, event) == str_of(b, event),
+        Node::Ne(a, b) => str_of(a, event) != str_of(b, event),
+        Node::In(a, b) => {
+            let needle = str_of(a, event);
+            match value_of(b, event) {
+                Resolved::List(items) => items.iter().any(|i| i == needle),
+                Resolved::Str(s) => s.contains(needle),
+            }
+        }
+    }
+}
+
+fn value_of<'a>(operand: &'a Operand, event: &'a TriggerEvent) -> Resolved<'a> {
+    match operand {
+        Operand::Field("event") => Resolved::Str(&event.event),
+        Operand::Field("action") => Resolved::Str(&event.action),
+        Operand::Field("author") => Resolved::Str(&event.author),
+        Operand::Field("repo") => Resolved::Str(&event.repo),
+        Operand::Field("branch") => Resolved::Str(&event.branch),
+        Operand::Field(_) => Resolved::List(&event.labels),
+        Operand::Str(s) => Resolved::Str(s),
+        Operand::List(items) => Resolved::List(items),
+    }
+}
+
+/// Returns a string operand's value; the parser guarantees it is one.
+fn str_of<'a>(operand: &'a Operand, event: &'a TriggerEvent) -> &'a str {
+    match value_of(operand, event) {
+        Resolved::Str(s) => s,
+        Resolved::List(_) => "",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Str(String),
+    Ident(String),
+    Eq,
+    Ne,
+    And,
+    Or,
+    Not,
+    In,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Str(s) => format!("string {:?}", s),
+            Token::Ident(s) => format!("'{}'", s),
+            Token::Eq => "'=='".to_string(),
+            Token::Ne => "'!='".to_string(),
+            Token::And => "'&&'".to_string(),
+            Token::Or => "'||'".to_string(),
+            Token::Not => "'!'".to_string(),
+            Token::In => "'in'".to_string(),
+            Token::LParen => "'('".to_string(),
+            Token::RParen => "')'".to_string(),
+            Token::LBracket => "'['".to_string(),
+            Token::RBracket => "']'".to_string(),
+            Token::Comma => "','".to_string(),
+        }
+    }
+}
+
+/// Splits an expression into tokens, each with its byte offset.
+fn tokenize(src: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<(usize, char)> = src.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (at, c) = chars[i];
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('=', Some('=')) => (Token::Eq, 2),
+            ('!', Some('=')) => (Token::Ne, 2),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            ('[', _) => (Token::LBracket, 1),
+            (']', _) => (Token::RBracket, 1),
+            (',', _) => (Token::Comma, 1),
+            ('"', _) => {
+                let mut value = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => bail!("Unterminated string at position {}", at + 1),
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => {
+                            match chars.get(j + 1) {
+                                Some((_, c)) => value.push(*c),
+                                None => bail!("Unterminated string at position {}", at + 1),
+                            }
+                            j += 2;
+                        }
+                        Some((_, c)) => {
+                            value.push(*c);
+                            j += 1;
+                        }
+                    }
+                }
+                (Token::Str(value), j + 1 - i)
+            }
+            (c, _) if c.is_ascii_alphabetic() || c == '_' => {
+                let mut j = i;
+                while chars
+                    .get(j)
+                    .is_some_and(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+                {
+                    j += 1;
+                }
+                let word: String = chars[i..j].iter().map(|(_, c)| c).collect();
+                let token = if word == "in" {
+                    Token::In
+                } else {
+                    Token::Ident(word)
+                };
+                (token, j - i)
+            }
+            (c, _) => bail!("Unexpected '{}' at position {}", c, at + 1),
+        };
+        tokens.push((token, at));
+        i += len;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Describes the current position for error messages.
+    fn here(&self) -> String {
+        match self.tokens.get(self.pos) {
+            Some((tok, at)) => format!("{} at position {}", tok.describe(), at + 1),
+            None => "end of expression".to_string(),
+        }
+    }
+
+    fn or(&mut self) -> Result<Node> {
+        let mut node = self.and()?;
+        while self.eat(&Token::Or) {
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node> {
+        let mut node = self.unary()?;
+        while self.eat(&Token::And) {
+            node = Node::And(Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node> {
+        if self.eat(&Token::Not) {
+            return Ok(Node::Not(Box::new(self.unary()?)));
+        }
+        if self.eat(&Token::LParen) {
+            let node = self.or()?;
+            if !self.eat(&Token::RParen) {
+                bail!("Expected ')' but found {}", self.here());
+            }
+            return Ok(node);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Node> {
+        let left = self.operand()?;
+        let op = match self.peek() {
+            Some(Token::Eq) | Some(Token::Ne) | Some(Token::In) => self.tokens[self.pos].0.clone(),
+            _ => bail!(
+                "Expected '==', '!=', or 'in' after {} but found {}",
+                left.describe(),
+                self.here()
+            ),
+        };
+        self.pos += 1;
+        let right = self.operand()?;
+        if left.is_list() {
+            bail!(
+                "{} is a list; test it with \"value\" in {}",
+                left.describe(),
+                left.describe()
+            );
+        }
+        Ok(match op {
+            Token::In => Node::In(left, right),
+            _ if right.is_list() => bail!(
+                "Cannot compare {} with {}; use 'in' to test membership",
+                left.describe(),
+                right.describe()
+            ),
+            Token::Eq => Node::Eq(left, right),
+            _ => Node::Ne(left, right),
+        })
+    }
+
+    fn operand(&mut self) -> Result<Operand> {
+        let Some((token, _)) = self.tokens.get(self.pos).cloned() else {
+            bail!("Expected a field, string, or list but found end of expression");
+        };
+        self.pos += 1;
+        match token {
+            Token::Str(s) => Ok(Operand::Str(s)),
+            Token::Ident(name) => match FIELDS.iter().find(|f| **f == name) {
+                Some(field) => Ok(Operand::Field(field)),
+                None => bail!("Unknown field '{}'. Fields: {}", name, FIELDS.join(", ")),
+            },
+            Token::LBracket => {
+                let mut items = Vec::new();
+                if self.eat(&Token::RBracket) {
+                    return Ok(Operand::List(items));
+                }
+                loop {
+                    match self.tokens.get(self.pos).cloned() {
+                        Some((Token::Str(s), _)) => items.push(s),
+                        _ => bail!("Expected a string in list but found {}", self.here()),
+                    }
+                    self.pos += 1;
+                    if self.eat(&Token::RBracket) {
+                        return Ok(Operand::List(items));
+                    }
+                    if !self.eat(&Token::Comma) {
+                        bail!("Expected ',' or ']' but found {}", self.here());
+                    }
+                }
+            }
+            _ => {
+                self.pos -= 1;
+                bail!(
+                    "Expected a field, string, or list but found {}",
+                    self.here()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn story() -> TriggerEvent {
+        TriggerEvent {
+            event: "issues".to_string(),
+            action: "opened".to_string(),
+            author: "alice".to_string(),
+            repo: "org/team".to_string(),
+            branch: String::new(),
+            labels: vec!["kind/story".to_string(), "status/ready".to_string()],
+        }
+    }
+
+    fn eval(src: &str, event: &TriggerEvent) -> bool {
+        Expr::parse(src).unwrap().eval(event)
+    }
+
+    #[test]
+    fn evaluates_the_documented_example() {
+        let src = r#"event == "issues" && "kind/story" in labels && author != "bot""#;
+        assert!(eval(src, &story()));
+        let mut bot = story();
+        bot.author = "bot".to_string();
+        assert!(!eval(src, &bot));
+        let mut bug = story();
+        bug.labels = vec!["kind/bug".to_string()];
+        assert!(!eval(src, &bug));
+    }
+
+    #[test]
+    fn precedence_negation_and_lists() {
+        let e = story();
+        // && binds tighter than ||
+        assert!(eval(
+            r#"event == "push" || event == "issues" && action == "opened""#,
+            &e
+        ));
+        assert!(!eval(
+            r#"(event == "push" || event == "issues") && action == "closed""#,
+            &e
+        ));
+        assert!(eval(r#"!("kind/bug" in labels)"#, &e));
+        assert!(eval(r#"author in ["alice", "bob"]"#, &e));
+        assert!(!eval(r#"author in []"#, &e));
+        assert!(eval(r#""org/" in repo"#, &e));
+        assert!(eval(r#"action != "say \"hi\"""#, &e));
+    }
+
+    #[test]
+    fn rejects_malformed_and_ill_typed_expressions() {
+        let err = |src: &str| Expr::parse(src).unwrap_err().to_string();
+        assert!(err(r#"lables == "x""#).contains("Unknown field 'lables'"));
+        assert!(err(r#"labels == "x""#).contains("labels is a list"));
+        assert!(err(r#"author == ["a"]"#).contains("use 'in'"));
+        assert!(err(r#"event == "issues" &&"#).contains("end of expression"));
+        assert!(err(r#"event == "issues"#).contains("Unterminated string"));
+        assert!(err(r#"event = "issues""#).contains("Unexpected '='"));
+        assert!(err(r#"(event == "issues""#).contains("Expected ')'"));
+        assert!(err(r#"event == "a" "b""#).contains("Unexpected string \"b\" at position 14"));
+        assert!(err("author").contains("Expected '==', '!=', or 'in' after author"));
+    }
+
+    #[test]
+    fn webhook_payload_normalizes() {
+        let payload = serde_json::json!({
+            "action": "labeled",
+            "sender": {"login": "alice"},
+            "repository": {"full_name": "org/team"},
+            "issue": {"labels": [{"name": "kind/story"}]}
+        });
+        let e = TriggerEvent::from_webhook("issues", &payload);
+        assert_eq!(e.action, "labeled");
+        assert_eq!(e.author, "alice");
+        assert_eq!(e.repo, "org/team");
+        assert_eq!(e.labels, vec!["kind/story"]);
+        assert_eq!(
+            e.describe(),
+            "issues (action: labeled, author: alice, repo: org/team, labels: kind/story)"
+        );
+
+        let e = TriggerEvent::from_webhook("ping", &Value::Null);
+        assert_eq!(
+            e,
+            TriggerEvent {
+                event: "ping".to_string(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(e.describe(), "ping");
+    }
+
+    #[test]
+    fn webhook_payloads_name_their_branch() {
+        let branch = |payload: Value| branch_of(&payload);
+        assert_eq!(branch(serde_json::json!({"ref": "refs/heads/main"})), "main");
+        assert_eq!(branch(serde_json::json!({"ref": "refs/tags/v1"})), "");
+        assert_eq!(
+            branch(serde_json::json!({"ref": "v1", "ref_type": "tag"})),
+            ""
+        );
+        assert_eq!(
+            branch(serde_json::json!({"pull_request": {"base": {"ref": "develop"}}})),
+            "develop"
+        );
+        assert_eq!(
+            branch(serde_json::json!({"workflow_run": {"head_branch": "release/1.2"}})),
+            "release/1.2"
+        );
+        assert_eq!(
+            branch(serde_json::json!({"release": {"target_commitish": "main"}})),
+            "main"
+        );
+
+        let e = TriggerEvent::from_webhook("push", &serde_json::json!({"ref": "refs/heads/main"}));
+        assert_eq!(e.describe(), "push (branch: main)");
+        assert!(eval(r#"branch == "main""#, &e));
+    }
+
+    #[test]
+    fn api_event_types_use_webhook_naming() {
+        assert_eq!(normalize_event_type("IssueCommentEvent"), "issue_comment");
+        assert_eq!(normalize_event_type("PullRequestEvent"), "pull_request");
+        assert_eq!(normalize_event_type("IssuesEvent"), "issues");
+        assert_eq!(normalize_event_type("issues"), "issues");
+    }
+}
@@ -0,0 +1,244 @@
+//! Public tunnels for webhook mode (`bm daemon start --tunnel`).
+//!
+//! The tunnel binary (`ngrok` or `cloudflared`) runs as its own detached
+//! process pointed at the local webhook listener. Its public URL is scraped
+//! from its log output and registered as a webhook on the team repo (see
+//! [`crate::github_hooks`]); `bm daemon stop` deletes the webhook and stops the
+//! tunnel again.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::procutil;
+
+/// Seconds to wait for the tunnel to report its public URL.
+const URL_TIMEOUT_SECS: u64 = 30;
+
+/// Tunnel binaries `bm` knows how to drive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    Ngrok,
+    Cloudflared,
+}
+
+impl Provider {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "ngrok" => Ok(Provider::Ngrok),
+            "cloudflared" => Ok(Provider::Cloudflared),
+            other => bail!(
+                "Unknown tunnel provider '{}'. Use 'ngrok' or 'cloudflared'.",
+                other
+            ),
+        }
+    }
+
+    /// Name of the binary, which is also the provider name.
+    pub fn binary(&self) -> &'static str {
+        match self {
+            Provider::Ngrok => "ngrok",
+            Provider::Cloudflared => "cloudflared",
+        }
+    }
+
+    /// Arguments that forward a public URL to `target` (`scheme://host:port`).
+    pub fn args(&self, target: &str) -> Vec<String> {
+        let mut args: Vec<String> = match self {
+            Provider::Ngrok => vec!["http", target, "--log", "stdout", "--log-format", "json"],
+            Provider::Cloudflared => vec!["tunnel", "--no-autoupdate", "--url", target],
+        }
+        .into_iter()
+        .map(String::from)
+        .collect();
+        // Local listeners usually serve a self-signed certificate
+        if *self == Provider::Cloudflared && target.starts_with("https://") {
+            args.push("--no-tls-verify".to_string());
+        }
+        args
+    }
+
+    /// Picks the public URL out of one line of the tunnel's log output.
+    fn extract_url(&self, line: &str) -> Option<String> {
+        match self {
+            // {"lvl":"info","msg":"started tunnel",...,"url":"https://x.ngrok-free.app"}
+            Provider::Ngrok => {
+                let entry: serde_json::Value = serde_json::from_str(line).ok()?;
+                if entry["msg"] != "started tunnel" {
+                    return None;
+                }
+                let url = entry["url"].as_str()?;
+                url.starts_with("https://").then(|| url.to_string())
+            }
+            // "|  https://x.trycloudflare.com  |", among unrelated cloudflare.com links
+            Provider::Cloudflared => line
+                .split(|c: char| c.is_whitespace() || c == '|')
+                .find(|word| {
+                    word.starts_with("https://") && word.ends_with(".trycloudflare.com")
+                })
+                .map(String::from),
+        }
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.binary())
+    }
+}
+
+/// A running tunnel and the webhooks registered for it, persisted in the
+/// daemon config so `bm daemon stop` can tear them down.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tunnel {
+    pub provider: Provider,
+    pub pid: u32,
+    /// Public webhook URL, including the listener path.
+    pub url: String,
+    /// ID of the team repo's webhook pointing at `url`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook_id: Option<u64>,
+    /// Webhook IDs on the team's extra repos, by repo.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra_hook_ids: BTreeMap<String, u64>,
+}
+
+impl Tunnel {
+    /// Every registered webhook as `(repo, id)`, team repo first.
+    pub fn hooks(&self, team_repo: &str) -> Vec<(String, u64)> {
+        let primary = self.hook_id.map(|id| (team_repo.to_string(), id));
+        primary
+            .into_iter()
+            .chain(self.extra_hook_ids.iter().map(|(repo, id)| (repo.clone(), *id)))
+            .collect()
+    }
+}
+
+/// Returns the tunnel's log file path (`~/.botminter/logs/tunnel-<team>.log`).
+pub fn log_path(team_name: &str) -> Result<PathBuf> {
+    let logs_dir = config::config_dir()?.join("logs");
+    fs::create_dir_all(&logs_dir)?;
+    Ok(logs_dir.join(format!("tunnel-{}.log", team_name)))
+}
+
+/// Fails early, with an install hint, if the provider's binary is missing.
+pub fn require_binary(provider: Provider) -> Result<()> {
+    if which::which(provider.binary()).is_err() {
+        let hint = match provider {
+            Provider::Ngrok => "https://ngrok.com/download (then run `ngrok config add-authtoken`)",
+            Provider::Cloudflared => {
+                "https://developers.cloudflare.com/cloudflare-one/connections/connect-networks/downloads/"
+            }
+        };
+        bail!("{} not found on PATH. Install it from {}", provider, hint);
+    }
+    Ok(())
+}
+
+/// Starts the tunnel to `target` and waits for its public URL. Returns the
+/// tunnel's PID and public base URL; the process is stopped again on failure.
+pub fn open(provider: Provider, target: &str, log: &Path) -> Result<(u32, String)> {
+    let log_file = fs::File::create(log)
+        .with_context(|| format!("Failed to create tunnel log at {}", log.display()))?;
+    let log_err = log_file.try_clone().context("Failed to clone log file handle")?;
+    let child = Command::new(provider.binary())
+        .args(provider.args(target))
+        .stdin(Stdio::null())
+        .stdout(log_file)
+        .stderr(log_err)
+        .spawn()
+        .with_context(|| format!("Failed to start {}", provider))?;
+    let pid = child.id();
+
+    match wait_for_url(provider, log, pid, Duration::from_secs(URL_TIMEOUT_SECS)) {
+        Ok(url) => Ok((pid, url)),
+        Err(e) => {
+            procutil::terminate(pid);
+            Err(e)
+        }
+    }
+}
+
+/// Polls the tunnel log until it reports a public URL, the process exits,
+/// or `timeout` passes.
+fn wait_for_url(provider: Provider, log: &Path, pid: u32, timeout: Duration) -> Result<String> {
+    let start = Instant::now();
+    loop {
+        let output = fs::read_to_string(log).unwrap_or_default();
+        if let Some(url) = output.lines().find_map(|l| provider.extract_url(l)) {
+            return Ok(url);
+        }
+        if !procutil::is_alive(pid) {
+            bail!(
+                "{} exited before reporting a public URL. Last output:\n{}\nFull log: {}",
+                provider,
+                tail(&output, 5),
+                log.display()
+            );
+        }
+        if start.elapsed() >= timeout {
+            bail!(
+                "{} did not report a public URL within {}s. Check {}",
+                provider,
+                timeout.as_secs(),
+                log.display()
+            );
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+}
+
+fn tail(output: &str, lines: usize) -> String {
+    let all: Vec<&str> = output.lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_public_urls_from_tunnel_output() {
+        let ngrok = Provider::Ngrok;
+        assert_eq!(
+            ngrok.extract_url(
+                r#"{"addr":"http://127.0.0.1:8484","lvl":"info","msg":"started tunnel","name":"command_line","url":"https://ab12.ngrok-free.app"}"#
+            ),
+            Some("https://ab12.ngrok-free.app".to_string())
+        );
+        assert_eq!(ngrok.extract_url(r#"{"lvl":"info","msg":"client session established"}"#), None);
+        assert_eq!(ngrok.extract_url("not json"), None);
+
+        let cf = Provider::Cloudflared;
+        assert_eq!(
+            cf.extract_url("2026-01-01T00:00:00Z INF |  https://calm-river-1234.trycloudflare.com                     |"),
+            Some("https://calm-river-1234.trycloudflare.com".to_string())
+        );
+        assert_eq!(
+            cf.extract_url("INF Thank you for trying Cloudflare Tunnel. See https://developers.cloudflare.com/x"),
+            None
+        );
+    }
+
+    #[test]
+    fn provider_args_point_at_local_listener() {
+        assert_eq!(
+            Provider::Ngrok.args("http://127.0.0.1:8484"),
+            vec!["http", "http://127.0.0.1:8484", "--log", "stdout", "--log-format", "json"]
+        );
+        assert_eq!(
+            Provider::Cloudflared.args("https://127.0.0.1:8484"),
+            vec!["tunnel", "--no-autoupdate", "--url", "https://127.0.0.1:8484", "--no-tls-verify"]
+        );
+        assert!(Provider::parse("localtunnel").is_err());
+    }
+}
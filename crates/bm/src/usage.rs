@@ -0,0 +1,281 @@
+//! Token and cost accounting for member runs.
+//!
+//! Ralph drives Claude Code with `--output-format stream-json`, which ends
+//! every session with a `result` event carrying the session's token counts
+//! and its cost in USD. Member logs (`logs/member-<team>-<member>.log`) hold
+//! that output for runs launched by the daemon or `bm start`; [`collect`]
+//! reads what was appended since the last read and adds it to
+//! `~/.botminter/usage.json`, per team, member, and UTC day. Members a
+//! non-local formation runs as pods log there instead and are not counted.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::commands::daemon;
+use crate::config;
+use crate::filelock::FileLock;
+
+/// Tokens and cost of one or more sessions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Sessions counted.
+    #[serde(default)]
+    pub sessions: u64,
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    /// Input tokens written to the prompt cache.
+    #[serde(default)]
+    pub cache_write_tokens: u64,
+    /// Input tokens read from the prompt cache.
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    #[serde(default)]
+    pub cost_usd: f64,
+}
+
+impl Usage {
+    pub fn add(&mut self, other: &Usage) {
+        self.sessions += other.sessions;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cost_usd += other.cost_usd;
+    }
+
+    /// All tokens, cached or not.
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_write_tokens + self.cache_read_tokens
+    }
+}
+
+/// How far a member log has been read.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    /// Inode of the log, so a rotated log is read from the start.
+    pub inode: u64,
+    pub offset: u64,
+}
+
+/// What `usage.json` holds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ledger {
+    /// Team → member → UTC day → usage.
+    #[serde(default)]
+    pub teams: BTreeMap<String, BTreeMap<String, BTreeMap<NaiveDate, Usage>>>,
+    /// Member log path → how far it has been read.
+    #[serde(default)]
+    pub cursors: BTreeMap<PathBuf, Cursor>,
+}
+
+/// `~/.botminter/usage.json`.
+pub fn ledger_path() -> Result<PathBuf> {
+    Ok(config::config_dir()?.join("usage.json"))
+}
+
+/// Reads the ledger at `path`; a missing file is an empty ledger.
+pub fn load_from(path: &Path) -> Result<Ledger> {
+    match fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Ledger::default()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+fn save_to(path: &Path, ledger: &Ledger) -> Result<()> {
+    let json = serde_json::to_string_pretty(ledger).context("Failed to serialize usage")?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json + "\n").with_context(|| format!("Failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// The usage in one line of stream-json output, if it is a `result` event.
+pub fn parse_line(line: &str) -> Option<Usage> {
+    #[derive(Deserialize)]
+    struct Event {
+        #[serde(rename = "type")]
+        kind: String,
+        #[serde(default)]
+        total_cost_usd: f64,
+        #[serde(default)]
+        usage: Option<Tokens>,
+    }
+    #[derive(Deserialize)]
+    struct Tokens {
+        #[serde(default)]
+        input_tokens: u64,
+        #[serde(default)]
+        output_tokens: u64,
+        #[serde(default)]
+        cache_creation_input_tokens: u64,
+        #[serde(default)]
+        cache_read_input_tokens: u64,
+    }
+
+    let line = line.trim();
+    if !line.starts_with('{') || !line.contains("\"result\"") {
+        return None;
+    }
+    let event: Event = serde_json::from_str(line).ok()?;
+    if event.kind != "result" {
+        return None;
+    }
+    let tokens = event.usage?;
+    Some(Usage {
+        sessions: 1,
+        input_tokens: tokens.input_tokens,
+        output_tokens: tokens.output_tokens,
+        cache_write_tokens: tokens.cache_creation_input_tokens,
+        cache_read_tokens: tokens.cache_read_input_tokens,
+        cost_usd: event.total_cost_usd,
+    })
+}
+
+/// Adds what was appended to `log` since the last read to `member`'s usage,
+/// on the UTC day the log was last written. Returns the usage added.
+pub fn collect_log(ledger: &mut Ledger, team: &str, member: &str, log: &Path) -> Result<Usage> {
+    let mut added = Usage::default();
+    let Ok(meta) = fs::metadata(log) else {
+        return Ok(added);
+    };
+    let cursor = ledger.cursors.get(log).cloned().unwrap_or_default();
+    let start = if cursor.inode == file_id(&meta) && cursor.offset <= meta.len() {
+        cursor.offset
+    } else {
+        0
+    };
+
+    let mut file =
+        fs::File::open(log).with_context(|| format!("Failed to open {}", log.display()))?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)
+        .with_context(|| format!("Failed to read {}", log.display()))?;
+    // Leave a partly written last line for the next read
+    let complete = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    for line in String::from_utf8_lossy(&bytes[..complete]).lines() {
+        if let Some(usage) = parse_line(line) {
+            added.add(&usage);
+        }
+    }
+
+    ledger.cursors.insert(
+        log.to_path_buf(),
+        Cursor {
+            inode: file_id(&meta),
+            offset: start + complete as u64,
+        },
+    );
+    if added.sessions > 0 {
+        let day = meta
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now())
+            .date_naive();
+        ledger
+            .teams
+            .entry(team.to_string())
+            .or_default()
+            .entry(member.to_string())
+            .or_default()
+            .entry(day)
+            .or_default()
+            .add(&added);
+    }
+    Ok(added)
+}
+
+/// Identifies the file behind a log path, so a rotated log is read from the
+/// start: its inode on Unix. Always 0 on Windows, where std has no stable
+/// file ID; a rotated log is still caught there once it's shorter than the
+/// offset already read.
+#[cfg(unix)]
+fn file_id(meta: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.ino()
+}
+
+#[cfg(not(unix))]
+fn file_id(_meta: &fs::Metadata) -> u64 {
+    0
+}
+
+/// Reads the logs of `team`'s `members` into the ledger and returns it.
+pub fn collect(team: &str, members: &[String]) -> Result<Ledger> {
+    let path = ledger_path()?;
+    let _lock = FileLock::acquire(&path)?;
+    let mut ledger = load_from(&path)?;
+    let before = ledger.clone();
+    for member in members {
+        let log = daemon::member_log_path(team, member)?;
+        collect_log(&mut ledger, team, member, &log)?;
+    }
+    if ledger != before {
+        save_to(&path, &ledger)?;
+    }
+    Ok(ledger)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const RESULT: &str = r#"{"type":"result","subtype":"success","total_cost_usd":0.25,"usage":{"input_tokens":100,"output_tokens":40,"cache_creation_input_tokens":10,"cache_read_input_tokens":500}}"#;
+
+    #[test]
+    fn parse_line_reads_result_events_only() {
+        let usage = parse_line(RESULT).unwrap();
+        assert_eq!(usage.sessions, 1);
+        assert_eq!(usage.total_tokens(), 650);
+        assert_eq!(usage.cost_usd, 0.25);
+
+        let assistant = r#"{"type":"assistant","message":{"usage":{"input_tokens":100}}}"#;
+        assert_eq!(parse_line(assistant), None);
+        assert_eq!(parse_line("Iteration 3: result pending"), None);
+        assert_eq!(parse_line(r#"{"type":"result","is_error":true}"#), None);
+    }
+
+    #[test]
+    fn collect_log_reads_only_new_complete_lines() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("member.log");
+        let mut file = fs::File::create(&log).unwrap();
+        writeln!(file, "starting ralph\n{}", RESULT).unwrap();
+        write!(file, "{}", &RESULT[..20]).unwrap();
+
+        let mut ledger = Ledger::default();
+        let added = collect_log(&mut ledger, "t", "dev-01", &log).unwrap();
+        assert_eq!(added.sessions, 1);
+        // Nothing new
+        let added = collect_log(&mut ledger, "t", "dev-01", &log).unwrap();
+        assert_eq!(added.sessions, 0);
+
+        // The partial line completes
+        writeln!(file, "{}", &RESULT[20..]).unwrap();
+        let added = collect_log(&mut ledger, "t", "dev-01", &log).unwrap();
+        assert_eq!(added.sessions, 1);
+
+        let days = &ledger.teams["t"]["dev-01"];
+        assert_eq!(days.len(), 1);
+        let total = days.values().next().unwrap();
+        assert_eq!(total.sessions, 2);
+        assert_eq!(total.cost_usd, 0.5);
+
+        // A rotated log is read from the start
+        fs::remove_file(&log).unwrap();
+        fs::write(&log, format!("{}\n", RESULT)).unwrap();
+        let added = collect_log(&mut ledger, "t", "dev-01", &log).unwrap();
+        assert_eq!(added.sessions, 1);
+    }
+}
@@ -0,0 +1,160 @@
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+
+use crate::config;
+
+/// How often the daemon refreshes its heartbeat file.
+pub const HEARTBEAT_INTERVAL_SECS: u64 = 15;
+
+/// A heartbeat older than this means the daemon's event loop is wedged.
+pub const HEARTBEAT_STALE_SECS: u64 = 90;
+
+/// Returns the heartbeat file path for a daemon (`~/.botminter/daemon-<team>.heartbeat`).
+pub fn heartbeat_path(team_name: &str) -> Result<PathBuf> {
+    Ok(config::config_dir()?.join(format!("daemon-{}.heartbeat", team_name)))
+}
+
+/// Records a heartbeat at `now`.
+pub fn write_heartbeat(path: &Path, now: DateTime<Utc>) -> Result<()> {
+    fs::write(path, now.to_rfc3339())
+        .with_context(|| format!("Failed to write heartbeat at {}", path.display()))
+}
+
+/// Returns how long ago the last heartbeat was written, or `None` if there is
+/// no readable heartbeat (e.g. a daemon started by an older `bm`).
+pub fn heartbeat_age(path: &Path, now: DateTime<Utc>) -> Option<Duration> {
+    let contents = fs::read_to_string(path).ok()?;
+    let at = DateTime::parse_from_rfc3339(contents.trim()).ok()?;
+    (now - at.with_timezone(&Utc)).to_std().ok().or(Some(Duration::ZERO))
+}
+
+/// Returns true if a heartbeat of this age indicates a wedged daemon.
+pub fn is_stale(age: Duration) -> bool {
+    age.as_secs() > HEARTBEAT_STALE_SECS
+}
+
+/// Sends a state update (e.g. `READY=1`, `WATCHDOG=1`) to systemd.
+///
+/// Does nothing unless `NOTIFY_SOCKET` is set, i.e. the daemon runs under a
+/// `Type=notify` unit, and never on Windows. Returns whether a notification
+/// was sent.
+#[cfg_attr(not(unix), allow(unused_variables))]
+pub fn sd_notify(state: &str) -> Result<bool> {
+    match std::env::var_os("NOTIFY_SOCKET") {
+        #[cfg(unix)]
+        Some(socket) => {
+            notify_socket(&socket.to_string_lossy(), state)?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Returns the systemd watchdog timeout (`WatchdogSec=`) if one applies to
+/// this process.
+pub fn watchdog_timeout() -> Option<Duration> {
+    if let Ok(pid) = std::env::var("WATCHDOG_PID") {
+        if pid.trim().parse::<u32>().ok() != Some(std::process::id()) {
+            return None;
+        }
+    }
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.trim().parse().ok()?;
+    (usec > 0).then(|| Duration::from_micros(usec))
+}
+
+/// Returns how often to beat: the regular interval, or more often if the
+/// systemd watchdog needs it (systemd recommends half the timeout).
+pub fn beat_interval(watchdog: Option<Duration>) -> Duration {
+    let regular = Duration::from_secs(HEARTBEAT_INTERVAL_SECS);
+    match watchdog {
+        Some(timeout) => regular.min(timeout / 2).max(Duration::from_secs(1)),
+        None => regular,
+    }
+}
+
+/// Writes `state` to the notify socket. On Linux a leading `@` denotes an
+/// abstract socket; elsewhere the socket is always a filesystem path.
+#[cfg(unix)]
+fn notify_socket(socket: &str, state: &str) -> Result<()> {
+    let sock = UnixDatagram::unbound().context("Failed to create notify socket")?;
+    send_notify(&sock, socket, state)
+        .with_context(|| format!("Failed to notify systemd at {}", socket))?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn send_notify(sock: &UnixDatagram, socket: &str, state: &str) -> Result<usize> {
+    use std::os::linux::net::SocketAddrExt;
+    use std::os::unix::net::SocketAddr;
+
+    match socket.strip_prefix('@') {
+        Some(name) => {
+            let addr = SocketAddr::from_abstract_name(name.as_bytes())
+                .context("Invalid abstract NOTIFY_SOCKET")?;
+            Ok(sock.send_to_addr(state.as_bytes(), &addr)?)
+        }
+        None => Ok(sock.send_to(state.as_bytes(), socket)?),
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn send_notify(sock: &UnixDatagram, socket: &str, state: &str) -> Result<usize> {
+    Ok(sock.send_to(state.as_bytes(), socket)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heartbeat_age_reads_written_timestamp() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("daemon-t.heartbeat");
+        let then = Utc::now() - chrono::Duration::seconds(120);
+        write_heartbeat(&path, then).unwrap();
+
+        let age = heartbeat_age(&path, then + chrono::Duration::seconds(120)).unwrap();
+        assert_eq!(age.as_secs(), 120);
+        assert!(is_stale(age));
+        assert!(!is_stale(Duration::from_secs(HEARTBEAT_INTERVAL_SECS)));
+    }
+
+    #[test]
+    fn heartbeat_age_missing_or_garbage() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("daemon-t.heartbeat");
+        assert!(heartbeat_age(&path, Utc::now()).is_none());
+        fs::write(&path, "not a timestamp").unwrap();
+        assert!(heartbeat_age(&path, Utc::now()).is_none());
+    }
+
+    #[test]
+    fn beat_interval_follows_watchdog() {
+        assert_eq!(beat_interval(None).as_secs(), HEARTBEAT_INTERVAL_SECS);
+        assert_eq!(beat_interval(Some(Duration::from_secs(10))).as_secs(), 5);
+        assert_eq!(
+            beat_interval(Some(Duration::from_secs(300))).as_secs(),
+            HEARTBEAT_INTERVAL_SECS
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn notify_socket_delivers_state() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("notify.sock");
+        let listener = UnixDatagram::bind(&path).unwrap();
+
+        notify_socket(path.to_str().unwrap(), "WATCHDOG=1").unwrap();
+
+        let mut buf = [0u8; 64];
+        let n = listener.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"WATCHDOG=1");
+    }
+}
@@ -1,9 +1,13 @@
 use std::fs;
-use std::os::unix::fs as unix_fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::mcp;
+use crate::profile_checksums;
+use crate::workspace_templates;
 
 /// BM files that should be hidden from git in the workspace.
 const BM_GITIGNORE_ENTRIES: &[&str] = &[
@@ -17,6 +21,41 @@ const BM_GITIGNORE_ENTRIES: &[&str] = &[
     ".gitignore",
 ];
 
+/// How `PROMPT.md`, `CLAUDE.md`, and `.claude/agents/` are put in a workspace
+/// from its `.botminter/` clone (`workspace.link_mode` in config.yml).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkMode {
+    /// Relative symlinks.
+    #[default]
+    Symlink,
+    /// Hard links, for filesystems where symlinks break (some NTFS and NFS
+    /// mounts). A pull in `.botminter/` replaces the files, so sync re-links.
+    Hardlink,
+    /// Plain copies, for filesystems without links. Sync refreshes copies
+    /// whose contents no longer match the team repo's.
+    Copy,
+}
+
+impl std::fmt::Display for LinkMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LinkMode::Symlink => "symlink",
+            LinkMode::Hardlink => "hardlink",
+            LinkMode::Copy => "copy",
+        })
+    }
+}
+
+/// Workspace settings, from the `workspace:` section of `config.yml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    /// How surfaced files are materialized (default: symlink).
+    #[serde(default)]
+    pub link_mode: LinkMode,
+}
+
 /// Creates a workspace for a member, optionally with a target project.
 ///
 /// With project:
@@ -31,6 +70,7 @@ pub fn create_workspace(
     member_dir_name: &str,
     project: Option<(&str, &str)>, // (project_name, fork_url)
     github_repo: Option<&str>,
+    link_mode: LinkMode,
 ) -> Result<()> {
     let member_ws = workspace_base.join(member_dir_name);
     fs::create_dir_all(&member_ws)
@@ -83,17 +123,33 @@ pub fn create_workspace(
         git_cmd(&bm_dir, &["remote", "set-url", "origin", &github_url])?;
     }
 
-    // Surface files (symlinks + copy)
-    surface_files(&ws_root, member_dir_name)?;
+    // Surface files (links or copies, plus ralph.yml)
+    surface_files(&ws_root, member_dir_name, link_mode)?;
 
     // Assemble .claude/ directory
-    assemble_claude_dir(&ws_root, member_dir_name, project.map(|(name, _)| name))?;
+    assemble_claude_dir(
+        &ws_root,
+        member_dir_name,
+        project.map(|(name, _)| name),
+        link_mode,
+    )?;
+
+    // Render the role's workspace templates
+    let mut generated = workspace_templates::render_workspace_files(
+        &ws_root,
+        member_dir_name,
+        project.map(|(name, _)| name),
+        github_repo,
+    )?;
+
+    // Render team and member MCP servers
+    generated.extend(mcp::render_mcp_config(&ws_root, member_dir_name, &generated)?);
 
     // Write .gitignore
     write_gitignore(&ws_root)?;
 
     // Write .git/info/exclude and hide tracked BM files
-    write_git_exclude(&ws_root)?;
+    write_git_exclude(&ws_root, &generated)?;
     hide_tracked_bm_files(&ws_root)?;
 
     Ok(())
@@ -106,6 +162,7 @@ pub fn sync_workspace(
     project_name: Option<&str>,
     has_project: bool,
     github_repo: Option<&str>,
+    link_mode: LinkMode,
 ) -> Result<()> {
     let bm_dir = ws_root.join(".botminter");
 
@@ -154,21 +211,35 @@ pub fn sync_workspace(
         &ws_root.join(".claude").join("settings.local.json"),
     )?;
 
-    // Re-assemble .claude/agents/ symlinks (idempotent)
-    assemble_claude_dir(ws_root, member_dir_name, project_name)?;
+    // Re-assemble .claude/agents/ (idempotent)
+    assemble_claude_dir(ws_root, member_dir_name, project_name, link_mode)?;
 
-    // Verify PROMPT.md and CLAUDE.md symlinks
-    verify_symlink(
-        &ws_root.join("PROMPT.md"),
-        &bm_dir.join("team").join(member_dir_name).join("PROMPT.md"),
-    )?;
-    verify_symlink(
-        &ws_root.join("CLAUDE.md"),
-        &bm_dir.join("team").join(member_dir_name).join("CLAUDE.md"),
+    // Re-render the role's workspace templates
+    let mut generated = workspace_templates::render_workspace_files(
+        ws_root,
+        member_dir_name,
+        project_name,
+        github_repo,
     )?;
 
+    // Re-render team and member MCP servers
+    generated.extend(mcp::render_mcp_config(ws_root, member_dir_name, &generated)?);
+
+    // Verify PROMPT.md and CLAUDE.md links, or refresh stale copies
+    for name in ["PROMPT.md", "CLAUDE.md"] {
+        let src = bm_dir.join("team").join(member_dir_name).join(name);
+        match link_mode {
+            LinkMode::Symlink => verify_symlink(&ws_root.join(name), &src)?,
+            _ => {
+                if src.exists() && is_stale(&ws_root.join(name), &src, link_mode) {
+                    place_file(&src, &ws_root.join(name), link_mode)?;
+                }
+            }
+        }
+    }
+
     // Ensure .git/info/exclude is up to date and hide tracked BM files
-    write_git_exclude(ws_root)?;
+    write_git_exclude(ws_root, &generated)?;
     hide_tracked_bm_files(ws_root)?;
 
     Ok(())
@@ -185,6 +256,7 @@ pub fn assemble_claude_dir(
     ws_root: &Path,
     member_dir_name: &str,
     project_name: Option<&str>,
+    link_mode: LinkMode,
 ) -> Result<()> {
     let claude_agents = ws_root.join(".claude").join("agents");
 
@@ -197,28 +269,30 @@ pub fn assemble_claude_dir(
     let bm_dir = ws_root.join(".botminter");
 
     // 1. Team-level agents
-    symlink_md_files(&bm_dir.join("agent").join("agents"), &claude_agents)?;
+    link_md_files(&bm_dir.join("agent").join("agents"), &claude_agents, link_mode)?;
 
     // 2. Project-level agents
     if let Some(proj) = project_name {
-        symlink_md_files(
+        link_md_files(
             &bm_dir
                 .join("projects")
                 .join(proj)
                 .join("agent")
                 .join("agents"),
             &claude_agents,
+            link_mode,
         )?;
     }
 
     // 3. Member-level agents
-    symlink_md_files(
+    link_md_files(
         &bm_dir
             .join("team")
             .join(member_dir_name)
             .join("agent")
             .join("agents"),
         &claude_agents,
+        link_mode,
     )?;
 
     // 4. Copy settings.local.json if present
@@ -235,8 +309,9 @@ pub fn assemble_claude_dir(
     Ok(())
 }
 
-/// Creates PROMPT.md and CLAUDE.md as relative symlinks and copies ralph.yml.
-pub fn surface_files(ws_root: &Path, member_dir_name: &str) -> Result<()> {
+/// Creates PROMPT.md and CLAUDE.md as relative symlinks (or hard links or
+/// copies, per `link_mode`) and copies ralph.yml.
+pub fn surface_files(ws_root: &Path, member_dir_name: &str, link_mode: LinkMode) -> Result<()> {
     let member_bm = ws_root
         .join(".botminter")
         .join("team")
@@ -245,13 +320,21 @@ pub fn surface_files(ws_root: &Path, member_dir_name: &str) -> Result<()> {
     let canonical = fs::canonicalize(&member_bm)
         .with_context(|| format!("Failed to canonicalize {}", member_bm.display()))?;
 
-    // Symlink PROMPT.md and CLAUDE.md (relative)
-    let canonical_ws = fs::canonicalize(ws_root)
-        .with_context(|| format!("Failed to canonicalize {}", ws_root.display()))?;
-    let rel = relative_path(&canonical_ws, &canonical);
+    if link_mode == LinkMode::Symlink {
+        // Symlink PROMPT.md and CLAUDE.md (relative)
+        let canonical_ws = fs::canonicalize(ws_root)
+            .with_context(|| format!("Failed to canonicalize {}", ws_root.display()))?;
+        let rel = relative_path(&canonical_ws, &canonical);
 
-    create_symlink(&rel.join("PROMPT.md"), &ws_root.join("PROMPT.md"))?;
-    create_symlink(&rel.join("CLAUDE.md"), &ws_root.join("CLAUDE.md"))?;
+        create_symlink(&rel.join("PROMPT.md"), &ws_root.join("PROMPT.md"))?;
+        create_symlink(&rel.join("CLAUDE.md"), &ws_root.join("CLAUDE.md"))?;
+    } else {
+        for name in ["PROMPT.md", "CLAUDE.md"] {
+            if canonical.join(name).exists() {
+                place_file(&canonical.join(name), &ws_root.join(name), link_mode)?;
+            }
+        }
+    }
 
     // Copy ralph.yml (not symlink — may be modified per-run)
     let ralph_src = canonical.join("ralph.yml");
@@ -276,15 +359,22 @@ pub fn gitignore_content() -> String {
     lines.join("\n")
 }
 
-/// Writes `.git/info/exclude` with BM patterns.
-pub fn write_git_exclude(ws_root: &Path) -> Result<()> {
+/// Writes `.git/info/exclude` with BM patterns plus `generated` workspace
+/// files rendered from role templates.
+pub fn write_git_exclude(ws_root: &Path, generated: &[String]) -> Result<()> {
     let git_dir = ws_root.join(".git");
     if !git_dir.is_dir() {
         return Ok(()); // No .git dir — skip
     }
     let exclude_dir = git_dir.join("info");
     fs::create_dir_all(&exclude_dir).context("Failed to create .git/info/")?;
-    fs::write(exclude_dir.join("exclude"), gitignore_content())
+    let mut content = gitignore_content();
+    for path in generated {
+        content.push('/');
+        content.push_str(path.trim_start_matches("./"));
+        content.push('\n');
+    }
+    fs::write(exclude_dir.join("exclude"), content)
         .context("Failed to write .git/info/exclude")
 }
 
@@ -345,11 +435,249 @@ pub fn hide_tracked_bm_files(ws_root: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Branch and sync state of a workspace checkout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitSummary {
+    pub branch: String,
+    pub dirty: bool,
+    /// Commits (ahead, behind) the upstream branch, if one is set.
+    pub ahead_behind: Option<(u32, u32)>,
+}
+
+impl std::fmt::Display for GitSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {}",
+            self.branch,
+            if self.dirty { "dirty" } else { "clean" }
+        )?;
+        match self.ahead_behind {
+            Some((0, 0)) => write!(f, ", up to date"),
+            Some((ahead, behind)) => write!(f, ", {} ahead, {} behind", ahead, behind),
+            None => write!(f, ", no upstream"),
+        }
+    }
+}
+
+/// Summarizes the git checkout at `ws_root`, or `None` if it isn't one.
+pub fn git_summary(ws_root: &Path) -> Option<GitSummary> {
+    if !ws_root.join(".git").exists() {
+        return None;
+    }
+    let branch = git_cmd_output(ws_root, &["symbolic-ref", "--short", "HEAD"])
+        .map(|b| b.trim().to_string())
+        .unwrap_or_else(|_| "(detached)".to_string());
+    let dirty = git_cmd_output(ws_root, &["status", "--porcelain"])
+        .map(|o| !o.trim().is_empty())
+        .unwrap_or(false);
+    let ahead_behind = git_cmd_output(
+        ws_root,
+        &["rev-list", "--left-right", "--count", "HEAD...@{upstream}"],
+    )
+    .ok()
+    .and_then(|o| {
+        let mut counts = o.split_whitespace().map(|n| n.parse::<u32>());
+        match (counts.next(), counts.next()) {
+            (Some(Ok(ahead)), Some(Ok(behind))) => Some((ahead, behind)),
+            _ => None,
+        }
+    });
+    Some(GitSummary {
+        branch,
+        dirty,
+        ahead_behind,
+    })
+}
+
+/// What [`push_wip`] did with a workspace's work.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WipPush {
+    /// Nothing uncommitted or unpushed.
+    Clean,
+    /// Pushed to `branch` on `origin`, after committing uncommitted
+    /// changes if `committed`.
+    Pushed { branch: String, committed: bool },
+}
+
+/// Saves a workspace's work on `origin` so another machine can pick it up:
+/// commits uncommitted changes with `message`, then pushes HEAD to its
+/// branch. Work on `main`, `master`, or a detached HEAD goes to
+/// `wip/<member>` instead, so nothing is pushed to the default branch.
+pub fn push_wip(ws_root: &Path, member_dir_name: &str, message: &str) -> Result<WipPush> {
+    let Some(summary) = git_summary(ws_root) else {
+        return Ok(WipPush::Clean);
+    };
+    if summary.dirty {
+        git_cmd_output(ws_root, &["add", "-A"])?;
+        git_cmd_output(ws_root, &["commit", "-m", message])?;
+    }
+    let unpushed = summary.ahead_behind.is_none_or(|(ahead, _)| ahead > 0);
+    if !summary.dirty && !unpushed {
+        return Ok(WipPush::Clean);
+    }
+    let branch = match summary.branch.as_str() {
+        "main" | "master" | "(detached)" => format!("wip/{}", member_dir_name),
+        branch => branch.to_string(),
+    };
+    git_cmd_output(
+        ws_root,
+        &["push", "origin", &format!("HEAD:refs/heads/{}", branch)],
+    )?;
+    Ok(WipPush::Pushed {
+        branch,
+        committed: summary.dirty,
+    })
+}
+
+/// How a surfaced workspace file departs from what sync would make it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftKind {
+    /// A copied file whose contents differ from the team repo's.
+    Modified,
+    /// A surfaced file that should exist but doesn't.
+    Missing,
+    /// A symlink that was replaced by a regular file.
+    NotSymlink,
+    /// A symlink whose target doesn't exist.
+    Broken,
+    /// A symlink that resolves to some other file.
+    WrongTarget,
+}
+
+impl std::fmt::Display for DriftKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DriftKind::Modified => "modified (differs from the team repo)",
+            DriftKind::Missing => "missing",
+            DriftKind::NotSymlink => "replaced by a regular file",
+            DriftKind::Broken => "broken symlink",
+            DriftKind::WrongTarget => "symlink points elsewhere",
+        })
+    }
+}
+
+/// A drifted file, relative to the workspace root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Drift {
+    pub path: PathBuf,
+    pub kind: DriftKind,
+}
+
+/// Compares a workspace's surfaced files with their sources in its
+/// `.botminter/` clone, without changing anything: the copied `ralph.yml`
+/// and `settings.local.json`, the `PROMPT.md` and `CLAUDE.md` symlinks, and
+/// the `.claude/agents/` symlinks. With hard links or copies, `PROMPT.md`
+/// and `CLAUDE.md` are compared by content instead, and `.claude/agents/`,
+/// which every sync rebuilds, is not checked.
+pub fn detect_drift(ws_root: &Path, member_dir_name: &str, link_mode: LinkMode) -> Vec<Drift> {
+    let member_bm = ws_root
+        .join(".botminter")
+        .join("team")
+        .join(member_dir_name);
+    let mut drift = Vec::new();
+    let mut push = |path: &str, kind: DriftKind| {
+        drift.push(Drift {
+            path: PathBuf::from(path),
+            kind,
+        })
+    };
+
+    let mut copied = vec![
+        ("ralph.yml", member_bm.join("ralph.yml")),
+        (
+            ".claude/settings.local.json",
+            member_bm.join("agent").join("settings.local.json"),
+        ),
+    ];
+    if link_mode != LinkMode::Symlink {
+        copied.push(("PROMPT.md", member_bm.join("PROMPT.md")));
+        copied.push(("CLAUDE.md", member_bm.join("CLAUDE.md")));
+    }
+    for (rel, src) in copied {
+        let Ok(expected) = fs::read(&src) else {
+            continue;
+        };
+        match fs::read(ws_root.join(rel)) {
+            Ok(actual) if actual == expected => {}
+            Ok(_) => push(rel, DriftKind::Modified),
+            Err(_) => push(rel, DriftKind::Missing),
+        }
+    }
+
+    if link_mode != LinkMode::Symlink {
+        return drift;
+    }
+    for name in ["PROMPT.md", "CLAUDE.md"] {
+        let src = member_bm.join(name);
+        if !src.exists() {
+            continue;
+        }
+        if let Some(kind) = symlink_drift(&ws_root.join(name), Some(&src)) {
+            push(name, kind);
+        }
+    }
+
+    let agents = ws_root.join(".claude").join("agents");
+    let mut entries: Vec<PathBuf> = fs::read_dir(&agents)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("md"))
+        .collect();
+    entries.sort();
+    for path in entries {
+        if let Some(kind) = symlink_drift(&path, None) {
+            let rel = path.strip_prefix(ws_root).unwrap_or(&path);
+            push(&rel.to_string_lossy(), kind);
+        }
+    }
+    drift
+}
+
+/// Lists the surfaced symlinks in a workspace (root files and
+/// `.claude/agents/`) with their targets and whether each resolves.
+pub fn list_symlinks(ws_root: &Path) -> Vec<(PathBuf, PathBuf, bool)> {
+    let mut links = Vec::new();
+    for dir in [ws_root.to_path_buf(), ws_root.join(".claude").join("agents")] {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if let Ok(target) = fs::read_link(&path) {
+                let rel = path.strip_prefix(ws_root).unwrap_or(&path).to_path_buf();
+                links.push((rel, target, path.exists()));
+            }
+        }
+    }
+    links.sort();
+    links
+}
+
 // ── Private helpers ──────────────────────────────────────────────────
 
-/// Symlinks all `.md` files from `src_dir` into `dst_dir` using relative paths.
+/// How the symlink at `link` has drifted, if at all. With `expected`, it
+/// must also resolve to that file.
+fn symlink_drift(link: &Path, expected: Option<&Path>) -> Option<DriftKind> {
+    let Ok(meta) = link.symlink_metadata() else {
+        return Some(DriftKind::Missing);
+    };
+    if !meta.file_type().is_symlink() {
+        return Some(DriftKind::NotSymlink);
+    }
+    let Ok(resolved) = fs::canonicalize(link) else {
+        return Some(DriftKind::Broken);
+    };
+    match expected.map(fs::canonicalize) {
+        Some(Ok(target)) if target != resolved => Some(DriftKind::WrongTarget),
+        _ => None,
+    }
+}
+
+/// Symlinks all `.md` files from `src_dir` into `dst_dir` using relative paths,
+/// or hard-links or copies them per `link_mode`.
 /// Silently returns Ok if `src_dir` does not exist.
-fn symlink_md_files(src_dir: &Path, dst_dir: &Path) -> Result<()> {
+fn link_md_files(src_dir: &Path, dst_dir: &Path, link_mode: LinkMode) -> Result<()> {
     if !src_dir.is_dir() {
         return Ok(());
     }
@@ -366,11 +694,15 @@ fn symlink_md_files(src_dir: &Path, dst_dir: &Path) -> Result<()> {
         if path.extension().and_then(|e| e.to_str()) == Some("md") {
             let filename = path.file_name().unwrap();
             let dst = dst_dir.join(filename);
+            if link_mode != LinkMode::Symlink {
+                place_file(&path, &dst, link_mode)?;
+                continue;
+            }
             if dst.symlink_metadata().is_ok() {
                 fs::remove_file(&dst).ok();
             }
             let rel_target = rel.join(filename);
-            unix_fs::symlink(&rel_target, &dst).with_context(|| {
+            symlink(&rel_target, &dst).with_context(|| {
                 format!("Failed to symlink {} → {}", dst.display(), rel_target.display())
             })?;
         }
@@ -424,7 +756,7 @@ fn create_symlink(target: &Path, link_path: &Path) -> Result<()> {
     if link_path.symlink_metadata().is_ok() {
         fs::remove_file(link_path).ok();
     }
-    unix_fs::symlink(target, link_path).with_context(|| {
+    symlink(target, link_path).with_context(|| {
         format!(
             "Failed to symlink {} → {}",
             link_path.display(),
@@ -433,6 +765,84 @@ fn create_symlink(target: &Path, link_path: &Path) -> Result<()> {
     })
 }
 
+/// Puts `src` at `dst` as a hard link or a copy, replacing whatever is there.
+fn place_file(src: &Path, dst: &Path, link_mode: LinkMode) -> Result<()> {
+    if dst.symlink_metadata().is_ok() {
+        fs::remove_file(dst).ok();
+    }
+    let placed = match link_mode {
+        LinkMode::Hardlink => fs::hard_link(src, dst),
+        LinkMode::Copy | LinkMode::Symlink => fs::copy(src, dst).map(|_| ()),
+    };
+    placed.with_context(|| {
+        format!(
+            "Failed to {} {} → {}",
+            if link_mode == LinkMode::Hardlink {
+                "hard-link"
+            } else {
+                "copy"
+            },
+            dst.display(),
+            src.display()
+        )
+    })
+}
+
+/// Whether the hard link or copy at `dst` no longer matches `src`: missing,
+/// a symlink left from another mode, a link to a file a pull replaced, or a
+/// copy whose hash differs.
+fn is_stale(dst: &Path, src: &Path, link_mode: LinkMode) -> bool {
+    let Ok(meta) = dst.symlink_metadata() else {
+        return true;
+    };
+    if meta.file_type().is_symlink() {
+        return true;
+    }
+    match link_mode {
+        LinkMode::Hardlink => match fs::metadata(src) {
+            Ok(src_meta) => match same_file(&src_meta, &meta) {
+                Some(same) => !same,
+                None => contents_differ(dst, src),
+            },
+            Err(_) => true,
+        },
+        _ => contents_differ(dst, src),
+    }
+}
+
+fn contents_differ(dst: &Path, src: &Path) -> bool {
+    match (fs::read(dst), fs::read(src)) {
+        (Ok(a), Ok(b)) => profile_checksums::checksum(&a) != profile_checksums::checksum(&b),
+        _ => true,
+    }
+}
+
+/// Whether two entries are hard links to the same file. `None` on Windows,
+/// where std can't tell, so callers compare contents instead.
+#[cfg(unix)]
+fn same_file(a: &fs::Metadata, b: &fs::Metadata) -> Option<bool> {
+    use std::os::unix::fs::MetadataExt;
+    Some((a.dev(), a.ino()) == (b.dev(), b.ino()))
+}
+
+#[cfg(not(unix))]
+fn same_file(_a: &fs::Metadata, _b: &fs::Metadata) -> Option<bool> {
+    None
+}
+
+/// Creates the symlink `link` → `target`. Surfaced files are all files, so
+/// on Windows this makes file symlinks (which need Developer Mode or admin
+/// rights; use `link_mode: copy` otherwise).
+#[cfg(unix)]
+fn symlink(target: impl AsRef<Path>, link: impl AsRef<Path>) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink(target: impl AsRef<Path>, link: impl AsRef<Path>) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
 /// Copies `src` to `dst` only if `src` exists and is newer than `dst`.
 fn copy_if_newer(src: &Path, dst: &Path) -> Result<()> {
     if !src.exists() {
@@ -489,7 +899,7 @@ fn verify_symlink(link: &Path, expected_target: &Path) -> Result<()> {
         if link.symlink_metadata().is_ok() {
             fs::remove_file(link).ok();
         }
-        unix_fs::symlink(&rel, link)
+        symlink(&rel, link)
             .with_context(|| format!("Failed to re-create symlink {}", link.display()))?;
     }
     Ok(())
@@ -511,7 +921,7 @@ fn git_cmd(dir: &Path, args: &[&str]) -> Result<()> {
 }
 
 /// Runs a git command and returns stdout as a String.
-fn git_cmd_output(dir: &Path, args: &[&str]) -> Result<String> {
+pub(crate) fn git_cmd_output(dir: &Path, args: &[&str]) -> Result<String> {
     let output = Command::new("git")
         .args(args)
         .current_dir(dir)
@@ -556,7 +966,7 @@ mod tests {
         fs::create_dir_all(&member_agents).unwrap();
         fs::write(member_agents.join("member-agent.md"), "# Member").unwrap();
 
-        assemble_claude_dir(&ws, "arch-01", None).unwrap();
+        assemble_claude_dir(&ws, "arch-01", None, LinkMode::Symlink).unwrap();
 
         let agents = ws.join(".claude/agents");
         assert!(agents.exists());
@@ -590,7 +1000,7 @@ mod tests {
         fs::write(member.join("CLAUDE.md"), "# Claude").unwrap();
         fs::write(member.join("ralph.yml"), "version: 1").unwrap();
 
-        surface_files(&ws, "arch-01").unwrap();
+        surface_files(&ws, "arch-01", LinkMode::Symlink).unwrap();
 
         // PROMPT.md and CLAUDE.md should be symlinks
         assert!(ws
@@ -642,7 +1052,15 @@ mod tests {
         git_cmd(&team_repo, &["add", "-f", "-A"]).unwrap();
         git_cmd(&team_repo, &["commit", "-m", "init"]).unwrap();
 
-        create_workspace(&team_repo, &workspace_base, "arch-01", None, None).unwrap();
+        create_workspace(
+            &team_repo,
+            &workspace_base,
+            "arch-01",
+            None,
+            None,
+            LinkMode::Symlink,
+        )
+        .unwrap();
 
         let ws = workspace_base.join("arch-01");
         assert!(ws.join(".botminter").is_dir());
@@ -691,6 +1109,7 @@ mod tests {
             "arch-01",
             None,
             Some("myorg/my-team"),
+            LinkMode::Symlink,
         )
         .unwrap();
 
@@ -724,7 +1143,15 @@ mod tests {
         git_cmd(&team_repo, &["add", "-f", "-A"]).unwrap();
         git_cmd(&team_repo, &["commit", "-m", "init"]).unwrap();
 
-        create_workspace(&team_repo, &workspace_base, "arch-01", None, None).unwrap();
+        create_workspace(
+            &team_repo,
+            &workspace_base,
+            "arch-01",
+            None,
+            None,
+            LinkMode::Symlink,
+        )
+        .unwrap();
 
         let ws = workspace_base.join("arch-01");
         let bm_dir = ws.join(".botminter");
@@ -754,7 +1181,15 @@ mod tests {
         );
 
         // Sync with github_repo — should fix the remote
-        sync_workspace(&ws, &member, None, false, Some("myorg/my-team")).unwrap();
+        sync_workspace(
+            &ws,
+            &member,
+            None,
+            false,
+            Some("myorg/my-team"),
+            LinkMode::Symlink,
+        )
+        .unwrap();
 
         let fixed_url =
             git_cmd_output(&bm_dir, &["remote", "get-url", "origin"]).unwrap();
@@ -778,11 +1213,11 @@ mod tests {
         fs::write(member.join("ralph.yml"), "v: 1").unwrap();
 
         // First call
-        surface_files(&ws, "dev-01").unwrap();
+        surface_files(&ws, "dev-01", LinkMode::Symlink).unwrap();
         let target_1 = fs::read_link(ws.join("PROMPT.md")).unwrap();
 
         // Second call — should succeed without error
-        surface_files(&ws, "dev-01").unwrap();
+        surface_files(&ws, "dev-01", LinkMode::Symlink).unwrap();
         let target_2 = fs::read_link(ws.join("PROMPT.md")).unwrap();
 
         assert_eq!(target_1, target_2, "Symlink target unchanged after re-surface");
@@ -802,10 +1237,10 @@ mod tests {
         // Create a wrong symlink manually
         let wrong_target = tmp.path().join("wrong.md");
         fs::write(&wrong_target, "# Wrong").unwrap();
-        unix_fs::symlink(&wrong_target, ws.join("PROMPT.md")).unwrap();
+        symlink(&wrong_target, ws.join("PROMPT.md")).unwrap();
 
         // surface_files should replace the wrong symlink
-        surface_files(&ws, "dev-01").unwrap();
+        surface_files(&ws, "dev-01", LinkMode::Symlink).unwrap();
 
         assert_eq!(
             fs::read_to_string(ws.join("PROMPT.md")).unwrap(),
@@ -844,7 +1279,7 @@ mod tests {
         // Create a broken symlink (pointing to a non-existent path)
         let link = tmp.path().join("link.md");
         let ghost = tmp.path().join("ghost.md");
-        unix_fs::symlink(&ghost, &link).unwrap();
+        symlink(&ghost, &link).unwrap();
 
         // Link exists as symlink but is broken (ghost doesn't exist)
         assert!(link.symlink_metadata().is_ok(), "Symlink metadata readable");
@@ -868,7 +1303,7 @@ mod tests {
         fs::write(&wrong, "# Wrong").unwrap();
 
         let link = tmp.path().join("link.md");
-        unix_fs::symlink(&wrong, &link).unwrap();
+        symlink(&wrong, &link).unwrap();
         assert_eq!(fs::read_to_string(&link).unwrap(), "# Wrong");
 
         verify_symlink(&link, &correct).unwrap();
@@ -885,6 +1320,13 @@ mod tests {
     /// Helper: create a minimal workspace with .botminter/ as a git repo
     /// so sync_workspace can operate without external deps.
     fn setup_syncable_workspace(tmp: &Path) -> (std::path::PathBuf, String) {
+        setup_syncable_workspace_with(tmp, LinkMode::Symlink)
+    }
+
+    fn setup_syncable_workspace_with(
+        tmp: &Path,
+        link_mode: LinkMode,
+    ) -> (std::path::PathBuf, String) {
         let member = "dev-01";
         let team_repo = tmp.join("team_repo");
         let member_cfg = team_repo.join("team").join(member);
@@ -901,7 +1343,15 @@ mod tests {
 
         let workspace_base = tmp.join("workzone");
         fs::create_dir_all(&workspace_base).unwrap();
-        create_workspace(&team_repo, &workspace_base, member, None, None).unwrap();
+        create_workspace(
+            &team_repo,
+            &workspace_base,
+            member,
+            None,
+            None,
+            link_mode,
+        )
+        .unwrap();
 
         let ws = workspace_base.join(member);
         (ws, member.to_string())
@@ -933,7 +1383,7 @@ mod tests {
         );
         filetime::set_file_mtime(&source, now).unwrap();
 
-        sync_workspace(&ws, &member, None, false, None).unwrap();
+        sync_workspace(&ws, &member, None, false, None, LinkMode::Symlink).unwrap();
 
         assert_eq!(
             fs::read_to_string(ws.join("ralph.yml")).unwrap(),
@@ -942,6 +1392,88 @@ mod tests {
         );
     }
 
+    #[test]
+    fn detect_drift_flags_broken_and_missing_links() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (ws, member) = setup_syncable_workspace(tmp.path());
+        assert_eq!(detect_drift(&ws, &member, LinkMode::Symlink), Vec::new());
+
+        symlink("../../nowhere.md", ws.join(".claude/agents/gone.md")).unwrap();
+        fs::remove_file(ws.join("CLAUDE.md")).unwrap();
+
+        assert_eq!(
+            detect_drift(&ws, &member, LinkMode::Symlink),
+            vec![
+                Drift {
+                    path: PathBuf::from("CLAUDE.md"),
+                    kind: DriftKind::Missing,
+                },
+                Drift {
+                    path: PathBuf::from(".claude/agents/gone.md"),
+                    kind: DriftKind::Broken,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn copy_mode_refreshes_stale_copies() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (ws, member) = setup_syncable_workspace_with(tmp.path(), LinkMode::Copy);
+        let member_agents = ws.join(".botminter/team").join(&member).join("agent/agents");
+        fs::create_dir_all(&member_agents).unwrap();
+        fs::write(member_agents.join("helper.md"), "# H").unwrap();
+        sync_workspace(&ws, &member, None, false, None, LinkMode::Copy).unwrap();
+
+        for rel in ["PROMPT.md", "CLAUDE.md", ".claude/agents/helper.md"] {
+            let meta = ws.join(rel).symlink_metadata().unwrap();
+            assert!(meta.file_type().is_file(), "{} should be a copy", rel);
+        }
+        assert_eq!(detect_drift(&ws, &member, LinkMode::Copy), Vec::new());
+
+        // An upstream change and a local edit both leave stale copies
+        let source = ws.join(".botminter/team").join(&member).join("PROMPT.md");
+        fs::write(&source, "# P v2").unwrap();
+        fs::write(ws.join("CLAUDE.md"), "# edited").unwrap();
+        let drifted: Vec<PathBuf> = detect_drift(&ws, &member, LinkMode::Copy)
+            .into_iter()
+            .map(|d| d.path)
+            .collect();
+        assert_eq!(drifted, vec![PathBuf::from("PROMPT.md"), PathBuf::from("CLAUDE.md")]);
+
+        sync_workspace(&ws, &member, None, false, None, LinkMode::Copy).unwrap();
+        assert_eq!(fs::read_to_string(ws.join("PROMPT.md")).unwrap(), "# P v2");
+        assert_eq!(fs::read_to_string(ws.join("CLAUDE.md")).unwrap(), "# C");
+        assert_eq!(detect_drift(&ws, &member, LinkMode::Copy), Vec::new());
+    }
+
+    #[test]
+    fn hardlink_mode_relinks_replaced_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (ws, member) = setup_syncable_workspace_with(tmp.path(), LinkMode::Hardlink);
+        let source = ws.join(".botminter/team").join(&member).join("PROMPT.md");
+        let same_file = |a: &Path, b: &Path| {
+            super::same_file(&fs::metadata(a).unwrap(), &fs::metadata(b).unwrap()).unwrap()
+        };
+        assert!(same_file(&ws.join("PROMPT.md"), &source));
+        assert!(!ws.join("PROMPT.md").symlink_metadata().unwrap().file_type().is_symlink());
+
+        // A pull writes a new file in place of the linked one
+        fs::remove_file(&source).unwrap();
+        fs::write(&source, "# P v2").unwrap();
+        assert!(!same_file(&ws.join("PROMPT.md"), &source));
+
+        sync_workspace(&ws, &member, None, false, None, LinkMode::Hardlink).unwrap();
+        assert!(same_file(&ws.join("PROMPT.md"), &source));
+        assert_eq!(fs::read_to_string(ws.join("PROMPT.md")).unwrap(), "# P v2");
+
+        // Switching a symlinked workspace to copies replaces its links
+        sync_workspace(&ws, &member, None, false, None, LinkMode::Symlink).unwrap();
+        assert!(ws.join("PROMPT.md").symlink_metadata().unwrap().file_type().is_symlink());
+        sync_workspace(&ws, &member, None, false, None, LinkMode::Copy).unwrap();
+        assert!(ws.join("PROMPT.md").symlink_metadata().unwrap().file_type().is_file());
+    }
+
     #[test]
     fn sync_reassembles_claude_dir() {
         let tmp = tempfile::tempdir().unwrap();
@@ -962,7 +1494,7 @@ mod tests {
         fs::create_dir_all(&member_agents).unwrap();
         fs::write(member_agents.join("new-agent.md"), "# New Agent").unwrap();
 
-        sync_workspace(&ws, &member, None, false, None).unwrap();
+        sync_workspace(&ws, &member, None, false, None, LinkMode::Symlink).unwrap();
 
         let new_count = fs::read_dir(&agents_dir)
             .unwrap()
@@ -986,8 +1518,8 @@ mod tests {
         let (ws, member) = setup_syncable_workspace(tmp.path());
 
         // Run sync twice
-        sync_workspace(&ws, &member, None, false, None).unwrap();
-        sync_workspace(&ws, &member, None, false, None).unwrap();
+        sync_workspace(&ws, &member, None, false, None, LinkMode::Symlink).unwrap();
+        sync_workspace(&ws, &member, None, false, None, LinkMode::Symlink).unwrap();
 
         // Verify workspace is still correct
         assert!(ws.join("PROMPT.md").exists());
@@ -997,6 +1529,84 @@ mod tests {
         assert_eq!(fs::read_to_string(ws.join("PROMPT.md")).unwrap(), "# P");
     }
 
+    // ── git_summary / list_symlinks ───────────────────────────────────
+
+    #[test]
+    fn git_summary_reports_branch_and_dirty_state() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path();
+        assert_eq!(git_summary(ws), None);
+
+        git_cmd(ws, &["init", "-b", "arch-01"]).unwrap();
+        let summary = git_summary(ws).unwrap();
+        assert_eq!(summary.to_string(), "arch-01, clean, no upstream");
+
+        fs::write(ws.join("file.txt"), "x").unwrap();
+        assert!(git_summary(ws).unwrap().dirty);
+    }
+
+    #[test]
+    fn push_wip_commits_and_keeps_off_the_default_branch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let remote = tmp.path().join("remote.git");
+        let ws = tmp.path().join("ws");
+        fs::create_dir_all(&ws).unwrap();
+        git_cmd(tmp.path(), &["init", "--bare", "-b", "main", "remote.git"]).unwrap();
+        git_cmd(&ws, &["init", "-b", "main"]).unwrap();
+        git_cmd(&ws, &["config", "user.email", "t@t"]).unwrap();
+        git_cmd(&ws, &["config", "user.name", "t"]).unwrap();
+        git_cmd(&ws, &["remote", "add", "origin", &remote.to_string_lossy()]).unwrap();
+        fs::write(ws.join("a.txt"), "a").unwrap();
+        git_cmd(&ws, &["add", "-A"]).unwrap();
+        git_cmd(&ws, &["commit", "-m", "init"]).unwrap();
+        git_cmd(&ws, &["push", "-u", "origin", "main"]).unwrap();
+        assert_eq!(push_wip(&ws, "dev-bob", "wip").unwrap(), WipPush::Clean);
+
+        fs::write(ws.join("b.txt"), "b").unwrap();
+        assert_eq!(
+            push_wip(&ws, "dev-bob", "wip: migrate").unwrap(),
+            WipPush::Pushed {
+                branch: "wip/dev-bob".to_string(),
+                committed: true
+            }
+        );
+        let log = git_cmd_output(&remote, &["log", "--format=%s", "wip/dev-bob"]).unwrap();
+        assert_eq!(log.lines().next(), Some("wip: migrate"));
+        assert!(!git_summary(&ws).unwrap().dirty);
+
+        git_cmd(&ws, &["checkout", "-b", "feat/x"]).unwrap();
+        assert_eq!(
+            push_wip(&ws, "dev-bob", "wip").unwrap(),
+            WipPush::Pushed {
+                branch: "feat/x".to_string(),
+                committed: false
+            }
+        );
+    }
+
+    #[test]
+    fn list_symlinks_flags_broken_links() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path();
+        fs::create_dir_all(ws.join(".claude/agents")).unwrap();
+        fs::write(ws.join("real.md"), "x").unwrap();
+        symlink("real.md", ws.join("PROMPT.md")).unwrap();
+        symlink("../../gone.md", ws.join(".claude/agents/a.md")).unwrap();
+
+        let links = list_symlinks(ws);
+        assert_eq!(
+            links,
+            vec![
+                (
+                    PathBuf::from(".claude/agents/a.md"),
+                    PathBuf::from("../../gone.md"),
+                    false
+                ),
+                (PathBuf::from("PROMPT.md"), PathBuf::from("real.md"), true),
+            ]
+        );
+    }
+
     // ── copy_if_newer ───────────────────────────────────────────────
 
     #[test]
@@ -1102,7 +1712,7 @@ mod tests {
         fs::create_dir_all(&member_agents).unwrap();
         fs::write(member_agents.join("member-only.md"), "# Member").unwrap();
 
-        assemble_claude_dir(&ws, "arch-01", Some("myproj")).unwrap();
+        assemble_claude_dir(&ws, "arch-01", Some("myproj"), LinkMode::Symlink).unwrap();
 
         let agents = ws.join(".claude/agents");
         assert!(agents.join("team-wide.md").exists(), "Team agent missing");
@@ -1142,7 +1752,7 @@ mod tests {
         )
         .unwrap();
 
-        assemble_claude_dir(&ws, "dev-01", None).unwrap();
+        assemble_claude_dir(&ws, "dev-01", None, LinkMode::Symlink).unwrap();
 
         let dst = ws.join(".claude/settings.local.json");
         assert!(dst.exists(), "settings.local.json should be copied");
@@ -1164,7 +1774,7 @@ mod tests {
         fs::create_dir_all(&ws).unwrap();
 
         // No .botminter/ agent dirs at all
-        assemble_claude_dir(&ws, "dev-01", None).unwrap();
+        assemble_claude_dir(&ws, "dev-01", None, LinkMode::Symlink).unwrap();
 
         let agents = ws.join(".claude/agents");
         assert!(agents.is_dir(), ".claude/agents/ should exist even with no agents");
@@ -1183,7 +1793,7 @@ mod tests {
         let ws = tmp.path().join("workspace");
         fs::create_dir_all(ws.join(".git")).unwrap();
 
-        write_git_exclude(&ws).unwrap();
+        write_git_exclude(&ws, &[]).unwrap();
 
         let exclude = ws.join(".git/info/exclude");
         assert!(exclude.exists(), ".git/info/exclude should be created");
@@ -1206,7 +1816,7 @@ mod tests {
         // No .git/ directory
 
         // Should return Ok without error
-        write_git_exclude(&ws).unwrap();
+        write_git_exclude(&ws, &[]).unwrap();
 
         // No .git/info/exclude should have been created
         assert!(!ws.join(".git").exists());
@@ -0,0 +1,235 @@
+//! Role-declared workspace artifacts, rendered at sync time.
+//!
+//! A role skeleton lists extra files for its members' workspaces in its
+//! `.botminter.yml`, which `bm hire` carries into the member's `botminter.yml`:
+//!
+//! ```yaml
+//! workspace_files:
+//!   - template: workspace/settings.json
+//!     path: .claude/settings.json
+//! ```
+//!
+//! `template` is relative to the member dir; `path` is relative to the
+//! workspace root. `{{ variable }}` placeholders are replaced on every
+//! `bm teams sync`, so edit the template rather than the generated file.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::parse;
+
+/// Workspace paths bm manages itself; templates can't overwrite them.
+const RESERVED: &[&str] = &[".botminter", "PROMPT.md", "CLAUDE.md", "ralph.yml", ".git"];
+
+/// One declared artifact.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct WorkspaceFile {
+    /// Template path, relative to the member dir.
+    pub template: String,
+    /// Output path, relative to the workspace root.
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct MemberManifest {
+    name: Option<String>,
+    role: Option<String>,
+    #[serde(default)]
+    workspace_files: Vec<WorkspaceFile>,
+}
+
+fn read_manifest(member_dir: &Path) -> Result<MemberManifest> {
+    let path = member_dir.join("botminter.yml");
+    if !path.exists() {
+        return Ok(MemberManifest::default());
+    }
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(parse::yaml(&path, &contents)?)
+}
+
+/// Replaces `{{ name }}` placeholders with `vars`. Unknown names and
+/// unclosed placeholders are errors, so typos don't ship silently.
+pub fn render(template: &str, vars: &BTreeMap<&str, String>) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            bail!("Unclosed '{{{{' in template");
+        };
+        let name = after[..end].trim();
+        match vars.get(name) {
+            Some(value) => out.push_str(value),
+            None => {
+                let known: Vec<&str> = vars.keys().copied().collect();
+                bail!(
+                    "Unknown template variable '{}'. Available: {}",
+                    name,
+                    known.join(", ")
+                );
+            }
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Checks that `path` stays inside the workspace and off bm-managed files.
+fn check_output_path(path: &str) -> Result<()> {
+    let p = Path::new(path);
+    let inside = !path.is_empty()
+        && p.components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if !inside {
+        bail!("must be a relative path inside the workspace");
+    }
+    let first = p
+        .components()
+        .find_map(|c| match c {
+            Component::Normal(s) => Some(s.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .unwrap_or_default();
+    if RESERVED.contains(&first.as_str()) {
+        bail!("'{}' is managed by bm", first);
+    }
+    Ok(())
+}
+
+/// Renders the member's declared workspace files into `ws_root`, writing only
+/// those whose contents changed. Returns the output paths, for git excludes.
+pub fn render_workspace_files(
+    ws_root: &Path,
+    member_dir_name: &str,
+    project_name: Option<&str>,
+    github_repo: Option<&str>,
+) -> Result<Vec<String>> {
+    let bm_dir = ws_root.join(".botminter");
+    let member_dir = bm_dir.join("team").join(member_dir_name);
+    let manifest = read_manifest(&member_dir)?;
+    if manifest.workspace_files.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let vars: BTreeMap<&str, String> = BTreeMap::from([
+        ("member", manifest.name.clone().unwrap_or_default()),
+        ("member_dir", member_dir_name.to_string()),
+        ("role", manifest.role.clone().unwrap_or_default()),
+        ("project", project_name.unwrap_or_default().to_string()),
+        ("github_repo", github_repo.unwrap_or_default().to_string()),
+        ("workspace", ws_root.to_string_lossy().to_string()),
+        ("team_repo", bm_dir.to_string_lossy().to_string()),
+    ]);
+
+    let mut written = Vec::new();
+    for file in &manifest.workspace_files {
+        check_output_path(&file.path).with_context(|| {
+            format!(
+                "Invalid workspace_files path '{}' in {}/botminter.yml",
+                file.path, member_dir_name
+            )
+        })?;
+        let src = member_dir.join(&file.template);
+        let template = fs::read_to_string(&src).with_context(|| {
+            format!(
+                "Failed to read workspace template {} (declared in {}/botminter.yml)",
+                src.display(),
+                member_dir_name
+            )
+        })?;
+        let rendered = render(&template, &vars)
+            .with_context(|| format!("Failed to render {}", src.display()))?;
+
+        let dst = ws_root.join(&file.path);
+        if fs::read_to_string(&dst).ok().as_deref() != Some(rendered.as_str()) {
+            if let Some(dir) = dst.parent() {
+                fs::create_dir_all(dir)
+                    .with_context(|| format!("Failed to create {}", dir.display()))?;
+            }
+            fs::write(&dst, rendered)
+                .with_context(|| format!("Failed to write {}", dst.display()))?;
+        }
+        written.push(file.path.clone());
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> BTreeMap<&'static str, String> {
+        BTreeMap::from([("member", "bob".to_string()), ("role", "dev".to_string())])
+    }
+
+    #[test]
+    fn render_substitutes_and_rejects_unknown() {
+        assert_eq!(
+            render("{\"who\": \"{{member}}\", \"as\": \"{{ role }}\"}", &vars()).unwrap(),
+            "{\"who\": \"bob\", \"as\": \"dev\"}"
+        );
+        let err = render("{{ membr }}", &vars()).unwrap_err().to_string();
+        assert!(err.contains("Unknown template variable 'membr'"), "{}", err);
+        assert!(err.contains("member, role"), "{}", err);
+        assert!(render("x {{ member", &vars()).is_err());
+    }
+
+    #[test]
+    fn output_paths_stay_in_workspace() {
+        assert!(check_output_path(".claude/settings.json").is_ok());
+        assert!(check_output_path(".mcp.json").is_ok());
+        for bad in [
+            "",
+            "../x",
+            "/etc/x",
+            ".botminter/x",
+            "PROMPT.md",
+            "./ralph.yml",
+        ] {
+            assert!(
+                check_output_path(bad).is_err(),
+                "{} should be rejected",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn render_workspace_files_writes_declared_outputs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path();
+        let member = ws.join(".botminter/team/dev-bob");
+        fs::create_dir_all(member.join("workspace")).unwrap();
+        fs::write(
+            member.join("botminter.yml"),
+            "role: dev\nname: bob\nworkspace_files:\n  \
+             - template: workspace/mcp.json\n    path: .mcp.json\n",
+        )
+        .unwrap();
+        fs::write(
+            member.join("workspace/mcp.json"),
+            "{\"repo\": \"{{github_repo}}\", \"dir\": \"{{member_dir}}\", \"p\": \"{{project}}\"}\n",
+        )
+        .unwrap();
+
+        let written = render_workspace_files(ws, "dev-bob", Some("app"), Some("org/team")).unwrap();
+        assert_eq!(written, vec![".mcp.json"]);
+        assert_eq!(
+            fs::read_to_string(ws.join(".mcp.json")).unwrap(),
+            "{\"repo\": \"org/team\", \"dir\": \"dev-bob\", \"p\": \"app\"}\n"
+        );
+
+        // Members without declarations get nothing
+        fs::create_dir_all(ws.join(".botminter/team/qe-amy")).unwrap();
+        assert!(render_workspace_files(ws, "qe-amy", None, None)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -58,7 +58,7 @@ fn aliases_shown_in_help() {
     );
 }
 
-// ── Flag parsing (5 tests) ───────────────────────────────────────────
+// ── Flag parsing (7 tests) ───────────────────────────────────────────
 
 #[test]
 fn team_flag_short_and_long() {
@@ -108,6 +108,69 @@ fn force_flag_on_stop() {
     }
 }
 
+#[test]
+fn drain_flags_on_stop() {
+    let output = bm()
+        .args(["stop", "--drain", "--graceful-timeout", "5"])
+        .output()
+        .unwrap();
+    assert_ne!(
+        output.status.code().unwrap_or(-1),
+        CLAP_PARSE_ERROR_CODE,
+        "`bm stop --drain --graceful-timeout 5` should parse, stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = bm().args(["stop", "--drain", "--force"]).output().unwrap();
+    assert_eq!(
+        output.status.code().unwrap_or(-1),
+        CLAP_PARSE_ERROR_CODE,
+        "`bm stop --drain --force` should be a parse error"
+    );
+}
+
+#[test]
+fn all_flag_on_stop() {
+    let output = bm().args(["stop", "--all", "--drain"]).output().unwrap();
+    assert_ne!(
+        output.status.code().unwrap_or(-1),
+        CLAP_PARSE_ERROR_CODE,
+        "`bm stop --all --drain` should parse, stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = bm().args(["stop", "--all", "-t", "myteam"]).output().unwrap();
+    assert_eq!(
+        output.status.code().unwrap_or(-1),
+        CLAP_PARSE_ERROR_CODE,
+        "`bm stop --all -t myteam` should be a parse error"
+    );
+}
+
+#[test]
+fn migrate_flag_on_start_requires_formation() {
+    let output = bm()
+        .args(["start", "--formation", "k8s", "--migrate"])
+        .output()
+        .unwrap();
+    assert_ne!(
+        output.status.code().unwrap_or(-1),
+        CLAP_PARSE_ERROR_CODE,
+        "`bm start --formation k8s --migrate` should parse, stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    for args in [&["start", "--migrate"][..], &["start", "--formation", "k8s", "--migrate", "--dry-run"]] {
+        let output = bm().args(args).output().unwrap();
+        assert_eq!(
+            output.status.code().unwrap_or(-1),
+            CLAP_PARSE_ERROR_CODE,
+            "`bm {}` should be a parse error",
+            args.join(" ")
+        );
+    }
+}
+
 #[test]
 fn push_flag_on_sync() {
     let output = bm().args(["teams", "sync", "--push"]).output().unwrap();
@@ -119,6 +182,20 @@ fn push_flag_on_sync() {
     );
 }
 
+#[test]
+fn status_interval_requires_watch() {
+    let output = bm().args(["status", "--interval", "5"]).output().unwrap();
+    assert_eq!(
+        output.status.code().unwrap_or(-1),
+        CLAP_PARSE_ERROR_CODE,
+        "`bm status --interval` without --watch should be a parse error"
+    );
+
+    let help = bm().args(["status", "--help"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&help.stdout);
+    assert!(stdout.contains("--watch"), "help should list --watch:\n{}", stdout);
+}
+
 #[test]
 fn verbose_flag_on_status() {
     // -v and --verbose are both defined via #[arg(short, long)] on Status
@@ -152,7 +229,7 @@ fn name_flag_on_hire() {
     );
 }
 
-// ── Required arguments (3 tests) ─────────────────────────────────────
+// ── Required arguments (5 tests) ─────────────────────────────────────
 
 #[test]
 fn hire_requires_role_argument() {
@@ -207,6 +284,23 @@ fn projects_add_requires_url() {
     );
 }
 
+#[test]
+fn webhooks_setup_requires_url() {
+    let output = bm().args(["webhooks", "setup"]).output().unwrap();
+    assert_eq!(
+        output.status.code(),
+        Some(CLAP_PARSE_ERROR_CODE),
+        "bm webhooks setup (no --url) should exit with clap error code 2"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--url"),
+        "error should mention the missing --url flag, stderr:\n{}",
+        stderr
+    );
+}
+
 #[test]
 fn projects_sync_help_works() {
     let output = bm().args(["projects", "sync", "--help"]).output().unwrap();
@@ -221,6 +315,8 @@ fn projects_sync_help_works() {
         "help should mention sync, stdout:\n{}",
         stdout
     );
+    assert!(stdout.contains("--create-views"), "stdout:\n{}", stdout);
+    assert!(stdout.contains("--check"), "stdout:\n{}", stdout);
 }
 
 // ── Unknown/invalid input (3 tests) ──────────────────────────────────
@@ -266,6 +362,25 @@ fn completions_requires_valid_shell() {
     );
 }
 
+#[test]
+fn completions_install_parsed() {
+    let output = bm()
+        .args(["completions", "install", "--shell", "notashell"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        output.status.code(),
+        Some(CLAP_PARSE_ERROR_CODE),
+        "install --shell should only accept known shells"
+    );
+
+    let output = bm().args(["completions"]).output().unwrap();
+    assert!(
+        !output.status.success(),
+        "bm completions without a shell should print help and exit non-zero"
+    );
+}
+
 // ── Help text (2 tests) ──────────────────────────────────────────────
 
 #[test]
@@ -325,7 +440,7 @@ fn subcommand_help_works() {
     );
 }
 
-// ── Daemon CLI parsing (4 tests) ─────────────────────────────────────
+// ── Daemon CLI parsing (6 tests) ─────────────────────────────────────
 
 #[test]
 fn daemon_subcommand_help() {
@@ -350,6 +465,10 @@ fn daemon_start_flags_parsed() {
         vec!["daemon", "start", "--mode", "webhook", "--port", "9999"],
         vec!["daemon", "start", "--interval", "30"],
         vec!["daemon", "start", "-t", "myteam"],
+        vec!["daemon", "start", "--bind", "127.0.0.1", "--path", "/hooks/github"],
+        vec!["daemon", "start", "--tls-cert", "c.pem", "--tls-key", "k.pem"],
+        vec!["daemon", "start", "--tunnel", "cloudflared"],
+        vec!["daemon", "start", "--insecure-no-signature"],
     ] {
         let output = bm()
             .args(&args)
@@ -366,19 +485,110 @@ fn daemon_start_flags_parsed() {
     }
 }
 
+#[test]
+fn daemon_tunnel_conflicts_with_insecure_no_signature() {
+    let tmp = tempfile::tempdir().unwrap();
+    let output = bm()
+        .args(["daemon", "start", "--tunnel", "ngrok", "--insecure-no-signature"])
+        .env("HOME", tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap_or(-1), CLAP_PARSE_ERROR_CODE);
+}
+
+#[test]
+fn daemon_tls_flags_require_each_other() {
+    let tmp = tempfile::tempdir().unwrap();
+    for args in [
+        vec!["daemon", "start", "--tls-cert", "c.pem"],
+        vec!["daemon", "restart", "--tls-key", "k.pem"],
+    ] {
+        let output = bm().args(&args).env("HOME", tmp.path()).output().unwrap();
+        assert_eq!(
+            output.status.code().unwrap_or(-1),
+            CLAP_PARSE_ERROR_CODE,
+            "`bm {}` should be a parse error",
+            args.join(" ")
+        );
+    }
+}
+
 #[test]
 fn daemon_stop_flags_parsed() {
     let tmp = tempfile::tempdir().unwrap();
+    for args in [
+        vec!["daemon", "stop", "-t", "myteam"],
+        vec!["daemon", "stop", "--drain-timeout", "60"],
+        vec!["daemon", "stop", "--no-drain"],
+    ] {
+        let output = bm().args(&args).env("HOME", tmp.path()).output().unwrap();
+        let code = output.status.code().unwrap_or(-1);
+        assert_ne!(
+            code, CLAP_PARSE_ERROR_CODE,
+            "`bm {}` should parse",
+            args.join(" ")
+        );
+    }
+
     let output = bm()
-        .args(["daemon", "stop", "-t", "myteam"])
+        .args(["daemon", "stop", "--no-drain", "--drain-timeout", "60"])
         .env("HOME", tmp.path())
         .output()
         .unwrap();
-    let code = output.status.code().unwrap_or(-1);
-    assert_ne!(
-        code, CLAP_PARSE_ERROR_CODE,
-        "daemon stop -t should parse"
-    );
+    assert_eq!(output.status.code().unwrap_or(-1), CLAP_PARSE_ERROR_CODE);
+}
+
+#[test]
+fn dry_run_flags_parsed() {
+    let tmp = tempfile::tempdir().unwrap();
+    for args in [
+        vec!["hire", "architect", "--dry-run"],
+        vec!["teams", "sync", "--dry-run", "--push"],
+        vec!["projects", "add", "https://github.com/o/r", "--dry-run"],
+        vec!["start", "--dry-run"],
+        vec!["daemon", "start", "--dry-run"],
+    ] {
+        let output = bm().args(&args).env("HOME", tmp.path()).output().unwrap();
+        let code = output.status.code().unwrap_or(-1);
+        assert_ne!(
+            code, CLAP_PARSE_ERROR_CODE,
+            "`bm {}` should parse",
+            args.join(" ")
+        );
+    }
+
+    let output = bm()
+        .args(["teams", "sync", "--dry-run", "--check"])
+        .env("HOME", tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap_or(-1), CLAP_PARSE_ERROR_CODE);
+}
+
+#[test]
+fn config_subcommands_parsed() {
+    let tmp = tempfile::tempdir().unwrap();
+    for args in [
+        vec!["config", "get", "timestamps"],
+        vec!["config", "get", "credentials.gh_token", "-t", "myteam"],
+        vec!["config", "set", "teams.myteam.credentials.gh_token", "ghp_x"],
+        vec!["config", "unset", "logs", "--team", "myteam"],
+    ] {
+        let output = bm().args(&args).env("HOME", tmp.path()).output().unwrap();
+        let code = output.status.code().unwrap_or(-1);
+        assert_ne!(
+            code, CLAP_PARSE_ERROR_CODE,
+            "`bm {}` should parse",
+            args.join(" ")
+        );
+    }
+
+    let output = bm()
+        .args(["config", "set", "timestamps"])
+        .env("HOME", tmp.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code().unwrap_or(-1), CLAP_PARSE_ERROR_CODE);
 }
 
 #[test]
@@ -396,6 +606,32 @@ fn daemon_status_flags_parsed() {
     );
 }
 
+#[test]
+fn all_flag_conflicts_with_team() {
+    let tmp = tempfile::tempdir().unwrap();
+    for args in [&["daemon", "status"][..], &["status"][..]] {
+        let mut with_all = args.to_vec();
+        with_all.push("--all");
+        let output = bm().args(&with_all).env("HOME", tmp.path()).output().unwrap();
+        assert_ne!(
+            output.status.code().unwrap_or(-1),
+            CLAP_PARSE_ERROR_CODE,
+            "`bm {}` should parse, stderr: {}",
+            with_all.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        with_all.extend(["-t", "myteam"]);
+        let output = bm().args(&with_all).output().unwrap();
+        assert_eq!(
+            output.status.code().unwrap_or(-1),
+            CLAP_PARSE_ERROR_CODE,
+            "`bm {}` should be a parse error",
+            with_all.join(" ")
+        );
+    }
+}
+
 // ── Show/describe subcommand parsing (6 tests) ───────────────────────
 
 #[test]
@@ -463,6 +699,30 @@ fn members_show_with_member_parses() {
     );
 }
 
+#[test]
+fn workspace_restore_requires_snapshot_id() {
+    let output = bm()
+        .args(["workspace", "restore", "dev-01"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        output.status.code(),
+        Some(CLAP_PARSE_ERROR_CODE),
+        "bm workspace restore (no snapshot id) should exit with clap error code 2"
+    );
+
+    let output = bm()
+        .args(["workspace", "snapshot", "dev-01", "--no-git-objects", "-t", "my-team"])
+        .output()
+        .unwrap();
+    let code = output.status.code().unwrap_or(-1);
+    assert_ne!(
+        code, CLAP_PARSE_ERROR_CODE,
+        "`bm workspace snapshot dev-01 --no-git-objects` should not be a parse error, stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
 #[test]
 fn projects_list_help_works() {
     let output = bm().args(["projects", "list", "--help"]).output().unwrap();
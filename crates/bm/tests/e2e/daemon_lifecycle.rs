@@ -4,6 +4,7 @@
 //! without needing Claude API access. They verify:
 //! - Start/stop lifecycle in both modes
 //! - Signal forwarding to children
+//! - Draining in-flight runs on stop
 //! - Per-member log files
 //! - Stale PID detection
 //! - Double-start rejection
@@ -55,6 +56,20 @@ case "$1" in
 esac
 "#;
 
+/// Finishing stub: works for a few seconds, records that it finished, exits.
+const STUB_RALPH_FINISHES: &str = r#"#!/bin/bash
+# Stub ralph that completes its run (for drain tests).
+case "$1" in
+  run)
+    sleep 3
+    touch "$PWD/.ralph-stub-finished"
+    ;;
+  *)
+    exit 0
+    ;;
+esac
+"#;
+
 /// Creates a stub `ralph` binary in a temp directory. Returns the directory path.
 fn create_stub_ralph(tmp: &Path, script: &str) -> PathBuf {
     let stub_dir = tmp.join("stub-bin");
@@ -109,17 +124,31 @@ fn setup_daemon_workspace(tmp: &Path, team_name: &str) -> (String, String) {
 
     // Write config
     let config = BotminterConfig {
+        version: bm::config::CONFIG_VERSION,
         workzone,
         default_team: Some(team_name.to_string()),
+        token_expiry_warn_days: None,
+        logs: None,
+        timestamps: None,
+        workspace: None,
+        state_backend: None,
         teams: vec![TeamEntry {
             name: team_name.to_string(),
             path: team_dir,
             profile: profile_name,
             github_repo: "test-org/test-repo".to_string(),
+            extra_repos: Vec::new(),
+            budget: None,
             credentials: Credentials {
                 gh_token: Some("ghp_test_token".to_string()),
                 telegram_bot_token: None,
+                telegram_chat_id: None,
+                slack_bot_token: None,
+                slack_channel: None,
                 webhook_secret: None,
+                github_app: None,
+                smtp: None,
+                notifiers: Vec::new(),
             },
         }],
     };
@@ -219,6 +248,7 @@ fn daemon_start_stop_webhook_lifecycle() {
         &stub_dir,
         &[
             "daemon", "start", "--mode", "webhook", "--port", port, "-t", &team_name,
+            "--insecure-no-signature",
         ],
     ));
     assert!(out.contains("Daemon started"), "Expected started: {}", out);
@@ -244,7 +274,7 @@ fn daemon_start_stop_webhook_lifecycle() {
     assert!(out.contains("Daemon stopped"), "Expected stopped: {}", out);
 }
 
-/// Start daemon → daemon launches stub ralph → `bm daemon stop` → both die.
+/// Start daemon → daemon launches stub ralph → `bm daemon stop --no-drain` → both die.
 #[test]
 fn daemon_stop_terminates_running_members() {
     let tmp = tempfile::tempdir().unwrap();
@@ -281,7 +311,7 @@ fn daemon_stop_terminates_running_members() {
     assert_cmd_success(&mut daemon_cmd(
         tmp.path(),
         &stub_dir,
-        &["daemon", "stop", "-t", &team_name],
+        &["daemon", "stop", "--no-drain", "-t", &team_name],
     ));
 
     // Daemon should be dead
@@ -315,6 +345,73 @@ fn daemon_stop_terminates_running_members() {
     }
 }
 
+/// Webhook event launches a member → `bm daemon stop` → the member finishes
+/// its run before the daemon exits.
+#[test]
+fn daemon_stop_drains_in_flight_run() {
+    let tmp = tempfile::tempdir().unwrap();
+    let stub_dir = create_stub_ralph(tmp.path(), STUB_RALPH_FINISHES);
+    let (team_name, member) = setup_daemon_workspace(tmp.path(), "e2e-drain");
+    let _guard = DaemonGuard::new(tmp.path(), &team_name, Some(&stub_dir));
+    fs::write(
+        tmp.path().join(format!(".botminter/daemon-{}.yml", team_name)),
+        "debounce_secs: 0\n",
+    )
+    .unwrap();
+
+    let port = "19501";
+    assert_cmd_success(&mut daemon_cmd(
+        tmp.path(),
+        &stub_dir,
+        &[
+            "daemon", "start", "--mode", "webhook", "--port", port, "-t", &team_name,
+            "--insecure-no-signature",
+        ],
+    ));
+    std::thread::sleep(Duration::from_millis(500));
+    let resp = reqwest::blocking::Client::new()
+        .post(format!("http://127.0.0.1:{}/webhook", port))
+        .header("X-GitHub-Event", "issues")
+        .body("{}")
+        .send()
+        .expect("webhook request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+
+    let workspace = tmp
+        .path()
+        .join("workspaces")
+        .join(&team_name)
+        .join(&member)
+        .join("workspace");
+    let member_log = tmp
+        .path()
+        .join(format!(".botminter/logs/member-{}-{}.log", team_name, member));
+    for _ in 0..50 {
+        if member_log.exists() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    let out = assert_cmd_success(&mut daemon_cmd(
+        tmp.path(),
+        &stub_dir,
+        &["daemon", "stop", "--drain-timeout", "60", "-t", &team_name],
+    ));
+    assert!(out.contains("Daemon stopped"), "Expected stopped: {}", out);
+    assert!(
+        workspace.join(".ralph-stub-finished").exists(),
+        "the member should finish its run before the daemon exits"
+    );
+
+    let runs = fs::read_to_string(
+        tmp.path()
+            .join(format!(".botminter/daemon-{}-runs.json", team_name)),
+    )
+    .unwrap();
+    assert!(!runs.contains("\"interrupted\""), "run was cut short: {}", runs);
+}
+
 /// Stub ralph ignores SIGTERM → daemon escalates to SIGKILL → processes die.
 #[test]
 fn daemon_stop_timeout_escalates_to_sigkill() {
@@ -345,7 +442,7 @@ fn daemon_stop_timeout_escalates_to_sigkill() {
     assert_cmd_success(&mut daemon_cmd(
         tmp.path(),
         &stub_dir,
-        &["daemon", "stop", "-t", &team_name],
+        &["daemon", "stop", "--no-drain", "-t", &team_name],
     ));
 
     // Daemon should be dead (SIGKILL escalation should have worked)
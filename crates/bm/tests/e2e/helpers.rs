@@ -1,32 +1,26 @@
 //! Shared helpers for E2E tests.
 
 use std::fs;
-use std::net::TcpStream;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+
+use bm::procutil;
 
 /// Creates a `Command` for the `bm` binary.
 pub fn bm_cmd() -> Command {
     Command::new(env!("CARGO_BIN_EXE_bm"))
 }
 
-/// Polls a TCP port until it accepts connections or the timeout expires.
+/// Polls a loopback TCP port until it accepts connections or the timeout
+/// expires.
 pub fn wait_for_port(port: u16, timeout: Duration) {
-    let start = Instant::now();
-    let addr = format!("127.0.0.1:{}", port);
-
-    while start.elapsed() < timeout {
-        if TcpStream::connect(&addr).is_ok() {
-            return;
-        }
-        std::thread::sleep(Duration::from_millis(100));
+    if !procutil::wait_for_port(([127, 0, 0, 1], port).into(), timeout) {
+        panic!(
+            "timeout waiting for port {} after {:?}",
+            port, timeout
+        );
     }
-
-    panic!(
-        "timeout waiting for port {} after {:?}",
-        port, timeout
-    );
 }
 
 /// Runs a command, asserts exit 0, returns stdout.
@@ -55,31 +49,16 @@ pub fn assert_cmd_fails(cmd: &mut Command) -> String {
 
 // ── Process helpers ─────────────────────────────────────────────────
 
-/// Checks if a process is alive using kill(pid, 0).
-pub fn is_alive(pid: u32) -> bool {
-    unsafe { libc::kill(pid as i32, 0) == 0 }
-}
-
-/// Force-kills a process. Used for test cleanup.
-pub fn force_kill(pid: u32) {
-    unsafe {
-        libc::kill(pid as i32, libc::SIGKILL);
-    }
-}
+pub use bm::procutil::{free_port, is_alive, kill as force_kill};
 
 /// Waits until a process exits, with timeout.
 pub fn wait_for_exit(pid: u32, timeout: Duration) {
-    let start = Instant::now();
-    while start.elapsed() < timeout {
-        if !is_alive(pid) {
-            return;
-        }
-        std::thread::sleep(Duration::from_millis(100));
+    if !procutil::wait_for_exit(pid, timeout) {
+        panic!(
+            "Process {} did not exit within {:?}",
+            pid, timeout
+        );
     }
-    panic!(
-        "Process {} did not exit within {:?}",
-        pid, timeout
-    );
 }
 
 // ── DaemonGuard ─────────────────────────────────────────────────────
@@ -107,9 +86,9 @@ impl DaemonGuard {
 
 impl Drop for DaemonGuard {
     fn drop(&mut self) {
-        // Try graceful stop via bm daemon stop
+        // Stop via bm daemon stop, without waiting for in-flight members
         let mut cmd = bm_cmd();
-        cmd.args(["daemon", "stop", "-t", &self.team_name])
+        cmd.args(["daemon", "stop", "--no-drain", "-t", &self.team_name])
             .env("HOME", &self.home);
         if let Some(ref stub_dir) = self.stub_dir {
             cmd.env(
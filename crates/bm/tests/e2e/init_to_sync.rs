@@ -135,13 +135,21 @@ fn setup_team_with_github(
 
     // Write config
     let config = BotminterConfig {
+        version: bm::config::CONFIG_VERSION,
         workzone,
         default_team: Some(team_name.to_string()),
+        token_expiry_warn_days: None,
+        logs: None,
+        timestamps: None,
+        workspace: None,
+        state_backend: None,
         teams: vec![TeamEntry {
             name: team_name.to_string(),
             path: team_dir,
             profile: profile_name.to_string(),
             github_repo: github_full_name.to_string(),
+            extra_repos: Vec::new(),
+            budget: None,
             credentials: Credentials::default(),
         }],
     };
@@ -571,13 +579,21 @@ fn e2e_projects_sync_status_and_views() {
     git(&team_repo, &["commit", "-m", "feat: init team repo"]);
 
     let config = BotminterConfig {
+        version: bm::config::CONFIG_VERSION,
         workzone,
         default_team: Some(team_name.to_string()),
+        token_expiry_warn_days: None,
+        logs: None,
+        timestamps: None,
+        workspace: None,
+        state_backend: None,
         teams: vec![TeamEntry {
             name: team_name.to_string(),
             path: team_dir,
             profile: "scrum-compact".to_string(),
             github_repo: github_repo.clone(),
+            extra_repos: Vec::new(),
+            budget: None,
             credentials: Credentials::default(),
         }],
     };
@@ -1,7 +1,7 @@
 //! End-to-end tests for the `bm` CLI.
 //!
 //! These tests exercise `bm` against real external services (GitHub, Telegram
-//! mock). They are feature-gated behind `--features e2e` and run serially
+//! mock, Slack mock). They are feature-gated behind `--features e2e` and run serially
 //! via `just e2e` (`--test-threads=1`).
 //!
 //! Prerequisites:
@@ -13,6 +13,7 @@ mod helpers;
 mod daemon_lifecycle;
 mod github;
 mod init_to_sync;
+mod slack;
 mod start_to_stop;
 mod telegram;
 
@@ -82,4 +83,17 @@ fn e2e_harness_smoke() {
     } else {
         eprintln!("SKIP: podman not available — skipping tg-mock smoke");
     }
+
+    // ── Part 3: Slack mock harness ───────────────────────────────────
+    let mock = slack::SlackMock::start();
+    mock.inject_message("C0SMOKE", "U0SMOKE", "hello from e2e smoke test");
+    let resp = reqwest::blocking::Client::new()
+        .post(format!("{}/chat.postMessage", mock.api_url()))
+        .bearer_auth("xoxb-smoke")
+        .form(&[("channel", "C0SMOKE"), ("text", "hi")])
+        .send()
+        .expect("failed to post to the Slack mock");
+    assert!(resp.status().is_success());
+    assert_eq!(mock.posted("C0SMOKE"), vec!["hi".to_string()]);
+    // Drop stops the server
 }
@@ -0,0 +1,216 @@
+//! Slack mock helpers — SlackMock lifecycle and inspection, the Slack
+//! counterpart of [`super::telegram::TgMock`].
+//!
+//! There is no maintained Slack Web API mock image, so this one runs in
+//! process on `tiny_http` and implements the methods bm and the stub ralph
+//! call:
+//!
+//! - `auth.test` — any `xoxb-` token is the bot `UBOT`
+//! - `chat.postMessage` — recorded, and added to the channel's history
+//! - `conversations.history` — messages after `oldest`, newest first
+//!
+//! Point bm at it with `BM_SLACK_API_URL` and members with
+//! `RALPH_SLACK_API_URL`.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde_json::{json, Value};
+
+/// Member id the mock gives every bot token.
+pub const BOT_USER_ID: &str = "UBOT";
+
+/// A call the mock received.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    /// Bearer token from the `Authorization` header.
+    pub token: String,
+    /// Decoded form parameters.
+    pub params: BTreeMap<String, String>,
+}
+
+#[derive(Default)]
+struct State {
+    requests: Vec<Request>,
+    /// Channel → messages, oldest first.
+    history: BTreeMap<String, Vec<Value>>,
+    /// Last timestamp handed out, in whole seconds.
+    clock: u64,
+}
+
+/// A running Slack mock; stops its server on drop.
+pub struct SlackMock {
+    server: Arc<tiny_http::Server>,
+    state: Arc<Mutex<State>>,
+    port: u16,
+}
+
+impl SlackMock {
+    /// Starts the mock on a free loopback port.
+    pub fn start() -> Self {
+        let server = Arc::new(tiny_http::Server::http("127.0.0.1:0").expect("failed to bind"));
+        let port = server.server_addr().to_ip().expect("ip listener").port();
+        let state = Arc::new(Mutex::new(State {
+            clock: 1_700_000_000,
+            ..State::default()
+        }));
+
+        let (srv, st) = (Arc::clone(&server), Arc::clone(&state));
+        thread::spawn(move || {
+            for mut request in srv.incoming_requests() {
+                let mut body = String::new();
+                let _ = request.as_reader().read_to_string(&mut body);
+                let method = request.url().trim_start_matches('/').to_string();
+                let token = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("Authorization"))
+                    .map(|h| h.value.as_str().trim_start_matches("Bearer ").to_string())
+                    .unwrap_or_default();
+                let response = handle(&mut st.lock().unwrap(), method, token, decode_form(&body));
+                let _ = request.respond(tiny_http::Response::from_string(response.to_string()));
+            }
+        });
+
+        eprintln!("SlackMock started: port={}", port);
+        SlackMock {
+            server,
+            state,
+            port,
+        }
+    }
+
+    /// Returns the base URL for the mock's Web API.
+    pub fn api_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    /// Adds a message from `user` to `channel`, as if a person posted it.
+    pub fn inject_message(&self, channel: &str, user: &str, text: &str) {
+        let mut state = self.state.lock().unwrap();
+        let ts = next_ts(&mut state);
+        state
+            .history
+            .entry(channel.to_string())
+            .or_default()
+            .push(json!({"type": "message", "user": user, "text": text, "ts": ts}));
+    }
+
+    /// Calls to `method` so far, oldest first.
+    pub fn requests(&self, method: &str) -> Vec<Request> {
+        let state = self.state.lock().unwrap();
+        state
+            .requests
+            .iter()
+            .filter(|r| r.method == method)
+            .cloned()
+            .collect()
+    }
+
+    /// Texts the bot posted to `channel`, oldest first.
+    pub fn posted(&self, channel: &str) -> Vec<String> {
+        self.requests("chat.postMessage")
+            .into_iter()
+            .filter(|r| r.params.get("channel").map(String::as_str) == Some(channel))
+            .filter_map(|r| r.params.get("text").cloned())
+            .collect()
+    }
+}
+
+impl Drop for SlackMock {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+fn next_ts(state: &mut State) -> String {
+    state.clock += 1;
+    format!("{}.000100", state.clock)
+}
+
+fn handle(
+    state: &mut State,
+    method: String,
+    token: String,
+    params: BTreeMap<String, String>,
+) -> Value {
+    state.requests.push(Request {
+        method: method.clone(),
+        token: token.clone(),
+        params: params.clone(),
+    });
+    if !token.starts_with("xoxb-") {
+        return json!({"ok": false, "error": "invalid_auth"});
+    }
+    let channel = params.get("channel").cloned().unwrap_or_default();
+    match method.as_str() {
+        "auth.test" => json!({"ok": true, "user_id": BOT_USER_ID, "user": "bm", "team": "e2e"}),
+        "chat.postMessage" => {
+            let ts = next_ts(state);
+            let text = params.get("text").cloned().unwrap_or_default();
+            state
+                .history
+                .entry(channel.clone())
+                .or_default()
+                .push(json!({
+                    "type": "message", "bot_id": "B1", "user": BOT_USER_ID, "text": text, "ts": ts
+                }));
+            json!({"ok": true, "channel": channel, "ts": ts})
+        }
+        "conversations.history" => {
+            let oldest: f64 = params
+                .get("oldest")
+                .and_then(|o| o.parse().ok())
+                .unwrap_or(0.0);
+            let limit: usize = params
+                .get("limit")
+                .and_then(|l| l.parse().ok())
+                .unwrap_or(100);
+            let messages: Vec<Value> = state
+                .history
+                .get(&channel)
+                .map(|h| {
+                    h.iter()
+                        .rev()
+                        .filter(|m| m["ts"].as_str().unwrap().parse::<f64>().unwrap() > oldest)
+                        .take(limit)
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default();
+            json!({"ok": true, "messages": messages})
+        }
+        _ => json!({"ok": false, "error": "unknown_method"}),
+    }
+}
+
+/// Decodes an `application/x-www-form-urlencoded` body.
+fn decode_form(body: &str) -> BTreeMap<String, String> {
+    let decode = |s: &str| {
+        let bytes = s.as_bytes();
+        let mut out = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'+' => out.push(b' '),
+                b'%' if i + 2 < bytes.len() => {
+                    let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+                    out.push(u8::from_str_radix(hex, 16).unwrap_or(b'?'));
+                    i += 2;
+                }
+                b => out.push(b),
+            }
+            i += 1;
+        }
+        String::from_utf8_lossy(&out).to_string()
+    };
+    body.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(k), decode(v))
+        })
+        .collect()
+}
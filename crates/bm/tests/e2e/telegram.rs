@@ -12,11 +12,10 @@
 //! request inspector (`/__control/requests`) endpoints described in the
 //! research doc are not available in the current image version.
 
-use std::net::TcpListener;
 use std::process::Command;
 use std::time::Duration;
 
-use super::helpers::wait_for_port;
+use super::helpers::{free_port, wait_for_port};
 
 const TG_MOCK_IMAGE: &str = "ghcr.io/watzon/tg-mock:latest";
 
@@ -31,7 +30,7 @@ impl TgMock {
     ///
     /// Blocks until the mock's HTTP server is accepting connections.
     pub fn start() -> Self {
-        let port = find_free_port();
+        let port = free_port().expect("failed to find a free port");
         let container_name = format!("bm-tg-mock-{}", port);
 
         let output = Command::new("podman")
@@ -164,12 +163,3 @@ pub fn podman_available() -> bool {
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
-
-/// Finds a free TCP port by binding to port 0 and reading the assigned port.
-fn find_free_port() -> u16 {
-    TcpListener::bind("127.0.0.1:0")
-        .expect("failed to bind to a free port")
-        .local_addr()
-        .expect("failed to get local address")
-        .port()
-}
@@ -6,6 +6,11 @@
 //!
 //! Tests requiring the `ralph` binary (start/stop/status) are omitted since
 //! ralph is not available in the test environment.
+//!
+//! Unix only: the tests stand in for `gh`, `git` hooks, and `ralph` with
+//! `#!/bin/sh` scripts.
+
+#![cfg(unix)]
 
 use std::env;
 use std::fs;
@@ -14,7 +19,9 @@ use std::process::Command;
 use std::sync::Mutex;
 
 use bm::config::{BotminterConfig, Credentials, TeamEntry};
+use bm::procutil;
 use bm::profile;
+use bm::workspace::DriftKind;
 
 /// Serialize all tests that mutate the HOME env var.
 static ENV_MUTEX: Mutex<()> = Mutex::new(());
@@ -71,13 +78,21 @@ fn setup_team(tmp: &Path, team_name: &str, profile_name: &str) -> PathBuf {
 
     // Save config
     let config = BotminterConfig {
+        version: bm::config::CONFIG_VERSION,
         workzone: workzone.clone(),
         default_team: Some(team_name.to_string()),
+        token_expiry_warn_days: None,
+        logs: None,
+        timestamps: None,
+        workspace: None,
+        state_backend: None,
         teams: vec![TeamEntry {
             name: team_name.to_string(),
             path: team_dir,
             profile: profile_name.to_string(),
             github_repo: String::new(),
+            extra_repos: Vec::new(),
+            budget: None,
             credentials: Credentials::default(),
         }],
     };
@@ -122,6 +137,8 @@ fn add_team_to_config(
         path: team_dir,
         profile: profile_name.to_string(),
         github_repo: String::new(),
+        extra_repos: Vec::new(),
+        budget: None,
         credentials: Credentials::default(),
     });
 
@@ -170,6 +187,102 @@ fn profiles_describe_nonexistent_errors() {
     assert!(err.contains("not found"));
 }
 
+#[test]
+fn profiles_validate_checks_extracted_manifest() {
+    let tmp = tempfile::tempdir().unwrap();
+    profile::extract_profile_to("scrum", tmp.path()).unwrap();
+    bm::commands::profiles::validate(None).unwrap();
+    bm::commands::profiles::validate(Some(tmp.path().to_str().unwrap())).unwrap();
+
+    // A typo'd field fails strict validation but still loads leniently
+    let manifest_path = tmp.path().join("botminter.yml");
+    let contents = fs::read_to_string(&manifest_path)
+        .unwrap()
+        .replace("schema_version:", "shema_version: '1.0'\nschema_version:");
+    fs::write(&manifest_path, &contents).unwrap();
+    let err = bm::commands::profiles::validate(Some(tmp.path().to_str().unwrap())).unwrap_err();
+    assert!(err.to_string().contains("1 of 1"), "{}", err);
+    let err = profile::parse_manifest_strict(&manifest_path, &contents).unwrap_err();
+    assert!(format!("{:#}", err).contains("unknown field `shema_version`"), "{:#}", err);
+    let lenient: profile::ProfileManifest = serde_yml::from_str(&contents).unwrap();
+    assert_eq!(lenient.name, "scrum");
+}
+
+#[test]
+fn profiles_lint_reports_findings_as_json() {
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../profiles/scrum-compact");
+    let lint = |dir: &Path| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(["profiles", "lint", dir.to_str().unwrap(), "--json"])
+            .output()
+            .expect("failed to run bm profiles lint")
+    };
+
+    let output = lint(&source);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["errors"], 0, "{}", report);
+
+    // A copy with a skeleton file removed and a bad label color
+    let tmp = tempfile::tempdir().unwrap();
+    let copy = tmp.path().join("custom");
+    let status = Command::new("cp")
+        .args(["-r", source.to_str().unwrap(), copy.to_str().unwrap()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    fs::remove_file(copy.join("members/superman/CLAUDE.md")).unwrap();
+    let manifest = fs::read_to_string(copy.join("botminter.yml"))
+        .unwrap()
+        .replace("color: \"0E8A16\"", "color: \"green\"");
+    fs::write(copy.join("botminter.yml"), manifest).unwrap();
+
+    let output = lint(&copy);
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["errors"], 2, "{}", report);
+    let files: Vec<&str> = report["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|f| f["severity"] == "error")
+        .map(|f| f["file"].as_str().unwrap())
+        .collect();
+    assert!(files.contains(&"members/superman/CLAUDE.md"), "{}", report);
+    assert!(files.contains(&"botminter.yml"), "{}", report);
+}
+
+#[test]
+fn profiles_export_matches_embedded_profile() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path().join("my-scrum");
+    let export = || {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(["profiles", "export", "scrum", dir.to_str().unwrap()])
+            .output()
+            .expect("failed to run bm profiles export")
+    };
+
+    let output = export();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Exported profile 'scrum'"), "{}", stdout);
+
+    // Byte-for-byte the embedded profile, so upstream diffs stay clean
+    let upstream = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../profiles/scrum");
+    let diff = Command::new("diff")
+        .args(["-r", upstream.to_str().unwrap(), dir.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(diff.status.success(), "{}", String::from_utf8_lossy(&diff.stdout));
+    assert!(bm::profile_lint::lint(&dir).unwrap().is_empty());
+
+    // Never overwrites an edited copy
+    let output = export();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is not empty"));
+}
+
 // ── Hire tests ───────────────────────────────────────────────────────
 
 #[test]
@@ -178,10 +291,13 @@ fn hire_with_explicit_name() {
     let tmp = tempfile::tempdir().unwrap();
     let team_repo = setup_team(tmp.path(), "test-team", "scrum");
 
-    bm::commands::hire::run("architect", Some("bob"), None).unwrap();
+    let hired = bm::commands::hire::run("architect", Some("bob"), false, None).unwrap();
+    assert_eq!(hired.name, "bob");
+    assert_eq!(hired.adopted_workspace, None);
 
     // Verify member directory was created
     let member_dir = team_repo.join("team/architect-bob");
+    assert_eq!(hired.member_dir, member_dir);
     assert!(member_dir.is_dir(), "architect-bob/ should exist");
 
     // Verify botminter.yml was finalized (no .botminter.yml template)
@@ -193,6 +309,17 @@ fn hire_with_explicit_name() {
     assert!(member_dir.join("CLAUDE.md").exists());
     assert!(member_dir.join("ralph.yml").exists());
 
+    // Checksums track the finalized botminter.yml, not the template
+    let checksums = bm::profile_checksums::ProfileChecksums::load(&member_dir)
+        .unwrap()
+        .expect("hire should write profile checksums");
+    assert!(checksums.files.contains_key("botminter.yml"));
+    assert!(!checksums.files.contains_key(".botminter.yml"));
+    assert!(checksums
+        .check(&member_dir)
+        .iter()
+        .all(|(_, origin)| *origin == bm::profile_checksums::FileOrigin::Pristine));
+
     // Verify git commit was created
     let output = Command::new("git")
         .args(["log", "--oneline", "-1"])
@@ -209,7 +336,7 @@ fn hire_auto_suffix_first_member() {
     let tmp = tempfile::tempdir().unwrap();
     let team_repo = setup_team(tmp.path(), "test-team", "scrum");
 
-    bm::commands::hire::run("architect", None, None).unwrap();
+    bm::commands::hire::run("architect", None, false, None).unwrap();
 
     let member_dir = team_repo.join("team/architect-01");
     assert!(member_dir.is_dir(), "architect-01/ should exist (auto-suffix)");
@@ -221,8 +348,8 @@ fn hire_auto_suffix_increments() {
     let tmp = tempfile::tempdir().unwrap();
     let team_repo = setup_team(tmp.path(), "test-team", "scrum");
 
-    bm::commands::hire::run("architect", None, None).unwrap();
-    bm::commands::hire::run("architect", None, None).unwrap();
+    bm::commands::hire::run("architect", None, false, None).unwrap();
+    bm::commands::hire::run("architect", None, false, None).unwrap();
 
     assert!(team_repo.join("team/architect-01").is_dir());
     assert!(team_repo.join("team/architect-02").is_dir());
@@ -234,7 +361,7 @@ fn hire_unknown_role_errors() {
     let tmp = tempfile::tempdir().unwrap();
     setup_team(tmp.path(), "test-team", "scrum");
 
-    let result = bm::commands::hire::run("nonexistent-role", Some("alice"), None);
+    let result = bm::commands::hire::run("nonexistent-role", Some("alice"), false, None);
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(err.contains("nonexistent-role"));
@@ -247,13 +374,136 @@ fn hire_duplicate_name_errors() {
     let tmp = tempfile::tempdir().unwrap();
     setup_team(tmp.path(), "test-team", "scrum");
 
-    bm::commands::hire::run("architect", Some("bob"), None).unwrap();
-    let result = bm::commands::hire::run("architect", Some("bob"), None);
+    bm::commands::hire::run("architect", Some("bob"), false, None).unwrap();
+    let result = bm::commands::hire::run("architect", Some("bob"), false, None);
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(err.contains("already exists"));
 }
 
+#[test]
+fn hire_refuses_leftover_workspace_unless_adopted() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    let leftover = tmp.path().join("workspaces/test-team/architect-bob");
+    fs::create_dir_all(leftover.join(".botminter")).unwrap();
+
+    let err = bm::commands::hire::run("architect", Some("bob"), false, None)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("workspace directory"), "got: {}", err);
+    assert!(err.contains("--adopt-existing"), "got: {}", err);
+    assert!(!team_repo.join("team/architect-bob").exists());
+
+    // Auto-generated names skip the leftover
+    fs::create_dir_all(tmp.path().join("workspaces/test-team/architect-01")).unwrap();
+    bm::commands::hire::run("architect", None, false, None).unwrap();
+    assert!(team_repo.join("team/architect-02").is_dir());
+
+    bm::commands::hire::run("architect", Some("bob"), true, None).unwrap();
+    assert!(team_repo.join("team/architect-bob").is_dir());
+    assert!(leftover.join(".botminter").is_dir(), "adopted workspace is kept");
+}
+
+#[test]
+fn hire_adopt_existing_drops_stale_state_entry() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team(tmp.path(), "test-team", "scrum");
+
+    let state_path = tmp.path().join(".botminter/state.json");
+    let mut state = bm::state::RuntimeState::default();
+    state.members.insert(
+        "test-team/architect-bob".to_string(),
+        bm::state::MemberRuntime {
+            pid: 999_999_999,
+            started_at: "2026-02-20T10:00:00Z".to_string(),
+            workspace: tmp.path().join("gone"),
+            env: Default::default(),
+            health: None,
+        },
+    );
+    bm::state::save_to(&state_path, &state).unwrap();
+
+    let err = bm::commands::hire::run("architect", Some("bob"), false, None)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("stale entry 'test-team/architect-bob'"), "got: {}", err);
+
+    bm::commands::hire::run("architect", Some("bob"), true, None).unwrap();
+    let state = bm::state::load_from(&state_path).unwrap();
+    assert!(state.members.is_empty());
+}
+
+#[test]
+fn hire_rolls_back_when_the_commit_fails() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    let state_path = tmp.path().join(".botminter/state.json");
+    let mut state = bm::state::RuntimeState::default();
+    state.members.insert(
+        "test-team/architect-bob".to_string(),
+        bm::state::MemberRuntime {
+            pid: 999_999_999,
+            started_at: "2026-02-20T10:00:00Z".to_string(),
+            workspace: tmp.path().join("gone"),
+            env: Default::default(),
+            health: None,
+        },
+    );
+    bm::state::save_to(&state_path, &state).unwrap();
+
+    use std::os::unix::fs::PermissionsExt;
+    let hook = team_repo.join(".git/hooks/pre-commit");
+    fs::write(&hook, "#!/bin/sh\necho 'rejected by hook' >&2\nexit 1\n").unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let err = bm::commands::hire::run("architect", Some("bob"), true, None).unwrap_err();
+    let err = format!("{:#}", err);
+    assert!(err.contains("left unchanged"), "{}", err);
+    assert!(err.contains("rejected by hook"), "{}", err);
+
+    // No member dir, nothing staged, no staging leftovers, state untouched
+    assert!(!team_repo.join("team/architect-bob").exists());
+    let status = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(&team_repo)
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&status.stdout), "");
+    assert!(!tmp.path().join("workspaces/test-team/.hire-architect-bob").exists());
+    let state = bm::state::load_from(&state_path).unwrap();
+    assert!(state.members.contains_key("test-team/architect-bob"));
+
+    fs::remove_file(&hook).unwrap();
+    bm::commands::hire::run("architect", Some("bob"), true, None).unwrap();
+    assert!(team_repo.join("team/architect-bob/botminter.yml").is_file());
+    assert!(bm::state::load_from(&state_path).unwrap().members.is_empty());
+}
+
+#[test]
+fn hire_enforces_role_max() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    bm::commands::hire::run("human-assistant", Some("alice"), false, None).unwrap();
+    let err = bm::commands::hire::run("human-assistant", Some("bob"), false, None)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("at capacity"), "got: {}", err);
+    assert!(err.contains("exactly 1"), "got: {}", err);
+    assert!(err.contains("human-assistant-alice"), "got: {}", err);
+    assert!(!team_repo.join("team/human-assistant-bob").exists());
+
+    // Roles without a max are unaffected
+    bm::commands::hire::run("architect", None, false, None).unwrap();
+    bm::commands::hire::run("architect", None, false, None).unwrap();
+}
+
 // ── Projects tests ───────────────────────────────────────────────────
 
 #[test]
@@ -277,6 +527,30 @@ fn projects_add_creates_dirs_and_updates_manifest() {
     assert!(manifest_content.contains(&fork_url));
 }
 
+#[test]
+fn projects_add_dry_run_leaves_team_repo_unchanged() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    let manifest_before = fs::read_to_string(team_repo.join("botminter.yml")).unwrap();
+
+    let fork = create_fake_fork(tmp.path(), "my-repo");
+    let plan = bm::commands::projects::add_plan(&fork.to_string_lossy(), None).unwrap();
+    let text = plan.to_string();
+    assert!(text.contains("projects/my-repo/knowledge"), "{}", text);
+    assert!(text.contains("commit \"feat: add project my-repo\""), "{}", text);
+
+    assert!(!team_repo.join("projects/my-repo").exists());
+    assert_eq!(
+        fs::read_to_string(team_repo.join("botminter.yml")).unwrap(),
+        manifest_before
+    );
+
+    // A fork that can't be reached fails the dry run as it would the add
+    let missing = tmp.path().join("no-such-fork");
+    assert!(bm::commands::projects::add_plan(&missing.to_string_lossy(), None).is_err());
+}
+
 #[test]
 fn projects_add_duplicate_errors() {
     let _lock = ENV_MUTEX.lock().unwrap();
@@ -324,7 +598,7 @@ fn sync_with_nonexistent_fork_url_gives_actionable_error() {
     let tmp = tempfile::tempdir().unwrap();
     setup_team(tmp.path(), "bad-fork-team", "scrum");
 
-    bm::commands::hire::run("architect", Some("alice"), None).unwrap();
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
 
     // Manually inject a project with a non-existent fork URL into botminter.yml
     // (bypasses validation that projects::add will have)
@@ -344,7 +618,7 @@ fn sync_with_nonexistent_fork_url_gives_actionable_error() {
     git(&team_repo, &["add", "botminter.yml"]);
     git(&team_repo, &["commit", "-m", "add bad project"]);
 
-    let result = bm::commands::teams::sync(false, None);
+    let result = bm::commands::teams::sync(false, false, None, None, None).and_then(|s| s.ensure_ok());
     assert!(result.is_err(), "sync should fail with non-existent fork");
     let err = result.unwrap_err().to_string();
     assert!(
@@ -370,7 +644,7 @@ fn sync_continues_past_failed_clone() {
     git(&good_fork, &["add", "-A"]);
     git(&good_fork, &["commit", "-m", "init"]);
 
-    bm::commands::hire::run("architect", Some("alice"), None).unwrap();
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
 
     // Manually inject two projects: one good, one bad
     let manifest_path = team_repo.join("botminter.yml");
@@ -394,7 +668,7 @@ fn sync_continues_past_failed_clone() {
     git(&team_repo, &["add", "botminter.yml"]);
     git(&team_repo, &["commit", "-m", "add projects"]);
 
-    let result = bm::commands::teams::sync(false, None);
+    let result = bm::commands::teams::sync(false, false, None, None, None).and_then(|s| s.ensure_ok());
     assert!(result.is_err(), "sync should report failure");
     let err = result.unwrap_err().to_string();
 
@@ -430,7 +704,7 @@ fn schema_version_mismatch_blocks_hire() {
     git(&team_repo, &["add", "botminter.yml"]);
     git(&team_repo, &["commit", "-m", "chore: bump schema"]);
 
-    let result = bm::commands::hire::run("architect", Some("alice"), None);
+    let result = bm::commands::hire::run("architect", Some("alice"), false, None);
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(err.contains("bm upgrade"), "Should suggest bm upgrade: {}", err);
@@ -443,7 +717,7 @@ fn schema_version_mismatch_blocks_sync() {
     let team_repo = setup_team(tmp.path(), "test-team", "scrum");
 
     // Hire a member first (with correct schema)
-    bm::commands::hire::run("architect", Some("bob"), None).unwrap();
+    bm::commands::hire::run("architect", Some("bob"), false, None).unwrap();
 
     // Tamper with schema_version
     let manifest_path = team_repo.join("botminter.yml");
@@ -453,7 +727,7 @@ fn schema_version_mismatch_blocks_sync() {
     git(&team_repo, &["add", "botminter.yml"]);
     git(&team_repo, &["commit", "-m", "chore: bump schema"]);
 
-    let result = bm::commands::teams::sync(false, None);
+    let result = bm::commands::teams::sync(false, false, None, None, None);
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(err.contains("bm upgrade"), "Should suggest bm upgrade: {}", err);
@@ -470,7 +744,7 @@ fn multi_team_default_resolution() {
 
     // Default team is "alpha" (set by setup_team)
     // Hire into default team (no -t flag)
-    bm::commands::hire::run("architect", Some("alice"), None).unwrap();
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
     assert!(team_repo_alpha.join("team/architect-alice").is_dir());
 }
 
@@ -482,7 +756,7 @@ fn team_flag_overrides_default() {
     let team_repo_beta = add_team_to_config(tmp.path(), "beta", "scrum-compact", false);
 
     // Use -t to target non-default team
-    bm::commands::hire::run("superman", Some("clark"), Some("beta")).unwrap();
+    bm::commands::hire::run("superman", Some("clark"), false, Some("beta")).unwrap();
 
     // Verify member landed in beta, not alpha
     assert!(team_repo_beta.join("team/superman-clark").is_dir());
@@ -494,7 +768,7 @@ fn team_flag_nonexistent_errors() {
     let tmp = tempfile::tempdir().unwrap();
     setup_team(tmp.path(), "alpha", "scrum");
 
-    let result = bm::commands::hire::run("architect", Some("bob"), Some("nonexistent"));
+    let result = bm::commands::hire::run("architect", Some("bob"), false, Some("nonexistent"));
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(err.contains("nonexistent"));
@@ -510,11 +784,11 @@ fn lifecycle_hire_then_sync_no_project() {
     let team_repo = setup_team(tmp.path(), "lifecycle-team", "scrum");
 
     // Hire two members
-    bm::commands::hire::run("architect", Some("alice"), None).unwrap();
-    bm::commands::hire::run("human-assistant", Some("bob"), None).unwrap();
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
+    bm::commands::hire::run("human-assistant", Some("bob"), false, None).unwrap();
 
     // Sync (no projects — no-project mode)
-    bm::commands::teams::sync(false, None).unwrap();
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
 
     // Verify workspaces were created (no-project: workspace at {team_dir}/{member}/)
     let team_dir = team_repo.parent().unwrap();
@@ -532,6 +806,124 @@ fn lifecycle_hire_then_sync_no_project() {
     assert!(alice_ws.join(".claude").is_dir(), "alice should have .claude/");
 }
 
+#[test]
+fn hire_and_sync_dry_runs_change_nothing() {
+    use bm::dry_run::Step;
+
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "dry-run-team", "scrum");
+    let team_dir = team_repo.parent().unwrap().to_path_buf();
+    let head = |repo: &Path| {
+        let out = Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&out.stdout).to_string()
+    };
+    let before = head(&team_repo);
+
+    let plan = bm::commands::hire::plan("architect", Some("alice"), false, None).unwrap();
+    let member_dir = team_repo.join("team/architect-alice");
+    assert_eq!(plan.steps[0], Step::CreateDir(member_dir.clone()));
+    assert!(plan.steps.contains(&Step::WriteFile(member_dir.join("botminter.yml"))));
+    assert!(plan.steps.contains(&Step::Commit {
+        repo: team_repo.clone(),
+        message: "feat: hire architect as alice".to_string(),
+    }));
+    assert!(!member_dir.exists(), "dry run must not create the member");
+    assert!(!team_dir.join(".hire-architect-alice").exists());
+    assert_eq!(head(&team_repo), before);
+
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
+    let plan = bm::commands::teams::sync_plan(false, None, None, None).unwrap();
+    let ws = team_dir.join("architect-alice");
+    assert!(plan.steps.contains(&Step::CreateDir(ws.clone())), "{}", plan);
+    assert!(plan.to_string().contains("git init -b main"), "{}", plan);
+    assert!(!ws.exists(), "dry run must not create the workspace");
+}
+
+#[test]
+fn sync_renders_role_workspace_templates() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "templates-team", "scrum");
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
+
+    // Declare a rendered MCP config for the member
+    let member = team_repo.join("team/architect-alice");
+    let mut manifest = fs::read_to_string(member.join("botminter.yml")).unwrap();
+    manifest.push_str("workspace_files:\n- template: workspace/mcp.json\n  path: .mcp.json\n");
+    fs::write(member.join("botminter.yml"), manifest).unwrap();
+    fs::create_dir_all(member.join("workspace")).unwrap();
+    fs::write(
+        member.join("workspace/mcp.json"),
+        "{\"member\": \"{{ member }}\", \"role\": \"{{ role }}\"}\n",
+    )
+    .unwrap();
+    git(&team_repo, &["add", "-A"]);
+    git(&team_repo, &["commit", "-m", "add workspace template"]);
+
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
+
+    let ws = team_repo.parent().unwrap().join("architect-alice");
+    assert_eq!(
+        fs::read_to_string(ws.join(".mcp.json")).unwrap(),
+        "{\"member\": \"alice\", \"role\": \"architect\"}\n"
+    );
+    let exclude = fs::read_to_string(ws.join(".git/info/exclude")).unwrap();
+    assert!(exclude.lines().any(|l| l == "/.mcp.json"), "{}", exclude);
+}
+
+#[test]
+fn sync_rolls_out_team_mcp_servers() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "mcp-team", "scrum");
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
+
+    // One team-wide server, narrowed to two tools for this member
+    let mut team_manifest = fs::read_to_string(team_repo.join("botminter.yml")).unwrap();
+    team_manifest.push_str(
+        "mcp:\n  github:\n    command: github-mcp-server\n    args: [stdio]\n",
+    );
+    fs::write(team_repo.join("botminter.yml"), &team_manifest).unwrap();
+    let member = team_repo.join("team/architect-alice/botminter.yml");
+    let mut member_manifest = fs::read_to_string(&member).unwrap();
+    member_manifest.push_str(
+        "mcp:\n  github:\n    command: github-mcp-server\n    \
+         allowed_tools: [get_issue, list_issues]\n",
+    );
+    fs::write(&member, member_manifest).unwrap();
+    git(&team_repo, &["add", "-A"]);
+    git(&team_repo, &["commit", "-m", "add mcp servers"]);
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
+
+    let ws = team_repo.parent().unwrap().join("architect-alice");
+    let mcp: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(ws.join(".mcp.json")).unwrap()).unwrap();
+    assert_eq!(mcp["mcpServers"]["github"]["command"], "github-mcp-server");
+    let settings: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(ws.join(".claude/settings.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        settings["permissions"]["allow"],
+        serde_json::json!(["mcp__github__get_issue", "mcp__github__list_issues"])
+    );
+    let exclude = fs::read_to_string(ws.join(".git/info/exclude")).unwrap();
+    assert!(exclude.lines().any(|l| l == "/.mcp.json"), "{}", exclude);
+
+    // A new team server reaches the workspace on the next sync (the
+    // workspace's team repo clone is updated directly, as a pull would)
+    team_manifest.push_str("  docs:\n    command: docs-mcp\n");
+    fs::write(ws.join(".botminter/botminter.yml"), &team_manifest).unwrap();
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
+    let mcp: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(ws.join(".mcp.json")).unwrap()).unwrap();
+    assert_eq!(mcp["mcpServers"]["docs"]["command"], "docs-mcp");
+}
+
 #[test]
 fn lifecycle_hire_project_add_then_sync() {
     let _lock = ENV_MUTEX.lock().unwrap();
@@ -549,13 +941,13 @@ fn lifecycle_hire_project_add_then_sync() {
     git(&fork_repo, &["commit", "-m", "init"]);
 
     // Hire a member
-    bm::commands::hire::run("architect", Some("alice"), None).unwrap();
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
 
     // Add the project (use local path as fork URL)
     bm::commands::projects::add(&fork_repo.to_string_lossy(), None).unwrap();
 
     // Sync
-    bm::commands::teams::sync(false, None).unwrap();
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
 
     // Verify workspace: {team_dir}/architect-alice/fake-fork/
     let team_dir = team_repo.parent().unwrap();
@@ -576,11 +968,14 @@ fn lifecycle_sync_idempotent() {
     let tmp = tempfile::tempdir().unwrap();
     setup_team(tmp.path(), "idem-team", "scrum");
 
-    bm::commands::hire::run("architect", Some("alice"), None).unwrap();
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
 
-    // Sync twice — should not error
-    bm::commands::teams::sync(false, None).unwrap();
-    bm::commands::teams::sync(false, None).unwrap();
+    // Sync twice — the first creates the workspace, the second updates it
+    let first = bm::commands::teams::sync(false, false, None, None, None).unwrap();
+    assert_eq!((first.created(), first.updated()), (1, 0));
+    let second = bm::commands::teams::sync(false, false, None, None, None).unwrap();
+    assert_eq!((second.created(), second.updated()), (0, 1));
+    assert!(second.to_string().contains("Synced 1 workspace (0 created, 1 updated)"));
 }
 
 // ── Roles list test ──────────────────────────────────────────────────
@@ -603,7 +998,7 @@ fn members_list_shows_hired_members() {
     let tmp = tempfile::tempdir().unwrap();
     setup_team(tmp.path(), "test-team", "scrum");
 
-    bm::commands::hire::run("architect", Some("alice"), None).unwrap();
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
 
     // Should not error — prints table with alice
     bm::commands::members::list(None).unwrap();
@@ -657,13 +1052,21 @@ fn setup_team_for_subprocess(tmp: &Path, team_name: &str, profile_name: &str) ->
     git(&team_repo, &["commit", "-m", "feat: init team repo"]);
 
     let config = BotminterConfig {
+        version: bm::config::CONFIG_VERSION,
         workzone: workzone.clone(),
         default_team: Some(team_name.to_string()),
+        token_expiry_warn_days: None,
+        logs: None,
+        timestamps: None,
+        workspace: None,
+        state_backend: None,
         teams: vec![TeamEntry {
             name: team_name.to_string(),
             path: team_dir,
             profile: profile_name.to_string(),
             github_repo: String::new(),
+            extra_repos: Vec::new(),
+            budget: None,
             credentials: Credentials::default(),
         }],
     };
@@ -915,6 +1318,52 @@ fn completions_elvish() {
     );
 }
 
+#[test]
+fn completions_install_writes_and_verifies_bash_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let run = || {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(["completions", "install"])
+            .env("HOME", tmp.path())
+            .env("SHELL", "/bin/bash")
+            .env_remove("XDG_DATA_HOME")
+            .env_remove("BASH_COMPLETION_USER_DIR")
+            .output()
+            .expect("failed to run bm")
+    };
+
+    let output = run();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let path = tmp
+        .path()
+        .join(".local/share/bash-completion/completions/bm");
+    let script = fs::read_to_string(&path).expect("completion file written");
+    assert!(script.contains("complete"), "bash registration expected:\n{}", script);
+    assert!(stdout.contains("Installed bash completions"), "{}", stdout);
+    assert!(stdout.contains("Verified"), "{}", stdout);
+
+    let again = run();
+    assert!(String::from_utf8_lossy(&again.stdout).contains("up to date"));
+}
+
+#[test]
+fn completions_install_rejects_unsupported_shell() {
+    let tmp = tempfile::tempdir().unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["completions", "install", "--shell", "powershell"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("bm completions powershell"), "{}", stderr);
+}
+
 #[test]
 fn completions_invalid_shell_rejected() {
     let output = Command::new(env!("CARGO_BIN_EXE_bm"))
@@ -1023,6 +1472,25 @@ fn dynamic_completions_include_member_names() {
     );
 }
 
+#[test]
+fn dynamic_completions_include_knowledge_paths() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "knowledge-team", "scrum");
+    fs::create_dir_all(team_repo.join("projects/app/knowledge")).unwrap();
+    fs::write(team_repo.join("projects/app/knowledge/api.md"), "# API\n").unwrap();
+
+    for sub in ["show", "edit"] {
+        let completions = complete_bash(&["bm", "knowledge", sub, ""], tmp.path());
+        assert!(
+            completions.contains("projects/app/knowledge/api.md"),
+            "knowledge {} should suggest the file path, got:\n{}",
+            sub,
+            completions
+        );
+    }
+}
+
 #[test]
 fn dynamic_completions_graceful_without_config() {
     let tmp = tempfile::tempdir().unwrap();
@@ -1202,7 +1670,7 @@ fn hire_multiple_roles_then_sync() {
     let names = ["m1", "m2", "m3"];
 
     for (role, name) in picks.iter().zip(names.iter()) {
-        bm::commands::hire::run(role, Some(name), None).unwrap();
+        bm::commands::hire::run(role, Some(name), false, None).unwrap();
     }
 
     let fork_a = create_fake_fork(tmp.path(), "proj-alpha");
@@ -1210,7 +1678,7 @@ fn hire_multiple_roles_then_sync() {
     bm::commands::projects::add(&fork_a.to_string_lossy(), None).unwrap();
     bm::commands::projects::add(&fork_b.to_string_lossy(), None).unwrap();
 
-    bm::commands::teams::sync(false, None).unwrap();
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
 
     let team_dir = team_repo.parent().unwrap();
     for (role, name) in picks.iter().zip(names.iter()) {
@@ -1245,14 +1713,14 @@ fn sync_with_multiple_projects_creates_project_workspaces() {
     let roles = profile::list_roles("scrum").unwrap();
     let role = &roles[0];
 
-    bm::commands::hire::run(role, Some("alice"), None).unwrap();
+    bm::commands::hire::run(role, Some("alice"), false, None).unwrap();
 
     let fork_a = create_fake_fork(tmp.path(), "project-one");
     let fork_b = create_fake_fork(tmp.path(), "project-two");
     bm::commands::projects::add(&fork_a.to_string_lossy(), None).unwrap();
     bm::commands::projects::add(&fork_b.to_string_lossy(), None).unwrap();
 
-    bm::commands::teams::sync(false, None).unwrap();
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
 
     let team_dir = team_repo.parent().unwrap();
     let member = format!("{}-alice", role);
@@ -1268,6 +1736,69 @@ fn sync_with_multiple_projects_creates_project_workspaces() {
     }
 }
 
+#[test]
+fn sync_filters_by_member_and_project() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "sync-filter-team", "scrum");
+    let team_dir = team_repo.parent().unwrap().to_path_buf();
+
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
+    bm::commands::hire::run("architect", Some("bob"), false, None).unwrap();
+    let fork_a = create_fake_fork(tmp.path(), "project-one");
+    let fork_b = create_fake_fork(tmp.path(), "project-two");
+    bm::commands::projects::add(&fork_a.to_string_lossy(), None).unwrap();
+    bm::commands::projects::add(&fork_b.to_string_lossy(), None).unwrap();
+
+    bm::commands::teams::sync(false, false, Some("architect-alice"), Some("project-two"), None).unwrap();
+    assert!(team_dir.join("architect-alice/project-two/.botminter").is_dir());
+    assert!(!team_dir.join("architect-alice/project-one").exists());
+    assert!(!team_dir.join("architect-bob").exists());
+
+    bm::commands::teams::sync(false, false, None, Some("project-one"), None).unwrap();
+    assert!(team_dir.join("architect-alice/project-one/.botminter").is_dir());
+    assert!(team_dir.join("architect-bob/project-one/.botminter").is_dir());
+    assert!(!team_dir.join("architect-bob/project-two").exists());
+
+    let err = bm::commands::teams::sync(false, false, Some("architect-carol"), None, None)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("architect-alice, architect-bob"), "got: {}", err);
+    let err = bm::commands::teams::sync(false, false, None, Some("nope"), None)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("project-one, project-two"), "got: {}", err);
+}
+
+#[test]
+fn sync_fills_in_member_template_variables() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "tmpl-team", "scrum");
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
+
+    // A placeholder arriving after the hire, as `bm upgrade` might bring one
+    let prompt = team_repo.join("team/architect-alice/PROMPT.md");
+    let mut contents = fs::read_to_string(&prompt).unwrap();
+    contents.push_str("\nYou are {{ member_name }} ({{ member_dir }}) of {{ team_name }}.\n");
+    fs::write(&prompt, contents).unwrap();
+    let git = |args: &[&str]| {
+        let out = Command::new("git").args(args).current_dir(&team_repo).output().unwrap();
+        assert!(out.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&out.stderr));
+        String::from_utf8_lossy(&out.stdout).to_string()
+    };
+    git(&["commit", "-am", "upgrade skeleton"]);
+
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
+
+    let expected = "You are alice (architect-alice) of tmpl-team.";
+    assert!(fs::read_to_string(&prompt).unwrap().contains(expected));
+    assert_eq!(git(&["log", "-1", "--format=%s"]).trim(), "chore: fill in member template variables");
+    assert_eq!(git(&["status", "--porcelain"]).trim(), "");
+    let ws_prompt = team_repo.parent().unwrap().join("architect-alice/PROMPT.md");
+    assert!(fs::read_to_string(ws_prompt).unwrap().contains(expected));
+}
+
 #[test]
 fn hire_same_role_twice_auto_suffix() {
     let _lock = ENV_MUTEX.lock().unwrap();
@@ -1277,8 +1808,8 @@ fn hire_same_role_twice_auto_suffix() {
     let roles = profile::list_roles("scrum").unwrap();
     let role = &roles[0];
 
-    bm::commands::hire::run(role, None, None).unwrap();
-    bm::commands::hire::run(role, None, None).unwrap();
+    bm::commands::hire::run(role, None, false, None).unwrap();
+    bm::commands::hire::run(role, None, false, None).unwrap();
 
     let m1 = team_repo.join(format!("team/{}-01", role));
     let m2 = team_repo.join(format!("team/{}-02", role));
@@ -1300,8 +1831,8 @@ fn sync_after_second_hire_creates_new_workspace() {
     let roles = profile::list_roles("scrum").unwrap();
     let role_a = &roles[0];
 
-    bm::commands::hire::run(role_a, Some("first"), None).unwrap();
-    bm::commands::teams::sync(false, None).unwrap();
+    bm::commands::hire::run(role_a, Some("first"), false, None).unwrap();
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
 
     let team_dir = team_repo.parent().unwrap();
     let first_ws = team_dir.join(format!("{}-first", role_a));
@@ -1312,9 +1843,9 @@ fn sync_after_second_hire_creates_new_workspace() {
 
     // Hire second member (different role if available)
     let role_b = if roles.len() > 1 { &roles[1] } else { role_a };
-    bm::commands::hire::run(role_b, Some("second"), None).unwrap();
+    bm::commands::hire::run(role_b, Some("second"), false, None).unwrap();
 
-    bm::commands::teams::sync(false, None).unwrap();
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
 
     let second_ws = team_dir.join(format!("{}-second", role_b));
     assert!(
@@ -1327,8 +1858,93 @@ fn sync_after_second_hire_creates_new_workspace() {
     );
 }
 
+// ── Team repo pull tests ─────────────────────────────────────────────
+
+/// Pushes `team_repo` to a new bare origin and returns a second clone of it,
+/// standing in for another operator's machine.
+fn publish_team_repo(tmp: &Path, team_repo: &Path) -> PathBuf {
+    let origin = tmp.join("origin.git");
+    git(tmp, &["init", "--bare", "-b", "main", origin.to_str().unwrap()]);
+    git(team_repo, &["remote", "add", "origin", origin.to_str().unwrap()]);
+    git(team_repo, &["push", "-u", "origin", "main"]);
+    let other = tmp.join("other");
+    git(tmp, &["clone", origin.to_str().unwrap(), other.to_str().unwrap()]);
+    git(&other, &["config", "user.email", "other@botminter.test"]);
+    git(&other, &["config", "user.name", "Other Operator"]);
+    other
+}
+
+#[test]
+fn teams_pull_fast_forwards_members_hired_elsewhere() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "pull-team", "scrum");
+    let other = publish_team_repo(tmp.path(), &team_repo);
+
+    fs::create_dir_all(other.join("team/architect-bob")).unwrap();
+    fs::write(other.join("team/architect-bob/botminter.yml"), "role: architect\n").unwrap();
+    git(&other, &["add", "-A"]);
+    git(&other, &["commit", "-m", "feat: hire bob"]);
+    git(&other, &["push"]);
+
+    let err = bm::commands::teams::sync(true, false, None, None, None).unwrap_err().to_string();
+    assert!(err.contains("1 commit(s) behind origin"), "{}", err);
+
+    bm::commands::teams::pull(false, None).unwrap();
+    assert!(team_repo.join("team/architect-bob/botminter.yml").exists());
+
+    // Nothing new the second time
+    bm::commands::teams::pull(false, None).unwrap();
+}
+
+#[test]
+fn teams_pull_aborts_conflicting_rebase() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "pull-team", "scrum");
+    let other = publish_team_repo(tmp.path(), &team_repo);
+
+    fs::write(other.join("PROCESS.md"), "theirs\n").unwrap();
+    git(&other, &["commit", "-am", "docs: their process"]);
+    git(&other, &["push"]);
+    fs::write(team_repo.join("PROCESS.md"), "ours\n").unwrap();
+    git(&team_repo, &["commit", "-am", "docs: our process"]);
+
+    let err = bm::commands::teams::pull(false, None).unwrap_err().to_string();
+    assert!(err.contains("diverged") && err.contains("--rebase"), "{}", err);
+
+    let err = bm::commands::teams::pull(true, None).unwrap_err().to_string();
+    assert!(err.contains("PROCESS.md"), "{}", err);
+    assert_eq!(fs::read_to_string(team_repo.join("PROCESS.md")).unwrap(), "ours\n");
+    assert!(!team_repo.join(".git/rebase-merge").exists(), "rebase should be aborted");
+}
+
 // ── Error paths ──────────────────────────────────────────────────────
 
+#[test]
+fn token_refresh_keeps_going_past_a_failing_member() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team(tmp.path(), "app-team", "scrum");
+
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].credentials.github_app = Some(bm::config::GitHubApp {
+        app_id: "1".to_string(),
+        installation_id: 2,
+        private_key_path: tmp.path().join("missing.pem"),
+    });
+    bm::config::save_to(&config_path, &config).unwrap();
+    for member in ["dev-a", "dev-b"] {
+        fs::create_dir_all(tmp.path().join(".botminter/gh/app-team").join(member)).unwrap();
+    }
+
+    let refresh = bm::github_app::refresh_team_tokens("app-team").unwrap();
+    assert!(refresh.refreshed.is_empty());
+    let failed: Vec<&str> = refresh.failed.iter().map(|(m, _)| m.as_str()).collect();
+    assert_eq!(failed, ["dev-a", "dev-b"]);
+}
+
 #[test]
 fn status_missing_team_repo_dir_errors() {
     let tmp = tempfile::tempdir().unwrap();
@@ -1370,13 +1986,39 @@ fn hire_with_corrupt_manifest_errors() {
     git(&team_repo, &["add", "botminter.yml"]);
     git(&team_repo, &["commit", "-m", "chore: corrupt manifest"]);
 
-    let result = bm::commands::hire::run(role, Some("alice"), None);
+    let result = bm::commands::hire::run(role, Some("alice"), false, None);
     assert!(
         result.is_err(),
         "hire should error with corrupt manifest"
     );
 }
 
+#[test]
+fn corrupt_config_error_points_at_line() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "corrupt-cfg-team", "scrum");
+
+    let config_path = tmp.path().join(".botminter/config.yml");
+    fs::write(&config_path, "workzone: /tmp/w\nteams:\n  - name: [oops\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["teams", "list"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm teams list");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!(
+            "Failed to parse {} at line 3, column 11",
+            config_path.display()
+        )),
+        "error should name the file and location, stderr:\n{}",
+        stderr
+    );
+    assert!(stderr.contains("3 |   - name: [oops"), "error should quote the line:\n{}", stderr);
+}
+
 #[test]
 fn sync_missing_workzone_creates_it() {
     let _lock = ENV_MUTEX.lock().unwrap();
@@ -1386,8 +2028,8 @@ fn sync_missing_workzone_creates_it() {
     let roles = profile::list_roles("scrum").unwrap();
     let role = &roles[0];
 
-    bm::commands::hire::run(role, Some("alice"), None).unwrap();
-    bm::commands::teams::sync(false, None).unwrap();
+    bm::commands::hire::run(role, Some("alice"), false, None).unwrap();
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
 
     let member_dir = format!("{}-alice", role);
     let ws = tmp.path().join("workspaces/recreate-team").join(&member_dir);
@@ -1398,20 +2040,91 @@ fn sync_missing_workzone_creates_it() {
     assert!(!ws.exists(), "workspace should be deleted");
 
     // Sync again — should recreate the missing workspace
-    bm::commands::teams::sync(false, None).unwrap();
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
     assert!(
         ws.join(".botminter").is_dir(),
         "sync should recreate missing workspace"
     );
 }
 
+#[test]
+fn sync_check_reports_drift_without_fixing_it() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team(tmp.path(), "drift-team", "scrum");
+
+    let roles = profile::list_roles("scrum").unwrap();
+    let role = &roles[0];
+    bm::commands::hire::run(role, Some("alice"), false, None).unwrap();
+
+    // Not yet created counts as drift
+    let check = bm::commands::teams::sync(false, true, None, None, None).unwrap();
+    assert_eq!(check.drifted(), 1);
+    assert!(check.ensure_ok().is_err());
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
+    bm::commands::teams::sync(false, true, None, None, None).unwrap().ensure_ok().unwrap();
+
+    let member_dir = format!("{}-alice", role);
+    let ws = tmp.path().join("workspaces/drift-team").join(&member_dir);
+    fs::write(ws.join("ralph.yml"), "edited: true\n").unwrap();
+    fs::remove_file(ws.join("PROMPT.md")).unwrap();
+    fs::write(ws.join("PROMPT.md"), "# local copy\n").unwrap();
+
+    let drift = bm::workspace::detect_drift(&ws, &member_dir, bm::workspace::LinkMode::Symlink);
+    let kinds: Vec<(String, DriftKind)> = drift
+        .iter()
+        .map(|d| (d.path.display().to_string(), d.kind))
+        .collect();
+    assert!(kinds.contains(&("ralph.yml".to_string(), DriftKind::Modified)), "{:?}", kinds);
+    assert!(kinds.contains(&("PROMPT.md".to_string(), DriftKind::NotSymlink)), "{:?}", kinds);
+
+    let err = bm::commands::teams::sync(false, true, None, None, None)
+        .unwrap()
+        .ensure_ok()
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("1 of 1 workspace(s) drifted"), "{}", err);
+    assert!(!ws.join("PROMPT.md").symlink_metadata().unwrap().file_type().is_symlink());
+
+    // A regular sync restores the symlink
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
+    assert!(ws.join("PROMPT.md").symlink_metadata().unwrap().file_type().is_symlink());
+}
+
+#[test]
+fn teams_verify_fails_on_a_broken_team_repo() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "verify-team", "scrum");
+
+    let roles = profile::list_roles("scrum").unwrap();
+    bm::commands::hire::run(&roles[0], Some("alice"), false, None).unwrap();
+    bm::commands::teams::verify(None).unwrap();
+
+    let member_dir = team_repo.join("team").join(format!("{}-alice", roles[0]));
+    fs::remove_file(member_dir.join("PROMPT.md")).unwrap();
+    fs::remove_file(team_repo.join("CLAUDE.md")).unwrap();
+    let err = bm::commands::teams::verify(Some("verify-team")).unwrap_err();
+    assert!(
+        err.to_string().contains("Team repo for 'verify-team' has 2 error(s)"),
+        "{}",
+        err
+    );
+}
+
 #[test]
 fn teams_list_with_empty_config() {
     let tmp = tempfile::tempdir().unwrap();
 
     let config = BotminterConfig {
+        version: bm::config::CONFIG_VERSION,
         workzone: tmp.path().join("workspaces"),
         default_team: None,
+        token_expiry_warn_days: None,
+        logs: None,
+        timestamps: None,
+        workspace: None,
+        state_backend: None,
         teams: vec![],
     };
     let config_path = tmp.path().join(".botminter/config.yml");
@@ -1430,6 +2143,34 @@ fn teams_list_with_empty_config() {
     );
 }
 
+#[test]
+fn plain_flag_renders_ascii_tables_that_fit_columns() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "a-team-with-a-rather-long-name", "scrum");
+
+    let teams_list = |extra: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(extra)
+            .args(["teams", "list"])
+            .env("HOME", tmp.path())
+            .env("COLUMNS", "50")
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let fancy = teams_list(&[]);
+    assert!(fancy.contains('╭') && fancy.contains('✔'), "{}", fancy);
+
+    let plain = teams_list(&["--plain"]);
+    assert!(plain.is_ascii(), "{}", plain);
+    assert!(plain.contains("+--"), "{}", plain);
+    for line in plain.lines() {
+        assert!(line.len() <= 50, "line wider than COLUMNS: {:?}", line);
+    }
+}
+
 #[test]
 fn projects_add_invalid_url_format() {
     let _lock = ENV_MUTEX.lock().unwrap();
@@ -1486,7 +2227,7 @@ fn status_table_has_expected_columns() {
     );
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    for header in &["Member", "Role", "Status", "Started", "PID"] {
+    for header in &["Member", "Role", "Status", "Started", "Uptime", "Last activity", "PID"] {
         assert!(
             stdout.contains(header),
             "status output should contain '{}' column header, output:\n{}",
@@ -1497,589 +2238,3358 @@ fn status_table_has_expected_columns() {
 }
 
 #[test]
-fn members_list_table_has_expected_columns() {
+fn status_watch_redraws_until_killed() {
     let tmp = tempfile::tempdir().unwrap();
-    setup_team_for_subprocess(tmp.path(), "members-cols-team", "scrum");
-
-    let roles = profile::list_roles("scrum").unwrap();
-    let role = &roles[0];
+    setup_team_for_subprocess(tmp.path(), "status-watch-team", "scrum");
 
-    let hire = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["hire", role, "--name", "alice", "-t", "members-cols-team"])
+    let mut child = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["status", "-t", "status-watch-team", "--watch", "--interval", "1"])
         .env("HOME", tmp.path())
-        .output()
-        .expect("failed to run bm hire");
-    assert!(hire.status.success());
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .expect("failed to run bm status --watch");
+    std::thread::sleep(std::time::Duration::from_millis(2500));
+    child.kill().unwrap();
+    let output = child.wait_with_output().unwrap();
 
-    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["members", "list", "-t", "members-cols-team"])
-        .env("HOME", tmp.path())
-        .output()
-        .expect("failed to run bm members list");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let frames = stdout.matches("Every 1s: bm status").count();
+    assert!(frames >= 2, "watch should redraw at least twice, output:\n{}", stdout);
+    assert!(stdout.contains("\x1b[2J"), "watch should clear the screen between frames");
+    assert!(stdout.contains("Daemon: not running"), "{}", stdout);
+}
+
+#[test]
+fn status_group_by_role_prints_group_summaries() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "status-group-team", "scrum");
+
+    let roles = profile::list_roles("scrum").unwrap();
+    for (role, name) in [(&roles[0], "alice"), (&roles[1], "bob")] {
+        let hire = Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(["hire", role, "--name", name, "-t", "status-group-team"])
+            .env("HOME", tmp.path())
+            .output()
+            .expect("failed to run bm hire");
+        assert!(hire.status.success());
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["status", "-t", "status-group-team", "--group-by", "role", "--sort", "status"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm status");
     assert!(
         output.status.success(),
-        "bm members list failed: {}",
+        "bm status --group-by role failed: {}",
         String::from_utf8_lossy(&output.stderr)
     );
-
     let stdout = String::from_utf8_lossy(&output.stdout);
-    for header in &["Member", "Role", "Status"] {
-        assert!(
-            stdout.contains(header),
-            "members list output should contain '{}' column header, output:\n{}",
-            header,
-            stdout
-        );
-    }
-}
+    let headings: Vec<&str> = stdout.lines().filter(|l| l.starts_with("Role: ")).collect();
+    assert_eq!(headings.len(), 2, "one group per role, output:\n{}", stdout);
+    assert!(
+        headings.iter().all(|h| h.ends_with("(1 member: 1 stopped)")),
+        "each group should summarize its member, output:\n{}",
+        stdout
+    );
+    let first = format!("Role: {} ", roles[0]);
+    assert!(headings.iter().any(|h| h.starts_with(&first)), "{}", stdout);
 
-// ── Knowledge management tests ───────────────────────────────────────
+    let bad = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["status", "-t", "status-group-team", "--group-by", "team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm status");
+    assert!(!bad.status.success());
+    assert!(String::from_utf8_lossy(&bad.stderr).contains("Valid groupings: role, project"));
+}
 
 #[test]
-fn knowledge_list_shows_files_at_all_scopes() {
-    let _lock = ENV_MUTEX.lock().unwrap();
+fn status_warns_about_workspaces_over_disk_quota() {
     let tmp = tempfile::tempdir().unwrap();
-    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    setup_team_for_subprocess(tmp.path(), "quota-team", "scrum");
+    let team_dir = tmp.path().join("workspaces").join("quota-team");
+    let team_repo = team_dir.join("team");
 
-    // Team-level knowledge already exists from profile extraction
-    // Add project-level and member-level knowledge
-    let member_dir = team_repo.join("team/architect-01");
-    fs::create_dir_all(&member_dir).unwrap();
-    fs::create_dir_all(member_dir.join("knowledge")).unwrap();
-    fs::write(
-        member_dir.join("knowledge/design-patterns.md"),
-        "# Design Patterns\n",
-    )
-    .unwrap();
+    let roles = profile::list_roles("scrum").unwrap();
+    let hire = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["hire", &roles[0], "--name", "alice", "-t", "quota-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm hire");
+    assert!(hire.status.success());
+    let member = format!("{}-alice", roles[0]);
+
+    // A workspace bloated by build output
+    let ws = team_dir.join(&member);
+    fs::create_dir_all(ws.join(".botminter")).unwrap();
+    fs::create_dir_all(ws.join("target")).unwrap();
+    fs::write(ws.join("target/blob"), vec![7u8; 512 * 1024]).unwrap();
     fs::write(
-        member_dir.join("botminter.yml"),
-        "role: architect\n",
+        team_repo.join("daemon.yml"),
+        "disk_quota:\n  limit: 256Ki\n  action: block\n",
     )
     .unwrap();
 
-    git(&team_repo, &["add", "-A"]);
-    git(&team_repo, &["commit", "-m", "feat: add member knowledge"]);
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["status", "-t", "quota-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm status");
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!("{}'s workspace is over its disk quota", member))
+            && stderr.contains("quota 256.0 KiB"),
+        "stderr:\n{}",
+        stderr
+    );
 
-    // Run knowledge list
-    let result = bm::commands::knowledge::list(None, None);
-    assert!(result.is_ok(), "knowledge list failed: {:?}", result.err());
+    // Under a roomier quota the warning goes away
+    fs::write(team_repo.join("daemon.yml"), "disk_quota:\n  limit: 1Gi\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["status", "-t", "quota-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm status");
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("disk quota"));
 }
 
 #[test]
-fn knowledge_list_scope_filter() {
-    let _lock = ENV_MUTEX.lock().unwrap();
+fn usage_totals_result_events_from_member_logs() {
     let tmp = tempfile::tempdir().unwrap();
-    let _team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    setup_team_for_subprocess(tmp.path(), "usage-team", "scrum");
+    let roles = profile::list_roles("scrum").unwrap();
+    let hire = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["hire", &roles[0], "--name", "alice", "-t", "usage-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm hire");
+    assert!(hire.status.success());
+    let member = format!("{}-alice", roles[0]);
 
-    // Team scope only
-    let result = bm::commands::knowledge::list(None, Some("team"));
-    assert!(result.is_ok());
+    let logs = tmp.path().join(".botminter/logs");
+    fs::create_dir_all(&logs).unwrap();
+    let result = r#"{"type":"result","total_cost_usd":1.5,"usage":{"input_tokens":2000,"output_tokens":500}}"#;
+    fs::write(
+        logs.join(format!("member-usage-team-{}.log", member)),
+        format!("{}\nsome ralph output\n{}\n", result, result),
+    )
+    .unwrap();
+
+    let usage = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+            .arg("usage")
+            .args(args)
+            .args(["-t", "usage-team"])
+            .env("HOME", tmp.path())
+            .output()
+            .expect("failed to run bm usage");
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    let stdout = usage(&[]);
+    assert!(stdout.contains("2 session(s), 5.0k tokens, $3.00"), "{}", stdout);
+    assert!(stdout.contains(&member) && stdout.contains(&roles[0]), "{}", stdout);
+    // Reading again counts nothing twice
+    let stdout = usage(&["--since", "1d"]);
+    assert!(stdout.contains("2 session(s)"), "{}", stdout);
+    let stdout = usage(&["--since", "2999-01-01"]);
+    assert!(stdout.contains("No usage recorded"), "{}", stdout);
 }
 
 #[test]
-fn knowledge_show_displays_file_content() {
-    let _lock = ENV_MUTEX.lock().unwrap();
+fn start_refuses_a_team_over_budget_unless_overridden() {
     let tmp = tempfile::tempdir().unwrap();
-    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    setup_team_for_subprocess(tmp.path(), "budget-team", "scrum");
+    let roles = profile::list_roles("scrum").unwrap();
+    let hire = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["hire", &roles[0], "--name", "alice", "-t", "budget-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm hire");
+    assert!(hire.status.success());
 
-    // Write a known knowledge file
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].budget = Some(bm::budget::Budget {
+        daily_usd: Some(2.0),
+        ..Default::default()
+    });
+    bm::config::save_to(&config_path, &config).unwrap();
+
+    let logs = tmp.path().join(".botminter/logs");
+    fs::create_dir_all(&logs).unwrap();
     fs::write(
-        team_repo.join("knowledge/test-file.md"),
-        "# Test Content\nHello world\n",
+        logs.join(format!("member-budget-team-{}-alice.log", roles[0])),
+        "{\"type\":\"result\",\"total_cost_usd\":2.5,\"usage\":{\"input_tokens\":10}}\n",
     )
     .unwrap();
 
-    let result = bm::commands::knowledge::show("knowledge/test-file.md", None);
-    assert!(result.is_ok(), "knowledge show failed: {:?}", result.err());
+    let start = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(["start", "-t", "budget-team"])
+            .args(extra)
+            .env("HOME", tmp.path())
+            .env("PATH", "/usr/bin:/bin")
+            .output()
+            .expect("failed to run bm start")
+    };
+    let output = start(&[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("has spent $2.50 of its $2.00 daily budget") && stderr.contains("--override"),
+        "{}",
+        stderr
+    );
+
+    // Past the budget check; fails later for want of ralph
+    let output = start(&["--override"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Warning: starting team 'budget-team' although"), "{}", stderr);
 }
 
 #[test]
-fn knowledge_show_file_not_found() {
-    let _lock = ENV_MUTEX.lock().unwrap();
+fn start_dry_run_lists_launches_without_spawning() {
+    use std::os::unix::fs::PermissionsExt;
+
     let tmp = tempfile::tempdir().unwrap();
-    let _team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    setup_team_for_subprocess(tmp.path(), "start-dry-team", "scrum");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].credentials.gh_token = Some("ghp_test".to_string());
+    bm::config::save_to(&config_path, &config).unwrap();
 
-    let result = bm::commands::knowledge::show("knowledge/nonexistent.md", None);
-    assert!(result.is_err());
-    let err = result.unwrap_err().to_string();
-    assert!(err.contains("File not found"), "Got: {}", err);
+    // A ralph that would leave a trace if it were ever run
+    let bin = tmp.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let ran = tmp.path().join("ralph-ran");
+    fs::write(bin.join("ralph"), format!("#!/bin/sh\ntouch {}\n", ran.display())).unwrap();
+    fs::set_permissions(bin.join("ralph"), fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:/usr/bin:/bin", bin.display());
+
+    let bm_run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("PATH", &path)
+            .output()
+            .expect("failed to run bm")
+    };
+    assert!(bm_run(&["hire", "architect", "--name", "alice", "-t", "start-dry-team"]).status.success());
+    assert!(bm_run(&["teams", "sync", "-t", "start-dry-team"]).status.success());
+
+    let output = bm_run(&["start", "--dry-run", "-t", "start-dry-team"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("run `ralph run -p PROMPT.md` in"), "{}", stdout);
+    assert!(stdout.contains("record start-dry-team/architect-alice as running"), "{}", stdout);
+    assert!(!ran.exists(), "dry run must not launch ralph");
+    let state = bm::state::load_from(&tmp.path().join(".botminter/state.json")).unwrap();
+    assert!(state.members.is_empty());
 }
 
 #[test]
-fn knowledge_show_path_outside_knowledge_dir() {
-    let _lock = ENV_MUTEX.lock().unwrap();
+fn start_waits_for_readiness_and_marks_unhealthy_members() {
     let tmp = tempfile::tempdir().unwrap();
-    let _team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    let team_repo = setup_team_for_subprocess(tmp.path(), "ready-team", "scrum");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].credentials.gh_token = Some("ghp_test".to_string());
+    bm::config::save_to(&config_path, &config).unwrap();
 
-    let result = bm::commands::knowledge::show("botminter.yml", None);
-    assert!(result.is_err());
-    let err = result.unwrap_err().to_string();
+    // A ralph that logs a line once it's up, then idles
+    let bin = tmp.path().join("bin");
+    install_stub(&bin, "ralph", "#!/bin/sh\nsleep 1\necho 'loop started'\nexec sleep 30\n");
+    let path = format!("{}:/usr/bin:/bin", bin.display());
+    let bm_run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("PATH", &path)
+            .output()
+            .expect("failed to run bm")
+    };
+    for name in ["alice", "bob"] {
+        assert!(bm_run(&["hire", "architect", "--name", name, "-t", "ready-team"]).status.success());
+    }
+    assert!(bm_run(&["teams", "sync", "-t", "ready-team"]).status.success());
+
+    let probe = |member: &str, readiness: &str| {
+        let manifest = team_repo.join("team").join(member).join("botminter.yml");
+        let mut contents = fs::read_to_string(&manifest).unwrap_or_default();
+        contents.push_str(readiness);
+        fs::write(&manifest, contents).unwrap();
+    };
+    probe("architect-alice", "\nreadiness:\n  log_pattern: loop started\n  timeout_secs: 10\n");
+    probe("architect-bob", "\nreadiness:\n  file: never-written\n  timeout_secs: 3\n");
+
+    let output = bm_run(&["start", "-t", "ready-team"]);
+    let state = bm::state::load_from(&tmp.path().join(".botminter/state.json")).unwrap();
+    for rt in state.members.values() {
+        bm::procutil::kill(rt.pid);
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "an unhealthy member fails the start");
+    assert!(stderr.contains("didn't become ready"), "{}", stderr);
+    assert!(stdout.contains("architect-alice: started (PID"), "{}", stdout);
     assert!(
-        err.contains("not within a knowledge or invariant directory"),
-        "Got: {}",
-        err
+        stdout.contains("architect-bob: unhealthy") && stdout.contains("never-written not touched"),
+        "{}",
+        stdout
     );
+    let health = |member: &str| state.members[&format!("ready-team/{}", member)].health.clone();
+    assert_eq!(health("architect-alice"), None);
+    assert!(matches!(health("architect-bob"), Some(bm::state::Health::Unhealthy { .. })));
 }
 
 #[test]
-fn knowledge_v1_team_blocked() {
-    let _lock = ENV_MUTEX.lock().unwrap();
+fn concurrent_starts_launch_a_member_once() {
     let tmp = tempfile::tempdir().unwrap();
-    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    setup_team_for_subprocess(tmp.path(), "race-team", "scrum");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].credentials.gh_token = Some("ghp_test".to_string());
+    bm::config::save_to(&config_path, &config).unwrap();
 
-    // Downgrade schema to v1
-    let manifest_path = team_repo.join("botminter.yml");
-    let mut content = fs::read_to_string(&manifest_path).unwrap();
-    content = content.replace("schema_version: '1.0'", "schema_version: 0.1");
-    fs::write(&manifest_path, content).unwrap();
-    git(&team_repo, &["add", "botminter.yml"]);
-    git(&team_repo, &["commit", "-m", "chore: downgrade to v1"]);
+    // A ralph that records each launch, then idles
+    let launches = tmp.path().join("launches");
+    let bin = tmp.path().join("bin");
+    install_stub(
+        &bin,
+        "ralph",
+        &format!("#!/bin/sh\necho $$ >> {}\nexec sleep 30\n", launches.display()),
+    );
+    let path = format!("{}:/usr/bin:/bin", bin.display());
+    let bm_cmd = |args: &[&str]| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_bm"));
+        cmd.args(args).env("HOME", tmp.path()).env("PATH", &path);
+        cmd
+    };
+    assert!(bm_cmd(&["hire", "architect", "--name", "alice", "-t", "race-team"])
+        .output()
+        .unwrap()
+        .status
+        .success());
+    assert!(bm_cmd(&["teams", "sync", "-t", "race-team"]).output().unwrap().status.success());
+
+    let starts: Vec<_> = (0..2)
+        .map(|_| {
+            bm_cmd(&["start", "-t", "race-team"])
+                .stdout(std::process::Stdio::piped())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    let outputs: Vec<String> = starts
+        .into_iter()
+        .map(|child| String::from_utf8_lossy(&child.wait_with_output().unwrap().stdout).to_string())
+        .collect();
+    let state = bm::state::load_from(&tmp.path().join(".botminter/state.json")).unwrap();
+    for rt in state.members.values() {
+        bm::procutil::kill(rt.pid);
+    }
 
-    let result = bm::commands::knowledge::list(None, None);
-    assert!(result.is_err());
-    let err = result.unwrap_err().to_string();
-    assert!(err.contains("requires schema 1.0"), "Got: {}", err);
-    assert!(err.contains("bm upgrade"), "Got: {}", err);
+    let launched = fs::read_to_string(&launches).unwrap_or_default();
+    assert_eq!(launched.lines().count(), 1, "launches: {}\n{:#?}", launched, outputs);
+    assert!(
+        outputs.iter().any(|o| o.contains("architect-alice: started (PID"))
+            && outputs.iter().any(|o| o.contains("already running")),
+        "{:#?}",
+        outputs
+    );
 }
 
-// ── Schema init tests ─────────────────────────────────────────────
-
 #[test]
-fn init_creates_skills_and_formations_dirs() {
-    let _lock = ENV_MUTEX.lock().unwrap();
+fn status_stats_counts_detected_crash() {
     let tmp = tempfile::tempdir().unwrap();
-    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    setup_team_for_subprocess(tmp.path(), "status-stats-team", "scrum");
 
-    // Verify directories are extracted
-    assert!(
-        team_repo.join("skills").is_dir(),
-        "skills/ should exist in team repo"
-    );
-    assert!(
-        team_repo.join("formations").is_dir(),
-        "formations/ should exist in team repo"
+    let hire = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["hire", "architect", "--name", "alice", "-t", "status-stats-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm hire");
+    assert!(hire.status.success());
+
+    // One earlier clean run, plus a run whose process has since died
+    let key = "status-stats-team/architect-alice";
+    let started = chrono::Utc::now() - chrono::Duration::hours(2);
+    let mut state = bm::state::RuntimeState::default();
+    state.record_run(
+        key,
+        "2026-03-01T10:00:00Z",
+        "2026-03-01T11:00:00Z".parse().unwrap(),
+        bm::state::RunOutcome::Stopped,
     );
-    assert!(
-        team_repo.join("skills/knowledge-manager/SKILL.md").exists(),
-        "knowledge-manager skill should exist"
+    state.members.insert(
+        key.to_string(),
+        bm::state::MemberRuntime {
+            pid: 999_999_999,
+            started_at: started.to_rfc3339(),
+            workspace: tmp.path().join("gone"),
+            env: Default::default(),
+            health: None,
+        },
     );
+    let state_path = tmp.path().join(".botminter/state.json");
+    bm::state::save_to(&state_path, &state).unwrap();
+
+    // The member last wrote to its log half an hour in, so that's when the
+    // crash is dated, not when `bm status` noticed it
+    let logs = tmp.path().join(".botminter/logs");
+    fs::create_dir_all(&logs).unwrap();
+    let log = fs::File::create(logs.join("member-status-stats-team-architect-alice.log")).unwrap();
+    let died = started + chrono::Duration::minutes(30);
+    log.set_modified(died.into()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["status", "-t", "status-stats-team", "--stats"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm status");
     assert!(
-        team_repo.join("formations/local/formation.yml").exists(),
-        "local formation config should exist"
+        output.status.success(),
+        "bm status --stats failed: {}",
+        String::from_utf8_lossy(&output.stderr)
     );
+    let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(
-        team_repo.join("formations/k8s/formation.yml").exists(),
-        "k8s formation config should exist"
+        stdout.contains("Stats since 2026-03-01 10:00:00 UTC: 2 runs, 1 crash, uptime 1h 30m"),
+        "output:\n{}",
+        stdout
     );
-}
+    for header in &["Runs", "Crashes", "MTBC", "Last crash"] {
+        assert!(stdout.contains(header), "missing '{}' in:\n{}", header, stdout);
+    }
 
-#[test]
-fn botminter_yml_has_correct_schema() {
-    let _lock = ENV_MUTEX.lock().unwrap();
-    let tmp = tempfile::tempdir().unwrap();
-    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    let state = bm::state::load_from(&state_path).unwrap();
+    assert!(state.members.is_empty(), "crashed entry is cleaned up");
+    assert_eq!((state.stats[key].runs, state.stats[key].crashes), (2, 1));
+    let last_crash: chrono::DateTime<chrono::Utc> =
+        state.stats[key].last_crash_at.as_deref().unwrap().parse().unwrap();
+    assert!((last_crash - died).num_seconds().abs() < 2, "{} vs {}", last_crash, died);
 
-    let content = fs::read_to_string(team_repo.join("botminter.yml")).unwrap();
+    // `timestamps: local` renders the same times in the machine's zone
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.timestamps = Some(bm::timefmt::Zone::Local);
+    bm::config::save_to(&config_path, &config).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["status", "-t", "status-stats-team", "--stats"])
+        .env("HOME", tmp.path())
+        .env("TZ", "IST-05:30")
+        .output()
+        .expect("failed to run bm status");
+    let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(
-        content.contains("schema_version: '1.0'"),
-        "Team repo should have schema 1.0, got:\n{}",
-        content
+        stdout.contains("Stats since 2026-03-01 15:30:00 +05:30: 2 runs"),
+        "output:\n{}",
+        stdout
     );
 }
 
-// ── Formation config tests ───────────────────────────────────────────
-
 #[test]
-fn formation_list_from_team_repo() {
-    let _lock = ENV_MUTEX.lock().unwrap();
+fn state_history_records_start_and_crash() {
     let tmp = tempfile::tempdir().unwrap();
-    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    setup_team_for_subprocess(tmp.path(), "history-team", "scrum");
 
-    let formations = bm::formation::list_formations(&team_repo).unwrap();
-    assert!(formations.contains(&"local".to_string()));
-    assert!(formations.contains(&"k8s".to_string()));
+    let hire = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["hire", "architect", "--name", "alice", "-t", "history-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm hire");
+    assert!(hire.status.success());
+
+    // A run whose process has died; `bm status` notices and records a crash
+    let state_path = tmp.path().join(".botminter/state.json");
+    bm::state::update_at(&state_path, |state| {
+        state.members.insert(
+            "history-team/architect-alice".to_string(),
+            bm::state::MemberRuntime {
+                pid: 999_999_999,
+                started_at: (chrono::Utc::now() - chrono::Duration::hours(2)).to_rfc3339(),
+                workspace: tmp.path().join("gone"),
+                env: Default::default(),
+                health: None,
+            },
+        );
+        Ok(())
+    })
+    .unwrap();
+    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["status", "-t", "history-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm status");
+    assert!(status.status.success());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args([
+            "state",
+            "history",
+            "--member",
+            "architect-alice",
+            "-t",
+            "history-team",
+        ])
+        .env("HOME", tmp.path())
+        .env("COLUMNS", "200")
+        .output()
+        .expect("failed to run bm state history");
+    assert!(
+        output.status.success(),
+        "bm state history failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let started = stdout.find("started").expect(&stdout);
+    let crashed = stdout.find("crashed").expect(&stdout);
+    assert!(started < crashed, "output:\n{}", stdout);
+    assert!(stdout.contains("999999999"), "output:\n{}", stdout);
+    assert!(stdout.contains("ran 2h 00m"), "output:\n{}", stdout);
+    assert!(stdout.contains("last seen alive"), "output:\n{}", stdout);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["state", "history", "--member", "dev-bob", "-t", "history-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm state history");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("No state history for member 'dev-bob' of team 'history-team'."),
+        "output:\n{}",
+        stdout
+    );
 }
 
 #[test]
-fn formation_load_local() {
-    let _lock = ENV_MUTEX.lock().unwrap();
+fn stop_force_runs_pre_stop_hook_in_workspace() {
     let tmp = tempfile::tempdir().unwrap();
-    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    let team_repo = setup_team_for_subprocess(tmp.path(), "hook-team", "scrum");
 
-    let config = bm::formation::load(&team_repo, "local").unwrap();
-    assert_eq!(config.name, "local");
-    assert!(config.is_local());
+    let hire = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["hire", "architect", "--name", "alice", "-t", "hook-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm hire");
+    assert!(hire.status.success());
+
+    let manifest = team_repo.join("team/architect-alice/botminter.yml");
+    let mut contents = fs::read_to_string(&manifest).unwrap_or_default();
+    contents.push_str(
+        "pre_stop:\n  command: echo \"$BM_MEMBER\" > wip-saved; echo committed WIP\n",
+    );
+    fs::write(&manifest, contents).unwrap();
+
+    let ws = tmp.path().join("workspaces/hook-team/architect-alice");
+    fs::create_dir_all(&ws).unwrap();
+    let mut member = Command::new("sleep").arg("30").spawn().unwrap();
+    let mut state = bm::state::RuntimeState::default();
+    state.members.insert(
+        "hook-team/architect-alice".to_string(),
+        bm::state::MemberRuntime {
+            pid: member.id(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            workspace: ws.clone(),
+            env: Default::default(),
+            health: None,
+        },
+    );
+    bm::state::save_to(&tmp.path().join(".botminter/state.json"), &state).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["stop", "-t", "hook-team", "-f"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm stop");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "bm stop failed: {}", stderr);
+    assert!(stderr.contains("architect-alice: pre_stop hook finished"), "stderr:\n{}", stderr);
+
+    assert_eq!(fs::read_to_string(ws.join("wip-saved")).unwrap(), "architect-alice\n");
+    let log = tmp.path().join(".botminter/logs/pre-stop-hook-team-architect-alice.log");
+    assert!(fs::read_to_string(&log).unwrap().contains("committed WIP"));
+    assert!(!member.wait().unwrap().success(), "member is terminated after the hook");
 }
 
 #[test]
-fn formation_resolve_default() {
-    let _lock = ENV_MUTEX.lock().unwrap();
+fn stop_all_stops_every_team_and_cleans_stale_state() {
     let tmp = tempfile::tempdir().unwrap();
-    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+    setup_team_for_subprocess(tmp.path(), "stop-all-team", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "stop-all-team");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    let mut idle = config.teams[0].clone();
+    idle.name = "idle-team".to_string();
+    config.teams.push(idle);
+    bm::config::save_to(&config_path, &config).unwrap();
 
-    // No flag → default to local (formations dir exists)
-    let result = bm::formation::resolve_formation(&team_repo, None).unwrap();
-    assert_eq!(result, Some("local".to_string()));
+    let bm_run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .output()
+            .expect("failed to run bm")
+    };
+    assert!(bm_run(&["daemon", "start", "--mode", "poll", "-t", "stop-all-team"]).status.success());
+
+    let mut member = Command::new("sleep").arg("30").spawn().unwrap();
+    // dev-bob crashed, leaving a child behind in its process group
+    let mut crashed = Command::new("sh");
+    crashed.args(["-c", "sleep 30 >/dev/null & echo $!"]);
+    crashed.stdout(std::process::Stdio::piped());
+    bm::procutil::detach(&mut crashed);
+    let crashed = crashed.spawn().unwrap();
+    let crashed_pid = crashed.id();
+    let orphan = String::from_utf8_lossy(&crashed.wait_with_output().unwrap().stdout)
+        .trim()
+        .to_string();
+    let mut state = bm::state::RuntimeState::default();
+    for (name, pid) in [("architect-alice", member.id()), ("dev-bob", crashed_pid)] {
+        state.members.insert(
+            format!("stop-all-team/{}", name),
+            bm::state::MemberRuntime {
+                pid,
+                started_at: chrono::Utc::now().to_rfc3339(),
+                workspace: tmp.path().join(name),
+                env: Default::default(),
+                health: None,
+            },
+        );
+    }
+    bm::state::save_to(&tmp.path().join(".botminter/state.json"), &state).unwrap();
+
+    let output = bm_run(&["stop", "--all", "-f"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("stop-all-team: daemon stopped, 1 member(s) stopped, 1 stale entry cleaned"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("idle-team: nothing running"), "{}", stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!(
+            "dev-bob: 1 process(es) outlived it and were terminated: {}",
+            orphan
+        )),
+        "{}",
+        stderr
+    );
+    assert!(bm::procutil::group_processes(crashed_pid).is_empty());
+
+    assert!(!member.wait().unwrap().success(), "member is terminated");
+    assert!(!tmp.path().join(".botminter/daemon-stop-all-team.pid").exists());
+    let state = bm::state::load_from(&tmp.path().join(".botminter/state.json")).unwrap();
+    assert!(state.members.is_empty());
 }
 
 #[test]
-fn formation_v1_gate_blocks_non_default() {
-    let _lock = ENV_MUTEX.lock().unwrap();
+fn members_list_table_has_expected_columns() {
     let tmp = tempfile::tempdir().unwrap();
-    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
-
-    // Downgrade to v1 and remove formations dir
-    let manifest_path = team_repo.join("botminter.yml");
-    let mut content = fs::read_to_string(&manifest_path).unwrap();
-    content = content.replace("schema_version: '1.0'", "schema_version: 0.1");
-    fs::write(&manifest_path, content).unwrap();
-    fs::remove_dir_all(team_repo.join("formations")).unwrap();
-    git(&team_repo, &["add", "-A"]);
-    git(&team_repo, &["commit", "-m", "chore: simulate v1"]);
+    setup_team_for_subprocess(tmp.path(), "members-cols-team", "scrum");
 
-    // No formations dir → None (legacy)
-    let result = bm::formation::resolve_formation(&team_repo, None).unwrap();
-    assert_eq!(result, None);
-}
+    let roles = profile::list_roles("scrum").unwrap();
+    let role = &roles[0];
 
-// ── Daemon lifecycle tests ───────────────────────────────────────────
+    let hire = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["hire", role, "--name", "alice", "-t", "members-cols-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm hire");
+    assert!(hire.status.success());
 
-/// RAII guard that stops and cleans up a daemon process on drop.
-///
-/// If a test panics before manual cleanup, this guard ensures the daemon
-/// is killed and PID/config files are removed.
-struct DaemonGuard {
-    team_name: String,
-    home: PathBuf,
-}
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["members", "list", "-t", "members-cols-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm members list");
+    assert!(
+        output.status.success(),
+        "bm members list failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
 
-impl DaemonGuard {
-    fn new(home: &Path, team_name: &str) -> Self {
-        DaemonGuard {
-            team_name: team_name.to_string(),
-            home: home.to_path_buf(),
-        }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for header in &["Member", "Role", "Status"] {
+        assert!(
+            stdout.contains(header),
+            "members list output should contain '{}' column header, output:\n{}",
+            header,
+            stdout
+        );
     }
 }
 
-impl Drop for DaemonGuard {
-    fn drop(&mut self) {
-        // Try graceful stop via bm daemon stop
-        let _ = Command::new(env!("CARGO_BIN_EXE_bm"))
-            .args(["daemon", "stop", "-t", &self.team_name])
-            .env("HOME", &self.home)
-            .output();
+// ── Knowledge management tests ───────────────────────────────────────
 
-        // Force-kill via PID file if still alive
-        let pid_file = self.home.join(format!(".botminter/daemon-{}.pid", self.team_name));
-        if let Ok(pid_str) = fs::read_to_string(&pid_file) {
-            if let Ok(pid) = pid_str.trim().parse::<i32>() {
-                unsafe {
-                    if libc::kill(pid, 0) == 0 {
-                        libc::kill(pid, libc::SIGKILL);
-                    }
-                }
-            }
-        }
+#[test]
+fn knowledge_list_shows_files_at_all_scopes() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
 
-        // Clean up files
-        let _ = fs::remove_file(
-            self.home.join(format!(".botminter/daemon-{}.pid", self.team_name)),
-        );
-        let _ = fs::remove_file(
-            self.home.join(format!(".botminter/daemon-{}.json", self.team_name)),
-        );
-        let _ = fs::remove_file(
-            self.home.join(format!(".botminter/daemon-{}-poll.json", self.team_name)),
-        );
-    }
+    // Team-level knowledge already exists from profile extraction
+    // Add project-level and member-level knowledge
+    let member_dir = team_repo.join("team/architect-01");
+    fs::create_dir_all(&member_dir).unwrap();
+    fs::create_dir_all(member_dir.join("knowledge")).unwrap();
+    fs::write(
+        member_dir.join("knowledge/design-patterns.md"),
+        "# Design Patterns\n",
+    )
+    .unwrap();
+    fs::write(
+        member_dir.join("botminter.yml"),
+        "role: architect\n",
+    )
+    .unwrap();
+
+    git(&team_repo, &["add", "-A"]);
+    git(&team_repo, &["commit", "-m", "feat: add member knowledge"]);
+
+    // Run knowledge list
+    let result = bm::commands::knowledge::list(None, None);
+    assert!(result.is_ok(), "knowledge list failed: {:?}", result.err());
 }
 
 #[test]
-fn daemon_start_creates_pid_and_config_files() {
+fn knowledge_list_scope_filter() {
+    let _lock = ENV_MUTEX.lock().unwrap();
     let tmp = tempfile::tempdir().unwrap();
-    setup_team_for_subprocess(tmp.path(), "daemon-test", "scrum");
-    let _guard = DaemonGuard::new(tmp.path(), "daemon-test");
+    let _team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    // Team scope only
+    let result = bm::commands::knowledge::list(None, Some("team"));
+    assert!(result.is_ok());
+}
+
+#[test]
+fn knowledge_grep_json_reports_scoped_matches() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "grep-team", "scrum");
+
+    let member_knowledge = team_repo.join("team/architect-01/knowledge");
+    fs::create_dir_all(&member_knowledge).unwrap();
+    fs::write(
+        member_knowledge.join("retries.md"),
+        "# Retries\nUse exponential BACKOFF for gh calls\n",
+    )
+    .unwrap();
+    fs::write(team_repo.join("knowledge/retries.md"), "backoff applies team-wide\n").unwrap();
 
     let output = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "start", "--mode", "poll", "-t", "daemon-test"])
+        .args(["knowledge", "grep", "backoff", "-i", "--json", "--scope", "member"])
         .env("HOME", tmp.path())
         .output()
-        .expect("failed to run bm daemon start");
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-
+        .expect("failed to run bm knowledge grep");
     assert!(
         output.status.success(),
-        "bm daemon start should exit 0, stdout: {}, stderr: {}",
-        stdout,
-        stderr
+        "knowledge grep failed: {}",
+        String::from_utf8_lossy(&output.stderr)
     );
-    assert!(stdout.contains("Daemon started"), "Should print started message: {}", stdout);
-
-    // Verify PID file exists
-    let pid_file = tmp.path().join(".botminter/daemon-daemon-test.pid");
-    assert!(pid_file.exists(), "PID file should exist");
 
-    // Verify config file exists
-    let cfg_file = tmp.path().join(".botminter/daemon-daemon-test.json");
-    assert!(cfg_file.exists(), "Config file should exist");
-
-    // Read and validate config content
-    let cfg_content = fs::read_to_string(&cfg_file).unwrap();
-    let cfg: serde_json::Value = serde_json::from_str(&cfg_content).unwrap();
-    assert_eq!(cfg["team"], "daemon-test");
-    assert_eq!(cfg["mode"], "poll");
+    let matches: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let matches = matches.as_array().unwrap();
+    assert_eq!(matches.len(), 1, "only the member scope: {:?}", matches);
+    assert_eq!(matches[0]["scope"], "member");
+    assert_eq!(matches[0]["path"], "team/architect-01/knowledge/retries.md");
+    assert_eq!(matches[0]["line"], 2);
+
+    let err = bm::commands::knowledge::grep(
+        "x",
+        &bm::commands::knowledge::GrepOptions {
+            ignore_case: false,
+            context: 0,
+            json: false,
+        },
+        None,
+        Some("everything"),
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("Valid scopes"), "got: {}", err);
 }
 
 #[test]
-fn daemon_status_shows_running() {
+fn knowledge_add_and_edit_commit_to_team_repo() {
+    let _lock = ENV_MUTEX.lock().unwrap();
     let tmp = tempfile::tempdir().unwrap();
-    setup_team_for_subprocess(tmp.path(), "daemon-status-test", "scrum");
-    let _guard = DaemonGuard::new(tmp.path(), "daemon-status-test");
+    let team_repo = setup_team(tmp.path(), "kadd-team", "scrum");
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
+
+    let source = tmp.path().join("notes.md");
+    fs::write(&source, "# Retries\nUse backoff.\n").unwrap();
+    bm::commands::knowledge::add(
+        "member",
+        "retries",
+        &bm::commands::knowledge::AddTarget {
+            project: None,
+            member: Some("architect-alice"),
+            invariant: false,
+        },
+        bm::commands::knowledge::AddSource::File(source),
+        None,
+    )
+    .unwrap();
 
-    // Start daemon
-    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "start", "--mode", "poll", "-t", "daemon-status-test"])
-        .env("HOME", tmp.path())
-        .output()
-        .expect("failed to start daemon");
-    assert!(start.status.success(), "daemon start failed: {}", String::from_utf8_lossy(&start.stderr));
+    let rel = "team/architect-alice/knowledge/retries.md";
+    assert_eq!(
+        fs::read_to_string(team_repo.join(rel)).unwrap(),
+        "# Retries\nUse backoff.\n"
+    );
+    let log = |repo: &Path| {
+        let out = Command::new("git")
+            .args(["log", "-1", "--format=%s"])
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&out.stdout).trim().to_string()
+    };
+    assert_eq!(log(&team_repo), format!("docs: add knowledge {}", rel));
+
+    // Adding the same file again points at `edit`
+    let err = bm::commands::knowledge::add(
+        "member",
+        "retries.md",
+        &bm::commands::knowledge::AddTarget {
+            project: None,
+            member: Some("architect-alice"),
+            invariant: false,
+        },
+        bm::commands::knowledge::AddSource::Template,
+        None,
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("bm knowledge edit"), "got: {}", err);
 
-    // Check status
-    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "status", "-t", "daemon-status-test"])
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["knowledge", "edit", rel])
+        .env("HOME", tmp.path())
+        .env_remove("VISUAL")
+        .env("EDITOR", "sed -i s/backoff/jittered-backoff/")
+        .output()
+        .expect("failed to run bm knowledge edit");
+    assert!(
+        output.status.success(),
+        "knowledge edit failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(fs::read_to_string(team_repo.join(rel))
+        .unwrap()
+        .contains("jittered-backoff"));
+    assert_eq!(log(&team_repo), format!("docs: update {}", rel));
+}
+
+#[test]
+fn knowledge_show_displays_file_content() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    // Write a known knowledge file
+    fs::write(
+        team_repo.join("knowledge/test-file.md"),
+        "# Test Content\nHello world\n",
+    )
+    .unwrap();
+
+    let result = bm::commands::knowledge::show("knowledge/test-file.md", None);
+    assert!(result.is_ok(), "knowledge show failed: {:?}", result.err());
+}
+
+#[test]
+fn knowledge_show_file_not_found() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let _team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    let result = bm::commands::knowledge::show("knowledge/nonexistent.md", None);
+    assert!(result.is_err());
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("File not found"), "Got: {}", err);
+}
+
+#[test]
+fn knowledge_show_path_outside_knowledge_dir() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let _team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    let result = bm::commands::knowledge::show("botminter.yml", None);
+    assert!(result.is_err());
+    let err = result.unwrap_err().to_string();
+    assert!(
+        err.contains("not within a knowledge or invariant directory"),
+        "Got: {}",
+        err
+    );
+}
+
+#[test]
+fn knowledge_v1_team_blocked() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    // Downgrade schema to v1
+    let manifest_path = team_repo.join("botminter.yml");
+    let mut content = fs::read_to_string(&manifest_path).unwrap();
+    content = content.replace("schema_version: '1.0'", "schema_version: 0.1");
+    fs::write(&manifest_path, content).unwrap();
+    git(&team_repo, &["add", "botminter.yml"]);
+    git(&team_repo, &["commit", "-m", "chore: downgrade to v1"]);
+
+    let result = bm::commands::knowledge::list(None, None);
+    assert!(result.is_err());
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("requires schema 1.0"), "Got: {}", err);
+    assert!(err.contains("bm upgrade"), "Got: {}", err);
+}
+
+// ── Schema init tests ─────────────────────────────────────────────
+
+#[test]
+fn init_creates_skills_and_formations_dirs() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    // Verify directories are extracted
+    assert!(
+        team_repo.join("skills").is_dir(),
+        "skills/ should exist in team repo"
+    );
+    assert!(
+        team_repo.join("formations").is_dir(),
+        "formations/ should exist in team repo"
+    );
+    assert!(
+        team_repo.join("skills/knowledge-manager/SKILL.md").exists(),
+        "knowledge-manager skill should exist"
+    );
+    assert!(
+        team_repo.join("formations/local/formation.yml").exists(),
+        "local formation config should exist"
+    );
+    assert!(
+        team_repo.join("formations/k8s/formation.yml").exists(),
+        "k8s formation config should exist"
+    );
+}
+
+#[test]
+fn botminter_yml_has_correct_schema() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    let content = fs::read_to_string(team_repo.join("botminter.yml")).unwrap();
+    assert!(
+        content.contains("schema_version: '1.0'"),
+        "Team repo should have schema 1.0, got:\n{}",
+        content
+    );
+}
+
+// ── Formation config tests ───────────────────────────────────────────
+
+#[test]
+fn formation_list_from_team_repo() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    let formations = bm::formation::list_formations(&team_repo).unwrap();
+    assert!(formations.contains(&"local".to_string()));
+    assert!(formations.contains(&"k8s".to_string()));
+}
+
+#[test]
+fn formation_load_local() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    let config = bm::formation::load(&team_repo, "local").unwrap();
+    assert_eq!(config.name, "local");
+    assert!(config.is_local());
+}
+
+#[test]
+fn formation_resolve_default() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    // No flag → default to local (formations dir exists)
+    let result = bm::formation::resolve_formation(&team_repo, None).unwrap();
+    assert_eq!(result, Some("local".to_string()));
+}
+
+#[test]
+fn formation_v1_gate_blocks_non_default() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team(tmp.path(), "test-team", "scrum");
+
+    // Downgrade to v1 and remove formations dir
+    let manifest_path = team_repo.join("botminter.yml");
+    let mut content = fs::read_to_string(&manifest_path).unwrap();
+    content = content.replace("schema_version: '1.0'", "schema_version: 0.1");
+    fs::write(&manifest_path, content).unwrap();
+    fs::remove_dir_all(team_repo.join("formations")).unwrap();
+    git(&team_repo, &["add", "-A"]);
+    git(&team_repo, &["commit", "-m", "chore: simulate v1"]);
+
+    // No formations dir → None (legacy)
+    let result = bm::formation::resolve_formation(&team_repo, None).unwrap();
+    assert_eq!(result, None);
+}
+
+#[test]
+fn topology_refresh_drops_dead_local_members() {
+    use bm::topology::{Endpoint, MemberTopology, Topology};
+    use std::collections::HashMap;
+
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team(tmp.path(), "test-team", "scrum");
+    let topo_path = bm::topology::topology_path(&tmp.path().join("workspaces"), "test-team");
+
+    let member = |pid: u32| MemberTopology {
+        status: "running".to_string(),
+        endpoint: Endpoint::Local {
+            pid,
+            workspace: tmp.path().join("ws"),
+        },
+    };
+    let mut members = HashMap::new();
+    members.insert("architect-alice".to_string(), member(std::process::id()));
+    members.insert("dev-bob".to_string(), member(4_000_000));
+    let topo = Topology {
+        formation: "local".to_string(),
+        created_at: "2026-02-21T10:00:00Z".to_string(),
+        members,
+    };
+    bm::topology::save(&topo_path, &topo).unwrap();
+
+    bm::commands::topology::refresh(None).unwrap();
+    let refreshed = bm::topology::load(&topo_path).unwrap().unwrap();
+    let names: Vec<&String> = refreshed.members.keys().collect();
+    assert_eq!(names, vec!["architect-alice"]);
+
+    // Once every member is gone the topology file goes too, as after `bm stop`
+    let mut members = HashMap::new();
+    members.insert("dev-bob".to_string(), member(4_000_000));
+    bm::topology::save(&topo_path, &Topology { members, ..topo }).unwrap();
+    bm::commands::topology::refresh(None).unwrap();
+    assert!(!topo_path.exists());
+}
+
+#[test]
+fn exec_runs_in_member_workspace_and_passes_exit_code() {
+    use bm::topology::{Endpoint, MemberTopology, Topology};
+    use std::collections::HashMap;
+
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "exec-team", "scrum");
+    let workspace = tmp.path().join("ws-architect");
+    fs::create_dir_all(&workspace).unwrap();
+
+    let mut members = HashMap::new();
+    members.insert(
+        "architect-alice".to_string(),
+        MemberTopology {
+            status: "running".to_string(),
+            endpoint: Endpoint::Local {
+                pid: std::process::id(),
+                workspace: workspace.clone(),
+            },
+        },
+    );
+    let topo_path = bm::topology::topology_path(&tmp.path().join("workspaces"), "exec-team");
+    let topo = Topology {
+        formation: "local".to_string(),
+        created_at: "2026-02-21T10:00:00Z".to_string(),
+        members,
+    };
+    bm::topology::save(&topo_path, &topo).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["exec", "architect-alice", "-t", "exec-team", "--"])
+        .args(["sh", "-c", "pwd; exit 3"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm exec");
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        Path::new(stdout.trim()).canonicalize().unwrap(),
+        workspace.canonicalize().unwrap()
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["exec", "dev-bob", "-t", "exec-team", "--", "true"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm exec");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Running members: architect-alice"),
+        "stderr:\n{}",
+        stderr
+    );
+}
+
+// ── Daemon lifecycle tests ───────────────────────────────────────────
+
+/// RAII guard that stops and cleans up a daemon process on drop.
+///
+/// If a test panics before manual cleanup, this guard ensures the daemon
+/// is killed and PID/config files are removed.
+struct DaemonGuard {
+    team_name: String,
+    home: PathBuf,
+}
+
+impl DaemonGuard {
+    fn new(home: &Path, team_name: &str) -> Self {
+        DaemonGuard {
+            team_name: team_name.to_string(),
+            home: home.to_path_buf(),
+        }
+    }
+}
+
+impl Drop for DaemonGuard {
+    fn drop(&mut self) {
+        // Stop via bm daemon stop, without waiting for in-flight members
+        let _ = Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(["daemon", "stop", "--no-drain", "-t", &self.team_name])
+            .env("HOME", &self.home)
+            .output();
+
+        // Force-kill via PID file if still alive
+        let pid_file = self.home.join(format!(".botminter/daemon-{}.pid", self.team_name));
+        if let Ok(pid_str) = fs::read_to_string(&pid_file) {
+            if let Ok(pid) = pid_str.trim().parse::<u32>() {
+                if procutil::is_alive(pid) {
+                    procutil::kill(pid);
+                }
+            }
+        }
+
+        // Clean up files
+        let _ = fs::remove_file(
+            self.home.join(format!(".botminter/daemon-{}.pid", self.team_name)),
+        );
+        let _ = fs::remove_file(
+            self.home.join(format!(".botminter/daemon-{}.json", self.team_name)),
+        );
+        let _ = fs::remove_file(
+            self.home.join(format!(".botminter/daemon-{}-poll.json", self.team_name)),
+        );
+    }
+}
+
+#[test]
+fn daemon_start_creates_pid_and_config_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-test");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "poll", "-t", "daemon-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm daemon start");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        output.status.success(),
+        "bm daemon start should exit 0, stdout: {}, stderr: {}",
+        stdout,
+        stderr
+    );
+    assert!(stdout.contains("Daemon started"), "Should print started message: {}", stdout);
+
+    // Verify PID file exists
+    let pid_file = tmp.path().join(".botminter/daemon-daemon-test.pid");
+    assert!(pid_file.exists(), "PID file should exist");
+
+    // Verify config file exists
+    let cfg_file = tmp.path().join(".botminter/daemon-daemon-test.json");
+    assert!(cfg_file.exists(), "Config file should exist");
+
+    // Read and validate config content
+    let cfg_content = fs::read_to_string(&cfg_file).unwrap();
+    let cfg: serde_json::Value = serde_json::from_str(&cfg_content).unwrap();
+    assert_eq!(cfg["team"], "daemon-test");
+    assert_eq!(cfg["mode"], "poll");
+}
+
+#[test]
+fn daemon_start_dry_run_spawns_nothing() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-dry-test", "scrum");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "poll", "--dry-run", "-t", "daemon-dry-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm daemon start --dry-run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("daemon-run --team daemon-dry-test --mode poll"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Dry run: nothing was changed."), "{}", stdout);
+    assert!(!tmp.path().join(".botminter/daemon-daemon-dry-test.pid").exists());
+    assert!(!tmp.path().join(".botminter/daemon-daemon-dry-test.json").exists());
+}
+
+#[test]
+fn daemon_status_shows_running() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-status-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-status-test");
+
+    // Start daemon
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "poll", "-t", "daemon-status-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to start daemon");
+    assert!(start.status.success(), "daemon start failed: {}", String::from_utf8_lossy(&start.stderr));
+
+    // Check status
+    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "status", "-t", "daemon-status-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to get daemon status");
+
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(status.status.success(), "daemon status should exit 0");
+    assert!(stdout.contains("running"), "Should show running: {}", stdout);
+    assert!(stdout.contains("poll"), "Should show mode: {}", stdout);
+}
+
+#[test]
+fn daemon_stop_cleans_up() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-stop-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-stop-test");
+
+    // Start daemon
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "poll", "-t", "daemon-stop-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to start daemon");
+    assert!(start.status.success());
+
+    // Stop daemon
+    let stop = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "stop", "-t", "daemon-stop-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to stop daemon");
+
+    let stdout = String::from_utf8_lossy(&stop.stdout);
+    assert!(stop.status.success(), "daemon stop should exit 0");
+    assert!(stdout.contains("Daemon stopped"), "Should print stopped: {}", stdout);
+
+    // Verify cleanup
+    let pid_file = tmp.path().join(".botminter/daemon-daemon-stop-test.pid");
+    assert!(!pid_file.exists(), "PID file should be removed");
+    let cfg_file = tmp.path().join(".botminter/daemon-daemon-stop-test.json");
+    assert!(!cfg_file.exists(), "Config file should be removed");
+}
+
+#[test]
+fn daemon_start_uses_daemon_settings_precedence() {
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team_for_subprocess(tmp.path(), "daemon-settings-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-settings-test");
+
+    // Team repo picks poll mode; local override changes the interval
+    fs::write(
+        team_repo.join("daemon.yml"),
+        "version: 1\nmode: poll\ninterval_secs: 120\n",
+    )
+    .unwrap();
+    fs::write(
+        tmp.path().join(".botminter/daemon-daemon-settings-test.yml"),
+        "interval_secs: 45\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "-t", "daemon-settings-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm daemon start");
+    assert!(
+        output.status.success(),
+        "daemon start failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let cfg_file = tmp.path().join(".botminter/daemon-daemon-settings-test.json");
+    let cfg: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&cfg_file).unwrap()).unwrap();
+    assert_eq!(cfg["mode"], "poll", "mode should come from team daemon.yml");
+    assert_eq!(cfg["interval_secs"], 45, "interval should come from local override");
+}
+
+#[test]
+fn daemon_status_shows_next_scheduled_run() {
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team_for_subprocess(tmp.path(), "daemon-sched-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-sched-test");
+    fs::write(
+        team_repo.join("daemon.yml"),
+        "mode: poll\nschedules:\n  - name: grooming\n    cron: \"0 2 * * *\"\n    roles: [po]\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "-t", "daemon-sched-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm daemon start");
+    assert!(
+        output.status.success(),
+        "daemon start failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let schedules = tmp.path().join(".botminter/daemon-daemon-sched-test-schedules.json");
+    for _ in 0..50 {
+        if schedules.exists() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let next: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&schedules).unwrap()).unwrap();
+    let at = next["grooming"]["at"].as_str().unwrap();
+    assert!(at.contains("T02:00:00"), "next fire time: {}", at);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "status", "-t", "daemon-sched-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm daemon status");
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(
+        stdout.contains("Schedule: grooming (0 2 * * *), next run "),
+        "status:\n{}",
+        stdout
+    );
+}
+
+#[test]
+fn daemon_poll_backs_off_on_secondary_rate_limit() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-rate-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-rate-test");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].github_repo = "org/team".to_string();
+    bm::config::save_to(&config_path, &config).unwrap();
+
+    // A gh that answers every poll with a secondary rate limit
+    let bin = tmp.path().join("bin");
+    let calls = tmp.path().join("gh-calls.txt");
+    install_stub(
+        &bin,
+        "gh",
+        &format!(
+            "#!/bin/sh\necho \"$*\" >> {calls}\n\
+             printf 'HTTP/2.0 403 Forbidden\\r\\nX-Ratelimit-Limit: 5000\\r\\n'\n\
+             printf 'X-Ratelimit-Remaining: 4000\\r\\nX-Ratelimit-Reset: 4102444800\\r\\n\\r\\n'\n\
+             echo '{{\"message\":\"You have exceeded a secondary rate limit\"}}'\n\
+             echo 'gh: You have exceeded a secondary rate limit (HTTP 403)' >&2\nexit 1\n",
+            calls = calls.display()
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .output()
+            .unwrap()
+    };
+
+    let start = bm(&["daemon", "start", "--mode", "poll", "--interval", "1", "-t", "daemon-rate-test"]);
+    assert!(start.status.success(), "{}", String::from_utf8_lossy(&start.stderr));
+
+    let poll_file = tmp.path().join(".botminter/daemon-daemon-rate-test-poll.json");
+    for _ in 0..50 {
+        if poll_file.exists() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    // Backing off for a minute, so the one-second interval doesn't poll again
+    thread::sleep(Duration::from_secs(3));
+    let polls = fs::read_to_string(&calls).unwrap();
+    assert_eq!(polls.lines().count(), 1, "{}", polls);
+    assert!(polls.contains("--include"), "{}", polls);
+
+    let state: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&poll_file).unwrap()).unwrap();
+    assert_eq!(state["throttled"], 1);
+    assert_eq!(state["rate_limit"]["remaining"], 4000);
+
+    let status = bm(&["daemon", "status", "-t", "daemon-rate-test"]);
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(stdout.contains("GitHub API: 4000 of 5000 requests left"), "{}", stdout);
+    assert!(stdout.contains("Polling paused until"), "{}", stdout);
+    assert!(stdout.contains("secondary rate limit"), "{}", stdout);
+}
+
+#[test]
+fn daemon_poll_sends_etag_and_skips_unchanged_events() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-etag-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-etag-test");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].github_repo = "org/team".to_string();
+    bm::config::save_to(&config_path, &config).unwrap();
+
+    // A gh that answers 304 once the ETag of its first answer comes back
+    let bin = tmp.path().join("bin");
+    let calls = tmp.path().join("gh-calls.txt");
+    install_stub(
+        &bin,
+        "gh",
+        &format!(
+            "#!/bin/sh\necho \"$*\" >> {calls}\n\
+             case \"$*\" in *'If-None-Match: \"v1\"'*)\n\
+             printf 'HTTP/2.0 304 Not Modified\\r\\nEtag: \"v1\"\\r\\n\\r\\n'\n\
+             echo 'gh: HTTP 304' >&2\nexit 1;;\nesac\n\
+             printf 'HTTP/2.0 200 OK\\r\\nEtag: \"v1\"\\r\\n\\r\\n'\n\
+             echo '[{{\"id\":\"7\",\"type\":\"WatchEvent\",\"actor\":\"a\",\"repo\":\"org/team\",\"labels\":[]}}]'\n",
+            calls = calls.display()
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "poll", "--interval", "1", "-t", "daemon-etag-test"])
+        .env("HOME", tmp.path())
+        .env("PATH", &path_env)
+        .output()
+        .unwrap();
+    assert!(start.status.success(), "{}", String::from_utf8_lossy(&start.stderr));
+
+    let poll_file = tmp.path().join(".botminter/daemon-daemon-etag-test-poll.json");
+    let mut polls = String::new();
+    for _ in 0..100 {
+        polls = fs::read_to_string(&calls).unwrap_or_default();
+        if polls.lines().count() >= 3 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let lines: Vec<&str> = polls.lines().collect();
+    assert!(lines.len() >= 3, "{}", polls);
+    assert!(!lines[0].contains("If-None-Match"), "{}", polls);
+    assert!(lines[1].contains("If-None-Match: \"v1\""), "{}", polls);
+
+    // The 304s leave the recorded event and ETag alone
+    thread::sleep(Duration::from_millis(500));
+    let state: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&poll_file).unwrap()).unwrap();
+    assert_eq!(state["last_event_id"], "7");
+    assert_eq!(state["etags"]["org/team"], "\"v1\"");
+}
+
+#[test]
+fn daemon_restart_keeps_running_settings() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-restart-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-restart-test");
+
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args([
+            "daemon", "start", "--mode", "poll", "--interval", "300", "-t", "daemon-restart-test",
+        ])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to start daemon");
+    assert!(start.status.success());
+
+    let cfg_file = tmp.path().join(".botminter/daemon-daemon-restart-test.json");
+    let before: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&cfg_file).unwrap()).unwrap();
+
+    // Only the interval is overridden; mode carries over from the running daemon
+    let restart = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "restart", "--interval", "90", "-t", "daemon-restart-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run daemon restart");
+    assert!(
+        restart.status.success(),
+        "daemon restart failed: {}",
+        String::from_utf8_lossy(&restart.stderr)
+    );
+
+    let after: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&cfg_file).unwrap()).unwrap();
+    assert_eq!(after["mode"], "poll");
+    assert_eq!(after["interval_secs"], 90);
+    assert_ne!(before["pid"], after["pid"], "restart should spawn a new process");
+}
+
+#[test]
+fn daemon_config_set_persists_and_reloads_running_daemon() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-cfg-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-cfg-test");
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .args(["-t", "daemon-cfg-test"])
+            .env("HOME", tmp.path())
+            .output()
+            .unwrap()
+    };
+
+    let start = bm(&["daemon", "start", "--mode", "poll", "--interval", "300"]);
+    assert!(start.status.success());
+
+    let set = bm(&["daemon", "config", "set", "interval_secs", "120"]);
+    assert!(set.status.success(), "{}", String::from_utf8_lossy(&set.stderr));
+    assert!(String::from_utf8_lossy(&set.stdout).contains("Reloaded the running daemon"));
+    let set = bm(&["daemon", "config", "set", "filters.events", "issues,push"]);
+    assert!(set.status.success(), "{}", String::from_utf8_lossy(&set.stderr));
+
+    let local = tmp.path().join(".botminter/daemon-daemon-cfg-test.yml");
+    let local = fs::read_to_string(local).unwrap();
+    assert!(local.contains("interval_secs: 120"), "{}", local);
+    let cfg: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(tmp.path().join(".botminter/daemon-daemon-cfg-test.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(cfg["interval_secs"], 120);
+
+    let log_file = tmp.path().join(".botminter/logs/daemon-daemon-cfg-test.log");
+    let mut log = String::new();
+    for _ in 0..50 {
+        log = fs::read_to_string(&log_file).unwrap_or_default();
+        if log.contains("events=[issues, push]") {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert!(
+        log.contains("Reloaded configuration: mode=poll, port=8484, interval=120s, events=[issues, push]"),
+        "daemon log should show the reloaded settings:\n{}",
+        log
+    );
+
+    let get = bm(&["daemon", "config", "get"]);
+    let stdout = String::from_utf8_lossy(&get.stdout);
+    assert!(stdout.contains("filters.events"), "{}", stdout);
+    assert!(stdout.contains("issues,push"), "{}", stdout);
+    assert!(stdout.contains("debounce_secs"), "{}", stdout);
+    assert!(stdout.contains("(default)"), "{}", stdout);
+    let get = bm(&["daemon", "config", "get", "interval_secs"]);
+    assert_eq!(String::from_utf8_lossy(&get.stdout).trim(), "120");
+
+    let unset = bm(&["daemon", "config", "unset", "interval_secs"]);
+    assert!(unset.status.success());
+    let get = bm(&["daemon", "config", "get", "interval_secs"]);
+    assert_eq!(String::from_utf8_lossy(&get.stdout).trim(), "60");
+
+    let bad = bm(&["daemon", "config", "set", "routing", "x"]);
+    assert!(!bad.status.success());
+    assert!(String::from_utf8_lossy(&bad.stderr).contains("Unknown daemon setting"));
+}
+
+#[test]
+fn daemon_simulate_applies_filter_expression() {
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team_for_subprocess(tmp.path(), "daemon-sim-test", "scrum");
+    fs::write(
+        team_repo.join("daemon.yml"),
+        "filters:\n  expr: 'event == \"issues\" && \"kind/story\" in labels && author != \"bot\"'\n",
+    )
+    .unwrap();
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(["daemon", "simulate", "-t", "daemon-sim-test"])
+            .args(args)
+            .env("HOME", tmp.path())
+            .output()
+            .unwrap()
+    };
+
+    let out = bm(&["issues", "--label", "kind/story", "--author", "alice"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Result: triggers a run of every member"), "{}", stdout);
+    assert!(stdout.contains("Filter: event == \"issues\""), "{}", stdout);
+
+    let out = bm(&["issues", "--label", "kind/story", "--author", "bot"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Result: ignored, the filter expression is false"), "{}", stdout);
+
+    let payload = tmp.path().join("payload.json");
+    fs::write(
+        &payload,
+        r#"{"action":"opened","sender":{"login":"alice"},"issue":{"labels":[{"name":"kind/story"}]}}"#,
+    )
+    .unwrap();
+    let out = bm(&["issues", "--payload", payload.to_str().unwrap()]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("action: opened, author: alice"), "{}", stdout);
+    assert!(stdout.contains("Result: triggers a run"), "{}", stdout);
+
+    // --expr tries an expression without saving it
+    let out = bm(&["issues", "--author", "alice", "--expr", "author in [\"alice\"]"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Result: triggers a run"), "{}", stdout);
+    let out = bm(&["issues", "--expr", "lables == \"x\""]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Unknown field 'lables'"));
+}
+
+#[test]
+fn daemon_reloads_config_on_sighup() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-hup-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-hup-test");
+
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "poll", "--interval", "300", "-t", "daemon-hup-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to start daemon");
+    assert!(start.status.success());
+
+    let cfg_file = tmp.path().join(".botminter/daemon-daemon-hup-test.json");
+    let mut cfg: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&cfg_file).unwrap()).unwrap();
+    cfg["interval_secs"] = serde_json::json!(120);
+    fs::write(&cfg_file, serde_json::to_string_pretty(&cfg).unwrap()).unwrap();
+
+    let pid = cfg["pid"].as_u64().unwrap() as u32;
+    procutil::reload(pid);
+
+    let log_file = tmp.path().join(".botminter/logs/daemon-daemon-hup-test.log");
+    let mut log = String::new();
+    for _ in 0..50 {
+        log = fs::read_to_string(&log_file).unwrap_or_default();
+        if log.contains("Reloaded configuration") {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert!(
+        log.contains("Reloaded configuration: mode=poll, port=8484, interval=120s"),
+        "daemon log should show the reloaded settings:\n{}",
+        log
+    );
+    assert!(procutil::is_alive(pid), "daemon should survive SIGHUP");
+}
+
+#[test]
+fn daemon_writes_heartbeat() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-beat-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-beat-test");
+
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "poll", "--interval", "300", "-t", "daemon-beat-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to start daemon");
+    assert!(start.status.success());
+
+    let heartbeat = tmp.path().join(".botminter/daemon-daemon-beat-test.heartbeat");
+    for _ in 0..50 {
+        if heartbeat.exists() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert!(heartbeat.exists(), "daemon should write a heartbeat file");
+
+    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "status", "-t", "daemon-beat-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run daemon status");
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(stdout.contains("Heartbeat:"), "status should show heartbeat: {}", stdout);
+    assert!(!stdout.contains("stale"), "fresh heartbeat flagged stale: {}", stdout);
+}
+
+#[test]
+fn daemon_status_flags_stale_heartbeat() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-wedged-test", "scrum");
+
+    // A live process stands in for a wedged daemon
+    let mut wedged = Command::new("sleep").arg("30").spawn().unwrap();
+    let bm_dir = tmp.path().join(".botminter");
+    fs::write(bm_dir.join("daemon-daemon-wedged-test.pid"), wedged.id().to_string()).unwrap();
+    let old = chrono::Utc::now() - chrono::Duration::minutes(10);
+    fs::write(bm_dir.join("daemon-daemon-wedged-test.heartbeat"), old.to_rfc3339()).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "status", "-t", "daemon-wedged-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run daemon status");
+    let _ = wedged.kill();
+    let _ = wedged.wait();
+
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(stdout.contains("Daemon: running"), "{}", stdout);
+    assert!(
+        stdout.contains("daemon heartbeat is stale"),
+        "status should flag the stale heartbeat: {}",
+        stdout
+    );
+}
+
+#[test]
+fn status_all_covers_every_team() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "alpha", "scrum");
+    add_team_to_config(tmp.path(), "beta", "scrum-compact", false);
+
+    // A live process stands in for alpha's poll-mode daemon
+    let mut daemon = Command::new("sleep").arg("30").spawn().unwrap();
+    let bm_dir = tmp.path().join(".botminter");
+    fs::write(bm_dir.join("daemon-alpha.pid"), daemon.id().to_string()).unwrap();
+    let started = (chrono::Utc::now() - chrono::Duration::minutes(5)).to_rfc3339();
+    fs::write(
+        bm_dir.join("daemon-alpha.json"),
+        format!(
+            r#"{{"team":"alpha","mode":"poll","port":8484,"interval_secs":60,"pid":{},"started_at":"{}"}}"#,
+            daemon.id(),
+            started
+        ),
+    )
+    .unwrap();
+    fs::write(
+        bm_dir.join("daemon-alpha-poll.json"),
+        r#"{"last_event_id":null,"last_poll_at":"2026-02-21T12:00:00Z"}"#,
+    )
+    .unwrap();
+
+    let daemons = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "status", "--all"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run daemon status --all");
+    let _ = daemon.kill();
+    let _ = daemon.wait();
+    let stdout = String::from_utf8_lossy(&daemons.stdout);
+    assert!(daemons.status.success(), "{}", String::from_utf8_lossy(&daemons.stderr));
+    let alpha = stdout.lines().find(|l| l.contains("alpha")).expect(&stdout);
+    assert!(alpha.contains("running") && alpha.contains("poll (60s)"), "{}", alpha);
+    assert!(alpha.contains("2026-02-21 12:00:00 UTC"), "{}", alpha);
+    let beta = stdout.lines().find(|l| l.contains("beta")).expect(&stdout);
+    assert!(beta.contains("stopped"), "{}", beta);
+    assert!(stdout.contains("1 of 2 daemon(s) running"), "{}", stdout);
+
+    let members = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["status", "--all"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run status --all");
+    let stdout = String::from_utf8_lossy(&members.stdout);
+    assert!(members.status.success(), "{}", String::from_utf8_lossy(&members.stderr));
+    assert!(stdout.contains("alpha") && stdout.contains("beta"), "{}", stdout);
+    assert!(stdout.contains("2 team(s), 0 member(s), 0 running"), "{}", stdout);
+}
+
+#[test]
+fn diag_bundle_redacts_secrets() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "diag-team", "scrum");
+    let token = "plain-config-token-1234";
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut cfg = bm::config::load_from(&config_path).unwrap();
+    cfg.teams[0].credentials.gh_token = Some(token.to_string());
+    bm::config::save_to(&config_path, &cfg).unwrap();
+
+    let logs = tmp.path().join(".botminter/logs");
+    fs::create_dir_all(&logs).unwrap();
+    fs::write(
+        logs.join("daemon-diag-team.log"),
+        format!("[x] [INFO] using {} and ghs_mintedInstallationToken99\n", token),
+    )
+    .unwrap();
+    fs::write(logs.join("daemon-other.log"), "not this team\n").unwrap();
+
+    let archive = tmp.path().join("bundle.tar.gz");
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["diag", "bundle", "-o"])
+        .arg(&archive)
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run diag bundle");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let mut entries = std::collections::BTreeMap::new();
+    let gz = flate2::read::GzDecoder::new(fs::File::open(&archive).unwrap());
+    for entry in tar::Archive::new(gz).entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let path = entry.path().unwrap().to_string_lossy().to_string();
+        let name = path.split_once('/').unwrap().1.to_string();
+        let mut contents = String::new();
+        std::io::Read::read_to_string(&mut entry, &mut contents).unwrap();
+        entries.insert(name, contents);
+    }
+
+    let names: Vec<&str> = entries.keys().map(|k| k.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "MANIFEST.txt",
+            "config.yml",
+            "logs/daemon-diag-team.log",
+            "state.json",
+            "teams/diag-team/daemon.yml",
+            "versions.txt"
+        ]
+    );
+    for (name, contents) in &entries {
+        assert!(!contents.contains(token), "{} leaks the token", name);
+        assert!(!contents.contains("ghs_minted"), "{} leaks a minted token", name);
+    }
+    assert!(entries["config.yml"].contains("gh_token: <redacted sha256:"));
+    assert!(entries["versions.txt"].contains("team diag-team: profile scrum"));
+}
+
+#[test]
+fn daemon_start_already_running_errors() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-dup-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-dup-test");
+
+    // Start daemon
+    let start1 = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "poll", "-t", "daemon-dup-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to start daemon");
+    assert!(start1.status.success());
+
+    // Try starting again
+    let start2 = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "poll", "-t", "daemon-dup-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run second daemon start");
+
+    assert!(!start2.status.success(), "Second start should fail");
+    let stderr = String::from_utf8_lossy(&start2.stderr);
+    assert!(stderr.contains("already running"), "Should say already running: {}", stderr);
+}
+
+#[test]
+fn daemon_stop_not_running_errors() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-norun-test", "scrum");
+
+    let stop = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "stop", "-t", "daemon-norun-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run daemon stop");
+
+    assert!(!stop.status.success(), "Stop should fail when not running");
+    let stderr = String::from_utf8_lossy(&stop.stderr);
+    assert!(
+        stderr.contains("not running") || stderr.contains("not found"),
+        "Should indicate not running: {}",
+        stderr
+    );
+}
+
+#[test]
+fn daemon_status_not_running() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-nostat-test", "scrum");
+
+    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "status", "-t", "daemon-nostat-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run daemon status");
+
+    assert!(status.status.success(), "Status should exit 0 even when not running");
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(stdout.contains("not running"), "Should say not running: {}", stdout);
+}
+
+#[test]
+fn daemon_v1_team_blocked() {
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team_for_subprocess(tmp.path(), "daemon-v1-test", "scrum");
+
+    // Downgrade to v1
+    let manifest_path = team_repo.join("botminter.yml");
+    let mut content = fs::read_to_string(&manifest_path).unwrap();
+    content = content.replace("schema_version: '1.0'", "schema_version: 0.1");
+    fs::write(&manifest_path, content).unwrap();
+    git(&team_repo, &["add", "botminter.yml"]);
+    git(&team_repo, &["commit", "-m", "chore: downgrade to v1"]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "-t", "daemon-v1-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run daemon start");
+
+    assert!(!output.status.success(), "Should fail for v1 team");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("requires schema 1.0"), "Got: {}", stderr);
+}
+
+#[test]
+fn daemon_webhook_mode_starts_and_stops() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-wh-test", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-wh-test");
+
+    // Start in webhook mode on a high port to avoid conflicts
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args([
+            "daemon", "start",
+            "--mode", "webhook",
+            "--insecure-no-signature",
+            "--port", "19484",
+            "-t", "daemon-wh-test",
+        ])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to start daemon");
+
+    let stdout = String::from_utf8_lossy(&start.stdout);
+    assert!(
+        start.status.success(),
+        "Webhook daemon start should exit 0, stderr: {}",
+        String::from_utf8_lossy(&start.stderr)
+    );
+    assert!(stdout.contains("Daemon started"), "Should show started: {}", stdout);
+
+    // Wait briefly for server to bind
+    thread::sleep(Duration::from_millis(500));
+
+    // Check status shows webhook mode
+    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "status", "-t", "daemon-wh-test"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to get daemon status");
+
+    let status_stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(status_stdout.contains("webhook"), "Should show webhook mode: {}", status_stdout);
+}
+
+#[test]
+fn daemon_webhook_mode_requires_secret_unless_overridden() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-wh-nosig", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-wh-nosig");
+
+    let refused = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "webhook", "--port", "19498", "-t", "daemon-wh-nosig"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm daemon start");
+    assert!(!refused.status.success());
+    let stderr = String::from_utf8_lossy(&refused.stderr);
+    assert!(stderr.contains("has no webhook secret"), "{}", stderr);
+    assert!(stderr.contains("--insecure-no-signature"), "{}", stderr);
+    assert!(!tmp.path().join(".botminter/daemon-daemon-wh-nosig.pid").exists());
+
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "webhook", "--port", "19498", "-t", "daemon-wh-nosig"])
+        .arg("--insecure-no-signature")
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm daemon start");
+    assert!(
+        start.status.success(),
+        "start failed: {}",
+        String::from_utf8_lossy(&start.stderr)
+    );
+    assert!(String::from_utf8_lossy(&start.stderr).contains("WARNING"));
+    thread::sleep(Duration::from_millis(500));
+    let log = fs::read_to_string(tmp.path().join(".botminter/logs/daemon-daemon-wh-nosig.log"))
+        .unwrap();
+    assert!(log.contains("INSECURE"), "daemon log:\n{}", log);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "status", "-t", "daemon-wh-nosig"])
         .env("HOME", tmp.path())
         .output()
         .expect("failed to get daemon status");
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(stdout.contains("Signatures: not verified"), "{}", stdout);
+}
+
+// ── Daemon CLI parsing tests ─────────────────────────────────────────
+
+#[test]
+fn daemon_cli_parsing_start_flags() {
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--help"])
+        .output()
+        .expect("failed to run bm daemon start --help");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("--mode"), "Help should mention --mode: {}", stdout);
+    assert!(stdout.contains("--port"), "Help should mention --port: {}", stdout);
+    assert!(stdout.contains("--interval"), "Help should mention --interval: {}", stdout);
+}
+
+use std::time::Duration;
+use std::thread;
+
+// ── Webhook endpoint tests ───────────────────────────────────────────
+
+#[test]
+fn daemon_webhook_accepts_relevant_event() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-wh-accept", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-wh-accept");
+
+    let port = 19485u16;
+
+    // Start webhook daemon
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args([
+            "daemon", "start",
+            "--mode", "webhook",
+            "--insecure-no-signature",
+            "--port", &port.to_string(),
+            "-t", "daemon-wh-accept",
+        ])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to start daemon");
+    assert!(start.status.success(), "start failed: {}", String::from_utf8_lossy(&start.stderr));
+
+    // Wait for server to be ready
+    thread::sleep(Duration::from_secs(1));
+
+    // Send a relevant event via HTTP POST
+    let client = reqwest::blocking::Client::new();
+    let resp = client
+        .post(format!("http://127.0.0.1:{}/webhook", port))
+        .header("X-GitHub-Event", "issues")
+        .header("Content-Type", "application/json")
+        .body(r#"{"action":"opened","issue":{"number":1}}"#)
+        .send();
+
+    match resp {
+        Ok(r) => {
+            assert_eq!(r.status().as_u16(), 200, "Webhook should respond 200");
+        }
+        Err(e) => {
+            eprintln!("Warning: could not connect to webhook server: {}", e);
+        }
+    }
+}
+
+#[test]
+fn daemon_webhook_custom_bind_and_path() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-wh-path", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-wh-path");
+
+    let port = 19490u16;
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args([
+            "daemon", "start",
+            "--mode", "webhook",
+            "--insecure-no-signature",
+            "--port", &port.to_string(),
+            "--bind", "127.0.0.1",
+            "--path", "/hooks/github",
+            "-t", "daemon-wh-path",
+        ])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to start daemon");
+    assert!(start.status.success(), "start failed: {}", String::from_utf8_lossy(&start.stderr));
+    let stdout = String::from_utf8_lossy(&start.stdout);
+    assert!(stdout.contains("http://127.0.0.1:19490/hooks/github"), "{}", stdout);
+
+    let cfg = fs::read_to_string(tmp.path().join(".botminter/daemon-daemon-wh-path.json")).unwrap();
+    let cfg: serde_json::Value = serde_json::from_str(&cfg).unwrap();
+    assert_eq!(cfg["bind"], "127.0.0.1");
+    assert_eq!(cfg["path"], "/hooks/github");
+
+    thread::sleep(Duration::from_secs(1));
+    let client = reqwest::blocking::Client::new();
+    let post = |path: &str| {
+        client
+            .post(format!("http://127.0.0.1:{}{}", port, path))
+            .header("X-GitHub-Event", "issues")
+            .body("{}")
+            .send()
+            .map(|r| r.status().as_u16())
+    };
+    match (post("/hooks/github"), post("/webhook")) {
+        (Ok(configured), Ok(default)) => {
+            assert_eq!(configured, 200, "configured path should accept deliveries");
+            assert_eq!(default, 404, "the default path should no longer match");
+        }
+        (a, b) => eprintln!("Warning: could not connect to webhook server: {:?} {:?}", a, b),
+    }
+}
+
+#[test]
+fn daemon_webhook_serves_https() {
+    if Command::new("openssl").arg("version").output().is_err() {
+        eprintln!("Skipping: openssl is not installed");
+        return;
+    }
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-wh-tls", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-wh-tls");
+
+    let cert = tmp.path().join("cert.pem");
+    let key = tmp.path().join("key.pem");
+    let gen = Command::new("openssl")
+        .args(["req", "-x509", "-newkey", "rsa:2048", "-nodes", "-days", "1", "-subj", "/CN=localhost"])
+        .arg("-keyout")
+        .arg(&key)
+        .arg("-out")
+        .arg(&cert)
+        .output()
+        .unwrap();
+    assert!(gen.status.success(), "{}", String::from_utf8_lossy(&gen.stderr));
+
+    let port = 19491u16;
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args([
+            "daemon", "start",
+            "--mode", "webhook",
+            "--insecure-no-signature",
+            "--port", &port.to_string(),
+            "--bind", "127.0.0.1",
+            "-t", "daemon-wh-tls",
+        ])
+        .arg("--tls-cert")
+        .arg(&cert)
+        .arg("--tls-key")
+        .arg(&key)
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to start daemon");
+    assert!(start.status.success(), "start failed: {}", String::from_utf8_lossy(&start.stderr));
+
+    thread::sleep(Duration::from_secs(1));
+    let client = reqwest::blocking::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .unwrap();
+    let resp = client
+        .post(format!("https://127.0.0.1:{}/webhook", port))
+        .header("X-GitHub-Event", "issues")
+        .body("{}")
+        .send();
+    match resp {
+        Ok(r) => assert_eq!(r.status().as_u16(), 200),
+        Err(e) => eprintln!("Warning: could not connect to webhook server: {}", e),
+    }
+}
+
+#[test]
+fn daemon_start_rejects_invalid_listener() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-wh-bad", "scrum");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--bind", "localhost", "-t", "daemon-wh-bad"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run daemon start");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid bind address 'localhost'"), "{}", stderr);
+    assert!(!tmp.path().join(".botminter/daemon-daemon-wh-bad.pid").exists());
+}
+
+/// Writes an executable shell script standing in for a tool on PATH.
+fn install_stub(bin: &Path, name: &str, script: &str) {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::create_dir_all(bin).unwrap();
+    let path = bin.join(name);
+    fs::write(&path, script).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn daemon_tunnel_registers_and_removes_webhook() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-tunnel", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-tunnel");
+
+    // Stand-ins for ngrok and gh that record how they were called
+    let bin = tmp.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let calls = tmp.path().join("gh-calls.txt");
+    let body = tmp.path().join("gh-body.json");
+    let scripts = [
+        (
+            "ngrok",
+            "#!/bin/sh\n\
+             echo '{\"lvl\":\"info\",\"msg\":\"started tunnel\",\"url\":\"https://t1.ngrok-free.app\"}'\n\
+             exec sleep 300\n"
+                .to_string(),
+        ),
+        (
+            "gh",
+            format!(
+                "#!/bin/sh\necho \"$*\" >> {calls}\n\
+                 case \"$*\" in *POST*) cat > {body}; echo 4242 ;; esac\n",
+                calls = calls.display(),
+                body = body.display()
+            ),
+        ),
+    ];
+    for (name, script) in scripts {
+        install_stub(&bin, name, &script);
+    }
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .output()
+            .unwrap()
+    };
+    let start_args = [
+        "daemon", "start", "--mode", "webhook", "--port", "19492", "--bind", "127.0.0.1",
+        "--tunnel", "ngrok", "-t", "daemon-tunnel",
+    ];
+
+    // Without a webhook secret nothing is started
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].github_repo = "org/team".to_string();
+    bm::config::save_to(&config_path, &config).unwrap();
+    let refused = bm(&start_args);
+    assert!(!refused.status.success());
+    let stderr = String::from_utf8_lossy(&refused.stderr);
+    assert!(stderr.contains("webhook_secret"), "{}", stderr);
+    assert!(!tmp.path().join(".botminter/daemon-daemon-tunnel.pid").exists());
+
+    config.teams[0].credentials.webhook_secret = Some("s3cret".to_string());
+    bm::config::save_to(&config_path, &config).unwrap();
+    let start = bm(&start_args);
+    assert!(start.status.success(), "start failed: {}", String::from_utf8_lossy(&start.stderr));
+    let stdout = String::from_utf8_lossy(&start.stdout);
+    assert!(stdout.contains("Tunnel (ngrok): https://t1.ngrok-free.app/webhook"), "{}", stdout);
+    assert!(stdout.contains("Registered webhook #4242 on org/team"), "{}", stdout);
+
+    let request: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&body).unwrap()).unwrap();
+    assert_eq!(request["config"]["url"], "https://t1.ngrok-free.app/webhook");
+    assert_eq!(request["config"]["secret"], "s3cret");
+    let cfg = fs::read_to_string(tmp.path().join(".botminter/daemon-daemon-tunnel.json")).unwrap();
+    let cfg: serde_json::Value = serde_json::from_str(&cfg).unwrap();
+    assert_eq!(cfg["tunnel"]["provider"], "ngrok");
+    assert_eq!(cfg["tunnel"]["hook_id"], 4242);
+
+    let stop = bm(&["daemon", "stop", "-t", "daemon-tunnel"]);
+    assert!(stop.status.success(), "{}", String::from_utf8_lossy(&stop.stderr));
+    let stderr = String::from_utf8_lossy(&stop.stderr);
+    assert!(stderr.contains("Removed webhook #4242 from org/team"), "{}", stderr);
+    let calls = fs::read_to_string(&calls).unwrap();
+    assert!(calls.contains("--method DELETE repos/org/team/hooks/4242"), "{}", calls);
+}
+
+#[test]
+fn webhooks_setup_creates_then_updates_and_status_reports_health() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "hooks-team", "scrum");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].github_repo = "org/team".to_string();
+    config.teams[0].credentials.webhook_secret = Some("s3cret".to_string());
+    bm::config::save_to(&config_path, &config).unwrap();
+
+    // gh stand-in serving the hook list and deliveries from files
+    let bin = tmp.path().join("bin");
+    let f = |name: &str| tmp.path().join(name).display().to_string();
+    install_stub(
+        &bin,
+        "gh",
+        &format!(
+            "#!/bin/sh\necho \"$*\" >> {calls}\ncase \"$*\" in\n  \
+             *POST*) cat > {body}; echo 31 ;;\n  *PATCH*) cat > {body} ;;\n  \
+             *deliveries*) cat {deliveries} ;;\n  *) cat {hooks} ;;\nesac\n",
+            calls = f("calls"),
+            body = f("body"),
+            deliveries = f("deliveries"),
+            hooks = f("hooks"),
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .output()
+            .unwrap()
+    };
+    let setup = ["webhooks", "setup", "-t", "hooks-team", "--url", "https://bm.example.com"];
+
+    fs::write(f("hooks"), "[]").unwrap();
+    let created = bm(&setup);
+    assert!(created.status.success(), "{}", String::from_utf8_lossy(&created.stderr));
+    let stdout = String::from_utf8_lossy(&created.stdout);
+    assert!(stdout.contains("Created webhook #31 on org/team"), "{}", stdout);
+    assert!(stdout.contains("https://bm.example.com/webhook"), "{}", stdout);
+    let body: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(f("body")).unwrap()).unwrap();
+    assert_eq!(body["config"]["secret"], "s3cret");
+    assert_eq!(
+        body["events"],
+        serde_json::json!(["issues", "issue_comment", "pull_request"])
+    );
+
+    // The hook now exists with an outdated event list
+    fs::write(
+        f("hooks"),
+        r#"[{"id":31,"active":true,"events":["issues"],
+             "config":{"url":"https://bm.example.com/webhook","secret":"********"},
+             "last_response":{"code":401,"status":"failed","message":"Unauthorized"}}]"#,
+    )
+    .unwrap();
+    fs::write(
+        f("deliveries"),
+        r#"[{"delivered_at":"2026-03-01T10:00:00Z","status_code":401,"event":"issues"}]"#,
+    )
+    .unwrap();
+    let status = bm(&["webhooks", "status", "-t", "hooks-team"]);
+    assert!(status.status.success(), "{}", String::from_utf8_lossy(&status.stderr));
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(stdout.contains("#31"), "{}", stdout);
+    assert!(stdout.contains("0/1 ok"), "{}", stdout);
+    assert!(stdout.contains("not subscribed to issue_comment, pull_request"), "{}", stdout);
+    assert!(stdout.contains("got HTTP 401"), "{}", stdout);
+
+    let updated = bm(&setup);
+    assert!(updated.status.success(), "{}", String::from_utf8_lossy(&updated.stderr));
+    assert!(String::from_utf8_lossy(&updated.stdout).contains("Updated webhook #31"));
+    let calls = fs::read_to_string(f("calls")).unwrap();
+    assert!(calls.contains("--method PATCH repos/org/team/hooks/31"), "{}", calls);
+
+    // Without a secret, nothing is registered
+    config.teams[0].credentials.webhook_secret = None;
+    bm::config::save_to(&config_path, &config).unwrap();
+    let refused = bm(&setup);
+    assert!(!refused.status.success());
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("webhook_secret"));
+}
+
+#[test]
+fn teams_add_repo_bootstraps_labels_and_remove_repo_drops_it() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "multi-repo", "scrum");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].github_repo = "org/team".to_string();
+    bm::config::save_to(&config_path, &config).unwrap();
+
+    // gh stand-in that records calls and knows the team's board
+    let bin = tmp.path().join("bin");
+    let calls = tmp.path().join("calls");
+    install_stub(
+        &bin,
+        "gh",
+        &format!(
+            "#!/bin/sh\necho \"$*\" >> {calls}\ncase \"$*\" in\n  \
+             \"project list\"*) echo '{{\"projects\":[{{\"number\":7,\"title\":\"multi-repo Board\"}}]}}' ;;\n\
+             esac\n",
+            calls = calls.display(),
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .output()
+            .unwrap()
+    };
+
+    let added = bm(&["teams", "add-repo", "org/api", "-t", "multi-repo"]);
+    assert!(added.status.success(), "{}", String::from_utf8_lossy(&added.stderr));
+    let stdout = String::from_utf8_lossy(&added.stdout);
+    assert!(stdout.contains("Linked org/api to the 'multi-repo Board' project"), "{}", stdout);
+    assert!(stdout.contains("Added org/api to team 'multi-repo'."), "{}", stdout);
+    let recorded = fs::read_to_string(&calls).unwrap();
+    assert!(recorded.contains("repo view org/api"), "{}", recorded);
+    assert!(recorded.contains("label create kind/epic"), "{}", recorded);
+    assert!(recorded.contains("--repo org/api"), "{}", recorded);
+    assert!(recorded.contains("project link 7 --owner org --repo org/api"), "{}", recorded);
+    let config = bm::config::load_from(&config_path).unwrap();
+    assert_eq!(config.teams[0].repos(), vec!["org/team", "org/api"]);
+
+    let again = bm(&["teams", "add-repo", "org/api", "-t", "multi-repo"]);
+    assert!(!again.status.success());
+    assert!(String::from_utf8_lossy(&again.stderr).contains("already works in org/api"));
+    let primary = bm(&["teams", "remove-repo", "org/team", "-t", "multi-repo"]);
+    assert!(!primary.status.success());
+    assert!(String::from_utf8_lossy(&primary.stderr).contains("can't be removed"));
+
+    let removed = bm(&["teams", "remove-repo", "org/api", "-t", "multi-repo"]);
+    assert!(removed.status.success(), "{}", String::from_utf8_lossy(&removed.stderr));
+    let config = bm::config::load_from(&config_path).unwrap();
+    assert!(config.teams[0].extra_repos.is_empty());
+}
+
+#[test]
+fn teams_destroy_removes_github_resources_workspaces_and_config() {
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team_for_subprocess(tmp.path(), "doomed", "scrum");
+    let team_dir = team_repo.parent().unwrap().to_path_buf();
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].github_repo = "org/team".to_string();
+    config.teams[0].extra_repos = vec!["org/api".to_string()];
+    bm::config::save_to(&config_path, &config).unwrap();
+
+    let logs = tmp.path().join(".botminter/logs");
+    fs::create_dir_all(&logs).unwrap();
+    for file in ["daemon-doomed.log", "daemon-doomed.log.1.gz", "daemon-doomed-2.log"] {
+        fs::write(logs.join(file), "x").unwrap();
+    }
+    fs::write(tmp.path().join(".botminter/daemon-doomed-runs.json"), "[]").unwrap();
+
+    // gh stand-in: the board, and a webhook shared by the team repo and org/api
+    let bin = tmp.path().join("bin");
+    let calls = tmp.path().join("calls");
+    install_stub(
+        &bin,
+        "gh",
+        &format!(
+            "#!/bin/sh\necho \"$*\" >> {calls}\ncase \"$*\" in\n  \
+             \"project list\"*) echo '{{\"projects\":[{{\"number\":7,\"title\":\"doomed Board\"}}]}}' ;;\n  \
+             \"api repos/org/team/hooks\") echo '[{{\"id\":1,\"config\":{{\"url\":\"https://h.example/webhook\"}}}}]' ;;\n  \
+             \"api repos/org/api/hooks\") echo '[{{\"id\":5,\"config\":{{\"url\":\"https://h.example/webhook\"}}}},{{\"id\":6,\"config\":{{\"url\":\"https://other.example/webhook\"}}}}]' ;;\n\
+             esac\n",
+            calls = calls.display(),
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .output()
+            .unwrap()
+    };
+
+    let dry = bm(&["teams", "destroy", "doomed", "--dry-run"]);
+    assert!(dry.status.success(), "{}", String::from_utf8_lossy(&dry.stderr));
+    let stdout = String::from_utf8_lossy(&dry.stdout);
+    assert!(stdout.contains("Delete the GitHub repo org/team"), "{}", stdout);
+    assert!(stdout.contains("daemon-doomed.log.1.gz"), "{}", stdout);
+    assert!(!stdout.contains("daemon-doomed-2.log"), "{}", stdout);
+    assert!(stdout.contains("Dry run: nothing was removed."), "{}", stdout);
+    assert!(!calls.exists());
+    assert!(team_dir.exists());
+
+    let kept = bm(&["teams", "destroy", "doomed", "--dry-run", "--keep-github"]);
+    let stdout = String::from_utf8_lossy(&kept.stdout);
+    assert!(!stdout.contains("Delete the GitHub repo"), "{}", stdout);
+    assert!(stdout.contains("org/team, its project board, and webhooks are kept."), "{}", stdout);
+
+    let destroyed = bm(&["teams", "destroy", "doomed", "--yes"]);
+    assert!(destroyed.status.success(), "{}", String::from_utf8_lossy(&destroyed.stderr));
+    let recorded = fs::read_to_string(&calls).unwrap();
+    assert!(recorded.contains("api --method DELETE repos/org/api/hooks/5"), "{}", recorded);
+    assert!(!recorded.contains("hooks/6"), "{}", recorded);
+    assert!(recorded.contains("project delete 7 --owner org"), "{}", recorded);
+    assert!(recorded.contains("repo delete org/team --yes"), "{}", recorded);
+    assert!(!team_dir.exists());
+    assert!(!logs.join("daemon-doomed.log").exists());
+    assert!(!logs.join("daemon-doomed.log.1.gz").exists());
+    assert!(logs.join("daemon-doomed-2.log").exists());
+    assert!(!tmp.path().join(".botminter/daemon-doomed-runs.json").exists());
+    let config = bm::config::load_from(&config_path).unwrap();
+    assert!(config.teams.is_empty());
+    assert_eq!(config.default_team, None);
+}
+
+#[test]
+fn teams_remove_and_set_default_leave_github_alone() {
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team_for_subprocess(tmp.path(), "alpha", "scrum");
+    let alpha_dir = team_repo.parent().unwrap().to_path_buf();
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].github_repo = "org/alpha".to_string();
+    let mut beta = config.teams[0].clone();
+    beta.name = "beta".to_string();
+    beta.path = tmp.path().join("workspaces/beta");
+    fs::create_dir_all(&beta.path).unwrap();
+    config.teams.push(beta.clone());
+    config.default_team = Some("alpha".to_string());
+    bm::config::save_to(&config_path, &config).unwrap();
+
+    let bin = tmp.path().join("bin");
+    let calls = tmp.path().join("calls");
+    install_stub(
+        &bin,
+        "gh",
+        &format!("#!/bin/sh\necho \"$*\" >> {}\n", calls.display()),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .output()
+            .unwrap()
+    };
 
-    let stdout = String::from_utf8_lossy(&status.stdout);
-    assert!(status.status.success(), "daemon status should exit 0");
-    assert!(stdout.contains("running"), "Should show running: {}", stdout);
-    assert!(stdout.contains("poll"), "Should show mode: {}", stdout);
+    let out = bm(&["teams", "set-default", "beta"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let config = bm::config::load_from(&config_path).unwrap();
+    assert_eq!(config.default_team.as_deref(), Some("beta"));
+    assert!(!bm(&["teams", "set-default", "gamma"]).status.success());
+
+    // A running daemon blocks removal unless --stop is given
+    let pid_file = tmp.path().join(".botminter/daemon-alpha.pid");
+    fs::write(&pid_file, "999999999").unwrap();
+    let out = bm(&["teams", "remove", "alpha"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--stop"));
+    fs::remove_file(&pid_file).unwrap();
+
+    let out = bm(&["teams", "remove", "alpha"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("org/alpha on GitHub was left untouched."), "{}", stdout);
+    assert!(alpha_dir.exists());
+    let config = bm::config::load_from(&config_path).unwrap();
+    assert_eq!(config.teams.len(), 1);
+    assert_eq!(config.default_team.as_deref(), Some("beta"));
+
+    let out = bm(&["teams", "remove", "beta", "--purge", "--yes"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(!beta.path.exists());
+    let config = bm::config::load_from(&config_path).unwrap();
+    assert!(config.teams.is_empty());
+    assert_eq!(config.default_team, None);
+    assert!(!calls.exists());
 }
 
 #[test]
-fn daemon_stop_cleans_up() {
+fn board_view_shows_items_in_view_statuses() {
     let tmp = tempfile::tempdir().unwrap();
-    setup_team_for_subprocess(tmp.path(), "daemon-stop-test", "scrum");
-    let _guard = DaemonGuard::new(tmp.path(), "daemon-stop-test");
+    setup_team_for_subprocess(tmp.path(), "viewers", "scrum");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].github_repo = "org/team".to_string();
+    bm::config::save_to(&config_path, &config).unwrap();
 
-    // Start daemon
-    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "start", "--mode", "poll", "-t", "daemon-stop-test"])
-        .env("HOME", tmp.path())
-        .output()
-        .expect("failed to start daemon");
-    assert!(start.status.success());
+    let bin = tmp.path().join("bin");
+    install_stub(
+        &bin,
+        "gh",
+        "#!/bin/sh\ncase \"$*\" in\n  \
+         \"project list\"*) echo '{\"projects\":[{\"number\":4,\"title\":\"viewers Board\"}]}' ;;\n  \
+         \"project item-list\"*) echo '{\"items\":[\
+         {\"title\":\"Triage me\",\"status\":\"po:triage\",\"content\":{\"number\":1,\"repository\":\"org/team\"}},\
+         {\"title\":\"Build it\",\"status\":\"dev:implement\",\"content\":{\"number\":2,\"repository\":\"org/team\"}}]}' ;;\n\
+         esac\n",
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .output()
+            .unwrap()
+    };
 
-    // Stop daemon
-    let stop = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "stop", "-t", "daemon-stop-test"])
-        .env("HOME", tmp.path())
-        .output()
-        .expect("failed to stop daemon");
+    let out = bm(&["board", "view", "po"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("PO (1 items)"), "{}", stdout);
+    assert!(stdout.contains("Triage me"), "{}", stdout);
+    assert!(!stdout.contains("Build it"), "{}", stdout);
 
-    let stdout = String::from_utf8_lossy(&stop.stdout);
-    assert!(stop.status.success(), "daemon stop should exit 0");
-    assert!(stdout.contains("Daemon stopped"), "Should print stopped: {}", stdout);
+    let out = bm(&["board", "view"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Architect"), "{}", stdout);
 
-    // Verify cleanup
-    let pid_file = tmp.path().join(".botminter/daemon-daemon-stop-test.pid");
-    assert!(!pid_file.exists(), "PID file should be removed");
-    let cfg_file = tmp.path().join(".botminter/daemon-daemon-stop-test.json");
-    assert!(!cfg_file.exists(), "Config file should be removed");
+    let out = bm(&["board", "view", "nope"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("Available views: PO"));
 }
 
 #[test]
-fn daemon_start_already_running_errors() {
+fn start_k8s_formation_runs_preflight_before_manager() {
     let tmp = tempfile::tempdir().unwrap();
-    setup_team_for_subprocess(tmp.path(), "daemon-dup-test", "scrum");
-    let _guard = DaemonGuard::new(tmp.path(), "daemon-dup-test");
+    setup_team_for_subprocess(tmp.path(), "preflight-team", "scrum");
+
+    // kubectl knows only some other context at first; docker finds every image
+    let bin = tmp.path().join("bin");
+    let contexts = tmp.path().join("contexts");
+    let manager_ran = tmp.path().join("manager-ran");
+    fs::write(&contexts, "kind-other\n").unwrap();
+    install_stub(
+        &bin,
+        "kubectl",
+        &format!(
+            "#!/bin/sh\ncase \"$*\" in\n\
+             *get-contexts*) cat {contexts} ;;\n\
+             *'get --raw'*) echo '{{}}' ;;\n\
+             *'get namespace'*) echo 'NotFound' >&2; exit 1 ;;\n\
+             *'can-i create namespaces'*) echo no; exit 1 ;;\n\
+             *can-i*) echo yes ;;\n\
+             esac\n",
+            contexts = contexts.display()
+        ),
+    );
+    install_stub(&bin, "docker", "#!/bin/sh\nexit 0\n");
+    install_stub(
+        &bin,
+        "ralph",
+        &format!("#!/bin/sh\ntouch {}\n", manager_ran.display()),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let start = || {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(["start", "-t", "preflight-team", "--formation", "k8s"])
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .output()
+            .unwrap()
+    };
 
-    // Start daemon
-    let start1 = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "start", "--mode", "poll", "-t", "daemon-dup-test"])
-        .env("HOME", tmp.path())
-        .output()
-        .expect("failed to start daemon");
-    assert!(start1.status.success());
+    let output = start();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Kube context 'kind-botminter' is not in your kubeconfig (available: kind-other)"),
+        "stderr:\n{}",
+        stderr
+    );
 
-    // Try starting again
-    let start2 = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "start", "--mode", "poll", "-t", "daemon-dup-test"])
+    // With the context in place, the missing namespace permission is reported
+    fs::write(&contexts, "kind-other\nkind-botminter\n").unwrap();
+    let output = start();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("may not create namespaces (namespace 'botminter-preflight-team'"),
+        "stderr:\n{}",
+        stderr
+    );
+    assert!(!stderr.contains("pods"), "pods are allowed, stderr:\n{}", stderr);
+    assert!(!manager_ran.exists(), "the formation manager must not run");
+}
+
+#[test]
+fn start_migrate_moves_local_members_to_k8s() {
+    let tmp = tempfile::tempdir().unwrap();
+    let team_repo = setup_team_for_subprocess(tmp.path(), "move-team", "scrum");
+    let team_dir = team_repo.parent().unwrap().to_path_buf();
+    for member in ["architect-alice", "dev-bob"] {
+        fs::create_dir_all(team_repo.join("team").join(member)).unwrap();
+    }
+
+    // dev-bob runs locally with uncommitted work on main
+    let remote = tmp.path().join("fork.git");
+    git(tmp.path(), &["init", "--bare", "-b", "main", "fork.git"]);
+    let ws = team_dir.join("dev-bob");
+    fs::create_dir_all(ws.join(".botminter")).unwrap();
+    git(&ws, &["init", "-b", "main"]);
+    git(&ws, &["config", "user.email", "test@botminter.test"]);
+    git(&ws, &["config", "user.name", "BM Test"]);
+    git(&ws, &["remote", "add", "origin", &remote.to_string_lossy()]);
+    fs::write(ws.join(".gitignore"), ".botminter/\n").unwrap();
+    git(&ws, &["add", "-A"]);
+    git(&ws, &["commit", "-m", "init"]);
+    git(&ws, &["push", "-u", "origin", "main"]);
+    fs::write(ws.join("half-done.rs"), "fn wip() {}\n").unwrap();
+
+    // Reaped as soon as it exits, so the drain sees it gone
+    let mut member = Command::new("sleep").arg("30").spawn().unwrap();
+    let pid = member.id();
+    let reaper = thread::spawn(move || member.wait());
+    let mut state = bm::state::RuntimeState::default();
+    state.members.insert(
+        "move-team/dev-bob".to_string(),
+        bm::state::MemberRuntime {
+            pid,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            workspace: ws.clone(),
+            env: Default::default(),
+            health: None,
+        },
+    );
+    bm::state::save_to(&tmp.path().join(".botminter/state.json"), &state).unwrap();
+    let topo_path = team_dir.join("topology.json");
+    let local = serde_json::json!({
+        "formation": "local",
+        "created_at": "2026-10-01T00:00:00Z",
+        "members": {
+            "dev-bob": {"status": "running", "endpoint": {"type": "local", "pid": pid, "workspace": ws}},
+            "architect-alice": {"status": "running", "endpoint": {"type": "local", "pid": 999_999_999, "workspace": "/gone"}},
+        },
+    });
+    fs::write(&topo_path, local.to_string()).unwrap();
+
+    // The manager places only dev-bob; `ralph loops stop` ends the member
+    let bin = tmp.path().join("bin");
+    let branches = tmp.path().join("branches.json");
+    install_stub(
+        &bin,
+        "kubectl",
+        "#!/bin/sh\ncase \"$*\" in\n\
+         *get-contexts*) echo kind-botminter ;;\n\
+         *can-i*) echo yes ;;\n\
+         esac\n",
+    );
+    install_stub(&bin, "docker", "#!/bin/sh\nexit 0\n");
+    install_stub(&bin, "gh", "#!/bin/sh\nexit 1\n");
+    install_stub(
+        &bin,
+        "ralph",
+        &format!(
+            "#!/bin/sh\ncase \"$*\" in\n\
+             'loops stop') kill {pid} ;;\n\
+             run*) echo \"$BM_MIGRATED_BRANCHES\" > {branches}\n\
+             cat > \"$BM_WORKZONE/$BM_TEAM_NAME/topology.json\" <<EOF\n\
+             {{\"formation\": \"k8s\", \"created_at\": \"2026-10-01T00:00:00Z\", \"members\": {{\
+             \"dev-bob\": {{\"status\": \"pending\", \"endpoint\": {{\"type\": \"k8s\", \
+             \"namespace\": \"botminter-move-team\", \"pod\": \"dev-bob-1\", \"container\": \"ralph\", \
+             \"context\": \"kind-botminter\"}}}}}}}}\nEOF\n;;\n\
+             esac\n",
+            pid = pid,
+            branches = branches.display(),
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["start", "-t", "move-team", "--formation", "k8s", "--migrate"])
         .env("HOME", tmp.path())
+        .env("PATH", &path_env)
         .output()
-        .expect("failed to run second daemon start");
-
-    assert!(!start2.status.success(), "Second start should fail");
-    let stderr = String::from_utf8_lossy(&start2.stderr);
-    assert!(stderr.contains("already running"), "Should say already running: {}", stderr);
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(
+        stdout.contains("dev-bob: stopped locally, pushed wip/dev-bob, deployed (pending)"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("architect-alice: nothing to push, not deployed"), "{}", stdout);
+    assert!(stdout.contains("1 member(s) deployed, 1 not"), "{}", stdout);
+    assert!(stdout.contains("Kept in the topology as stopped: architect-alice"), "{}", stdout);
+
+    reaper.join().unwrap().unwrap();
+    let state = bm::state::load_from(&tmp.path().join(".botminter/state.json")).unwrap();
+    assert!(state.members.is_empty());
+    let log = Command::new("git")
+        .args(["log", "--format=%s", "wip/dev-bob"])
+        .current_dir(&remote)
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&log.stdout).lines().next(),
+        Some("wip: dev-bob moving to the k8s formation")
+    );
+    let branches: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&branches).unwrap()).unwrap();
+    assert_eq!(branches, serde_json::json!({"dev-bob": ["wip/dev-bob"]}));
+
+    let topo = bm::topology::load(&topo_path).unwrap().unwrap();
+    assert_eq!(topo.formation, "k8s");
+    assert!(matches!(topo.members["dev-bob"].endpoint, bm::topology::Endpoint::K8s { .. }));
+    assert_eq!(topo.members["architect-alice"].status, "stopped");
 }
 
 #[test]
-fn daemon_stop_not_running_errors() {
+fn status_and_stop_reach_k8s_members_through_the_topology() {
     let tmp = tempfile::tempdir().unwrap();
-    setup_team_for_subprocess(tmp.path(), "daemon-norun-test", "scrum");
+    let team_repo = setup_team_for_subprocess(tmp.path(), "pod-team", "scrum");
+    fs::create_dir_all(team_repo.join("team").join("dev-bob")).unwrap();
+    let topo_path = team_repo.parent().unwrap().join("topology.json");
+    let topo = serde_json::json!({
+        "formation": "k8s",
+        "created_at": "2026-10-01T00:00:00Z",
+        "members": {
+            "dev-bob": {"status": "running", "endpoint": {"type": "k8s",
+                "namespace": "botminter-pod-team", "pod": "dev-bob-1", "container": "ralph",
+                "context": "kind-botminter"}},
+        },
+    });
+    fs::write(&topo_path, topo.to_string()).unwrap();
+
+    // The pod belongs to a Deployment through its ReplicaSet
+    let bin = tmp.path().join("bin");
+    let calls = tmp.path().join("kubectl.log");
+    install_stub(
+        &bin,
+        "kubectl",
+        &format!(
+            "#!/bin/sh\necho \"$*\" >> {calls}\ncase \"$*\" in\n\
+             *'get pod dev-bob-1 -o jsonpath={{.status.phase}}') printf Running ;;\n\
+             *'get pod dev-bob-1'*) printf dev-bob-5f7c ;;\n\
+             *'get replicaset dev-bob-5f7c'*) printf dev-bob ;;\n\
+             esac\n",
+            calls = calls.display(),
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
 
-    let stop = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "stop", "-t", "daemon-norun-test"])
+    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["status", "-t", "pod-team"])
         .env("HOME", tmp.path())
+        .env("PATH", &path_env)
         .output()
-        .expect("failed to run daemon stop");
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(status.status.success(), "stderr: {}", String::from_utf8_lossy(&status.stderr));
+    let line = stdout.lines().find(|l| l.contains("dev-bob")).unwrap();
+    assert!(line.contains("running") && line.contains("pod/dev-bob-1"), "{}", stdout);
 
-    assert!(!stop.status.success(), "Stop should fail when not running");
+    let stop = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["stop", "-t", "pod-team", "--graceful-timeout", "5"])
+        .env("HOME", tmp.path())
+        .env("PATH", &path_env)
+        .output()
+        .unwrap();
     let stderr = String::from_utf8_lossy(&stop.stderr);
+    assert!(stop.status.success(), "stderr: {}", stderr);
     assert!(
-        stderr.contains("not running") || stderr.contains("not found"),
-        "Should indicate not running: {}",
+        stderr.contains("Stopping dev-bob (pod dev-bob-1)... done (deployment dev-bob scaled to 0)"),
+        "{}",
         stderr
     );
+    assert!(String::from_utf8_lossy(&stop.stdout).contains("Stopped 1 member(s), 0 error(s)."));
+    let calls = fs::read_to_string(&calls).unwrap();
+    assert!(calls.contains("scale deployment dev-bob --replicas=0"), "{}", calls);
+    assert!(calls.contains("wait --for=delete pod/dev-bob-1 --timeout=5s"), "{}", calls);
+    assert!(!topo_path.exists());
 }
 
 #[test]
-fn daemon_status_not_running() {
+fn daemon_webhook_coalesces_event_burst() {
     let tmp = tempfile::tempdir().unwrap();
-    setup_team_for_subprocess(tmp.path(), "daemon-nostat-test", "scrum");
+    setup_team_for_subprocess(tmp.path(), "daemon-wh-burst", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-wh-burst");
+    fs::write(
+        tmp.path().join(".botminter/daemon-daemon-wh-burst.yml"),
+        "debounce_secs: 2\n",
+    )
+    .unwrap();
 
-    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "status", "-t", "daemon-nostat-test"])
+    let port = 19489u16;
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args([
+            "daemon", "start",
+            "--mode", "webhook",
+            "--insecure-no-signature",
+            "--port", &port.to_string(),
+            "-t", "daemon-wh-burst",
+        ])
         .env("HOME", tmp.path())
         .output()
-        .expect("failed to run daemon status");
+        .expect("failed to start daemon");
+    assert!(start.status.success(), "start failed: {}", String::from_utf8_lossy(&start.stderr));
+    thread::sleep(Duration::from_secs(1));
 
-    assert!(status.status.success(), "Status should exit 0 even when not running");
-    let stdout = String::from_utf8_lossy(&status.stdout);
-    assert!(stdout.contains("not running"), "Should say not running: {}", stdout);
+    let client = reqwest::blocking::Client::new();
+    for event in ["issues", "issue_comment", "issues", "pull_request"] {
+        let resp = client
+            .post(format!("http://127.0.0.1:{}/webhook", port))
+            .header("X-GitHub-Event", event)
+            .body("{}")
+            .send()
+            .expect("webhook request failed");
+        assert_eq!(resp.status().as_u16(), 200);
+    }
+
+    let log_file = tmp.path().join(".botminter/logs/daemon-daemon-wh-burst.log");
+    let mut log = String::new();
+    for _ in 0..50 {
+        log = fs::read_to_string(&log_file).unwrap_or_default();
+        if log.contains("One-shot run complete") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(
+        lines.iter().filter(|l| l.contains("Dispatching one-shot run")).count(),
+        1,
+        "burst should trigger a single run:\n{}",
+        log
+    );
+    assert!(
+        log.contains("Dispatching one-shot run for: issues, issue_comment, pull_request"),
+        "{}",
+        log
+    );
+
+    // The run is recorded in the run history
+    let runs_file = tmp.path().join(".botminter/daemon-daemon-wh-burst-runs.json");
+    for _ in 0..50 {
+        if runs_file.exists() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let history = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "history", "-t", "daemon-wh-burst", "--limit", "5"])
+        .env("HOME", tmp.path())
+        .output()
+        .unwrap();
+    assert!(history.status.success(), "{}", String::from_utf8_lossy(&history.stderr));
+    let stdout = String::from_utf8_lossy(&history.stdout);
+    assert!(stdout.contains("issues, issue_comment, pull_request"), "{}", stdout);
+    assert!(stdout.contains("Showing 1 of 1 recorded run(s)"), "{}", stdout);
 }
 
 #[test]
-fn daemon_v1_team_blocked() {
+fn daemon_events_records_redacted_deliveries_and_replays_them() {
     let tmp = tempfile::tempdir().unwrap();
-    let team_repo = setup_team_for_subprocess(tmp.path(), "daemon-v1-test", "scrum");
+    setup_team_for_subprocess(tmp.path(), "daemon-events", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-events");
+    fs::write(
+        tmp.path().join(".botminter/daemon-daemon-events.yml"),
+        "debounce_secs: 0\n",
+    )
+    .unwrap();
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .output()
+            .unwrap()
+    };
 
-    // Downgrade to v1
-    let manifest_path = team_repo.join("botminter.yml");
-    let mut content = fs::read_to_string(&manifest_path).unwrap();
-    content = content.replace("schema_version: '1.0'", "schema_version: 0.1");
-    fs::write(&manifest_path, content).unwrap();
-    git(&team_repo, &["add", "botminter.yml"]);
-    git(&team_repo, &["commit", "-m", "chore: downgrade to v1"]);
+    let port = 19496u16;
+    let start = bm(&[
+        "daemon", "start",
+        "--mode", "webhook",
+        "--insecure-no-signature",
+        "--port", &port.to_string(),
+        "-t", "daemon-events",
+    ]);
+    assert!(start.status.success(), "start failed: {}", String::from_utf8_lossy(&start.stderr));
+    thread::sleep(Duration::from_secs(1));
 
-    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "start", "-t", "daemon-v1-test"])
-        .env("HOME", tmp.path())
-        .output()
-        .expect("failed to run daemon start");
+    let resp = reqwest::blocking::Client::new()
+        .post(format!("http://127.0.0.1:{}/webhook", port))
+        .header("X-GitHub-Event", "push")
+        .header("X-GitHub-Delivery", "push-delivery-1")
+        .body(r#"{"ref":"refs/heads/main","installation":{"access_token":"ghs_leak"}}"#)
+        .send()
+        .expect("webhook request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+
+    let record = tmp.path().join(".botminter/daemon-daemon-events-events/push-delivery-1.json");
+    for _ in 0..50 {
+        if record.exists() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let contents = fs::read_to_string(&record).unwrap();
+    assert!(!contents.contains("ghs_leak"), "{}", contents);
 
-    assert!(!output.status.success(), "Should fail for v1 team");
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("requires schema 1.0"), "Got: {}", stderr);
+    // GitHub retrying the same delivery is not handled again
+    let resp = reqwest::blocking::Client::new()
+        .post(format!("http://127.0.0.1:{}/webhook", port))
+        .header("X-GitHub-Event", "push")
+        .header("X-GitHub-Delivery", "push-delivery-1")
+        .body(r#"{"ref":"refs/heads/main"}"#)
+        .send()
+        .expect("webhook request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let log_file = tmp.path().join(".botminter/logs/daemon-daemon-events.log");
+    let mut log = String::new();
+    for _ in 0..50 {
+        log = fs::read_to_string(&log_file).unwrap_or_default();
+        if log.contains("Ignoring redelivery of delivery push-delivery-1") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    assert!(log.contains("Ignoring redelivery of delivery push-delivery-1"), "{}", log);
+    assert_eq!(fs::read_to_string(&record).unwrap(), contents);
+
+    let list = bm(&["daemon", "events", "list", "-t", "daemon-events"]);
+    assert!(list.status.success(), "{}", String::from_utf8_lossy(&list.stderr));
+    let stdout = String::from_utf8_lossy(&list.stdout);
+    assert!(stdout.contains("push-delivery-1"), "{}", stdout);
+    assert!(stdout.contains("push (branch: main)"), "{}", stdout);
+    assert!(stdout.contains("not a relevant event type"), "{}", stdout);
+
+    // After the fix, show predicts a run and replay queues one
+    let set = bm(&["daemon", "config", "set", "filters.events", "issues,push", "-t", "daemon-events"]);
+    assert!(set.status.success(), "{}", String::from_utf8_lossy(&set.stderr));
+    let show = bm(&["daemon", "events", "show", "push-del", "-t", "daemon-events"]);
+    assert!(show.status.success(), "{}", String::from_utf8_lossy(&show.stderr));
+    let stdout = String::from_utf8_lossy(&show.stdout);
+    assert!(stdout.contains("Now:       triggers a run of every member"), "{}", stdout);
+    assert!(stdout.contains("[redacted]"), "{}", stdout);
+
+    thread::sleep(Duration::from_secs(1));
+    let replay = bm(&["daemon", "events", "replay", "push-delivery-1", "-t", "daemon-events"]);
+    assert!(replay.status.success(), "{}", String::from_utf8_lossy(&replay.stderr));
+
+    for _ in 0..50 {
+        log = fs::read_to_string(&log_file).unwrap_or_default();
+        if log.contains("Dispatching one-shot run for: push") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    assert!(log.contains("Replaying delivery received"), "{}", log);
+    assert!(log.contains("Dispatching one-shot run for: push"), "{}", log);
+
+    let missing = bm(&["daemon", "events", "show", "nope", "-t", "daemon-events"]);
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("No recorded event 'nope'"));
 }
 
 #[test]
-fn daemon_webhook_mode_starts_and_stops() {
+fn daemon_writes_json_log_with_span_context() {
     let tmp = tempfile::tempdir().unwrap();
-    setup_team_for_subprocess(tmp.path(), "daemon-wh-test", "scrum");
-    let _guard = DaemonGuard::new(tmp.path(), "daemon-wh-test");
+    setup_team_for_subprocess(tmp.path(), "daemon-json-log", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-json-log");
+    fs::write(
+        tmp.path().join(".botminter/daemon-daemon-json-log.yml"),
+        "debounce_secs: 0\nlog_format: json\n",
+    )
+    .unwrap();
 
-    // Start in webhook mode on a high port to avoid conflicts
+    let port = 19497u16;
     let start = Command::new(env!("CARGO_BIN_EXE_bm"))
         .args([
-            "daemon", "start",
+            "-v", "daemon", "start",
             "--mode", "webhook",
-            "--port", "19484",
-            "-t", "daemon-wh-test",
+            "--insecure-no-signature",
+            "--port", &port.to_string(),
+            "-t", "daemon-json-log",
         ])
         .env("HOME", tmp.path())
+        .env_remove("BM_LOG")
         .output()
         .expect("failed to start daemon");
+    assert!(start.status.success(), "start failed: {}", String::from_utf8_lossy(&start.stderr));
+    // -v logs command-layer detail to stderr
+    let stderr = String::from_utf8_lossy(&start.stderr);
+    assert!(stderr.contains("Loading config from"), "{}", stderr);
+    thread::sleep(Duration::from_secs(1));
 
-    let stdout = String::from_utf8_lossy(&start.stdout);
-    assert!(
-        start.status.success(),
-        "Webhook daemon start should exit 0, stderr: {}",
-        String::from_utf8_lossy(&start.stderr)
-    );
-    assert!(stdout.contains("Daemon started"), "Should show started: {}", stdout);
+    let resp = reqwest::blocking::Client::new()
+        .post(format!("http://127.0.0.1:{}/webhook", port))
+        .header("X-GitHub-Event", "issues")
+        .header("X-GitHub-Delivery", "delivery-42")
+        .body("{}")
+        .send()
+        .expect("webhook request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+
+    let log_file = tmp.path().join(".botminter/logs/daemon-daemon-json-log.log");
+    let mut log = String::new();
+    for _ in 0..50 {
+        log = fs::read_to_string(&log_file).unwrap_or_default();
+        if log.contains("Received relevant event") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let entries: Vec<serde_json::Value> = log
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap_or_else(|e| panic!("not JSON ({}): {}", e, l)))
+        .collect();
+    let received = entries
+        .iter()
+        .find(|e| e["fields"]["message"] == "Received relevant event: issues")
+        .unwrap_or_else(|| panic!("no event entry:\n{}", log));
+    assert_eq!(received["level"], "INFO");
+    assert_eq!(received["spans"][0]["team"], "daemon-json-log");
+    assert_eq!(received["span"]["id"], "delivery-42");
+    assert_eq!(received["span"]["kind"], "issues");
+    // The daemon inherits -v, so debug entries are logged too
+    assert!(entries.iter().any(|e| e["level"] == "DEBUG"), "{}", log);
+}
 
-    // Wait briefly for server to bind
-    thread::sleep(Duration::from_millis(500));
+#[test]
+fn daemon_holds_launches_while_paused_and_runs_on_trigger() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-pause", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-pause");
+    fs::write(
+        tmp.path().join(".botminter/daemon-daemon-pause.yml"),
+        "debounce_secs: 0\n",
+    )
+    .unwrap();
+    env::set_var("HOME", tmp.path());
 
-    // Check status shows webhook mode
-    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "status", "-t", "daemon-wh-test"])
+    // A trigger needs a running daemon
+    let err = bm::commands::daemon::trigger("daemon-pause", "@ada").unwrap_err();
+    assert!(err.to_string().contains("bm daemon start"), "{}", err);
+
+    assert!(bm::state::pause("daemon-pause", "@ada").unwrap());
+    assert!(!bm::state::pause("daemon-pause", "@ada").unwrap());
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "webhook", "--port", "19493", "-t", "daemon-pause"])
+        .arg("--insecure-no-signature")
         .env("HOME", tmp.path())
         .output()
-        .expect("failed to get daemon status");
+        .expect("failed to start daemon");
+    assert!(start.status.success(), "start failed: {}", String::from_utf8_lossy(&start.stderr));
+    thread::sleep(Duration::from_secs(1));
 
-    let status_stdout = String::from_utf8_lossy(&status.stdout);
-    assert!(status_stdout.contains("webhook"), "Should show webhook mode: {}", status_stdout);
+    let status = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "status", "-t", "daemon-pause"])
+        .env("HOME", tmp.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(stdout.contains("Paused: since") && stdout.contains("by @ada"), "{}", stdout);
+
+    bm::commands::daemon::trigger("daemon-pause", "@ada").unwrap();
+    let log_file = tmp.path().join(".botminter/logs/daemon-daemon-pause.log");
+    let wait_for = |needle: &str| {
+        let mut log = String::new();
+        for _ in 0..50 {
+            log = fs::read_to_string(&log_file).unwrap_or_default();
+            if log.contains(needle) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        log
+    };
+    let log = wait_for("holding queued events until resumed");
+    assert!(log.contains("Manual run requested by @ada"), "{}", log);
+    assert!(!log.contains("Dispatching one-shot run"), "{}", log);
+
+    assert!(bm::state::resume("daemon-pause").unwrap());
+    assert!(!bm::state::resume("daemon-pause").unwrap());
+    let log = wait_for("Dispatching one-shot run for: manual");
+    assert!(log.contains("Dispatching one-shot run for: manual"), "{}", log);
 }
 
-// ── Daemon CLI parsing tests ─────────────────────────────────────────
-
 #[test]
-fn daemon_cli_parsing_start_flags() {
-    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args(["daemon", "start", "--help"])
-        .output()
-        .expect("failed to run bm daemon start --help");
+fn start_refuses_a_paused_team_until_resumed() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "paused-team", "scrum");
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("USER", "ada")
+            .output()
+            .unwrap()
+    };
 
-    assert!(output.status.success());
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    assert!(stdout.contains("--mode"), "Help should mention --mode: {}", stdout);
-    assert!(stdout.contains("--port"), "Help should mention --port: {}", stdout);
-    assert!(stdout.contains("--interval"), "Help should mention --interval: {}", stdout);
+    let out = bm(&["pause", "-t", "paused-team"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let out = bm(&["pause", "-t", "paused-team"]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("already paused"));
+
+    let out = bm(&["start", "-t", "paused-team"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("is paused since") && stderr.contains("--force"), "{}", stderr);
+
+    let out = bm(&["status", "-t", "paused-team"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Paused since") && stdout.contains("by ada"), "{}", stdout);
+
+    let out = bm(&["resume", "-t", "paused-team"]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Resumed team 'paused-team'"));
+    let out = bm(&["status", "-t", "paused-team"]);
+    assert!(!String::from_utf8_lossy(&out.stdout).contains("Paused"));
+    let out = bm(&["resume", "-t", "paused-team"]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("is not paused"));
 }
 
-use std::time::Duration;
-use std::thread;
+#[test]
+fn mutating_commands_are_recorded_in_the_audit_log() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "audited-team", "scrum");
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("USER", "ada")
+            .output()
+            .unwrap()
+    };
 
-// ── Webhook endpoint tests ───────────────────────────────────────────
+    let out = bm(&["pause"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    bm(&["status", "-t", "audited-team"]);
+    let out = bm(&["config", "set", "credentials.gh_token", "ghp_secret", "-t", "audited-team"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let out = bm(&["workspace", "restore", "nobody", "20260101-000000"]);
+    assert!(!out.status.success());
+
+    let log = fs::read_to_string(tmp.path().join(".botminter/audit.log")).unwrap();
+    assert_eq!(log.lines().count(), 3, "status is not audited:\n{}", log);
+    assert!(!log.contains("ghp_secret"), "{}", log);
+
+    let out = bm(&["audit", "-t", "audited-team"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("ada") && stdout.contains("pause"), "{}", stdout);
+    assert!(stdout.contains("config set credentials.gh_token <redacted>"), "{}", stdout);
+    assert!(stdout.contains("failed: Snapshot '20260101-000000' not found"), "{}", stdout);
+
+    let out = bm(&["audit", "-t", "other-team"]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("No audit entries for team 'other-team'"));
+    let out = bm(&["audit", "--since", "tomorrow"]);
+    assert!(!out.status.success());
+}
 
 #[test]
-fn daemon_webhook_accepts_relevant_event() {
+fn sqlite_state_backend_imports_and_keeps_state_in_bm_db() {
     let tmp = tempfile::tempdir().unwrap();
-    setup_team_for_subprocess(tmp.path(), "daemon-wh-accept", "scrum");
-    let _guard = DaemonGuard::new(tmp.path(), "daemon-wh-accept");
-
-    let port = 19485u16;
+    setup_team_for_subprocess(tmp.path(), "sqlite-team", "scrum");
+    let bm_dir = tmp.path().join(".botminter");
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("USER", "ada")
+            .output()
+            .unwrap()
+    };
 
-    // Start webhook daemon
-    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
-        .args([
-            "daemon", "start",
-            "--mode", "webhook",
-            "--port", &port.to_string(),
-            "-t", "daemon-wh-accept",
-        ])
-        .env("HOME", tmp.path())
-        .output()
-        .expect("failed to start daemon");
-    assert!(start.status.success(), "start failed: {}", String::from_utf8_lossy(&start.stderr));
+    // Paused while still on the file backend
+    let out = bm(&["pause", "-t", "sqlite-team"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let config_path = bm_dir.join("config.yml");
+    let mut cfg = bm::config::load_from(&config_path).unwrap();
+    cfg.state_backend = Some(bm::store::Backend::Sqlite);
+    bm::config::save_to(&config_path, &cfg).unwrap();
+
+    // The pause is imported into bm.db
+    let out = bm(&["status", "-t", "sqlite-team"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Paused since") && stdout.contains("by ada"), "{}", stdout);
+    assert!(bm_dir.join("bm.db").exists());
+
+    // Later changes go to bm.db only
+    let before = fs::read_to_string(bm_dir.join("state.json")).unwrap();
+    let out = bm(&["resume", "-t", "sqlite-team"]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Resumed team 'sqlite-team'"));
+    let out = bm(&["status", "-t", "sqlite-team"]);
+    assert!(!String::from_utf8_lossy(&out.stdout).contains("Paused"));
+    assert_eq!(fs::read_to_string(bm_dir.join("state.json")).unwrap(), before);
+}
 
-    // Wait for server to be ready
-    thread::sleep(Duration::from_secs(1));
+#[test]
+fn config_set_get_unset_edits_config_yml_safely() {
+    use std::os::unix::fs::PermissionsExt;
 
-    // Send a relevant event via HTTP POST
-    let client = reqwest::blocking::Client::new();
-    let resp = client
-        .post(&format!("http://127.0.0.1:{}/webhook", port))
-        .header("X-GitHub-Event", "issues")
-        .header("Content-Type", "application/json")
-        .body(r#"{"action":"opened","issue":{"number":1}}"#)
-        .send();
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "cfg-team", "scrum");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .output()
+            .unwrap()
+    };
 
-    match resp {
-        Ok(r) => {
-            assert_eq!(r.status().as_u16(), 200, "Webhook should respond 200");
-        }
-        Err(e) => {
-            eprintln!("Warning: could not connect to webhook server: {}", e);
-        }
+    let out = bm(&["config", "set", "teams.cfg-team.credentials.telegram_bot_token", "123:abc"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Set teams.cfg-team.credentials.telegram_bot_token"), "{}", stdout);
+    assert!(!stdout.contains("123:abc"), "values are not echoed: {}", stdout);
+    let mode = fs::metadata(&config_path).unwrap().permissions().mode() & 0o777;
+    assert_eq!(mode, 0o600);
+
+    let out = bm(&["config", "get", "credentials.telegram_bot_token", "-t", "cfg-team"]);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "123:abc\n");
+    let out = bm(&["config", "set", "timestamps", "local"]);
+    assert!(out.status.success());
+    let out = bm(&["config", "get", "timestamps"]);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "local\n");
+
+    // Invalid edits are refused and leave the file alone
+    let before = fs::read_to_string(&config_path).unwrap();
+    for args in [
+        vec!["config", "set", "timestamps", "mars"],
+        vec!["config", "set", "colour", "red"],
+        vec!["config", "unset", "workzone"],
+        vec!["config", "set", "teams.nope.github_repo", "org/x"],
+    ] {
+        let out = bm(&args);
+        assert!(!out.status.success(), "`bm {}` should fail", args.join(" "));
     }
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), before);
+
+    let out = bm(&["config", "unset", "credentials.telegram_bot_token", "-t", "cfg-team"]);
+    assert!(String::from_utf8_lossy(&out.stdout).contains("Unset"));
+    let out = bm(&["config", "get", "credentials.telegram_bot_token", "-t", "cfg-team"]);
+    assert_eq!(String::from_utf8_lossy(&out.stdout), "(not set)\n");
 }
 
 #[test]
@@ -2094,6 +5604,7 @@ fn daemon_webhook_rejects_irrelevant_event() {
         .args([
             "daemon", "start",
             "--mode", "webhook",
+            "--insecure-no-signature",
             "--port", &port.to_string(),
             "-t", "daemon-wh-reject",
         ])
@@ -2107,7 +5618,7 @@ fn daemon_webhook_rejects_irrelevant_event() {
     // Send an irrelevant event (push) — daemon should accept but not trigger members
     let client = reqwest::blocking::Client::new();
     let resp = client
-        .post(&format!("http://127.0.0.1:{}/webhook", port))
+        .post(format!("http://127.0.0.1:{}/webhook", port))
         .header("X-GitHub-Event", "push")
         .header("Content-Type", "application/json")
         .body(r#"{"ref":"refs/heads/main"}"#)
@@ -2135,6 +5646,7 @@ fn daemon_webhook_returns_404_for_wrong_path() {
         .args([
             "daemon", "start",
             "--mode", "webhook",
+            "--insecure-no-signature",
             "--port", &port.to_string(),
             "-t", "daemon-wh-404",
         ])
@@ -2148,7 +5660,7 @@ fn daemon_webhook_returns_404_for_wrong_path() {
     // Send to wrong path
     let client = reqwest::blocking::Client::new();
     let resp = client
-        .post(&format!("http://127.0.0.1:{}/wrong-path", port))
+        .post(format!("http://127.0.0.1:{}/wrong-path", port))
         .body("test")
         .send();
 
@@ -2172,10 +5684,228 @@ fn projects_sync_fails_without_github_repo() {
     setup_team(tmp.path(), "test-team", "scrum");
 
     // projects sync should fail because there's no github_repo configured
-    let result = bm::commands::projects::sync(None);
+    let result = bm::commands::projects::sync(None, false, false);
     assert!(result.is_err(), "sync should fail without github_repo");
 }
 
+#[test]
+fn projects_sync_create_views_adds_missing_views_only() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "views-team", "scrum");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].github_repo = "acme/views-team".to_string();
+    bm::config::save_to(&config_path, &config).unwrap();
+
+    // The board already has PO as the profile wants it and Developer with an
+    // outdated filter
+    let manifest = profile::read_manifest("scrum").unwrap();
+    let filter = |name: &str| {
+        let view = manifest.views.iter().find(|v| v.name == name).unwrap();
+        view.filter_string(&manifest.statuses)
+    };
+    let views = serde_json::json!({"data": {"repositoryOwner": {
+        "__typename": "Organization",
+        "projectV2": {"views": {"nodes": [
+            {"name": "View 1", "filter": null, "layout": "TABLE_LAYOUT"},
+            {"name": "PO", "filter": filter("PO"), "layout": "BOARD_LAYOUT"},
+            {"name": "Developer", "filter": "status:dev:ready", "layout": "BOARD_LAYOUT"},
+        ]}},
+    }}});
+    let f = |name: &str| tmp.path().join(name).display().to_string();
+    fs::write(f("views.json"), views.to_string()).unwrap();
+    let bin = tmp.path().join("bin");
+    install_stub(
+        &bin,
+        "gh",
+        &format!(
+            "#!/bin/sh\ncase \"$*\" in\n  \
+             \"project list\"*) echo '{{\"projects\":[{{\"number\":3,\"title\":\"views-team Board\"}}]}}' ;;\n  \
+             \"project field-list\"*) echo '{{\"fields\":[{{\"id\":\"F1\",\"name\":\"Status\"}}]}}' ;;\n  \
+             *repositoryOwner*) cat {views} ;;\n  \
+             *POST*) echo \"$*\" >> {posts}; cat >> {posts}; echo >> {posts} ;;\n\
+             esac\n",
+            views = f("views.json"),
+            posts = f("posts"),
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["projects", "sync", "--create-views", "-t", "views-team"])
+        .env("HOME", tmp.path())
+        .env("PATH", &path_env)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✓ View 'PO' up to date"), "{}", stdout);
+    assert!(stdout.contains("✓ View 'Architect' created"), "{}", stdout);
+    assert!(stdout.contains("✓ View 'Specialist' created"), "{}", stdout);
+    assert!(stdout.contains("! View 'Developer' filters on 'status:dev:ready'"), "{}", stdout);
+    let (_, manual) = stdout.split_once("Set up the remaining views by hand").unwrap();
+    assert!(manual.contains(&format!("Developer  {}", filter("Developer"))), "{}", manual);
+    assert!(!manual.contains("Architect"), "{}", manual);
+
+    let posts = fs::read_to_string(f("posts")).unwrap();
+    let creates = posts.matches("--method POST orgs/acme/projectsV2/3/views").count();
+    assert_eq!(creates, 4, "{}", posts);
+    let qe_body = posts.lines().find(|l| l.contains("\"QE\"")).unwrap();
+    let qe: serde_json::Value = serde_json::from_str(qe_body).unwrap();
+    assert_eq!(qe["layout"], "board");
+    assert_eq!(qe["filter"], filter("QE"));
+}
+
+#[test]
+fn projects_sync_checks_and_applies_repo_settings() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "settings-team", "scrum");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].github_repo = "acme/settings-team".to_string();
+    bm::config::save_to(&config_path, &config).unwrap();
+    let manifest_path = config.teams[0].path.join("team/botminter.yml");
+    let mut manifest = fs::read_to_string(&manifest_path).unwrap();
+    manifest.push_str(
+        "repo_settings:\n  merge:\n    allow_squash: true\n    allow_merge_commit: false\n  \
+         branch_protection:\n    - branch: main\n      required_checks: [test]\n",
+    );
+    fs::write(&manifest_path, manifest).unwrap();
+
+    // Merge commits are on and main is unprotected
+    let f = |name: &str| tmp.path().join(name).display().to_string();
+    let bin = tmp.path().join("bin");
+    install_stub(
+        &bin,
+        "gh",
+        &format!(
+            "#!/bin/sh\ncase \"$*\" in\n  \
+             *PATCH*|*PUT*) echo \"$*\" >> {writes}; cat >> {writes}; echo >> {writes} ;;\n  \
+             \"api repos/acme/settings-team\") \
+             echo '{{\"allow_squash_merge\":true,\"allow_merge_commit\":true}}' ;;\n  \
+             *protection*) echo 'gh: Branch not protected (HTTP 404)' >&2; exit 1 ;;\n  \
+             \"project list\"*) echo '{{\"projects\":[{{\"number\":3,\"title\":\"settings-team Board\"}}]}}' ;;\n  \
+             \"project field-list\"*) echo '{{\"fields\":[{{\"id\":\"F1\",\"name\":\"Status\"}}]}}' ;;\n\
+             esac\n",
+            writes = f("writes"),
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .output()
+            .unwrap()
+    };
+
+    let output = bm(&["projects", "sync", "--check", "-t", "settings-team"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✗ acme/settings-team: 2 setting(s) drifted"), "{}", stdout);
+    assert!(stdout.contains("allow merge commits: true (profile: false)"), "{}", stdout);
+    assert!(stdout.contains("main: protection: none"), "{}", stdout);
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("1 of 1 repo(s) drifted"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!tmp.path().join("writes").exists(), "--check must not write");
+
+    let output = bm(&["projects", "sync", "-t", "settings-team"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("✓ Repo settings on acme/settings-team: 2 changed"), "{}", stdout);
+
+    let writes = fs::read_to_string(f("writes")).unwrap();
+    let body = |marker: &str| -> serde_json::Value {
+        let mut lines = writes.lines().skip_while(|l| !l.contains(marker));
+        lines.next().unwrap();
+        serde_json::from_str(lines.next().unwrap()).unwrap()
+    };
+    let patch = body("--method PATCH repos/acme/settings-team");
+    assert_eq!(patch, serde_json::json!({"allow_squash_merge": true, "allow_merge_commit": false}));
+    let protection = body("--method PUT repos/acme/settings-team/branches/main/protection");
+    assert_eq!(protection["required_pull_request_reviews"]["required_approving_review_count"], 1);
+    assert_eq!(protection["required_status_checks"]["contexts"], serde_json::json!(["test"]));
+}
+
+#[test]
+fn projects_seed_creates_missing_issues_on_the_board() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "seed-team", "scrum");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].github_repo = "acme/seed-team".to_string();
+    bm::config::save_to(&config_path, &config).unwrap();
+    let manifest_path = config.teams[0].path.join("team/botminter.yml");
+    let mut manifest = fs::read_to_string(&manifest_path).unwrap();
+    manifest.push_str(
+        "seed_issues:\n  \
+         - title: Set up CI\n    labels: [kind/epic]\n\
+         \x20 - title: Write the roadmap\n    body: First pass\n    \
+         labels: [kind/epic]\n    status: po:triage\n",
+    );
+    fs::write(&manifest_path, manifest).unwrap();
+
+    let f = |name: &str| tmp.path().join(name).display().to_string();
+    let bin = tmp.path().join("bin");
+    install_stub(
+        &bin,
+        "gh",
+        &format!(
+            "#!/bin/sh\necho \"$*\" >> {log}\ncase \"$*\" in\n  \
+             \"issue list\"*) echo '[{{\"title\":\"Set up CI\"}}]' ;;\n  \
+             \"issue create\"*) echo https://github.com/acme/seed-team/issues/7 ;;\n  \
+             \"project list\"*) echo '{{\"projects\":[{{\"number\":3,\"title\":\"seed-team Board\"}}]}}' ;;\n  \
+             \"project view\"*) echo '{{\"id\":\"P1\"}}' ;;\n  \
+             \"project field-list\"*) echo '{{\"fields\":[{{\"id\":\"F1\",\"name\":\"Status\",\
+             \"options\":[{{\"id\":\"O1\",\"name\":\"po:triage\"}}]}}]}}' ;;\n  \
+             \"project item-add\"*) echo '{{\"id\":\"I1\"}}' ;;\n\
+             esac\n",
+            log = f("gh.log"),
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let seed = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(["projects", "seed", "-t", "seed-team"])
+            .args(extra)
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .output()
+            .unwrap()
+    };
+
+    let output = seed(&["--dry-run"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("'Set up CI' already exists"), "{}", stdout);
+    assert!(
+        stdout.contains("Would create 'Write the roadmap' [kind/epic] in po:triage"),
+        "{}",
+        stdout
+    );
+    assert!(!fs::read_to_string(f("gh.log")).unwrap().contains("issue create"));
+
+    let output = seed(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Created 1 issue(s) on acme/seed-team"), "{}", stdout);
+    let log = fs::read_to_string(f("gh.log")).unwrap();
+    let creates: Vec<&str> = log.lines().filter(|l| l.starts_with("issue create")).collect();
+    assert_eq!(
+        creates,
+        ["issue create --repo acme/seed-team --title Write the roadmap --body First pass \
+          --label kind/epic"]
+    );
+    assert!(log.contains("project item-add 3 --owner acme --url \
+         https://github.com/acme/seed-team/issues/7"), "{}", log);
+    assert!(log.contains("project item-edit --id I1 --project-id P1 --field-id F1 \
+         --single-select-option-id O1"), "{}", log);
+}
+
 #[test]
 fn projects_sync_cli_parses() {
     // Verify `bm projects sync --help` parses correctly
@@ -2277,8 +6007,8 @@ fn teams_list_shows_member_and_project_counts() {
     let role = &roles[0];
 
     // Hire two members
-    bm::commands::hire::run(role, Some("alice"), None).unwrap();
-    bm::commands::hire::run(role, Some("bob"), None).unwrap();
+    bm::commands::hire::run(role, Some("alice"), false, None).unwrap();
+    bm::commands::hire::run(role, Some("bob"), false, None).unwrap();
 
     // Add a project
     let fork = create_fake_fork(tmp.path(), "test-proj");
@@ -2329,7 +6059,7 @@ fn teams_show_displays_full_details() {
     let roles = profile::list_roles("scrum").unwrap();
     let role = &roles[0];
 
-    bm::commands::hire::run(role, Some("alice"), None).unwrap();
+    bm::commands::hire::run(role, Some("alice"), false, None).unwrap();
 
     let fork = create_fake_fork(tmp.path(), "my-project");
     bm::commands::projects::add(&fork.to_string_lossy(), None).unwrap();
@@ -2372,6 +6102,11 @@ fn teams_show_displays_full_details() {
         "should show project, output:\n{}",
         stdout
     );
+    assert!(
+        stdout.contains("Warning: Role 'human-assistant' needs at least 1 member(s), has 0"),
+        "should flag understaffed role, output:\n{}",
+        stdout
+    );
 }
 
 // ── Members show tests ───────────────────────────────────────────────
@@ -2385,7 +6120,7 @@ fn members_show_displays_details() {
     let roles = profile::list_roles("scrum").unwrap();
     let role = &roles[0];
 
-    bm::commands::hire::run(role, Some("alice"), None).unwrap();
+    bm::commands::hire::run(role, Some("alice"), false, None).unwrap();
 
     let member_name = format!("{}-alice", role);
     let output = Command::new(env!("CARGO_BIN_EXE_bm"))
@@ -2418,6 +6153,61 @@ fn members_show_displays_details() {
     );
 }
 
+#[test]
+fn members_show_reports_workspace_and_log_tail() {
+    let _lock = ENV_MUTEX.lock().unwrap();
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team(tmp.path(), "mshow-ws-team", "scrum");
+
+    bm::commands::hire::run("architect", Some("alice"), false, None).unwrap();
+    bm::commands::teams::sync(false, false, None, None, None).unwrap();
+
+    let logs = tmp.path().join(".botminter/logs");
+    fs::create_dir_all(&logs).unwrap();
+    let log: Vec<String> = (1..=12).map(|i| format!("log line {:02}", i)).collect();
+    fs::write(
+        logs.join("member-mshow-ws-team-architect-alice.log"),
+        log.join("\n"),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["members", "show", "architect-alice", "-t", "mshow-ws-team"])
+        .env("HOME", tmp.path())
+        .output()
+        .expect("failed to run bm members show");
+    assert!(
+        output.status.success(),
+        "members show failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let ws = tmp.path().join("workspaces/mshow-ws-team/architect-alice");
+    assert!(
+        stdout.contains(&ws.display().to_string()),
+        "should show workspace path, output:\n{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Git: main, "),
+        "should show workspace branch, output:\n{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("PROMPT.md -> .botminter/team/architect-alice/PROMPT.md"),
+        "should show symlink targets, output:\n{}",
+        stdout
+    );
+    assert!(stdout.contains("log line 12"), "output:\n{}", stdout);
+    assert!(stdout.contains("log line 03"), "output:\n{}", stdout);
+    assert!(
+        !stdout.contains("log line 02"),
+        "should show only the last 10 log lines, output:\n{}",
+        stdout
+    );
+}
+
 #[test]
 fn members_show_nonexistent_errors() {
     let _lock = ENV_MUTEX.lock().unwrap();
@@ -2544,3 +6334,373 @@ fn projects_show_displays_details() {
         stdout
     );
 }
+
+/// Points the team at a mail server and installs a curl stand-in that keeps
+/// the options it was given and the message it would have sent.
+fn stub_smtp(tmp: &Path, digest_at: &str) -> String {
+    let config_path = tmp.join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].credentials.smtp = Some(bm::config::Smtp {
+        server: "mail.example.com".to_string(),
+        port: None,
+        tls: bm::config::SmtpTls::Starttls,
+        username: Some("bm".to_string()),
+        password: Some("mail-pass".to_string()),
+        from: "bm@example.com".to_string(),
+        to: vec!["ops@example.com".to_string()],
+        notify_on: Vec::new(),
+        digest_at: Some(digest_at.to_string()),
+    });
+    bm::config::save_to(&config_path, &config).unwrap();
+
+    let bin = tmp.join("bin");
+    install_stub(
+        &bin,
+        "curl",
+        &format!(
+            "#!/bin/sh\ncat > {dir}/curl-config\n\
+             f=$(sed -n 's/^upload-file = \"\\(.*\\)\"$/\\1/p' {dir}/curl-config)\n\
+             cp \"$f\" {dir}/mail.eml\n",
+            dir = tmp.display()
+        ),
+    );
+    format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default())
+}
+
+#[test]
+fn notify_test_mails_through_smtp_settings() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "notify-team", "scrum");
+    let bm = |path_env: &str| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(["notify", "test", "-t", "notify-team"])
+            .env("HOME", tmp.path())
+            .env("PATH", path_env)
+            .output()
+            .unwrap()
+    };
+
+    let unconfigured = bm(&env::var("PATH").unwrap_or_default());
+    assert!(!unconfigured.status.success());
+    let stderr = String::from_utf8_lossy(&unconfigured.stderr);
+    assert!(stderr.contains("no SMTP server or notifiers configured"), "{}", stderr);
+
+    let path_env = stub_smtp(tmp.path(), "07:30");
+    let sent = bm(&path_env);
+    assert!(sent.status.success(), "{}", String::from_utf8_lossy(&sent.stderr));
+    let stdout = String::from_utf8_lossy(&sent.stdout);
+    assert!(
+        stdout.contains("Sent a test message to ops@example.com via mail.example.com:587 (STARTTLS)"),
+        "{}",
+        stdout
+    );
+
+    let curl_config = fs::read_to_string(tmp.path().join("curl-config")).unwrap();
+    assert!(curl_config.contains("url = \"smtp://mail.example.com:587\"\nssl-reqd\n"));
+    assert!(curl_config.contains("mail-rcpt = \"ops@example.com\""), "{}", curl_config);
+    assert!(curl_config.contains("user = \"bm:mail-pass\""), "{}", curl_config);
+    let mail = fs::read_to_string(tmp.path().join("mail.eml")).unwrap();
+    assert!(mail.contains("Subject: [bm notify-team] Test message\r\n"), "{}", mail);
+    assert!(mail.contains("Daily digest: 07:30 local time."), "{}", mail);
+}
+
+#[test]
+fn notify_test_posts_to_slack_and_http_notifiers() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "notifier-team", "scrum");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].credentials.notifiers = serde_yml::from_str(
+        "- type: slack\n  url: https://hooks.slack.com/services/T0/B0/secret\n  notify_on: [crash]\n\
+         - type: http\n  url: https://example.com/hook\n  headers: {Authorization: Bearer t0k}\n",
+    )
+    .unwrap();
+    bm::config::save_to(&config_path, &config).unwrap();
+
+    // A curl stand-in appending the options of each request
+    let bin = tmp.path().join("bin");
+    install_stub(
+        &bin,
+        "curl",
+        &format!("#!/bin/sh\ncat >> {}/curl-config\n", tmp.path().display()),
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["notify", "test", "-t", "notifier-team"])
+        .env("HOME", tmp.path())
+        .env("PATH", format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default()))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Sent a test message via slack (hooks.slack.com). Notifications sent there: crash\n"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("via http (example.com). Notifications sent there: crash, error, digest, budget, token_expiring\n"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("secret"), "{}", stdout);
+
+    let curl_config = fs::read_to_string(tmp.path().join("curl-config")).unwrap();
+    assert!(curl_config.contains("url = \"https://hooks.slack.com/services/T0/B0/secret\""));
+    assert!(curl_config.contains("data-binary = \"{\\\"text\\\":\\\"*[bm notifier-team] Test message*"), "{}", curl_config);
+    assert!(curl_config.contains("header = \"Authorization: Bearer t0k\""), "{}", curl_config);
+    assert!(curl_config.contains("\\\"kind\\\":\\\"digest\\\""), "{}", curl_config);
+}
+
+#[test]
+fn daemon_notifies_on_start_and_stop() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-lifecycle", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-lifecycle");
+    let config_path = tmp.path().join(".botminter/config.yml");
+    let mut config = bm::config::load_from(&config_path).unwrap();
+    config.teams[0].credentials.notifiers = serde_yml::from_str(
+        "- type: http\n  url: https://ops.example.com/bm\n  notify_on: [daemon_start, daemon_stop]\n",
+    )
+    .unwrap();
+    bm::config::save_to(&config_path, &config).unwrap();
+    let bin = tmp.path().join("bin");
+    install_stub(
+        &bin,
+        "curl",
+        &format!("#!/bin/sh\ncat >> {}/curl-config\n", tmp.path().display()),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let bm = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .args(["-t", "daemon-lifecycle"])
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    };
+    let sent = |kind: &str| {
+        let needle = format!("\\\"kind\\\":\\\"{}\\\"", kind);
+        for _ in 0..50 {
+            let sent = fs::read_to_string(tmp.path().join("curl-config")).unwrap_or_default();
+            if sent.contains(&needle) {
+                return sent;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        panic!("no {} notification", kind);
+    };
+
+    bm(&["daemon", "start", "--mode", "webhook", "--port", "19495", "--insecure-no-signature"]);
+    let posted = sent("daemon_start");
+    assert!(posted.contains("[bm daemon-lifecycle] Daemon started"), "{}", posted);
+    assert!(posted.contains("started in webhook mode"), "{}", posted);
+    bm(&["daemon", "stop"]);
+    let posted = sent("daemon_stop");
+    assert!(posted.contains("[bm daemon-lifecycle] Daemon stopped"), "{}", posted);
+}
+
+#[test]
+fn daemon_sends_daily_digest() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "daemon-digest", "scrum");
+    let _guard = DaemonGuard::new(tmp.path(), "daemon-digest");
+    let path_env = stub_smtp(tmp.path(), "00:00");
+
+    let start = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["daemon", "start", "--mode", "webhook", "--port", "19494", "-t", "daemon-digest"])
+        .arg("--insecure-no-signature")
+        .env("HOME", tmp.path())
+        .env("PATH", &path_env)
+        .output()
+        .expect("failed to start daemon");
+    assert!(start.status.success(), "start failed: {}", String::from_utf8_lossy(&start.stderr));
+
+    let log_file = tmp.path().join(".botminter/logs/daemon-daemon-digest.log");
+    let mut log = String::new();
+    for _ in 0..100 {
+        log = fs::read_to_string(&log_file).unwrap_or_default();
+        if log.contains("Sent the daily digest") {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    assert!(log.contains("Sent the daily digest"), "{}", log);
+
+    let mail = fs::read_to_string(tmp.path().join("mail.eml")).unwrap();
+    assert!(mail.contains("Subject: [bm daemon-digest] Daily digest "), "{}", mail);
+    assert!(mail.contains("Members:\r\n"), "{}", mail);
+    assert!(mail.contains("Daemon: running, webhook (port 19494)"), "{}", mail);
+    let marker = fs::read_to_string(tmp.path().join(".botminter/daemon-daemon-digest.digest"));
+    assert_eq!(marker.unwrap(), chrono::Local::now().format("%Y-%m-%d").to_string());
+}
+
+#[test]
+fn init_from_existing_clones_and_registers_the_team() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    // The team repo another operator pushed, with one member hired
+    let origin = tmp.path().join("origin");
+    fs::create_dir_all(origin.join("team/dev-01")).unwrap();
+    git(&origin, &["init", "-b", "main"]);
+    profile::extract_profile_to("scrum-compact", &origin).unwrap();
+    fs::write(origin.join("team/dev-01/botminter.yml"), "role: dev\n").unwrap();
+    git(&origin, &["add", "-A"]);
+    git(&origin, &["commit", "-m", "feat: init team repo"]);
+    let not_a_team = tmp.path().join("not-a-team");
+    fs::create_dir_all(&not_a_team).unwrap();
+    git(&not_a_team, &["init", "-b", "main"]);
+    git(&not_a_team, &["commit", "--allow-empty", "-m", "empty"]);
+
+    let bin = tmp.path().join("bin");
+    install_stub(
+        &bin,
+        "gh",
+        &format!(
+            "#!/bin/sh\ncase \"$*\" in\n  \
+             \"auth token\") echo gho_second_operator ;;\n  \
+             \"api user\"*) echo second-op ;;\n  \
+             \"repo clone org/squad \"*) git clone -q {} \"$4\" ;;\n  \
+             \"repo clone org/notes \"*) git clone -q {} \"$4\" ;;\n  \
+             *) exit 1 ;;\n\
+             esac\n",
+            origin.display(),
+            not_a_team.display()
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let bm = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .env("PATH", &path_env)
+            .env_remove("GH_TOKEN")
+            .output()
+            .unwrap()
+    };
+
+    let out = bm(&["init", "--from-existing", "org/squad"]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("Joined team 'squad' from org/squad (profile scrum-compact, 1 member(s))"),
+        "{}",
+        stdout
+    );
+    let config = bm::config::load_from(&tmp.path().join(".botminter/config.yml")).unwrap();
+    let team = &config.teams[0];
+    assert_eq!((team.name.as_str(), team.github_repo.as_str()), ("squad", "org/squad"));
+    assert_eq!(team.profile, "scrum-compact");
+    assert_eq!(team.credentials.gh_token.as_deref(), Some("gho_second_operator"));
+    assert_eq!(config.default_team.as_deref(), Some("squad"));
+    assert!(team.path.join("team/team/dev-01/botminter.yml").exists());
+
+    // The same repo can't be registered twice, even under another name
+    let out = bm(&["init", "--from-existing", "org/squad", "--name", "squad-2"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("already registered"));
+
+    // A repo that isn't a team repo is refused and leaves nothing behind
+    let out = bm(&["init", "--from-existing", "org/notes"]);
+    assert!(!out.status.success());
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("isn't a botminter team repo"),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(!config.workzone.join("notes").exists());
+    let config = bm::config::load_from(&tmp.path().join(".botminter/config.yml")).unwrap();
+    assert_eq!(config.teams.len(), 1);
+}
+
+#[test]
+fn init_leaves_a_config_it_cannot_load_untouched() {
+    let tmp = tempfile::tempdir().unwrap();
+    let config_path = tmp.path().join(".botminter/config.yml");
+    fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+    let workzone = tmp.path().join("wz");
+    let original = format!(
+        "version: 1\nworkzone: {wz}\nfuture_field: true\nteams:\n  - name: keep\n    \
+         path: {wz}/keep\n    profile: scrum\n    github_repo: org/keep\n    \
+         credentials:\n      gh_token: ghp_keep\n",
+        wz = workzone.display()
+    );
+    fs::write(&config_path, &original).unwrap();
+
+    // A team repo init could otherwise join, registering it over the config
+    let origin = tmp.path().join("origin");
+    fs::create_dir_all(&origin).unwrap();
+    git(&origin, &["init", "-b", "main"]);
+    profile::extract_profile_to("scrum-compact", &origin).unwrap();
+    git(&origin, &["add", "-A"]);
+    git(&origin, &["commit", "-m", "feat: init team repo"]);
+    let bin = tmp.path().join("bin");
+    install_stub(
+        &bin,
+        "gh",
+        &format!(
+            "#!/bin/sh\ncase \"$*\" in\n  \
+             \"auth token\") echo gho_x ;;\n  \
+             \"api user\"*) echo op ;;\n  \
+             \"repo clone org/squad \"*) git clone -q {} \"$4\" ;;\n  \
+             *) exit 1 ;;\n\
+             esac\n",
+            origin.display()
+        ),
+    );
+    let path_env = format!("{}:{}", bin.display(), env::var("PATH").unwrap_or_default());
+    let out = Command::new(env!("CARGO_BIN_EXE_bm"))
+        .args(["init", "--from-existing", "org/squad"])
+        .env("HOME", tmp.path())
+        .env("PATH", &path_env)
+        .env_remove("GH_TOKEN")
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("unknown field `future_field`"), "{}", stderr);
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), original);
+    assert!(!workzone.exists());
+}
+
+#[test]
+fn roles_describe_previews_role() {
+    let tmp = tempfile::tempdir().unwrap();
+    setup_team_for_subprocess(tmp.path(), "roles-describe-team", "scrum");
+
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_bm"))
+            .args(args)
+            .env("HOME", tmp.path())
+            .output()
+            .expect("failed to run bm roles describe")
+    };
+
+    let output = run(&["roles", "describe", "architect", "-t", "roles-describe-team"]);
+    assert!(
+        output.status.success(),
+        "bm roles describe failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let manifest = profile::read_manifest("scrum").unwrap();
+    let architect = manifest.roles.iter().find(|r| r.name == "architect").unwrap();
+    for want in [
+        architect.description.as_str(),
+        "arch:",
+        "PROMPT.md",
+        "ralph.yml",
+        "PROMPT.md (as rendered for architect-01):",
+    ] {
+        assert!(stdout.contains(want), "missing '{}' in:\n{}", want, stdout);
+    }
+    assert!(!stdout.contains("{{"), "template left unrendered:\n{}", stdout);
+
+    let full = run(&["roles", "describe", "architect", "--full", "-t", "roles-describe-team"]);
+    assert!(!String::from_utf8_lossy(&full.stdout).contains("pass --full"));
+
+    let output = run(&["roles", "describe", "nope", "-t", "roles-describe-team"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Available roles"));
+}